@@ -5,7 +5,11 @@ pub mod proto;
 
 use failure::prelude::*;
 use libra_mempool_shared_proto::MempoolAddTransactionStatus;
-use libra_types::vm_error::VMStatus;
+use libra_types::{
+    contract_event::ContractEvent,
+    transaction::{TransactionOutput, TransactionStatus},
+    vm_error::VMStatus,
+};
 use logger::prelude::*;
 use std::convert::TryFrom;
 
@@ -122,3 +126,46 @@ impl From<SubmitTransactionResponse>
         proto
     }
 }
+
+impl TryFrom<crate::proto::admission_control::SimulateTransactionResponse> for TransactionOutput {
+    type Error = Error;
+
+    fn try_from(
+        proto: crate::proto::admission_control::SimulateTransactionResponse,
+    ) -> Result<Self> {
+        use crate::proto::admission_control::simulate_transaction_response::Status::*;
+
+        let write_set = lcs::from_bytes(&proto.write_set)?;
+        let events = proto
+            .events
+            .into_iter()
+            .map(ContractEvent::try_from)
+            .collect::<Result<Vec<_>>>()?;
+        let status = match proto.status.ok_or_else(|| format_err!("Missing status"))? {
+            Keep(status) => TransactionStatus::Keep(VMStatus::try_from(status)?),
+            Discard(status) => TransactionStatus::Discard(VMStatus::try_from(status)?),
+        };
+        Ok(TransactionOutput::new(
+            write_set,
+            events,
+            proto.gas_used,
+            status,
+        ))
+    }
+}
+
+impl From<TransactionOutput> for crate::proto::admission_control::SimulateTransactionResponse {
+    fn from(output: TransactionOutput) -> Self {
+        use crate::proto::admission_control::simulate_transaction_response::Status::*;
+
+        Self {
+            write_set: lcs::to_bytes(output.write_set()).expect("WriteSet serialization failed"),
+            events: output.events().iter().cloned().map(Into::into).collect(),
+            gas_used: output.gas_used(),
+            status: Some(match output.status().clone() {
+                TransactionStatus::Keep(status) => Keep(status.into()),
+                TransactionStatus::Discard(status) => Discard(status.into()),
+            }),
+        }
+    }
+}
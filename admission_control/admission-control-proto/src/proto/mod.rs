@@ -11,5 +11,7 @@ pub mod admission_control {
 }
 
 pub use self::admission_control::{
-    AdmissionControlMsg, SubmitTransactionRequest, SubmitTransactionResponse,
+    AdmissionControlMsg, SimulateTransactionRequest, SimulateTransactionResponse,
+    SubmitTransactionRequest, SubmitTransactionResponse, SubscribeToAccountTransactionsRequest,
+    SubscribeToEventsRequest,
 };
@@ -4,9 +4,9 @@
 use super::AdmissionControlService;
 use crate::{
     admission_control_service::SubmitTransactionRequest,
-    mocks::local_mock_mempool::LocalMockMempool,
+    mocks::local_mock_mempool::LocalMockMempool, subscription::SubscriptionConfig,
 };
-use futures::channel::mpsc;
+use config::config::AdmissionControlConfig;
 use libra_types::transaction::SignedTransaction;
 use proptest;
 use proptest_helpers::ValueGenerator;
@@ -48,15 +48,13 @@ pub fn fuzzer(data: &[u8]) {
             return;
         }
     };
-    let (upstream_proxy_sender, _) = mpsc::unbounded();
-
     // create service to receive it
     let ac_service = AdmissionControlService::new(
-        Some(Arc::new(LocalMockMempool::new())),
+        Arc::new(LocalMockMempool::new()),
         Arc::new(MockStorageReadClient),
         Arc::new(MockVMValidator),
         false,
-        upstream_proxy_sender,
+        SubscriptionConfig::from(&AdmissionControlConfig::default()),
     );
 
     // process the request
@@ -5,21 +5,25 @@
 //! from external clients (such as wallets) and performs necessary processing before sending them to
 //! next step.
 
-use crate::OP_COUNTERS;
+use crate::{
+    subscription::{Subscription, SubscriptionConfig, SubscriptionCursor},
+    OP_COUNTERS,
+};
 use admission_control_proto::{
     proto::admission_control::{
-        submit_transaction_response::Status, AdmissionControl, SubmitTransactionRequest,
-        SubmitTransactionResponse,
+        submit_transaction_response::Status, AdmissionControl, SimulateTransactionRequest,
+        SimulateTransactionResponse, SubmitTransactionRequest, SubmitTransactionResponse,
+        SubscribeToAccountTransactionsRequest, SubscribeToEventsRequest,
     },
     AdmissionControlStatus,
 };
 use failure::prelude::*;
-use futures::{
-    channel::{mpsc, oneshot},
-    executor::block_on,
+use futures::executor::block_on;
+use futures_01::{future::Future, stream::Stream, sync::mpsc as mpsc_01, Sink};
+use grpc_helpers::{
+    create_grpc_invalid_arg_status, default_reply_error_logger, provide_grpc_response,
 };
-use futures_01::future::Future;
-use grpc_helpers::provide_grpc_response;
+use grpcio::{RpcStatus, RpcStatusCode, WriteFlags};
 use libra_mempool::proto::{
     mempool::{AddTransactionWithValidationRequest, HealthCheckRequest},
     mempool_client::MempoolClientTrait,
@@ -29,13 +33,22 @@ use libra_mempool_shared_proto::proto::mempool_status::{
     MempoolAddTransactionStatusCode::{self, MempoolIsFull},
 };
 use libra_types::{
+    access_path::AccessPath,
+    account_address::AccountAddress,
     proto::types::{UpdateToLatestLedgerRequest, UpdateToLatestLedgerResponse},
-    transaction::SignedTransaction,
+    transaction::{SignedTransaction, Version},
 };
 use logger::prelude::*;
 use metrics::counters::SVC_COUNTERS;
 use std::convert::TryFrom;
-use std::sync::Arc;
+use std::{
+    str::from_utf8,
+    sync::{
+        atomic::{AtomicUsize, Ordering},
+        Arc,
+    },
+    thread,
+};
 use storage_client::StorageRead;
 use vm_validator::vm_validator::{get_account_state, TransactionValidation};
 
@@ -52,7 +65,7 @@ pub mod fuzzing;
 #[derive(Clone)]
 pub struct AdmissionControlService<M, V> {
     /// gRPC client connecting Mempool.
-    mempool_client: Option<Arc<M>>,
+    mempool_client: Arc<M>,
     /// gRPC client to send read requests to Storage.
     storage_read_client: Arc<dyn StorageRead>,
     /// VM validator instance to validate transactions sent from wallets.
@@ -60,11 +73,10 @@ pub struct AdmissionControlService<M, V> {
     /// Flag indicating whether we need to check mempool before validation, drop txn if check
     /// fails.
     need_to_check_mempool_before_validation: bool,
-    /// mpsc sender connection to send transaction message to upstream proxy
-    upstream_proxy_sender: mpsc::UnboundedSender<(
-        SubmitTransactionRequest,
-        oneshot::Sender<Result<SubmitTransactionResponse>>,
-    )>,
+    /// Parameters of the account transactions / events subscriptions.
+    subscription_config: SubscriptionConfig,
+    /// Number of subscriptions being served.
+    active_subscriptions: Arc<AtomicUsize>,
 }
 
 impl<M: 'static, V> AdmissionControlService<M, V>
@@ -74,21 +86,19 @@ where
 {
     /// Constructs a new AdmissionControlService instance.
     pub fn new(
-        mempool_client: Option<Arc<M>>,
+        mempool_client: Arc<M>,
         storage_read_client: Arc<dyn StorageRead>,
         vm_validator: Arc<V>,
         need_to_check_mempool_before_validation: bool,
-        upstream_proxy_sender: mpsc::UnboundedSender<(
-            SubmitTransactionRequest,
-            oneshot::Sender<failure::Result<SubmitTransactionResponse>>,
-        )>,
+        subscription_config: SubscriptionConfig,
     ) -> Self {
         AdmissionControlService {
             mempool_client,
             storage_read_client,
             vm_validator,
             need_to_check_mempool_before_validation,
-            upstream_proxy_sender,
+            subscription_config,
+            active_subscriptions: Arc::new(AtomicUsize::new(0)),
         }
     }
 
@@ -167,10 +177,7 @@ where
     fn can_send_txn_to_mempool(&self) -> Result<bool> {
         if self.need_to_check_mempool_before_validation {
             let req = HealthCheckRequest::default();
-            let is_mempool_healthy = match &self.mempool_client {
-                Some(client) => client.health_check(&req)?.is_healthy,
-                None => false,
-            };
+            let is_mempool_healthy = self.mempool_client.health_check(&req)?.is_healthy;
             return Ok(is_mempool_healthy);
         }
         Ok(true)
@@ -181,31 +188,26 @@ where
         &self,
         add_transaction_request: AddTransactionWithValidationRequest,
     ) -> Result<SubmitTransactionResponse> {
-        match &self.mempool_client {
-            Some(mempool_client) => {
-                let mempool_result =
-                    mempool_client.add_transaction_with_validation(&add_transaction_request)?;
+        let mempool_result = self
+            .mempool_client
+            .add_transaction_with_validation(&add_transaction_request)?;
 
-                debug!("[GRPC] Done with transaction submission request");
-                let mut response = SubmitTransactionResponse::default();
-                if let Some(status) = mempool_result.status {
-                    if status.code() == MempoolAddTransactionStatusCode::Valid {
-                        OP_COUNTERS.inc_by("submit_txn.txn_accepted", 1);
-                        response.status =
-                            Some(Status::AcStatus(AdmissionControlStatus::Accepted.into()));
-                    } else {
-                        debug!(
-                            "txn failed in mempool, status: {:?}, txn: {:?}",
-                            status, add_transaction_request.signed_txn
-                        );
-                        OP_COUNTERS.inc_by("submit_txn.mempool.failure", 1);
-                        response.status = Some(Status::MempoolStatus(status));
-                    }
-                }
-                Ok(response)
+        debug!("[GRPC] Done with transaction submission request");
+        let mut response = SubmitTransactionResponse::default();
+        if let Some(status) = mempool_result.status {
+            if status.code() == MempoolAddTransactionStatusCode::Valid {
+                OP_COUNTERS.inc_by("submit_txn.txn_accepted", 1);
+                response.status = Some(Status::AcStatus(AdmissionControlStatus::Accepted.into()));
+            } else {
+                debug!(
+                    "txn failed in mempool, status: {:?}, txn: {:?}",
+                    status, add_transaction_request.signed_txn
+                );
+                OP_COUNTERS.inc_by("submit_txn.mempool.failure", 1);
+                response.status = Some(Status::MempoolStatus(status));
             }
-            None => Err(format_err!("Mempool is not initialized")),
         }
+        Ok(response)
     }
 
     /// Pass the UpdateToLatestLedgerRequest to Storage for read query.
@@ -230,6 +232,81 @@ where
         );
         Ok(rust_resp.into())
     }
+
+    /// Execute the transaction against the latest state via VM. Neither Mempool nor Storage is
+    /// updated with the output.
+    pub(crate) fn simulate_transaction_inner(
+        &self,
+        req: SimulateTransactionRequest,
+    ) -> Result<SimulateTransactionResponse> {
+        let signed_txn = req
+            .signed_txn
+            .ok_or_else(|| format_err!("Missing signed_txn"))
+            .and_then(SignedTransaction::try_from)?;
+        let output = self
+            .vm_validator
+            .simulate_transaction(signed_txn, req.skip_signature_check)
+            .wait()?;
+        OP_COUNTERS.inc("simulate_txn");
+        Ok(output.into())
+    }
+
+    /// Serves a subscription starting from `cursor` on a separate thread, which streams the new
+    /// data to `sink`.
+    fn start_subscription(
+        &self,
+        ctx: grpcio::RpcContext<'_>,
+        cursor: Result<SubscriptionCursor>,
+        client_known_version: Version,
+        sink: grpcio::ServerStreamingSink<UpdateToLatestLedgerResponse>,
+    ) {
+        let cursor = match cursor {
+            Ok(cursor) => cursor,
+            Err(e) => {
+                let status = create_grpc_invalid_arg_status(
+                    from_utf8(ctx.method()).expect("Unable to convert function name to string"),
+                    e,
+                );
+                ctx.spawn(sink.fail(status).map_err(default_reply_error_logger));
+                return;
+            }
+        };
+        let active_subscriptions = self.active_subscriptions.fetch_add(1, Ordering::SeqCst);
+        if active_subscriptions >= self.subscription_config.max_concurrent_subscriptions {
+            self.active_subscriptions.fetch_sub(1, Ordering::SeqCst);
+            OP_COUNTERS.inc("subscription.rejected");
+            let status = RpcStatus::new(
+                RpcStatusCode::RESOURCE_EXHAUSTED,
+                Some("Too many subscriptions".to_string()),
+            );
+            ctx.spawn(sink.fail(status).map_err(default_reply_error_logger));
+            return;
+        }
+        OP_COUNTERS.inc("subscription.started");
+
+        let subscription = Subscription::new(
+            Arc::clone(&self.storage_read_client),
+            cursor,
+            client_known_version,
+            self.subscription_config.batch_size,
+        );
+        let poll_interval = self.subscription_config.poll_interval;
+        let active_subscriptions = Arc::clone(&self.active_subscriptions);
+        let (sender, receiver) = mpsc_01::channel(0);
+        thread::spawn(move || {
+            subscription.run(sender, poll_interval);
+            active_subscriptions.fetch_sub(1, Ordering::SeqCst);
+        });
+
+        let responses = receiver
+            .map(|response| (response, WriteFlags::default()))
+            .map_err(|()| grpcio::Error::RemoteStopped);
+        ctx.spawn(
+            sink.send_all(responses)
+                .map(|_| ())
+                .map_err(default_reply_error_logger),
+        );
+    }
 }
 
 impl<M: 'static, V> AdmissionControl for AdmissionControlService<M, V>
@@ -248,29 +325,7 @@ where
     ) {
         debug!("[GRPC] AdmissionControl::submit_transaction");
         let _timer = SVC_COUNTERS.req(&ctx);
-        let resp = match self.mempool_client {
-            None => {
-                let (req_sender, res_receiver) = oneshot::channel();
-                let sent_result = self.upstream_proxy_sender.unbounded_send((req, req_sender));
-                match sent_result {
-                    Ok(()) => {
-                        let result = block_on(res_receiver);
-                        match result {
-                            Ok(res) => res,
-                            Err(e) => Err(format_err!(
-                                "[admission-control] Upstream transaction failed with error: {:?}",
-                                e
-                            )),
-                        }
-                    }
-                    Err(e) => Err(format_err!(
-                        "[admission-control] Failed to submit write request with error: {:?}",
-                        e
-                    )),
-                }
-            }
-            Some(_) => self.submit_transaction_inner(req),
-        };
+        let resp = self.submit_transaction_inner(req);
         provide_grpc_response(resp, ctx, sink);
     }
 
@@ -291,4 +346,61 @@ where
         let resp = self.update_to_latest_ledger_inner(req);
         provide_grpc_response(resp, ctx, sink);
     }
+
+    /// Execute a transaction against the latest state and return its output (gas used, status,
+    /// events and write set). The transaction is not submitted to Mempool, so this API is served
+    /// by full nodes as well.
+    fn simulate_transaction(
+        &mut self,
+        ctx: grpcio::RpcContext<'_>,
+        req: SimulateTransactionRequest,
+        sink: grpcio::UnarySink<SimulateTransactionResponse>,
+    ) {
+        debug!("[GRPC] AdmissionControl::simulate_transaction");
+        let _timer = SVC_COUNTERS.req(&ctx);
+        let resp = self.simulate_transaction_inner(req);
+        provide_grpc_response(resp, ctx, sink);
+    }
+
+    /// Streams the transactions sent by an account as they get committed, starting from the
+    /// requested sequence number.
+    fn subscribe_to_account_transactions(
+        &mut self,
+        ctx: grpcio::RpcContext<'_>,
+        req: SubscribeToAccountTransactionsRequest,
+        sink: grpcio::ServerStreamingSink<UpdateToLatestLedgerResponse>,
+    ) {
+        debug!("[GRPC] AdmissionControl::subscribe_to_account_transactions");
+        let _timer = SVC_COUNTERS.req(&ctx);
+        let cursor = AccountAddress::try_from(req.account).map(|account| {
+            SubscriptionCursor::AccountTransactions {
+                account,
+                sequence_number: req.start_sequence_number,
+                fetch_events: req.fetch_events,
+            }
+        });
+        self.start_subscription(ctx, cursor, req.client_known_version, sink);
+    }
+
+    /// Streams the events emitted on an event access path as they get committed, starting from
+    /// the requested event sequence number.
+    fn subscribe_to_events(
+        &mut self,
+        ctx: grpcio::RpcContext<'_>,
+        req: SubscribeToEventsRequest,
+        sink: grpcio::ServerStreamingSink<UpdateToLatestLedgerResponse>,
+    ) {
+        debug!("[GRPC] AdmissionControl::subscribe_to_events");
+        let _timer = SVC_COUNTERS.req(&ctx);
+        let start_event_seq_num = req.start_event_seq_num;
+        let cursor = req
+            .access_path
+            .ok_or_else(|| format_err!("Missing access_path"))
+            .and_then(AccessPath::try_from)
+            .map(|access_path| SubscriptionCursor::Events {
+                access_path,
+                event_seq_num: start_event_seq_num,
+            });
+        self.start_subscription(ctx, cursor, req.client_known_version, sink);
+    }
 }
@@ -7,19 +7,21 @@
 //! Admission Control
 //!
 //! Admission Control (AC) is the public API end point taking public gRPC requests from clients.
-//! AC serves two types of request from clients:
+//! AC serves three types of request from clients:
 //! 1. SubmitTransaction, to submit transaction to associated validator.
 //! 2. UpdateToLatestLedger, to query storage, e.g. account state, transaction log, and proofs.
+//! 3. SubscribeToAccountTransactions and SubscribeToEvents, to stream the transactions of an
+//!    account or the events of an event access path as they get committed.
 
 /// AC gRPC service.
 pub mod admission_control_service;
 #[cfg(any(test, feature = "fuzzing"))]
 /// Useful Mocks
 pub mod mocks;
-/// AC runtime to launch gRPC and network service
+/// AC runtime to launch gRPC service
 pub mod runtime;
-/// Handler for sending transaction write requests upstream if needed
-mod upstream_proxy;
+/// Subscriptions streaming account transactions and events to clients
+pub mod subscription;
 use lazy_static::lazy_static;
 use metrics::OpMetrics;
 
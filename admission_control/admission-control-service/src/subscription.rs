@@ -0,0 +1,206 @@
+// Copyright (c) The Libra Core Contributors
+// SPDX-License-Identifier: Apache-2.0
+
+//! Subscriptions push the data committed after a cursor provided by the client (the sequence
+//! number of a transaction sent by an account or of an event) instead of having the client poll
+//! AC. Every subscription polls storage on its own thread and streams each batch of new data with
+//! the same proofs as UpdateToLatestLedger.
+
+use config::config::AdmissionControlConfig;
+use failure::prelude::*;
+use futures_01::{sink::Sink, sync::mpsc, Future};
+use libra_types::{
+    access_path::AccessPath,
+    account_address::AccountAddress,
+    get_with_proof::{RequestItem, ResponseItem, UpdateToLatestLedgerResponse},
+    proto::types::UpdateToLatestLedgerResponse as ProtoUpdateToLatestLedgerResponse,
+    transaction::Version,
+};
+use logger::prelude::*;
+use std::{sync::Arc, thread, time::Duration};
+use storage_client::StorageRead;
+
+#[cfg(test)]
+#[path = "unit_tests/subscription_test.rs"]
+mod subscription_test;
+
+/// Parameters of the subscriptions served by AC.
+#[derive(Clone, Copy, Debug)]
+pub struct SubscriptionConfig {
+    /// Max number of subscriptions served at the same time.
+    pub max_concurrent_subscriptions: usize,
+    /// Interval between two polls of storage for new data.
+    pub poll_interval: Duration,
+    /// Max number of transactions / events sent in a single response.
+    pub batch_size: u64,
+}
+
+impl From<&AdmissionControlConfig> for SubscriptionConfig {
+    fn from(config: &AdmissionControlConfig) -> Self {
+        Self {
+            max_concurrent_subscriptions: config.max_concurrent_subscriptions,
+            poll_interval: Duration::from_millis(config.subscription_poll_interval_ms),
+            batch_size: config.subscription_batch_size,
+        }
+    }
+}
+
+/// Position of a subscription: the first transaction / event that has not been streamed yet.
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub(crate) enum SubscriptionCursor {
+    AccountTransactions {
+        account: AccountAddress,
+        sequence_number: u64,
+        fetch_events: bool,
+    },
+    Events {
+        access_path: AccessPath,
+        event_seq_num: u64,
+    },
+}
+
+impl SubscriptionCursor {
+    /// Returns the items requesting up to `batch_size` transactions / events from the cursor.
+    fn request_items(&self, batch_size: u64) -> Vec<RequestItem> {
+        match self {
+            SubscriptionCursor::AccountTransactions {
+                account,
+                sequence_number,
+                fetch_events,
+            } => (*sequence_number..sequence_number.saturating_add(batch_size))
+                .map(|sequence_number| RequestItem::GetAccountTransactionBySequenceNumber {
+                    account: *account,
+                    sequence_number,
+                    fetch_events: *fetch_events,
+                })
+                .collect(),
+            SubscriptionCursor::Events {
+                access_path,
+                event_seq_num,
+            } => vec![RequestItem::GetEventsByEventAccessPath {
+                access_path: access_path.clone(),
+                start_event_seq_num: *event_seq_num,
+                ascending: true,
+                limit: batch_size,
+            }],
+        }
+    }
+
+    /// Moves the cursor past the committed transactions / events of `response_items` (the
+    /// response to `request_items`) and returns the items carrying them.
+    fn advance(&mut self, response_items: Vec<ResponseItem>) -> Result<Vec<ResponseItem>> {
+        let mut new_items = vec![];
+        for item in response_items {
+            match (&mut *self, &item) {
+                (
+                    SubscriptionCursor::AccountTransactions {
+                        sequence_number, ..
+                    },
+                    ResponseItem::GetAccountTransactionBySequenceNumber {
+                        signed_transaction_with_proof,
+                        ..
+                    },
+                ) => {
+                    // The transactions after the first uncommitted one are not committed either.
+                    if signed_transaction_with_proof.is_none() {
+                        break;
+                    }
+                    *sequence_number += 1;
+                }
+                (
+                    SubscriptionCursor::Events { event_seq_num, .. },
+                    ResponseItem::GetEventsByEventAccessPath {
+                        events_with_proof, ..
+                    },
+                ) => {
+                    if events_with_proof.is_empty() {
+                        break;
+                    }
+                    *event_seq_num += events_with_proof.len() as u64;
+                }
+                _ => bail!("Unexpected response item {:?}", item),
+            }
+            new_items.push(item);
+        }
+        Ok(new_items)
+    }
+}
+
+/// A subscription of a client to the transactions sent by an account or to the events emitted on
+/// an event access path.
+pub(crate) struct Subscription {
+    storage_read_client: Arc<dyn StorageRead>,
+    cursor: SubscriptionCursor,
+    // version of the latest LedgerInfo sent to the client
+    client_known_version: Version,
+    batch_size: u64,
+    // whether the last poll found nothing new, in which case storage is polled for a single
+    // transaction / event until new data shows up
+    idle: bool,
+}
+
+impl Subscription {
+    pub(crate) fn new(
+        storage_read_client: Arc<dyn StorageRead>,
+        cursor: SubscriptionCursor,
+        client_known_version: Version,
+        batch_size: u64,
+    ) -> Self {
+        Self {
+            storage_read_client,
+            cursor,
+            client_known_version,
+            batch_size,
+            idle: false,
+        }
+    }
+
+    /// Reads the transactions / events committed after the cursor from storage.
+    /// Returns None if nothing new has been committed.
+    pub(crate) fn poll(&mut self) -> Result<Option<ProtoUpdateToLatestLedgerResponse>> {
+        let batch_size = if self.idle { 1 } else { self.batch_size };
+        let (response_items, ledger_info_with_sigs, validator_change_events, consistency_proof) =
+            self.storage_read_client.update_to_latest_ledger(
+                self.client_known_version,
+                self.cursor.request_items(batch_size),
+            )?;
+        let response_items = self.cursor.advance(response_items)?;
+        self.idle = response_items.is_empty();
+        if self.idle {
+            return Ok(None);
+        }
+        self.client_known_version = ledger_info_with_sigs.ledger_info().version();
+        Ok(Some(
+            UpdateToLatestLedgerResponse::new(
+                response_items,
+                ledger_info_with_sigs,
+                validator_change_events,
+                consistency_proof,
+            )
+            .into(),
+        ))
+    }
+
+    /// Polls storage every `poll_interval` and sends the new data to `sender` until either the
+    /// client goes away or storage fails.
+    pub(crate) fn run(
+        mut self,
+        mut sender: mpsc::Sender<ProtoUpdateToLatestLedgerResponse>,
+        poll_interval: Duration,
+    ) {
+        while !sender.is_closed() {
+            match self.poll() {
+                Ok(Some(response)) => match sender.send(response).wait() {
+                    Ok(s) => sender = s,
+                    Err(_) => break,
+                },
+                Ok(None) => thread::sleep(poll_interval),
+                Err(e) => {
+                    error!("[admission control] Subscription failed: {:?}", e);
+                    break;
+                }
+            }
+        }
+        debug!("[admission control] Subscription {:?} terminated", self.cursor);
+    }
+}
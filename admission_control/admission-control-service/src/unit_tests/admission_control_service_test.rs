@@ -3,18 +3,20 @@
 
 use crate::{
     admission_control_service::{
-        AdmissionControlService, SubmitTransactionRequest,
+        AdmissionControlService, SimulateTransactionRequest, SubmitTransactionRequest,
         SubmitTransactionResponse as ProtoSubmitTransactionResponse,
     },
     mocks::local_mock_mempool::LocalMockMempool,
+    subscription::SubscriptionConfig,
 };
 use admission_control_proto::{AdmissionControlStatus, SubmitTransactionResponse};
+use config::config::AdmissionControlConfig;
 use crypto::{ed25519::*, test_utils::TEST_SEED};
-use futures::channel::mpsc;
 use libra_mempool_shared_proto::proto::mempool_status::MempoolAddTransactionStatusCode;
 use libra_types::{
     account_address::{AccountAddress, ADDRESS_LENGTH},
     test_helpers::transaction_test_helpers::get_test_signed_txn,
+    transaction::{TransactionOutput, TransactionStatus},
     vm_error::{StatusCode, VMStatus},
 };
 use rand::SeedableRng;
@@ -24,13 +26,12 @@ use storage_service::mocks::mock_storage_client::MockStorageReadClient;
 use vm_validator::mocks::mock_vm_validator::MockVMValidator;
 
 pub fn create_ac_service_for_ut() -> AdmissionControlService<LocalMockMempool, MockVMValidator> {
-    let (upstream_proxy_sender, _) = mpsc::unbounded();
     AdmissionControlService::new(
-        Some(Arc::new(LocalMockMempool::new())),
+        Arc::new(LocalMockMempool::new()),
         Arc::new(MockStorageReadClient),
         Arc::new(MockVMValidator),
         false,
-        upstream_proxy_sender,
+        SubscriptionConfig::from(&AdmissionControlConfig::default()),
     )
 }
 
@@ -186,3 +187,43 @@ fn test_submit_txn_inner_mempool() {
         MempoolAddTransactionStatusCode::MempoolIsFull,
     );
 }
+
+#[test]
+fn test_simulate_txn_inner() {
+    let mut rng = ::rand::rngs::StdRng::from_seed(TEST_SEED);
+    let ac_service = create_ac_service_for_ut();
+    let keypair = compat::generate_keypair(&mut rng);
+    let test_key = compat::generate_keypair(&mut rng);
+    let simulate = |sender, public_key, skip_signature_check| {
+        let mut req = SimulateTransactionRequest::default();
+        req.signed_txn =
+            Some(get_test_signed_txn(sender, 0, keypair.0.clone(), public_key, None).into());
+        req.skip_signature_check = skip_signature_check;
+        let response = ac_service.simulate_transaction_inner(req).unwrap();
+        TransactionOutput::try_from(response).unwrap().status().clone()
+    };
+
+    let sender = AccountAddress::new([8; ADDRESS_LENGTH]);
+    assert_eq!(
+        simulate(sender, keypair.1.clone(), false),
+        TransactionStatus::Keep(VMStatus::new(StatusCode::EXECUTED))
+    );
+    assert_eq!(
+        simulate(sender, test_key.1.clone(), false),
+        TransactionStatus::Discard(VMStatus::new(StatusCode::INVALID_SIGNATURE))
+    );
+    assert_eq!(
+        simulate(sender, test_key.1.clone(), true),
+        TransactionStatus::Keep(VMStatus::new(StatusCode::EXECUTED))
+    );
+    let sender = AccountAddress::new([3; ADDRESS_LENGTH]);
+    assert_eq!(
+        simulate(sender, keypair.1.clone(), false),
+        TransactionStatus::Discard(VMStatus::new(StatusCode::SEQUENCE_NUMBER_TOO_NEW))
+    );
+
+    // Requests without a transaction are rejected.
+    assert!(ac_service
+        .simulate_transaction_inner(SimulateTransactionRequest::default())
+        .is_err());
+}
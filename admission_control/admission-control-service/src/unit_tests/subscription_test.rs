@@ -0,0 +1,126 @@
+// Copyright (c) The Libra Core Contributors
+// SPDX-License-Identifier: Apache-2.0
+
+use crate::subscription::SubscriptionCursor;
+use libra_types::{
+    access_path::AccessPath,
+    account_address::AccountAddress,
+    account_state_blob::AccountStateWithProof,
+    contract_event::EventWithProof,
+    get_with_proof::{RequestItem, ResponseItem},
+    transaction::SignedTransactionWithProof,
+};
+use proptest::prelude::*;
+use proptest_helpers::ValueGenerator;
+
+fn transaction_item(gen: &mut ValueGenerator, committed: bool) -> ResponseItem {
+    let (signed_transaction_with_proof, proof_of_current_sequence_number) = if committed {
+        (Some(gen.generate(any::<SignedTransactionWithProof>())), None)
+    } else {
+        (None, Some(gen.generate(any::<AccountStateWithProof>())))
+    };
+    ResponseItem::GetAccountTransactionBySequenceNumber {
+        signed_transaction_with_proof,
+        proof_of_current_sequence_number,
+    }
+}
+
+fn events_item(gen: &mut ValueGenerator, num_events: usize) -> ResponseItem {
+    ResponseItem::GetEventsByEventAccessPath {
+        events_with_proof: (0..num_events)
+            .map(|_| gen.generate(any::<EventWithProof>()))
+            .collect(),
+        proof_of_latest_event: gen.generate(any::<AccountStateWithProof>()),
+    }
+}
+
+#[test]
+fn test_account_transactions_cursor() {
+    let mut gen = ValueGenerator::new();
+    let account = AccountAddress::random();
+    let mut cursor = SubscriptionCursor::AccountTransactions {
+        account,
+        sequence_number: 5,
+        fetch_events: true,
+    };
+    assert_eq!(
+        cursor.request_items(2),
+        vec![
+            RequestItem::GetAccountTransactionBySequenceNumber {
+                account,
+                sequence_number: 5,
+                fetch_events: true,
+            },
+            RequestItem::GetAccountTransactionBySequenceNumber {
+                account,
+                sequence_number: 6,
+                fetch_events: true,
+            },
+        ]
+    );
+
+    // Nothing committed yet.
+    let response_items = vec![transaction_item(&mut gen, false)];
+    assert!(cursor.advance(response_items).unwrap().is_empty());
+
+    // Only the transactions committed before the first uncommitted one are streamed.
+    let response_items = vec![
+        transaction_item(&mut gen, true),
+        transaction_item(&mut gen, true),
+        transaction_item(&mut gen, false),
+        transaction_item(&mut gen, true),
+    ];
+    assert_eq!(
+        cursor.advance(response_items.clone()).unwrap(),
+        response_items[..2].to_vec()
+    );
+    assert_eq!(
+        cursor,
+        SubscriptionCursor::AccountTransactions {
+            account,
+            sequence_number: 7,
+            fetch_events: true,
+        }
+    );
+
+    assert!(cursor.advance(vec![events_item(&mut gen, 1)]).is_err());
+}
+
+#[test]
+fn test_events_cursor() {
+    let mut gen = ValueGenerator::new();
+    let access_path = AccessPath::new_for_received_event(AccountAddress::random());
+    let mut cursor = SubscriptionCursor::Events {
+        access_path: access_path.clone(),
+        event_seq_num: 0,
+    };
+    assert_eq!(
+        cursor.request_items(10),
+        vec![RequestItem::GetEventsByEventAccessPath {
+            access_path: access_path.clone(),
+            start_event_seq_num: 0,
+            ascending: true,
+            limit: 10,
+        }]
+    );
+
+    // Nothing emitted yet.
+    assert!(cursor
+        .advance(vec![events_item(&mut gen, 0)])
+        .unwrap()
+        .is_empty());
+
+    let response_items = vec![events_item(&mut gen, 3)];
+    assert_eq!(cursor.advance(response_items.clone()).unwrap(), response_items);
+    assert_eq!(
+        cursor,
+        SubscriptionCursor::Events {
+            access_path,
+            event_seq_num: 3,
+        }
+    );
+
+    assert!(cursor
+        .advance(vec![transaction_item(&mut gen, true)])
+        .is_err());
+}
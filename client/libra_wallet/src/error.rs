@@ -64,6 +64,12 @@ impl convert::From<failure::prelude::Error> for WalletError {
     }
 }
 
+impl convert::From<lcs::Error> for WalletError {
+    fn from(err: lcs::Error) -> WalletError {
+        WalletError::LibraWalletGeneric(format!("{}", err))
+    }
+}
+
 impl convert::From<ed25519_dalek::SignatureError> for WalletError {
     fn from(err: ed25519_dalek::SignatureError) -> WalletError {
         WalletError::LibraWalletGeneric(format!("{}", err))
@@ -0,0 +1,418 @@
+// Copyright (c) The Libra Core Contributors
+// SPDX-License-Identifier: Apache-2.0
+
+//! An encrypted keystore for the LibraWallet. The Mnemonic of a wallet, along with metadata on
+//! each of its accounts, is encrypted with AES-256-GCM under a key derived from a passphrase by
+//! the scrypt password-based Key Derivation Function (KDF).
+//!
+//! A keystore file starts with a header made of `KEYSTORE_MAGIC` and the little-endian version
+//! of the format, followed by the LCS serialization of a `Keystore`. The header and the KDF
+//! parameters are authenticated along with the ciphertext, so that tampering with any of them
+//! makes unlocking fail.
+
+use crate::{
+    error::*, key_factory::ChildNumber, mnemonic::Mnemonic, wallet_library::WalletLibrary,
+};
+use byteorder::{ByteOrder, LittleEndian};
+use crypto::{
+    aead::{AeadDecryptor, AeadEncryptor},
+    aes::KeySize,
+    aes_gcm::AesGcm,
+    scrypt::{scrypt, ScryptParams},
+};
+use libra_types::account_address::AccountAddress;
+use rand::{rngs::EntropyRng, Rng};
+use serde::{Deserialize, Serialize};
+use std::{
+    fs::{self, File, OpenOptions},
+    io::{Read, Write},
+    path::Path,
+};
+
+/// Bytes every keystore file starts with.
+pub const KEYSTORE_MAGIC: &[u8; 8] = b"LIBRAKS\0";
+/// Version of the keystore format written by this library.
+pub const KEYSTORE_VERSION: u32 = 1;
+
+const HEADER_LENGTH: usize = 12;
+const KEY_LENGTH: usize = 32;
+const NONCE_LENGTH: usize = 12;
+const SALT_LENGTH: usize = 32;
+const TAG_LENGTH: usize = 16;
+const MAX_LOG_N: u8 = 18;
+const MAX_R: u32 = 8;
+const MAX_P: u32 = 16;
+
+/// Parameters of the scrypt KDF deriving the encryption key from the passphrase.
+#[derive(Clone, Debug, Eq, PartialEq, Serialize, Deserialize)]
+pub struct KdfParams {
+    /// Base 2 logarithm of the CPU/memory cost.
+    pub log_n: u8,
+    /// Block size.
+    pub r: u32,
+    /// Parallelization.
+    pub p: u32,
+    /// Random salt.
+    pub salt: Vec<u8>,
+}
+
+impl KdfParams {
+    /// Parameters with the cost recommended for interactive use and a fresh random salt.
+    #[allow(clippy::new_without_default)]
+    pub fn new() -> Self {
+        Self::with_cost(15, 8, 1)
+    }
+
+    /// Parameters with the given cost and a fresh random salt. Note that a low cost makes the
+    /// passphrase easier to brute force and should only be used in tests.
+    pub fn with_cost(log_n: u8, r: u32, p: u32) -> Self {
+        let mut rng = EntropyRng::new();
+        let salt: [u8; SALT_LENGTH] = rng.gen();
+        Self {
+            log_n,
+            r,
+            p,
+            salt: salt.to_vec(),
+        }
+    }
+
+    fn derive_key(&self, passphrase: &str) -> Result<[u8; KEY_LENGTH]> {
+        // Bound the cost so that a forged keystore cannot exhaust the memory of the client.
+        let valid_cost = (1..=MAX_LOG_N).contains(&self.log_n)
+            && (1..=MAX_R).contains(&self.r)
+            && (1..=MAX_P).contains(&self.p)
+            && (self.log_n as u32) < self.r * 16;
+        if !valid_cost {
+            return Err(WalletError::LibraWalletGeneric(
+                "Invalid keystore KDF parameters".to_string(),
+            ));
+        }
+        let mut key = [0u8; KEY_LENGTH];
+        let params = ScryptParams::new(self.log_n, self.r, self.p);
+        scrypt(passphrase.as_bytes(), &self.salt, &params, &mut key);
+        Ok(key)
+    }
+}
+
+/// Metadata on an account of the wallet, stored encrypted in the keystore.
+#[derive(Clone, Debug, Eq, PartialEq, Serialize, Deserialize)]
+pub struct AccountMetadata {
+    /// ChildNumber of the key of the account.
+    pub child_number: ChildNumber,
+    /// Address of the account, checked against the key derived when unlocking.
+    pub address: AccountAddress,
+    /// Label given to the account by the user.
+    pub label: Option<String>,
+}
+
+/// The plaintext content of a keystore.
+#[derive(Serialize, Deserialize)]
+struct KeystorePayload {
+    mnemonic: String,
+    accounts: Vec<AccountMetadata>,
+}
+
+/// An encrypted wallet, as stored in a keystore file.
+#[derive(Clone, Debug, Eq, PartialEq, Serialize, Deserialize)]
+pub struct Keystore {
+    kdf_params: KdfParams,
+    nonce: Vec<u8>,
+    ciphertext: Vec<u8>,
+    tag: Vec<u8>,
+}
+
+impl Keystore {
+    /// Encrypts the Mnemonic and the accounts of `wallet` under `passphrase`.
+    pub fn encrypt(
+        wallet: &WalletLibrary,
+        passphrase: &str,
+        kdf_params: KdfParams,
+    ) -> Result<Self> {
+        let accounts = wallet
+            .get_addresses()?
+            .into_iter()
+            .enumerate()
+            .map(|(i, address)| AccountMetadata {
+                child_number: ChildNumber::new(i as u64),
+                address,
+                label: wallet.label(&address).map(str::to_string),
+            })
+            .collect();
+        let payload = KeystorePayload {
+            mnemonic: wallet.mnemonic(),
+            accounts,
+        };
+        Self::encrypt_payload(&payload, passphrase, kdf_params)
+    }
+
+    /// Decrypts the keystore with `passphrase` and recreates the wallet it holds, along with the
+    /// labels of its accounts.
+    pub fn decrypt(&self, passphrase: &str) -> Result<WalletLibrary> {
+        let payload = self.decrypt_payload(passphrase)?;
+        let mut wallet = WalletLibrary::new_from_mnemonic(Mnemonic::from(&payload.mnemonic)?);
+        wallet.generate_addresses(payload.accounts.len() as u64)?;
+        let addresses = wallet.get_addresses()?;
+        for (i, (account, address)) in payload.accounts.into_iter().zip(addresses).enumerate() {
+            if account.child_number != ChildNumber::new(i as u64) || account.address != address {
+                return Err(WalletError::LibraWalletGeneric(format!(
+                    "Keystore account {} does not match the key derived from the mnemonic",
+                    account.address
+                )));
+            }
+            if let Some(label) = account.label {
+                wallet.set_label(&account.address, label)?;
+            }
+        }
+        Ok(wallet)
+    }
+
+    /// Re-encrypts the content of the keystore under `new_passphrase`, with new KDF parameters.
+    pub fn change_passphrase(
+        &self,
+        old_passphrase: &str,
+        new_passphrase: &str,
+        kdf_params: KdfParams,
+    ) -> Result<Self> {
+        let payload = self.decrypt_payload(old_passphrase)?;
+        Self::encrypt_payload(&payload, new_passphrase, kdf_params)
+    }
+
+    /// Serializes the keystore, header included.
+    pub fn to_bytes(&self) -> Result<Vec<u8>> {
+        let mut bytes = header().to_vec();
+        bytes.extend(lcs::to_bytes(self)?);
+        Ok(bytes)
+    }
+
+    /// Deserializes a keystore, checking its header first.
+    pub fn from_bytes(bytes: &[u8]) -> Result<Self> {
+        if bytes.len() < HEADER_LENGTH || !bytes.starts_with(KEYSTORE_MAGIC) {
+            return Err(WalletError::LibraWalletGeneric(
+                "Not a Libra wallet keystore".to_string(),
+            ));
+        }
+        let version = LittleEndian::read_u32(&bytes[KEYSTORE_MAGIC.len()..HEADER_LENGTH]);
+        if version != KEYSTORE_VERSION {
+            return Err(WalletError::LibraWalletGeneric(format!(
+                "Unsupported keystore version {}",
+                version
+            )));
+        }
+        Ok(lcs::from_bytes(&bytes[HEADER_LENGTH..])?)
+    }
+
+    /// Writes the keystore to `output_file_path`, readable by its owner only where supported.
+    /// The keystore is written to a temporary file in the same directory, which then replaces
+    /// `output_file_path`, so that a crash never leaves a truncated keystore behind.
+    pub fn write(&self, output_file_path: &Path) -> Result<()> {
+        let file_name = output_file_path.file_name().ok_or_else(|| {
+            WalletError::LibraWalletGeneric(format!(
+                "Invalid keystore path {}",
+                output_file_path.display()
+            ))
+        })?;
+        let suffix: u64 = EntropyRng::new().gen();
+        let temp_file_path = output_file_path.with_file_name(format!(
+            ".{}.{:016x}.tmp",
+            file_name.to_string_lossy(),
+            suffix
+        ));
+
+        let mut options = OpenOptions::new();
+        options.write(true).create_new(true);
+        #[cfg(unix)]
+        {
+            use std::os::unix::fs::OpenOptionsExt;
+            options.mode(0o600);
+        }
+        let mut file = options.open(&temp_file_path)?;
+        let result = file
+            .write_all(&self.to_bytes()?)
+            .and_then(|()| file.sync_all())
+            .and_then(|()| fs::rename(&temp_file_path, output_file_path));
+        if result.is_err() {
+            let _ = fs::remove_file(&temp_file_path);
+        }
+        Ok(result?)
+    }
+
+    /// Reads a keystore from `input_file_path`.
+    pub fn read(input_file_path: &Path) -> Result<Self> {
+        Self::from_bytes(&fs::read(input_file_path)?)
+    }
+
+    /// Returns whether the file at `path` looks like a keystore, as opposed to a plaintext
+    /// recovery file.
+    pub fn is_keystore(path: &Path) -> bool {
+        let mut magic = [0u8; 8];
+        File::open(path)
+            .and_then(|mut file| file.read_exact(&mut magic))
+            .map(|_| magic == *KEYSTORE_MAGIC)
+            .unwrap_or(false)
+    }
+
+    fn encrypt_payload(
+        payload: &KeystorePayload,
+        passphrase: &str,
+        kdf_params: KdfParams,
+    ) -> Result<Self> {
+        let key = kdf_params.derive_key(passphrase)?;
+        let mut rng = EntropyRng::new();
+        let nonce: [u8; NONCE_LENGTH] = rng.gen();
+        let plaintext = lcs::to_bytes(payload)?;
+        let mut ciphertext = vec![0u8; plaintext.len()];
+        let mut tag = [0u8; TAG_LENGTH];
+        let aad = associated_data(&kdf_params)?;
+        let mut cipher = AesGcm::new(KeySize::KeySize256, &key, &nonce, &aad);
+        cipher.encrypt(&plaintext, &mut ciphertext, &mut tag);
+        Ok(Self {
+            kdf_params,
+            nonce: nonce.to_vec(),
+            ciphertext,
+            tag: tag.to_vec(),
+        })
+    }
+
+    fn decrypt_payload(&self, passphrase: &str) -> Result<KeystorePayload> {
+        if self.nonce.len() != NONCE_LENGTH || self.tag.len() != TAG_LENGTH {
+            return Err(WalletError::LibraWalletGeneric(
+                "Malformed keystore".to_string(),
+            ));
+        }
+        let key = self.kdf_params.derive_key(passphrase)?;
+        let mut plaintext = vec![0u8; self.ciphertext.len()];
+        let aad = associated_data(&self.kdf_params)?;
+        let mut cipher = AesGcm::new(KeySize::KeySize256, &key, &self.nonce, &aad);
+        if !cipher.decrypt(&self.ciphertext, &mut plaintext, &self.tag) {
+            return Err(WalletError::LibraWalletGeneric(
+                "Wrong passphrase or corrupted keystore".to_string(),
+            ));
+        }
+        Ok(lcs::from_bytes(&plaintext)?)
+    }
+}
+
+fn header() -> [u8; HEADER_LENGTH] {
+    let mut header = [0u8; HEADER_LENGTH];
+    header[..KEYSTORE_MAGIC.len()].copy_from_slice(KEYSTORE_MAGIC);
+    LittleEndian::write_u32(&mut header[KEYSTORE_MAGIC.len()..], KEYSTORE_VERSION);
+    header
+}
+
+fn associated_data(kdf_params: &KdfParams) -> Result<Vec<u8>> {
+    let mut aad = header().to_vec();
+    aad.extend(lcs::to_bytes(kdf_params)?);
+    Ok(aad)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use libra_tools::tempdir::TempPath;
+
+    fn test_wallet() -> WalletLibrary {
+        let mut wallet = WalletLibrary::new();
+        wallet.generate_addresses(3).unwrap();
+        let address = wallet.get_addresses().unwrap()[1];
+        wallet.set_label(&address, "savings".to_string()).unwrap();
+        wallet
+    }
+
+    fn encrypt(wallet: &WalletLibrary, passphrase: &str) -> Keystore {
+        // A low cost keeps the tests fast.
+        Keystore::encrypt(wallet, passphrase, KdfParams::with_cost(4, 8, 1)).unwrap()
+    }
+
+    #[test]
+    fn test_encrypt_decrypt() {
+        let wallet = test_wallet();
+        let unlocked = encrypt(&wallet, "passphrase")
+            .decrypt("passphrase")
+            .unwrap();
+
+        assert_eq!(unlocked.mnemonic(), wallet.mnemonic());
+        let addresses = wallet.get_addresses().unwrap();
+        assert_eq!(unlocked.get_addresses().unwrap(), addresses);
+        assert_eq!(unlocked.label(&addresses[0]), None);
+        assert_eq!(unlocked.label(&addresses[1]), Some("savings"));
+    }
+
+    #[test]
+    fn test_wrong_passphrase() {
+        let keystore = encrypt(&test_wallet(), "passphrase");
+        assert!(keystore.decrypt("wrong passphrase").is_err());
+    }
+
+    #[test]
+    fn test_change_passphrase() {
+        let wallet = test_wallet();
+        let keystore = encrypt(&wallet, "old");
+        let kdf_params = KdfParams::with_cost(4, 8, 1);
+        assert!(keystore
+            .change_passphrase("wrong", "new", kdf_params.clone())
+            .is_err());
+
+        let keystore = keystore
+            .change_passphrase("old", "new", kdf_params)
+            .unwrap();
+        assert!(keystore.decrypt("old").is_err());
+        let unlocked = keystore.decrypt("new").unwrap();
+        assert_eq!(unlocked.mnemonic(), wallet.mnemonic());
+    }
+
+    #[test]
+    fn test_tampered_kdf_params() {
+        let mut keystore = encrypt(&test_wallet(), "passphrase");
+        keystore.kdf_params.salt[0] ^= 1;
+        assert!(keystore.decrypt("passphrase").is_err());
+    }
+
+    #[test]
+    fn test_write_read() {
+        let wallet = test_wallet();
+        let file = TempPath::new();
+        let path = file.path();
+        encrypt(&wallet, "passphrase").write(path).unwrap();
+        assert!(Keystore::is_keystore(path));
+
+        let contents = String::from_utf8_lossy(&fs::read(path).unwrap()).to_string();
+        assert!(!contents.contains(&wallet.mnemonic()));
+        let unlocked = Keystore::read(path).unwrap().decrypt("passphrase").unwrap();
+        assert_eq!(unlocked.mnemonic(), wallet.mnemonic());
+    }
+
+    #[test]
+    fn test_overwrite() {
+        let dir = TempPath::new();
+        dir.create_as_dir().unwrap();
+        let path = dir.path().join("wallet.keystore");
+        encrypt(&test_wallet(), "old").write(&path).unwrap();
+        let wallet = test_wallet();
+        encrypt(&wallet, "new").write(&path).unwrap();
+
+        // The temporary file replaced the keystore.
+        assert_eq!(fs::read_dir(dir.path()).unwrap().count(), 1);
+        let unlocked = Keystore::read(&path).unwrap().decrypt("new").unwrap();
+        assert_eq!(unlocked.mnemonic(), wallet.mnemonic());
+    }
+
+    #[test]
+    fn test_kdf_cost_bounds() {
+        let wallet = test_wallet();
+        assert!(Keystore::encrypt(&wallet, "passphrase", KdfParams::with_cost(19, 8, 1)).is_err());
+        assert!(Keystore::encrypt(&wallet, "passphrase", KdfParams::with_cost(4, 9, 1)).is_err());
+        assert!(Keystore::encrypt(&wallet, "passphrase", KdfParams::with_cost(4, 8, 17)).is_err());
+        assert!(Keystore::encrypt(&wallet, "passphrase", KdfParams::with_cost(0, 8, 1)).is_err());
+    }
+
+    #[test]
+    fn test_unsupported_version() {
+        let keystore = encrypt(&test_wallet(), "passphrase");
+        let mut bytes = keystore.to_bytes().unwrap();
+        assert_eq!(Keystore::from_bytes(&bytes).unwrap(), keystore);
+
+        bytes[KEYSTORE_MAGIC.len()] = 2;
+        assert!(Keystore::from_bytes(&bytes).is_err());
+        assert!(Keystore::from_bytes(b"LIBRAKS").is_err());
+    }
+}
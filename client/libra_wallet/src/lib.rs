@@ -14,6 +14,9 @@ pub mod io_utils;
 /// Utils for key derivation
 pub mod key_factory;
 
+/// Encrypted keystore
+pub mod keystore;
+
 /// Utils for mnemonic seed
 pub mod mnemonic;
 
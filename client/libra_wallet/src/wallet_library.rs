@@ -2,19 +2,21 @@
 // SPDX-License-Identifier: Apache-2.0
 
 //! The following document is a minimalist version of Libra Wallet. Note that this Wallet does
-//! not promote security as the mnemonic is stored in unencrypted form. In future iterations,
-//! we will be realesing more robust Wallet implementations. It is our intention to present a
-//! foundation that is simple to understand and incrementally improve the LibraWallet
-//! implementation and it's security guarantees throughout testnet. For a more robust wallet
-//! reference, the authors suggest to audit the file of the same name in the rust-wallet crate.
-//! That file can be found here:
+//! not promote security as the mnemonic is held in unencrypted form in memory, and is only
+//! encrypted at rest when written to a keystore. In future iterations, we will be realesing
+//! more robust Wallet implementations. It is our intention to present a foundation that is
+//! simple to understand and incrementally improve the LibraWallet implementation and it's
+//! security guarantees throughout testnet. For a more robust wallet reference, the authors
+//! suggest to audit the file of the same name in the rust-wallet crate. That file can be found
+//! here:
 //!
 //! https://github.com/rust-bitcoin/rust-wallet/blob/master/wallet/src/walletlibrary.rs
 
 use crate::{
     error::*,
     io_utils,
-    key_factory::{ChildNumber, KeyFactory, Seed},
+    key_factory::{ChildNumber, ExtendedPrivKey, KeyFactory, Seed},
+    keystore::{KdfParams, Keystore},
     mnemonic::Mnemonic,
 };
 pub use libra_crypto::{
@@ -34,6 +36,7 @@ pub struct WalletLibrary {
     mnemonic: Mnemonic,
     key_factory: KeyFactory,
     addr_map: HashMap<AccountAddress, ChildNumber>,
+    labels: HashMap<AccountAddress, String>,
     key_leaf: ChildNumber,
 }
 
@@ -55,6 +58,7 @@ impl WalletLibrary {
             mnemonic,
             key_factory: KeyFactory::new(&seed).unwrap(),
             addr_map: HashMap::new(),
+            labels: HashMap::new(),
             key_leaf: ChildNumber(0),
         }
     }
@@ -79,6 +83,39 @@ impl WalletLibrary {
         Ok(wallet)
     }
 
+    /// Function that writes the wallet Mnemonic, along with the metadata of its accounts, to an
+    /// encrypted keystore file protected by passphrase
+    pub fn write_keystore(&self, output_file_path: &Path, passphrase: &str) -> Result<()> {
+        let keystore = Keystore::encrypt(self, passphrase, KdfParams::new())?;
+        keystore.write(output_file_path)
+    }
+
+    /// Unlock the wallet stored in the keystore file at input_file_path with passphrase
+    pub fn unlock_keystore(input_file_path: &Path, passphrase: &str) -> Result<WalletLibrary> {
+        Keystore::read(input_file_path)?.decrypt(passphrase)
+    }
+
+    /// Re-encrypt the keystore file at keystore_file_path under new_passphrase
+    pub fn change_keystore_passphrase(
+        keystore_file_path: &Path,
+        old_passphrase: &str,
+        new_passphrase: &str,
+    ) -> Result<()> {
+        let keystore = Keystore::read(keystore_file_path)?.change_passphrase(
+            old_passphrase,
+            new_passphrase,
+            KdfParams::new(),
+        )?;
+        keystore.write(keystore_file_path)
+    }
+
+    /// Export the wallet as the bytes of an encrypted keystore protected by passphrase, which
+    /// can be unlocked with `Keystore::from_bytes` and `Keystore::decrypt`
+    pub fn export_keystore(&self, passphrase: &str) -> Result<Vec<u8>> {
+        let keystore = Keystore::encrypt(self, passphrase, KdfParams::new())?;
+        keystore.to_bytes()
+    }
+
     /// Get the current ChildNumber in u64 format
     pub fn key_leaf(&self) -> u64 {
         self.key_leaf.0
@@ -123,6 +160,22 @@ impl WalletLibrary {
         }
     }
 
+    /// Sets the label of an account controlled by this wallet
+    pub fn set_label(&mut self, address: &AccountAddress, label: String) -> Result<()> {
+        if !self.addr_map.contains_key(address) {
+            return Err(WalletError::LibraWalletGeneric(
+                "Cannot label an address that is not in your wallet".to_string(),
+            ));
+        }
+        self.labels.insert(*address, label);
+        Ok(())
+    }
+
+    /// Returns the label of an account controlled by this wallet, if it was given one
+    pub fn label(&self, address: &AccountAddress) -> Option<&str> {
+        self.labels.get(address).map(String::as_str)
+    }
+
     /// Returns a list of all addresses controlled by this wallet that are currently held by the
     /// addr_map
     pub fn get_addresses(&self) -> Result<Vec<AccountAddress>> {
@@ -153,14 +206,31 @@ impl WalletLibrary {
     /// associated to a particular AccountAddress. If the PrivateKey associated to an
     /// AccountAddress is not contained in the addr_map, then this function will return an Error
     pub fn sign_txn(&self, txn: RawTransaction) -> Result<SignedTransaction> {
-        if let Some(child) = self.addr_map.get(&txn.sender()) {
-            let child_key = self.key_factory.private_child(child.clone())?;
-            let signature = child_key.sign(txn.hash());
-            Ok(SignedTransaction::new(
-                txn,
-                child_key.get_public(),
-                signature,
-            ))
+        let (public_key, signature) = self.sign_txn_with(&txn.sender(), &txn)?;
+        Ok(SignedTransaction::new(txn, public_key, signature))
+    }
+
+    /// Signs a Libra RawTransaction with the PrivateKey associated to a particular AccountAddress,
+    /// which does not need to be the sender of the transaction, and returns the signature along
+    /// with the PublicKey. This is how a key of a multi-signature account held by this wallet
+    /// contributes its partial signature.
+    pub fn sign_txn_with(
+        &self,
+        address: &AccountAddress,
+        txn: &RawTransaction,
+    ) -> Result<(Ed25519PublicKey, Ed25519Signature)> {
+        let child_key = self.private_child(address)?;
+        Ok((child_key.get_public(), child_key.sign(txn.hash())))
+    }
+
+    /// Returns the PublicKey associated to a particular AccountAddress.
+    pub fn get_public_key(&self, address: &AccountAddress) -> Result<Ed25519PublicKey> {
+        Ok(self.private_child(address)?.get_public())
+    }
+
+    fn private_child(&self, address: &AccountAddress) -> Result<ExtendedPrivKey> {
+        if let Some(child) = self.addr_map.get(address) {
+            self.key_factory.private_child(child.clone())
         } else {
             Err(WalletError::LibraWalletGeneric(
                 "Well, that address is nowhere to be found... This is awkward".to_string(),
@@ -2,6 +2,9 @@
 // SPDX-License-Identifier: Apache-2.0
 
 use crate::{client_proxy::ClientProxy, commands::*};
+use failure::prelude::*;
+use libra_wallet::keystore::Keystore;
+use std::path::Path;
 
 /// Major command for account related operations.
 pub struct AccountCommand {}
@@ -60,11 +63,22 @@ impl Command for AccountCommandRecoverWallet {
         "<file_path>"
     }
     fn get_description(&self) -> &'static str {
-        "Recover Libra wallet from the file path"
+        "Recover Libra wallet from the file path. Prompts for the passphrase if the file is an \
+         encrypted keystore"
     }
     fn execute(&self, client: &mut ClientProxy, params: &[&str]) {
         println!(">> Recovering Wallet");
-        match client.recover_wallet_accounts(&params) {
+        // Plaintext recovery files written by earlier versions of the client are still supported.
+        let passphrase = match params.get(1) {
+            Some(path) if Keystore::is_keystore(Path::new(path)) => {
+                match read_passphrase("Enter keystore passphrase: ") {
+                    Ok(passphrase) => Some(passphrase),
+                    Err(e) => return report_error("Error reading passphrase", e),
+                }
+            }
+            _ => None,
+        };
+        match client.recover_wallet_accounts(&params, passphrase.as_ref().map(String::as_str)) {
             Ok(account_data) => {
                 println!(
                     "Wallet recovered and the first {} child accounts were derived",
@@ -90,12 +104,16 @@ impl Command for AccountCommandWriteRecovery {
         "<file_path>"
     }
     fn get_description(&self) -> &'static str {
-        "Save Libra wallet mnemonic recovery seed to disk"
+        "Save Libra wallet mnemonic recovery seed to disk, encrypted with a passphrase"
     }
     fn execute(&self, client: &mut ClientProxy, params: &[&str]) {
         println!(">> Saving Libra wallet mnemonic recovery seed to disk");
-        match client.write_recovery(&params) {
-            Ok(_) => println!("Saved mnemonic seed to disk"),
+        let passphrase = match read_new_passphrase() {
+            Ok(passphrase) => passphrase,
+            Err(e) => return report_error("Error reading passphrase", e),
+        };
+        match client.write_recovery(&params, &passphrase) {
+            Ok(_) => println!("Saved encrypted mnemonic seed to disk"),
             Err(e) => report_error("Error writing mnemonic recovery seed to file", e),
         }
     }
@@ -150,3 +168,17 @@ impl Command for AccountCommandMint {
         }
     }
 }
+
+/// Reads a passphrase from the terminal without echoing it.
+fn read_passphrase(prompt: &str) -> Result<String> {
+    Ok(rpassword::read_password_from_tty(Some(prompt))?)
+}
+
+/// Reads a new passphrase from the terminal, asking for it twice to rule out typos.
+fn read_new_passphrase() -> Result<String> {
+    let passphrase = read_passphrase("Enter new keystore passphrase: ")?;
+    ensure!(!passphrase.is_empty(), "Passphrase cannot be empty");
+    let confirmation = read_passphrase("Confirm keystore passphrase: ")?;
+    ensure!(passphrase == confirmation, "Passphrases do not match");
+    Ok(passphrase)
+}
@@ -4,7 +4,12 @@
 use crate::{commands::*, grpc_client::GRPCClient, AccountData, AccountStatus};
 use admission_control_proto::proto::admission_control::SubmitTransactionRequest;
 use config::{config::PersistableConfig, trusted_peers::ConsensusPeersConfig};
-use crypto::{ed25519::*, test_utils::KeyPair};
+use crypto::{
+    ed25519::*,
+    multi_ed25519::{MultiEd25519PublicKey, MultiEd25519Signature},
+    test_utils::KeyPair,
+    traits::ValidKeyStringExt,
+};
 use failure::prelude::*;
 use libra_tools::tempdir::TempPath;
 use libra_types::{
@@ -18,11 +23,11 @@ use libra_types::{
     contract_event::{ContractEvent, EventWithProof},
     transaction::{
         parse_as_transaction_argument, RawTransaction, Script, SignedTransaction,
-        TransactionPayload, Version,
+        TransactionOutput, TransactionPayload, Version,
     },
     transaction_helpers::{create_signed_txn, create_unsigned_txn, TransactionSigner},
 };
-use libra_wallet::{io_utils, wallet_library::WalletLibrary};
+use libra_wallet::{io_utils, keystore::Keystore, wallet_library::WalletLibrary};
 use logger::prelude::*;
 use num_traits::{
     cast::{FromPrimitive, ToPrimitive},
@@ -35,7 +40,7 @@ use std::{
     collections::{BTreeMap, HashMap},
     convert::TryFrom,
     fmt, fs,
-    io::{stdout, Write},
+    io::{self, stdout, Write},
     path::{Display, Path, PathBuf},
     process::{Command, Stdio},
     str::{self, FromStr},
@@ -536,7 +541,158 @@ impl ClientProxy {
         signature: Ed25519Signature,
     ) -> Result<()> {
         let signed_txn = SignedTransaction::new(raw_txn, public_key.clone(), signature);
+        self.submit_and_wait(signed_txn)
+    }
+
+    /// Create a k-of-n multi-signature public key out of the public keys of wallet accounts,
+    /// referenced by account_ref_id, or hex encoded public keys. Returns the key along with the
+    /// address of the account it controls.
+    pub fn create_multisig_account(
+        &self,
+        space_delim_strings: &[&str],
+    ) -> Result<(MultiEd25519PublicKey, AccountAddress)> {
+        ensure!(
+            space_delim_strings.len() >= 3,
+            "Invalid number of arguments for creating multisig account"
+        );
+        let threshold = space_delim_strings[1].parse::<u8>().map_err(|error| {
+            format_parse_data_error(
+                "threshold",
+                InputType::UnsignedInt,
+                space_delim_strings[1],
+                error,
+            )
+        })?;
+        let public_keys = space_delim_strings[2..]
+            .iter()
+            .map(|para| match para.parse::<usize>() {
+                Ok(account_ref_id) => {
+                    let account_data = self.accounts.get(account_ref_id).ok_or_else(|| {
+                        format_err!(
+                            "Unable to find account by account reference id: {}",
+                            account_ref_id
+                        )
+                    })?;
+                    Ok(self.wallet.get_public_key(&account_data.address)?)
+                }
+                Err(_) => Ok(Ed25519PublicKey::from_encoded_string(para)?),
+            })
+            .collect::<Result<Vec<_>>>()?;
+        let public_key = MultiEd25519PublicKey::new(public_keys, threshold)?;
+        let address = AccountAddress::from_public_key(&public_key);
+        Ok((public_key, address))
+    }
+
+    /// Prepare a transfer transaction from a multi-signature account: return the unsigned raw
+    /// transaction, hex encoded, to be signed by the holders of the keys.
+    pub fn prepare_multisig_transfer(&mut self, space_delim_strings: &[&str]) -> Result<String> {
+        ensure!(
+            space_delim_strings.len() >= 4 && space_delim_strings.len() <= 6,
+            "Invalid number of arguments for multisig transfer"
+        );
+        let public_key = MultiEd25519PublicKey::from_encoded_string(space_delim_strings[1])?;
+        let sender_address = AccountAddress::from_public_key(&public_key);
+        let receiver_address = self.get_account_address_from_parameter(space_delim_strings[2])?;
+        let num_coins = Self::convert_to_micro_libras(space_delim_strings[3])?;
+        let gas_unit_price = space_delim_strings
+            .get(4)
+            .map(|para| para.parse::<u64>())
+            .transpose()?;
+        let max_gas_amount = space_delim_strings
+            .get(5)
+            .map(|para| para.parse::<u64>())
+            .transpose()?;
+        let sequence_number = self
+            .get_account_resource_and_update(sender_address)?
+            .sequence_number();
+
+        let raw_txn = self.prepare_transfer_coins(
+            sender_address,
+            sequence_number,
+            receiver_address,
+            num_coins,
+            gas_unit_price,
+            max_gas_amount,
+        )?;
+        Ok(hex::encode(lcs::to_bytes(&raw_txn)?))
+    }
 
+    /// Sign a hex encoded raw transaction of a multi-signature account with the key of a wallet
+    /// account. Returns the partial signature, made of the index of the key in the
+    /// multi-signature public key and the hex encoded signature, separated by a colon.
+    pub fn sign_multisig_transaction(&self, space_delim_strings: &[&str]) -> Result<String> {
+        ensure!(
+            space_delim_strings.len() == 4,
+            "Invalid number of arguments for signing multisig transaction"
+        );
+        let signer_address = self.get_account_address_from_parameter(space_delim_strings[1])?;
+        let public_key = MultiEd25519PublicKey::from_encoded_string(space_delim_strings[2])?;
+        let raw_txn: RawTransaction = lcs::from_bytes(&hex::decode(space_delim_strings[3])?)?;
+        ensure!(
+            raw_txn.sender() == AccountAddress::from_public_key(&public_key),
+            "The transaction is not sent by the account of the multisig public key"
+        );
+
+        let (signer_public_key, signature) =
+            self.wallet.sign_txn_with(&signer_address, &raw_txn)?;
+        let index = public_key
+            .public_keys()
+            .iter()
+            .position(|key| *key == signer_public_key)
+            .ok_or_else(|| {
+                format_err!(
+                    "The key of account {} is not part of the multisig public key",
+                    signer_address
+                )
+            })?;
+        Ok(format!(
+            "{}:{}",
+            index,
+            hex::encode(signature.to_bytes().to_vec())
+        ))
+    }
+
+    /// Assemble a transaction of a multi-signature account out of the hex encoded raw transaction
+    /// and at least threshold partial signatures.
+    pub fn assemble_multisig_transaction(
+        &self,
+        space_delim_strings: &[&str],
+    ) -> Result<SignedTransaction> {
+        ensure!(
+            space_delim_strings.len() >= 4,
+            "Invalid number of arguments for assembling multisig transaction"
+        );
+        let public_key = MultiEd25519PublicKey::from_encoded_string(space_delim_strings[1])?;
+        let raw_txn: RawTransaction = lcs::from_bytes(&hex::decode(space_delim_strings[2])?)?;
+        let signatures = space_delim_strings[3..]
+            .iter()
+            .map(|para| {
+                let mut parts = para.splitn(2, ':');
+                match (parts.next(), parts.next()) {
+                    (Some(index), Some(signature)) => Ok((
+                        Ed25519Signature::from_encoded_string(signature)?,
+                        index.parse::<u8>()?,
+                    )),
+                    _ => bail!("Invalid partial signature: {}", para),
+                }
+            })
+            .collect::<Result<Vec<_>>>()?;
+
+        let signed_txn = SignedTransaction::new_multisig(
+            raw_txn,
+            public_key,
+            MultiEd25519Signature::new(signatures)?,
+        );
+        Ok(signed_txn.check_signature()?.into_inner())
+    }
+
+    /// Assemble a transaction of a multi-signature account and submit it to the network.
+    pub fn submit_multisig_transaction(&mut self, space_delim_strings: &[&str]) -> Result<()> {
+        let signed_txn = self.assemble_multisig_transaction(space_delim_strings)?;
+        self.submit_and_wait(signed_txn)
+    }
+
+    fn submit_and_wait(&mut self, signed_txn: SignedTransaction) -> Result<()> {
         let mut req = SubmitTransactionRequest::default();
         let sender_address = signed_txn.sender();
         let sender_sequence = signed_txn.sequence_number();
@@ -576,16 +732,30 @@ impl ClientProxy {
 
     /// Execute custom script
     pub fn execute_script(&mut self, space_delim_strings: &[&str]) -> Result<()> {
+        let script = Self::script_from_parameters(space_delim_strings)?;
+        self.submit_program(space_delim_strings, TransactionPayload::Script(script))
+    }
+
+    /// Simulate custom script against the latest state without submitting it
+    pub fn simulate_script(&self, space_delim_strings: &[&str]) -> Result<TransactionOutput> {
+        let script = Self::script_from_parameters(space_delim_strings)?;
+        let sender_address = self.get_account_address_from_parameter(space_delim_strings[1])?;
+        let sender_ref_id = self.get_account_ref_id(&sender_address)?;
+        let sender = self.accounts.get(sender_ref_id).unwrap();
+        let signed_txn =
+            self.create_signed_transaction(TransactionPayload::Script(script), sender, None, None)?;
+        self.client
+            .simulate_transaction(signed_txn, /* skip_signature_check = */ false)
+    }
+
+    fn script_from_parameters(space_delim_strings: &[&str]) -> Result<Script> {
         let script: Script = serde_json::from_slice(&fs::read(space_delim_strings[2])?)?;
         let (script_bytes, _) = script.into_inner();
-        let arguments: Vec<_> = space_delim_strings[3..]
+        let arguments = space_delim_strings[3..]
             .iter()
-            .filter_map(|arg| parse_as_transaction_argument(arg).ok())
-            .collect();
-        self.submit_program(
-            space_delim_strings,
-            TransactionPayload::Script(Script::new(script_bytes, arguments)),
-        )
+            .map(|arg| parse_as_transaction_argument(arg))
+            .collect::<Result<Vec<_>>>()?;
+        Ok(Script::new(script_bytes, arguments))
     }
 
     /// Get the latest account state from validator.
@@ -739,29 +909,36 @@ impl ClientProxy {
             .get_events_by_access_path(access_path, start_seq_number, ascending, limit)
     }
 
-    /// Write mnemonic recover to the file specified.
-    pub fn write_recovery(&self, space_delim_strings: &[&str]) -> Result<()> {
+    /// Write mnemonic recover to the file specified, as a keystore encrypted with passphrase.
+    pub fn write_recovery(&self, space_delim_strings: &[&str], passphrase: &str) -> Result<()> {
         ensure!(
             space_delim_strings.len() == 2,
             "Invalid number of arguments for writing recovery"
         );
 
         self.wallet
-            .write_recovery(&Path::new(space_delim_strings[1]))?;
+            .write_keystore(&Path::new(space_delim_strings[1]), passphrase)?;
         Ok(())
     }
 
-    /// Recover wallet accounts from file and return vec<(account_address, index)>.
+    /// Recover wallet accounts from file and return vec<(account_address, index)>. The file is
+    /// unlocked as an encrypted keystore if a passphrase is given, and read as a plaintext
+    /// recovery file otherwise.
     pub fn recover_wallet_accounts(
         &mut self,
         space_delim_strings: &[&str],
+        passphrase: Option<&str>,
     ) -> Result<Vec<AddressAndIndex>> {
         ensure!(
             space_delim_strings.len() == 2,
             "Invalid number of arguments for recovering wallets"
         );
 
-        let wallet = WalletLibrary::recover(&Path::new(space_delim_strings[1]))?;
+        let path = Path::new(space_delim_strings[1]);
+        let wallet = match passphrase {
+            Some(passphrase) => WalletLibrary::unlock_keystore(&path, passphrase)?,
+            None => WalletLibrary::recover(&path)?,
+        };
         let wallet_addresses = wallet.get_addresses()?;
         let mut account_data = Vec::new();
         for address in wallet_addresses {
@@ -861,23 +1038,53 @@ impl ClientProxy {
         })
     }
 
+    /// Loads the wallet from `mnemonic_file`, or from the recovery file of the current directory
+    /// if none is given. A keystore is unlocked with a passphrase read from the terminal. Without
+    /// a recovery file in the current directory, a new wallet is created and not saved: the
+    /// `account write` command saves it to a keystore.
     fn get_libra_wallet(mnemonic_file: Option<String>) -> Result<WalletLibrary> {
         let wallet_recovery_file_path = if let Some(input_mnemonic_word) = mnemonic_file {
             Path::new(&input_mnemonic_word).to_path_buf()
         } else {
             let mut file_path = std::env::current_dir()?;
             file_path.push(CLIENT_WALLET_MNEMONIC_FILE);
+            if !file_path.exists() {
+                return Ok(WalletLibrary::new());
+            }
             file_path
         };
 
-        let wallet = if let Ok(recovered_wallet) = io_utils::recover(&wallet_recovery_file_path) {
-            recovered_wallet
-        } else {
-            let new_wallet = WalletLibrary::new();
-            new_wallet.write_recovery(&wallet_recovery_file_path)?;
-            new_wallet
+        if Keystore::is_keystore(&wallet_recovery_file_path) {
+            let passphrase = rpassword::read_password_from_tty(Some(&format!(
+                "Enter passphrase of keystore {}: ",
+                wallet_recovery_file_path.display()
+            )))?;
+            return Ok(WalletLibrary::unlock_keystore(
+                &wallet_recovery_file_path,
+                &passphrase,
+            )?);
+        }
+        let is_empty = match fs::metadata(&wallet_recovery_file_path) {
+            Ok(metadata) => metadata.len() == 0,
+            Err(ref err) if err.kind() == io::ErrorKind::NotFound => true,
+            Err(err) => return Err(err.into()),
         };
-        Ok(wallet)
+        if !is_empty {
+            // A file that can't be recovered is reported rather than replaced, as it may hold the
+            // only copy of a wallet.
+            return io_utils::recover(&wallet_recovery_file_path).map_err(|err| {
+                format_err!(
+                    "Unable to recover the wallet from {}: {}",
+                    wallet_recovery_file_path.display(),
+                    err
+                )
+            });
+        }
+        // The given file holds no wallet yet: the new wallet is saved there, so that the clients
+        // sharing the file use the same accounts.
+        let new_wallet = WalletLibrary::new();
+        new_wallet.write_recovery(&wallet_recovery_file_path)?;
+        Ok(new_wallet)
     }
 
     /// Set wallet instance used by this client.
@@ -1010,6 +1217,24 @@ impl ClientProxy {
         max_gas_amount: Option<u64>,
         gas_unit_price: Option<u64>,
     ) -> Result<SubmitTransactionRequest> {
+        let signed_txn = self.create_signed_transaction(
+            program,
+            sender_account,
+            max_gas_amount,
+            gas_unit_price,
+        )?;
+        let mut req = SubmitTransactionRequest::default();
+        req.signed_txn = Some(signed_txn.into());
+        Ok(req)
+    }
+
+    fn create_signed_transaction(
+        &self,
+        program: TransactionPayload,
+        sender_account: &AccountData,
+        max_gas_amount: Option<u64>,
+        gas_unit_price: Option<u64>,
+    ) -> Result<SignedTransaction> {
         let signer: Box<&dyn TransactionSigner> = match &sender_account.key_pair {
             Some(key_pair) => Box::new(key_pair),
             None => Box::new(&self.wallet),
@@ -1024,9 +1249,7 @@ impl ClientProxy {
             TX_EXPIRATION,
         )
         .unwrap();
-        let mut req = SubmitTransactionRequest::default();
-        req.signed_txn = Some(signed_txn.into());
-        Ok(req)
+        Ok(signed_txn)
     }
 
     fn mut_account_from_parameter(&mut self, para: &str) -> Result<&mut AccountData> {
@@ -1084,11 +1307,19 @@ impl fmt::Display for AccountEntry {
 
 #[cfg(test)]
 mod tests {
-    use crate::client_proxy::{parse_bool, AddressAndIndex, ClientProxy};
+    use crate::client_proxy::{
+        parse_bool, AddressAndIndex, ClientProxy, GAS_UNIT_PRICE, MAX_GAS_AMOUNT, TX_EXPIRATION,
+    };
     use config::{config::PersistableConfig, trusted_peers::ConfigHelpers};
+    use crypto::traits::ValidKeyStringExt;
     use libra_tools::tempdir::TempPath;
+    use libra_types::{
+        transaction::{Script, TransactionPayload},
+        transaction_helpers::create_unsigned_txn,
+    };
     use libra_wallet::io_utils;
     use proptest::prelude::*;
+    use std::fs;
 
     fn generate_accounts_from_wallet(count: usize) -> (ClientProxy, Vec<AddressAndIndex>) {
         let mut accounts = Vec::new();
@@ -1176,6 +1407,74 @@ mod tests {
         assert_eq!(client.wallet.mnemonic(), wallet.mnemonic());
     }
 
+    #[test]
+    fn test_get_libra_wallet() {
+        // A missing recovery file is initialized with the new wallet.
+        let file = TempPath::new();
+        let path = file.path().to_str().unwrap().to_string();
+        let wallet = ClientProxy::get_libra_wallet(Some(path.clone())).unwrap();
+        let recovered = ClientProxy::get_libra_wallet(Some(path)).unwrap();
+        assert_eq!(recovered.mnemonic(), wallet.mnemonic());
+
+        // A file that isn't a recovery file is left untouched.
+        let file = TempPath::new();
+        fs::write(file.path(), "not a mnemonic").unwrap();
+        let path = file.path().to_str().unwrap().to_string();
+        assert!(ClientProxy::get_libra_wallet(Some(path)).is_err());
+        assert_eq!(fs::read_to_string(file.path()).unwrap(), "not a mnemonic");
+    }
+
+    #[test]
+    fn test_multisig_sign_and_assemble() {
+        let (client, accounts) = generate_accounts_from_wallet(3);
+        let (public_key, address) = client
+            .create_multisig_account(&["create", "2", "0", "1", "2"])
+            .unwrap();
+        let encoded_public_key = public_key.to_encoded_string().unwrap();
+        let raw_txn = create_unsigned_txn(
+            TransactionPayload::Script(Script::new(vec![], vec![])),
+            address,
+            0,
+            MAX_GAS_AMOUNT,
+            GAS_UNIT_PRICE,
+            TX_EXPIRATION,
+        );
+        let encoded_raw_txn = hex::encode(lcs::to_bytes(&raw_txn).unwrap());
+
+        let sign = |account: &AddressAndIndex| {
+            client
+                .sign_multisig_transaction(&[
+                    "sign",
+                    &account.index.to_string(),
+                    &encoded_public_key,
+                    &encoded_raw_txn,
+                ])
+                .unwrap()
+        };
+        let signature_0 = sign(&accounts[0]);
+        let signature_2 = sign(&accounts[2]);
+        assert!(signature_2.starts_with("2:"));
+
+        let signed_txn = client
+            .assemble_multisig_transaction(&[
+                "submit",
+                &encoded_public_key,
+                &encoded_raw_txn,
+                &signature_2,
+                &signature_0,
+            ])
+            .unwrap();
+        assert_eq!(signed_txn.into_raw_transaction(), raw_txn);
+        assert!(client
+            .assemble_multisig_transaction(&[
+                "submit",
+                &encoded_public_key,
+                &encoded_raw_txn,
+                &signature_0,
+            ])
+            .is_err());
+    }
+
     proptest! {
         // Proptest is used to verify that the conversion will not panic with random input.
         #[test]
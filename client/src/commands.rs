@@ -3,7 +3,8 @@
 
 use crate::{
     account_commands::AccountCommand, client_proxy::ClientProxy, dev_commands::DevCommand,
-    query_commands::QueryCommand, transfer_commands::TransferCommand,
+    multisig_commands::MultisigCommand, query_commands::QueryCommand,
+    transfer_commands::TransferCommand,
 };
 
 use failure::prelude::*;
@@ -63,6 +64,7 @@ pub fn get_commands(
         Arc::new(AccountCommand {}),
         Arc::new(QueryCommand {}),
         Arc::new(TransferCommand {}),
+        Arc::new(MultisigCommand {}),
     ];
     if include_dev {
         commands.push(Arc::new(DevCommand {}));
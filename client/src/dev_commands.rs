@@ -18,6 +18,7 @@ impl Command for DevCommand {
             Box::new(DevCommandCompile {}),
             Box::new(DevCommandPublish {}),
             Box::new(DevCommandExecute {}),
+            Box::new(DevCommandSimulate {}),
         ];
         subcommand_execute(&params[0], commands, client, &params[1..]);
     }
@@ -90,7 +91,8 @@ impl Command for DevCommandExecute {
     }
 
     fn get_description(&self) -> &'static str {
-        "Execute custom move script"
+        "Execute custom move script. Parameters are u64 (42), u8 (42u8), u128 (42u128), \
+         address (0x1), bytearray (b\"01ab\"), bool (true) or vector ([0x1,0x2]) values"
     }
 
     fn execute(&self, client: &mut ClientProxy, params: &[&str]) {
@@ -104,3 +106,42 @@ impl Command for DevCommandExecute {
         }
     }
 }
+
+/// Sub command to simulate custom move script
+pub struct DevCommandSimulate {}
+
+impl Command for DevCommandSimulate {
+    fn get_aliases(&self) -> Vec<&'static str> {
+        vec!["simulate", "s"]
+    }
+
+    fn get_params_help(&self) -> &'static str {
+        "<sender_account_address>|<sender_account_ref_id> <compiled_module_path> [parameters]"
+    }
+
+    fn get_description(&self) -> &'static str {
+        "Simulate custom move script against the latest state without submitting it"
+    }
+
+    fn execute(&self, client: &mut ClientProxy, params: &[&str]) {
+        if params.len() < 3 {
+            println!("Invalid number of arguments to simulate script");
+            return;
+        }
+        match client.simulate_script(params) {
+            Ok(output) => {
+                println!("Status: {:?}", output.status());
+                println!("Gas used: {}", output.gas_used());
+                println!("Events: ");
+                for event in output.events() {
+                    println!("{}", event);
+                }
+                println!("Write set: ");
+                for (access_path, write_op) in output.write_set() {
+                    println!("{}: {:?}", access_path, write_op);
+                }
+            }
+            Err(e) => report_error("Error simulating script", e),
+        }
+    }
+}
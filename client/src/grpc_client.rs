@@ -4,7 +4,7 @@
 use crate::AccountData;
 use admission_control_proto::{
     proto::admission_control::{
-        AdmissionControlClient, SubmitTransactionRequest,
+        AdmissionControlClient, SimulateTransactionRequest, SubmitTransactionRequest,
         SubmitTransactionResponse as ProtoSubmitTransactionResponse,
     },
     AdmissionControlStatus, SubmitTransactionResponse,
@@ -23,7 +23,7 @@ use libra_types::{
     get_with_proof::{
         RequestItem, ResponseItem, UpdateToLatestLedgerRequest, UpdateToLatestLedgerResponse,
     },
-    transaction::{SignedTransaction, Version},
+    transaction::{SignedTransaction, TransactionOutput, Version},
     vm_error::StatusCode,
 };
 use logger::prelude::*;
@@ -129,6 +129,22 @@ impl GRPCClient {
             .submit_transaction_opt(resp, Self::get_default_grpc_call_option())?)
     }
 
+    /// Executes a transaction against the latest state of the validator and returns its output.
+    /// The transaction is not submitted, hence nothing is committed.
+    pub fn simulate_transaction(
+        &self,
+        signed_txn: SignedTransaction,
+        skip_signature_check: bool,
+    ) -> Result<TransactionOutput> {
+        let mut req = SimulateTransactionRequest::default();
+        req.signed_txn = Some(signed_txn.into());
+        req.skip_signature_check = skip_signature_check;
+        let resp = self
+            .client
+            .simulate_transaction_opt(&req, Self::get_default_grpc_call_option())?;
+        TransactionOutput::try_from(resp)
+    }
+
     fn get_with_proof_async(
         &self,
         requested_items: Vec<RequestItem>,
@@ -21,6 +21,7 @@ pub mod commands;
 pub(crate) mod dev_commands;
 /// gRPC client wrapper to connect to validator.
 pub(crate) mod grpc_client;
+pub(crate) mod multisig_commands;
 pub(crate) mod query_commands;
 pub(crate) mod transfer_commands;
 
@@ -32,9 +32,10 @@ struct Args {
     /// If not passed, will be derived from host parameter
     #[structopt(short = "f", long)]
     pub faucet_server: Option<String>,
-    /// File location from which to load mnemonic word for user account address/key generation.
-    /// If not passed, a new mnemonic file will be generated by libra_wallet in the current
-    /// directory.
+    /// File location from which to load mnemonic word for user account address/key generation,
+    /// either an encrypted keystore or a plaintext recovery file. An empty or missing recovery
+    /// file is initialized with a new mnemonic. If not passed, the client.mnemonic file of the
+    /// current directory is loaded if it exists, and a new wallet is created otherwise.
     #[structopt(short = "n", long)]
     pub mnemonic_file: Option<String>,
     /// File location from which to load config of trusted validators. It is used to verify
@@ -0,0 +1,144 @@
+// Copyright (c) The Libra Core Contributors
+// SPDX-License-Identifier: Apache-2.0
+
+use crate::{client_proxy::ClientProxy, commands::*};
+use crypto::traits::ValidKeyStringExt;
+
+/// Major command for multi-signature account operations.
+pub struct MultisigCommand {}
+
+impl Command for MultisigCommand {
+    fn get_aliases(&self) -> Vec<&'static str> {
+        vec!["multisig", "ms"]
+    }
+    fn get_description(&self) -> &'static str {
+        "Multi-signature account operations"
+    }
+    fn execute(&self, client: &mut ClientProxy, params: &[&str]) {
+        let commands: Vec<Box<dyn Command>> = vec![
+            Box::new(MultisigCommandCreate {}),
+            Box::new(MultisigCommandTransfer {}),
+            Box::new(MultisigCommandSign {}),
+            Box::new(MultisigCommandSubmit {}),
+        ];
+
+        subcommand_execute(&params[0], commands, client, &params[1..]);
+    }
+}
+
+/// Sub command to create a k-of-n multi-signature public key and the address of the account it
+/// controls. The account will not be saved on chain.
+pub struct MultisigCommandCreate {}
+
+impl Command for MultisigCommandCreate {
+    fn get_aliases(&self) -> Vec<&'static str> {
+        vec!["create", "c"]
+    }
+    fn get_params_help(&self) -> &'static str {
+        "<threshold> <account_ref_id>|<public_key> ..."
+    }
+    fn get_description(&self) -> &'static str {
+        "Create a multisig public key out of the keys of wallet accounts or hex encoded public \
+         keys. Any threshold of them sign for the account of the returned address"
+    }
+    fn execute(&self, client: &mut ClientProxy, params: &[&str]) {
+        if params.len() < 3 {
+            println!("Invalid number of arguments for creating multisig account");
+            return;
+        }
+        match client.create_multisig_account(params) {
+            Ok((public_key, address)) => match public_key.to_encoded_string() {
+                Ok(encoded_public_key) => println!(
+                    "Created multisig public key {} for address {}",
+                    encoded_public_key,
+                    hex::encode(address)
+                ),
+                Err(e) => report_error("Error encoding multisig public key", e),
+            },
+            Err(e) => report_error("Error creating multisig account", e),
+        }
+    }
+}
+
+/// Sub command to prepare a transfer from a multi-signature account.
+pub struct MultisigCommandTransfer {}
+
+impl Command for MultisigCommandTransfer {
+    fn get_aliases(&self) -> Vec<&'static str> {
+        vec!["transfer", "t"]
+    }
+    fn get_params_help(&self) -> &'static str {
+        "<multisig_public_key> <receiver_account_address>|<receiver_account_ref_id> \
+         <number_of_coins> [gas_unit_price_in_micro_libras (default=0)] \
+         [max_gas_amount_in_micro_libras (default 140000)]"
+    }
+    fn get_description(&self) -> &'static str {
+        "Prepare an unsigned transfer of coins (in libra) from a multisig account to another"
+    }
+    fn execute(&self, client: &mut ClientProxy, params: &[&str]) {
+        if params.len() < 4 || params.len() > 6 {
+            println!("Invalid number of arguments for multisig transfer");
+            return;
+        }
+        match client.prepare_multisig_transfer(params) {
+            Ok(raw_txn) => println!(
+                "Prepared transaction, to be signed by the holders of the keys: {}",
+                raw_txn
+            ),
+            Err(e) => report_error("Error preparing multisig transfer", e),
+        }
+    }
+}
+
+/// Sub command to sign a transaction of a multi-signature account with a wallet account.
+pub struct MultisigCommandSign {}
+
+impl Command for MultisigCommandSign {
+    fn get_aliases(&self) -> Vec<&'static str> {
+        vec!["sign", "s"]
+    }
+    fn get_params_help(&self) -> &'static str {
+        "<signer_account_ref_id>|<signer_account_address> <multisig_public_key> <transaction>"
+    }
+    fn get_description(&self) -> &'static str {
+        "Partially sign a prepared multisig transaction with the key of a wallet account"
+    }
+    fn execute(&self, client: &mut ClientProxy, params: &[&str]) {
+        if params.len() != 4 {
+            println!("Invalid number of arguments for signing multisig transaction");
+            return;
+        }
+        match client.sign_multisig_transaction(params) {
+            Ok(signature) => println!("Partial signature: {}", signature),
+            Err(e) => report_error("Error signing multisig transaction", e),
+        }
+    }
+}
+
+/// Sub command to assemble partial signatures of a transaction of a multi-signature account and
+/// submit it.
+pub struct MultisigCommandSubmit {}
+
+impl Command for MultisigCommandSubmit {
+    fn get_aliases(&self) -> Vec<&'static str> {
+        vec!["submit", "sb"]
+    }
+    fn get_params_help(&self) -> &'static str {
+        "<multisig_public_key> <transaction> <partial_signature> ..."
+    }
+    fn get_description(&self) -> &'static str {
+        "Assemble at least threshold partial signatures of a prepared multisig transaction and \
+         submit it. Blocks until the transaction is committed"
+    }
+    fn execute(&self, client: &mut ClientProxy, params: &[&str]) {
+        if params.len() < 4 {
+            println!("Invalid number of arguments for submitting multisig transaction");
+            return;
+        }
+        println!(">> Submitting multisig transaction");
+        match client.submit_multisig_transaction(params) {
+            Ok(_) => println!("Finished transaction!"),
+            Err(e) => report_error("Failed to submit multisig transaction", e),
+        }
+    }
+}
@@ -48,6 +48,9 @@ pub enum SecurityEvent {
     /// Network discovery received an invalid DiscoveryMsg
     InvalidDiscoveryMsg,
 
+    /// Network peer kept exceeding its inbound rate limits
+    NetworkRateLimitExceeded,
+
     /// Error for testing
     #[cfg(test)]
     TestError,
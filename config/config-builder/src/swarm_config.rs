@@ -94,6 +94,7 @@ impl SwarmConfig {
             network_keypairs: NetworkKeyPairs::default(),
             network_peers: template_network.network_peers.clone(),
             seed_peers: template_network.seed_peers.clone(),
+            inbound_rate_limit: template_network.inbound_rate_limit.clone(),
         };
         let (mut private_keys, mut network_peers_config) =
             ConfigHelpers::gen_full_nodes(num_nodes, key_seed);
@@ -336,6 +337,7 @@ impl SwarmConfig {
             network_keypairs: NetworkKeyPairs::default(),
             network_peers: template_network.network_peers.clone(),
             seed_peers: template_network.seed_peers.clone(),
+            inbound_rate_limit: template_network.inbound_rate_limit.clone(),
         };
         let consensus_config = ConsensusConfig {
             max_block_size: template.consensus.max_block_size,
@@ -343,6 +345,8 @@ impl SwarmConfig {
             contiguous_rounds: template.consensus.contiguous_rounds,
             max_pruned_blocks_in_mem: template.consensus.max_pruned_blocks_in_mem,
             pacemaker_initial_timeout_ms: template.consensus.pacemaker_initial_timeout_ms,
+            safety_rules_backend: template.consensus.safety_rules_backend.clone(),
+            safety_rules_file: template.consensus.safety_rules_file.clone(),
             consensus_keypair_file: consensus_keys_file_name.into(),
             consensus_peers_file: consensus_peers_file_name.into(),
             // Dummy values - will be loaded from corresponding files.
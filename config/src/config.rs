@@ -2,7 +2,13 @@
 // SPDX-License-Identifier: Apache-2.0
 
 use crate::{
-    config::ConsensusProposerType::{FixedProposer, MultipleOrderedProposers, RotatingProposer},
+    config::{
+        ConsensusProposerType::{
+            FixedProposer, LeaderReputation, MultipleOrderedProposers, RotatingProposer,
+            VrfProposer,
+        },
+        SafetyRulesBackend::{InMemory, OnDisk, SecretService},
+    },
     keys::{ConsensusKeyPair, NetworkKeyPairs},
     seed_peers::{SeedPeersConfig, SeedPeersConfigHelpers},
     trusted_peers::{
@@ -217,6 +223,7 @@ impl Default for LoggerConfig {
 pub struct SecretServiceConfig {
     pub address: String,
     pub secret_service_port: u16,
+    pub safety_rules_port: u16,
 }
 
 impl Default for SecretServiceConfig {
@@ -224,6 +231,7 @@ impl Default for SecretServiceConfig {
         SecretServiceConfig {
             address: "localhost".to_string(),
             secret_service_port: 6185,
+            safety_rules_port: 6186,
         }
     }
 }
@@ -234,7 +242,12 @@ pub struct AdmissionControlConfig {
     pub address: String,
     pub admission_control_service_port: u16,
     pub need_to_check_mempool_before_validation: bool,
-    pub upstream_proxy_timeout: Duration,
+    // max number of account transactions / events subscriptions served at the same time
+    pub max_concurrent_subscriptions: usize,
+    // interval between two polls of storage for new data of a subscription
+    pub subscription_poll_interval_ms: u64,
+    // max number of transactions / events sent in a single response of a subscription
+    pub subscription_batch_size: u64,
 }
 
 impl Default for AdmissionControlConfig {
@@ -243,7 +256,9 @@ impl Default for AdmissionControlConfig {
             address: "0.0.0.0".to_string(),
             admission_control_service_port: 8000,
             need_to_check_mempool_before_validation: false,
-            upstream_proxy_timeout: Duration::from_secs(1),
+            max_concurrent_subscriptions: 100,
+            subscription_poll_interval_ms: 500,
+            subscription_batch_size: 100,
         }
     }
 }
@@ -278,6 +293,16 @@ pub struct StorageConfig {
     pub port: u16,
     pub dir: PathBuf,
     pub grpc_max_receive_len: Option<i32>,
+    // Other than the latest version, how many historical versions of account states to keep.
+    // Older ones are pruned, and no pruning happens if unset.
+    pub state_prune_window: Option<u64>,
+    // Same as above, for transactions.
+    pub transaction_prune_window: Option<u64>,
+    // Same as above, for events.
+    pub event_prune_window: Option<u64>,
+    // Same as above, for transaction infos and the transaction accumulator. Accumulator nodes
+    // needed to prove the versions kept are never pruned.
+    pub ledger_prune_window: Option<u64>,
 }
 
 impl Default for StorageConfig {
@@ -287,6 +312,10 @@ impl Default for StorageConfig {
             port: 6184,
             dir: PathBuf::from("libradb/db"),
             grpc_max_receive_len: Some(100_000_000),
+            state_prune_window: Some(1_000_000),
+            transaction_prune_window: None,
+            event_prune_window: None,
+            ledger_prune_window: None,
         }
     }
 }
@@ -325,6 +354,8 @@ pub struct NetworkConfig {
     #[serde(skip)]
     pub seed_peers: SeedPeersConfig,
     pub seed_peers_file: PathBuf,
+    // Limits on the inbound traffic of each connected peer.
+    pub inbound_rate_limit: RateLimitConfig,
 }
 
 impl Default for NetworkConfig {
@@ -344,6 +375,7 @@ impl Default for NetworkConfig {
             network_peers: NetworkPeersConfig::default(),
             seed_peers_file: PathBuf::from("seed_peers.config.toml"),
             seed_peers: SeedPeersConfig::default(),
+            inbound_rate_limit: RateLimitConfig::default(),
         }
     }
 }
@@ -386,6 +418,38 @@ impl NetworkConfig {
     }
 }
 
+#[derive(Clone, Debug, Deserialize, Serialize)]
+#[serde(default)]
+pub struct RateLimitConfig {
+    // Token bucket limits on the bytes and messages a peer sends on each protocol: a peer can
+    // send bursts of up to `burst_bytes` and `burst_messages`, refilled at `bytes_per_sec` and
+    // `messages_per_sec`. A rate of 0 disables the corresponding limit.
+    pub bytes_per_sec: u64,
+    pub burst_bytes: u64,
+    pub messages_per_sec: u64,
+    pub burst_messages: u64,
+    // The direct-send messages of a peer exceeding its limits are delayed until the buckets
+    // refill, and its rpc requests are rejected. The peer is disconnected once more than this
+    // number of its messages in a row are delayed or rejected.
+    pub max_throttled_messages: u64,
+    // A disconnected peer keeps its buckets, and is disconnected again if it sends messages
+    // before this number of seconds has elapsed.
+    pub disconnect_cool_down_secs: u64,
+}
+
+impl Default for RateLimitConfig {
+    fn default() -> RateLimitConfig {
+        RateLimitConfig {
+            bytes_per_sec: 20_000_000,
+            burst_bytes: 100_000_000,
+            messages_per_sec: 1000,
+            burst_messages: 5000,
+            max_throttled_messages: 100,
+            disconnect_cool_down_secs: 300,
+        }
+    }
+}
+
 #[cfg_attr(any(test, feature = "testing"), derive(Clone))]
 #[derive(Debug, Deserialize, Serialize)]
 #[serde(default)]
@@ -395,6 +459,12 @@ pub struct ConsensusConfig {
     pub contiguous_rounds: u32,
     pub max_pruned_blocks_in_mem: Option<u64>,
     pub pacemaker_initial_timeout_ms: Option<u64>,
+    // safety_rules_backend chooses where the consensus state of safety rules is persisted and
+    // its votes are signed.
+    pub safety_rules_backend: String,
+    // safety_rules_file is the file the consensus state is persisted in by the on disk backend,
+    // relative paths are relative to the data directory.
+    pub safety_rules_file: PathBuf,
     // consensus_keypair contains the node's consensus keypair.
     // it is filled later on from consensus_keypair_file.
     #[serde(skip)]
@@ -413,6 +483,8 @@ impl Default for ConsensusConfig {
             contiguous_rounds: 2,
             max_pruned_blocks_in_mem: None,
             pacemaker_initial_timeout_ms: None,
+            safety_rules_backend: "in_memory".to_string(),
+            safety_rules_file: PathBuf::from("safety_rules.lcs"),
             consensus_keypair: ConsensusKeyPair::default(),
             consensus_keypair_file: PathBuf::from("consensus_keypair.config.toml"),
             consensus_peers: ConsensusPeersConfig::default(),
@@ -429,6 +501,20 @@ pub enum ConsensusProposerType {
     RotatingProposer,
     // Multiple ordered proposers per round (primary, secondary, etc.)
     MultipleOrderedProposers,
+    // Proposers weighted by their participation in the recently committed blocks
+    LeaderReputation,
+    // Proposers privately elected by a verifiable random function, unknown in advance
+    VrfProposer,
+}
+
+#[derive(Debug, Eq, PartialEq, Clone, Copy)]
+pub enum SafetyRulesBackend {
+    // Keep the consensus state in memory, recovered from the consensus db on restart
+    InMemory,
+    // Persist the consensus state in safety_rules_file
+    OnDisk,
+    // Persist the consensus state and sign votes in the secret service process
+    SecretService,
 }
 
 impl ConsensusConfig {
@@ -451,10 +537,24 @@ impl ConsensusConfig {
             "fixed_proposer" => FixedProposer,
             "rotating_proposer" => RotatingProposer,
             "multiple_ordered_proposers" => MultipleOrderedProposers,
+            "leader_reputation" => LeaderReputation,
+            "vrf_proposer" => VrfProposer,
             &_ => unimplemented!("Invalid proposer type: {}", self.proposer_type),
         }
     }
 
+    pub fn get_safety_rules_backend(&self) -> SafetyRulesBackend {
+        match self.safety_rules_backend.as_str() {
+            "in_memory" => InMemory,
+            "on_disk" => OnDisk,
+            "secret_service" => SecretService,
+            &_ => unimplemented!(
+                "Invalid safety rules backend: {}",
+                self.safety_rules_backend
+            ),
+        }
+    }
+
     pub fn contiguous_rounds(&self) -> u32 {
         self.contiguous_rounds
     }
@@ -479,9 +579,18 @@ pub struct MempoolConfig {
     pub shared_mempool_tick_interval_ms: u64,
     pub shared_mempool_batch_size: usize,
     pub shared_mempool_max_concurrent_inbound_syncs: usize,
+    // how often full nodes send all their pending transactions to their upstream peers again, in
+    // case they were dropped upstream (0 disables it), counted in shared mempool ticks
+    pub shared_mempool_rebroadcast_interval_ms: u64,
     pub capacity: usize,
     // max number of transactions per user in Mempool
     pub capacity_per_user: usize,
+    // min increase of the gas price (in percents) for a transaction to replace the transaction
+    // with the same sender and sequence number in Mempool
+    pub replacement_gas_price_bump_percent: u64,
+    // persist the transactions accepted by Mempool in a journal under the storage directory, so
+    // they are not lost when the node restarts
+    pub persist_transactions: bool,
     pub system_transaction_timeout_secs: u64,
     pub system_transaction_gc_interval_ms: u64,
     pub mempool_service_port: u16,
@@ -495,8 +604,11 @@ impl Default for MempoolConfig {
             shared_mempool_tick_interval_ms: 50,
             shared_mempool_batch_size: 100,
             shared_mempool_max_concurrent_inbound_syncs: 100,
+            shared_mempool_rebroadcast_interval_ms: 30_000,
             capacity: 1_000_000,
             capacity_per_user: 100,
+            replacement_gas_price_bump_percent: 10,
+            persist_transactions: false,
             system_transaction_timeout_secs: 86400,
             address: "localhost".to_string(),
             mempool_service_port: 6182,
@@ -518,6 +630,13 @@ pub struct StateSyncConfig {
     pub max_chunk_limit: u64,
     // valid maximum timeout limit for sanity check
     pub max_timeout_ms: u64,
+    // if enabled, a node that is far behind downloads a snapshot of the target state
+    // instead of replaying every transaction
+    pub state_snapshot_sync: bool,
+    // minimal number of versions a node has to be behind to sync via state snapshot
+    pub state_snapshot_threshold: u64,
+    // number of accounts to request per state snapshot chunk
+    pub state_chunk_limit: u64,
     // List of peers to use as upstream in state sync protocols.
     #[serde(flatten)]
     pub upstream_peers: UpstreamPeersConfig,
@@ -531,6 +650,9 @@ impl Default for StateSyncConfig {
             long_poll_timeout_ms: 30000,
             max_chunk_limit: 1000,
             max_timeout_ms: 120_000,
+            state_snapshot_sync: false,
+            state_snapshot_threshold: 1_000_000,
+            state_chunk_limit: 1000,
             upstream_peers: UpstreamPeersConfig::default(),
         }
     }
@@ -620,6 +742,15 @@ impl NodeConfig {
         }
     }
 
+    pub fn get_safety_rules_file(&self) -> PathBuf {
+        let path = self.consensus.safety_rules_file.clone();
+        if path.is_relative() {
+            self.base.data_dir_path.join(path)
+        } else {
+            path
+        }
+    }
+
     pub fn get_metrics_dir(&self) -> PathBuf {
         let path = self.metrics.dir.clone();
         if path.is_relative() {
@@ -726,22 +857,27 @@ impl NodeConfigHelpers {
         config.execution.port = get_available_port();
         config.mempool.mempool_service_port = get_available_port();
         config.secret_service.secret_service_port = get_available_port();
+        config.secret_service.safety_rules_port = get_available_port();
         config.storage.port = get_available_port();
     }
 }
 
-/// Holds the VM configuration, currently this is only the publishing options for scripts and
-/// modules, but in the future this may need to be expanded to hold more information.
+/// Holds the VM configuration: the publishing options for scripts and modules, and whether the
+/// transactions of a block are executed in parallel.
 #[derive(Clone, Debug, Deserialize, Serialize)]
 #[serde(default)]
 pub struct VMConfig {
     pub publishing_options: VMPublishingOption,
+    /// Execute the transactions of a block speculatively in parallel instead of one after the
+    /// other. Both produce the same outputs.
+    pub parallel_execution: bool,
 }
 
 impl Default for VMConfig {
     fn default() -> VMConfig {
         VMConfig {
             publishing_options: VMPublishingOption::Open,
+            parallel_execution: false,
         }
     }
 }
@@ -789,6 +925,7 @@ impl VMConfig {
     pub fn empty_whitelist_FOR_TESTING() -> Self {
         VMConfig {
             publishing_options: VMPublishingOption::Locked(HashSet::new()),
+            parallel_execution: false,
         }
     }
 }
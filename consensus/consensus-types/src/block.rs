@@ -8,6 +8,7 @@ use crate::{
     vote_data::VoteData,
 };
 use crypto::{
+    ed25519::Ed25519Signature,
     hash::{BlockHasher, CryptoHash, CryptoHasher},
     HashValue,
 };
@@ -37,6 +38,47 @@ pub mod block_test_utils;
 #[path = "block_test.rs"]
 pub mod block_test;
 
+/// Signs the proposals of a validator. The signer is given the serialized block fields rather than
+/// the block id, so that a signer kept in another process only signs ids it has hashed itself with
+/// the block hasher, which can't be mistaken for the hash of a vote.
+pub trait ProposalSigner: Send + Sync {
+    /// Returns the author of the proposals.
+    fn author(&self) -> Author;
+
+    /// Signs the id of the block whose fields serialize to `block_data`, see `block_id()`.
+    fn sign_proposal(&self, block_data: &[u8]) -> failure::Result<Ed25519Signature>;
+}
+
+impl ProposalSigner for ValidatorSigner {
+    fn author(&self) -> Author {
+        ValidatorSigner::author(self)
+    }
+
+    fn sign_proposal(&self, block_data: &[u8]) -> failure::Result<Ed25519Signature> {
+        self.sign_message(block_id(block_data))
+    }
+}
+
+/// Returns the id of the block whose fields serialize to `block_data`.
+pub fn block_id(block_data: &[u8]) -> HashValue {
+    let mut state = BlockHasher::default();
+    state.write(block_data);
+    state.finish()
+}
+
+/// Returns the epoch and the round of the block whose fields serialize to `block_data`, so that a
+/// proposal signer can tell which round it signs a proposal for.
+pub fn block_epoch_and_round(block_data: &[u8]) -> failure::Result<(u64, Round)> {
+    // The timestamp, the epoch and the round lead the block fields, 8 bytes each.
+    ensure!(
+        block_data.len() >= 24,
+        "Block data of {} bytes is too short",
+        block_data.len()
+    );
+    let (_timestamp_usecs, epoch, round): (u64, u64, Round) = lcs::from_bytes(&block_data[..24])?;
+    Ok((epoch, round))
+}
+
 #[derive(Deserialize, Serialize, Clone, Debug, PartialEq, Eq)]
 pub enum BlockType<T> {
     Proposal {
@@ -265,21 +307,39 @@ where
         quorum_cert: QuorumCert,
         validator_signer: &ValidatorSigner,
     ) -> Self {
-        let block_internal = BlockSerializer {
+        Self::new_proposal(
+            payload,
+            epoch,
+            round,
+            timestamp_usecs,
+            quorum_cert,
+            validator_signer,
+        )
+        .expect("Failed to sign message")
+    }
+
+    fn new_proposal(
+        payload: T,
+        epoch: u64,
+        round: Round,
+        timestamp_usecs: u64,
+        quorum_cert: QuorumCert,
+        signer: &dyn ProposalSigner,
+    ) -> failure::Result<Self> {
+        let author = signer.author();
+        let block_data = lcs::to_bytes(&BlockSerializer {
             payload: Some(&payload),
             epoch,
             round,
             timestamp_usecs,
             quorum_cert: &quorum_cert,
-            author: Some(validator_signer.author()),
-        };
+            author: Some(author),
+        })?;
 
-        let id = block_internal.hash();
-        let signature = validator_signer
-            .sign_message(id)
-            .expect("Failed to sign message");
+        let id = block_id(&block_data);
+        let signature = signer.sign_proposal(&block_data)?;
 
-        Block {
+        Ok(Block {
             id,
             epoch,
             round,
@@ -287,10 +347,10 @@ where
             quorum_cert,
             block_type: BlockType::Proposal {
                 payload,
-                author: validator_signer.author(),
+                author,
                 signature: signature.into(),
             },
-        }
+        })
     }
 
     pub fn make_block(
@@ -301,17 +361,38 @@ where
         quorum_cert: QuorumCert,
         validator_signer: &ValidatorSigner,
     ) -> Self {
+        Self::make_proposal(
+            parent_block,
+            payload,
+            round,
+            timestamp_usecs,
+            quorum_cert,
+            validator_signer,
+        )
+        .expect("Failed to sign message")
+    }
+
+    /// Same as `make_block()`, but the block is signed by any `ProposalSigner`, which may fail to
+    /// sign when it lives in another process.
+    pub fn make_proposal(
+        parent_block: &Block<T>,
+        payload: T,
+        round: Round,
+        timestamp_usecs: u64,
+        quorum_cert: QuorumCert,
+        signer: &dyn ProposalSigner,
+    ) -> failure::Result<Self> {
         // A block must carry a QC to its parent.
         checked_precondition_eq!(quorum_cert.certified_block().id(), parent_block.id());
         checked_precondition!(round > parent_block.round());
 
-        Block::new_internal(
+        Block::new_proposal(
             payload,
             parent_block.epoch(),
             round,
             timestamp_usecs,
             quorum_cert,
-            validator_signer,
+            signer,
         )
     }
 
@@ -477,7 +558,7 @@ where
 }
 
 // Internal use only. Contains all the fields in Block that contribute to the computation of
-// Block Id
+// Block Id. `block_epoch_and_round()` relies on the order of the leading fields.
 #[derive(Serialize)]
 struct BlockSerializer<'a, T> {
     timestamp_usecs: u64,
@@ -496,9 +577,7 @@ where
 
     fn hash(&self) -> HashValue {
         let bytes = lcs::to_bytes(self).expect("block serialization failed");
-        let mut state = Self::Hasher::default();
-        state.write(bytes.as_ref());
-        state.finish()
+        block_id(&bytes)
     }
 }
 
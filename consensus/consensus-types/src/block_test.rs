@@ -2,7 +2,7 @@
 // SPDX-License-Identifier: Apache-2.0
 
 use crate::{
-    block::{block_test_utils::*, Block},
+    block::{block_epoch_and_round, block_test_utils::*, Block, BlockSerializer},
     quorum_cert::QuorumCert,
 };
 use crypto::hash::{CryptoHash, HashValue};
@@ -18,6 +18,21 @@ fn test_genesis() {
     assert!(genesis_block.is_genesis_block());
 }
 
+#[test]
+fn test_block_epoch_and_round() {
+    let block_data = lcs::to_bytes(&BlockSerializer {
+        payload: Some(&vec![1u8]),
+        epoch: 2,
+        round: 3,
+        timestamp_usecs: 4,
+        quorum_cert: &QuorumCert::certificate_for_genesis(),
+        author: None,
+    })
+    .unwrap();
+    assert_eq!(block_epoch_and_round(&block_data).unwrap(), (2, 3));
+    assert!(block_epoch_and_round(&block_data[..16]).is_err());
+}
+
 #[test]
 fn test_nil_block() {
     let genesis_block = Block::make_genesis_block();
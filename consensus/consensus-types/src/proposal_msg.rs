@@ -17,6 +17,9 @@ use std::fmt;
 pub struct ProposalMsg<T> {
     proposal: Block<T>,
     sync_info: SyncInfo,
+    /// Proof that the author is a valid proposer for the round, required by the proposer
+    /// elections that other validators cannot compute on their own.
+    proposer_proof: Option<Vec<u8>>,
 }
 
 /// A ProposalMsg is only accessible after verifying the signatures of a ProposalUncheckedSignatures
@@ -35,9 +38,11 @@ impl<T: Payload> TryFrom<network::proto::Proposal> for ProposalUncheckedSignatur
             .sync_info
             .ok_or_else(|| format_err!("Missing sync_info"))?
             .try_into()?;
-        Ok(ProposalUncheckedSignatures(ProposalMsg::new(
-            proposal, sync_info,
-        )))
+        let mut proposal_msg = ProposalMsg::new(proposal, sync_info);
+        if !proto.proposer_proof.is_empty() {
+            proposal_msg = proposal_msg.with_proposer_proof(proto.proposer_proof);
+        }
+        Ok(ProposalUncheckedSignatures(proposal_msg))
     }
 }
 
@@ -89,9 +94,16 @@ impl<T: Payload> ProposalMsg<T> {
         Self {
             proposal,
             sync_info,
+            proposer_proof: None,
         }
     }
 
+    /// Attaches the proof that the author is a valid proposer for the round.
+    pub fn with_proposer_proof(mut self, proposer_proof: Vec<u8>) -> Self {
+        self.proposer_proof = Some(proposer_proof);
+        self
+    }
+
     /// Verifies that the ProposalMsg is well-formed.
     pub fn verify_well_formed(self) -> Result<Self> {
         ensure!(
@@ -147,6 +159,10 @@ impl<T: Payload> ProposalMsg<T> {
         &self.sync_info
     }
 
+    pub fn proposer_proof(&self) -> Option<&[u8]> {
+        self.proposer_proof.as_ref().map(Vec::as_slice)
+    }
+
     pub fn round(&self) -> Round {
         self.proposal.round()
     }
@@ -173,6 +189,7 @@ impl<T: Payload> From<ProposalMsg<T>> for network::proto::Proposal {
         Self {
             proposed_block: Some(proposal.proposal.into()),
             sync_info: Some(proposal.sync_info.into()),
+            proposer_proof: proposal.proposer_proof.unwrap_or_default(),
         }
     }
 }
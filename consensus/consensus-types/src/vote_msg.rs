@@ -65,11 +65,29 @@ impl VoteMsg {
         let li_sig = validator_signer
             .sign_message(ledger_info_placeholder.hash())
             .expect("Failed to sign LedgerInfo");
+        Self::new_with_signature(
+            vote_data,
+            author,
+            ledger_info_placeholder,
+            li_sig.into(),
+            sync_info,
+        )
+    }
+
+    /// Generates a new VoteMsg out of a signature of the LedgerInfo produced elsewhere (e.g., by
+    /// SafetyRules). The consensus data hash of the LedgerInfo must be the hash of the vote data.
+    pub fn new_with_signature(
+        vote_data: VoteData,
+        author: Author,
+        ledger_info: LedgerInfo,
+        signature: Signature,
+        sync_info: SyncInfo,
+    ) -> Self {
         Self {
             vote_data,
             author,
-            ledger_info: ledger_info_placeholder,
-            signature: li_sig.into(),
+            ledger_info,
+            signature,
             round_signature: None,
             sync_info,
         }
@@ -81,7 +99,7 @@ impl VoteMsg {
         if self.round_signature.is_some() {
             return; // round signature is already set
         }
-        self.round_signature.replace(
+        self.add_round_signature_with(
             validator_signer
                 .sign_message(common::round_hash(self.vote_data().proposed().round()))
                 .expect("Failed to sign round")
@@ -89,6 +107,14 @@ impl VoteMsg {
         );
     }
 
+    /// Sets the round signature to a signature of the round produced elsewhere (e.g., by
+    /// SafetyRules), unless the round signature is already set.
+    pub fn add_round_signature_with(&mut self, round_signature: Signature) {
+        if self.round_signature.is_none() {
+            self.round_signature.replace(round_signature);
+        }
+    }
+
     pub fn vote_data(&self) -> &VoteData {
         &self.vote_data
     }
@@ -5,12 +5,20 @@ use consensus_types::{
     block::Block,
     common::{Payload, Round},
     quorum_cert::QuorumCert,
+    vote_data::VoteData,
 };
-use crypto::HashValue;
-use failure::Fail;
+use crypto::{ed25519::Ed25519Signature, HashValue};
+use failure::{ensure, Fail, Result};
+use libra_types::ledger_info::LedgerInfo;
 use serde::{Deserialize, Serialize};
 use std::fmt::{Display, Formatter};
 
+mod persistent_storage;
+
+pub use persistent_storage::{
+    check_timeout, check_vote, InMemoryStorage, OnDiskStorage, PersistentStorage,
+};
+
 #[cfg(test)]
 #[path = "safety_rules_test.rs"]
 mod safety_rules_test;
@@ -154,15 +162,23 @@ impl ConsensusState {
 /// 2) commit rules.
 /// SafetyRules is NOT THREAD SAFE (should be protected outside via e.g., RwLock).
 /// The commit decisions are returned to the caller as result of learning about a new QuorumCert.
+/// Votes are signed by the persistent storage, which only signs a vote once the consensus state
+/// justifying it is persisted.
 pub struct SafetyRules {
     // Keeps the state.
     state: ConsensusState,
+    // Persists the state and signs votes.
+    storage: Box<dyn PersistentStorage>,
 }
 
 impl SafetyRules {
-    /// Constructs a new instance of SafetyRules given the BlockTree and ConsensusState.
-    pub fn new(state: ConsensusState) -> Self {
-        Self { state }
+    /// Constructs a new instance of SafetyRules starting from the ConsensusState persisted in the
+    /// given storage.
+    pub fn new(storage: Box<dyn PersistentStorage>) -> Self {
+        let state = storage
+            .consensus_state()
+            .expect("Failed to read the consensus state from persistent storage");
+        Self { state, storage }
     }
 
     /// Learn about a new quorum certificate. Several things can happen as a result of that:
@@ -250,4 +266,30 @@ impl SafetyRules {
             })
         }
     }
+
+    /// Signs the ledger info of a vote for a proposal that passed the voting rules, persisting
+    /// the consensus state first. The consensus data hash of `ledger_info` must be the hash of
+    /// `vote_data`, and `quorum_cert` is the quorum cert carried by the proposal. The storage
+    /// enforces the voting rules on its own, whatever the state kept here.
+    pub fn sign_vote(
+        &mut self,
+        vote_data: &VoteData,
+        ledger_info: &LedgerInfo,
+        quorum_cert: &QuorumCert,
+    ) -> Result<Ed25519Signature> {
+        ensure!(
+            self.state.last_vote_round() == vote_data.proposed().round(),
+            "The voting rules were not applied to the proposal at round {}",
+            vote_data.proposed().round()
+        );
+        self.storage.sign_vote(vote_data, ledger_info, quorum_cert)
+    }
+
+    /// Signs a round, the round signatures are aggregated into timeout certificates. Once a round
+    /// is timed out, no vote is signed in it or in an older round.
+    pub fn sign_timeout(&mut self, round: Round) -> Result<Ed25519Signature> {
+        let signature = self.storage.sign_timeout(round)?;
+        self.state.set_last_vote_round(round);
+        Ok(signature)
+    }
 }
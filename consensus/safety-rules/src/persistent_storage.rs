@@ -0,0 +1,331 @@
+// Copyright (c) The Libra Core Contributors
+// SPDX-License-Identifier: Apache-2.0
+
+use crate::ConsensusState;
+use consensus_types::{
+    block::{block_epoch_and_round, ProposalSigner},
+    common::{self, Round},
+    quorum_cert::QuorumCert,
+    vote_data::VoteData,
+};
+use crypto::{ed25519::Ed25519Signature, hash::CryptoHash, HashValue};
+use failure::prelude::*;
+use libra_types::{
+    crypto_proxies::{ValidatorSigner, ValidatorVerifier},
+    ledger_info::LedgerInfo,
+};
+use serde::{Deserialize, Serialize};
+use std::{
+    fs::{self, File},
+    io::Write,
+    path::{Path, PathBuf},
+};
+
+/// SafetyRules keeps its consensus state and the key its votes are signed with behind this
+/// interface. An implementation persists the consensus state a vote is justified by before it
+/// signs the vote, and refuses to sign a vote the persisted state does not allow, so that a
+/// validator never votes twice in a round even across restarts. Implementations may live in
+/// another process than consensus, in which case a compromised consensus process cannot sign
+/// equivocating votes.
+pub trait PersistentStorage: Send + Sync {
+    /// Returns the latest persisted consensus state.
+    fn consensus_state(&self) -> Result<ConsensusState>;
+
+    /// Persists the consensus state justifying a vote for the proposal in `vote_data` and signs
+    /// the ledger info of the vote. `quorum_cert` is the quorum cert carried by the proposal. See
+    /// `check_vote` for the conditions under which the vote is signed.
+    fn sign_vote(
+        &mut self,
+        vote_data: &VoteData,
+        ledger_info: &LedgerInfo,
+        quorum_cert: &QuorumCert,
+    ) -> Result<Ed25519Signature>;
+
+    /// Signs a round, the round signatures are aggregated into timeout certificates. See
+    /// `check_timeout` for the conditions under which the round is signed.
+    fn sign_timeout(&mut self, round: Round) -> Result<Ed25519Signature>;
+}
+
+/// Checks that the vote for the proposal in `vote_data` is allowed given the persisted consensus
+/// state `persisted`, and returns the consensus state to persist before signing it:
+/// 1) the ledger info carries the hash of the vote data,
+/// 2) `quorum_cert` is signed by the validators and certifies the parent of the proposal,
+/// 3) the proposal is newer than any round voted on before,
+/// 4) the parent of the proposal is no older than the persisted preferred block,
+/// 5) the ledger info commits a block only if the vote data allows for a 3-chain, and then
+/// neither the proposal nor its parent.
+/// The last vote round to persist is the round of the proposal. The preferred block round is
+/// raised to the round of the parent of the certified block, the head of the 2-chain ending in
+/// `quorum_cert`, so that the locking rule holds whatever the caller claims.
+pub fn check_vote(
+    persisted: &ConsensusState,
+    validator: &ValidatorVerifier,
+    vote_data: &VoteData,
+    ledger_info: &LedgerInfo,
+    quorum_cert: &QuorumCert,
+) -> Result<ConsensusState> {
+    ensure!(
+        ledger_info.consensus_data_hash() == vote_data.hash(),
+        "Ledger info does not carry the hash of the vote data"
+    );
+    let proposed = vote_data.proposed();
+    let parent = vote_data.parent();
+    verify_quorum_cert(quorum_cert, validator)?;
+    ensure!(
+        quorum_cert.certified_block() == parent,
+        "Quorum cert certifies block {} instead of the parent {} of the proposal",
+        quorum_cert.certified_block().id(),
+        parent.id()
+    );
+    ensure!(
+        proposed.round() > persisted.last_vote_round(),
+        "Proposal at round {} is not newer than the last vote round {}",
+        proposed.round(),
+        persisted.last_vote_round()
+    );
+    ensure!(
+        parent.round() >= persisted.preferred_block_round(),
+        "Parent round {} is lower than the persisted preferred block round {}",
+        parent.round(),
+        persisted.preferred_block_round()
+    );
+    let preferred_block_round = std::cmp::max(
+        persisted.preferred_block_round(),
+        quorum_cert.parent_block().round(),
+    );
+
+    let committed_id = ledger_info.consensus_block_id();
+    if committed_id != HashValue::zero() {
+        // Only the grandparent of a proposal is committed by a vote for it, when the rounds of
+        // the three blocks are contiguous.
+        ensure!(
+            parent.round() + 1 == proposed.round(),
+            "Ledger info commits block {} but the proposal at round {} does not follow its parent \
+             at round {}",
+            committed_id,
+            proposed.round(),
+            parent.round()
+        );
+        ensure!(
+            committed_id != proposed.id() && committed_id != parent.id(),
+            "Ledger info commits the proposal or its parent {}",
+            committed_id
+        );
+        ensure!(
+            ledger_info.version() <= parent.version(),
+            "Ledger info commits version {} beyond the parent version {}",
+            ledger_info.version(),
+            parent.version()
+        );
+    }
+
+    Ok(ConsensusState::new(proposed.round(), preferred_block_round))
+}
+
+/// Checks that `quorum_cert` is signed by a quorum of `validator`. `QuorumCert::verify` leaves
+/// the signatures of a ledger info committing nothing unchecked, while the preferred block round
+/// is taken from such quorum certs, so the signatures are checked here whatever the ledger info.
+/// Only the quorum cert of the genesis, at round 0, is unsigned, and it can't raise the preferred
+/// block round.
+fn verify_quorum_cert(quorum_cert: &QuorumCert, validator: &ValidatorVerifier) -> Result<()> {
+    quorum_cert.verify(validator)?;
+    if quorum_cert.certified_block().round() == 0 {
+        return Ok(());
+    }
+    let ledger_info = quorum_cert.ledger_info();
+    validator
+        .batch_verify_aggregated_signature(
+            ledger_info.ledger_info().hash(),
+            ledger_info.signatures(),
+        )
+        .map_err(|e| format_err!("Quorum cert is not signed by the validators: {:?}", e))
+}
+
+/// Checks that a timeout at `round` is allowed given the persisted consensus state `persisted`,
+/// and returns the consensus state to persist before signing it. Rounds older than the last vote
+/// round are refused. The last vote round itself is allowed, as the timeout then comes with the
+/// vote already cast in that round. A newer round becomes the last vote round, so that no vote
+/// is signed in a round timed out.
+pub fn check_timeout(persisted: &ConsensusState, round: Round) -> Result<ConsensusState> {
+    ensure!(
+        round >= persisted.last_vote_round(),
+        "Timeout at round {} is older than the last vote round {}",
+        round,
+        persisted.last_vote_round()
+    );
+    Ok(ConsensusState::new(round, persisted.preferred_block_round()))
+}
+
+/// Keeps the consensus state in memory, nothing survives a restart. The initial consensus state
+/// is expected to be recovered by the caller. `validator` is the validator set quorum certs are
+/// verified against.
+pub struct InMemoryStorage {
+    signer: ValidatorSigner,
+    validator: ValidatorVerifier,
+    state: ConsensusState,
+}
+
+impl InMemoryStorage {
+    pub fn new(
+        signer: ValidatorSigner,
+        validator: ValidatorVerifier,
+        state: ConsensusState,
+    ) -> Self {
+        Self {
+            signer,
+            validator,
+            state,
+        }
+    }
+}
+
+impl PersistentStorage for InMemoryStorage {
+    fn consensus_state(&self) -> Result<ConsensusState> {
+        Ok(self.state.clone())
+    }
+
+    fn sign_vote(
+        &mut self,
+        vote_data: &VoteData,
+        ledger_info: &LedgerInfo,
+        quorum_cert: &QuorumCert,
+    ) -> Result<Ed25519Signature> {
+        self.state = check_vote(
+            &self.state,
+            &self.validator,
+            vote_data,
+            ledger_info,
+            quorum_cert,
+        )?;
+        self.signer.sign_message(ledger_info.hash())
+    }
+
+    fn sign_timeout(&mut self, round: Round) -> Result<Ed25519Signature> {
+        self.state = check_timeout(&self.state, round)?;
+        self.signer.sign_message(common::round_hash(round))
+    }
+}
+
+/// What `OnDiskStorage` persists: the consensus state, and the epoch and round of the last
+/// proposal signed.
+#[derive(Default, Deserialize, Serialize)]
+struct SafetyData {
+    consensus_state: ConsensusState,
+    last_proposal: (u64, Round),
+}
+
+/// Keeps the consensus state in a file. The file is replaced atomically by renaming a fully
+/// written and synced temporary file over it, and the rename is synced before anything is signed,
+/// so neither a crash nor a power loss leaves a state behind that is older than a signature.
+/// The storage also signs the proposals of its validator, at most one per round.
+pub struct OnDiskStorage {
+    signer: ValidatorSigner,
+    validator: ValidatorVerifier,
+    file_path: PathBuf,
+    data: SafetyData,
+}
+
+impl OnDiskStorage {
+    /// Opens the storage at `file_path`, starting from the default consensus state if the file
+    /// does not exist yet. `validator` is the validator set quorum certs are verified against.
+    pub fn new(
+        signer: ValidatorSigner,
+        validator: ValidatorVerifier,
+        file_path: PathBuf,
+    ) -> Result<Self> {
+        let data = if file_path.exists() {
+            let bytes = fs::read(&file_path)?;
+            lcs::from_bytes(&bytes).map_err(|e| {
+                format_err!("Failed to decode safety data in {:?}: {}", file_path, e)
+            })?
+        } else {
+            let data = SafetyData::default();
+            Self::write(&file_path, &data)?;
+            data
+        };
+        Ok(Self {
+            signer,
+            validator,
+            file_path,
+            data,
+        })
+    }
+
+    /// Signs the id of the block whose fields serialize to `block_data` if the block is newer
+    /// than any proposal signed before, persisting its epoch and round first. See `block_id()`.
+    pub fn sign_proposal(&mut self, block_data: &[u8]) -> Result<Ed25519Signature> {
+        let (epoch, round) = block_epoch_and_round(block_data)?;
+        ensure!(
+            (epoch, round) > self.data.last_proposal,
+            "Proposal at epoch {} round {} is not newer than the last one at epoch {} round {}",
+            epoch,
+            round,
+            self.data.last_proposal.0,
+            self.data.last_proposal.1
+        );
+        let data = SafetyData {
+            consensus_state: self.data.consensus_state.clone(),
+            last_proposal: (epoch, round),
+        };
+        self.persist(data)?;
+        self.signer.sign_proposal(block_data)
+    }
+
+    fn persist(&mut self, data: SafetyData) -> Result<()> {
+        Self::write(&self.file_path, &data)?;
+        self.data = data;
+        Ok(())
+    }
+
+    fn write(file_path: &Path, data: &SafetyData) -> Result<()> {
+        let tmp_path = file_path.with_extension("tmp");
+        let mut file = File::create(&tmp_path)?;
+        file.write_all(&lcs::to_bytes(data)?)?;
+        file.sync_all()?;
+        fs::rename(&tmp_path, file_path)?;
+        // The rename is only durable once the directory holding the file is synced.
+        let dir = match file_path.parent() {
+            Some(parent) if parent != Path::new("") => parent,
+            _ => Path::new("."),
+        };
+        File::open(dir)?.sync_all()?;
+        Ok(())
+    }
+}
+
+impl PersistentStorage for OnDiskStorage {
+    fn consensus_state(&self) -> Result<ConsensusState> {
+        Ok(self.data.consensus_state.clone())
+    }
+
+    fn sign_vote(
+        &mut self,
+        vote_data: &VoteData,
+        ledger_info: &LedgerInfo,
+        quorum_cert: &QuorumCert,
+    ) -> Result<Ed25519Signature> {
+        let consensus_state = check_vote(
+            &self.data.consensus_state,
+            &self.validator,
+            vote_data,
+            ledger_info,
+            quorum_cert,
+        )?;
+        self.persist(SafetyData {
+            consensus_state,
+            last_proposal: self.data.last_proposal,
+        })?;
+        self.signer.sign_message(ledger_info.hash())
+    }
+
+    fn sign_timeout(&mut self, round: Round) -> Result<Ed25519Signature> {
+        let consensus_state = check_timeout(&self.data.consensus_state, round)?;
+        if consensus_state != self.data.consensus_state {
+            self.persist(SafetyData {
+                consensus_state,
+                last_proposal: self.data.last_proposal,
+            })?;
+        }
+        self.signer.sign_message(common::round_hash(round))
+    }
+}
@@ -1,14 +1,26 @@
 // Copyright (c) The Libra Core Contributors
 // SPDX-License-Identifier: Apache-2.0
 
-use crate::{ConsensusState, ProposalReject, SafetyRules};
+use crate::{
+    check_vote, ConsensusState, InMemoryStorage, OnDiskStorage, PersistentStorage, ProposalReject,
+    SafetyRules,
+};
 use consensus_types::{
-    block::Block, block_info::BlockInfo, common::Round, quorum_cert::QuorumCert,
-    sync_info::SyncInfo, vote_data::VoteData, vote_msg::VoteMsg,
+    block::{block_id, Block},
+    block_info::BlockInfo,
+    common::{round_hash, Round},
+    quorum_cert::QuorumCert,
+    sync_info::SyncInfo,
+    vote_data::VoteData,
+    vote_msg::VoteMsg,
+};
+use crypto::{
+    hash::{CryptoHash, HashValue},
+    traits::Signature,
 };
-use crypto::hash::{CryptoHash, HashValue};
+use libra_tools::tempdir::TempPath;
 use libra_types::{
-    crypto_proxies::ValidatorSigner,
+    crypto_proxies::{ValidatorSigner, ValidatorVerifier},
     ledger_info::{LedgerInfo, LedgerInfoWithSignatures},
 };
 use std::{
@@ -16,6 +28,43 @@ use std::{
     time::{SystemTime, UNIX_EPOCH},
 };
 
+fn validator_verifier(validator_signer: &ValidatorSigner) -> ValidatorVerifier {
+    ValidatorVerifier::new_single(validator_signer.author(), validator_signer.public_key())
+}
+
+fn in_memory_storage() -> Box<dyn PersistentStorage> {
+    let validator_signer = ValidatorSigner::from_int(0);
+    let validator = validator_verifier(&validator_signer);
+    Box::new(InMemoryStorage::new(
+        validator_signer,
+        validator,
+        ConsensusState::default(),
+    ))
+}
+
+fn make_block_data(round: Round) -> Vec<u8> {
+    let mut block_data = lcs::to_bytes(&(0u64, 0u64, round)).unwrap();
+    block_data.extend_from_slice(b"other serialized block fields");
+    block_data
+}
+
+fn make_vote(block: &Block<Round>) -> (VoteData, LedgerInfo) {
+    let vote_data = VoteData::new(
+        BlockInfo::from_block(block, HashValue::zero(), 0),
+        block.quorum_cert().certified_block().clone(),
+    );
+    let ledger_info = LedgerInfo::new(
+        0,
+        HashValue::zero(),
+        vote_data.hash(),
+        HashValue::zero(),
+        0,
+        0,
+        None,
+    );
+    (vote_data, ledger_info)
+}
+
 fn make_block_with_qc(
     round: Round,
     qc: QuorumCert,
@@ -99,7 +148,7 @@ fn test_initial_state() {
     // Start from scratch, verify the state
     let block = Block::<u64>::make_genesis_block();
 
-    let safety_rules = SafetyRules::new(ConsensusState::default());
+    let safety_rules = SafetyRules::new(in_memory_storage());
     let state = safety_rules.consensus_state();
     assert_eq!(state.last_vote_round(), block.round());
     assert_eq!(state.preferred_block_round(), block.round());
@@ -109,7 +158,7 @@ fn test_initial_state() {
 fn test_preferred_block_rule() {
     // Preferred block is the highest 2-chain head.
     let validator_signer = ValidatorSigner::from_int(0);
-    let mut safety_rules = SafetyRules::new(ConsensusState::default());
+    let mut safety_rules = SafetyRules::new(in_memory_storage());
 
     // build a tree of the following form:
     //             _____    _____
@@ -177,7 +226,7 @@ fn test_preferred_block_rule() {
 /// Test the potential ledger info that we're going to use in case of voting
 fn test_voting_potential_commit_id() {
     let validator_signer = ValidatorSigner::from_int(0);
-    let mut safety_rules = SafetyRules::new(ConsensusState::default());
+    let mut safety_rules = SafetyRules::new(in_memory_storage());
 
     // build a tree of the following form:
     //            _____
@@ -232,7 +281,7 @@ fn test_voting_potential_commit_id() {
 #[test]
 fn test_voting() {
     let validator_signer = ValidatorSigner::from_int(0);
-    let mut safety_rules = SafetyRules::new(ConsensusState::default());
+    let mut safety_rules = SafetyRules::new(in_memory_storage());
 
     // build a tree of the following form:
     //             _____    __________
@@ -318,7 +367,7 @@ fn test_voting() {
 #[test]
 fn test_commit_rule_consecutive_rounds() {
     let validator_signer = ValidatorSigner::from_int(0);
-    let safety_rules = SafetyRules::new(ConsensusState::default());
+    let safety_rules = SafetyRules::new(in_memory_storage());
 
     // build a tree of the following form:
     //             ___________
@@ -364,3 +413,250 @@ fn test_commit_rule_consecutive_rounds() {
         Some(a2.id())
     );
 }
+
+#[test]
+fn test_sign_vote() {
+    let validator_signer = ValidatorSigner::from_int(0);
+    let mut safety_rules = SafetyRules::new(in_memory_storage());
+
+    let genesis_qc = QuorumCert::certificate_for_genesis();
+    let a1 = make_block_with_qc(1, genesis_qc.clone(), &validator_signer);
+    // Same round, another payload.
+    let b1 = Block::<Round>::new_internal(2, 0, 1, 0, genesis_qc, &validator_signer);
+    let (vote_data, ledger_info) = make_vote(&a1);
+
+    let qc = a1.quorum_cert();
+
+    // The voting rules were not applied yet.
+    assert!(safety_rules
+        .sign_vote(&vote_data, &ledger_info, qc)
+        .is_err());
+
+    assert!(safety_rules.voting_rule(&a1).is_ok());
+    let signature = safety_rules
+        .sign_vote(&vote_data, &ledger_info, qc)
+        .unwrap();
+    assert!(signature
+        .verify(&ledger_info.hash(), &validator_signer.public_key())
+        .is_ok());
+
+    // Whatever the caller does, there is no second vote in the same round.
+    assert!(safety_rules
+        .sign_vote(&vote_data, &ledger_info, qc)
+        .is_err());
+    let (equivocating_vote_data, equivocating_ledger_info) = make_vote(&b1);
+    assert!(safety_rules
+        .sign_vote(
+            &equivocating_vote_data,
+            &equivocating_ledger_info,
+            b1.quorum_cert()
+        )
+        .is_err());
+}
+
+#[test]
+fn test_check_vote() {
+    let validator_signer = ValidatorSigner::from_int(0);
+    let validator = validator_verifier(&validator_signer);
+    let genesis_qc = QuorumCert::certificate_for_genesis();
+
+    // genesis---a1---a2---a3-------a5
+    let a1 = make_block_with_qc(1, genesis_qc.clone(), &validator_signer);
+    let a2 = make_block_with_parent(2, &a1, None, &validator_signer, &genesis_qc);
+    let a3 = make_block_with_parent(3, &a2, None, &validator_signer, a2.quorum_cert());
+    let a5 = make_block_with_parent(5, &a3, None, &validator_signer, a3.quorum_cert());
+    let (vote_data, mut ledger_info) = make_vote(&a2);
+    let qc = a2.quorum_cert();
+    let persisted = ConsensusState::new(1, 0);
+
+    assert_eq!(
+        check_vote(&persisted, &validator, &vote_data, &ledger_info, qc).unwrap(),
+        ConsensusState::new(2, 0)
+    );
+    // Not newer than the persisted last vote round.
+    assert!(check_vote(
+        &ConsensusState::new(2, 0),
+        &validator,
+        &vote_data,
+        &ledger_info,
+        qc
+    )
+    .is_err());
+    // The parent is older than the persisted preferred block round.
+    assert!(check_vote(
+        &ConsensusState::new(1, 2),
+        &validator,
+        &vote_data,
+        &ledger_info,
+        qc
+    )
+    .is_err());
+
+    // The preferred block round is the head of the 2-chain of the quorum cert, whatever the
+    // caller knows about.
+    let (a3_vote_data, a3_ledger_info) = make_vote(&a3);
+    assert_eq!(
+        check_vote(
+            &ConsensusState::new(2, 0),
+            &validator,
+            &a3_vote_data,
+            &a3_ledger_info,
+            a3.quorum_cert()
+        )
+        .unwrap(),
+        ConsensusState::new(3, 1)
+    );
+    // A quorum cert which does not certify the parent of the proposal.
+    assert!(check_vote(
+        &ConsensusState::new(2, 0),
+        &validator,
+        &a3_vote_data,
+        &a3_ledger_info,
+        qc
+    )
+    .is_err());
+    // A quorum cert which is not signed by the validators, even if it commits nothing.
+    let other_validator = validator_verifier(&ValidatorSigner::from_int(1));
+    assert!(check_vote(
+        &ConsensusState::new(2, 0),
+        &other_validator,
+        &a3_vote_data,
+        &a3_ledger_info,
+        a3.quorum_cert()
+    )
+    .is_err());
+
+    // A commit without contiguous rounds.
+    let (a5_vote_data, _) = make_vote(&a5);
+    let committing_ledger_info = LedgerInfo::new(
+        0,
+        HashValue::zero(),
+        a5_vote_data.hash(),
+        a1.id(),
+        0,
+        0,
+        None,
+    );
+    assert!(check_vote(
+        &ConsensusState::new(3, 1),
+        &validator,
+        &a5_vote_data,
+        &committing_ledger_info,
+        a5.quorum_cert()
+    )
+    .is_err());
+    // Ledger info of another vote.
+    ledger_info.set_consensus_data_hash(HashValue::zero());
+    assert!(check_vote(&persisted, &validator, &vote_data, &ledger_info, qc).is_err());
+}
+
+#[test]
+fn test_sign_timeout() {
+    let validator_signer = ValidatorSigner::from_int(0);
+    let mut safety_rules = SafetyRules::new(in_memory_storage());
+
+    let a1 = make_block_with_qc(1, QuorumCert::certificate_for_genesis(), &validator_signer);
+    let (vote_data, ledger_info) = make_vote(&a1);
+    assert!(safety_rules.voting_rule(&a1).is_ok());
+    assert!(safety_rules
+        .sign_vote(&vote_data, &ledger_info, a1.quorum_cert())
+        .is_ok());
+
+    // A timeout in the round of the vote is fine, not in an older round.
+    assert!(safety_rules.sign_timeout(1).is_ok());
+    assert!(safety_rules.sign_timeout(0).is_err());
+
+    // No vote in a round timed out.
+    let a3 = make_block_with_qc(3, QuorumCert::certificate_for_genesis(), &validator_signer);
+    let (vote_data, ledger_info) = make_vote(&a3);
+    let signature = safety_rules.sign_timeout(3).unwrap();
+    assert!(signature
+        .verify(&round_hash(3), &validator_signer.public_key())
+        .is_ok());
+    assert_eq!(safety_rules.consensus_state().last_vote_round(), 3);
+    assert!(safety_rules.voting_rule(&a3).is_err());
+    assert!(safety_rules
+        .sign_vote(&vote_data, &ledger_info, a3.quorum_cert())
+        .is_err());
+}
+
+#[test]
+fn test_on_disk_storage() {
+    let tmp_dir = TempPath::new();
+    tmp_dir.create_as_dir().unwrap();
+    let file_path = tmp_dir.path().join("safety_rules.lcs");
+    let validator_signer = ValidatorSigner::from_int(0);
+    let open = || {
+        OnDiskStorage::new(
+            validator_signer.clone(),
+            validator_verifier(&validator_signer),
+            file_path.clone(),
+        )
+        .unwrap()
+    };
+
+    let mut storage = open();
+    assert_eq!(
+        storage.consensus_state().unwrap(),
+        ConsensusState::default()
+    );
+
+    let a1 = make_block_with_qc(1, QuorumCert::certificate_for_genesis(), &validator_signer);
+    let (vote_data, ledger_info) = make_vote(&a1);
+    assert!(storage
+        .sign_vote(&vote_data, &ledger_info, a1.quorum_cert())
+        .is_ok());
+
+    // The state survives reopening the storage, and so does the vote.
+    let mut storage = open();
+    assert_eq!(
+        storage.consensus_state().unwrap(),
+        ConsensusState::new(1, 0)
+    );
+    assert!(storage
+        .sign_vote(&vote_data, &ledger_info, a1.quorum_cert())
+        .is_err());
+
+    // So does a timeout in a newer round.
+    assert!(storage.sign_timeout(2).is_ok());
+    let storage = open();
+    assert_eq!(
+        storage.consensus_state().unwrap(),
+        ConsensusState::new(2, 0)
+    );
+}
+
+#[test]
+fn test_on_disk_storage_sign_proposal() {
+    let tmp_dir = TempPath::new();
+    tmp_dir.create_as_dir().unwrap();
+    let file_path = tmp_dir.path().join("safety_rules.lcs");
+    let validator_signer = ValidatorSigner::from_int(0);
+    let open = || {
+        OnDiskStorage::new(
+            validator_signer.clone(),
+            validator_verifier(&validator_signer),
+            file_path.clone(),
+        )
+        .unwrap()
+    };
+
+    let mut storage = open();
+    let block_data = make_block_data(1);
+    let signature = storage.sign_proposal(&block_data).unwrap();
+    assert!(signature
+        .verify(&block_id(&block_data), &validator_signer.public_key())
+        .is_ok());
+
+    // A single proposal per round, even across a restart.
+    assert!(storage.sign_proposal(&make_block_data(1)).is_err());
+    assert!(storage.sign_proposal(&make_block_data(3)).is_ok());
+    let mut storage = open();
+    assert!(storage.sign_proposal(&make_block_data(2)).is_err());
+    assert!(storage.sign_proposal(&make_block_data(4)).is_ok());
+    // The votes are unaffected.
+    assert_eq!(
+        storage.consensus_state().unwrap(),
+        ConsensusState::default()
+    );
+}
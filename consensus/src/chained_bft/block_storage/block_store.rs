@@ -9,8 +9,8 @@ use crate::{
     state_replication::StateComputer,
 };
 use consensus_types::{
-    block::{Block, ExecutedBlock},
-    common::{Payload, Round},
+    block::{Block, ExecutedBlock, ProposalSigner},
+    common::{Author, Payload, Round},
     quorum_cert::QuorumCert,
     timeout_certificate::TimeoutCertificate,
     vote_msg::VoteMsg,
@@ -21,7 +21,7 @@ use failure::ResultExt;
 #[cfg(any(test, feature = "fuzzing"))]
 use libra_types::validator_set::ValidatorSet;
 use libra_types::{
-    crypto_proxies::{ValidatorSigner, ValidatorVerifier},
+    crypto_proxies::ValidatorVerifier,
     ledger_info::LedgerInfo,
 };
 use logger::prelude::*;
@@ -62,7 +62,7 @@ pub enum NeedFetchResult {
 ///             ╰--------------> D3
 pub struct BlockStore<T> {
     inner: Arc<RwLock<BlockTree<T>>>,
-    validator_signer: ValidatorSigner,
+    proposal_signer: Arc<dyn ProposalSigner>,
     state_computer: Arc<dyn StateComputer<Payload = T>>,
     enforce_increasing_timestamps: bool,
     /// The persistent storage backing up the in-memory data structure, every write should go
@@ -74,7 +74,7 @@ impl<T: Payload> BlockStore<T> {
     pub async fn new(
         storage: Arc<dyn PersistentStorage<T>>,
         initial_data: RecoveryData<T>,
-        validator_signer: ValidatorSigner,
+        proposal_signer: Arc<dyn ProposalSigner>,
         state_computer: Arc<dyn StateComputer<Payload = T>>,
         enforce_increasing_timestamps: bool,
         max_pruned_blocks_in_mem: usize,
@@ -94,7 +94,7 @@ impl<T: Payload> BlockStore<T> {
         ));
         BlockStore {
             inner,
-            validator_signer,
+            proposal_signer,
             state_computer,
             enforce_increasing_timestamps,
             storage,
@@ -182,8 +182,9 @@ impl<T: Payload> BlockStore<T> {
         *self.inner.write().unwrap() = tree;
     }
 
-    pub fn signer(&self) -> &ValidatorSigner {
-        &self.validator_signer
+    /// Returns the author of the proposals created by this block store.
+    pub fn author(&self) -> Author {
+        self.proposal_signer.author()
     }
 
     /// Execute and insert a block if it passes all validation tests.
@@ -486,7 +487,7 @@ impl<T: Payload> BlockReader for BlockStore<T> {
         payload: Self::Payload,
         round: Round,
         timestamp_usecs: u64,
-    ) -> Block<Self::Payload> {
+    ) -> failure::Result<Block<Self::Payload>> {
         if self.enforce_increasing_timestamps {
             checked_precondition!(parent.timestamp_usecs() < timestamp_usecs);
         }
@@ -495,13 +496,13 @@ impl<T: Payload> BlockReader for BlockStore<T> {
             .expect("Parent for the newly created block is not certified!")
             .as_ref()
             .clone();
-        Block::make_block(
+        Block::make_proposal(
             parent,
             payload,
             round,
             timestamp_usecs,
             quorum_cert,
-            &self.validator_signer,
+            self.proposal_signer.as_ref(),
         )
     }
 
@@ -58,9 +58,12 @@ fn build_simple_tree() -> (
 
 #[test]
 fn test_block_store_create_block() {
-    let block_store = build_empty_tree();
+    let signer = ValidatorSigner::random(None);
+    let block_store = build_empty_tree_with_custom_signing(signer.clone());
     let genesis = block_store.root();
-    let a1 = block_store.create_block(genesis.block(), vec![1], 1, 1);
+    let a1 = block_store
+        .create_block(genesis.block(), vec![1], 1, 1)
+        .unwrap();
     assert_eq!(a1.parent_id(), genesis.id());
     assert_eq!(a1.round(), 1);
     assert_eq!(a1.quorum_cert().certified_block().id(), genesis.id());
@@ -81,18 +84,17 @@ fn test_block_store_create_block() {
             ),
             a1_ref.quorum_cert().certified_block().clone(),
         ),
-        block_store.signer().author(),
+        signer.author(),
         placeholder_ledger_info(),
-        block_store.signer(),
+        &signer,
         test_utils::placeholder_sync_info(),
     );
-    let validator_verifier = ValidatorVerifier::new_single(
-        block_store.signer().author(),
-        block_store.signer().public_key(),
-    );
+    let validator_verifier = ValidatorVerifier::new_single(signer.author(), signer.public_key());
     block_store.insert_vote_and_qc(vote_msg, &validator_verifier);
 
-    let b1 = block_store.create_block(a1_ref.block(), vec![2], 2, 2);
+    let b1 = block_store
+        .create_block(a1_ref.block(), vec![2], 2, 2)
+        .unwrap();
     assert_eq!(b1.parent_id(), a1_ref.id());
     assert_eq!(b1.round(), 2);
     assert_eq!(b1.quorum_cert().certified_block().id(), a1_ref.id());
@@ -407,7 +409,8 @@ fn test_insert_vote() {
 
 #[test]
 fn test_illegal_timestamp() {
-    let block_store = build_empty_tree();
+    let signer = ValidatorSigner::random(None);
+    let block_store = build_empty_tree_with_custom_signing(signer.clone());
     let genesis = block_store.root();
     let block_with_illegal_timestamp = Block::<Vec<usize>>::new_internal(
         vec![],
@@ -416,7 +419,7 @@ fn test_illegal_timestamp() {
         // This timestamp is illegal, it is the same as genesis
         genesis.timestamp_usecs(),
         QuorumCert::certificate_for_genesis(),
-        block_store.signer(),
+        &signer,
     );
     let result = block_on(block_store.execute_and_insert_block(block_with_illegal_timestamp));
     assert!(result.is_err());
@@ -451,7 +454,7 @@ fn test_need_fetch_for_qc() {
     let a3 = inserter.insert_block(&a2, 3);
     block_tree.prune_tree(a2.id());
     let need_fetch_qc = placeholder_certificate_for_block(
-        vec![block_tree.signer()],
+        vec![inserter.signer()],
         HashValue::zero(),
         a3.round() + 1,
         HashValue::zero(),
@@ -459,7 +462,7 @@ fn test_need_fetch_for_qc() {
     );
     let too_old_qc = QuorumCert::certificate_for_genesis();
     let can_insert_qc = placeholder_certificate_for_block(
-        vec![block_tree.signer()],
+        vec![inserter.signer()],
         a3.id(),
         a3.round(),
         a2.id(),
@@ -497,7 +500,7 @@ fn test_need_sync_for_qc() {
     let a3 = inserter.insert_block(&a2, 3);
     block_tree.prune_tree(a3.id());
     let qc = placeholder_certificate_for_block(
-        vec![block_tree.signer()],
+        vec![inserter.signer()],
         HashValue::zero(),
         a3.round() + 3,
         HashValue::zero(),
@@ -508,7 +511,7 @@ fn test_need_sync_for_qc() {
         true
     );
     let qc = placeholder_certificate_for_block(
-        vec![block_tree.signer()],
+        vec![inserter.signer()],
         HashValue::zero(),
         a3.round() + 2,
         HashValue::zero(),
@@ -70,6 +70,7 @@ pub trait BlockReader: Send + Sync {
     /// Note that it does not add the block to the tree, just generates it.
     /// The main reason we want this function in the BlockStore is the fact that the signer required
     /// for signing the newly created block is held by the block store.
+    /// Returns an error if the signer fails to sign the block.
     /// The function panics in the following cases:
     /// * If the parent or its quorum certificate are not present in the tree,
     /// * If the given round (which is typically calculated by Pacemaker) is not greater than that
@@ -80,7 +81,7 @@ pub trait BlockReader: Send + Sync {
         payload: Self::Payload,
         round: Round,
         timestamp_usecs: u64,
-    ) -> Block<Self::Payload>;
+    ) -> failure::Result<Block<Self::Payload>>;
 
     /// Return the certified block with the highest round.
     fn highest_certified_block(&self) -> Arc<ExecutedBlock<Self::Payload>>;
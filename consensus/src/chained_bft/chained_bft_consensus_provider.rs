@@ -7,14 +7,15 @@ use crate::{
         chained_bft_smr::{ChainedBftSMR, ChainedBftSMRConfig},
         persistent_storage::{PersistentStorage, StorageWriteProxy},
     },
-    consensus_provider::ConsensusProvider,
+    consensus_provider::{create_safety_rules_client, ConsensusProvider},
     counters,
     state_computer::ExecutionProxy,
     state_replication::StateMachineReplication,
     txn_manager::MempoolProxy,
 };
-use config::config::NodeConfig;
-use consensus_types::common::Author;
+use config::config::{NodeConfig, SafetyRulesBackend};
+use consensus_types::{block::ProposalSigner, common::Author};
+use crypto::{ed25519::Ed25519PrivateKey, vrf::ecvrf::VRFPrivateKey};
 use executor::Executor;
 use failure::prelude::*;
 use libra_mempool::proto::mempool::MempoolClient;
@@ -25,6 +26,10 @@ use libra_types::{
 };
 use logger::prelude::*;
 use network::validator_network::{ConsensusNetworkEvents, ConsensusNetworkSender};
+use safety_rules::{
+    ConsensusState, InMemoryStorage, OnDiskStorage, PersistentStorage as SafetyRulesStorage,
+};
+use secret_service::safety_rules_client::{RemoteProposalSigner, RemoteStorage};
 use state_synchronizer::StateSyncClient;
 use std::{convert::TryFrom, sync::Arc};
 use tokio::runtime;
@@ -34,7 +39,10 @@ use vm_runtime::MoveVM;
 pub struct InitialSetup {
     pub author: Author,
     pub epoch: u64,
-    pub signer: ValidatorSigner,
+    pub proposal_signer: Arc<dyn ProposalSigner>,
+    /// The VRF key proving the leadership of the validator in the VRF proposer election, if any.
+    pub vrf_private_key: Option<VRFPrivateKey>,
+    pub safety_rules_storage: Box<dyn SafetyRulesStorage>,
     pub validator: ValidatorVerifier,
     pub network_sender: ConsensusNetworkSender,
     pub network_events: ConsensusNetworkEvents,
@@ -61,10 +69,15 @@ impl ChainedBftProvider {
             .build()
             .expect("Failed to create Tokio runtime!");
 
-        let initial_setup = Self::initialize_setup(network_sender, network_events, node_config);
+        let (storage, initial_data) = StorageWriteProxy::start(node_config);
+        let initial_setup = Self::initialize_setup(
+            network_sender,
+            network_events,
+            node_config,
+            initial_data.state(),
+        );
         debug!("[Consensus] My peer: {:?}", initial_setup.author);
         let config = ChainedBftSMRConfig::from_node_config(&node_config.consensus);
-        let (storage, initial_data) = StorageWriteProxy::start(node_config);
         info!(
             "Starting up the consensus state machine with recovery data - [consensus state {:?}], [last_vote {}], [highest timeout certificate: {}]",
             initial_data.state(),
@@ -87,6 +100,7 @@ impl ChainedBftProvider {
         network_sender: ConsensusNetworkSender,
         network_events: ConsensusNetworkEvents,
         node_config: &mut NodeConfig,
+        consensus_state: ConsensusState,
     ) -> InitialSetup {
         let peer_id_str = node_config
             .get_validator_network_config()
@@ -95,20 +109,14 @@ impl ChainedBftProvider {
             .clone();
         let author =
             AccountAddress::try_from(peer_id_str).expect("Failed to parse peer id of a validator");
-        let private_key = node_config
-            .consensus
-            .consensus_keypair
-            .take_consensus_private()
-            .expect(
-            "Failed to move a Consensus private key from a NodeConfig, key absent or already read",
-        );
-        let signer = ValidatorSigner::new(author, private_key);
         // Keeping the initial set of validators in a node config is embarrassing and we should
         // all feel bad about it.
         let validator = node_config
             .consensus
             .consensus_peers
             .get_validator_verifier();
+        let (proposal_signer, vrf_private_key, safety_rules_storage) =
+            Self::create_signers(node_config, author, &validator, consensus_state);
         counters::EPOCH.set(0); // No reconfiguration yet, so it is always zero
         counters::CURRENT_EPOCH_VALIDATORS.set(validator.len() as i64);
         counters::CURRENT_EPOCH_QUORUM_SIZE.set(validator.quorum_voting_power() as i64);
@@ -120,12 +128,83 @@ impl ChainedBftProvider {
             author,
             // TODO: this is placeholder for now, replace with reconfiguration
             epoch: 0,
-            signer,
+            proposal_signer,
+            vrf_private_key,
+            safety_rules_storage,
             validator,
             network_sender,
             network_events,
         }
     }
+
+    /// Creates the signers of proposals and votes for the safety rules backend chosen by the
+    /// config. Only the backends keeping the consensus state in this process load the consensus
+    /// private key. The secret service keeps it in its own process and signs on behalf of
+    /// consensus, at most one proposal per round. The quorum certs of the proposals voted on are
+    /// verified by the storage against `validator`.
+    fn create_signers(
+        node_config: &mut NodeConfig,
+        author: Author,
+        validator: &ValidatorVerifier,
+        consensus_state: ConsensusState,
+    ) -> (
+        Arc<dyn ProposalSigner>,
+        Option<VRFPrivateKey>,
+        Box<dyn SafetyRulesStorage>,
+    ) {
+        let backend = node_config.consensus.get_safety_rules_backend();
+        if backend == SafetyRulesBackend::SecretService {
+            // Without the private key, no VRF proof is computed: the validator is never elected
+            // by the VRF proposer election.
+            let client = create_safety_rules_client(node_config);
+            return (
+                Arc::new(RemoteProposalSigner::new(author, client.clone())),
+                None,
+                Box::new(RemoteStorage::new(client)),
+            );
+        }
+
+        let private_key = node_config
+            .consensus
+            .consensus_keypair
+            .take_consensus_private()
+            .expect(
+            "Failed to move a Consensus private key from a NodeConfig, key absent or already read",
+        );
+        // ECVRF-ED25519 keys are Ed25519 keys: the consensus keys of the validators serve as their
+        // VRF keys.
+        let vrf_private_key = VRFPrivateKey::try_from(&private_key.to_bytes()[..])
+            .expect("Failed to derive the VRF private key");
+        // The safety rules storage signs votes with its own copy of the key.
+        let storage_signer = ValidatorSigner::new(
+            author,
+            Ed25519PrivateKey::try_from(&private_key.to_bytes()[..])
+                .expect("Failed to copy the consensus private key"),
+        );
+        let safety_rules_storage: Box<dyn SafetyRulesStorage> =
+            if backend == SafetyRulesBackend::OnDisk {
+                Box::new(
+                    OnDiskStorage::new(
+                        storage_signer,
+                        validator.clone(),
+                        node_config.get_safety_rules_file(),
+                    )
+                    .expect("Failed to open the safety rules storage"),
+                )
+            } else {
+                Box::new(InMemoryStorage::new(
+                    storage_signer,
+                    validator.clone(),
+                    consensus_state,
+                ))
+            };
+        let proposal_signer = ValidatorSigner::new(author, private_key);
+        (
+            Arc::new(proposal_signer),
+            Some(vrf_private_key),
+            safety_rules_storage,
+        )
+    }
 }
 
 impl ConsensusProvider for ChainedBftProvider {
@@ -8,11 +8,13 @@ use crate::{
         block_storage::BlockStore,
         event_processor::EventProcessor,
         liveness::{
+            leader_reputation::{ActiveInactiveHeuristic, ConsensusDBBackend, LeaderReputation},
             multi_proposer_election::MultiProposer,
             pacemaker::{ExponentialTimeInterval, Pacemaker},
             proposal_generator::ProposalGenerator,
             proposer_election::ProposerElection,
             rotating_proposer_election::{choose_leader, RotatingProposer},
+            vrf_proposer_election::VrfProposer,
         },
         network::ConsensusNetworkImpl,
         persistent_storage::{PersistentStorage, RecoveryData},
@@ -23,16 +25,29 @@ use crate::{
 };
 use channel;
 use config::config::{ConsensusConfig, ConsensusProposerType};
-use consensus_types::common::{Payload, Round};
+use consensus_types::{
+    block::ProposalSigner,
+    common::{Author, Payload, Round},
+};
+use crypto::vrf::ecvrf::{VRFPrivateKey, VRFPublicKey};
 use failure::prelude::*;
 use futures::{executor::block_on, select, stream::StreamExt};
-use libra_types::crypto_proxies::{ValidatorSigner, ValidatorVerifier};
+use libra_types::crypto_proxies::ValidatorVerifier;
 use logger::prelude::*;
 use network::validator_network::{ConsensusNetworkEvents, ConsensusNetworkSender};
-use safety_rules::SafetyRules;
-use std::{sync::Arc, time::Duration};
+use safety_rules::{PersistentStorage as SafetyRulesStorage, SafetyRules};
+use std::{collections::HashMap, convert::TryFrom, sync::Arc, time::Duration};
 use tokio::runtime::{Runtime, TaskExecutor};
 
+// The leader reputation election considers the proposers and voters of this number of latest
+// ancestors of the block a proposal extends active.
+const LEADER_REPUTATION_WINDOW_SIZE: usize = 20;
+const LEADER_REPUTATION_ACTIVE_WEIGHT: u64 = 100;
+const LEADER_REPUTATION_INACTIVE_WEIGHT: u64 = 1;
+// The number of proposers elected on average per round by the VRF proposer election: the
+// probability that none is elected (and the round times out) decreases exponentially with it.
+const VRF_EXPECTED_PROPOSERS_PER_ROUND: usize = 3;
+
 /// Consensus configuration derived from ConsensusConfig
 pub struct ChainedBftSMRConfig {
     /// Keep up to this number of committed blocks before cleaning them up from the block store.
@@ -113,6 +128,10 @@ impl<T: Payload> ChainedBftSMR<T> {
     /// Create a proposer election handler based on proposers
     fn create_proposer_election(
         &self,
+        block_store: Arc<BlockStore<T>>,
+        author: Author,
+        vrf_private_key: Option<VRFPrivateKey>,
+        epoch: u64,
         validators: &ValidatorVerifier,
     ) -> Box<dyn ProposerElection<T> + Send + Sync> {
         let proposers = validators.get_ordered_account_addresses();
@@ -132,6 +151,41 @@ impl<T: Payload> ChainedBftSMR<T> {
                     self.config.contiguous_rounds,
                 ))
             }
+            ConsensusProposerType::LeaderReputation => Box::new(LeaderReputation::new(
+                proposers,
+                Box::new(ConsensusDBBackend::new(
+                    block_store,
+                    self.storage.persistent_liveness_storage(),
+                    LEADER_REPUTATION_WINDOW_SIZE,
+                )),
+                Box::new(ActiveInactiveHeuristic::new(
+                    LEADER_REPUTATION_ACTIVE_WEIGHT,
+                    LEADER_REPUTATION_INACTIVE_WEIGHT,
+                )),
+            )),
+            ConsensusProposerType::VrfProposer => {
+                // The consensus keys of the validators serve as their VRF keys.
+                let public_keys: HashMap<Author, VRFPublicKey> = proposers
+                    .into_iter()
+                    .filter_map(|proposer| {
+                        let public_key = validators.get_public_key(&proposer)?;
+                        match VRFPublicKey::try_from(&public_key.to_bytes()[..]) {
+                            Ok(vrf_public_key) => Some((proposer, vrf_public_key)),
+                            Err(e) => {
+                                error!("Invalid VRF public key of {}: {:?}", proposer, e);
+                                None
+                            }
+                        }
+                    })
+                    .collect();
+                Box::new(VrfProposer::new(
+                    author,
+                    epoch,
+                    vrf_private_key,
+                    public_keys,
+                    VRF_EXPECTED_PROPOSERS_PER_ROUND,
+                ))
+            }
         }
     }
 
@@ -175,7 +229,9 @@ impl<T: Payload> ChainedBftSMR<T> {
         epoch_mgr: Arc<EpochManager>,
         network_sender: ConsensusNetworkSender,
         network_events: ConsensusNetworkEvents,
-        signer: ValidatorSigner,
+        proposal_signer: Arc<dyn ProposalSigner>,
+        vrf_private_key: Option<VRFPrivateKey>,
+        safety_rules_storage: Box<dyn SafetyRulesStorage>,
         initial_data: RecoveryData<T>,
         txn_manager: Arc<dyn TxnManager<Payload = T>>,
         state_computer: Arc<dyn StateComputer<Payload = T>>,
@@ -186,20 +242,21 @@ impl<T: Payload> ChainedBftSMR<T> {
             .expect("Consensus start: No valid runtime found!")
             .executor();
         let time_service = Arc::new(ClockTimeService::new(executor.clone()));
+        let author = proposal_signer.author();
         let network = ConsensusNetworkImpl::new(
-            signer.author(),
+            author,
             network_sender.clone(),
             network_events,
             Arc::clone(&epoch_mgr),
         );
 
         let last_vote = initial_data.last_vote();
-        let safety_rules = SafetyRules::new(initial_data.state());
+        let safety_rules = SafetyRules::new(safety_rules_storage);
 
         let block_store = Arc::new(block_on(BlockStore::new(
             Arc::clone(&self.storage),
             initial_data,
-            signer,
+            proposal_signer,
             Arc::clone(&state_computer),
             true,
             self.config.max_pruned_blocks_in_mem,
@@ -221,7 +278,13 @@ impl<T: Payload> ChainedBftSMR<T> {
             channel::new(1_024, &counters::PENDING_PACEMAKER_TIMEOUTS);
         let pacemaker = self.create_pacemaker(time_service.clone(), timeout_sender);
 
-        let proposer_election = self.create_proposer_election(epoch_mgr.validators().as_ref());
+        let proposer_election = self.create_proposer_election(
+            Arc::clone(&block_store),
+            author,
+            vrf_private_key,
+            epoch_mgr.epoch(),
+            epoch_mgr.validators().as_ref(),
+        );
         let event_processor = EventProcessor::new(
             Arc::clone(&block_store),
             last_vote,
@@ -280,7 +343,9 @@ impl<T: Payload> StateMachineReplication for ChainedBftSMR<T> {
             epoch_mgr,
             initial_setup.network_sender,
             initial_setup.network_events,
-            initial_setup.signer,
+            initial_setup.proposal_signer,
+            initial_setup.vrf_private_key,
+            initial_setup.safety_rules_storage,
             initial_data,
             txn_manager,
             state_computer,
@@ -19,6 +19,7 @@ use crypto::hash::CryptoHash;
 use futures::{channel::mpsc, executor::block_on, prelude::*};
 use network::proto::ConsensusMsg_oneof;
 use network::validator_network::{ConsensusNetworkEvents, ConsensusNetworkSender};
+use safety_rules::InMemoryStorage;
 use std::convert::TryFrom;
 use std::sync::Arc;
 
@@ -29,7 +30,7 @@ use crate::chained_bft::{
     test_utils::{consensus_runtime, with_smr_id},
 };
 use config::config::ConsensusProposerType::{
-    self, FixedProposer, MultipleOrderedProposers, RotatingProposer,
+    self, FixedProposer, LeaderReputation, MultipleOrderedProposers, RotatingProposer,
 };
 use libra_types::crypto_proxies::{
     random_validator_verifier, LedgerInfoWithSignatures, ValidatorSigner,
@@ -82,7 +83,13 @@ impl SMRNode {
         };
         let initial_setup = InitialSetup {
             author,
-            signer: signer.clone(),
+            proposal_signer: Arc::new(signer.clone()),
+            vrf_private_key: None,
+            safety_rules_storage: Box::new(InMemoryStorage::new(
+                signer.clone(),
+                epoch_mgr.validators().as_ref().clone(),
+                initial_data.state(),
+            )),
             epoch: 0,
             validator: epoch_mgr.validators().as_ref().clone(),
             network_sender,
@@ -293,6 +300,12 @@ fn happy_path_with_multi_proposer() {
     basic_full_round(2, 2, MultipleOrderedProposers);
 }
 
+#[test]
+/// Basic happy path with leader reputation
+fn happy_path_with_leader_reputation() {
+    basic_full_round(2, 2, LeaderReputation);
+}
+
 /// Verify the basic e2e flow: blocks are committed, txn manager is notified, block tree is
 /// pruned, restart the node and we can still continue.
 #[test]
@@ -3,6 +3,8 @@
 
 use super::*;
 use libra_tools::tempdir::TempPath;
+use libra_types::account_address::AccountAddress;
+use std::collections::BTreeMap;
 
 #[test]
 fn test_put_get() {
@@ -56,3 +58,39 @@ fn test_delete_block_and_qc() {
     assert_eq!(db.get_blocks::<i64>().unwrap().len(), 0);
     assert_eq!(db.get_quorum_certificates().unwrap().len(), 0);
 }
+
+#[test]
+fn test_block_metadata() {
+    let tmp_dir = TempPath::new();
+    let db = ConsensusDB::new(&tmp_dir);
+    let make_metadata = |round| {
+        (
+            round,
+            BlockMetadata::new(
+                HashValue::zero(),
+                round,
+                BTreeMap::new(),
+                AccountAddress::default(),
+            ),
+        )
+    };
+
+    assert!(db.get_block_metadata(0, 10).unwrap().is_empty());
+    let block_metadata: Vec<_> = (1..=5).map(make_metadata).collect();
+    db.save_block_metadata(block_metadata.clone(), 0).unwrap();
+    assert_eq!(
+        db.get_block_metadata(2, 4).unwrap(),
+        block_metadata[1..4].to_vec()
+    );
+
+    // The metadata of the rounds lower than 4 is removed.
+    db.save_block_metadata(vec![make_metadata(7)], 4).unwrap();
+    assert_eq!(
+        db.get_block_metadata(0, 10).unwrap(),
+        vec![
+            block_metadata[3].clone(),
+            block_metadata[4].clone(),
+            make_metadata(7)
+        ]
+    );
+}
@@ -7,14 +7,20 @@ mod schema;
 
 use crate::chained_bft::consensusdb::schema::{
     block::{BlockSchema, SchemaBlock},
+    block_metadata::BlockMetadataSchema,
     quorum_certificate::QCSchema,
     single_entry::{SingleEntryKey, SingleEntrySchema},
 };
-use consensus_types::{block::Block, common::Payload, quorum_cert::QuorumCert};
+use consensus_types::{
+    block::Block,
+    common::{Payload, Round},
+    quorum_cert::QuorumCert,
+};
 use crypto::HashValue;
 use failure::prelude::*;
+use libra_types::block_metadata::BlockMetadata;
 use logger::prelude::*;
-use schema::{BLOCK_CF_NAME, QC_CF_NAME, SINGLE_ENTRY_CF_NAME};
+use schema::{BLOCK_CF_NAME, BLOCK_METADATA_CF_NAME, QC_CF_NAME, SINGLE_ENTRY_CF_NAME};
 use schemadb::{
     ColumnFamilyOptions, ColumnFamilyOptionsMap, ReadOptions, SchemaBatch, DB, DEFAULT_CF_NAME,
 };
@@ -36,6 +42,7 @@ impl ConsensusDB {
                 ColumnFamilyOptions::default(),
             ),
             (BLOCK_CF_NAME, ColumnFamilyOptions::default()),
+            (BLOCK_METADATA_CF_NAME, ColumnFamilyOptions::default()),
             (QC_CF_NAME, ColumnFamilyOptions::default()),
             (SINGLE_ENTRY_CF_NAME, ColumnFamilyOptions::default()),
         ]
@@ -150,6 +157,42 @@ impl ConsensusDB {
         self.commit(batch)
     }
 
+    /// Persist the metadata of committed blocks and delete the metadata of the blocks with rounds
+    /// lower than `min_round`.
+    pub fn save_block_metadata(
+        &self,
+        block_metadata: Vec<(Round, BlockMetadata)>,
+        min_round: Round,
+    ) -> Result<()> {
+        let mut batch = SchemaBatch::new();
+        block_metadata
+            .iter()
+            .map(|(round, metadata)| batch.put::<BlockMetadataSchema>(round, metadata))
+            .collect::<Result<()>>()?;
+        self.commit(batch)?;
+        self.db.range_delete::<BlockMetadataSchema, Round>(&0, &min_round)
+    }
+
+    /// Get the metadata of the committed blocks with rounds in [start_round, end_round], ordered
+    /// by round.
+    pub fn get_block_metadata(
+        &self,
+        start_round: Round,
+        end_round: Round,
+    ) -> Result<Vec<(Round, BlockMetadata)>> {
+        let mut iter = self.db.iter::<BlockMetadataSchema>(ReadOptions::default())?;
+        iter.seek(&start_round)?;
+        let mut block_metadata = vec![];
+        for entry in iter {
+            let (round, metadata) = entry?;
+            if round > end_round {
+                break;
+            }
+            block_metadata.push((round, metadata));
+        }
+        Ok(block_metadata)
+    }
+
     /// Write the whole schema batch including all data necessary to mutate the ledger
     /// state of some transaction by leveraging rocksdb atomicity support.
     fn commit(&self, batch: SchemaBatch) -> Result<()> {
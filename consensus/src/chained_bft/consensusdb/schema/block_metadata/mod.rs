@@ -0,0 +1,55 @@
+// Copyright (c) The Libra Core Contributors
+// SPDX-License-Identifier: Apache-2.0
+
+//! This module defines physical storage schema for the metadata of committed blocks.
+//!
+//! Serialized block metadata identified by the round of the block, the keys are big endian so
+//! that the rows are ordered by round.
+//! ```text
+//! |<--key-->|<----value---->|
+//! |  round  | BlockMetadata |
+//! ```
+
+use super::{ensure_slice_len_eq, BLOCK_METADATA_CF_NAME};
+use byteorder::{BigEndian, ReadBytesExt, WriteBytesExt};
+use consensus_types::common::Round;
+use failure::prelude::*;
+use libra_types::block_metadata::BlockMetadata;
+use schemadb::{
+    define_schema,
+    schema::{KeyCodec, ValueCodec},
+};
+use std::mem::size_of;
+
+define_schema!(
+    BlockMetadataSchema,
+    Round,
+    BlockMetadata,
+    BLOCK_METADATA_CF_NAME
+);
+
+impl KeyCodec<BlockMetadataSchema> for Round {
+    fn encode_key(&self) -> Result<Vec<u8>> {
+        let mut encoded_key = Vec::with_capacity(size_of::<Round>());
+        encoded_key.write_u64::<BigEndian>(*self)?;
+        Ok(encoded_key)
+    }
+
+    fn decode_key(data: &[u8]) -> Result<Self> {
+        ensure_slice_len_eq(data, size_of::<Round>())?;
+        Ok((&data[..]).read_u64::<BigEndian>()?)
+    }
+}
+
+impl ValueCodec<BlockMetadataSchema> for BlockMetadata {
+    fn encode_value(&self) -> Result<Vec<u8>> {
+        Ok(lcs::to_bytes(self)?)
+    }
+
+    fn decode_value(data: &[u8]) -> Result<Self> {
+        Ok(lcs::from_bytes(data)?)
+    }
+}
+
+#[cfg(test)]
+mod test;
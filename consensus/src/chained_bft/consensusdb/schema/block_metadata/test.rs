@@ -0,0 +1,20 @@
+// Copyright (c) The Libra Core Contributors
+// SPDX-License-Identifier: Apache-2.0
+
+use super::*;
+use crypto::HashValue;
+use libra_types::{account_address::AccountAddress, crypto_proxies::ValidatorSigner};
+use schemadb::schema::assert_encode_decode;
+use std::collections::BTreeMap;
+
+#[test]
+fn test_block_metadata_schema() {
+    let signer = ValidatorSigner::random(None);
+    let mut votes = BTreeMap::new();
+    votes.insert(
+        signer.author(),
+        signer.sign_message(HashValue::random()).unwrap(),
+    );
+    let metadata = BlockMetadata::new(HashValue::random(), 1, votes, AccountAddress::random());
+    assert_encode_decode::<BlockMetadataSchema>(&5, &metadata);
+}
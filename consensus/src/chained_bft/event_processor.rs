@@ -5,6 +5,7 @@ use crate::{
     chained_bft::{
         block_storage::{BlockReader, BlockStore, NeedFetchResult, VoteReceptionResult},
         liveness::{
+            leader_reputation::block_metadata,
             pacemaker::{NewRoundEvent, NewRoundReason, Pacemaker},
             proposal_generator::ProposalGenerator,
             proposer_election::ProposerElection,
@@ -30,7 +31,7 @@ use consensus_types::{
     vote_data::VoteData,
     vote_msg::VoteMsg,
 };
-use crypto::HashValue;
+use crypto::{hash::CryptoHash, HashValue};
 use failure::ResultExt;
 use libra_types::crypto_proxies::{LedgerInfoWithSignatures, ValidatorVerifier};
 use logger::prelude::*;
@@ -100,7 +101,7 @@ impl<T: Payload> EventProcessor<T> {
             network.clone(),
             Arc::clone(&state_computer),
         );
-        let author = block_store.signer().author();
+        let author = block_store.author();
         let last_vote_sent = last_vote.map(|v| {
             let round = v.vote_data().proposed().round();
             (v, round)
@@ -136,7 +137,7 @@ impl<T: Payload> EventProcessor<T> {
     /// Replica:
     ///
     /// Do nothing
-    async fn process_new_round_event(&self, new_round_event: NewRoundEvent) {
+    async fn process_new_round_event(&mut self, new_round_event: NewRoundEvent) {
         debug!("Processing {}", new_round_event);
         counters::CURRENT_ROUND.set(new_round_event.round as i64);
         counters::ROUND_TIMEOUT_MS.set(new_round_event.timeout.as_millis() as i64);
@@ -148,6 +149,13 @@ impl<T: Payload> EventProcessor<T> {
                 counters::TIMEOUT_ROUNDS_COUNT.inc();
             }
         };
+        // The proposal is going to extend the highest certified block.
+        let parent_block_id = self.block_store.highest_certified_block().id();
+        self.proposer_election
+            .set_parent_block(new_round_event.round, parent_block_id);
+        let proposer_proof = self
+            .proposer_election
+            .prove_leadership(new_round_event.round);
         if self
             .proposer_election
             .is_valid_proposer(self.author, new_round_event.round)
@@ -155,7 +163,10 @@ impl<T: Payload> EventProcessor<T> {
         {
             return;
         }
-        let proposal_msg = match self.generate_proposal(new_round_event).await {
+        let proposal_msg = match self
+            .generate_proposal(new_round_event, proposer_proof)
+            .await
+        {
             Ok(x) => x,
             Err(e) => {
                 error!("Error while generating proposal: {:?}", e);
@@ -170,6 +181,7 @@ impl<T: Payload> EventProcessor<T> {
     async fn generate_proposal(
         &self,
         new_round_event: NewRoundEvent,
+        proposer_proof: Option<Vec<u8>>,
     ) -> failure::Result<ProposalMsg<T>> {
         // Proposal generator will ensure that at most one proposal is generated per round
         let proposal = self
@@ -180,8 +192,12 @@ impl<T: Payload> EventProcessor<T> {
             )
             .await?;
         debug!("Propose {}", proposal);
+        let proposal_msg = ProposalMsg::new(proposal, self.gen_sync_info());
         // return proposal
-        Ok(ProposalMsg::new(proposal, self.gen_sync_info()))
+        Ok(match proposer_proof {
+            Some(proof) => proposal_msg.with_proposer_proof(proof),
+            None => proposal_msg,
+        })
     }
 
     /// Process a ProposalMsg, pre_process would bring all the dependencies and filter out invalid
@@ -206,6 +222,26 @@ impl<T: Payload> EventProcessor<T> {
         if proposal_msg.round() < current_round {
             return None;
         }
+        if let Err(e) = self
+            .sync_up(proposal_msg.sync_info(), proposal_msg.proposer(), true)
+            .await
+        {
+            warn!(
+                "Dependencies of proposal {} could not be added to the block store: {}",
+                proposal_msg, e
+            );
+            return None;
+        }
+        // The proposer is checked once the block the proposal extends is in the block store, as
+        // the election may depend on the chain.
+        let parent_block_id = proposal_msg.proposal().parent_id();
+        self.proposer_election
+            .set_parent_block(proposal_msg.round(), parent_block_id);
+        self.proposer_election.process_leadership_proof(
+            proposal_msg.proposer(),
+            proposal_msg.round(),
+            proposal_msg.proposer_proof(),
+        );
         if self
             .proposer_election
             .is_valid_proposer(proposal_msg.proposer(), proposal_msg.round())
@@ -218,16 +254,6 @@ impl<T: Payload> EventProcessor<T> {
             );
             return None;
         }
-        if let Err(e) = self
-            .sync_up(proposal_msg.sync_info(), proposal_msg.proposer(), true)
-            .await
-        {
-            warn!(
-                "Dependencies of proposal {} could not be added to the block store: {}",
-                proposal_msg, e
-            );
-            return None;
-        }
 
         // pacemaker may catch up with the SyncInfo, check again
         let current_round = self.pacemaker.current_round();
@@ -378,7 +404,17 @@ impl<T: Payload> EventProcessor<T> {
         };
 
         if !timeout_vote_msg.is_timeout() {
-            timeout_vote_msg.add_round_signature(self.block_store.signer());
+            let round_signature = match self
+                .safety_rules
+                .sign_timeout(timeout_vote_msg.vote_data().proposed().round())
+            {
+                Ok(round_signature) => round_signature,
+                Err(e) => {
+                    error!("Failed to sign the timeout vote: {}", e);
+                    return;
+                }
+            };
+            timeout_vote_msg.add_round_signature_with(round_signature.into());
         }
         self.network.broadcast_vote(timeout_vote_msg).await
     }
@@ -503,6 +539,9 @@ impl<T: Payload> EventProcessor<T> {
                 >= certified_parent_block_round
         );
 
+        // The next round's proposal is going to extend the voted block once certified.
+        self.proposer_election
+            .set_parent_block(proposal_round + 1, proposal_id);
         let recipients = self
             .proposer_election
             .get_valid_proposers(proposal_round + 1);
@@ -630,18 +669,24 @@ impl<T: Payload> EventProcessor<T> {
             .expect("Block proposed_block: no execution state found for inserted block.")
             .executed_state;
 
-        let ledger_info_placeholder = self
+        let vote_data = VoteData::new(
+            BlockInfo::from_block(block, executed_state.state_id, executed_state.version),
+            block.quorum_cert().certified_block().clone(),
+        );
+        let mut ledger_info = self
             .block_store
             .ledger_info_placeholder(vote_info.potential_commit_id());
+        ledger_info.set_consensus_data_hash(vote_data.hash());
+        let signature = self
+            .safety_rules
+            .sign_vote(&vote_data, &ledger_info, block.quorum_cert())
+            .with_context(|e| format!("Fail to sign the vote: {:?}", e))?;
 
-        let vote_msg = VoteMsg::new(
-            VoteData::new(
-                BlockInfo::from_block(block, executed_state.state_id, executed_state.version),
-                block.quorum_cert().certified_block().clone(),
-            ),
+        let vote_msg = VoteMsg::new_with_signature(
+            vote_data,
             self.author,
-            ledger_info_placeholder,
-            self.block_store.signer(),
+            ledger_info,
+            signature.into(),
             self.gen_sync_info(),
         );
 
@@ -663,6 +708,11 @@ impl<T: Payload> EventProcessor<T> {
         if !vote_msg.is_timeout() {
             // Unlike timeout votes regular votes are sent to the leaders of the next round only.
             let next_round = vote_msg.vote_data().proposed().round() + 1;
+            // The next round's proposal is going to extend the voted block once certified.
+            let parent_block_id = vote_msg.vote_data().proposed().id();
+            self.proposer_election
+                .set_parent_block(next_round, parent_block_id);
+            self.proposer_election.prove_leadership(next_round);
             if self
                 .proposer_election
                 .is_valid_proposer(self.author, next_round)
@@ -804,6 +854,15 @@ impl<T: Payload> EventProcessor<T> {
             );
             return;
         }
+        // The proposers and voters of the committed blocks determine the reputation of the
+        // validators in the leader reputation proposer election. NIL blocks have no proposer.
+        let committed_metadata = blocks_to_commit
+            .iter()
+            .filter_map(|b| block_metadata(b.block()).map(|metadata| (b.round(), metadata)))
+            .collect();
+        if let Err(e) = self.storage.save_committed_block_metadata(committed_metadata) {
+            error!("Failed to persist committed block metadata: {:?}", e);
+        }
         // At this moment the new state is persisted and we can notify the clients.
         // Multiple blocks might be committed at once: notify about all the transactions in the
         // path from the old root to the new root.
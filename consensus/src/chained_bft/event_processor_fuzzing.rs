@@ -24,7 +24,7 @@ use network::{
 };
 use prost::Message as _;
 use prost_ext::MessageExt;
-use safety_rules::SafetyRules;
+use safety_rules::{InMemoryStorage, SafetyRules};
 use std::convert::TryFrom;
 use std::sync::Arc;
 use tokio::runtime::Runtime;
@@ -63,7 +63,7 @@ fn build_empty_store(
     Arc::new(block_on(BlockStore::new(
         storage,
         initial_data,
-        signer,
+        Arc::new(signer),
         Arc::new(EmptyStateComputer),
         true,
         10, // max pruned blocks in mem
@@ -88,14 +88,18 @@ fn create_node_for_fuzzing() -> EventProcessor<TestPayload> {
     let validator = ValidatorVerifier::new_single(signer.author(), signer.public_key());
 
     // EpochManager
-    let epoch_mgr = Arc::new(EpochManager::new(0, validator));
+    let epoch_mgr = Arc::new(EpochManager::new(0, validator.clone()));
 
     // TODO: EmptyStorage
     let (storage, initial_data) = MockStorage::<TestPayload>::start_for_testing();
     let consensus_state = initial_data.state();
 
     // TODO: remove
-    let safety_rules = SafetyRules::new(consensus_state);
+    let safety_rules = SafetyRules::new(Box::new(InMemoryStorage::new(
+        signer.clone(),
+        validator,
+        consensus_state,
+    )));
 
     // TODO: mock channels
     let (network_reqs_tx, _network_reqs_rx) = channel::new_test(8);
@@ -50,7 +50,7 @@ use network::{
     proto::{BlockRetrievalStatus, ConsensusMsg_oneof},
     validator_network::{ConsensusNetworkEvents, ConsensusNetworkSender},
 };
-use safety_rules::{ConsensusState, SafetyRules};
+use safety_rules::{ConsensusState, InMemoryStorage, SafetyRules};
 use std::convert::TryFrom;
 use std::{collections::HashMap, sync::Arc, time::Duration};
 use tokio::runtime::TaskExecutor;
@@ -76,7 +76,7 @@ impl NodeSetup {
         Arc::new(block_on(BlockStore::new(
             storage,
             initial_data,
-            signer,
+            Arc::new(signer),
             state_computer,
             true,
             10, // max pruned blocks in mem
@@ -165,7 +165,11 @@ impl NodeSetup {
             1,
             true,
         );
-        let safety_rules = SafetyRules::new(consensus_state);
+        let safety_rules = SafetyRules::new(Box::new(InMemoryStorage::new(
+            signer.clone(),
+            epoch_mgr.validators().as_ref().clone(),
+            consensus_state,
+        )));
 
         let pacemaker = Self::create_pacemaker(time_service.clone());
 
@@ -221,7 +225,7 @@ fn basic_new_rank_event_test() {
     let nodes = NodeSetup::create_nodes(&mut playground, runtime.executor(), 2);
     let node = &nodes[0];
     let genesis = node.block_store.root();
-    let mut inserter = TreeInserter::new(node.block_store.clone());
+    let mut inserter = TreeInserter::new_with_signer(node.signer.clone(), node.block_store.clone());
     let a1 = inserter.insert_block_with_qc(QuorumCert::certificate_for_genesis(), &genesis, 1);
     block_on(async move {
         let new_round = 1;
@@ -271,14 +275,14 @@ fn basic_new_rank_event_test() {
                 BlockInfo::from_block(a1.block(), executed_state.state_id, executed_state.version),
                 a1.quorum_cert().certified_block().clone(),
             ),
-            node.block_store.signer().author(),
+            node.signer.author(),
             placeholder_ledger_info(),
-            node.block_store.signer(),
+            &node.signer,
             test_utils::placeholder_sync_info(),
         );
         let validator_verifier = Arc::new(ValidatorVerifier::new_single(
-            node.block_store.signer().author(),
-            node.block_store.signer().public_key(),
+            node.signer.author(),
+            node.signer.public_key(),
         ));
         node.block_store
             .insert_vote_and_qc(vote_msg, validator_verifier.as_ref());
@@ -336,7 +340,7 @@ fn process_successful_proposal_test() {
             1,
             1,
             genesis_qc.clone(),
-            node.block_store.signer(),
+            &node.signer,
         );
         let proposal_id = proposal.id();
         node.event_processor.process_proposed_block(proposal).await;
@@ -387,7 +391,7 @@ fn process_old_proposal_test() {
         1,
         1,
         genesis_qc.clone(),
-        node.block_store.signer(),
+        &node.signer,
     );
     let new_block_id = new_block.id();
     let old_block = Block::make_block(
@@ -396,7 +400,7 @@ fn process_old_proposal_test() {
         1,
         2,
         genesis_qc.clone(),
-        node.block_store.signer(),
+        &node.signer,
     );
     let old_block_id = old_block.id();
     block_on(async move {
@@ -446,7 +450,7 @@ fn process_round_mismatch_test() {
         1,
         1,
         genesis_qc.clone(),
-        node.block_store.signer(),
+        &node.signer,
     );
     let block_skip_round = Block::make_block(
         genesis.block(),
@@ -454,7 +458,7 @@ fn process_round_mismatch_test() {
         2,
         2,
         genesis_qc.clone(),
-        node.block_store.signer(),
+        &node.signer,
     );
     block_on(async move {
         let bad_proposal = ProposalMsg::<TestPayload>::new(
@@ -493,7 +497,8 @@ fn process_vote_timeout_msg_test() {
     let genesis = non_proposer.block_store.root();
     let block_0 = non_proposer
         .block_store
-        .create_block(genesis.block(), vec![1], 1, 1);
+        .create_block(genesis.block(), vec![1], 1, 1)
+        .unwrap();
     let block_0_id = block_0.id();
     block_on(
         non_proposer
@@ -588,7 +593,7 @@ fn process_proposer_mismatch_test() {
         1,
         1,
         genesis_qc.clone(),
-        node.block_store.signer(),
+        &node.signer,
     );
     let block_incorrect_proposer = Block::make_block(
         genesis.block(),
@@ -596,7 +601,7 @@ fn process_proposer_mismatch_test() {
         1,
         1,
         genesis_qc.clone(),
-        incorrect_proposer.block_store.signer(),
+        &incorrect_proposer.signer,
     );
     block_on(async move {
         let bad_proposal = ProposalMsg::<TestPayload>::new(
@@ -641,7 +646,7 @@ fn process_timeout_certificate_test() {
         1,
         1,
         genesis_qc.clone(),
-        node.block_store.signer(),
+        &node.signer,
     );
     let block_skip_round = Block::make_block(
         genesis.block(),
@@ -649,7 +654,7 @@ fn process_timeout_certificate_test() {
         2,
         2,
         genesis_qc.clone(),
-        node.block_store.signer(),
+        &node.signer,
     );
     let tc = TimeoutCertificate::new(1, HashMap::new());
 
@@ -687,7 +692,7 @@ fn process_votes_basic_test() {
         .pop()
         .unwrap();
     let genesis = node.block_store.root();
-    let mut inserter = TreeInserter::new(node.block_store.clone());
+    let mut inserter = TreeInserter::new_with_signer(node.signer.clone(), node.block_store.clone());
     let a1 = inserter.insert_block_with_qc(QuorumCert::certificate_for_genesis(), &genesis, 1);
     let executed_state = &node
         .block_store
@@ -709,9 +714,9 @@ fn process_votes_basic_test() {
 
     let vote_msg = VoteMsg::new(
         vote_data,
-        node.block_store.signer().author(),
+        node.signer.author(),
         placeholder_ledger_info(),
-        node.block_store.signer(),
+        &node.signer,
         test_utils::placeholder_sync_info(),
     );
 
@@ -746,7 +751,7 @@ fn process_block_retrieval() {
         1,
         1,
         genesis_qc.clone(),
-        node.block_store.signer(),
+        &node.signer,
     );
     let block_id = block.id();
 
@@ -822,7 +827,7 @@ fn basic_restart_test() {
     let mut node = NodeSetup::create_nodes(&mut playground, runtime.executor(), 1)
         .pop()
         .unwrap();
-    let mut inserter = TreeInserter::new(node.block_store.clone());
+    let mut inserter = TreeInserter::new_with_signer(node.signer.clone(), node.block_store.clone());
     let node_mut = &mut node;
 
     let genesis = node_mut.block_store.root();
@@ -0,0 +1,239 @@
+// Copyright (c) The Libra Core Contributors
+// SPDX-License-Identifier: Apache-2.0
+
+use crate::chained_bft::{
+    block_storage::BlockReader,
+    liveness::{multi_proposer_election::hash, proposer_election::ProposerElection},
+    persistent_storage::PersistentLivenessStorage,
+};
+use consensus_types::{
+    block::Block,
+    common::{Author, Payload, Round},
+};
+use crypto::HashValue;
+use failure::prelude::*;
+use libra_types::block_metadata::BlockMetadata;
+use logger::prelude::*;
+use std::{
+    collections::{BTreeMap, HashSet},
+    sync::Arc,
+};
+
+// The parents of the proposals of the rounds that are at most this number of rounds lower than
+// the latest one recorded are kept.
+const PARENT_ROUNDS_KEPT: Round = 10;
+
+/// Returns the metadata the reputation of the proposers is derived from for a block: its
+/// proposer and the voters of its quorum certificate. NIL blocks have no proposer, hence no
+/// metadata.
+pub fn block_metadata<T>(block: &Block<T>) -> Option<BlockMetadata> {
+    block.author().map(|author| {
+        BlockMetadata::new(
+            block.id(),
+            block.timestamp_usecs(),
+            block.quorum_cert().ledger_info().signatures().clone(),
+            author,
+        )
+    })
+}
+
+/// Interface to query the metadata of the blocks the reputation of the proposers is derived from.
+pub trait MetadataBackend: Send + Sync {
+    /// Return the metadata of the latest ancestors of the block `parent_block_id` (included) used
+    /// to elect the proposers of its children, ordered by round.
+    fn get_block_metadata(&self, parent_block_id: HashValue) -> Result<Vec<BlockMetadata>>;
+}
+
+/// Reads the metadata of the latest `window_size` ancestors of a block: the ones that are not
+/// committed yet come from the block tree, the committed ones from the consensus storage. All the
+/// honest validators electing the proposer of a child of the same block read the same history,
+/// however far they are in committing it.
+pub struct ConsensusDBBackend<T> {
+    block_reader: Arc<dyn BlockReader<Payload = T>>,
+    storage: Box<dyn PersistentLivenessStorage>,
+    window_size: usize,
+}
+
+impl<T> ConsensusDBBackend<T> {
+    pub fn new(
+        block_reader: Arc<dyn BlockReader<Payload = T>>,
+        storage: Box<dyn PersistentLivenessStorage>,
+        window_size: usize,
+    ) -> Self {
+        Self {
+            block_reader,
+            storage,
+            window_size,
+        }
+    }
+}
+
+impl<T: Payload> MetadataBackend for ConsensusDBBackend<T> {
+    fn get_block_metadata(&self, parent_block_id: HashValue) -> Result<Vec<BlockMetadata>> {
+        let root_round = self.block_reader.root().round();
+        let path = self
+            .block_reader
+            .path_from_root(parent_block_id)
+            .ok_or_else(|| format_err!("Block {} does not extend the root", parent_block_id))?;
+        // The path goes from the parent block down to the root.
+        let mut uncommitted: Vec<_> = path
+            .iter()
+            .filter_map(|block| block_metadata(block.block()))
+            .take(self.window_size)
+            .collect();
+        uncommitted.reverse();
+        let mut history = self
+            .storage
+            .get_committed_block_metadata(root_round, self.window_size - uncommitted.len())?;
+        history.extend(uncommitted);
+        Ok(history)
+    }
+}
+
+/// Interface to weigh the candidates of the election given the history of committed blocks.
+pub trait ReputationHeuristic: Send + Sync {
+    /// Return the weights of the `candidates` in the same order.
+    fn get_weights(&self, candidates: &[Author], history: &[BlockMetadata]) -> Vec<u64>;
+}
+
+/// Weighs the candidates that proposed or voted in any of the committed blocks of the history
+/// with `active_weight` and the others with `inactive_weight`.
+pub struct ActiveInactiveHeuristic {
+    active_weight: u64,
+    inactive_weight: u64,
+}
+
+impl ActiveInactiveHeuristic {
+    pub fn new(active_weight: u64, inactive_weight: u64) -> Self {
+        Self {
+            active_weight,
+            inactive_weight,
+        }
+    }
+}
+
+impl ReputationHeuristic for ActiveInactiveHeuristic {
+    fn get_weights(&self, candidates: &[Author], history: &[BlockMetadata]) -> Vec<u64> {
+        let active: HashSet<Author> = history
+            .iter()
+            .flat_map(|metadata| {
+                let mut participants = metadata.voters();
+                participants.push(metadata.proposer());
+                participants
+            })
+            .collect();
+        candidates
+            .iter()
+            .map(|candidate| {
+                if active.contains(candidate) {
+                    self.active_weight
+                } else {
+                    self.inactive_weight
+                }
+            })
+            .collect()
+    }
+}
+
+/// The LeaderReputation maps a round to a single author by a weighted choice among the
+/// proposers: the choice is seeded by hash(round) and the weights reflect the reputation of the
+/// proposers in the latest ancestors of the block the proposals of the round extend. Every honest
+/// replica derives the same weights from the same chain, hence elects the same proposer, while
+/// validators that neither proposed nor voted recently (e.g., crashed ones) are rarely elected.
+pub struct LeaderReputation {
+    // Ordering of proposers to choose from (all honest replicas must agree on this)
+    proposers: Vec<Author>,
+    backend: Box<dyn MetadataBackend>,
+    heuristic: Box<dyn ReputationHeuristic>,
+    // The block the proposals extend, for the latest rounds
+    parent_blocks: BTreeMap<Round, HashValue>,
+}
+
+impl LeaderReputation {
+    pub fn new(
+        proposers: Vec<Author>,
+        backend: Box<dyn MetadataBackend>,
+        heuristic: Box<dyn ReputationHeuristic>,
+    ) -> Self {
+        assert!(!proposers.is_empty());
+        Self {
+            proposers,
+            backend,
+            heuristic,
+            parent_blocks: BTreeMap::new(),
+        }
+    }
+
+    /// Returns the proposer of `round` in a chain extending the block `parent_block_id`, or None
+    /// if the history of the block cannot be read.
+    fn get_proposer(&self, round: Round, parent_block_id: HashValue) -> Option<Author> {
+        let history = match self.backend.get_block_metadata(parent_block_id) {
+            Ok(history) => history,
+            Err(e) => {
+                error!(
+                    "Failed to read the history of block {} for the proposer of round {}: {:?}",
+                    parent_block_id, round, e
+                );
+                return None;
+            }
+        };
+        let weights = self.heuristic.get_weights(&self.proposers, &history);
+        let total_weight: u64 = weights.iter().sum();
+        if total_weight == 0 {
+            return Some(self.proposers[(hash(round) % self.proposers.len() as u64) as usize]);
+        }
+        let mut chosen_weight = hash(round) % total_weight;
+        for (proposer, weight) in self.proposers.iter().zip(weights) {
+            if chosen_weight < weight {
+                return Some(*proposer);
+            }
+            chosen_weight -= weight;
+        }
+        unreachable!("The chosen weight is lower than the total weight")
+    }
+
+    /// Returns the proposer of `round` in the chain extending the recorded parent block.
+    fn get_round_proposer(&self, round: Round) -> Option<Author> {
+        match self.parent_blocks.get(&round) {
+            Some(parent_block_id) => self.get_proposer(round, *parent_block_id),
+            None => {
+                warn!("The block extended in round {} is unknown", round);
+                None
+            }
+        }
+    }
+}
+
+impl<T: Payload> ProposerElection<T> for LeaderReputation {
+    fn is_valid_proposer(&self, author: Author, round: Round) -> Option<Author> {
+        if self.get_round_proposer(round) == Some(author) {
+            Some(author)
+        } else {
+            None
+        }
+    }
+
+    fn get_valid_proposers(&self, round: Round) -> Vec<Author> {
+        self.get_round_proposer(round).into_iter().collect()
+    }
+
+    fn process_proposal(&mut self, proposal: Block<T>) -> Option<Block<T>> {
+        // A single proposer is elected per round, there are no backup proposals to keep.
+        let round_author = self.get_proposer(proposal.round(), proposal.parent_id());
+        if round_author.is_none() || round_author != proposal.author() {
+            None
+        } else {
+            Some(proposal)
+        }
+    }
+
+    fn take_backup_proposal(&mut self, _round: Round) -> Option<Block<T>> {
+        None
+    }
+
+    fn set_parent_block(&mut self, round: Round, parent_block_id: HashValue) {
+        self.parent_blocks.insert(round, parent_block_id);
+        let lowest_kept_round = round.saturating_sub(PARENT_ROUNDS_KEPT);
+        self.parent_blocks = self.parent_blocks.split_off(&lowest_kept_round);
+    }
+}
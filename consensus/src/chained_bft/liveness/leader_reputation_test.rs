@@ -0,0 +1,195 @@
+// Copyright (c) The Libra Core Contributors
+// SPDX-License-Identifier: Apache-2.0
+
+use crate::chained_bft::{
+    liveness::{
+        leader_reputation::{
+            block_metadata, ActiveInactiveHeuristic, ConsensusDBBackend, LeaderReputation,
+            MetadataBackend, ReputationHeuristic,
+        },
+        proposer_election::ProposerElection,
+    },
+    persistent_storage::{PersistentLivenessStorage, PersistentStorage},
+    test_utils::{build_empty_tree, MockStorage, TreeInserter},
+};
+use consensus_types::{
+    block::{Block, ExecutedBlock},
+    common::Author,
+    quorum_cert::QuorumCert,
+};
+use crypto::HashValue;
+use failure::prelude::*;
+use libra_types::{block_metadata::BlockMetadata, crypto_proxies::ValidatorSigner};
+
+struct MockHistory {
+    history: Vec<BlockMetadata>,
+}
+
+impl MetadataBackend for MockHistory {
+    fn get_block_metadata(&self, parent_block_id: HashValue) -> Result<Vec<BlockMetadata>> {
+        ensure!(
+            parent_block_id != HashValue::zero(),
+            "Unknown block {}",
+            parent_block_id
+        );
+        Ok(self.history.clone())
+    }
+}
+
+fn make_metadata(proposer: &ValidatorSigner, voters: &[&ValidatorSigner]) -> BlockMetadata {
+    let votes = voters
+        .iter()
+        .map(|voter| {
+            (
+                voter.author(),
+                voter.sign_message(HashValue::zero()).unwrap(),
+            )
+        })
+        .collect();
+    BlockMetadata::new(HashValue::random(), 0, votes, proposer.author())
+}
+
+fn tree_metadata(blocks: &[&ExecutedBlock<Vec<usize>>]) -> Vec<BlockMetadata> {
+    blocks
+        .iter()
+        .map(|block| block_metadata(block.block()).unwrap())
+        .collect()
+}
+
+#[test]
+fn test_active_inactive_heuristic() {
+    let signers: Vec<_> = (0..4).map(|i| ValidatorSigner::random([i; 32])).collect();
+    let authors: Vec<Author> = signers.iter().map(ValidatorSigner::author).collect();
+    let heuristic = ActiveInactiveHeuristic::new(100, 1);
+    let history = vec![
+        make_metadata(&signers[0], &[&signers[1]]),
+        make_metadata(&signers[1], &[&signers[0]]),
+        make_metadata(&signers[0], &[&signers[2]]),
+    ];
+    assert_eq!(
+        heuristic.get_weights(&authors, &history),
+        vec![100, 100, 100, 1]
+    );
+    assert_eq!(heuristic.get_weights(&authors, &[]), vec![1, 1, 1, 1]);
+}
+
+#[test]
+fn test_leader_reputation_skips_inactive() {
+    let signers: Vec<_> = (0..4).map(|i| ValidatorSigner::random([i; 32])).collect();
+    let authors: Vec<Author> = signers.iter().map(ValidatorSigner::author).collect();
+    let history = vec![
+        make_metadata(&signers[0], &[&signers[1], &signers[2]]),
+        make_metadata(&signers[1], &[&signers[0], &signers[2]]),
+    ];
+    let mut pe = LeaderReputation::new(
+        authors.clone(),
+        Box::new(MockHistory { history }),
+        Box::new(ActiveInactiveHeuristic::new(1, 0)),
+    );
+    let mut another_pe = LeaderReputation::new(
+        authors.clone(),
+        Box::new(MockHistory {
+            history: vec![make_metadata(&signers[2], &[&signers[0], &signers[1]])],
+        }),
+        Box::new(ActiveInactiveHeuristic::new(1, 0)),
+    );
+
+    // The crashed validator 3 is never elected and all the replicas with the same history elect
+    // the same proposer.
+    for round in 1..100 {
+        let parent_block_id = HashValue::random();
+        ProposerElection::<u32>::set_parent_block(&mut pe, round, parent_block_id);
+        ProposerElection::<u32>::set_parent_block(&mut another_pe, round, parent_block_id);
+        let proposers = ProposerElection::<u32>::get_valid_proposers(&pe, round);
+        assert_eq!(proposers.len(), 1);
+        assert_ne!(proposers[0], authors[3]);
+        assert_eq!(
+            ProposerElection::<u32>::get_valid_proposers(&another_pe, round),
+            proposers
+        );
+        assert_eq!(
+            ProposerElection::<u32>::is_valid_proposer(&pe, authors[3], round),
+            None
+        );
+    }
+}
+
+#[test]
+fn test_leader_reputation_process_proposal() {
+    let signers: Vec<_> = (0..2).map(|i| ValidatorSigner::random([i; 32])).collect();
+    let authors: Vec<Author> = signers.iter().map(ValidatorSigner::author).collect();
+    let history = vec![make_metadata(&signers[1], &[&signers[1]])];
+    let mut pe: Box<dyn ProposerElection<u32>> = Box::new(LeaderReputation::new(
+        authors.clone(),
+        Box::new(MockHistory { history }),
+        Box::new(ActiveInactiveHeuristic::new(1, 0)),
+    ));
+
+    // Only the active validator 1 is elected.
+    let genesis_block = Block::make_genesis_block();
+    let quorum_cert = QuorumCert::certificate_for_genesis();
+    let good_proposal = Block::make_block(
+        &genesis_block,
+        1,
+        1,
+        1,
+        quorum_cert.clone(),
+        &signers[1],
+    );
+    let bad_proposal = Block::make_block(&genesis_block, 2, 1, 2, quorum_cert, &signers[0]);
+    assert_eq!(
+        pe.process_proposal(good_proposal.clone()),
+        Some(good_proposal)
+    );
+    assert_eq!(pe.process_proposal(bad_proposal), None);
+    assert_eq!(pe.take_backup_proposal(1), None);
+
+    // The proposers of a round are unknown until the block its proposals extend is known.
+    assert!(pe.get_valid_proposers(1).is_empty());
+    pe.set_parent_block(1, genesis_block.id());
+    assert_eq!(pe.get_valid_proposers(1), vec![authors[1]]);
+    // Nobody is elected when the history cannot be read.
+    pe.set_parent_block(2, HashValue::zero());
+    assert!(pe.get_valid_proposers(2).is_empty());
+    assert_eq!(pe.is_valid_proposer(authors[1], 2), None);
+}
+
+#[test]
+fn test_consensus_db_backend() {
+    let signers: Vec<_> = (0..3).map(|i| ValidatorSigner::random([i; 32])).collect();
+    let (storage, _) = MockStorage::<u32>::start_for_testing();
+    // The committed history up to the root of the block tree (genesis).
+    let committed: Vec<_> = vec![make_metadata(&signers[1], &[&signers[0]])];
+    storage
+        .save_committed_block_metadata(vec![(0, committed[0].clone())])
+        .unwrap();
+
+    // build a tree of the following form
+    //                 ↙ b2
+    // genesis <- a1 <- a2 <- a3
+    let block_tree = build_empty_tree();
+    let mut inserter = TreeInserter::new(block_tree.clone());
+    let genesis = block_tree.root();
+    let a1 = inserter.insert_block_with_qc(QuorumCert::certificate_for_genesis(), &genesis, 1);
+    let a2 = inserter.insert_block(&a1, 2);
+    let a3 = inserter.insert_block(&a2, 3);
+    let b2 = inserter.insert_block(&a1, 4);
+
+    let backend = ConsensusDBBackend::new(block_tree, storage.persistent_liveness_storage(), 3);
+    // The latest 3 ancestors of a block, whether committed or not.
+    assert_eq!(
+        backend.get_block_metadata(a3.id()).unwrap(),
+        tree_metadata(&[&a1, &a2, &a3])
+    );
+    // The votes of the quorum certificates are recorded.
+    assert_eq!(
+        tree_metadata(&[&a2])[0].voters(),
+        vec![inserter.signer().author()]
+    );
+    let mut expected = committed.clone();
+    expected.extend(tree_metadata(&[&a1, &b2]));
+    assert_eq!(backend.get_block_metadata(b2.id()).unwrap(), expected);
+    assert_eq!(backend.get_block_metadata(genesis.id()).unwrap(), committed);
+    // Blocks out of the tree have no history.
+    assert!(backend.get_block_metadata(HashValue::random()).is_err());
+}
@@ -1,15 +1,21 @@
 // Copyright (c) The Libra Core Contributors
 // SPDX-License-Identifier: Apache-2.0
 
+pub(crate) mod leader_reputation;
 pub(crate) mod multi_proposer_election;
 pub(crate) mod pacemaker;
 pub(crate) mod proposal_generator;
 pub(crate) mod proposer_election;
 pub(crate) mod rotating_proposer_election;
+pub(crate) mod vrf_proposer_election;
 
+#[cfg(test)]
+mod leader_reputation_test;
 #[cfg(test)]
 mod multi_proposer_test;
 #[cfg(test)]
 mod pacemaker_test;
 #[cfg(test)]
 mod rotating_proposer_test;
+#[cfg(test)]
+mod vrf_proposer_test;
@@ -210,11 +210,11 @@ impl<T: Payload> ProposalGenerator<T> {
                 .with_context(|e| format!("Fail to retrieve txn: {}", e))?
         };
 
-        Ok(self.block_store.create_block(
+        self.block_store.create_block(
             hqc_block.block(),
             txns,
             round,
             block_timestamp.as_micros() as u64,
-        ))
+        )
     }
 }
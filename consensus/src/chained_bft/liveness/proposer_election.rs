@@ -5,6 +5,7 @@ use consensus_types::{
     block::Block,
     common::{Author, Round},
 };
+use crypto::HashValue;
 
 /// ProposerElection incorporates the logic of choosing a leader among multiple candidates.
 /// We are open to a possibility for having multiple proposers per round, the ultimate choice
@@ -33,4 +34,24 @@ pub trait ProposerElection<T> {
     /// Note that once the backup proposal is taken and no other proposals are submitted, the
     /// following take requests are going to return None.
     fn take_backup_proposal(&mut self, round: Round) -> Option<Block<T>>;
+
+    /// Notifies the election that the proposals of `round` extend the block `parent_block_id`,
+    /// before the proposers of `round` are queried. Elections depending on the chain (e.g., on
+    /// the reputation of the proposers in its history) elect the proposers of `round` from it;
+    /// the others ignore it (the default).
+    fn set_parent_block(&mut self, _round: Round, _parent_block_id: HashValue) {}
+
+    /// Proves that this validator is a valid proposer for `round`, for the election protocols the
+    /// other validators cannot compute on their own. The returned proof is to be carried in the
+    /// proposal message; afterwards, `is_valid_proposer` reflects whether this validator is a
+    /// valid proposer for `round`.
+    /// Protocols known to all the validators (the default) need no proof and return None.
+    fn prove_leadership(&mut self, _round: Round) -> Option<Vec<u8>> {
+        None
+    }
+
+    /// Verifies and records the proof carried by a proposal message that `author` is a valid
+    /// proposer for `round`, before the author is checked by `is_valid_proposer`. Protocols that
+    /// need no proof ignore it (the default).
+    fn process_leadership_proof(&mut self, _author: Author, _round: Round, _proof: Option<&[u8]>) {}
 }
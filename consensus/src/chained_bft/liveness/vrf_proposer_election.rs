@@ -0,0 +1,243 @@
+// Copyright (c) The Libra Core Contributors
+// SPDX-License-Identifier: Apache-2.0
+
+use crate::chained_bft::liveness::proposer_election::ProposerElection;
+use consensus_types::{
+    block::Block,
+    common::{Author, Payload, Round},
+};
+use crypto::vrf::ecvrf::{Output, Proof, VRFPrivateKey, VRFPublicKey};
+use logger::prelude::*;
+use std::{
+    collections::{BTreeMap, HashMap},
+    convert::TryFrom,
+};
+
+// The elected proposers of the rounds that are at most this number of rounds lower than the
+// latest one recorded are kept.
+const ELECTED_ROUNDS_KEPT: Round = 10;
+
+/// The rank of an elected proposer, the lower the better: the value of its VRF output, ties being
+/// broken by author so that all the validators rank the same proposals the same way.
+type Rank = (u64, Author);
+
+/// The VrfProposer elects the proposers of a round with a verifiable random function (VRF): a
+/// validator is a valid proposer for a round if the output of its VRF over the epoch and the round
+/// is below a threshold, such that `expected_proposers_per_round` validators are elected on
+/// average. The input does not depend on the chain, so that proposers cannot grind it (e.g., by
+/// trying different blocks or timestamps) to get elected.
+///
+/// Unlike with the other elections, the proposers of a round cannot be predicted: a validator
+/// only knows whether it is elected itself, while the others learn about it from the VRF proof
+/// carried in its proposal. Hence, attackers cannot target the next proposers in advance, and the
+/// votes are sent to all the validators.
+///
+/// Several proposers may be elected for the same round (or none, in which case the round times
+/// out). The proposals are ranked by their VRF output: a proposal is processed right away if it
+/// is the best ranked one received so far in its round, the best ranked of the others is kept as
+/// the backup proposal.
+///
+/// There is no waiting for better ranked proposals, which affects liveness: a validator votes for
+/// the first proposal it processes in a round, and cannot vote again for a better ranked one
+/// received later. When several proposers are elected, the validators receiving their proposals
+/// in different orders may split their votes, in which case no QC is formed and the round times
+/// out. Hence `expected_proposers_per_round` trades the rounds without any proposer for the rounds
+/// with competing proposals, which only succeed if a quorum receives the same proposal first.
+pub struct VrfProposer<T> {
+    // The validator running the election
+    author: Author,
+    // The epoch the proposers are elected in
+    epoch: u64,
+    // The VRF key of the validator to prove its own leadership, if it proposes at all
+    private_key: Option<VRFPrivateKey>,
+    // The latest leadership proof of the validator, if elected, by round: it is requested again
+    // for each vote received
+    own_proof: Option<(Round, Option<Vec<u8>>)>,
+    // The VRF keys of the proposers (all honest replicas must agree on this)
+    public_keys: HashMap<Author, VRFPublicKey>,
+    // A proposer is elected if the value of its VRF output is lower than the threshold
+    threshold: u64,
+    // The ranks of the elected proposers whose proofs were verified, for the latest rounds
+    elected_proposers: BTreeMap<Round, HashMap<Author, Rank>>,
+    // The round and rank of the best proposal processed in the round of the latest proposal
+    best_processed_proposal: Option<(Round, Rank)>,
+    // Keeps the best ranked backup proposal of the highest round (see `MultiProposer`).
+    backup_proposal_round: Round,
+    backup_proposal: Option<(Rank, Block<T>)>,
+}
+
+impl<T> VrfProposer<T> {
+    pub fn new(
+        author: Author,
+        epoch: u64,
+        private_key: Option<VRFPrivateKey>,
+        public_keys: HashMap<Author, VRFPublicKey>,
+        expected_proposers_per_round: usize,
+    ) -> Self {
+        assert!(!public_keys.is_empty());
+        assert!(expected_proposers_per_round > 0);
+        let num_proposers = public_keys.len();
+        let threshold = if expected_proposers_per_round >= num_proposers {
+            std::u64::MAX
+        } else {
+            std::u64::MAX / num_proposers as u64 * expected_proposers_per_round as u64
+        };
+        Self {
+            author,
+            epoch,
+            private_key,
+            own_proof: None,
+            public_keys,
+            threshold,
+            elected_proposers: BTreeMap::new(),
+            best_processed_proposal: None,
+            backup_proposal_round: 0,
+            backup_proposal: None,
+        }
+    }
+
+    /// The VRF input electing the proposers of `round`.
+    fn vrf_input(&self, round: Round) -> Vec<u8> {
+        let mut input = self.epoch.to_le_bytes().to_vec();
+        input.extend_from_slice(&round.to_le_bytes());
+        input
+    }
+
+    /// Returns the rank of the author of a verified VRF proof if it is elected, None otherwise.
+    fn get_rank(&self, author: Author, proof: &Proof) -> Option<Rank> {
+        let output = Output::from(proof).to_bytes();
+        let mut value = [0u8; 8];
+        value.copy_from_slice(&output[..8]);
+        let value = u64::from_be_bytes(value);
+        if value < self.threshold {
+            Some((value, author))
+        } else {
+            None
+        }
+    }
+
+    fn verify_proof(&self, author: Author, round: Round, proof: &[u8]) -> Option<Rank> {
+        let public_key = self.public_keys.get(&author)?;
+        let proof = match Proof::try_from(proof) {
+            Ok(proof) => proof,
+            Err(e) => {
+                warn!("Malformed leadership proof from {}: {:?}", author, e);
+                return None;
+            }
+        };
+        if let Err(e) = public_key.verify(&proof, &self.vrf_input(round)) {
+            warn!("Invalid leadership proof from {}: {}", author, e);
+            return None;
+        }
+        self.get_rank(author, &proof)
+    }
+
+    fn record_rank(&mut self, author: Author, round: Round, rank: Option<Rank>) {
+        match rank {
+            Some(rank) => {
+                self.elected_proposers
+                    .entry(round)
+                    .or_insert_with(HashMap::new)
+                    .insert(author, rank);
+            }
+            None => {
+                if let Some(proposers) = self.elected_proposers.get_mut(&round) {
+                    proposers.remove(&author);
+                }
+            }
+        }
+        let lowest_kept_round = round.saturating_sub(ELECTED_ROUNDS_KEPT);
+        self.elected_proposers = self.elected_proposers.split_off(&lowest_kept_round);
+    }
+
+    fn elected_rank(&self, author: Author, round: Round) -> Option<Rank> {
+        self.elected_proposers.get(&round)?.get(&author).copied()
+    }
+}
+
+impl<T: Payload> ProposerElection<T> for VrfProposer<T> {
+    fn is_valid_proposer(&self, author: Author, round: Round) -> Option<Author> {
+        self.elected_rank(author, round).map(|_| author)
+    }
+
+    fn get_valid_proposers(&self, _round: Round) -> Vec<Author> {
+        // Any validator might be elected: the proposers of a round are unknown in advance.
+        let mut proposers: Vec<Author> = self.public_keys.keys().cloned().collect();
+        proposers.sort();
+        proposers
+    }
+
+    fn process_proposal(&mut self, proposal: Block<T>) -> Option<Block<T>> {
+        let author = proposal.author()?;
+        let round = proposal.round();
+        let rank = match self.elected_rank(author, round) {
+            Some(rank) => rank,
+            None => {
+                warn!(
+                    "Proposal {} is not from an elected proposer for round {}, ignore.",
+                    proposal, round
+                );
+                return None;
+            }
+        };
+        let is_best = match self.best_processed_proposal {
+            Some((best_round, best_rank)) if best_round == round => rank < best_rank,
+            _ => true,
+        };
+        if is_best {
+            debug!(
+                "Best ranked proposal {} so far: going to process it right now.",
+                proposal
+            );
+            self.best_processed_proposal = Some((round, rank));
+            return Some(proposal);
+        }
+        debug!(
+            "Lower ranked proposal {}: will process it if nothing better available.",
+            proposal
+        );
+        if round > self.backup_proposal_round {
+            self.backup_proposal = Some((rank, proposal));
+            self.backup_proposal_round = round;
+        } else if round == self.backup_proposal_round {
+            let is_best_backup = self
+                .backup_proposal
+                .as_ref()
+                .map_or(true, |(backup_rank, _)| rank < *backup_rank);
+            if is_best_backup {
+                self.backup_proposal = Some((rank, proposal));
+            }
+        }
+        None
+    }
+
+    fn take_backup_proposal(&mut self, round: Round) -> Option<Block<T>> {
+        if self.backup_proposal_round != round {
+            return None;
+        }
+        if let Some((_, block)) = self.backup_proposal.take() {
+            return Some(block);
+        }
+
+        None
+    }
+
+    fn prove_leadership(&mut self, round: Round) -> Option<Vec<u8>> {
+        if let Some((proof_round, proof)) = &self.own_proof {
+            if *proof_round == round {
+                return proof.clone();
+            }
+        }
+        let proof = self.private_key.as_ref()?.prove(&self.vrf_input(round));
+        let rank = self.get_rank(self.author, &proof);
+        self.record_rank(self.author, round, rank);
+        let proof = rank.map(|_| proof.to_bytes().to_vec());
+        self.own_proof = Some((round, proof.clone()));
+        proof
+    }
+
+    fn process_leadership_proof(&mut self, author: Author, round: Round, proof: Option<&[u8]>) {
+        let rank = proof.and_then(|proof| self.verify_proof(author, round, proof));
+        self.record_rank(author, round, rank);
+    }
+}
@@ -0,0 +1,281 @@
+// Copyright (c) The Libra Core Contributors
+// SPDX-License-Identifier: Apache-2.0
+
+use crate::chained_bft::liveness::{
+    proposer_election::ProposerElection, vrf_proposer_election::VrfProposer,
+};
+use consensus_types::{block::Block, common::Author, quorum_cert::QuorumCert};
+use crypto::{
+    ed25519::*,
+    vrf::ecvrf::{Output, Proof, VRFPrivateKey, VRFPublicKey},
+};
+use libra_types::validator_signer::ValidatorSigner;
+use std::{collections::HashMap, convert::TryFrom};
+
+const EPOCH: u64 = 1;
+
+struct TestValidators {
+    signers: Vec<ValidatorSigner<Ed25519PrivateKey>>,
+}
+
+impl TestValidators {
+    fn new(num_validators: u8) -> Self {
+        let signers = (0..num_validators)
+            .map(|i| ValidatorSigner::random([i; 32]))
+            .collect();
+        Self { signers }
+    }
+
+    fn author(&self, idx: usize) -> Author {
+        self.signers[idx].author()
+    }
+
+    fn vrf_private_key(idx: usize) -> VRFPrivateKey {
+        VRFPrivateKey::try_from(&[idx as u8; 32][..]).unwrap()
+    }
+
+    fn vrf_public_keys(&self) -> HashMap<Author, VRFPublicKey> {
+        (0..self.signers.len())
+            .map(|idx| (self.author(idx), (&Self::vrf_private_key(idx)).into()))
+            .collect()
+    }
+
+    /// The election run by the validator `idx`, electing `expected_proposers` per round.
+    fn proposer_election(&self, idx: usize, expected_proposers: usize) -> VrfProposer<u32> {
+        VrfProposer::new(
+            self.author(idx),
+            EPOCH,
+            Some(Self::vrf_private_key(idx)),
+            self.vrf_public_keys(),
+            expected_proposers,
+        )
+    }
+}
+
+/// The value of the VRF output of a leadership proof the proposals are ranked by.
+fn proof_value(proof: &[u8]) -> u64 {
+    let output = Output::from(&Proof::try_from(proof).unwrap()).to_bytes();
+    let mut value = [0u8; 8];
+    value.copy_from_slice(&output[..8]);
+    u64::from_be_bytes(value)
+}
+
+#[test]
+fn test_vrf_proposer_election() {
+    let validators = TestValidators::new(4);
+    let mut pes: Vec<VrfProposer<u32>> = (0..4)
+        .map(|idx| validators.proposer_election(idx, 2))
+        .collect();
+    let round = 1;
+
+    // Nobody is a valid proposer before proving it.
+    for idx in 0..4 {
+        let author = validators.author(idx);
+        assert_eq!(pes[0].is_valid_proposer(author, round), None);
+    }
+
+    let proofs: Vec<Option<Vec<u8>>> = pes
+        .iter_mut()
+        .map(|pe| pe.prove_leadership(round))
+        .collect();
+    for (idx, proof) in proofs.iter().enumerate() {
+        let author = validators.author(idx);
+        // Each validator knows whether it is elected itself.
+        assert_eq!(
+            pes[idx].is_valid_proposer(author, round),
+            proof.as_ref().map(|_| author)
+        );
+        // The others learn about it from the proof.
+        let observer = &mut pes[(idx + 1) % 4];
+        observer.process_leadership_proof(author, round, proof.as_ref().map(Vec::as_slice));
+        assert_eq!(
+            observer.is_valid_proposer(author, round),
+            proof.as_ref().map(|_| author)
+        );
+    }
+
+    // Any validator is a potential proposer.
+    let mut all_validators: Vec<Author> = (0..4).map(|idx| validators.author(idx)).collect();
+    all_validators.sort();
+    assert_eq!(pes[0].get_valid_proposers(round), all_validators);
+}
+
+#[test]
+fn test_vrf_proposer_invalid_proof() {
+    let validators = TestValidators::new(4);
+    // All the validators are elected, the proofs are only checked for validity.
+    let mut prover = validators.proposer_election(1, 4);
+    let mut pe = validators.proposer_election(0, 4);
+    let author = validators.author(1);
+    let proof = prover.prove_leadership(1).unwrap();
+
+    // A proof for another round or another epoch is rejected.
+    pe.process_leadership_proof(author, 2, Some(&proof[..]));
+    assert_eq!(pe.is_valid_proposer(author, 2), None);
+    let mut next_epoch_pe = VrfProposer::<u32>::new(
+        validators.author(0),
+        EPOCH + 1,
+        None,
+        validators.vrf_public_keys(),
+        4,
+    );
+    next_epoch_pe.process_leadership_proof(author, 1, Some(&proof[..]));
+    assert_eq!(next_epoch_pe.is_valid_proposer(author, 1), None);
+
+    // A proof by another validator is rejected.
+    let other_author = validators.author(2);
+    pe.process_leadership_proof(other_author, 1, Some(&proof[..]));
+    assert_eq!(pe.is_valid_proposer(other_author, 1), None);
+
+    // Malformed or missing proofs are rejected.
+    pe.process_leadership_proof(author, 1, Some(&proof[1..]));
+    assert_eq!(pe.is_valid_proposer(author, 1), None);
+    pe.process_leadership_proof(author, 1, None);
+    assert_eq!(pe.is_valid_proposer(author, 1), None);
+
+    pe.process_leadership_proof(author, 1, Some(&proof[..]));
+    assert_eq!(pe.is_valid_proposer(author, 1), Some(author));
+}
+
+#[test]
+fn test_vrf_proposer_no_private_key() {
+    let validators = TestValidators::new(4);
+    let mut pe = VrfProposer::<u32>::new(
+        validators.author(0),
+        EPOCH,
+        None,
+        validators.vrf_public_keys(),
+        4,
+    );
+    assert_eq!(pe.prove_leadership(1), None);
+    assert_eq!(pe.is_valid_proposer(validators.author(0), 1), None);
+}
+
+#[test]
+fn test_vrf_proposer_ranking() {
+    let validators = TestValidators::new(4);
+    let genesis_block = Block::make_genesis_block();
+    // All the validators are elected and propose.
+    let mut proposals = vec![];
+    for idx in 0..4 {
+        let proof = validators
+            .proposer_election(idx, 4)
+            .prove_leadership(1)
+            .unwrap();
+        let proposal = Block::make_block(
+            &genesis_block,
+            1,
+            1,
+            1,
+            QuorumCert::certificate_for_genesis(),
+            &validators.signers[idx],
+        );
+        proposals.push((proof_value(&proof), proof, proposal));
+    }
+    proposals.sort_by_key(|(value, _, _)| *value);
+    let new_proposer_election = || {
+        let mut pe = validators.proposer_election(0, 4);
+        for (_, proof, proposal) in proposals.iter() {
+            pe.process_leadership_proof(proposal.author().unwrap(), 1, Some(proof.as_slice()));
+        }
+        pe
+    };
+
+    // Received from the worst to the best ranked, each proposal is better than the previous ones.
+    let mut pe = new_proposer_election();
+    for (_, _, proposal) in proposals.iter().rev() {
+        assert_eq!(
+            pe.process_proposal(proposal.clone()),
+            Some(proposal.clone())
+        );
+    }
+    assert_eq!(pe.take_backup_proposal(1), None);
+
+    // Received from the best to the worst ranked, the first one is processed and the second best
+    // one kept as backup.
+    let mut pe = new_proposer_election();
+    assert_eq!(
+        pe.process_proposal(proposals[0].2.clone()),
+        Some(proposals[0].2.clone())
+    );
+    for (_, _, proposal) in proposals.iter().skip(1).rev() {
+        assert_eq!(pe.process_proposal(proposal.clone()), None);
+    }
+    assert_eq!(pe.take_backup_proposal(2), None);
+    assert_eq!(pe.take_backup_proposal(1), Some(proposals[1].2.clone()));
+    // has been already popped out
+    assert_eq!(pe.take_backup_proposal(1), None);
+
+    // Proposals without a verified leadership proof are ignored.
+    let mut pe = validators.proposer_election(0, 4);
+    assert_eq!(pe.process_proposal(proposals[0].2.clone()), None);
+    assert_eq!(pe.take_backup_proposal(1), None);
+}
+
+#[test]
+fn test_vrf_proposer_split_votes() {
+    // The proposals are not held back waiting for better ranked ones: the validators receiving
+    // competing proposals in different orders process, hence vote for, different proposals first.
+    let validators = TestValidators::new(4);
+    let genesis_block = Block::make_genesis_block();
+    let mut pes: Vec<VrfProposer<u32>> = (0..2)
+        .map(|idx| validators.proposer_election(idx, 4))
+        .collect();
+    let mut proposals = vec![];
+    for idx in 0..4 {
+        let proof = validators
+            .proposer_election(idx, 4)
+            .prove_leadership(1)
+            .unwrap();
+        let proposal = Block::make_block(
+            &genesis_block,
+            1,
+            1,
+            1,
+            QuorumCert::certificate_for_genesis(),
+            &validators.signers[idx],
+        );
+        for pe in pes.iter_mut() {
+            pe.process_leadership_proof(proposal.author().unwrap(), 1, Some(proof.as_slice()));
+        }
+        proposals.push((proof_value(&proof), proposal));
+    }
+    proposals.sort_by_key(|(value, _)| *value);
+    let best = proposals[0].1.clone();
+    let second = proposals[1].1.clone();
+
+    // The first validator receives the best ranked proposal first and votes for it.
+    assert_eq!(pes[0].process_proposal(best.clone()), Some(best.clone()));
+    assert_eq!(pes[0].process_proposal(second.clone()), None);
+
+    // The second validator receives the other proposal first and votes for it. The best ranked
+    // proposal is processed once received, but the validator has already voted in this round:
+    // the votes are split and the round times out unless a quorum voted for the same proposal.
+    assert_eq!(pes[1].process_proposal(second.clone()), Some(second));
+    assert_eq!(pes[1].process_proposal(best.clone()), Some(best));
+}
+
+#[test]
+fn test_vrf_proposer_distribution() {
+    // Verify that the expected number of proposers is elected per round on average, and that all
+    // the validators are elected in some rounds.
+    const NUM_VALIDATORS: usize = 10;
+    const NUM_ROUNDS: u64 = 100;
+    let validators = TestValidators::new(NUM_VALIDATORS as u8);
+    let mut pes: Vec<VrfProposer<u32>> = (0..NUM_VALIDATORS)
+        .map(|idx| validators.proposer_election(idx, 2))
+        .collect();
+    let mut counts = vec![0; NUM_VALIDATORS];
+    for round in 1..=NUM_ROUNDS {
+        for (idx, pe) in pes.iter_mut().enumerate() {
+            if pe.prove_leadership(round).is_some() {
+                counts[idx] += 1;
+            }
+        }
+    }
+    let total: u64 = counts.iter().sum();
+    assert!(total > 120 && total < 280, "{} proposers elected", total);
+    for c in counts {
+        assert!(c > 0);
+    }
+}
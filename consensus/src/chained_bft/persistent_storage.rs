@@ -6,25 +6,47 @@ use crate::{
 };
 use config::config::NodeConfig;
 use consensus_types::{
-    block::Block, common::Payload, quorum_cert::QuorumCert,
-    timeout_certificate::TimeoutCertificate, vote_msg::VoteMsg,
+    block::Block,
+    common::{Payload, Round},
+    quorum_cert::QuorumCert,
+    timeout_certificate::TimeoutCertificate,
+    vote_msg::VoteMsg,
 };
 use crypto::HashValue;
 use failure::{Result, ResultExt};
-use libra_types::ledger_info::LedgerInfo;
+use libra_types::{block_metadata::BlockMetadata, ledger_info::LedgerInfo};
 use logger::prelude::*;
 use rmp_serde::{from_slice, to_vec_named};
 use safety_rules::ConsensusState;
 use std::{
-    collections::{HashMap, HashSet},
+    collections::{HashMap, HashSet, VecDeque},
     sync::Arc,
 };
 
+/// The metadata of committed blocks is kept for this number of rounds after they are committed.
+pub const BLOCK_METADATA_RETENTION_ROUNDS: Round = 1000;
+
 /// Persistent storage for liveness data
 pub trait PersistentLivenessStorage: Send + Sync {
     /// Persist the highest timeout certificate for improved liveness - proof for other replicas
     /// to jump to this round
     fn save_highest_timeout_cert(&self, highest_timeout_cert: TimeoutCertificate) -> Result<()>;
+
+    /// Persist the metadata (proposer and voters) of newly committed blocks, which the leader
+    /// reputation proposer election relies on. The metadata of blocks older than
+    /// BLOCK_METADATA_RETENTION_ROUNDS rounds may be removed.
+    fn save_committed_block_metadata(
+        &self,
+        block_metadata: Vec<(Round, BlockMetadata)>,
+    ) -> Result<()>;
+
+    /// Get the metadata of the (at most) `limit` latest committed blocks with rounds not higher
+    /// than `round`, ordered by round.
+    fn get_committed_block_metadata(
+        &self,
+        round: Round,
+        limit: usize,
+    ) -> Result<Vec<BlockMetadata>>;
 }
 
 /// Persistent storage is essential for maintaining safety when a node crashes.  Specifically,
@@ -301,6 +323,33 @@ impl PersistentLivenessStorage for StorageWriteProxy {
         self.db
             .save_highest_timeout_certificate(to_vec_named(&highest_timeout_cert)?)
     }
+
+    fn save_committed_block_metadata(
+        &self,
+        block_metadata: Vec<(Round, BlockMetadata)>,
+    ) -> Result<()> {
+        let min_round = match block_metadata.iter().map(|(round, _)| *round).max() {
+            Some(round) => round.saturating_sub(BLOCK_METADATA_RETENTION_ROUNDS),
+            None => return Ok(()),
+        };
+        self.db.save_block_metadata(block_metadata, min_round)
+    }
+
+    fn get_committed_block_metadata(
+        &self,
+        round: Round,
+        limit: usize,
+    ) -> Result<Vec<BlockMetadata>> {
+        let start_round = round.saturating_sub(BLOCK_METADATA_RETENTION_ROUNDS);
+        let mut latest = VecDeque::with_capacity(limit + 1);
+        for (_, metadata) in self.db.get_block_metadata(start_round, round)? {
+            latest.push_back(metadata);
+            if latest.len() > limit {
+                latest.pop_front();
+            }
+        }
+        Ok(latest.into_iter().collect())
+    }
 }
 
 impl<T: Payload> PersistentStorage<T> for StorageWriteProxy {
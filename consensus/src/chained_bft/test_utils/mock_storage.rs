@@ -7,15 +7,18 @@ use crate::chained_bft::persistent_storage::{
 
 use config::config::{NodeConfig, NodeConfigHelpers};
 use consensus_types::{
-    block::Block, common::Payload, quorum_cert::QuorumCert,
-    timeout_certificate::TimeoutCertificate, vote_msg::VoteMsg,
+    block::Block,
+    common::{Payload, Round},
+    quorum_cert::QuorumCert,
+    timeout_certificate::TimeoutCertificate,
+    vote_msg::VoteMsg,
 };
 use crypto::HashValue;
 use failure::Result;
-use libra_types::ledger_info::LedgerInfo;
+use libra_types::{block_metadata::BlockMetadata, ledger_info::LedgerInfo};
 use safety_rules::ConsensusState;
 use std::{
-    collections::HashMap,
+    collections::{BTreeMap, HashMap},
     sync::{Arc, Mutex},
 };
 
@@ -28,6 +31,7 @@ pub struct MockSharedStorage<T> {
 
     // Liveness state
     pub highest_timeout_certificate: Mutex<Option<TimeoutCertificate>>,
+    pub block_metadata: Mutex<BTreeMap<Round, BlockMetadata>>,
 }
 
 /// A storage that simulates the operations in-memory, used in the tests that cares about storage
@@ -113,6 +117,34 @@ impl<T: Payload> PersistentLivenessStorage for MockStorage<T> {
             .replace(highest_timeout_certificate);
         Ok(())
     }
+
+    fn save_committed_block_metadata(
+        &self,
+        block_metadata: Vec<(Round, BlockMetadata)>,
+    ) -> Result<()> {
+        self.shared_storage
+            .block_metadata
+            .lock()
+            .unwrap()
+            .extend(block_metadata);
+        Ok(())
+    }
+
+    fn get_committed_block_metadata(
+        &self,
+        round: Round,
+        limit: usize,
+    ) -> Result<Vec<BlockMetadata>> {
+        let block_metadata = self.shared_storage.block_metadata.lock().unwrap();
+        let mut latest: Vec<_> = block_metadata
+            .range(..=round)
+            .rev()
+            .take(limit)
+            .map(|(_, metadata)| metadata.clone())
+            .collect();
+        latest.reverse();
+        Ok(latest)
+    }
 }
 
 // A impl that always start from genesis.
@@ -170,6 +202,7 @@ impl<T: Payload> PersistentStorage<T> for MockStorage<T> {
             state: Mutex::new(ConsensusState::default()),
             last_vote: Mutex::new(None),
             highest_timeout_certificate: Mutex::new(None),
+            block_metadata: Mutex::new(BTreeMap::new()),
         });
         let storage = MockStorage::new(Arc::clone(&shared_storage));
 
@@ -200,6 +233,14 @@ impl PersistentLivenessStorage for EmptyStorage {
     fn save_highest_timeout_cert(&self, _: TimeoutCertificate) -> Result<()> {
         Ok(())
     }
+
+    fn save_committed_block_metadata(&self, _: Vec<(Round, BlockMetadata)>) -> Result<()> {
+        Ok(())
+    }
+
+    fn get_committed_block_metadata(&self, _: Round, _: usize) -> Result<Vec<BlockMetadata>> {
+        Ok(vec![])
+    }
 }
 
 impl<T: Payload> PersistentStorage<T> for EmptyStorage {
@@ -38,7 +38,7 @@ pub fn build_empty_tree_with_custom_signing(
     Arc::new(block_on(BlockStore::new(
         storage,
         initial_data,
-        my_signer,
+        Arc::new(my_signer),
         Arc::new(EmptyStateComputer),
         true,
         10, // max pruned blocks in mem
@@ -47,19 +47,33 @@ pub fn build_empty_tree_with_custom_signing(
 
 pub struct TreeInserter {
     payload_val: usize,
+    signer: ValidatorSigner,
     block_store: Arc<BlockStore<Vec<usize>>>,
 }
 
 impl TreeInserter {
+    /// Inserts blocks authored by a random signer.
     pub fn new(block_store: Arc<BlockStore<Vec<usize>>>) -> Self {
+        Self::new_with_signer(ValidatorSigner::random(None), block_store)
+    }
+
+    pub fn new_with_signer(
+        signer: ValidatorSigner,
+        block_store: Arc<BlockStore<Vec<usize>>>,
+    ) -> Self {
         Self {
             payload_val: 0,
+            signer,
             block_store,
         }
     }
 
+    pub fn signer(&self) -> &ValidatorSigner {
+        &self.signer
+    }
+
     /// This function is generating a placeholder QC for a block's parent that is signed by a single
-    /// signer kept by the block store. If more sophisticated QC required, please use
+    /// signer kept by the inserter. If more sophisticated QC required, please use
     /// `insert_block_with_qc`.
     pub fn insert_block(
         &mut self,
@@ -93,7 +107,7 @@ impl TreeInserter {
 
     pub fn create_qc_for_block(&self, block: &ExecutedBlock<TestPayload>) -> QuorumCert {
         placeholder_certificate_for_block(
-            vec![self.block_store.signer()],
+            vec![&self.signer],
             block.id(),
             block.round(),
             block.quorum_cert().certified_block().id(),
@@ -120,7 +134,7 @@ impl TreeInserter {
             round,
             parent.timestamp_usecs() + 1,
             parent_qc,
-            self.block_store.signer(),
+            &self.signer,
         )
     }
 
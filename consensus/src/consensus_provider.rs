@@ -9,6 +9,7 @@ use crate::chained_bft::chained_bft_consensus_provider::ChainedBftProvider;
 use executor::Executor;
 use grpcio::{ChannelBuilder, EnvBuilder};
 use libra_mempool::proto::mempool::MempoolClient;
+use secret_service::proto::SafetyRulesServiceClient;
 use state_synchronizer::StateSyncClient;
 use std::sync::Arc;
 use storage_client::{StorageRead, StorageReadServiceClient};
@@ -64,3 +65,14 @@ pub fn create_storage_read_client(config: &NodeConfig) -> Arc<dyn StorageRead> {
         config.storage.port,
     ))
 }
+
+/// Create a client of the safety rules served by the secret service based on the config
+pub fn create_safety_rules_client(config: &NodeConfig) -> SafetyRulesServiceClient {
+    let connection_str = format!(
+        "{}:{}",
+        config.secret_service.address, config.secret_service.safety_rules_port
+    );
+
+    let env = Arc::new(EnvBuilder::new().name_prefix("grpc-con-sr-").build());
+    SafetyRulesServiceClient::new(ChannelBuilder::new(env).connect(&connection_str))
+}
@@ -27,16 +27,23 @@
 //! **Note**: The above example generates a private key using a private function intended only for
 //! testing purposes. Production code should generate the key according to the spec [draft-irtf-cfrg-bls-signature-00](https://tools.ietf.org/id/draft-irtf-cfrg-bls-signature-00.html#keygen).
 //!
-//! This module is not currently used, but could be included in the future for improved
-//! performance in consensus.
+//! Signatures of a same message by different keys can be aggregated into a single signature,
+//! which verifies against the aggregate of the public keys (see `Signature::aggregate` and
+//! `VerifyingKey::aggregate`). Neither consensus nor the ledger info signatures use it: the
+//! consensus keys of validators are Ed25519 keys.
+//!
+//! **Warning**: Aggregating public keys is only safe for keys whose owners have proven possession
+//! of the matching private keys. No proof of possession is implemented nor checked when keys are
+//! registered, so a rogue key crafted from the keys of others can forge an aggregate signature.
+//! Do not aggregate BLS12-381 keys that are not known to be honestly generated.
 
 use crate::{traits::*, HashValue};
 use core::convert::TryFrom;
 use crypto_derive::{Deref, SilentDebug, SilentDisplay};
 use failure::prelude::*;
 use pairing::{
-    bls12_381::{Fr, FrRepr},
-    PrimeField,
+    bls12_381::{Fr, FrRepr, G1Compressed, G2Compressed},
+    CurveAffine, CurveProjective, EncodedPoint, PrimeField,
 };
 use rand::Rng;
 use serde::{Deserialize, Serialize};
@@ -192,6 +199,17 @@ impl PublicKey for BLS12381PublicKey {
 impl VerifyingKey for BLS12381PublicKey {
     type SigningKeyMaterial = BLS12381PrivateKey;
     type SignatureMaterial = BLS12381Signature;
+
+    /// Aggregates public keys by adding up their points on G1.
+    fn aggregate(keys: &[&Self]) -> Option<Self> {
+        let encodings: Vec<_> = keys.iter().map(|key| key.0.to_bytes()).collect();
+        let sum: G1Compressed = sum_compressed_points(&encodings)?;
+        let mut bytes = [0u8; BLS12381_PUBLIC_KEY_LENGTH];
+        bytes.copy_from_slice(sum.as_ref());
+        threshold_crypto::PublicKey::from_bytes(bytes)
+            .ok()
+            .map(BLS12381PublicKey)
+    }
 }
 impl std::fmt::Display for BLS12381PublicKey {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
@@ -249,6 +267,44 @@ impl Signature for BLS12381Signature {
     fn to_bytes(&self) -> Vec<u8> {
         self.0.to_bytes().to_vec()
     }
+
+    /// Aggregates signatures by adding up their points on G2.
+    fn aggregate(signatures: &[&Self]) -> Option<Self> {
+        let encodings: Vec<_> = signatures.iter().map(|sig| sig.0.to_bytes()).collect();
+        let sum: G2Compressed = sum_compressed_points(&encodings)?;
+        let mut bytes = [0u8; BLS12381_SIGNATURE_LENGTH];
+        bytes.copy_from_slice(sum.as_ref());
+        threshold_crypto::Signature::from_bytes(bytes)
+            .ok()
+            .map(BLS12381Signature)
+    }
+}
+
+/// Adds up the curve points with the given compressed encodings and returns the compressed
+/// encoding of the sum, or `None` if there is no point or an encoding is invalid.
+fn sum_compressed_points<E, B>(encodings: &[B]) -> Option<E>
+where
+    E: EncodedPoint,
+    E::Affine: CurveAffine<Compressed = E>,
+    B: AsRef<[u8]>,
+{
+    let mut sum = None;
+    for encoding in encodings {
+        let mut compressed = E::empty();
+        if compressed.as_ref().len() != encoding.as_ref().len() {
+            return None;
+        }
+        compressed.as_mut().copy_from_slice(encoding.as_ref());
+        let point = compressed.into_affine().ok()?.into_projective();
+        sum = Some(match sum {
+            Some(mut sum) => {
+                CurveProjective::add_assign(&mut sum, &point);
+                sum
+            }
+            None => point,
+        });
+    }
+    sum.map(|sum| sum.into_affine().into_compressed())
 }
 
 impl TryFrom<&[u8]> for BLS12381Signature {
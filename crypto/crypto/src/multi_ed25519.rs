@@ -0,0 +1,493 @@
+// Copyright (c) The Libra Core Contributors
+// SPDX-License-Identifier: Apache-2.0
+
+//! This module provides an API for the accountable threshold multi-sig PureEdDSA signature
+//! scheme over the ed25519 twisted Edwards curve as defined in
+//! [RFC8032](https://tools.ietf.org/html/rfc8032).
+//!
+//! A [`MultiEd25519PublicKey`] is made of up to [`MAX_NUM_OF_KEYS`] Ed25519 public keys and a
+//! threshold `k`. A [`MultiEd25519Signature`] is made of at least `k` Ed25519 signatures, together
+//! with a bitmap telling which of the public keys produced them, so that the signers can be held
+//! accountable.
+//!
+//! The signatures are usually produced separately by the holders of the individual private keys
+//! and then assembled with [`MultiEd25519Signature::new`].
+//!
+//! # Examples
+//!
+//! ```
+//! use crypto::hash::{CryptoHasher, TestOnlyHasher};
+//! use crypto::{
+//!     ed25519::*,
+//!     multi_ed25519::*,
+//!     traits::{Signature, SigningKey, Uniform},
+//! };
+//! use rand::{rngs::StdRng, SeedableRng};
+//!
+//! let mut hasher = TestOnlyHasher::default();
+//! hasher.write("Test message".as_bytes());
+//! let hashed_message = hasher.finish();
+//!
+//! let mut rng: StdRng = SeedableRng::from_seed([0; 32]);
+//! let private_keys: Vec<_> = (0..3)
+//!     .map(|_| Ed25519PrivateKey::generate_for_testing(&mut rng))
+//!     .collect();
+//! let public_key = MultiEd25519PublicKey::new(
+//!     private_keys.iter().map(Ed25519PublicKey::from).collect(),
+//!     2, /* threshold */
+//! )
+//! .unwrap();
+//!
+//! // The holders of the first and the last keys sign.
+//! let signature = MultiEd25519Signature::new(vec![
+//!     (private_keys[0].sign_message(&hashed_message), 0),
+//!     (private_keys[2].sign_message(&hashed_message), 2),
+//! ])
+//! .unwrap();
+//! assert!(signature.verify(&hashed_message, &public_key).is_ok());
+//! ```
+//! **Note**: The above example generates private keys using a private function intended only for
+//! testing purposes. Production code should find an alternate means for secure key generation.
+
+use crate::{
+    ed25519::{
+        Ed25519PrivateKey, Ed25519PublicKey, Ed25519Signature, ED25519_PRIVATE_KEY_LENGTH,
+        ED25519_PUBLIC_KEY_LENGTH, ED25519_SIGNATURE_LENGTH,
+    },
+    traits::*,
+    HashValue,
+};
+use core::convert::TryFrom;
+use crypto_derive::{SilentDebug, SilentDisplay};
+use failure::prelude::*;
+use serde::{de, ser};
+use std::fmt;
+
+/// The maximum number of Ed25519 keys in a multi-sig key.
+pub const MAX_NUM_OF_KEYS: usize = 32;
+
+/// The length of the bitmap in a `MultiEd25519Signature`, one bit per key.
+pub const BITMAP_NUM_OF_BYTES: usize = 4;
+
+/// A multi-sig private key, made of Ed25519 private keys and a threshold.
+#[derive(SilentDisplay, SilentDebug)]
+pub struct MultiEd25519PrivateKey {
+    private_keys: Vec<Ed25519PrivateKey>,
+    threshold: u8,
+}
+
+/// A multi-sig public key, made of Ed25519 public keys and a threshold.
+#[derive(Clone, Debug, Eq, Hash, PartialEq)]
+pub struct MultiEd25519PublicKey {
+    public_keys: Vec<Ed25519PublicKey>,
+    threshold: u8,
+}
+
+/// A multi-sig signature, made of Ed25519 signatures ordered by the index of the key producing
+/// them, and a bitmap with the bits of these indexes set.
+#[derive(Clone, Debug, Eq, Hash, PartialEq)]
+pub struct MultiEd25519Signature {
+    signatures: Vec<Ed25519Signature>,
+    bitmap: [u8; BITMAP_NUM_OF_BYTES],
+}
+
+impl MultiEd25519PrivateKey {
+    /// Creates a multi-sig private key out of `private_keys`, of which any `threshold` are needed
+    /// to sign.
+    pub fn new(
+        private_keys: Vec<Ed25519PrivateKey>,
+        threshold: u8,
+    ) -> std::result::Result<Self, CryptoMaterialError> {
+        check_threshold(private_keys.len(), threshold)?;
+        Ok(Self {
+            private_keys,
+            threshold,
+        })
+    }
+
+    /// Serialize a MultiEd25519PrivateKey as the private keys followed by the threshold.
+    pub fn to_bytes(&self) -> Vec<u8> {
+        concat_bytes(
+            self.private_keys.iter().map(|key| key.to_bytes().to_vec()),
+            &[self.threshold],
+        )
+    }
+}
+
+impl MultiEd25519PublicKey {
+    /// Creates a multi-sig public key out of `public_keys`, of which any `threshold` are needed to
+    /// verify a signature.
+    pub fn new(
+        public_keys: Vec<Ed25519PublicKey>,
+        threshold: u8,
+    ) -> std::result::Result<Self, CryptoMaterialError> {
+        check_threshold(public_keys.len(), threshold)?;
+        Ok(Self {
+            public_keys,
+            threshold,
+        })
+    }
+
+    /// Returns the Ed25519 public keys.
+    pub fn public_keys(&self) -> &[Ed25519PublicKey] {
+        &self.public_keys
+    }
+
+    /// Returns the number of signatures needed.
+    pub fn threshold(&self) -> u8 {
+        self.threshold
+    }
+
+    /// Serialize a MultiEd25519PublicKey as the public keys followed by the threshold. This is also
+    /// the pre-image of the authentication key of an account controlled by the multi-sig key. Its
+    /// length is never that of a single Ed25519 public key, so the two never collide.
+    pub fn to_bytes(&self) -> Vec<u8> {
+        concat_bytes(
+            self.public_keys.iter().map(|key| key.to_bytes().to_vec()),
+            &[self.threshold],
+        )
+    }
+}
+
+impl MultiEd25519Signature {
+    /// Assembles a multi-sig signature out of Ed25519 signatures, each along with the index of the
+    /// public key verifying it. The order of `signatures` does not matter, but an index can only
+    /// appear once.
+    pub fn new(
+        mut signatures: Vec<(Ed25519Signature, u8)>,
+    ) -> std::result::Result<Self, CryptoMaterialError> {
+        if signatures.is_empty() || signatures.len() > MAX_NUM_OF_KEYS {
+            return Err(CryptoMaterialError::ValidationError);
+        }
+        signatures.sort_by_key(|(_, index)| *index);
+
+        let mut bitmap = [0u8; BITMAP_NUM_OF_BYTES];
+        for (_, index) in &signatures {
+            if *index as usize >= MAX_NUM_OF_KEYS || bitmap_get_bit(bitmap, *index as usize) {
+                return Err(CryptoMaterialError::ValidationError);
+            }
+            bitmap_set_bit(&mut bitmap, *index as usize);
+        }
+
+        Ok(Self {
+            signatures: signatures
+                .into_iter()
+                .map(|(signature, _)| signature)
+                .collect(),
+            bitmap,
+        })
+    }
+
+    /// Returns the Ed25519 signatures, ordered by the index of their public keys.
+    pub fn signatures(&self) -> &[Ed25519Signature] {
+        &self.signatures
+    }
+
+    /// Returns the bitmap. The bit of key `i` is bit `7 - i % 8` of byte `i / 8`.
+    pub fn bitmap(&self) -> [u8; BITMAP_NUM_OF_BYTES] {
+        self.bitmap
+    }
+
+    /// Serialize a MultiEd25519Signature as the signatures followed by the bitmap.
+    pub fn to_bytes(&self) -> Vec<u8> {
+        concat_bytes(
+            self.signatures.iter().map(|sig| sig.to_bytes().to_vec()),
+            &self.bitmap,
+        )
+    }
+}
+
+fn check_threshold(num_keys: usize, threshold: u8) -> std::result::Result<(), CryptoMaterialError> {
+    if threshold == 0 || num_keys < threshold as usize || num_keys > MAX_NUM_OF_KEYS {
+        Err(CryptoMaterialError::ValidationError)
+    } else {
+        Ok(())
+    }
+}
+
+fn concat_bytes<I: Iterator<Item = Vec<u8>>>(items: I, suffix: &[u8]) -> Vec<u8> {
+    let mut bytes: Vec<u8> = items.flatten().collect();
+    bytes.extend_from_slice(suffix);
+    bytes
+}
+
+fn bitmap_set_bit(bitmap: &mut [u8; BITMAP_NUM_OF_BYTES], index: usize) {
+    bitmap[index / 8] |= 128 >> (index % 8);
+}
+
+fn bitmap_get_bit(bitmap: [u8; BITMAP_NUM_OF_BYTES], index: usize) -> bool {
+    bitmap[index / 8] & (128 >> (index % 8)) != 0
+}
+
+fn bitmap_count_ones(bitmap: [u8; BITMAP_NUM_OF_BYTES]) -> u32 {
+    bitmap.iter().map(|byte| byte.count_ones()).sum()
+}
+
+///////////////////////
+// PrivateKey Traits //
+///////////////////////
+
+impl PrivateKey for MultiEd25519PrivateKey {
+    type PublicKeyMaterial = MultiEd25519PublicKey;
+}
+
+impl SigningKey for MultiEd25519PrivateKey {
+    type VerifyingKeyMaterial = MultiEd25519PublicKey;
+    type SignatureMaterial = MultiEd25519Signature;
+
+    /// Signs with the first `threshold` keys.
+    fn sign_message(&self, message: &HashValue) -> MultiEd25519Signature {
+        let signatures = self
+            .private_keys
+            .iter()
+            .take(self.threshold as usize)
+            .enumerate()
+            .map(|(index, key)| (key.sign_message(message), index as u8))
+            .collect();
+        MultiEd25519Signature::new(signatures)
+            .expect("Signatures of a valid private key should be valid.")
+    }
+}
+
+impl PartialEq<Self> for MultiEd25519PrivateKey {
+    fn eq(&self, other: &Self) -> bool {
+        self.to_bytes() == other.to_bytes()
+    }
+}
+
+impl Eq for MultiEd25519PrivateKey {}
+
+impl TryFrom<&[u8]> for MultiEd25519PrivateKey {
+    type Error = CryptoMaterialError;
+
+    /// Deserialize a MultiEd25519PrivateKey. This method will also check for key and threshold
+    /// validity.
+    fn try_from(bytes: &[u8]) -> std::result::Result<MultiEd25519PrivateKey, CryptoMaterialError> {
+        let (keys, threshold) = split_keys(bytes, ED25519_PRIVATE_KEY_LENGTH)?;
+        let private_keys = keys
+            .chunks(ED25519_PRIVATE_KEY_LENGTH)
+            .map(Ed25519PrivateKey::try_from)
+            .collect::<std::result::Result<Vec<_>, _>>()?;
+        MultiEd25519PrivateKey::new(private_keys, threshold)
+    }
+}
+
+impl ValidKey for MultiEd25519PrivateKey {
+    fn to_bytes(&self) -> Vec<u8> {
+        self.to_bytes()
+    }
+}
+
+/// Splits the keys from the threshold, checking the length of the keys.
+fn split_keys(
+    bytes: &[u8],
+    key_length: usize,
+) -> std::result::Result<(&[u8], u8), CryptoMaterialError> {
+    match bytes.split_last() {
+        Some((threshold, keys)) if !keys.is_empty() && keys.len() % key_length == 0 => {
+            Ok((keys, *threshold))
+        }
+        _ => Err(CryptoMaterialError::WrongLengthError),
+    }
+}
+
+//////////////////////
+// PublicKey Traits //
+//////////////////////
+
+impl From<&MultiEd25519PrivateKey> for MultiEd25519PublicKey {
+    fn from(private_key: &MultiEd25519PrivateKey) -> Self {
+        MultiEd25519PublicKey {
+            public_keys: private_key
+                .private_keys
+                .iter()
+                .map(Ed25519PublicKey::from)
+                .collect(),
+            threshold: private_key.threshold,
+        }
+    }
+}
+
+impl PublicKey for MultiEd25519PublicKey {
+    type PrivateKeyMaterial = MultiEd25519PrivateKey;
+}
+
+impl VerifyingKey for MultiEd25519PublicKey {
+    type SigningKeyMaterial = MultiEd25519PrivateKey;
+    type SignatureMaterial = MultiEd25519Signature;
+}
+
+impl std::fmt::Display for MultiEd25519PublicKey {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", hex::encode(&self.to_bytes()))
+    }
+}
+
+impl TryFrom<&[u8]> for MultiEd25519PublicKey {
+    type Error = CryptoMaterialError;
+
+    /// Deserialize a MultiEd25519PublicKey. This method will also check for key and threshold
+    /// validity.
+    fn try_from(bytes: &[u8]) -> std::result::Result<MultiEd25519PublicKey, CryptoMaterialError> {
+        let (keys, threshold) = split_keys(bytes, ED25519_PUBLIC_KEY_LENGTH)?;
+        let public_keys = keys
+            .chunks(ED25519_PUBLIC_KEY_LENGTH)
+            .map(Ed25519PublicKey::try_from)
+            .collect::<std::result::Result<Vec<_>, _>>()?;
+        MultiEd25519PublicKey::new(public_keys, threshold)
+    }
+}
+
+impl ValidKey for MultiEd25519PublicKey {
+    fn to_bytes(&self) -> Vec<u8> {
+        self.to_bytes()
+    }
+}
+
+//////////////////////
+// Signature Traits //
+//////////////////////
+
+impl Signature for MultiEd25519Signature {
+    type VerifyingKeyMaterial = MultiEd25519PublicKey;
+    type SigningKeyMaterial = MultiEd25519PrivateKey;
+
+    /// Checks that `self` is valid for `message` using `public_key`.
+    fn verify(&self, message: &HashValue, public_key: &MultiEd25519PublicKey) -> Result<()> {
+        self.verify_arbitrary_msg(message.as_ref(), public_key)
+    }
+
+    /// Checks that `self` is valid for an arbitrary &[u8] `message` using `public_key`: there are
+    /// enough signatures, and each of them is valid under the public key its bit stands for.
+    fn verify_arbitrary_msg(
+        &self,
+        message: &[u8],
+        public_key: &MultiEd25519PublicKey,
+    ) -> Result<()> {
+        ensure!(
+            self.signatures.len() >= public_key.threshold as usize,
+            "Not enough signatures: {}, threshold is {}.",
+            self.signatures.len(),
+            public_key.threshold,
+        );
+        ensure!(
+            bitmap_count_ones(self.bitmap) as usize == self.signatures.len(),
+            "Bitmap doesn't match the number of signatures."
+        );
+
+        let indexes = (0..MAX_NUM_OF_KEYS).filter(|index| bitmap_get_bit(self.bitmap, *index));
+        for (index, signature) in indexes.zip(self.signatures.iter()) {
+            let key = public_key
+                .public_keys
+                .get(index)
+                .ok_or_else(|| format_err!("Signature of key {} which doesn't exist.", index))?;
+            signature.verify_arbitrary_msg(message, key)?;
+        }
+        Ok(())
+    }
+
+    fn to_bytes(&self) -> Vec<u8> {
+        self.to_bytes()
+    }
+}
+
+impl TryFrom<&[u8]> for MultiEd25519Signature {
+    type Error = CryptoMaterialError;
+
+    /// Deserialize a MultiEd25519Signature. This method will also check for malleability of the
+    /// signatures and consistency with the bitmap.
+    fn try_from(bytes: &[u8]) -> std::result::Result<MultiEd25519Signature, CryptoMaterialError> {
+        if bytes.len() <= BITMAP_NUM_OF_BYTES {
+            return Err(CryptoMaterialError::WrongLengthError);
+        }
+        let (signatures, bitmap_bytes) = bytes.split_at(bytes.len() - BITMAP_NUM_OF_BYTES);
+        if signatures.len() % ED25519_SIGNATURE_LENGTH != 0 {
+            return Err(CryptoMaterialError::WrongLengthError);
+        }
+
+        let mut bitmap = [0u8; BITMAP_NUM_OF_BYTES];
+        bitmap.copy_from_slice(bitmap_bytes);
+        let signatures = signatures
+            .chunks(ED25519_SIGNATURE_LENGTH)
+            .map(Ed25519Signature::try_from)
+            .collect::<std::result::Result<Vec<_>, _>>()?;
+        if bitmap_count_ones(bitmap) as usize != signatures.len() {
+            return Err(CryptoMaterialError::ValidationError);
+        }
+
+        Ok(MultiEd25519Signature { signatures, bitmap })
+    }
+}
+
+//////////////////////////////
+// Compact Serialization    //
+//////////////////////////////
+
+impl ser::Serialize for MultiEd25519PublicKey {
+    fn serialize<S>(&self, serializer: S) -> std::result::Result<S::Ok, S::Error>
+    where
+        S: ser::Serializer,
+    {
+        serializer.serialize_bytes(&self.to_bytes())
+    }
+}
+
+impl ser::Serialize for MultiEd25519Signature {
+    fn serialize<S>(&self, serializer: S) -> std::result::Result<S::Ok, S::Error>
+    where
+        S: ser::Serializer,
+    {
+        serializer.serialize_bytes(&self.to_bytes())
+    }
+}
+
+struct MultiEd25519PublicKeyVisitor;
+struct MultiEd25519SignatureVisitor;
+
+impl<'de> de::Visitor<'de> for MultiEd25519PublicKeyVisitor {
+    type Value = MultiEd25519PublicKey;
+
+    fn expecting(&self, formatter: &mut fmt::Formatter<'_>) -> fmt::Result {
+        formatter.write_str("multi-sig public key in bytes")
+    }
+
+    fn visit_bytes<E>(self, value: &[u8]) -> std::result::Result<MultiEd25519PublicKey, E>
+    where
+        E: de::Error,
+    {
+        MultiEd25519PublicKey::try_from(value).map_err(E::custom)
+    }
+}
+
+impl<'de> de::Visitor<'de> for MultiEd25519SignatureVisitor {
+    type Value = MultiEd25519Signature;
+
+    fn expecting(&self, formatter: &mut fmt::Formatter<'_>) -> fmt::Result {
+        formatter.write_str("multi-sig signature in bytes")
+    }
+
+    fn visit_bytes<E>(self, value: &[u8]) -> std::result::Result<MultiEd25519Signature, E>
+    where
+        E: de::Error,
+    {
+        MultiEd25519Signature::try_from(value).map_err(E::custom)
+    }
+}
+
+impl<'de> de::Deserialize<'de> for MultiEd25519PublicKey {
+    fn deserialize<D>(deserializer: D) -> std::result::Result<Self, D::Error>
+    where
+        D: de::Deserializer<'de>,
+    {
+        deserializer.deserialize_bytes(MultiEd25519PublicKeyVisitor {})
+    }
+}
+
+impl<'de> de::Deserialize<'de> for MultiEd25519Signature {
+    fn deserialize<D>(deserializer: D) -> std::result::Result<Self, D::Error>
+    where
+        D: de::Deserializer<'de>,
+    {
+        deserializer.deserialize_bytes(MultiEd25519SignatureVisitor {})
+    }
+}
@@ -168,6 +168,13 @@ pub trait VerifyingKey:
     ) -> Result<()> {
         Self::SignatureMaterial::batch_verify_signatures(message, keys_and_signatures)
     }
+
+    /// Aggregates public keys into a single key, against which the aggregate of their signatures
+    /// of a same message verifies (see [`Signature::aggregate`][Signature::aggregate]). Schemes
+    /// supporting aggregation override this; by default, it returns `None`.
+    fn aggregate(_keys: &[&Self]) -> Option<Self> {
+        None
+    }
 }
 
 /// A type family for signature material that knows which public key type
@@ -215,6 +222,14 @@ pub trait Signature:
         }
         Ok(())
     }
+
+    /// Aggregates signatures of a same message by different keys into a single signature, which
+    /// verifies against the aggregate of these keys (see
+    /// [`VerifyingKey::aggregate`][VerifyingKey::aggregate]). Schemes supporting aggregation
+    /// override this; by default, it returns `None`.
+    fn aggregate(_signatures: &[&Self]) -> Option<Self> {
+        None
+    }
 }
 
 /// A type family for schemes which know how to generate key material from
@@ -3,7 +3,7 @@
 
 use crate::{
     bls12381::{
-        BLS12381PrivateKey, BLS12381PublicKey, BLS12381_PRIVATE_KEY_LENGTH,
+        BLS12381PrivateKey, BLS12381PublicKey, BLS12381Signature, BLS12381_PRIVATE_KEY_LENGTH,
         BLS12381_PUBLIC_KEY_LENGTH, BLS12381_SIGNATURE_LENGTH,
     },
     hash::HashValue,
@@ -89,3 +89,37 @@ proptest! {
         prop_assert!(keypair.public_key.verify_signature(&hash, &deserialized).is_ok());
     }
 }
+
+proptest! {
+    #![proptest_config(ProptestConfig::with_cases(10))]
+
+    #[test]
+    fn test_aggregate_and_verify(
+        hash in any::<HashValue>(),
+        keypairs in proptest::collection::vec(
+            uniform_keypair_strategy::<BLS12381PrivateKey, BLS12381PublicKey>(),
+            2..5,
+        )
+    ) {
+        let public_keys: Vec<_> = keypairs.iter().map(|keypair| &keypair.public_key).collect();
+        let signatures: Vec<_> = keypairs
+            .iter()
+            .map(|keypair| keypair.private_key.sign_message(&hash))
+            .collect();
+        let signature_refs: Vec<_> = signatures.iter().collect();
+
+        let aggregated_key = BLS12381PublicKey::aggregate(&public_keys).unwrap();
+        let aggregated_signature = BLS12381Signature::aggregate(&signature_refs).unwrap();
+        prop_assert!(aggregated_signature.verify(&hash, &aggregated_key).is_ok());
+
+        // The aggregate of fewer signatures does not verify against the aggregate of all keys.
+        let partial_signature = BLS12381Signature::aggregate(&signature_refs[1..]).unwrap();
+        prop_assert!(partial_signature.verify(&hash, &aggregated_key).is_err());
+    }
+}
+
+#[test]
+fn test_aggregate_nothing() {
+    assert!(BLS12381PublicKey::aggregate(&[]).is_none());
+    assert!(BLS12381Signature::aggregate(&[]).is_none());
+}
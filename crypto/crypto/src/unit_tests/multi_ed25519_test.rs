@@ -0,0 +1,151 @@
+// Copyright (c) The Libra Core Contributors
+// SPDX-License-Identifier: Apache-2.0
+
+use crate::{
+    ed25519::{Ed25519PrivateKey, Ed25519PublicKey},
+    hash::HashValue,
+    multi_ed25519::{
+        MultiEd25519PrivateKey, MultiEd25519PublicKey, MultiEd25519Signature, MAX_NUM_OF_KEYS,
+    },
+    traits::*,
+};
+use core::convert::TryFrom;
+use proptest::prelude::*;
+use rand::{rngs::StdRng, SeedableRng};
+
+fn generate_private_keys(seed: [u8; 32], n: usize) -> Vec<Ed25519PrivateKey> {
+    let mut rng = StdRng::from_seed(seed);
+    (0..n)
+        .map(|_| Ed25519PrivateKey::generate_for_testing(&mut rng))
+        .collect()
+}
+
+fn multi_public_key(private_keys: &[Ed25519PrivateKey], threshold: u8) -> MultiEd25519PublicKey {
+    MultiEd25519PublicKey::new(
+        private_keys.iter().map(Ed25519PublicKey::from).collect(),
+        threshold,
+    )
+    .unwrap()
+}
+
+fn sign(
+    private_keys: &[Ed25519PrivateKey],
+    indexes: &[u8],
+    message: &HashValue,
+) -> MultiEd25519Signature {
+    MultiEd25519Signature::new(
+        indexes
+            .iter()
+            .map(|index| (private_keys[*index as usize].sign_message(message), *index))
+            .collect(),
+    )
+    .unwrap()
+}
+
+#[test]
+fn test_threshold_validity() {
+    let keys = generate_private_keys([0; 32], MAX_NUM_OF_KEYS + 1);
+    let public_keys: Vec<_> = keys.iter().map(Ed25519PublicKey::from).collect();
+
+    assert!(MultiEd25519PublicKey::new(public_keys[..3].to_vec(), 0).is_err());
+    assert!(MultiEd25519PublicKey::new(public_keys[..3].to_vec(), 4).is_err());
+    assert!(MultiEd25519PublicKey::new(public_keys.clone(), 1).is_err());
+    assert!(MultiEd25519PublicKey::new(public_keys[..MAX_NUM_OF_KEYS].to_vec(), 32).is_ok());
+    assert!(MultiEd25519PublicKey::new(public_keys[..3].to_vec(), 3).is_ok());
+}
+
+#[test]
+fn test_k_of_n_signatures() {
+    let keys = generate_private_keys([1; 32], 5);
+    let public_key = multi_public_key(&keys, 3);
+    let message = HashValue::random();
+
+    // Any 3 keys, in any order.
+    assert!(sign(&keys, &[0, 1, 2], &message)
+        .verify(&message, &public_key)
+        .is_ok());
+    assert!(sign(&keys, &[4, 0, 2], &message)
+        .verify(&message, &public_key)
+        .is_ok());
+    assert!(sign(&keys, &[0, 1, 3, 4], &message)
+        .verify(&message, &public_key)
+        .is_ok());
+
+    // Not enough signatures.
+    assert!(sign(&keys, &[1, 3], &message)
+        .verify(&message, &public_key)
+        .is_err());
+    // Signature of another message.
+    assert!(sign(&keys, &[1, 2, 3], &HashValue::random())
+        .verify(&message, &public_key)
+        .is_err());
+    // Signature claimed to be of another key.
+    let signature = MultiEd25519Signature::new(vec![
+        (keys[0].sign_message(&message), 0),
+        (keys[1].sign_message(&message), 2),
+        (keys[2].sign_message(&message), 1),
+    ])
+    .unwrap();
+    assert!(signature.verify(&message, &public_key).is_err());
+    // Signature of a key beyond the public key.
+    let other_keys = generate_private_keys([2; 32], 6);
+    assert!(sign(&other_keys, &[0, 1, 5], &message)
+        .verify(&message, &multi_public_key(&other_keys[..5], 3))
+        .is_err());
+}
+
+#[test]
+fn test_invalid_signature_assembly() {
+    let keys = generate_private_keys([3; 32], 2);
+    let message = HashValue::random();
+    let signature = keys[0].sign_message(&message);
+
+    assert!(MultiEd25519Signature::new(vec![]).is_err());
+    assert!(
+        MultiEd25519Signature::new(vec![(signature.clone(), 0), (signature.clone(), 0)]).is_err()
+    );
+    assert!(MultiEd25519Signature::new(vec![(signature, MAX_NUM_OF_KEYS as u8)]).is_err());
+}
+
+#[test]
+fn test_private_key_signs_with_threshold() {
+    let keys = generate_private_keys([4; 32], 4);
+    let private_key = MultiEd25519PrivateKey::new(keys, 2).unwrap();
+    let public_key: MultiEd25519PublicKey = (&private_key).into();
+    let message = HashValue::random();
+
+    let signature = private_key.sign_message(&message);
+    assert_eq!(signature.signatures().len(), 2);
+    assert_eq!(signature.bitmap(), [0b1100_0000, 0, 0, 0]);
+    assert!(signature.verify(&message, &public_key).is_ok());
+}
+
+proptest! {
+    #[test]
+    fn test_encoding_roundtrip(
+        seed in any::<[u8; 32]>(),
+        n in 1..=MAX_NUM_OF_KEYS,
+        threshold_seed in any::<usize>(),
+    ) {
+        let keys = generate_private_keys(seed, n);
+        let threshold = (threshold_seed % n + 1) as u8;
+        let public_key = multi_public_key(&keys, threshold);
+        let bytes = public_key.to_bytes();
+        prop_assert_eq!(bytes.len(), n * 32 + 1);
+        prop_assert_eq!(&MultiEd25519PublicKey::try_from(&bytes[..]).unwrap(), &public_key);
+        let encoded: Vec<u8> = lcs::to_bytes(&public_key).unwrap();
+        prop_assert_eq!(lcs::from_bytes::<MultiEd25519PublicKey>(&encoded).unwrap(), public_key);
+
+        let message = HashValue::random();
+        let indexes: Vec<u8> = (0..threshold).collect();
+        let signature = sign(&keys, &indexes, &message);
+        let bytes = signature.to_bytes();
+        prop_assert_eq!(&MultiEd25519Signature::try_from(&bytes[..]).unwrap(), &signature);
+        let encoded: Vec<u8> = lcs::to_bytes(&signature).unwrap();
+        prop_assert_eq!(lcs::from_bytes::<MultiEd25519Signature>(&encoded).unwrap(), signature);
+
+        let private_key = MultiEd25519PrivateKey::new(keys, threshold).unwrap();
+        let bytes = private_key.to_bytes();
+        prop_assert_eq!(MultiEd25519PrivateKey::try_from(&bytes[..]).unwrap(), private_key);
+    }
+}
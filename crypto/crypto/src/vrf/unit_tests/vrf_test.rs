@@ -1,7 +1,10 @@
 // Copyright (c) The Libra Core Contributors
 // SPDX-License-Identifier: Apache-2.0
 
-use crate::{hash::HashValue, unit_tests::uniform_keypair_strategy, vrf::ecvrf::*};
+use crate::{
+    hash::HashValue, traits::CryptoMaterialError, unit_tests::uniform_keypair_strategy,
+    vrf::ecvrf::*,
+};
 use core::convert::TryFrom;
 use curve25519_dalek::{
     constants::ED25519_BASEPOINT_POINT, edwards::CompressedEdwardsY,
@@ -177,6 +180,26 @@ fn test_output_from_proof() {
     }
 }
 
+#[test]
+fn test_malformed_proof() {
+    let pi = from_string!(Proof, TESTVECTORS[0].pi).to_bytes();
+    assert_eq!(
+        Proof::try_from(&pi[..PROOF_LENGTH - 1]).err(),
+        Some(CryptoMaterialError::WrongLengthError)
+    );
+    // About half of the y coordinates do not match any point of the curve.
+    let mut bad_gamma = [0u8; 32];
+    while CompressedEdwardsY(bad_gamma).decompress().is_some() {
+        bad_gamma[0] += 1;
+    }
+    let mut bad_pi = pi;
+    bad_pi[..32].copy_from_slice(&bad_gamma);
+    assert_eq!(
+        Proof::try_from(&bad_pi[..]).err(),
+        Some(CryptoMaterialError::DeserializationError)
+    );
+}
+
 proptest! {
     #[test]
     fn test_prove_and_verify(
@@ -0,0 +1,102 @@
+// Copyright (c) The Libra Core Contributors
+// SPDX-License-Identifier: Apache-2.0
+
+//! RemoteStorage is the persistent storage of safety rules for consensus to use when the
+//! consensus state and the consensus signing key are kept by the safety rules server in the
+//! secret service process. RemoteProposalSigner signs the proposals of consensus with the same
+//! key.
+
+use crate::proto::{
+    ConsensusStateRequest, ErrorCode, SafetyRulesServiceClient, SignProposalRequest,
+    SignResponse, SignTimeoutRequest, SignVoteRequest,
+};
+use consensus_types::{
+    block::ProposalSigner,
+    common::{Author, Round},
+    quorum_cert::QuorumCert,
+    vote_data::VoteData,
+};
+use crypto::ed25519::Ed25519Signature;
+use failure::prelude::*;
+use libra_types::ledger_info::LedgerInfo;
+use safety_rules::{ConsensusState, PersistentStorage};
+use std::convert::TryFrom;
+
+/// Persistent storage of safety rules forwarding all the requests to the safety rules server.
+pub struct RemoteStorage {
+    client: SafetyRulesServiceClient,
+}
+
+impl RemoteStorage {
+    /// Forwards the requests through `client`.
+    pub fn new(client: SafetyRulesServiceClient) -> Self {
+        Self { client }
+    }
+
+    fn into_signature(response: SignResponse) -> Result<Ed25519Signature> {
+        ensure!(
+            response.code() == ErrorCode::Success,
+            "Safety rules server failed to sign: {:?}",
+            response.code()
+        );
+        Ok(Ed25519Signature::try_from(&response.signature[..])?)
+    }
+}
+
+impl PersistentStorage for RemoteStorage {
+    fn consensus_state(&self) -> Result<ConsensusState> {
+        let response = self
+            .client
+            .get_consensus_state(&ConsensusStateRequest::default())?;
+        ensure!(
+            response.code() == ErrorCode::Success,
+            "Safety rules server failed to read the consensus state: {:?}",
+            response.code()
+        );
+        Ok(lcs::from_bytes(&response.consensus_state)?)
+    }
+
+    fn sign_vote(
+        &mut self,
+        vote_data: &VoteData,
+        ledger_info: &LedgerInfo,
+        quorum_cert: &QuorumCert,
+    ) -> Result<Ed25519Signature> {
+        let mut req = SignVoteRequest::default();
+        req.vote_data = lcs::to_bytes(vote_data)?;
+        req.ledger_info = lcs::to_bytes(ledger_info)?;
+        req.quorum_cert = lcs::to_bytes(quorum_cert)?;
+        Self::into_signature(self.client.sign_vote(&req)?)
+    }
+
+    fn sign_timeout(&mut self, round: Round) -> Result<Ed25519Signature> {
+        let mut req = SignTimeoutRequest::default();
+        req.round = round;
+        Self::into_signature(self.client.sign_timeout(&req)?)
+    }
+}
+
+/// Signs proposals through the safety rules server.
+pub struct RemoteProposalSigner {
+    author: Author,
+    client: SafetyRulesServiceClient,
+}
+
+impl RemoteProposalSigner {
+    /// Signs the proposals of `author` through `client`.
+    pub fn new(author: Author, client: SafetyRulesServiceClient) -> Self {
+        Self { author, client }
+    }
+}
+
+impl ProposalSigner for RemoteProposalSigner {
+    fn author(&self) -> Author {
+        self.author
+    }
+
+    fn sign_proposal(&self, block_data: &[u8]) -> Result<Ed25519Signature> {
+        let mut req = SignProposalRequest::default();
+        req.block_data = block_data.to_vec();
+        RemoteStorage::into_signature(self.client.sign_proposal(&req)?)
+    }
+}
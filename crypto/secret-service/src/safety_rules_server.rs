@@ -0,0 +1,190 @@
+// Copyright (c) The Libra Core Contributors
+// SPDX-License-Identifier: Apache-2.0
+
+//! The safety rules server keeps the consensus state of safety rules on disk next to the
+//! consensus signing key. It only signs a vote once the consensus state justifying it is
+//! persisted and allows it, so that a compromised consensus process cannot make the validator
+//! vote twice in a round. It also signs the proposals of the validator, at most one per round,
+//! hashing the block ids itself so that a proposal signature is never a vote signature.
+
+use crate::proto::{
+    ConsensusStateRequest, ConsensusStateResponse, ErrorCode, SafetyRulesService,
+    SignProposalRequest, SignResponse, SignTimeoutRequest, SignVoteRequest,
+};
+use consensus_types::{common::Round, quorum_cert::QuorumCert, vote_data::VoteData};
+use crypto::ed25519::Ed25519Signature;
+use failure::prelude::*;
+use grpc_helpers::provide_grpc_response;
+use libra_types::ledger_info::LedgerInfo;
+use logger::prelude::*;
+use safety_rules::{ConsensusState, OnDiskStorage, PersistentStorage};
+use std::sync::{Arc, Mutex};
+
+#[cfg(test)]
+#[path = "unit_tests/safety_rules_server_test.rs"]
+mod safety_rules_server_test;
+
+/// Safety rules server that persists the consensus state and signs votes on behalf of consensus.
+#[derive(Clone)]
+pub struct SafetyRulesServer {
+    storage: Arc<Mutex<OnDiskStorage>>,
+}
+
+/// SafetyRulesServer matches the API of the SafetyRulesService in proto/secret_service.proto but
+/// operates on the consensus structures.
+impl SafetyRulesServer {
+    /// Serves the consensus state persisted in `storage`.
+    pub fn new(storage: OnDiskStorage) -> Self {
+        Self {
+            storage: Arc::new(Mutex::new(storage)),
+        }
+    }
+
+    /// Returns the persisted consensus state.
+    pub fn consensus_state_inner(&self) -> Result<ConsensusState> {
+        self.storage
+            .lock()
+            .expect("[getting consensus state] acquire storage lock")
+            .consensus_state()
+    }
+
+    /// Persists the consensus state and signs the ledger info of the vote it justifies.
+    pub fn sign_vote_inner(
+        &self,
+        vote_data: &VoteData,
+        ledger_info: &LedgerInfo,
+        quorum_cert: &QuorumCert,
+    ) -> Result<Ed25519Signature> {
+        self.storage
+            .lock()
+            .expect("[signing vote] acquire storage lock")
+            .sign_vote(vote_data, ledger_info, quorum_cert)
+    }
+
+    /// Persists the timed out round if it is new and signs it for a timeout certificate.
+    pub fn sign_timeout_inner(&self, round: Round) -> Result<Ed25519Signature> {
+        self.storage
+            .lock()
+            .expect("[signing timeout] acquire storage lock")
+            .sign_timeout(round)
+    }
+
+    /// Signs the id of the proposal whose serialized block fields are `block_data`, if it is
+    /// newer than any proposal signed before.
+    pub fn sign_proposal_inner(&self, block_data: &[u8]) -> Result<Ed25519Signature> {
+        self.storage
+            .lock()
+            .expect("[signing proposal] acquire storage lock")
+            .sign_proposal(block_data)
+    }
+
+    fn decode_sign_vote_request(
+        req: &SignVoteRequest,
+    ) -> Result<(VoteData, LedgerInfo, QuorumCert)> {
+        Ok((
+            lcs::from_bytes(&req.vote_data)?,
+            lcs::from_bytes(&req.ledger_info)?,
+            lcs::from_bytes(&req.quorum_cert)?,
+        ))
+    }
+}
+
+/// SafetyRulesServer implements the proto trait SafetyRulesService.
+/// The methods below wrap around inner methods of SafetyRulesServer and operate on grpc's
+/// requests/responses.
+impl SafetyRulesService for SafetyRulesServer {
+    /// Returns the persisted consensus state answering a ConsensusStateRequest with a
+    /// ConsensusStateResponse.
+    fn get_consensus_state(
+        &mut self,
+        ctx: ::grpcio::RpcContext,
+        _req: ConsensusStateRequest,
+        sink: ::grpcio::UnarySink<ConsensusStateResponse>,
+    ) {
+        let mut response = ConsensusStateResponse::default();
+        match self
+            .consensus_state_inner()
+            .and_then(|state| Ok(lcs::to_bytes(&state)?))
+        {
+            Ok(consensus_state) => {
+                response.set_code(ErrorCode::Success);
+                response.consensus_state = consensus_state;
+            }
+            Err(e) => {
+                error!("Failed to read the consensus state: {}", e);
+                response.set_code(ErrorCode::Unspecified);
+            }
+        }
+        provide_grpc_response(Ok(response), ctx, sink);
+    }
+
+    /// Returns a signature of the ledger info of a vote answering a SignVoteRequest with a
+    /// SignResponse.
+    fn sign_vote(
+        &mut self,
+        ctx: ::grpcio::RpcContext,
+        req: SignVoteRequest,
+        sink: ::grpcio::UnarySink<SignResponse>,
+    ) {
+        let mut response = SignResponse::default();
+        match Self::decode_sign_vote_request(&req) {
+            Ok((vote_data, ledger_info, quorum_cert)) => {
+                match self.sign_vote_inner(&vote_data, &ledger_info, &quorum_cert) {
+                    Ok(signature) => {
+                        response.set_code(ErrorCode::Success);
+                        response.signature = signature.to_bytes().to_vec();
+                    }
+                    Err(e) => {
+                        warn!("Refused to sign vote {}: {}", vote_data, e);
+                        response.set_code(ErrorCode::Rejected);
+                    }
+                }
+            }
+            Err(_) => response.set_code(ErrorCode::InvalidParameters),
+        }
+        provide_grpc_response(Ok(response), ctx, sink);
+    }
+
+    /// Returns a signature of a round answering a SignTimeoutRequest with a SignResponse.
+    fn sign_timeout(
+        &mut self,
+        ctx: ::grpcio::RpcContext,
+        req: SignTimeoutRequest,
+        sink: ::grpcio::UnarySink<SignResponse>,
+    ) {
+        let mut response = SignResponse::default();
+        match self.sign_timeout_inner(req.round) {
+            Ok(signature) => {
+                response.set_code(ErrorCode::Success);
+                response.signature = signature.to_bytes().to_vec();
+            }
+            Err(e) => {
+                warn!("Refused to sign timeout at round {}: {}", req.round, e);
+                response.set_code(ErrorCode::Rejected);
+            }
+        }
+        provide_grpc_response(Ok(response), ctx, sink);
+    }
+
+    /// Returns a signature of the id of a proposal answering a SignProposalRequest with a
+    /// SignResponse.
+    fn sign_proposal(
+        &mut self,
+        ctx: ::grpcio::RpcContext,
+        req: SignProposalRequest,
+        sink: ::grpcio::UnarySink<SignResponse>,
+    ) {
+        let mut response = SignResponse::default();
+        match self.sign_proposal_inner(&req.block_data) {
+            Ok(signature) => {
+                response.set_code(ErrorCode::Success);
+                response.signature = signature.to_bytes().to_vec();
+            }
+            Err(e) => {
+                warn!("Refused to sign proposal: {}", e);
+                response.set_code(ErrorCode::Rejected);
+            }
+        }
+        provide_grpc_response(Ok(response), ctx, sink);
+    }
+}
@@ -6,13 +6,21 @@
 //! accepts connections on port node_config.secret_service.secret_service_port.
 //! The proto/secret_service.proto file shows the requests that the service accepts and the
 //! responses that it gives back. For an example on how to run the secret service see main.rs.
+//! If consensus is configured with the secret_service safety rules backend, the node also serves
+//! safety rules on port node_config.secret_service.safety_rules_port.
 
-use crate::{proto::create_secret_service, secret_service_server::SecretServiceServer};
-use config::config::NodeConfig;
+use crate::{
+    proto::{create_safety_rules_service, create_secret_service},
+    safety_rules_server::SafetyRulesServer,
+    secret_service_server::SecretServiceServer,
+};
+use config::config::{NodeConfig, SafetyRulesBackend};
 use debug_interface::{node_debug_service::NodeDebugService, proto::create_node_debug_interface};
 use failure::prelude::*;
 use grpc_helpers::spawn_service_thread;
+use libra_types::crypto_proxies::ValidatorSigner;
 use logger::prelude::*;
+use safety_rules::OnDiskStorage;
 use std::thread;
 
 #[cfg(test)]
@@ -22,12 +30,38 @@ mod secret_service_test;
 /// Secret service node is run a separate process and handles the secret keys.
 pub struct SecretServiceNode {
     node_config: NodeConfig,
+    safety_rules: Option<SafetyRulesServer>,
 }
 
 impl SecretServiceNode {
-    /// Instantiates the service with a config file.
-    pub fn new(node_config: NodeConfig) -> Self {
-        SecretServiceNode { node_config }
+    /// Instantiates the service with a config file. The consensus private key is moved out of
+    /// the config to the safety rules server if the node serves safety rules.
+    pub fn new(mut node_config: NodeConfig) -> Self {
+        let safety_rules = if node_config.consensus.get_safety_rules_backend()
+            == SafetyRulesBackend::SecretService
+        {
+            let private_key = node_config
+                .consensus
+                .consensus_keypair
+                .take_consensus_private()
+                .expect("Failed to move a Consensus private key from a NodeConfig");
+            let storage = OnDiskStorage::new(
+                ValidatorSigner::new(None, private_key),
+                node_config
+                    .consensus
+                    .consensus_peers
+                    .get_validator_verifier(),
+                node_config.get_safety_rules_file(),
+            )
+            .expect("Failed to open the safety rules storage");
+            Some(SafetyRulesServer::new(storage))
+        } else {
+            None
+        };
+        SecretServiceNode {
+            node_config,
+            safety_rules,
+        }
     }
 
     /// Starts the secret service
@@ -43,6 +77,15 @@ impl SecretServiceNode {
             "secret_service",
         );
 
+        let _safety_rules_handle = self.safety_rules.clone().map(|safety_rules| {
+            spawn_service_thread(
+                create_safety_rules_service(safety_rules),
+                self.node_config.secret_service.address.clone(),
+                self.node_config.secret_service.safety_rules_port,
+                "safety_rules",
+            )
+        });
+
         // Start Debug interface
         let debug_service = create_node_debug_interface(NodeDebugService::new());
         let _debug_handle = spawn_service_thread(
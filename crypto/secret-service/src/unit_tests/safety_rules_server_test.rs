@@ -0,0 +1,136 @@
+// Copyright (c) The Libra Core Contributors
+// SPDX-License-Identifier: Apache-2.0
+
+use crate::safety_rules_server::SafetyRulesServer;
+use consensus_types::{
+    block, block_info::BlockInfo, common, quorum_cert::QuorumCert, vote_data::VoteData,
+};
+use crypto::{
+    hash::{CryptoHash, HashValue},
+    traits::Signature,
+};
+use libra_tools::tempdir::TempPath;
+use libra_types::{
+    crypto_proxies::{LedgerInfoWithSignatures, ValidatorSigner, ValidatorVerifier},
+    ledger_info::LedgerInfo,
+};
+use safety_rules::{ConsensusState, OnDiskStorage};
+use std::{collections::BTreeMap, path::PathBuf};
+
+fn open_server(file_path: PathBuf) -> SafetyRulesServer {
+    let signer = ValidatorSigner::from_int(0);
+    let validator = ValidatorVerifier::new_single(signer.author(), signer.public_key());
+    SafetyRulesServer::new(OnDiskStorage::new(signer, validator, file_path).unwrap())
+}
+
+fn make_ledger_info(vote_data: &VoteData) -> LedgerInfo {
+    LedgerInfo::new(
+        0,
+        HashValue::zero(),
+        vote_data.hash(),
+        HashValue::zero(),
+        0,
+        0,
+        None,
+    )
+}
+
+/// Returns a vote for a proposal at `round`, and the quorum cert of its parent at `round - 1`.
+fn make_vote(round: u64) -> (VoteData, LedgerInfo, QuorumCert) {
+    let signer = ValidatorSigner::from_int(0);
+    let parent = BlockInfo::new(0, round - 1, HashValue::random(), HashValue::zero(), 0, 0);
+    let parent_vote_data = VoteData::new(
+        parent.clone(),
+        BlockInfo::new(0, 0, HashValue::random(), HashValue::zero(), 0, 0),
+    );
+    let parent_ledger_info = make_ledger_info(&parent_vote_data);
+    let mut signatures = BTreeMap::new();
+    signatures.insert(
+        signer.author(),
+        signer.sign_message(parent_ledger_info.hash()).unwrap(),
+    );
+    let quorum_cert = QuorumCert::new(
+        parent_vote_data,
+        LedgerInfoWithSignatures::new(parent_ledger_info, signatures),
+    );
+
+    let vote_data = VoteData::new(
+        BlockInfo::new(0, round, HashValue::random(), HashValue::zero(), 0, 0),
+        parent,
+    );
+    let ledger_info = make_ledger_info(&vote_data);
+    (vote_data, ledger_info, quorum_cert)
+}
+
+fn make_block_data(round: u64) -> Vec<u8> {
+    let mut block_data = lcs::to_bytes(&(0u64, 0u64, round)).unwrap();
+    block_data.extend_from_slice(b"other serialized block fields");
+    block_data
+}
+
+#[test]
+fn test_sign_votes_once_per_round() {
+    let tmp_dir = TempPath::new();
+    tmp_dir.create_as_dir().unwrap();
+    let file_path = tmp_dir.path().join("safety_rules.lcs");
+    let public_key = ValidatorSigner::from_int(0).public_key();
+    let server = open_server(file_path.clone());
+    assert_eq!(
+        server.consensus_state_inner().unwrap(),
+        ConsensusState::default()
+    );
+
+    let (vote_data, ledger_info, quorum_cert) = make_vote(1);
+    let signature = server
+        .sign_vote_inner(&vote_data, &ledger_info, &quorum_cert)
+        .unwrap();
+    assert!(signature.verify(&ledger_info.hash(), &public_key).is_ok());
+    assert_eq!(
+        server.consensus_state_inner().unwrap(),
+        ConsensusState::new(1, 0)
+    );
+
+    // Another proposal in the same round, even after a restart of the server.
+    let (vote_data, ledger_info, quorum_cert) = make_vote(1);
+    assert!(server
+        .sign_vote_inner(&vote_data, &ledger_info, &quorum_cert)
+        .is_err());
+    let server = open_server(file_path);
+    assert!(server
+        .sign_vote_inner(&vote_data, &ledger_info, &quorum_cert)
+        .is_err());
+
+    let (vote_data, ledger_info, quorum_cert) = make_vote(2);
+    assert!(server
+        .sign_vote_inner(&vote_data, &ledger_info, &quorum_cert)
+        .is_ok());
+
+    let signature = server.sign_timeout_inner(2).unwrap();
+    assert!(signature
+        .verify(&common::round_hash(2), &public_key)
+        .is_ok());
+    // No timeout in a round older than the last vote.
+    assert!(server.sign_timeout_inner(1).is_err());
+}
+
+#[test]
+fn test_sign_proposal() {
+    let tmp_dir = TempPath::new();
+    tmp_dir.create_as_dir().unwrap();
+    let file_path = tmp_dir.path().join("safety_rules.lcs");
+    let public_key = ValidatorSigner::from_int(0).public_key();
+    let server = open_server(file_path.clone());
+
+    let block_data = make_block_data(1);
+    let signature = server.sign_proposal_inner(&block_data).unwrap();
+    assert!(signature
+        .verify(&block::block_id(&block_data), &public_key)
+        .is_ok());
+
+    // A single proposal per round, even after a restart of the server.
+    assert!(server.sign_proposal_inner(&make_block_data(1)).is_err());
+    assert!(server.sign_proposal_inner(&make_block_data(2)).is_ok());
+    let server = open_server(file_path);
+    assert!(server.sign_proposal_inner(&make_block_data(2)).is_err());
+    assert!(server.sign_proposal_inner(b"too short").is_err());
+}
@@ -205,9 +205,35 @@ where
                     warn!("Failed to send execute and commit chunk response.");
                 }
             }
+            Command::ReloadCommittedTrees { resp_sender } => {
+                let res = self.reload_committed_trees();
+                if let Err(_err) = resp_sender.send(res) {
+                    warn!("Failed to send reload committed trees response.");
+                }
+            }
         }
     }
 
+    /// Reloads the committed trees and timestamp from the latest startup info in storage.
+    fn reload_committed_trees(&mut self) -> Result<()> {
+        let info = self
+            .storage_read_client
+            .get_startup_info()?
+            .ok_or_else(|| format_err!("Startup info is empty."))?;
+        *self.committed_trees.lock().unwrap() = ExecutedTrees::new(
+            info.account_state_root_hash,
+            info.ledger_frozen_subtree_hashes,
+            info.latest_version + 1,
+        );
+        self.committed_timestamp_usecs = info.ledger_info.timestamp_usecs();
+        self.mode = Mode::Normal;
+        info!(
+            "Reloaded committed trees from storage at version {}.",
+            info.latest_version
+        );
+        Ok(())
+    }
+
     /// Verifies the transactions based on the provided proofs and ledger info. If the transactions
     /// are valid, executes them and commits immediately if execution results match the proofs.
     fn execute_and_commit_chunk(&mut self, chunk: Chunk) -> Result<()> {
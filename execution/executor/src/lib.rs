@@ -459,6 +459,28 @@ where
         resp_receiver
     }
 
+    /// Reloads the latest committed trees from storage. This is used after storage has been
+    /// restored from a state snapshot, which happens without going through the executor.
+    pub fn reload_committed_trees(&self) -> oneshot::Receiver<Result<()>> {
+        debug!("Received request to reload committed trees.");
+
+        let (resp_sender, resp_receiver) = oneshot::channel();
+        match self
+            .command_sender
+            .lock()
+            .expect("Failed to lock mutex.")
+            .as_ref()
+        {
+            Some(sender) => sender
+                .send(Command::ReloadCommittedTrees { resp_sender })
+                .expect("Did block processor thread panic?"),
+            None => resp_sender
+                .send(Err(format_err!("Executor is shutting down.")))
+                .expect("Failed to send error message."),
+        }
+        resp_receiver
+    }
+
     pub fn committed_trees(&self) -> ExecutedTrees {
         (*self.committed_trees.lock().unwrap()).clone()
     }
@@ -536,6 +558,9 @@ enum Command {
         chunk: Chunk,
         resp_sender: oneshot::Sender<Result<()>>,
     },
+    ReloadCommittedTrees {
+        resp_sender: oneshot::Sender<Result<()>>,
+    },
 }
 
 #[derive(Clone, Debug)]
@@ -0,0 +1,82 @@
+// Copyright (c) The Libra Core Contributors
+// SPDX-License-Identifier: Apache-2.0
+
+use bytecode_verifier::{verify_module_upgrade, VerifiedModule};
+use libra_types::vm_error::{StatusCode, VMStatus};
+use vm::{
+    errors::verification_error,
+    file_format::{basic_test_module, CodeUnit, CompiledModuleMut, SignatureToken},
+    IndexKind,
+};
+
+fn verified(module: CompiledModuleMut) -> VerifiedModule {
+    VerifiedModule::bypass_verifier_DANGEROUS_FOR_TESTING_ONLY(module.freeze().unwrap())
+}
+
+fn upgrade_errors(old_module: CompiledModuleMut, new_module: CompiledModuleMut) -> Vec<VMStatus> {
+    verify_module_upgrade(&verified(old_module), &verified(new_module))
+}
+
+fn incompatibility(kind: IndexKind) -> Vec<VMStatus> {
+    vec![verification_error(
+        kind,
+        0,
+        StatusCode::INCOMPATIBLE_MODULE_UPGRADE,
+    )]
+}
+
+fn public_test_module() -> CompiledModuleMut {
+    let mut module = basic_test_module();
+    module.function_defs[0].flags = CodeUnit::PUBLIC;
+    module
+}
+
+#[test]
+fn unchanged_module() {
+    assert!(upgrade_errors(public_test_module(), public_test_module()).is_empty());
+}
+
+#[test]
+fn publish_private_function() {
+    assert!(upgrade_errors(basic_test_module(), public_test_module()).is_empty());
+}
+
+#[test]
+fn hide_public_function() {
+    assert_eq!(
+        upgrade_errors(public_test_module(), basic_test_module()),
+        incompatibility(IndexKind::FunctionDefinition)
+    );
+}
+
+#[test]
+fn change_public_function_signature() {
+    let mut new_module = public_test_module();
+    new_module.function_signatures[0]
+        .arg_types
+        .push(SignatureToken::U64);
+    assert_eq!(
+        upgrade_errors(public_test_module(), new_module),
+        incompatibility(IndexKind::FunctionDefinition)
+    );
+}
+
+#[test]
+fn change_field_type() {
+    let mut new_module = public_test_module();
+    new_module.type_signatures[0].0 = SignatureToken::Bool;
+    assert_eq!(
+        upgrade_errors(public_test_module(), new_module),
+        incompatibility(IndexKind::StructDefinition)
+    );
+}
+
+#[test]
+fn change_struct_kind() {
+    let mut new_module = public_test_module();
+    new_module.struct_handles[0].is_nominal_resource = true;
+    assert_eq!(
+        upgrade_errors(public_test_module(), new_module),
+        incompatibility(IndexKind::StructDefinition)
+    );
+}
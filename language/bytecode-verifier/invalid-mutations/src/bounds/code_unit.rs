@@ -289,9 +289,10 @@ impl<'a> ApplyCodeUnitBoundsContext<'a> {
 
                     // List out the other options explicitly so there's a compile error if a new
                     // bytecode gets added.
-                    FreezeRef | Pop | Ret | LdConst(_) | LdTrue | LdFalse | ReadRef | WriteRef
-                    | Add | Sub | Mul | Mod | Div | BitOr | BitAnd | Xor | Or | And | Not | Eq
-                    | Neq | Lt | Gt | Le | Ge | Abort | GetTxnGasUnitPrice | GetTxnMaxGasUnits
+                    FreezeRef | Pop | Ret | LdU8(_) | LdConst(_) | LdU128(_) | CastU8
+                    | CastU64 | CastU128 | LdTrue | LdFalse | ReadRef | WriteRef | Add | Sub
+                    | Mul | Mod | Div | BitOr | BitAnd | Xor | Or | And | Not | Eq | Neq | Lt
+                    | Gt | Le | Ge | Abort | GetTxnGasUnitPrice | GetTxnMaxGasUnits
                     | GetGasRemaining | GetTxnSenderAddress | CreateAccount
                     | GetTxnSequenceNumber | GetTxnPublicKey => {
                         panic!("Bytecode has no internal index: {:?}", code[bytecode_idx])
@@ -334,9 +335,10 @@ fn is_interesting(bytecode: &Bytecode) -> bool {
 
         // List out the other options explicitly so there's a compile error if a new
         // bytecode gets added.
-        FreezeRef | Pop | Ret | LdConst(_) | LdTrue | LdFalse | ReadRef | WriteRef | Add | Sub
-        | Mul | Mod | Div | BitOr | BitAnd | Xor | Or | And | Not | Eq | Neq | Lt | Gt | Le
-        | Ge | Abort | GetTxnGasUnitPrice | GetTxnMaxGasUnits | GetGasRemaining
-        | GetTxnSenderAddress | CreateAccount | GetTxnSequenceNumber | GetTxnPublicKey => false,
+        FreezeRef | Pop | Ret | LdU8(_) | LdConst(_) | LdU128(_) | CastU8 | CastU64 | CastU128
+        | LdTrue | LdFalse | ReadRef | WriteRef | Add | Sub | Mul | Mod | Div | BitOr | BitAnd
+        | Xor | Or | And | Not | Eq | Neq | Lt | Gt | Le | Ge | Abort | GetTxnGasUnitPrice
+        | GetTxnMaxGasUnits | GetGasRemaining | GetTxnSenderAddress | CreateAccount
+        | GetTxnSequenceNumber | GetTxnPublicKey => false,
     }
 }
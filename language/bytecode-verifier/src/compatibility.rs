@@ -0,0 +1,112 @@
+// Copyright (c) The Libra Core Contributors
+// SPDX-License-Identifier: Apache-2.0
+
+//! This module implements the check that a module can replace the module published under the same
+//! name. The modules linking against the published module must remain valid, and so must the
+//! resources and values of the structs it declares that are stored on chain:
+//! - every struct keeps its kind, type formals and fields, in the same order and with the same
+//!   types;
+//! - every public function remains public with the same signature.
+//!
+//! Structs and functions may be added and private functions may change freely.
+use crate::VerifiedModule;
+use libra_types::vm_error::{StatusCode, VMStatus};
+use vm::{
+    access::ModuleAccess,
+    errors::verification_error,
+    file_format::CompiledModule,
+    resolver::Resolver,
+    views::{ModuleView, StructDefinitionView, ViewInternals},
+    IndexKind,
+};
+
+/// Verifies that `new_module` is a compatible upgrade of `old_module`. Each returned error is
+/// located at the definition of `old_module` that `new_module` breaks.
+pub fn verify_module_upgrade(
+    old_module: &VerifiedModule,
+    new_module: &VerifiedModule,
+) -> Vec<VMStatus> {
+    if old_module.self_id() != new_module.self_id() {
+        return vec![verification_error(
+            IndexKind::ModuleHandle,
+            CompiledModule::IMPLEMENTED_MODULE_INDEX as usize,
+            StatusCode::INCOMPATIBLE_MODULE_UPGRADE,
+        )];
+    }
+
+    // Types are compared in the context of the new module.
+    let resolver = Resolver::new(new_module);
+    let old_module_view = ModuleView::new(old_module);
+    let new_module_view = ModuleView::new(new_module);
+    let mut errors = vec![];
+
+    for (idx, old_struct) in old_module_view.structs().enumerate() {
+        let compatible = match new_module_view.struct_definition(old_struct.name()) {
+            Some(new_struct) => {
+                struct_layout_unchanged(&resolver, old_module, &old_struct, new_struct)
+            }
+            None => false,
+        };
+        if !compatible {
+            errors.push(verification_error(
+                IndexKind::StructDefinition,
+                idx,
+                StatusCode::INCOMPATIBLE_MODULE_UPGRADE,
+            ));
+        }
+    }
+
+    for (idx, old_function) in old_module_view.functions().enumerate() {
+        if !old_function.is_public() {
+            continue;
+        }
+        let compatible = match new_module_view.function_definition(old_function.name()) {
+            Some(new_function) => {
+                new_function.is_public()
+                    && resolver
+                        .import_function_signature(old_module, old_function.signature().as_inner())
+                        .map_or(false, |signature| {
+                            signature == *new_function.signature().as_inner()
+                        })
+            }
+            None => false,
+        };
+        if !compatible {
+            errors.push(verification_error(
+                IndexKind::FunctionDefinition,
+                idx,
+                StatusCode::INCOMPATIBLE_MODULE_UPGRADE,
+            ));
+        }
+    }
+
+    errors
+}
+
+fn struct_layout_unchanged(
+    resolver: &Resolver,
+    old_module: &VerifiedModule,
+    old_struct: &StructDefinitionView<VerifiedModule>,
+    new_struct: &StructDefinitionView<VerifiedModule>,
+) -> bool {
+    if old_struct.is_nominal_resource() != new_struct.is_nominal_resource()
+        || old_struct.type_formals() != new_struct.type_formals()
+    {
+        return false;
+    }
+    match (old_struct.fields(), new_struct.fields()) {
+        (None, None) => true,
+        (Some(old_fields), Some(new_fields)) => {
+            let old_fields: Vec<_> = old_fields.collect();
+            let new_fields: Vec<_> = new_fields.collect();
+            old_fields.len() == new_fields.len()
+                && old_fields.iter().zip(&new_fields).all(|(old_field, new_field)| {
+                    old_field.name() == new_field.name()
+                        && resolver
+                            .import_signature_token(old_module, old_field.signature_token())
+                            .map_or(false, |token| token == *new_field.signature_token())
+                })
+        }
+        _ => false,
+    }
+}
@@ -9,6 +9,7 @@ pub mod abstract_state;
 pub mod acquires_list_verifier;
 pub mod check_duplication;
 pub mod code_unit_verifier;
+pub mod compatibility;
 pub mod control_flow_graph;
 pub mod instantiation_loops;
 pub mod nonce;
@@ -24,6 +25,7 @@ pub mod verifier;
 
 pub use check_duplication::DuplicationChecker;
 pub use code_unit_verifier::CodeUnitVerifier;
+pub use compatibility::verify_module_upgrade;
 pub use resources::ResourceTransitiveChecker;
 pub use signature::SignatureChecker;
 pub use stack_usage_verifier::StackUsageVerifier;
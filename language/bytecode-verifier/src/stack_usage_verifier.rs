@@ -84,7 +84,9 @@ impl<'a> StackUsageVerifier<'a> {
             | Bytecode::StLoc(_) => (1, 0),
 
             // Instructions that push, but don't pop
-            Bytecode::LdConst(_)
+            Bytecode::LdU8(_)
+            | Bytecode::LdConst(_)
+            | Bytecode::LdU128(_)
             | Bytecode::LdAddr(_)
             | Bytecode::LdStr(_)
             | Bytecode::LdTrue
@@ -102,7 +104,10 @@ impl<'a> StackUsageVerifier<'a> {
             | Bytecode::GetTxnSenderAddress => (0, 1),
 
             // Instructions that pop and push once
-            Bytecode::Not
+            Bytecode::CastU8
+            | Bytecode::CastU64
+            | Bytecode::CastU128
+            | Bytecode::Not
             | Bytecode::FreezeRef
             | Bytecode::ReadRef
             | Bytecode::Exists(_, _)
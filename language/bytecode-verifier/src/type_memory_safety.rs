@@ -500,6 +500,14 @@ impl<'a> TypeAndMemorySafetyAnalysis<'a> {
                 self.borrow_field(state, offset, false, *field_definition_index)
             }
 
+            Bytecode::LdU8(_) => {
+                self.stack.push(StackAbstractValue {
+                    signature: SignatureToken::U8,
+                    value: AbstractValue::full_value(Kind::Unrestricted),
+                });
+                Ok(())
+            }
+
             Bytecode::LdConst(_) => {
                 self.stack.push(StackAbstractValue {
                     signature: SignatureToken::U64,
@@ -508,6 +516,14 @@ impl<'a> TypeAndMemorySafetyAnalysis<'a> {
                 Ok(())
             }
 
+            Bytecode::LdU128(_) => {
+                self.stack.push(StackAbstractValue {
+                    signature: SignatureToken::U128,
+                    value: AbstractValue::full_value(Kind::Unrestricted),
+                });
+                Ok(())
+            }
+
             Bytecode::LdAddr(_) => {
                 self.stack.push(StackAbstractValue {
                     signature: SignatureToken::Address,
@@ -830,6 +846,27 @@ impl<'a> TypeAndMemorySafetyAnalysis<'a> {
                 }
             }
 
+            Bytecode::CastU8 | Bytecode::CastU64 | Bytecode::CastU128 => {
+                let operand = self.stack.pop().unwrap();
+                if operand.signature.is_integer() {
+                    let signature = match bytecode {
+                        Bytecode::CastU8 => SignatureToken::U8,
+                        Bytecode::CastU64 => SignatureToken::U64,
+                        _ => SignatureToken::U128,
+                    };
+                    self.stack.push(StackAbstractValue {
+                        signature,
+                        value: AbstractValue::full_value(Kind::Unrestricted),
+                    });
+                    Ok(())
+                } else {
+                    Err(err_at_offset(
+                        StatusCode::INTEGER_OP_TYPE_MISMATCH_ERROR,
+                        offset,
+                    ))
+                }
+            }
+
             Bytecode::Add
             | Bytecode::Sub
             | Bytecode::Mul
@@ -840,11 +877,9 @@ impl<'a> TypeAndMemorySafetyAnalysis<'a> {
             | Bytecode::Xor => {
                 let operand1 = self.stack.pop().unwrap();
                 let operand2 = self.stack.pop().unwrap();
-                if operand1.signature == SignatureToken::U64
-                    && operand2.signature == SignatureToken::U64
-                {
+                if operand1.signature.is_integer() && operand1.signature == operand2.signature {
                     self.stack.push(StackAbstractValue {
-                        signature: SignatureToken::U64,
+                        signature: operand1.signature,
                         value: AbstractValue::full_value(Kind::Unrestricted),
                     });
                     Ok(())
@@ -934,9 +969,7 @@ impl<'a> TypeAndMemorySafetyAnalysis<'a> {
             Bytecode::Lt | Bytecode::Gt | Bytecode::Le | Bytecode::Ge => {
                 let operand1 = self.stack.pop().unwrap();
                 let operand2 = self.stack.pop().unwrap();
-                if operand1.signature == SignatureToken::U64
-                    && operand2.signature == SignatureToken::U64
-                {
+                if operand1.signature.is_integer() && operand1.signature == operand2.signature {
                     self.stack.push(StackAbstractValue {
                         signature: SignatureToken::Bool,
                         value: AbstractValue::full_value(Kind::Unrestricted),
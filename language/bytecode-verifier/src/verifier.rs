@@ -16,14 +16,14 @@ use std::{collections::BTreeMap, fmt};
 use vm::{
     access::{ModuleAccess, ScriptAccess},
     errors::{append_err_info, verification_error},
-    file_format::{CompiledModule, CompiledProgram, CompiledScript},
+    file_format::{CompiledModule, CompiledProgram, CompiledScript, SignatureToken},
     resolver::Resolver,
     views::{ModuleView, ViewInternals},
     IndexKind,
 };
 use vm_runtime_types::{
     native_functions::dispatch::dispatch_native_function,
-    native_structs::dispatch::dispatch_native_struct,
+    native_structs::dispatch::{dispatch_native_struct, is_native_vector},
 };
 
 /// A program that has been verified for internal consistency.
@@ -310,13 +310,32 @@ pub fn verify_main_signature(script: &CompiledScript) -> Vec<VMStatus> {
         return vec![VMStatus::new(StatusCode::INVALID_MAIN_FUNCTION_SIGNATURE)];
     }
     for arg_type in &function_signature.arg_types {
-        if !arg_type.is_primitive() {
+        if !is_valid_main_arg_type(script, arg_type) {
             return vec![VMStatus::new(StatusCode::INVALID_MAIN_FUNCTION_SIGNATURE)];
         }
     }
     vec![]
 }
 
+/// The main function of a script can take primitive values and (possibly nested) native vectors
+/// of them, as these are the types a transaction argument can have.
+fn is_valid_main_arg_type(script: &CompiledScript, ty: &SignatureToken) -> bool {
+    match ty {
+        SignatureToken::Struct(idx, type_actuals) => {
+            let handle = script.struct_handle_at(*idx);
+            let module_handle = script.module_handle_at(handle.module);
+            let module_id = ModuleId::new(
+                *script.address_at(module_handle.address),
+                script.identifier_at(module_handle.name).to_owned(),
+            );
+            is_native_vector(&module_id, script.identifier_at(handle.name))
+                && type_actuals.len() == 1
+                && is_valid_main_arg_type(script, &type_actuals[0])
+        }
+        ty => ty.is_primitive(),
+    }
+}
+
 /// Verification of a module in isolation (using `VerifiedModule::new`) trusts that struct and
 /// function handles not implemented in the module are declared correctly. The following procedure
 /// justifies this trust by checking that these declarations match the definitions in the module
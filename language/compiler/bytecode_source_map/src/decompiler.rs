@@ -0,0 +1,1222 @@
+// Copyright (c) The Libra Core Contributors
+// SPDX-License-Identifier: Apache-2.0
+
+//! Decompiles a `CompiledModule` back to Move IR source code.
+//!
+//! The declarations of the module are read from its tables. Function bodies are rebuilt by
+//! symbolically executing the bytecode: the values an instruction pushes on the stack become
+//! expressions, and the instructions consuming the values of a whole statement (stores, pops,
+//! returns, aborts and branches) end a command. The loops of a function are found in its control
+//! flow graph, and the `if`, `while` and `loop` statements are reconstructed from the branches the
+//! IR compiler generates for them. Bytecode that can't be expressed in the IR, e.g. a branch into
+//! the middle of a loop, is reported as an error.
+//!
+//! Compiling the decompiled source with the IR compiler yields a module with the same
+//! declarations and code, although the tables of the module may be ordered differently.
+
+use crate::mapping::SourceMapping;
+use crate::source_map::SourceName;
+use bytecode_verifier::control_flow_graph::{ControlFlowGraph, VMControlFlowGraph};
+use failure::prelude::*;
+use libra_types::account_address::AccountAddress;
+use std::cell::RefCell;
+use std::collections::BTreeMap;
+use std::fmt;
+use std::rc::Rc;
+use vm::access::ModuleAccess;
+use vm::file_format::{
+    Bytecode, CodeOffset, CompiledModule, FieldDefinitionIndex, FunctionDefinition,
+    FunctionDefinitionIndex, Kind, LocalIndex, LocalsSignatureIndex, SignatureToken,
+    StructDefinitionIndex, StructFieldInformation, StructHandleIndex, TableIndex,
+};
+
+pub struct Decompiler<Location: Clone + Eq + Default> {
+    source_mapper: SourceMapping<Location>,
+    // The name the code refers to each module handle with: `Self` for the module itself, and the
+    // module name or an alias for the imported modules.
+    module_aliases: Vec<String>,
+}
+
+impl<Location: Clone + Eq + Default> Decompiler<Location> {
+    pub fn new(source_mapper: SourceMapping<Location>) -> Self {
+        let module = &source_mapper.bytecode;
+        let names: Vec<&str> = module
+            .module_handles()
+            .iter()
+            .map(|handle| module.identifier_at(handle.name).as_str())
+            .collect();
+        let module_aliases = names
+            .iter()
+            .enumerate()
+            .map(|(idx, name)| {
+                if idx == CompiledModule::IMPLEMENTED_MODULE_INDEX as usize {
+                    "Self".to_string()
+                } else if names.iter().filter(|other| *other == name).count() > 1 {
+                    // Modules with the same name published under different addresses.
+                    format!("{}{}", name, idx)
+                } else {
+                    name.to_string()
+                }
+            })
+            .collect();
+        Self {
+            source_mapper,
+            module_aliases,
+        }
+    }
+
+    //***************************************************************************
+    // Helpers
+    //***************************************************************************
+
+    fn module(&self) -> &CompiledModule {
+        &self.source_mapper.bytecode
+    }
+
+    fn type_parameter_name(type_params: &[SourceName<Location>], idx: usize) -> String {
+        type_params
+            .get(idx)
+            .map_or_else(|| format!("Ty{}", idx), |(name, _)| name.to_string())
+    }
+
+    fn type_formals(type_params: &[SourceName<Location>], kinds: &[Kind]) -> String {
+        if kinds.is_empty() {
+            return "".to_string();
+        }
+        let type_formals: Vec<String> = kinds
+            .iter()
+            .enumerate()
+            .map(|(idx, kind)| {
+                let name = Self::type_parameter_name(type_params, idx);
+                match kind {
+                    Kind::All => name,
+                    Kind::Resource => format!("{}: resource", name),
+                    Kind::Unrestricted => format!("{}: unrestricted", name),
+                }
+            })
+            .collect();
+        format!("<{}>", type_formals.join(", "))
+    }
+
+    // Type parameters are named in the context of the function or struct definition they are
+    // used in.
+    fn signature_token(
+        &self,
+        sig_tok: &SignatureToken,
+        type_params: &[SourceName<Location>],
+    ) -> Result<String> {
+        Ok(match sig_tok {
+            SignatureToken::Bool => "bool".to_string(),
+            SignatureToken::U8 => "u8".to_string(),
+            SignatureToken::U64 => "u64".to_string(),
+            SignatureToken::U128 => "u128".to_string(),
+            SignatureToken::String => bail!("The string type can't be expressed in Move IR"),
+            SignatureToken::ByteArray => "bytearray".to_string(),
+            SignatureToken::Address => "address".to_string(),
+            SignatureToken::Struct(struct_handle_idx, type_actuals) => format!(
+                "{}{}",
+                self.qualified_struct_name(*struct_handle_idx),
+                self.type_actuals(type_actuals, type_params)?
+            ),
+            SignatureToken::Reference(sig_tok) => {
+                format!("&{}", self.signature_token(sig_tok, type_params)?)
+            }
+            SignatureToken::MutableReference(sig_tok) => {
+                format!("&mut {}", self.signature_token(sig_tok, type_params)?)
+            }
+            SignatureToken::TypeParameter(idx) => {
+                Self::type_parameter_name(type_params, *idx as usize)
+            }
+        })
+    }
+
+    fn type_actuals(
+        &self,
+        type_actuals: &[SignatureToken],
+        type_params: &[SourceName<Location>],
+    ) -> Result<String> {
+        if type_actuals.is_empty() {
+            return Ok("".to_string());
+        }
+        let type_actuals = type_actuals
+            .iter()
+            .map(|sig_tok| self.signature_token(sig_tok, type_params))
+            .collect::<Result<Vec<String>>>()?;
+        Ok(format!("<{}>", type_actuals.join(", ")))
+    }
+
+    fn qualified_struct_name(&self, struct_handle_idx: StructHandleIndex) -> String {
+        let struct_handle = self.module().struct_handle_at(struct_handle_idx);
+        format!(
+            "{}.{}",
+            self.module_aliases[struct_handle.module.0 as usize],
+            self.module().identifier_at(struct_handle.name)
+        )
+    }
+
+    // Structs are packed, unpacked and stored by the module declaring them, which refers to them by
+    // their unqualified name.
+    fn struct_instance(
+        &self,
+        struct_def_idx: StructDefinitionIndex,
+        type_actuals_idx: LocalsSignatureIndex,
+        type_params: &[SourceName<Location>],
+    ) -> Result<String> {
+        let module = self.module();
+        let struct_handle_idx = module.struct_def_at(struct_def_idx).struct_handle;
+        let struct_handle = module.struct_handle_at(struct_handle_idx);
+        Ok(format!(
+            "{}{}",
+            module.identifier_at(struct_handle.name),
+            self.type_actuals(&module.locals_signature_at(type_actuals_idx).0, type_params)?
+        ))
+    }
+
+    fn field_name(&self, field_def_idx: FieldDefinitionIndex) -> String {
+        let module = self.module();
+        module
+            .identifier_at(module.field_def_at(field_def_idx).name)
+            .to_string()
+    }
+
+    fn field_names(&self, struct_def_idx: StructDefinitionIndex) -> Result<Vec<String>> {
+        match self.module().struct_def_at(struct_def_idx).field_information {
+            StructFieldInformation::Native => bail!("The fields of a native struct are unknown"),
+            StructFieldInformation::Declared {
+                field_count,
+                fields,
+            } => Ok((fields.0..fields.0 + field_count)
+                .map(|idx| self.field_name(FieldDefinitionIndex(idx)))
+                .collect()),
+        }
+    }
+
+    //***************************************************************************
+    // Decompilers
+    //***************************************************************************
+
+    pub fn decompile_struct_def(&self, struct_def_idx: StructDefinitionIndex) -> Result<String> {
+        let module = self.module();
+        if struct_def_idx.0 as usize >= module.struct_defs().len() {
+            bail!("Invalid struct definition index supplied when decompiling struct")
+        }
+        let struct_definition = module.struct_def_at(struct_def_idx);
+        let struct_handle = module.struct_handle_at(struct_definition.struct_handle);
+        let type_params = &self
+            .source_mapper
+            .source_map
+            .get_struct_source_map(struct_def_idx)?
+            .type_parameters;
+
+        let nominal_name = if struct_handle.is_nominal_resource {
+            "resource"
+        } else {
+            "struct"
+        };
+        let declaration = format!(
+            "{} {}{}",
+            nominal_name,
+            module.identifier_at(struct_handle.name),
+            Self::type_formals(type_params, &struct_handle.type_formals)
+        );
+
+        match struct_definition.field_information {
+            StructFieldInformation::Native => Ok(format!("    native {};\n", declaration)),
+            StructFieldInformation::Declared {
+                field_count,
+                fields,
+            } => {
+                let mut struct_def = format!("    {} {{\n", declaration);
+                for idx in fields.0..fields.0 + field_count {
+                    let field_definition = module.field_def_at(FieldDefinitionIndex(idx));
+                    let type_sig = module.type_signature_at(field_definition.signature);
+                    struct_def.push_str(&format!(
+                        "        {}: {},\n",
+                        module.identifier_at(field_definition.name),
+                        self.signature_token(&type_sig.0, type_params)?
+                    ));
+                }
+                struct_def.push_str("    }\n");
+                Ok(struct_def)
+            }
+        }
+    }
+
+    pub fn decompile_function_def(
+        &self,
+        function_definition_index: FunctionDefinitionIndex,
+    ) -> Result<String> {
+        let module = self.module();
+        if function_definition_index.0 as usize >= module.function_defs().len() {
+            bail!("Invalid function definition index supplied when decompiling function")
+        }
+        let function_definition = module.function_def_at(function_definition_index);
+        let function_handle = module.function_handle_at(function_definition.function);
+        let function_signature = module.function_signature_at(function_handle.signature);
+        let function_source_map = self
+            .source_mapper
+            .source_map
+            .get_function_source_map(function_definition_index)?;
+        let type_params = &function_source_map.type_parameters;
+
+        let locals = if function_definition.is_native() {
+            &function_signature.arg_types
+        } else {
+            &module.locals_signature_at(function_definition.code.locals).0
+        };
+        let local_names: Vec<String> = (0..locals.len())
+            .map(|idx| {
+                function_source_map
+                    .locals
+                    .get(idx)
+                    .map_or_else(|| format!("loc{}", idx), |(name, _)| name.to_string())
+            })
+            .collect();
+        let arg_count = function_signature.arg_types.len();
+        if locals.len() < arg_count {
+            bail!("The locals of a function don't include its arguments")
+        }
+
+        let args = locals[..arg_count]
+            .iter()
+            .zip(&local_names)
+            .map(|(sig_tok, name)| {
+                Ok(format!(
+                    "{}: {}",
+                    name,
+                    self.signature_token(sig_tok, type_params)?
+                ))
+            })
+            .collect::<Result<Vec<String>>>()?;
+        let mut function_def = format!(
+            "    {native}{public}{name}{type_formals}({args})",
+            native = if function_definition.is_native() {
+                "native "
+            } else {
+                ""
+            },
+            public = if function_definition.is_public() {
+                "public "
+            } else {
+                ""
+            },
+            name = module.identifier_at(function_handle.name),
+            type_formals = Self::type_formals(type_params, &function_signature.type_formals),
+            args = args.join(", "),
+        );
+        if !function_signature.return_types.is_empty() {
+            let return_types = function_signature
+                .return_types
+                .iter()
+                .map(|sig_tok| self.signature_token(sig_tok, type_params))
+                .collect::<Result<Vec<String>>>()?;
+            function_def.push_str(&format!(": {}", return_types.join(" * ")));
+        }
+        if !function_definition.acquires_global_resources.is_empty() {
+            let acquires: Vec<String> = function_definition
+                .acquires_global_resources
+                .iter()
+                .map(|struct_def_idx| {
+                    let struct_handle_idx = module.struct_def_at(*struct_def_idx).struct_handle;
+                    module
+                        .identifier_at(module.struct_handle_at(struct_handle_idx).name)
+                        .to_string()
+                })
+                .collect();
+            function_def.push_str(&format!(" acquires {}", acquires.join(", ")));
+        }
+        if function_definition.is_native() {
+            function_def.push_str(";\n");
+            return Ok(function_def);
+        }
+
+        function_def.push_str(" {\n");
+        for (sig_tok, name) in locals.iter().zip(&local_names).skip(arg_count) {
+            function_def.push_str(&format!(
+                "        let {}: {};\n",
+                name,
+                self.signature_token(sig_tok, type_params)?
+            ));
+        }
+        let body = FunctionDecompiler::new(self, function_definition, local_names, type_params)
+            .decompile()
+            .map_err(|err| {
+                format_err!(
+                    "Unable to decompile function {}: {}",
+                    module.identifier_at(function_handle.name),
+                    err
+                )
+            })?;
+        write_block(&mut function_def, &body, 2);
+        function_def.push_str("    }\n");
+        Ok(function_def)
+    }
+
+    pub fn decompile(&self) -> Result<String> {
+        let module = self.module();
+        let mut imports = String::new();
+        for (idx, module_handle) in module.module_handles().iter().enumerate() {
+            if idx == CompiledModule::IMPLEMENTED_MODULE_INDEX as usize {
+                continue;
+            }
+            let name = module.identifier_at(module_handle.name);
+            let alias = &self.module_aliases[idx];
+            imports.push_str(&format!(
+                "    import {}.{}{};\n",
+                address_literal(module.address_at(module_handle.address)),
+                name,
+                if alias.as_str() == name.as_str() {
+                    "".to_string()
+                } else {
+                    format!(" as {}", alias)
+                }
+            ));
+        }
+
+        let struct_defs = (0..module.struct_defs().len())
+            .map(|idx| self.decompile_struct_def(StructDefinitionIndex(idx as TableIndex)))
+            .collect::<Result<Vec<String>>>()?;
+        let function_defs = (0..module.function_defs().len())
+            .map(|idx| self.decompile_function_def(FunctionDefinitionIndex(idx as TableIndex)))
+            .collect::<Result<Vec<String>>>()?;
+
+        let sections: Vec<String> = vec![imports, struct_defs.concat(), function_defs.join("\n")]
+            .into_iter()
+            .filter(|section| !section.is_empty())
+            .collect();
+        Ok(format!(
+            "module {} {{\n{}}}\n",
+            module.name(),
+            sections.join("\n")
+        ))
+    }
+}
+
+fn address_literal(address: &AccountAddress) -> String {
+    let hex = format!("{:x}", address);
+    let digits = hex.trim_start_matches('0');
+    format!("0x{}", if digits.is_empty() { "0" } else { digits })
+}
+
+//***************************************************************************
+// Function bodies
+//***************************************************************************
+
+/// The loop the code being decompiled is in. A `continue` branches to its start and a `break` to
+/// its end.
+#[derive(Clone, Copy, Debug, Eq, Ord, PartialEq, PartialOrd)]
+struct Loop {
+    start: CodeOffset,
+    end: CodeOffset,
+}
+
+/// A statement the code at some offset may be decompiled to. The blocks of the compound
+/// statements are only decompiled when the statement is tried.
+enum Shape {
+    /// A statement without blocks, followed by the code at the given offset.
+    Statement(Statement, CodeOffset),
+    /// `loop { .. }` closed by the `Branch` at `branch`.
+    Loop { branch: CodeOffset },
+    /// `while (cond) { .. }` whose condition is tested by the `BrFalse` at `branch`.
+    While {
+        cond: Exp,
+        branch: CodeOffset,
+        exit: CodeOffset,
+    },
+    /// `if (cond) { .. } else { .. }`
+    IfElse {
+        cond: Exp,
+        branch: CodeOffset,
+        else_start: CodeOffset,
+        exit: CodeOffset,
+    },
+    /// `if (cond) { .. }`
+    If {
+        cond: Exp,
+        branch: CodeOffset,
+        exit: CodeOffset,
+    },
+}
+
+/// A code range and the innermost loop it is in.
+type BlockKey = (CodeOffset, CodeOffset, Option<Loop>);
+
+struct FunctionDecompiler<'a, Location: Clone + Eq + Default> {
+    decompiler: &'a Decompiler<Location>,
+    code: &'a [Bytecode],
+    local_names: Vec<String>,
+    type_params: &'a [SourceName<Location>],
+    // The offsets of the branches back to each loop start: the branch closing the loop and the
+    // `continue`s.
+    back_edges: BTreeMap<CodeOffset, Vec<CodeOffset>>,
+    // The blocks already decompiled, by code range and innermost loop. A block is decompiled once
+    // even when the statements it is part of are tried several times.
+    blocks: RefCell<BTreeMap<BlockKey, std::result::Result<Vec<Statement>, String>>>,
+}
+
+impl<'a, Location: Clone + Eq + Default> FunctionDecompiler<'a, Location> {
+    fn new(
+        decompiler: &'a Decompiler<Location>,
+        function_definition: &'a FunctionDefinition,
+        local_names: Vec<String>,
+        type_params: &'a [SourceName<Location>],
+    ) -> Self {
+        let code = &function_definition.code.code;
+        let mut back_edges = BTreeMap::new();
+        if !code.is_empty() {
+            let cfg = VMControlFlowGraph::new(code);
+            for block_id in cfg.blocks() {
+                let block_end = cfg.block_end(&block_id);
+                if let Bytecode::Branch(target) = code[block_end as usize] {
+                    if target <= block_end {
+                        back_edges
+                            .entry(target)
+                            .or_insert_with(Vec::new)
+                            .push(block_end);
+                    }
+                }
+            }
+        }
+        Self {
+            decompiler,
+            code,
+            local_names,
+            type_params,
+            back_edges,
+            blocks: RefCell::new(BTreeMap::new()),
+        }
+    }
+
+    fn decompile(&self) -> Result<Vec<Statement>> {
+        self.block(0, self.code.len() as CodeOffset, None)
+    }
+
+    fn local_name(&self, idx: LocalIndex) -> Result<String> {
+        self.local_names
+            .get(idx as usize)
+            .cloned()
+            .ok_or_else(|| format_err!("Invalid local index {}", idx))
+    }
+
+    /// Decompiles the code in `[start, end)` to a block of statements.
+    fn block(
+        &self,
+        start: CodeOffset,
+        end: CodeOffset,
+        innermost_loop: Option<Loop>,
+    ) -> Result<Vec<Statement>> {
+        let key = (start, end, innermost_loop);
+        if let Some(block) = self.blocks.borrow().get(&key) {
+            return block.clone().map_err(|err| format_err!("{}", err));
+        }
+        let block = self.decompile_block(start, end, innermost_loop);
+        let memoized = match &block {
+            Ok(statements) => Ok(statements.clone()),
+            Err(err) => Err(err.to_string()),
+        };
+        self.blocks.borrow_mut().insert(key, memoized);
+        block
+    }
+
+    // The same bytecode may be decompiled to different statements, e.g. the end of a loop body
+    // and a `continue`. The shapes of the first statement are tried in turn, the outer loops first,
+    // until the rest of the block can be decompiled.
+    fn decompile_block(
+        &self,
+        start: CodeOffset,
+        end: CodeOffset,
+        innermost_loop: Option<Loop>,
+    ) -> Result<Vec<Statement>> {
+        if start == end {
+            return Ok(vec![]);
+        }
+        let mut shapes: Vec<Shape> = self
+            .back_edges
+            .get(&start)
+            .map(|branches| {
+                branches
+                    .iter()
+                    .rev()
+                    .filter(|branch| **branch < end)
+                    .map(|branch| Shape::Loop { branch: *branch })
+                    .collect()
+            })
+            .unwrap_or_else(Vec::new);
+        let mut error = None;
+        match self.statement_shapes(start, end, innermost_loop) {
+            Ok(statement_shapes) => shapes.extend(statement_shapes),
+            Err(err) => error = Some(err),
+        }
+        for shape in shapes {
+            match self.statements(start, shape, end, innermost_loop) {
+                Ok(statements) => return Ok(statements),
+                Err(err) => error = Some(err),
+            }
+        }
+        Err(error.unwrap_or_else(|| {
+            format_err!("Unable to decompile the code at offset {}", start)
+        }))
+    }
+
+    /// Decompiles the statement of `shape` starting at `start`, followed by the rest of the block.
+    fn statements(
+        &self,
+        start: CodeOffset,
+        shape: Shape,
+        end: CodeOffset,
+        innermost_loop: Option<Loop>,
+    ) -> Result<Vec<Statement>> {
+        let (statement, next) = match shape {
+            Shape::Statement(statement, next) => (statement, next),
+            Shape::Loop { branch } => {
+                let loop_ = Loop {
+                    start,
+                    end: branch + 1,
+                };
+                (Statement::Loop(self.block(start, branch, Some(loop_))?), branch + 1)
+            }
+            Shape::While { cond, branch, exit } => {
+                let loop_ = Loop { start, end: exit };
+                let body = self.block(branch + 1, exit - 1, Some(loop_))?;
+                (Statement::While(cond, body), exit)
+            }
+            Shape::IfElse {
+                cond,
+                branch,
+                else_start,
+                exit,
+            } => {
+                let if_block = self.block(branch + 1, else_start - 1, innermost_loop)?;
+                let else_block = self.block(else_start, exit, innermost_loop)?;
+                (Statement::If(cond, if_block, Some(else_block)), exit)
+            }
+            Shape::If { cond, branch, exit } => {
+                let if_block = self.block(branch + 1, exit, innermost_loop)?;
+                (Statement::If(cond, if_block, None), exit)
+            }
+        };
+        let mut statements = vec![statement];
+        statements.extend(self.block(next, end, innermost_loop)?);
+        Ok(statements)
+    }
+
+    /// Symbolically executes the code from `start` to the end of the first statement, and returns
+    /// the statements it may be.
+    fn statement_shapes(
+        &self,
+        start: CodeOffset,
+        end: CodeOffset,
+        innermost_loop: Option<Loop>,
+    ) -> Result<Vec<Shape>> {
+        let mut stack = Stack::default();
+        for pc in start..end {
+            let statement = match &self.code[pc as usize] {
+                Bytecode::StLoc(_) | Bytecode::Pop | Bytecode::WriteRef => {
+                    let (statement, next) = self.assignment(pc, end, stack)?;
+                    return Ok(vec![Shape::Statement(statement, next)]);
+                }
+                Bytecode::Unpack(struct_def_idx, type_actuals_idx) => {
+                    let exp = stack.pop()?;
+                    stack.expect_empty(pc)?;
+                    let fields = self.decompiler.field_names(*struct_def_idx)?;
+                    // The fields are stored in reverse order.
+                    let mut bindings = vec![];
+                    for (field, offset) in fields.into_iter().rev().zip(pc + 1..) {
+                        match self.code.get(offset as usize) {
+                            Some(Bytecode::StLoc(idx)) if offset < end => {
+                                bindings.push((field, self.local_name(*idx)?))
+                            }
+                            _ => bail!("Field {} unpacked at offset {} isn't stored", field, pc),
+                        }
+                    }
+                    bindings.reverse();
+                    let next = pc + 1 + bindings.len() as CodeOffset;
+                    let name = self.decompiler.struct_instance(
+                        *struct_def_idx,
+                        *type_actuals_idx,
+                        self.type_params,
+                    )?;
+                    return Ok(vec![Shape::Statement(
+                        Statement::Unpack(name, bindings, exp),
+                        next,
+                    )]);
+                }
+                Bytecode::Ret => Statement::Return(stack.pop_list(stack.len())?),
+                Bytecode::Abort => {
+                    let code = stack.pop()?;
+                    stack.expect_empty(pc)?;
+                    Statement::Abort(code)
+                }
+                Bytecode::Branch(target) => {
+                    stack.expect_empty(pc)?;
+                    match innermost_loop {
+                        Some(loop_) if loop_.start == *target => Statement::Continue,
+                        Some(loop_) if loop_.end == *target => Statement::Break,
+                        _ => bail!("Unstructured branch at offset {}", pc),
+                    }
+                }
+                Bytecode::BrFalse(target) => {
+                    let cond = stack.pop()?;
+                    stack.expect_empty(pc)?;
+                    let shapes = self.conditional_shapes(start, pc, *target, end, cond);
+                    if shapes.is_empty() {
+                        bail!("Unstructured branch at offset {}", pc)
+                    }
+                    return Ok(shapes);
+                }
+                instr => match self.expression(instr, &mut stack)? {
+                    Some(exp) => {
+                        stack.expect_empty(pc)?;
+                        Statement::Exp(exp)
+                    }
+                    None => continue,
+                },
+            };
+            return Ok(vec![Shape::Statement(statement, pc + 1)]);
+        }
+        bail!("The statement at offset {} doesn't end before offset {}", start, end)
+    }
+
+    /// Returns the statements the `BrFalse` at `branch` may start, with the condition computed
+    /// from `start`.
+    fn conditional_shapes(
+        &self,
+        start: CodeOffset,
+        branch: CodeOffset,
+        target: CodeOffset,
+        end: CodeOffset,
+        cond: Exp,
+    ) -> Vec<Shape> {
+        let mut shapes = vec![];
+        if target <= branch || target > end {
+            return shapes;
+        }
+        if target - 1 > branch {
+            match self.code[target as usize - 1] {
+                // The body of a `while` branches back to its condition.
+                Bytecode::Branch(loop_start) if loop_start == start => {
+                    shapes.push(Shape::While {
+                        cond: cond.clone(),
+                        branch,
+                        exit: target,
+                    });
+                }
+                // An `if` block skips the `else` block that follows it.
+                Bytecode::Branch(exit) if exit >= target && exit <= end => {
+                    shapes.push(Shape::IfElse {
+                        cond: cond.clone(),
+                        branch,
+                        else_start: target,
+                        exit,
+                    });
+                }
+                _ => (),
+            }
+        }
+        shapes.push(Shape::If {
+            cond,
+            branch,
+            exit: target,
+        });
+        shapes
+    }
+
+    /// Decompiles the assignment ending the statement at the store at `pc`. The values on `stack`
+    /// are assigned, except for the reference written by a leading `WriteRef`.
+    fn assignment(
+        &self,
+        mut pc: CodeOffset,
+        end: CodeOffset,
+        mut stack: Stack,
+    ) -> Result<(Statement, CodeOffset)> {
+        // The references written to are computed after the assigned values, on top of them.
+        let mut lvalue_stack = Stack::default();
+        if self.code[pc as usize] == Bytecode::WriteRef {
+            lvalue_stack.push(stack.pop()?);
+        }
+        let mut count = stack.len();
+        if count == 0 {
+            bail!("Nothing to assign at offset {}", pc)
+        }
+        let rhs = stack.pop_list(count)?;
+
+        // The values are assigned in reverse order.
+        let mut lvalues = vec![];
+        while count > 0 {
+            if pc >= end {
+                bail!("The assignment at offset {} doesn't end before offset {}", pc, end)
+            }
+            match &self.code[pc as usize] {
+                Bytecode::StLoc(idx) if lvalue_stack.is_empty() => {
+                    lvalues.push(LValue::Var(self.local_name(*idx)?));
+                    count -= 1;
+                }
+                Bytecode::Pop if lvalue_stack.is_empty() => {
+                    lvalues.push(LValue::Pop);
+                    count -= 1;
+                }
+                Bytecode::WriteRef => {
+                    let reference = lvalue_stack.pop()?;
+                    lvalue_stack.expect_empty(pc)?;
+                    lvalues.push(LValue::Mutate(reference));
+                    count -= 1;
+                }
+                instr => {
+                    if self.expression(instr, &mut lvalue_stack)?.is_some() {
+                        bail!("Unexpected statement in the assignment at offset {}", pc)
+                    }
+                }
+            }
+            pc += 1;
+        }
+        lvalues.reverse();
+        Ok((Statement::Assign(lvalues, rhs), pc))
+    }
+
+    /// Applies an instruction computing an expression to the stack. The calls returning no value,
+    /// which are statements on their own, are returned instead of being pushed.
+    fn expression(&self, instr: &Bytecode, stack: &mut Stack) -> Result<Option<Exp>> {
+        let module = self.decompiler.module();
+        let exp = match instr {
+            Bytecode::LdU8(value) => Exp::Value(format!("{}u8", value)),
+            Bytecode::LdConst(value) => Exp::Value(value.to_string()),
+            Bytecode::LdU128(value) => Exp::Value(format!("{}u128", value)),
+            Bytecode::LdTrue => Exp::Value("true".to_string()),
+            Bytecode::LdFalse => Exp::Value("false".to_string()),
+            Bytecode::LdAddr(idx) => Exp::Value(address_literal(module.address_at(*idx))),
+            Bytecode::LdByteArray(idx) => {
+                let bytes: Vec<String> = module
+                    .byte_array_at(*idx)
+                    .as_bytes()
+                    .iter()
+                    .map(|byte| format!("{:02x}", byte))
+                    .collect();
+                Exp::Value(format!("h\"{}\"", bytes.concat()))
+            }
+            Bytecode::CopyLoc(idx) => Exp::Local(format!("copy({})", self.local_name(*idx)?)),
+            Bytecode::MoveLoc(idx) => Exp::Local(format!("move({})", self.local_name(*idx)?)),
+            Bytecode::ImmBorrowLoc(idx) => Exp::Value(format!("&{}", self.local_name(*idx)?)),
+            Bytecode::MutBorrowLoc(idx) => {
+                Exp::Value(format!("&mut {}", self.local_name(*idx)?))
+            }
+            Bytecode::ImmBorrowField(idx) | Bytecode::MutBorrowField(idx) => Exp::Borrow {
+                mutable: *instr == Bytecode::MutBorrowField(*idx),
+                exp: Box::new(stack.pop()?),
+                field: self.decompiler.field_name(*idx),
+            },
+            Bytecode::ReadRef => Exp::Dereference(Box::new(stack.pop()?)),
+            Bytecode::FreezeRef => Exp::Call("freeze".to_string(), vec![stack.pop()?]),
+            Bytecode::CastU8 => Exp::Call("to_u8".to_string(), vec![stack.pop()?]),
+            Bytecode::CastU64 => Exp::Call("to_u64".to_string(), vec![stack.pop()?]),
+            Bytecode::CastU128 => Exp::Call("to_u128".to_string(), vec![stack.pop()?]),
+            Bytecode::Not => Exp::Not(Box::new(stack.pop()?)),
+            Bytecode::Pack(struct_def_idx, type_actuals_idx) => {
+                let fields = self.decompiler.field_names(*struct_def_idx)?;
+                let values = stack.pop_values(fields.len())?;
+                let name = self.decompiler.struct_instance(
+                    *struct_def_idx,
+                    *type_actuals_idx,
+                    self.type_params,
+                )?;
+                Exp::Pack(name, fields.into_iter().zip(values).collect())
+            }
+            Bytecode::Exists(struct_def_idx, type_actuals_idx) => {
+                self.builtin("exists", *struct_def_idx, *type_actuals_idx, stack)?
+            }
+            Bytecode::ImmBorrowGlobal(struct_def_idx, type_actuals_idx) => {
+                self.builtin("borrow_global", *struct_def_idx, *type_actuals_idx, stack)?
+            }
+            Bytecode::MutBorrowGlobal(struct_def_idx, type_actuals_idx) => {
+                self.builtin("borrow_global_mut", *struct_def_idx, *type_actuals_idx, stack)?
+            }
+            Bytecode::MoveFrom(struct_def_idx, type_actuals_idx) => {
+                self.builtin("move_from", *struct_def_idx, *type_actuals_idx, stack)?
+            }
+            Bytecode::MoveToSender(struct_def_idx, type_actuals_idx) => {
+                return Ok(Some(self.builtin(
+                    "move_to_sender",
+                    *struct_def_idx,
+                    *type_actuals_idx,
+                    stack,
+                )?));
+            }
+            Bytecode::CreateAccount => {
+                return Ok(Some(Exp::Call(
+                    "create_account".to_string(),
+                    vec![stack.pop()?],
+                )));
+            }
+            Bytecode::GetTxnGasUnitPrice => Exp::Call("get_txn_gas_unit_price".to_string(), vec![]),
+            Bytecode::GetTxnMaxGasUnits => Exp::Call("get_txn_max_gas_units".to_string(), vec![]),
+            Bytecode::GetGasRemaining => Exp::Call("get_gas_remaining".to_string(), vec![]),
+            Bytecode::GetTxnSenderAddress => Exp::Call("get_txn_sender".to_string(), vec![]),
+            Bytecode::GetTxnSequenceNumber => {
+                Exp::Call("get_txn_sequence_number".to_string(), vec![])
+            }
+            Bytecode::GetTxnPublicKey => Exp::Call("get_txn_public_key".to_string(), vec![]),
+            Bytecode::Call(function_handle_idx, type_actuals_idx) => {
+                let function_handle = module.function_handle_at(*function_handle_idx);
+                let function_signature = module.function_signature_at(function_handle.signature);
+                let args = stack.pop_list(function_signature.arg_types.len())?;
+                let function = format!(
+                    "{}.{}{}",
+                    self.decompiler.module_aliases[function_handle.module.0 as usize],
+                    module.identifier_at(function_handle.name),
+                    self.decompiler.type_actuals(
+                        &module.locals_signature_at(*type_actuals_idx).0,
+                        self.type_params
+                    )?
+                );
+                let exp = Exp::Call(function, args);
+                match function_signature.return_types.len() {
+                    0 => return Ok(Some(exp)),
+                    count => {
+                        stack.push_list(exp, count);
+                        return Ok(None);
+                    }
+                }
+            }
+            instr => match binary_operator(instr) {
+                Some((operator, precedence)) => {
+                    let rhs = stack.pop()?;
+                    let lhs = stack.pop()?;
+                    Exp::BinOp(Box::new(lhs), operator, precedence, Box::new(rhs))
+                }
+                None => bail!("Unexpected instruction {:?} in an expression", instr),
+            },
+        };
+        stack.push(exp);
+        Ok(None)
+    }
+
+    fn builtin(
+        &self,
+        name: &str,
+        struct_def_idx: StructDefinitionIndex,
+        type_actuals_idx: LocalsSignatureIndex,
+        stack: &mut Stack,
+    ) -> Result<Exp> {
+        let struct_instance =
+            self.decompiler
+                .struct_instance(struct_def_idx, type_actuals_idx, self.type_params)?;
+        Ok(Exp::Call(
+            format!("{}<{}>", name, struct_instance),
+            vec![stack.pop()?],
+        ))
+    }
+}
+
+// The binary operators and their precedence in the IR grammar, from the comparisons, which bind
+// the least tightly, to the multiplicative operators.
+fn binary_operator(instr: &Bytecode) -> Option<(&'static str, u8)> {
+    Some(match instr {
+        Bytecode::Eq => ("==", 0),
+        Bytecode::Neq => ("!=", 0),
+        Bytecode::Lt => ("<", 0),
+        Bytecode::Gt => (">", 0),
+        Bytecode::Le => ("<=", 0),
+        Bytecode::Ge => (">=", 0),
+        Bytecode::Or => ("||", 1),
+        Bytecode::And => ("&&", 2),
+        Bytecode::Xor => ("^", 3),
+        Bytecode::BitOr => ("|", 4),
+        Bytecode::BitAnd => ("&", 5),
+        Bytecode::Add => ("+", 6),
+        Bytecode::Sub => ("-", 6),
+        Bytecode::Mul => ("*", 7),
+        Bytecode::Div => ("/", 7),
+        Bytecode::Mod => ("%", 7),
+        _ => return None,
+    })
+}
+
+//***************************************************************************
+// Expressions and statements
+//***************************************************************************
+
+// The precedence of the expressions that aren't binary operations, which never need parentheses
+// as operands of a binary operation.
+const UNARY_PRECEDENCE: u8 = 8;
+
+#[derive(Clone, Debug)]
+enum Exp {
+    /// `copy(x)` or `move(x)`
+    Local(String),
+    /// A literal or a borrowed local
+    Value(String),
+    Call(String, Vec<Exp>),
+    Pack(String, Vec<(String, Exp)>),
+    Borrow {
+        mutable: bool,
+        exp: Box<Exp>,
+        field: String,
+    },
+    Dereference(Box<Exp>),
+    Not(Box<Exp>),
+    BinOp(Box<Exp>, &'static str, u8, Box<Exp>),
+}
+
+impl Exp {
+    fn precedence(&self) -> u8 {
+        match self {
+            Exp::BinOp(_, _, precedence, _) => *precedence,
+            _ => UNARY_PRECEDENCE,
+        }
+    }
+
+    /// Formats the expression as an operand of an operator of the given precedence.
+    fn operand(&self, precedence: u8) -> String {
+        if self.precedence() < precedence {
+            format!("({})", self)
+        } else {
+            self.to_string()
+        }
+    }
+}
+
+impl fmt::Display for Exp {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Exp::Local(exp) | Exp::Value(exp) => write!(f, "{}", exp),
+            Exp::Call(function, args) => write!(f, "{}({})", function, comma_separated(args)),
+            Exp::Pack(name, fields) => {
+                let fields: Vec<String> = fields
+                    .iter()
+                    .map(|(field, exp)| format!("{}: {}", field, exp))
+                    .collect();
+                write!(f, "{}{{{}}}", name, fields.join(", "))
+            }
+            Exp::Borrow {
+                mutable,
+                exp,
+                field,
+            } => {
+                let borrow = if *mutable { "&mut " } else { "&" };
+                match **exp {
+                    Exp::Local(_) => write!(f, "{}{}.{}", borrow, exp, field),
+                    _ => write!(f, "{}({}).{}", borrow, exp, field),
+                }
+            }
+            Exp::Dereference(exp) => write!(f, "*{}", exp.operand(UNARY_PRECEDENCE)),
+            Exp::Not(exp) => write!(f, "!{}", exp.operand(UNARY_PRECEDENCE)),
+            // The binary operators are left associative.
+            Exp::BinOp(lhs, operator, precedence, rhs) => write!(
+                f,
+                "{} {} {}",
+                lhs.operand(*precedence),
+                operator,
+                rhs.operand(*precedence + 1)
+            ),
+        }
+    }
+}
+
+#[derive(Clone, Debug)]
+enum LValue {
+    Var(String),
+    Mutate(Exp),
+    Pop,
+}
+
+impl fmt::Display for LValue {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            LValue::Var(name) => write!(f, "{}", name),
+            LValue::Mutate(exp) => write!(f, "*{}", exp),
+            LValue::Pop => write!(f, "_"),
+        }
+    }
+}
+
+#[derive(Clone, Debug)]
+enum Statement {
+    Assign(Vec<LValue>, Vec<Exp>),
+    Unpack(String, Vec<(String, String)>, Exp),
+    Exp(Exp),
+    Return(Vec<Exp>),
+    Abort(Exp),
+    Break,
+    Continue,
+    If(Exp, Vec<Statement>, Option<Vec<Statement>>),
+    While(Exp, Vec<Statement>),
+    Loop(Vec<Statement>),
+}
+
+impl Statement {
+    fn write(&self, out: &mut String, indent: usize) {
+        let prefix = "    ".repeat(indent);
+        match self {
+            Statement::Assign(lvalues, rhs) => {
+                let rhs = if rhs.len() == 1 {
+                    rhs[0].to_string()
+                } else {
+                    format!("({})", comma_separated(rhs))
+                };
+                out.push_str(&format!("{}{} = {};\n", prefix, comma_separated(lvalues), rhs));
+            }
+            Statement::Unpack(name, bindings, exp) => {
+                let bindings: Vec<String> = bindings
+                    .iter()
+                    .map(|(field, var)| format!("{}: {}", field, var))
+                    .collect();
+                out.push_str(&format!(
+                    "{}{}{{{}}} = {};\n",
+                    prefix,
+                    name,
+                    bindings.join(", "),
+                    exp
+                ));
+            }
+            Statement::Exp(exp) => out.push_str(&format!("{}{};\n", prefix, exp)),
+            Statement::Return(values) if values.is_empty() => {
+                out.push_str(&format!("{}return;\n", prefix))
+            }
+            Statement::Return(values) => {
+                out.push_str(&format!("{}return {};\n", prefix, comma_separated(values)))
+            }
+            Statement::Abort(code) => out.push_str(&format!("{}abort {};\n", prefix, code)),
+            Statement::Break => out.push_str(&format!("{}break;\n", prefix)),
+            Statement::Continue => out.push_str(&format!("{}continue;\n", prefix)),
+            // `assert(cond, code)` is compiled to `if (!cond) { abort code; }`.
+            Statement::If(Exp::Not(cond), if_block, None)
+                if if_block.len() == 1 && if_block[0].is_abort() =>
+            {
+                if let Statement::Abort(code) = &if_block[0] {
+                    out.push_str(&format!("{}assert({}, {});\n", prefix, cond, code));
+                }
+            }
+            Statement::If(cond, if_block, else_block) => {
+                out.push_str(&format!("{}if ({}) {{\n", prefix, cond));
+                write_block(out, if_block, indent + 1);
+                if let Some(else_block) = else_block {
+                    out.push_str(&format!("{}}} else {{\n", prefix));
+                    write_block(out, else_block, indent + 1);
+                }
+                out.push_str(&format!("{}}}\n", prefix));
+            }
+            Statement::While(cond, body) => {
+                out.push_str(&format!("{}while ({}) {{\n", prefix, cond));
+                write_block(out, body, indent + 1);
+                out.push_str(&format!("{}}}\n", prefix));
+            }
+            Statement::Loop(body) => {
+                out.push_str(&format!("{}loop {{\n", prefix));
+                write_block(out, body, indent + 1);
+                out.push_str(&format!("{}}}\n", prefix));
+            }
+        }
+    }
+
+    fn is_abort(&self) -> bool {
+        match self {
+            Statement::Abort(_) => true,
+            _ => false,
+        }
+    }
+}
+
+fn write_block(out: &mut String, statements: &[Statement], indent: usize) {
+    for statement in statements {
+        statement.write(out, indent);
+    }
+}
+
+fn comma_separated<T: fmt::Display>(items: &[T]) -> String {
+    items
+        .iter()
+        .map(|item| item.to_string())
+        .collect::<Vec<_>>()
+        .join(", ")
+}
+
+//***************************************************************************
+// Symbolic stack
+//***************************************************************************
+
+/// A value on the symbolic stack: the expression computing it, or one of the values returned by a
+/// call returning several values.
+#[derive(Clone)]
+struct StackValue {
+    exp: Rc<Exp>,
+    idx: usize,
+    count: usize,
+}
+
+#[derive(Default)]
+struct Stack(Vec<StackValue>);
+
+impl Stack {
+    fn len(&self) -> usize {
+        self.0.len()
+    }
+
+    fn is_empty(&self) -> bool {
+        self.0.is_empty()
+    }
+
+    fn expect_empty(&self, pc: CodeOffset) -> Result<()> {
+        if !self.is_empty() {
+            bail!("Values are left on the stack at offset {}", pc)
+        }
+        Ok(())
+    }
+
+    fn push(&mut self, exp: Exp) {
+        self.push_list(exp, 1)
+    }
+
+    /// Pushes the `count` values returned by the call `exp`.
+    fn push_list(&mut self, exp: Exp, count: usize) {
+        let exp = Rc::new(exp);
+        for idx in 0..count {
+            self.0.push(StackValue {
+                exp: exp.clone(),
+                idx,
+                count,
+            });
+        }
+    }
+
+    /// Pops a single value.
+    fn pop(&mut self) -> Result<Exp> {
+        match self.0.pop() {
+            Some(StackValue { exp, count: 1, .. }) => Ok((*exp).clone()),
+            Some(_) => bail!("A value returned by a call with several return values is used alone"),
+            None => bail!("Stack underflow"),
+        }
+    }
+
+    /// Pops `count` single values.
+    fn pop_values(&mut self, count: usize) -> Result<Vec<Exp>> {
+        let mut values = (0..count)
+            .map(|_| self.pop())
+            .collect::<Result<Vec<Exp>>>()?;
+        values.reverse();
+        Ok(values)
+    }
+
+    /// Pops `count` values, where all the values returned by a call are popped at once, as the
+    /// call expression.
+    fn pop_list(&mut self, count: usize) -> Result<Vec<Exp>> {
+        if count > self.0.len() {
+            bail!("Stack underflow")
+        }
+        let values = self.0.split_off(self.0.len() - count);
+        let mut exps = vec![];
+        let mut idx = 0;
+        while idx < values.len() {
+            let value = &values[idx];
+            let complete = value.idx == 0
+                && idx + value.count <= values.len()
+                && values[idx..idx + value.count]
+                    .iter()
+                    .all(|other| Rc::ptr_eq(&other.exp, &value.exp));
+            if !complete {
+                bail!("The values returned by a call are used separately")
+            }
+            exps.push((*value.exp).clone());
+            idx += value.count;
+        }
+        Ok(exps)
+    }
+}
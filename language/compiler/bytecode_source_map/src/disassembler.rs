@@ -85,7 +85,9 @@ impl<Location: Clone + Eq + Default> Disassembler<Location> {
     ) -> Result<String> {
         Ok(match sig_tok {
             SignatureToken::Bool => "bool".to_string(),
+            SignatureToken::U8 => "u8".to_string(),
             SignatureToken::U64 => "u64".to_string(),
+            SignatureToken::U128 => "u128".to_string(),
             SignatureToken::String => "string".to_string(),
             SignatureToken::ByteArray => "bytearray".to_string(),
             SignatureToken::Address => "address".to_string(),
@@ -1,6 +1,7 @@
 // Copyright (c) The Libra Core Contributors
 // SPDX-License-Identifier: Apache-2.0
 
+pub mod decompiler;
 pub mod disassembler;
 pub mod mapping;
 pub mod marking;
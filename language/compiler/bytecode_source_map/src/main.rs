@@ -1,6 +1,7 @@
 // Copyright (c) The Libra Core Contributors
 // SPDX-License-Identifier: Apache-2.0
 
+use bytecode_source_map::decompiler::Decompiler;
 use bytecode_source_map::disassembler::{Disassembler, DisassemblerOptions};
 use bytecode_source_map::mapping::SourceMapping;
 use bytecode_source_map::source_map::ModuleSourceMap;
@@ -33,6 +34,10 @@ struct Args {
     #[structopt(short = "s", long = "script")]
     pub is_script: bool,
 
+    /// Prints the module as Move IR source code instead of disassembling it.
+    #[structopt(short = "d", long = "decompile")]
+    pub decompile: bool,
+
     /// The path to the bytecode file.
     #[structopt(short = "b", long = "bytecode")]
     pub bytecode_file_path: String,
@@ -41,6 +46,11 @@ struct Args {
 fn main() {
     let args = Args::from_args();
 
+    if args.decompile && args.is_script {
+        println!("Only modules can be decompiled");
+        std::process::exit(1);
+    }
+
     let mvir_extension = "mvir";
     let mv_bytecode_extension = "mv";
     let source_map_extension = "mvsm";
@@ -93,6 +103,13 @@ fn main() {
             .with_source_code((ir_source_path.to_str().unwrap().to_string(), source_code));
     }
 
+    if args.decompile {
+        let decompiler = Decompiler::new(source_mapping);
+        let decompiled_string = decompiler.decompile().expect("Unable to decompile");
+        println!("{}", decompiled_string);
+        return;
+    }
+
     let disassembler = Disassembler::new(source_mapping, disassembler_options);
 
     let dissassemble_string = disassembler.disassemble().expect("Unable to dissassemble");
@@ -6,14 +6,20 @@ use crate::source_map::{ModuleSourceMap, SourceMap};
 use codespan::{CodeMap, FileName};
 use codespan_reporting::{
     emit,
-    termcolor::{ColorChoice, StandardStream},
+    termcolor::{Buffer, ColorChoice, StandardStream},
     Diagnostic, Label,
 };
 use failure::prelude::*;
 use ir_to_bytecode_syntax::ast::Loc;
+use libra_types::vm_error::VMStatus;
 use serde::de::DeserializeOwned;
 use std::fs::File;
 use std::path::Path;
+use vm::{
+    errors::err_info,
+    file_format::{CodeOffset, FunctionDefinitionIndex, StructDefinitionIndex, TableIndex},
+    IndexKind,
+};
 
 pub type Error = (Loc, String);
 pub type Errors = Vec<Error>;
@@ -59,3 +65,69 @@ pub fn create_diagnostic(error: Error) -> Diagnostic {
     let label = Label::new_primary(error.0);
     Diagnostic::new_error(error.1.clone()).with_label(label)
 }
+
+/// Renders the errors as snippets of `source`, labeled with the line and column of each error.
+pub fn render_errors_to_string(file_name: &str, source: &str, errors: Errors) -> String {
+    render_diagnostics(
+        file_name,
+        source,
+        errors.into_iter().map(create_diagnostic).collect(),
+    )
+}
+
+/// Renders the bytecode verifier errors of the module compiled from `source` with
+/// `source_map`. An error that points at a struct, a function or an instruction of a function is
+/// labeled with the corresponding snippet of `source`.
+pub fn render_verification_errors(
+    file_name: &str,
+    source: &str,
+    source_map: &ModuleSourceMap<Loc>,
+    errors: &[VMStatus],
+) -> String {
+    let diagnostics = errors
+        .iter()
+        .map(|error| {
+            let diagnostic = Diagnostic::new_error(error.to_string());
+            match verification_error_location(source_map, error) {
+                Some(loc) => diagnostic.with_label(Label::new_primary(loc)),
+                None => diagnostic,
+            }
+        })
+        .collect();
+    render_diagnostics(file_name, source, diagnostics)
+}
+
+/// Returns the source location of a bytecode verifier error, when the error points at a struct
+/// definition, a function definition or an instruction of a function definition.
+pub fn verification_error_location(
+    source_map: &ModuleSourceMap<Loc>,
+    error: &VMStatus,
+) -> Option<Loc> {
+    let info = err_info(error)?;
+    match info.kind {
+        IndexKind::FunctionDefinition => {
+            let function_map = source_map
+                .get_function_source_map(FunctionDefinitionIndex(info.idx as TableIndex))
+                .ok()?;
+            let code_location = info
+                .offset
+                .and_then(|offset| function_map.get_code_location(offset as CodeOffset));
+            Some(code_location.unwrap_or(function_map.decl_location))
+        }
+        IndexKind::StructDefinition => source_map
+            .get_struct_source_map(StructDefinitionIndex(info.idx as TableIndex))
+            .ok()
+            .map(|struct_map| struct_map.decl_location),
+        _ => None,
+    }
+}
+
+fn render_diagnostics(file_name: &str, source: &str, diagnostics: Vec<Diagnostic>) -> String {
+    let mut codemap = CodeMap::new();
+    codemap.add_filemap(FileName::real(file_name), source.to_string());
+    let mut buffer = Buffer::no_color();
+    for diagnostic in diagnostics {
+        emit(&mut buffer, &codemap, &diagnostic).unwrap();
+    }
+    String::from_utf8_lossy(buffer.as_slice()).into_owned()
+}
@@ -12,6 +12,7 @@ use crate::{
         Statement, StructDefinitionFields, StructDefinition_ as MoveStruct_, Type, TypeVar,
         TypeVar_, UnaryOp, Var, Var_, While,
     },
+    specs::{module_specs, ModuleSpecs},
 };
 
 use bytecode_source_map::source_map::{ModuleSourceMap, SourceMap};
@@ -138,7 +139,9 @@ enum InferredType {
 
     // Signature tokens
     Bool,
+    U8,
     U64,
+    U128,
     String,
     ByteArray,
     Address,
@@ -154,7 +157,9 @@ impl InferredType {
         use SignatureToken as S;
         match sig_token {
             S::Bool => I::Bool,
+            S::U8 => I::U8,
             S::U64 => I::U64,
+            S::U128 => I::U128,
             S::String => I::String,
             S::ByteArray => I::ByteArray,
             S::Address => I::Address,
@@ -175,7 +180,9 @@ impl InferredType {
         match self {
             InferredType::Anything => bail!("could not infer struct type"),
             InferredType::Bool => bail!("no struct type for Bool"),
+            InferredType::U8 => bail!("no struct type for U8"),
             InferredType::U64 => bail!("no struct type for U64"),
+            InferredType::U128 => bail!("no struct type for U128"),
             InferredType::String => bail!("no struct type for String"),
             InferredType::ByteArray => bail!("no struct type for ByteArray"),
             InferredType::Address => bail!("no struct type for Address"),
@@ -344,10 +351,13 @@ pub fn compile_script<'a, T: 'a + ModuleAccess>(
     compile_imports(&mut context, address, script.imports)?;
     let main_name = FunctionName::new(Identifier::new("main").unwrap());
     let function = script.main;
+    let loc = function.span;
 
-    let sig = function_signature(&mut context, &function.signature)?;
+    let sig =
+        function_signature(&mut context, &function.signature).map_err(|err| with_loc(err, loc))?;
     context.declare_function(self_name.clone(), main_name.clone(), sig)?;
-    let main = compile_function(&mut context, &self_name, main_name, function, 0)?;
+    let main = compile_function(&mut context, &self_name, main_name, function, 0)
+        .map_err(|err| with_loc(err, loc))?;
 
     let (
         MaterializedPools {
@@ -389,6 +399,16 @@ pub fn compile_module<'a, T: 'a + ModuleAccess>(
     module: ModuleDefinition,
     dependencies: impl IntoIterator<Item = &'a T>,
 ) -> Result<(CompiledModule, ModuleSourceMap<Loc>)> {
+    compile_module_with_specs(address, module, dependencies)
+        .map(|(compiled_module, source_map, _)| (compiled_module, source_map))
+}
+
+/// Compile a module, and collect the specifications of its functions and structs.
+pub fn compile_module_with_specs<'a, T: 'a + ModuleAccess>(
+    address: AccountAddress,
+    module: ModuleDefinition,
+    dependencies: impl IntoIterator<Item = &'a T>,
+) -> Result<(CompiledModule, ModuleSourceMap<Loc>, ModuleSpecs)> {
     let current_module = QualifiedModuleIdent {
         address,
         name: module.name,
@@ -404,15 +424,20 @@ pub fn compile_module<'a, T: 'a + ModuleAccess>(
             module: self_name.clone(),
             name: s.name.clone(),
         };
-        let (_, tys) = type_formals(&s.type_formals)?;
+        let (_, tys) = type_formals(&s.type_formals).map_err(|err| with_loc(err, s.span))?;
         context.declare_struct_handle_index(ident, s.is_nominal_resource, tys)?;
     }
 
     for (name, function) in &module.functions {
-        let sig = function_signature(&mut context, &function.signature)?;
+        let sig = function_signature(&mut context, &function.signature)
+            .map_err(|err| with_loc(err, function.span))?;
         context.declare_function(self_name.clone(), name.clone(), sig)?;
     }
 
+    // The specifications don't add anything to the pools, so they are collected before
+    // the module is consumed
+    let specs = module_specs(&context, &module)?;
+
     // Current module
 
     let (struct_defs, field_defs) = compile_structs(&mut context, &self_name, module.structs)?;
@@ -452,7 +477,7 @@ pub fn compile_module<'a, T: 'a + ModuleAccess>(
     compiled_module
         .freeze()
         .map_err(|errs| InternalCompilerError::BoundsCheckErrors(errs).into())
-        .map(|frozen_module| (frozen_module, source_map))
+        .map(|frozen_module| (frozen_module, source_map, specs))
 }
 
 fn compile_imports(
@@ -500,7 +525,9 @@ fn compile_types(context: &mut Context, tys: &[Type]) -> Result<Vec<SignatureTok
 fn compile_type(context: &mut Context, ty: &Type) -> Result<SignatureToken> {
     Ok(match ty {
         Type::Address => SignatureToken::Address,
+        Type::U8 => SignatureToken::U8,
         Type::U64 => SignatureToken::U64,
+        Type::U128 => SignatureToken::U128,
         Type::Bool => SignatureToken::Bool,
         Type::ByteArray => SignatureToken::ByteArray,
         Type::Reference(is_mutable, inner_type) => {
@@ -558,9 +585,10 @@ fn compile_structs(
         let sh_idx = context.struct_handle_index(sident.clone())?;
         record_src_loc!(struct_decl: context, s.span);
         record_src_loc!(struct_type_formals: context, &s.type_formals);
-        let (map, _) = type_formals(&s.type_formals)?;
+        let (map, _) = type_formals(&s.type_formals).map_err(|err| with_loc(err, s.span))?;
         context.bind_type_formals(map)?;
-        let field_information = compile_fields(context, &mut field_defs, sh_idx, s.value.fields)?;
+        let field_information = compile_fields(context, &mut field_defs, sh_idx, s.value.fields)
+            .map_err(|err| with_loc(err, s.span))?;
         context.declare_struct_definition_index(s.value.name)?;
         struct_defs.push(StructDefinition {
             struct_handle: sh_idx,
@@ -590,9 +618,12 @@ fn compile_fields(
             for (decl_order, (f, ty)) in fields.into_iter().enumerate() {
                 let name = context.identifier_index(f.name())?;
                 record_src_loc!(field: context, f);
-                let sig_token = compile_type(context, &ty)?;
+                let sig_token = compile_type(context, &ty).map_err(|err| with_loc(err, f.span))?;
                 let signature = context.type_signature_index(sig_token.clone())?;
-                context.declare_field(sh_idx, f.value, sig_token, decl_order)?;
+                let loc = f.span;
+                context
+                    .declare_field(sh_idx, f.value, sig_token, decl_order)
+                    .map_err(|err| with_loc(err, loc))?;
                 field_pool.push(FieldDefinition {
                     struct_: sh_idx,
                     name,
@@ -613,7 +644,9 @@ fn compile_functions(
         .into_iter()
         .enumerate()
         .map(|(func_index, (name, ast_function))| {
+            let loc = ast_function.span;
             compile_function(context, self_name, name, ast_function, func_index)
+                .map_err(|err| with_loc(err, loc))
         })
         .collect()
 }
@@ -719,7 +752,9 @@ fn compile_block(
     for stmt in block.stmts {
         let stmt_info = match stmt {
             Statement::CommandStatement(command) => {
-                compile_command(context, function_frame, code, command)?
+                let loc = command.span;
+                compile_command(context, function_frame, code, command)
+                    .map_err(|err| with_loc(err, loc))?
             }
             Statement::WhileStatement(while_) => {
                 // always assume the loop might not be taken
@@ -936,7 +971,9 @@ fn compile_lvalues(
     for lvalue_ in lvalues.into_iter().rev() {
         match lvalue_.value {
             LValue::Var(v) => {
-                let loc_idx = function_frame.get_local(&v.value)?;
+                let loc_idx = function_frame
+                    .get_local(&v.value)
+                    .map_err(|err| with_loc(err, v.span))?;
                 push_instr!(lvalue_.span, Bytecode::StLoc(loc_idx));
                 function_frame.pop()?;
             }
@@ -966,6 +1003,16 @@ fn compile_expression(
     function_frame: &mut FunctionFrame,
     code: &mut Vec<Bytecode>,
     exp: Exp_,
+) -> Result<VecDeque<InferredType>> {
+    let loc = exp.span;
+    compile_expression_impl(context, function_frame, code, exp).map_err(|err| with_loc(err, loc))
+}
+
+fn compile_expression_impl(
+    context: &mut Context,
+    function_frame: &mut FunctionFrame,
+    code: &mut Vec<Bytecode>,
+    exp: Exp_,
 ) -> Result<VecDeque<InferredType>> {
     make_push_instr!(context, code);
     Ok(match exp.value {
@@ -1006,11 +1053,21 @@ fn compile_expression(
                 function_frame.push()?;
                 vec_deque![InferredType::Address]
             }
+            CopyableVal::U8(i) => {
+                push_instr!(exp.span, Bytecode::LdU8(i));
+                function_frame.push()?;
+                vec_deque![InferredType::U8]
+            }
             CopyableVal::U64(i) => {
                 push_instr!(exp.span, Bytecode::LdConst(i));
                 function_frame.push()?;
                 vec_deque![InferredType::U64]
             }
+            CopyableVal::U128(i) => {
+                push_instr!(exp.span, Bytecode::LdU128(i));
+                function_frame.push()?;
+                vec_deque![InferredType::U128]
+            }
             CopyableVal::ByteArray(buf) => {
                 let buf_idx = context.byte_array_index(&buf)?;
                 push_instr!(exp.span, Bytecode::LdByteArray(buf_idx));
@@ -1070,41 +1127,44 @@ fn compile_expression(
             }
         }
         Exp::BinopExp(e1, op, e2) => {
-            compile_expression(context, function_frame, code, *e1)?;
+            let mut lhs_types = compile_expression(context, function_frame, code, *e1)?;
             compile_expression(context, function_frame, code, *e2)?;
             function_frame.pop()?;
+            // The operands of arithmetic and bitwise operators are integers of the same type,
+            // which is also the type of the result.
+            let integer_type = lhs_types.pop_front().unwrap_or(InferredType::Anything);
             match op {
                 BinOp::Add => {
                     push_instr!(exp.span, Bytecode::Add);
-                    vec_deque![InferredType::U64]
+                    vec_deque![integer_type]
                 }
                 BinOp::Sub => {
                     push_instr!(exp.span, Bytecode::Sub);
-                    vec_deque![InferredType::U64]
+                    vec_deque![integer_type]
                 }
                 BinOp::Mul => {
                     push_instr!(exp.span, Bytecode::Mul);
-                    vec_deque![InferredType::U64]
+                    vec_deque![integer_type]
                 }
                 BinOp::Mod => {
                     push_instr!(exp.span, Bytecode::Mod);
-                    vec_deque![InferredType::U64]
+                    vec_deque![integer_type]
                 }
                 BinOp::Div => {
                     push_instr!(exp.span, Bytecode::Div);
-                    vec_deque![InferredType::U64]
+                    vec_deque![integer_type]
                 }
                 BinOp::BitOr => {
                     push_instr!(exp.span, Bytecode::BitOr);
-                    vec_deque![InferredType::U64]
+                    vec_deque![integer_type]
                 }
                 BinOp::BitAnd => {
                     push_instr!(exp.span, Bytecode::BitAnd);
-                    vec_deque![InferredType::U64]
+                    vec_deque![integer_type]
                 }
                 BinOp::Xor => {
                     push_instr!(exp.span, Bytecode::Xor);
-                    vec_deque![InferredType::U64]
+                    vec_deque![integer_type]
                 }
                 BinOp::Or => {
                     push_instr!(exp.span, Bytecode::Or);
@@ -1297,6 +1357,24 @@ fn compile_call(
                     function_frame.push()?;
                     vec_deque![InferredType::ByteArray]
                 }
+                Builtin::ToU8 => {
+                    push_instr!(call.span, Bytecode::CastU8);
+                    function_frame.pop()?;
+                    function_frame.push()?;
+                    vec_deque![InferredType::U8]
+                }
+                Builtin::ToU64 => {
+                    push_instr!(call.span, Bytecode::CastU64);
+                    function_frame.pop()?;
+                    function_frame.push()?;
+                    vec_deque![InferredType::U64]
+                }
+                Builtin::ToU128 => {
+                    push_instr!(call.span, Bytecode::CastU128);
+                    function_frame.pop()?;
+                    function_frame.push()?;
+                    vec_deque![InferredType::U128]
+                }
                 Builtin::Freeze => {
                     push_instr!(call.span, Bytecode::FreezeRef);
                     function_frame.pop()?; // pop mut ref
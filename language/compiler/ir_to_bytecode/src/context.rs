@@ -565,6 +565,24 @@ impl<'a> Context<'a> {
         }
     }
 
+    /// Given an identifier, find the module and the name of the struct it refers to, fails if the
+    /// struct is not bound. Unlike `struct_handle_index`, does not add the struct to the pool.
+    pub fn resolve_struct(&self, s: &QualifiedStructIdent) -> Result<QualifiedStructIdent> {
+        let mident = self.module_ident(&s.module)?;
+        let ident = QualifiedStructIdent::new(mident.name.clone(), s.name.clone());
+        let is_bound = if self.structs.contains_key(s) {
+            true
+        } else if s.module.as_inner() == ModuleName::self_name() {
+            false
+        } else {
+            self.dependency(mident)?.struct_handle(&ident).is_some()
+        };
+        if !is_bound {
+            bail!("Unbound struct {}", s)
+        }
+        Ok(ident)
+    }
+
     /// Given an identifier, find the struct handle index.
     /// Creates the handle and adds it to the pool if it it is the *first* time it looks
     /// up the struct in a dependency.
@@ -585,7 +603,9 @@ impl<'a> Context<'a> {
     ) -> Result<SignatureToken> {
         Ok(match orig {
             x @ SignatureToken::Bool
+            | x @ SignatureToken::U8
             | x @ SignatureToken::U64
+            | x @ SignatureToken::U128
             | x @ SignatureToken::String
             | x @ SignatureToken::ByteArray
             | x @ SignatureToken::Address
@@ -1,7 +1,9 @@
 // Copyright (c) The Libra Core Contributors
 // SPDX-License-Identifier: Apache-2.0
 
-use failure::Fail;
+use crate::parser::ast::Loc;
+use bytecode_source_map::utils::render_errors_to_string;
+use failure::{Error, Fail};
 use libra_types::vm_error::VMStatus;
 
 #[derive(Clone, Debug, Eq, Fail, Ord, PartialEq, PartialOrd)]
@@ -9,3 +11,35 @@ pub enum InternalCompilerError {
     #[fail(display = "Post-compile bounds check errors: {:?}", _0)]
     BoundsCheckErrors(Vec<VMStatus>),
 }
+
+/// An error raised while compiling the construct at `loc` in the source code.
+#[derive(Clone, Debug, Eq, Fail, PartialEq)]
+#[fail(display = "{}", message)]
+pub struct SourceError {
+    pub loc: Loc,
+    pub message: String,
+}
+
+/// Locates `err` at `loc`, unless it was already located at a construct nested in `loc`.
+pub fn with_loc(err: Error, loc: Loc) -> Error {
+    if err.downcast_ref::<SourceError>().is_some() {
+        err
+    } else {
+        SourceError {
+            loc,
+            message: err.to_string(),
+        }
+        .into()
+    }
+}
+
+/// Renders an error raised while compiling `source`, the contents of the file `file_name`. A
+/// located error is rendered as a labeled snippet of the source with its line and column.
+pub fn render_error(file_name: &str, source: &str, err: &Error) -> String {
+    match err.downcast_ref::<SourceError>() {
+        Some(error) => {
+            render_errors_to_string(file_name, source, vec![(error.loc, error.message.clone())])
+        }
+        None => format!("error: {}\n", err),
+    }
+}
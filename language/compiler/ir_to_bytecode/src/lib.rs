@@ -7,5 +7,6 @@ pub mod compiler;
 mod context;
 pub mod errors;
 pub mod parser;
+pub mod specs;
 
 // Unit tests for this crate are in the parent "compiler" crate.
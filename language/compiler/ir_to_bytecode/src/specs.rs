@@ -0,0 +1,270 @@
+// Copyright (c) The Libra Core Contributors
+// SPDX-License-Identifier: Apache-2.0
+
+//! The specifications of a compiled module. The bytecode has no room for them, so the compiler
+//! returns them in a side table indexed like the definitions of the module.
+
+use crate::{
+    context::Context,
+    errors::with_loc,
+    parser::ast::{
+        Condition, Condition_, ModuleDefinition, Spanned, SpecExp, StorageLocation,
+        StructDefinitionFields, Type, TypeVar, Var,
+    },
+};
+use failure::*;
+use vm::file_format::{FunctionDefinitionIndex, StructDefinitionIndex};
+
+/// The specifications of a function
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct FunctionSpecs {
+    /// The names of the formals, which the conditions refer to the arguments by
+    pub formals: Vec<Var>,
+    /// The names of the type formals, in declaration order
+    pub type_formals: Vec<TypeVar>,
+    /// The conditions on the behavior of the function
+    pub conditions: Vec<Condition_>,
+}
+
+/// The specifications of a struct
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct StructSpecs {
+    /// The names of the type formals, in declaration order
+    pub type_formals: Vec<TypeVar>,
+    /// The invariants that hold for every instance of the struct
+    pub invariants: Vec<SpecExp>,
+}
+
+/// The specifications of the functions and structs of a module.
+/// Module aliases in the specifications are replaced with the names of the modules they refer to,
+/// so that the specifications can be read without the imports of the module.
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct ModuleSpecs {
+    /// The specifications of the functions, by function definition index
+    pub functions: Vec<FunctionSpecs>,
+    /// The specifications of the structs, by struct definition index
+    pub structs: Vec<StructSpecs>,
+}
+
+impl ModuleSpecs {
+    /// Returns the specifications of the function at the given definition index
+    pub fn function_specs(&self, idx: FunctionDefinitionIndex) -> &FunctionSpecs {
+        &self.functions[idx.0 as usize]
+    }
+
+    /// Returns the specifications of the struct at the given definition index
+    pub fn struct_specs(&self, idx: StructDefinitionIndex) -> &StructSpecs {
+        &self.structs[idx.0 as usize]
+    }
+}
+
+/// Collects the specifications of `module`. Fails if a specification refers to an unbound
+/// formal, field, type formal, struct or return value.
+/// The structs and functions of the module are compiled in declaration order, so their positions
+/// in `module` are their definition indices.
+pub(crate) fn module_specs(context: &Context, module: &ModuleDefinition) -> Result<ModuleSpecs> {
+    let mut structs = vec![];
+    for s in &module.structs {
+        let type_formals = s.type_formals.iter().map(|(tv, _)| tv.value.clone());
+        let fields = match &s.fields {
+            StructDefinitionFields::Move { fields } => fields
+                .iter()
+                .map(|(f, _)| Var::new(f.value.name().into()))
+                .collect(),
+            StructDefinitionFields::Native => vec![],
+        };
+        let resolver = SpecResolver {
+            context,
+            names: fields,
+            type_formals: type_formals.collect(),
+            return_count: None,
+        };
+        let invariants = s
+            .invariants
+            .iter()
+            .map(|e| resolver.exp(e))
+            .collect::<Result<_>>()
+            .map_err(|err| {
+                let err = format_err!("Invalid invariant of struct {}: {}", s.name, err);
+                with_loc(err, s.span)
+            })?;
+        structs.push(StructSpecs {
+            type_formals: resolver.type_formals,
+            invariants,
+        });
+    }
+
+    let mut functions = vec![];
+    for (name, function) in &module.functions {
+        let signature = &function.signature;
+        let resolver = SpecResolver {
+            context,
+            names: signature
+                .formals
+                .iter()
+                .map(|(v, _)| v.value.clone())
+                .collect(),
+            type_formals: signature
+                .type_formals
+                .iter()
+                .map(|(tv, _)| tv.value.clone())
+                .collect(),
+            return_count: Some(signature.return_type.len()),
+        };
+        let conditions = function
+            .specifications
+            .iter()
+            .map(|condition| {
+                resolver
+                    .condition(&condition.value)
+                    .map(|value| Spanned {
+                        span: condition.span,
+                        value,
+                    })
+                    .map_err(|err| {
+                        let err =
+                            format_err!("Invalid specification of function {}: {}", name, err);
+                        with_loc(err, condition.span)
+                    })
+            })
+            .collect::<Result<_>>()?;
+        functions.push(FunctionSpecs {
+            formals: resolver.names,
+            type_formals: resolver.type_formals,
+            conditions,
+        });
+    }
+    Ok(ModuleSpecs { functions, structs })
+}
+
+// Fails if `e` refers to the state after the call, which only an ensures condition can do
+fn check_pre_state(e: &SpecExp) -> Result<()> {
+    match e {
+        SpecExp::Constant(_) => Ok(()),
+        SpecExp::StorageLocation(l) | SpecExp::Dereference(l) => check_pre_state_location(l),
+        SpecExp::GlobalExists { address, .. } => check_pre_state_location(address),
+        SpecExp::Not(e) => check_pre_state(e),
+        SpecExp::Binop(e1, _, e2) => {
+            check_pre_state(e1)?;
+            check_pre_state(e2)
+        }
+        SpecExp::Old(_) => bail!("old() can only be used in an ensures condition"),
+    }
+}
+
+fn check_pre_state_location(l: &StorageLocation) -> Result<()> {
+    match l {
+        StorageLocation::GlobalResource { address, .. } => check_pre_state_location(address),
+        StorageLocation::AccessPath { base, .. } => check_pre_state_location(base),
+        StorageLocation::Ret(_) => bail!("RET can only be used in an ensures condition"),
+        StorageLocation::Formal(_)
+        | StorageLocation::TxnSenderAddress
+        | StorageLocation::Address(_) => Ok(()),
+    }
+}
+
+struct SpecResolver<'a, 'b> {
+    context: &'b Context<'a>,
+    // The formals of a function, or the fields of a struct
+    names: Vec<Var>,
+    type_formals: Vec<TypeVar>,
+    // The number of values a function returns, `None` for a struct
+    return_count: Option<usize>,
+}
+
+impl<'a, 'b> SpecResolver<'a, 'b> {
+    fn condition(&self, condition: &Condition) -> Result<Condition> {
+        Ok(match condition {
+            Condition::Requires(e) => {
+                check_pre_state(e)?;
+                Condition::Requires(self.exp(e)?)
+            }
+            Condition::Ensures(e) => Condition::Ensures(self.exp(e)?),
+            Condition::AbortsIf(e) => {
+                check_pre_state(e)?;
+                Condition::AbortsIf(self.exp(e)?)
+            }
+        })
+    }
+
+    fn exp(&self, e: &SpecExp) -> Result<SpecExp> {
+        Ok(match e {
+            SpecExp::Constant(v) => SpecExp::Constant(v.clone()),
+            SpecExp::StorageLocation(l) => SpecExp::StorageLocation(self.location(l)?),
+            SpecExp::GlobalExists {
+                type_,
+                type_actuals,
+                address,
+            } => SpecExp::GlobalExists {
+                type_: self.context.resolve_struct(type_)?,
+                type_actuals: self.types(type_actuals)?,
+                address: self.location(address)?,
+            },
+            SpecExp::Dereference(l) => SpecExp::Dereference(self.location(l)?),
+            SpecExp::Not(e) => SpecExp::Not(Box::new(self.exp(e)?)),
+            SpecExp::Binop(e1, op, e2) => {
+                SpecExp::Binop(Box::new(self.exp(e1)?), op.clone(), Box::new(self.exp(e2)?))
+            }
+            SpecExp::Old(e) => {
+                if self.return_count.is_none() {
+                    bail!("old() can only be used in the specification of a function")
+                }
+                SpecExp::Old(Box::new(self.exp(e)?))
+            }
+        })
+    }
+
+    fn location(&self, l: &StorageLocation) -> Result<StorageLocation> {
+        Ok(match l {
+            StorageLocation::Formal(v) => {
+                if !self.names.contains(v) {
+                    bail!("Unbound name {}", v)
+                }
+                StorageLocation::Formal(v.clone())
+            }
+            StorageLocation::GlobalResource {
+                type_,
+                type_actuals,
+                address,
+            } => StorageLocation::GlobalResource {
+                type_: self.context.resolve_struct(type_)?,
+                type_actuals: self.types(type_actuals)?,
+                address: Box::new(self.location(address)?),
+            },
+            StorageLocation::AccessPath { base, fields } => StorageLocation::AccessPath {
+                base: Box::new(self.location(base)?),
+                fields: fields.clone(),
+            },
+            StorageLocation::Ret(i) => match self.return_count {
+                Some(count) if (*i as usize) < count => StorageLocation::Ret(*i),
+                Some(_) => bail!("The function does not return a value at position {}", i),
+                None => bail!("RET can only be used in the specification of a function"),
+            },
+            StorageLocation::TxnSenderAddress => StorageLocation::TxnSenderAddress,
+            StorageLocation::Address(a) => StorageLocation::Address(*a),
+        })
+    }
+
+    fn types(&self, tys: &[Type]) -> Result<Vec<Type>> {
+        tys.iter().map(|ty| self.type_(ty)).collect()
+    }
+
+    fn type_(&self, ty: &Type) -> Result<Type> {
+        Ok(match ty {
+            Type::Struct(ident, tys) => {
+                Type::Struct(self.context.resolve_struct(ident)?, self.types(tys)?)
+            }
+            Type::Reference(is_mutable, t) => {
+                Type::Reference(*is_mutable, Box::new(self.type_(t)?))
+            }
+            Type::TypeParameter(tv) => {
+                if !self.type_formals.contains(tv) {
+                    bail!("Unbound type formal {}", tv)
+                }
+                Type::TypeParameter(tv.clone())
+            }
+            Type::String => bail!("`string` type is currently unused"),
+            ty => ty.clone(),
+        })
+    }
+}
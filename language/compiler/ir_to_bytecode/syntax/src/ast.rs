@@ -165,8 +165,12 @@ pub enum Kind {
 pub enum Type {
     /// `address`
     Address,
+    /// `u8`
+    U8,
     /// `u64`
     U64,
+    /// `u128`
+    U128,
     /// `bool`
     Bool,
     /// `bytearray`
@@ -221,6 +225,8 @@ pub struct StructDefinition {
     pub type_formals: Vec<(TypeVar_, Kind)>,
     /// the fields each instance has
     pub fields: StructDefinitionFields,
+    /// Expressions over the fields that hold for every instance of the struct
+    pub invariants: Vec<SpecExp>,
 }
 
 /// The type of a StructDefinition along with its source location information
@@ -291,6 +297,8 @@ pub struct Function {
     /// This list of acquires grants the borrow checker the ability to statically verify the safety
     /// of references into global storage
     pub acquires: Vec<StructName>,
+    /// The conditions that specify the behavior of the procedure
+    pub specifications: Vec<Condition_>,
     /// The code for the procedure
     pub body: FunctionBody,
 }
@@ -335,6 +343,13 @@ pub enum Builtin {
 
     /// Convert a mutable reference into an immutable one
     Freeze,
+
+    /// Casts an integer to a `u8`, aborts if the integer does not fit
+    ToU8,
+    /// Casts an integer to a `u64`, aborts if the integer does not fit
+    ToU64,
+    /// Casts an integer to a `u128`
+    ToU128,
 }
 
 /// Enum for different function calls
@@ -447,8 +462,12 @@ pub type Block_ = Spanned<Block>;
 pub enum CopyableVal {
     /// An address in the global storage
     Address(AccountAddress),
+    /// An unsigned 8-bit integer, `<n>u8`
+    U8(u8),
     /// An unsigned 64-bit integer
     U64(u64),
+    /// An unsigned 128-bit integer, `<n>u128`
+    U128(u128),
     /// true or false
     Bool(bool),
     /// `b"<bytes>"`
@@ -553,6 +572,91 @@ pub enum Exp {
 /// The type for a `Exp` and its location
 pub type Exp_ = Spanned<Exp>;
 
+//**************************************************************************************************
+// Specifications
+//**************************************************************************************************
+
+/// A location in memory that a specification refers to
+#[derive(Debug, Clone, PartialEq)]
+pub enum StorageLocation {
+    /// A formal of the function, or a field of the struct in a struct invariant
+    /// `x`
+    Formal(Var),
+    /// The resource of the given type published under an address
+    /// `global<M.T<tys>>(address)`
+    GlobalResource {
+        /// The struct of the resource
+        type_: QualifiedStructIdent,
+        /// The type actuals of the resource
+        type_actuals: Vec<Type>,
+        /// The location of the address the resource is published under
+        address: Box<StorageLocation>,
+    },
+    /// A path of fields from another location
+    /// `l.f_1.f_2. ... .f_j`
+    AccessPath {
+        /// The location the path starts from
+        base: Box<StorageLocation>,
+        /// The fields accessed, in order
+        fields: Vec<Field>,
+    },
+    /// The value returned by the function at the given position
+    /// `RET(i)`
+    Ret(u8),
+    /// The address of the sender of the transaction
+    /// `txn_sender`
+    TxnSenderAddress,
+    /// A literal address
+    Address(AccountAddress),
+}
+
+/// Enum for the expressions of specifications
+#[derive(Debug, Clone, PartialEq)]
+pub enum SpecExp {
+    /// A literal value
+    Constant(CopyableVal),
+    /// The value held in a storage location
+    StorageLocation(StorageLocation),
+    /// Whether a resource of the given type is published under an address
+    /// `global_exists<M.T<tys>>(address)`
+    GlobalExists {
+        /// The struct of the resource
+        type_: QualifiedStructIdent,
+        /// The type actuals of the resource
+        type_actuals: Vec<Type>,
+        /// The location of the address
+        address: StorageLocation,
+    },
+    /// The value a reference held in a storage location points to
+    /// `*l`
+    Dereference(StorageLocation),
+    /// `!e`
+    Not(Box<SpecExp>),
+    /// `e_1 op e_2`
+    Binop(Box<SpecExp>, BinOp, Box<SpecExp>),
+    /// The value of an expression in the state the function is called in
+    /// `old(e)`
+    Old(Box<SpecExp>),
+}
+
+/// A condition on the behavior of a function
+#[derive(Debug, Clone, PartialEq)]
+pub enum Condition {
+    /// The expression must hold when the function is called
+    /// `requires e`
+    Requires(SpecExp),
+    /// The expression holds when the function returns without aborting
+    /// `ensures e`
+    Ensures(SpecExp),
+    /// The function aborts if the expression holds. The function aborts if and only if one of
+    /// its `aborts_if` conditions holds.
+    /// `aborts_if e`
+    AbortsIf(SpecExp),
+}
+
+/// The type for a `Condition` and its location
+pub type Condition_ = Spanned<Condition>;
+
 //**************************************************************************************************
 // impls
 //**************************************************************************************************
@@ -698,11 +802,21 @@ impl Type {
         Type::Address
     }
 
+    /// Creates a new u8 type
+    pub fn u8() -> Type {
+        Type::U8
+    }
+
     /// Creates a new u64 type
     pub fn u64() -> Type {
         Type::U64
     }
 
+    /// Creates a new u128 type
+    pub fn u128() -> Type {
+        Type::U128
+    }
+
     /// Creates a new bool type
     pub fn bool() -> Type {
         Type::Bool
@@ -767,8 +881,8 @@ impl StructName {
 
 impl StructDefinition {
     /// Creates a new StructDefinition from the resource kind (true if resource), the string
-    /// representation of the name, and the user specified fields, a map from their names to their
-    /// types
+    /// representation of the name, the user specified fields, a map from their names to their
+    /// types, and the invariants over those fields
     /// Does not verify the correctness of any internal properties, e.g. doesn't check that the
     /// fields do not have reference types
     pub fn move_declared<L, T>(
@@ -776,12 +890,14 @@ impl StructDefinition {
         name: impl Into<Box<str>>,
         type_formals: Vec<(TypeVar_, Kind)>,
         fields: Fields<Type>,
+        invariants: Vec<SpecExp>,
     ) -> Result<Self, ParseError<L, T, failure::Error>> {
         Ok(StructDefinition {
             is_nominal_resource,
             name: StructName::parse(name)?,
             type_formals,
             fields: StructDefinitionFields::Move { fields },
+            invariants,
         })
     }
 
@@ -798,6 +914,7 @@ impl StructDefinition {
             name: StructName::parse(name)?,
             type_formals,
             fields: StructDefinitionFields::Native,
+            invariants: vec![],
         })
     }
 }
@@ -848,6 +965,7 @@ impl Function {
         return_type: Vec<Type>,
         type_formals: Vec<(TypeVar_, Kind)>,
         acquires: Vec<StructName>,
+        specifications: Vec<Condition_>,
         body: FunctionBody,
     ) -> Self {
         let signature = FunctionSignature::new(formals, return_type, type_formals);
@@ -855,6 +973,7 @@ impl Function {
             visibility,
             signature,
             acquires,
+            specifications,
             body,
         }
     }
@@ -1071,6 +1190,25 @@ fn parse_identifier<L, T>(s: Box<str>) -> Result<Identifier, ParseError<L, T, fa
     Identifier::new(s).map_err(|error| ParseError::User { error })
 }
 
+/// Parses the digits of the integer literal `literal` starting at byte `location`, failing if the
+/// integer does not fit in the type of the literal.
+pub fn parse_integer<N, T>(
+    location: usize,
+    literal: &str,
+    digits: &str,
+) -> Result<N, ParseError<usize, T, failure::Error>>
+where
+    N: std::str::FromStr,
+{
+    N::from_str(digits).map_err(|_| ParseError::User {
+        error: failure::format_err!(
+            "Integer literal {} at byte {} is out of range",
+            literal,
+            location
+        ),
+    })
+}
+
 //**************************************************************************************************
 // Trait impls
 //**************************************************************************************************
@@ -1324,7 +1462,9 @@ fn format_type_formals(formals: &[(TypeVar_, Kind)]) -> String {
 impl fmt::Display for Type {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
         match self {
+            Type::U8 => write!(f, "u8"),
             Type::U64 => write!(f, "u64"),
+            Type::U128 => write!(f, "u128"),
             Type::Bool => write!(f, "bool"),
             Type::Address => write!(f, "address"),
             Type::ByteArray => write!(f, "bytearray"),
@@ -1370,6 +1510,9 @@ impl fmt::Display for Builtin {
                 write!(f, "move_to_sender<{}{}>", t, format_type_actuals(tys))
             }
             Builtin::Freeze => write!(f, "freeze"),
+            Builtin::ToU8 => write!(f, "to_u8"),
+            Builtin::ToU64 => write!(f, "to_u64"),
+            Builtin::ToU128 => write!(f, "to_u128"),
         }
     }
 }
@@ -1496,7 +1639,9 @@ impl fmt::Display for Block {
 impl fmt::Display for CopyableVal {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
         match self {
+            CopyableVal::U8(v) => write!(f, "{}u8", v),
             CopyableVal::U64(v) => write!(f, "{}", v),
+            CopyableVal::U128(v) => write!(f, "{}u128", v),
             CopyableVal::Bool(v) => write!(f, "{}", v),
             CopyableVal::ByteArray(v) => write!(f, "{}", v),
             CopyableVal::Address(v) => write!(f, "0x{}", hex::encode(&v)),
@@ -1548,6 +1693,69 @@ impl fmt::Display for BinOp {
     }
 }
 
+impl fmt::Display for StorageLocation {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            StorageLocation::Formal(v) => write!(f, "{}", v),
+            StorageLocation::GlobalResource {
+                type_,
+                type_actuals,
+                address,
+            } => write!(
+                f,
+                "global<{}{}>({})",
+                type_,
+                format_type_actuals(type_actuals),
+                address
+            ),
+            StorageLocation::AccessPath { base, fields } => {
+                write!(f, "{}", base)?;
+                for field in fields {
+                    write!(f, ".{}", field)?;
+                }
+                Ok(())
+            }
+            StorageLocation::Ret(i) => write!(f, "RET({})", i),
+            StorageLocation::TxnSenderAddress => write!(f, "txn_sender"),
+            StorageLocation::Address(a) => write!(f, "0x{}", hex::encode(&a)),
+        }
+    }
+}
+
+impl fmt::Display for SpecExp {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            SpecExp::Constant(v) => write!(f, "{}", v),
+            SpecExp::StorageLocation(l) => write!(f, "{}", l),
+            SpecExp::GlobalExists {
+                type_,
+                type_actuals,
+                address,
+            } => write!(
+                f,
+                "global_exists<{}{}>({})",
+                type_,
+                format_type_actuals(type_actuals),
+                address
+            ),
+            SpecExp::Dereference(l) => write!(f, "*{}", l),
+            SpecExp::Not(e) => write!(f, "!({})", e),
+            SpecExp::Binop(e1, o, e2) => write!(f, "({} {} {})", e1, o, e2),
+            SpecExp::Old(e) => write!(f, "old({})", e),
+        }
+    }
+}
+
+impl fmt::Display for Condition {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Condition::Requires(e) => write!(f, "requires {}", e),
+            Condition::Ensures(e) => write!(f, "ensures {}", e),
+            Condition::AbortsIf(e) => write!(f, "aborts_if {}", e),
+        }
+    }
+}
+
 impl fmt::Display for Exp {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
         match self {
@@ -19,7 +19,9 @@
 //!
 //! g ∈ GroundType ::=
 //!   | bool
+//!   | u8        // unsigned 8 bit integer
 //!   | u64       // unsigned 64 bit integer
+//!   | u128      // unsigned 128 bit integer
 //!   | address   // 32 byte account address
 //!   | bytearray // immutable, arbitrarily sized array of bytes
 //!
@@ -52,6 +54,8 @@
 //!   | true
 //!   | false
 //!   | u        // u64 literal
+//!   | uu8      // u8 literal, e.g. 42u8
+//!   | uu128    // u128 literal, e.g. 42u128
 //!   | 0xaddr   // 32 byte address literal
 //!   | b"bytes" // arbitrary length bytearray literal
 //! ```
@@ -82,7 +86,7 @@
 //!   | !e_1
 //!   | e_1 || e_2
 //!   | e_1 && e_2
-//!   // u64 operators
+//!   // integer operators, both operands have the same integer type
 //!   | e_1 >= e_2
 //!   | e_1 <= e_2
 //!   | e_1 > e_2
@@ -136,6 +140,12 @@
 //!                               // gives the sequence number for this transaction
 //!   | get_gas_remaining()       // type: 'unit -> u64'
 //!                               // gives the amount of gas gas units remaining before the transaction execution will be forced to halt execution
+//!   | to_u8(e)                  // type: 'u8 -> u8' or 'u64 -> u8' or 'u128 -> u8'
+//!                               // casts an integer to a u8, aborting if it does not fit
+//!   | to_u64(e)                 // type: 'u8 -> u64' or 'u64 -> u64' or 'u128 -> u64'
+//!                               // casts an integer to a u64, aborting if it does not fit
+//!   | to_u128(e)                // type: 'u8 -> u128' or 'u64 -> u128' or 'u128 -> u128'
+//!                               // casts an integer to a u128
 //!
 //! call ∈ Call ::=
 //!   | mop
@@ -168,6 +178,32 @@
 //!   | s_1 s_2                     // sequencing
 //! ```
 //!
+//! ## Specifications
+//! ```text
+//! l ∈ StorageLocation ::=
+//!   | x                       // formal 'x' of the procedure, or field 'x' in a struct invariant
+//!   | RET(i)                  // the 'i'th value returned by the procedure
+//!   | txn_sender              // the address of the sender of the transaction
+//!   | global<d.n>(l)          // the resource struct 'n' published at the address held in 'l'
+//!   | global<d.n>(0xaddr)     // the resource struct 'n' published at the address 'addr'
+//!   | l.f_1. ... .f_j         // the field 'f_j' of ... the field 'f_1' of 'l'
+//!
+//! se ∈ SpecExp ::=
+//!   | v
+//!   | l
+//!   | *l                      // the value the reference held in 'l' points to
+//!   | global_exists<d.n>(l)   // whether the resource struct 'n' is published at the address 'l'
+//!   | old(se)                 // the value of 'se' when the procedure is called
+//!   | !se
+//!   | se_1 op se_2            // any binary operator of expressions
+//!                             // '||' and '&&' bind less tightly than comparisons
+//!
+//! cond ∈ Condition ::=
+//!   | requires se             // 'se' holds when the procedure is called
+//!   | ensures se              // 'se' holds when the procedure returns without aborting
+//!   | aborts_if se            // the procedure aborts if and only if one of its 'aborts_if' holds
+//! ```
+//!
 //! ## Imports
 //!```text
 //! idecl ∈ Import ::=
@@ -180,6 +216,8 @@
 //!   | resource n { f_1: t_1, ..., f_j: t_j } // declaration of a resource struct
 //!   | struct n { f_1: t_1, ..., f_j: t_j }   // declaration of a non-resource (value) struct
 //!                                            // s.t. any 't_i' is not of resource kind
+//!   | resource n { f_1: t_1, ..., f_j: t_j invariant se_1 ... invariant se_k }
+//!                                            // the invariants hold for every instance of 'n'
 //!
 //! body ∈ ProcedureBody ::=
 //!  | let x_1; ... let x_j; s // The locals declared in this procedure, and the code for that procedure
@@ -190,6 +228,8 @@
 //!   | native (public?) p(x_1: 𝛕_1, ..., x_j: 𝛕_j): 𝛕-list; // declaration of a native procedure
 //!                                                         // the implementation is provided by the VM
 //!                                                         // the procedure may be public, or internal to the module
+//!   | (public?) p(x_1: 𝛕_1, ..., x_j: 𝛕_j): 𝛕-list cond_1 ... cond_k { body }
+//!                                                          // the conditions specify the behavior of the procedure
 //!
 //! mdecl ∈ ModuleDecl ::=
 //!   | module m { idecl_1 ... idecl_i sdecl_1 ... sdecl_j pdecl_1 ... pdecl_k }
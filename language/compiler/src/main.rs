@@ -1,12 +1,19 @@
 // Copyright (c) The Libra Core Contributors
 // SPDX-License-Identifier: Apache-2.0
 
+use bytecode_source_map::{
+    source_map::{ModuleSourceMap, SourceMap},
+    utils::render_verification_errors,
+};
 use bytecode_verifier::{
-    verifier::{verify_module_dependencies, VerifiedProgram},
-    VerifiedModule,
+    verifier::{verify_module_dependencies, verify_script_dependencies},
+    VerifiedModule, VerifiedScript,
 };
 use compiler::{util, Compiler};
-use ir_to_bytecode::parser::{parse_module, parse_script};
+use ir_to_bytecode::{
+    errors::render_error,
+    parser::{ast::Loc, parse_module, parse_script},
+};
 use libra_types::{
     access_path::AccessPath,
     account_address::AccountAddress,
@@ -22,7 +29,7 @@ use std::{
 };
 use stdlib::stdlib_modules;
 use structopt::StructOpt;
-use vm::file_format::CompiledModule;
+use vm::file_format::{CompiledModule, CompiledProgram};
 
 #[derive(Debug, StructOpt)]
 #[structopt(name = "IR Compiler", about = "Move IR to bytecode compiler.")]
@@ -53,24 +60,68 @@ struct Args {
     pub output_source_maps: bool,
 }
 
-fn print_errors_and_exit(verification_errors: &[VMStatus]) -> ! {
+fn print_errors_and_exit(
+    source_path: &Path,
+    source_map: &ModuleSourceMap<Loc>,
+    verification_errors: &[VMStatus],
+) -> ! {
     println!("Verification failed. Errors below:");
-    for e in verification_errors {
-        println!("{:?}", e);
-    }
+    let source = fs::read_to_string(source_path).expect("Unable to read file");
+    print!(
+        "{}",
+        render_verification_errors(
+            &source_path.to_string_lossy(),
+            &source,
+            source_map,
+            verification_errors
+        )
+    );
     std::process::exit(1);
 }
 
-fn do_verify_module(module: CompiledModule, dependencies: &[VerifiedModule]) -> VerifiedModule {
-    let verified_module =
-        VerifiedModule::new(module).unwrap_or_else(|(_, errors)| print_errors_and_exit(&errors));
+fn do_verify_module(
+    source_path: &Path,
+    source_map: &ModuleSourceMap<Loc>,
+    module: CompiledModule,
+    dependencies: &[VerifiedModule],
+) -> VerifiedModule {
+    let verified_module = VerifiedModule::new(module)
+        .unwrap_or_else(|(_, errors)| print_errors_and_exit(source_path, source_map, &errors));
     let errors = verify_module_dependencies(&verified_module, dependencies);
     if !errors.is_empty() {
-        print_errors_and_exit(&errors);
+        print_errors_and_exit(source_path, source_map, &errors);
     }
     verified_module
 }
 
+fn do_verify_program(
+    source_path: &Path,
+    source_maps: &SourceMap<Loc>,
+    program: CompiledProgram,
+    dependencies: &[VerifiedModule],
+) -> CompiledProgram {
+    // The modules of the program come first, in the same order as their source maps.
+    let mut dependencies = dependencies.to_vec();
+    let mut modules = vec![];
+    for (module, source_map) in program.modules.into_iter().zip(source_maps) {
+        let verified_module = do_verify_module(source_path, source_map, module, &dependencies);
+        modules.push(verified_module.as_inner().clone());
+        dependencies.push(verified_module);
+    }
+
+    let source_map = source_maps.last().expect("Missing source map for script");
+    let verified_script = VerifiedScript::new(program.script)
+        .unwrap_or_else(|(_, errors)| print_errors_and_exit(source_path, source_map, &errors));
+    let errors = verify_script_dependencies(&verified_script, &dependencies);
+    if !errors.is_empty() {
+        print_errors_and_exit(source_path, source_map, &errors);
+    }
+    CompiledProgram {
+        modules,
+        script: verified_script.into_inner(),
+    }
+}
+
 fn write_output(path: &PathBuf, buf: &[u8]) {
     let mut f = fs::File::create(path)
         .unwrap_or_else(|err| panic!("Unable to open output file {:?}: {}", path, err));
@@ -151,12 +202,13 @@ fn main() {
         };
         let (compiled_program, source_map, dependencies) = compiler
             .into_compiled_program_and_source_maps_deps(&source)
-            .expect("Failed to compile program");
+            .unwrap_or_else(|err| {
+                print!("{}", render_error(&source_path.to_string_lossy(), &source, &err));
+                std::process::exit(1);
+            });
 
         let compiled_program = if !args.no_verify {
-            let verified_program = VerifiedProgram::new(compiled_program, &dependencies)
-                .expect("Failed to verify program");
-            verified_program.into_inner()
+            do_verify_program(source_path, &source_map, compiled_program, &dependencies)
         } else {
             compiled_program
         };
@@ -182,7 +234,8 @@ fn main() {
         let (compiled_module, source_map) =
             util::do_compile_module(&args.source_path, address, &deps);
         let compiled_module = if !args.no_verify {
-            let verified_module = do_verify_module(compiled_module, &deps);
+            let verified_module =
+                do_verify_module(source_path, &source_map, compiled_module, &deps);
             verified_module.into_inner()
         } else {
             compiled_module
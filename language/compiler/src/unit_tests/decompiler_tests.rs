@@ -0,0 +1,165 @@
+// Copyright (c) The Libra Core Contributors
+// SPDX-License-Identifier: Apache-2.0
+
+use crate::unit_tests::testutils::compile_module_string_with_stdlib;
+use bytecode_source_map::{
+    decompiler::Decompiler, mapping::SourceMapping, source_map::ModuleSourceMap,
+};
+use bytecode_verifier::VerifiedModule;
+use ir_to_bytecode::{compiler::compile_module, parser::parse_module};
+use stdlib::stdlib_modules;
+use vm::{
+    access::ModuleAccess, file_format::CompiledModule, printers::display_function_definitions,
+};
+
+fn decompile(module: CompiledModule) -> String {
+    let source_map = ModuleSourceMap::dummy_from_module(&module).unwrap();
+    Decompiler::new(SourceMapping::new(source_map, module))
+        .decompile()
+        .unwrap()
+}
+
+// Decompiles `module`, compiles the decompiled code again and checks that it decompiles to the
+// same code, and that the functions of the recompiled module have the same signatures, acquires
+// and code as the functions of `module`. Returns the decompiled code.
+fn round_trip(module: CompiledModule, deps: &[VerifiedModule]) -> String {
+    let address = *module.address();
+    let functions = display_function_definitions(&module);
+    let decompiled = decompile(module);
+    let parsed_module = parse_module(&decompiled)
+        .unwrap_or_else(|err| panic!("Unable to parse {}: {}", decompiled, err));
+    let recompiled = compile_module(address, parsed_module, deps)
+        .unwrap_or_else(|err| panic!("Unable to compile {}: {}", decompiled, err))
+        .0;
+    let recompiled = VerifiedModule::new(recompiled)
+        .unwrap_or_else(|(_, errors)| panic!("Unable to verify {}: {:?}", decompiled, errors));
+    assert_eq!(
+        display_function_definitions(recompiled.as_inner()),
+        functions,
+        "The functions of the recompiled module differ from\n{}",
+        decompiled,
+    );
+    assert_eq!(decompile(recompiled.into_inner()), decompiled);
+    decompiled
+}
+
+fn round_trip_with_stdlib(code: &str) -> String {
+    let module = compile_module_string_with_stdlib(code).unwrap();
+    round_trip(module, stdlib_modules())
+}
+
+#[test]
+fn decompile_stdlib() {
+    let stdlib = stdlib_modules();
+    for (idx, module) in stdlib.iter().enumerate() {
+        round_trip(module.clone().into_inner(), &stdlib[..idx]);
+    }
+}
+
+#[test]
+fn decompile_declarations() {
+    let code = "
+        module M {
+            import 0x0.LibraCoin;
+            resource R<T: resource> { coin: LibraCoin.T, t: T }
+            struct S { x: u64, y: bool, z: bytearray }
+
+            public publish<T: resource>(coin: LibraCoin.T, t: T) {
+                move_to_sender<R<T>>(R<T> { coin: move(coin), t: move(t) });
+                return;
+            }
+
+            take<T: resource>(addr: address): LibraCoin.T * T acquires R {
+                let coin: LibraCoin.T;
+                let t: T;
+                R<T> { coin: coin, t: t } = move_from<R<T>>(move(addr));
+                return move(coin), move(t);
+            }
+        }
+    ";
+    let decompiled = round_trip_with_stdlib(code);
+    assert!(decompiled.contains("    import 0x0.LibraCoin;\n"));
+    assert!(decompiled.contains("    resource R<Ty0: resource> {\n"));
+    assert!(decompiled
+        .contains("    take<Ty0: resource>(loc0: address): LibraCoin.T * Ty0 acquires R {\n"));
+    assert!(decompiled.contains("        R<Ty0>{coin: loc1, t: loc2} = move_from<R<Ty0>>"));
+}
+
+#[test]
+fn decompile_control_flow() {
+    let code = "
+        module M {
+            sum(n: u64): u64 {
+                let i: u64;
+                let s: u64;
+                i = 0;
+                s = 0;
+                while (copy(i) < copy(n)) {
+                    i = copy(i) + 1;
+                    if (copy(i) % 2 == 0) {
+                        continue;
+                    }
+                    s = copy(s) + copy(i) * 3;
+                }
+                return move(s);
+            }
+
+            find(n: u64): bool {
+                loop {
+                    if (copy(n) == 0) {
+                        break;
+                    } else {
+                        n = copy(n) - 1;
+                    }
+                    if (copy(n) == 42) {
+                        return true;
+                    }
+                }
+                return false;
+            }
+
+            check(x: u64, y: u64) {
+                assert((copy(x) > copy(y)) && !(copy(x) == 0), 7);
+                return;
+            }
+        }
+    ";
+    let decompiled = round_trip_with_stdlib(code);
+    assert!(decompiled.contains("        while (copy(loc1) < copy(loc0)) {\n"));
+    assert!(decompiled.contains("            continue;\n"));
+    assert!(decompiled.contains("loc2 = copy(loc2) + copy(loc1) * 3;\n"));
+    assert!(decompiled.contains("        loop {\n"));
+    assert!(decompiled.contains("                break;\n            } else {\n"));
+    assert!(decompiled
+        .contains("        assert((copy(loc0) > copy(loc1)) && !(copy(loc0) == 0), 7);\n"));
+}
+
+#[test]
+fn decompile_assignments() {
+    let code = "
+        module M {
+            struct S { f: u64, g: u64 }
+
+            pair(s: Self.S): u64 * u64 {
+                let f: u64;
+                let g: u64;
+                S { f: f, g: g } = move(s);
+                return move(f), move(g);
+            }
+
+            update(s: &mut Self.S, x: u64) {
+                let a: u64;
+                let b: u64;
+                a, b = Self.pair(S { f: copy(x), g: 1 });
+                *(&mut copy(s).f), _ = (move(a), move(b));
+                *(&mut move(s).g) = copy(x) + 1;
+                return;
+            }
+        }
+    ";
+    let decompiled = round_trip_with_stdlib(code);
+    assert!(decompiled.contains("        S{f: loc1, g: loc2} = move(loc0);\n"));
+    assert!(decompiled.contains("        loc2, loc3 = Self.pair(S{f: copy(loc1), g: 1});\n"));
+    assert!(decompiled.contains("        *&mut copy(loc0).f, _ = (move(loc2), move(loc3));\n"));
+    assert!(decompiled.contains("        *&mut move(loc0).g = copy(loc1) + 1;\n"));
+}
@@ -0,0 +1,64 @@
+// Copyright (c) The Libra Core Contributors
+// SPDX-License-Identifier: Apache-2.0
+
+use bytecode_source_map::utils::{render_verification_errors, verification_error_location};
+use bytecode_verifier::VerifiedModule;
+use ir_to_bytecode::{
+    compiler::{compile_module, compile_program},
+    errors::{render_error, SourceError},
+    parser::{ast::Loc, parse_module, parse_program},
+};
+use libra_types::account_address::AccountAddress;
+use stdlib::stdlib_modules;
+
+fn snippet(code: &str, loc: Loc) -> &str {
+    &code[loc.start().0 as usize..loc.end().0 as usize]
+}
+
+#[test]
+fn compile_error_is_located_at_innermost_construct() {
+    let code = "main() {\n    let x: u64;\n    x = move(y) + 1;\n    return;\n}\n";
+    let program = parse_program(code).unwrap();
+    let err = compile_program(AccountAddress::default(), program, stdlib_modules()).unwrap_err();
+    let source_error = err.downcast_ref::<SourceError>().unwrap();
+    assert_eq!(source_error.message, "variable y undefined");
+    assert_eq!(snippet(code, source_error.loc), "move(y)");
+
+    let rendered = render_error("script.mvir", code, &err);
+    assert!(rendered.contains("variable y undefined"));
+    assert!(rendered.contains("script.mvir:3:9"));
+}
+
+#[test]
+fn compile_error_in_signature_is_located_at_function() {
+    let code = "
+        module M {
+            f(x: Self.Missing) { return; }
+        }
+    ";
+    let module = parse_module(code).unwrap();
+    let err = compile_module(AccountAddress::default(), module, stdlib_modules()).unwrap_err();
+    let source_error = err.downcast_ref::<SourceError>().unwrap();
+    assert!(snippet(code, source_error.loc).starts_with("f(x: Self.Missing)"));
+}
+
+#[test]
+fn verification_error_is_located_at_instruction() {
+    let code = "
+        module M {
+            f(): u64 {
+                return true;
+            }
+        }
+    ";
+    let module = parse_module(code).unwrap();
+    let (compiled_module, source_map) =
+        compile_module(AccountAddress::default(), module, stdlib_modules()).unwrap();
+    let (_, errors) = VerifiedModule::new(compiled_module).unwrap_err();
+    let loc = verification_error_location(&source_map, &errors[0]).unwrap();
+    assert!(snippet(code, loc).starts_with("return true"));
+
+    let rendered = render_verification_errors("m.mvir", code, &source_map, &errors);
+    assert!(rendered.contains("RET_TYPE_MISMATCH_ERROR"));
+    assert!(rendered.contains("m.mvir:4:17"));
+}
@@ -7,8 +7,11 @@ pub(crate) mod testutils;
 
 mod branch_tests;
 mod cfg_tests;
+mod decompiler_tests;
+mod diagnostics_tests;
 mod expression_tests;
 mod function_tests;
 mod import_tests;
 mod serializer_tests;
+mod spec_tests;
 mod stdlib_scripts;
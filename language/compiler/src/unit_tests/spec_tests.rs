@@ -0,0 +1,202 @@
+// Copyright (c) The Libra Core Contributors
+// SPDX-License-Identifier: Apache-2.0
+
+use ir_to_bytecode::{
+    compiler::compile_module_with_specs,
+    parser::{
+        ast::{BinOp, Condition, CopyableVal, SpecExp, StorageLocation, Type, Var},
+        parse_module,
+    },
+    specs::ModuleSpecs,
+};
+use libra_types::{account_address::AccountAddress, identifier::Identifier};
+use stdlib::stdlib_modules;
+use vm::file_format::{FunctionDefinitionIndex, StructDefinitionIndex};
+
+fn compile_specs(code: &str) -> failure::Result<ModuleSpecs> {
+    let module = parse_module(code)?;
+    let address = AccountAddress::default();
+    let (_, _, specs) = compile_module_with_specs(address, module, stdlib_modules())?;
+    Ok(specs)
+}
+
+fn var(name: &str) -> Var {
+    Var::new(Identifier::new(name).unwrap())
+}
+
+#[test]
+fn compile_function_specs() {
+    let code = "
+        module M {
+            import 0x0.LibraAccount as Account;
+
+            public balance(addr: address): u64
+                requires global_exists<Account.T>(addr)
+                ensures RET(0) == global<Account.T>(addr).balance.value
+            {
+                return Account.balance(move(addr));
+            }
+
+            incr(x: &mut u64)
+                aborts_if *x + 1 > 18446744073709551615 || false
+                ensures *x == old(*x) + 1
+            {
+                *copy(x) = *copy(x) + 1;
+                return;
+            }
+        }
+    ";
+    let specs = compile_specs(code).unwrap();
+    let balance = specs.function_specs(FunctionDefinitionIndex::new(0));
+    assert_eq!(balance.formals, vec![var("addr")]);
+    assert_eq!(balance.conditions.len(), 2);
+    // The alias of the module is replaced with its name.
+    match &balance.conditions[0].value {
+        Condition::Requires(SpecExp::GlobalExists { type_, .. }) => {
+            assert_eq!(type_.module().as_inner().as_str(), "LibraAccount")
+        }
+        condition => panic!("Unexpected condition {}", condition),
+    }
+    assert_eq!(
+        balance.conditions[1].value.to_string(),
+        "ensures (RET(0) == global<LibraAccount.T>(addr).balance.value)"
+    );
+
+    let incr = specs.function_specs(FunctionDefinitionIndex::new(1));
+    assert_eq!(incr.conditions.len(), 2);
+    // The logical operators bind less tightly than comparisons.
+    match &incr.conditions[0].value {
+        Condition::AbortsIf(SpecExp::Binop(lhs, BinOp::Or, rhs)) => {
+            assert_eq!(lhs.to_string(), "((*x + 1) > 18446744073709551615)");
+            assert_eq!(**rhs, SpecExp::Constant(CopyableVal::Bool(false)));
+        }
+        condition => panic!("Unexpected condition {}", condition),
+    }
+    assert_eq!(
+        incr.conditions[1].value.to_string(),
+        "ensures (*x == (old(*x) + 1))"
+    );
+}
+
+#[test]
+fn compile_struct_invariants() {
+    let code = "
+        module M {
+            struct Range<T> {
+                low: u64,
+                high: u64,
+                t: T,
+                invariant low <= high
+                invariant high < 100 && !(low == 42)
+            }
+            native struct N;
+
+            public make(): Self.Range<u64> {
+                return Range<u64> { low: 0, high: 1, t: 0 };
+            }
+        }
+    ";
+    let specs = compile_specs(code).unwrap();
+    let range = specs.struct_specs(StructDefinitionIndex::new(0));
+    assert_eq!(range.invariants.len(), 2);
+    assert_eq!(
+        range.invariants[0],
+        SpecExp::Binop(
+            Box::new(SpecExp::StorageLocation(StorageLocation::Formal(var(
+                "low"
+            )))),
+            BinOp::Le,
+            Box::new(SpecExp::StorageLocation(StorageLocation::Formal(var(
+                "high"
+            )))),
+        )
+    );
+    assert!(specs
+        .struct_specs(StructDefinitionIndex::new(1))
+        .invariants
+        .is_empty());
+    assert!(specs
+        .function_specs(FunctionDefinitionIndex::new(0))
+        .conditions
+        .is_empty());
+}
+
+#[test]
+fn compile_specs_with_type_actuals() {
+    let code = "
+        module M {
+            resource R<T> { t: T }
+
+            public has<T>(addr: address): bool
+                ensures RET(0) == global_exists<Self.R<T>>(addr)
+            {
+                return exists<R<T>>(move(addr));
+            }
+        }
+    ";
+    let specs = compile_specs(code).unwrap();
+    match &specs.functions[0].conditions[0].value {
+        Condition::Ensures(SpecExp::Binop(_, BinOp::Eq, rhs)) => match &**rhs {
+            SpecExp::GlobalExists {
+                type_,
+                type_actuals,
+                address,
+            } => {
+                assert_eq!(type_.module().as_inner().as_str(), "M");
+                assert_eq!(type_actuals.len(), 1);
+                match &type_actuals[0] {
+                    Type::TypeParameter(tv) => assert_eq!(tv.name().as_str(), "T"),
+                    ty => panic!("Unexpected type {}", ty),
+                }
+                assert_eq!(*address, StorageLocation::Formal(var("addr")));
+            }
+            e => panic!("Unexpected expression {}", e),
+        },
+        condition => panic!("Unexpected condition {}", condition),
+    }
+}
+
+#[test]
+fn compile_invalid_specs() {
+    let unbound_formal = "
+        module M {
+            f(x: u64) requires y > 0 { return; }
+        }
+    ";
+    assert!(compile_specs(unbound_formal).is_err());
+
+    let missing_return = "
+        module M {
+            f(x: u64): u64 ensures RET(1) == 0 { return 0; }
+        }
+    ";
+    assert!(compile_specs(missing_return).is_err());
+
+    let unbound_struct = "
+        module M {
+            f(x: address) requires global_exists<Missing.T>(x) { return; }
+        }
+    ";
+    assert!(compile_specs(unbound_struct).is_err());
+
+    let ret_in_invariant = "
+        module M {
+            struct S { x: u64 invariant RET(0) == 0 }
+        }
+    ";
+    assert!(compile_specs(ret_in_invariant).is_err());
+
+    let ret_in_requires = "
+        module M {
+            f(): u64 requires RET(0) == 0 { return 0; }
+        }
+    ";
+    assert!(compile_specs(ret_in_requires).is_err());
+
+    let old_in_aborts_if = "
+        module M {
+            f(x: u64) aborts_if old(x) == 0 { return; }
+        }
+    ";
+    assert!(compile_specs(old_in_aborts_if).is_err());
+}
@@ -4,6 +4,7 @@
 use bytecode_source_map::source_map::ModuleSourceMap;
 use ir_to_bytecode::{
     compiler::compile_module,
+    errors::render_error,
     parser::{ast::Loc, parse_module},
 };
 use libra_types::account_address::AccountAddress;
@@ -18,5 +19,8 @@ pub fn do_compile_module<T: ModuleAccess>(
     let source = fs::read_to_string(source_path)
         .unwrap_or_else(|_| panic!("Unable to read file: {:?}", source_path));
     let parsed_module = parse_module(&source).unwrap();
-    compile_module(address, parsed_module, dependencies).unwrap()
+    compile_module(address, parsed_module, dependencies).unwrap_or_else(|err| {
+        print!("{}", render_error(&source_path.to_string_lossy(), &source, &err));
+        std::process::exit(1);
+    })
 }
@@ -18,7 +18,9 @@ use libra_types::{
 };
 use state_view::StateView;
 use vm::CompiledModule;
-use vm_runtime::{MoveVM, VMExecutor, VMVerifier};
+use vm_runtime::{
+    debugger::Debugger, gas_profiler::GasProfile, MoveVM, VMExecutor, VMSimulator, VMVerifier,
+};
 
 /// Provides an environment to run a VM instance.
 ///
@@ -142,6 +144,26 @@ impl FakeExecutor {
         )
     }
 
+    /// Executes the given block of transactions with the parallel executor of the VM.
+    ///
+    /// The outputs are expected to be the same as the ones of `execute_block`. Like
+    /// `execute_block`, this doesn't apply the results to the data store.
+    pub fn execute_block_parallel(
+        &self,
+        txn_block: Vec<SignedTransaction>,
+    ) -> Vec<TransactionOutput> {
+        let mut vm_config = self.config.vm_config.clone();
+        vm_config.parallel_execution = true;
+        MoveVM::execute_block(
+            txn_block
+                .into_iter()
+                .map(Transaction::UserTransaction)
+                .collect(),
+            &vm_config,
+            &self.data_store,
+        )
+    }
+
     pub fn execute_transaction(&self, txn: SignedTransaction) -> TransactionOutput {
         let txn_block = vec![txn];
         let mut outputs = self.execute_block(txn_block);
@@ -150,6 +172,37 @@ impl FakeExecutor {
             .expect("A block with one transaction should have one output")
     }
 
+    /// Simulates the given transaction, skipping the signature check if `skip_signature_check`
+    /// is set. Like `execute_transaction`, this doesn't apply the output to the data store.
+    pub fn simulate_transaction(
+        &self,
+        txn: SignedTransaction,
+        skip_signature_check: bool,
+    ) -> TransactionOutput {
+        MoveVM::simulate_transaction(
+            txn,
+            skip_signature_check,
+            &self.config.vm_config,
+            &self.data_store,
+        )
+    }
+
+    /// Executes the given transaction with `debugger` attached to its execution. Like
+    /// `execute_transaction`, this doesn't apply the output to the data store.
+    pub fn debug_transaction(
+        &self,
+        txn: SignedTransaction,
+        debugger: &mut dyn Debugger,
+    ) -> TransactionOutput {
+        MoveVM::debug_transaction(txn, &self.config.vm_config, &self.data_store, debugger)
+    }
+
+    /// Executes the given transaction, recording the gas it consumes by call stack. Like
+    /// `execute_transaction`, this doesn't apply the output to the data store.
+    pub fn profile_transaction(&self, txn: SignedTransaction) -> (TransactionOutput, GasProfile) {
+        MoveVM::profile_transaction(txn, &self.config.vm_config, &self.data_store)
+    }
+
     /// Get the blob for the associated AccessPath
     pub fn read_from_access_path(&self, path: &AccessPath) -> Option<Vec<u8>> {
         StateView::get(&self.data_store, path).unwrap()
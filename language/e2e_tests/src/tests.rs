@@ -12,11 +12,15 @@
 mod account_universe;
 mod arithmetic;
 mod create_account;
+mod debugger;
 mod function_call;
+mod gas_profiler;
+mod gas_schedule;
 mod genesis;
 mod mint;
 mod module_publishing;
 mod pack_unpack;
 mod peer_to_peer;
 mod rotate_key;
+mod simulate;
 mod verify_txn;
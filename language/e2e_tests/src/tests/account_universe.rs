@@ -82,6 +82,16 @@ proptest! {
 
         run_and_assert_universe(universe, transactions)?;
     }
+
+    /// Ensure that executing a block in parallel has the same outputs as executing it
+    /// sequentially. The universes are small so that the transactions often conflict.
+    #[test]
+    fn parallel_execution(
+        universe in AccountUniverseGen::strategy(2..10, log_balance_strategy(10_000_000)),
+        transactions in vec(all_transactions_strategy(1, 1_000_000), 0..default_num_transactions()),
+    ) {
+        run_and_assert_parallel_execution(universe, transactions)?;
+    }
 }
 
 /// A strategy that returns a random transaction.
@@ -160,6 +170,28 @@ pub(crate) fn run_and_assert_universe(
     })
 }
 
+/// Run these transactions both sequentially and in parallel and make sure the outputs match.
+pub(crate) fn run_and_assert_parallel_execution(
+    universe: AccountUniverseGen,
+    transaction_gens: Vec<impl AUTransactionGen + Clone>,
+) -> Result<(), TestCaseError> {
+    test_all_genesis_impl({
+        |mut executor| {
+            let mut universe = universe.clone().setup(&mut executor);
+            let transactions: Vec<_> = transaction_gens
+                .iter()
+                .map(|transaction_gen| transaction_gen.clone().apply(&mut universe).0)
+                .collect();
+
+            prop_assert_eq!(
+                executor.execute_block_parallel(transactions.clone()),
+                executor.execute_block(transactions)
+            );
+            Ok(())
+        }
+    })
+}
+
 /// Verify that the account information in the universe matches the information in the executor.
 pub(crate) fn assert_accounts_match(
     universe: &AccountUniverse,
@@ -0,0 +1,90 @@
+// Copyright (c) The Libra Core Contributors
+// SPDX-License-Identifier: Apache-2.0
+
+use crate::{account::AccountData, common_transactions::peer_to_peer_txn, executor::FakeExecutor};
+use libra_types::identifier::Identifier;
+use vm_runtime::debugger::{Breakpoint, BreakpointDebugger, DebugCommand};
+
+#[test]
+fn debug_step_from_function_entry() {
+    let mut executor = FakeExecutor::from_genesis_file();
+    let sender = AccountData::new(1_000_000, 10);
+    let receiver = AccountData::new(100_000, 10);
+    executor.add_account_data(&sender);
+    executor.add_account_data(&receiver);
+    let txn = peer_to_peer_txn(sender.account(), receiver.account(), 10, 1_000);
+
+    let mut stops = vec![];
+    let output = {
+        let breakpoints = vec![Breakpoint::FunctionEntry {
+            module: None,
+            function: Identifier::new("main").unwrap(),
+        }];
+        let mut debugger = BreakpointDebugger::new(breakpoints, |context, _| {
+            let frame = context.current_frame().unwrap();
+            stops.push((
+                frame.function.to_string(),
+                frame.pc,
+                context.value_stack().len(),
+            ));
+            if stops.len() < 4 {
+                DebugCommand::Step
+            } else {
+                DebugCommand::Continue
+            }
+        });
+        executor.debug_transaction(txn.clone(), &mut debugger)
+    };
+
+    // The arguments of `main` are moved to the stack before the call to `pay_from_sender`, which
+    // moves them to its locals.
+    assert_eq!(
+        stops,
+        vec![
+            ("main".to_string(), 0, 0),
+            ("main".to_string(), 1, 1),
+            ("main".to_string(), 2, 2),
+            ("pay_from_sender".to_string(), 0, 0),
+        ]
+    );
+    // Debugging doesn't change the output of the transaction.
+    assert_eq!(output, executor.execute_transaction(txn));
+}
+
+#[test]
+fn debug_break_at_code_offset() {
+    let mut executor = FakeExecutor::from_genesis_file();
+    let sender = AccountData::new(1_000_000, 10);
+    let receiver = AccountData::new(100_000, 10);
+    executor.add_account_data(&sender);
+    executor.add_account_data(&receiver);
+    let txn = peer_to_peer_txn(sender.account(), receiver.account(), 10, 1_000);
+
+    let mut stops = vec![];
+    {
+        let breakpoints = vec![
+            Breakpoint::CodeOffset {
+                module: Some(Identifier::new("LibraAccount").unwrap()),
+                function: Identifier::new("epilogue").unwrap(),
+                offset: 0,
+            },
+            // `main` is not defined by `LibraCoin`.
+            Breakpoint::FunctionEntry {
+                module: Some(Identifier::new("LibraCoin").unwrap()),
+                function: Identifier::new("main").unwrap(),
+            },
+        ];
+        let mut debugger = BreakpointDebugger::new(breakpoints, |context, _| {
+            let call_stack = context.call_stack();
+            let sender_resource_loaded = context
+                .resources()
+                .any(|(access_path, _)| *access_path == sender.make_access_path());
+            stops.push((call_stack.len(), sender_resource_loaded));
+            DebugCommand::Continue
+        });
+        executor.debug_transaction(txn, &mut debugger);
+    }
+
+    // The epilogue is called once, after the transfer loaded the account of the sender.
+    assert_eq!(stops, vec![(1, true)]);
+}
@@ -0,0 +1,36 @@
+// Copyright (c) The Libra Core Contributors
+// SPDX-License-Identifier: Apache-2.0
+
+use crate::{account::AccountData, common_transactions::peer_to_peer_txn, executor::FakeExecutor};
+
+#[test]
+fn profile_peer_to_peer() {
+    let mut executor = FakeExecutor::from_genesis_file();
+    let sender = AccountData::new(1_000_000, 10);
+    let receiver = AccountData::new(100_000, 10);
+    executor.add_account_data(&sender);
+    executor.add_account_data(&receiver);
+    let txn = peer_to_peer_txn(sender.account(), receiver.account(), 10, 1_000);
+
+    let (output, profile) = executor.profile_transaction(txn.clone());
+
+    // Profiling doesn't change the output of the transaction.
+    assert_eq!(output, executor.execute_transaction(txn));
+    // The gas unit price is 1, so all the gas used is attributed.
+    assert_eq!(profile.total(), output.gas_used());
+    assert!(profile.intrinsic() > 0);
+
+    let functions = profile.functions();
+    let main = &functions["<SELF>::main"];
+    let pay_from_sender = &functions["LibraAccount::pay_from_sender"];
+    assert_eq!(main.total_gas.total(), profile.total() - profile.intrinsic());
+    assert!(main.self_gas.total() > 0);
+    assert!(pay_from_sender.total_gas.total() > pay_from_sender.self_gas.total());
+    assert!(main.total_gas.total() > pay_from_sender.total_gas.total());
+
+    let folded_stacks = profile.to_folded_stacks();
+    assert!(folded_stacks.starts_with(&format!("<intrinsic> {}\n", profile.intrinsic())));
+    assert!(folded_stacks
+        .lines()
+        .any(|line| line.starts_with("<SELF>::main;LibraAccount::pay_from_sender ")));
+}
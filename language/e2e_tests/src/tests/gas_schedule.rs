@@ -0,0 +1,153 @@
+// Copyright (c) The Libra Core Contributors
+// SPDX-License-Identifier: Apache-2.0
+
+use crate::{
+    account::{Account, AccountData},
+    assert_prologue_parity, assert_status_eq,
+    common_transactions::peer_to_peer_txn,
+    executor::FakeExecutor,
+    gas_costs::TXN_RESERVED,
+    transaction_status_eq,
+};
+use libra_types::{
+    transaction::{TransactionPayload, TransactionStatus},
+    vm_error::{StatusCode, VMStatus},
+    write_set::{WriteOp, WriteSetMut},
+};
+use transaction_builder::encode_update_gas_schedule_script;
+use vm::gas_schedule::{
+    gas_schedule_access_path, CostTable, GasAlgebra, GasCost, INITIAL_GAS_SCHEDULE,
+};
+use vm_genesis::{encode_genesis_transaction, GENESIS_KEYPAIR};
+
+fn executor_with_fresh_genesis() -> FakeExecutor {
+    let genesis = encode_genesis_transaction(&GENESIS_KEYPAIR.0, GENESIS_KEYPAIR.1.clone());
+    match genesis.payload() {
+        TransactionPayload::WriteSet(write_set) => FakeExecutor::from_genesis(write_set, None),
+        _ => panic!("Expected writeset txn in genesis txn"),
+    }
+}
+
+#[test]
+fn genesis_publishes_initial_gas_schedule() {
+    let executor = executor_with_fresh_genesis();
+    let blob = executor
+        .read_from_access_path(&gas_schedule_access_path())
+        .expect("the gas schedule must be published at genesis");
+    assert_eq!(
+        CostTable::from_resource_bytes(&blob).unwrap(),
+        *INITIAL_GAS_SCHEDULE
+    );
+}
+
+#[test]
+fn update_gas_schedule() {
+    let mut executor = executor_with_fresh_genesis();
+    let association = Account::new_association();
+    let sender = AccountData::new(1_000_000, 10);
+    let receiver = AccountData::new(100_000, 10);
+    executor.add_account_data(&sender);
+    executor.add_account_data(&receiver);
+    let transfer = peer_to_peer_txn(sender.account(), receiver.account(), 10, 1_000);
+    let gas_used_before = executor.execute_transaction(transfer.clone()).gas_used();
+
+    // Double the cost of every instruction.
+    let mut gas_schedule = INITIAL_GAS_SCHEDULE.clone();
+    for cost in gas_schedule.instruction_table.iter_mut() {
+        *cost = GasCost::new(cost.instruction_gas.get() * 2, cost.memory_gas.get() * 2);
+    }
+    let txn = association.create_signed_txn(
+        TransactionPayload::Script(encode_update_gas_schedule_script(&gas_schedule)),
+        1,
+        TXN_RESERVED,
+        1,
+    );
+    let output = executor.execute_transaction(txn);
+    assert_eq!(
+        output.status(),
+        &TransactionStatus::Keep(VMStatus::new(StatusCode::EXECUTED))
+    );
+    executor.apply_write_set(output.write_set());
+
+    let blob = executor
+        .read_from_access_path(&gas_schedule_access_path())
+        .unwrap();
+    assert_eq!(CostTable::from_resource_bytes(&blob).unwrap(), gas_schedule);
+    // The next blocks are charged with the new schedule.
+    let gas_used_after = executor.execute_transaction(transfer).gas_used();
+    assert!(gas_used_after > gas_used_before);
+}
+
+#[test]
+fn update_gas_schedule_not_association() {
+    let mut executor = executor_with_fresh_genesis();
+    let sender = AccountData::new(1_000_000, 10);
+    executor.add_account_data(&sender);
+
+    let txn = sender.account().create_signed_txn(
+        TransactionPayload::Script(encode_update_gas_schedule_script(&INITIAL_GAS_SCHEDULE)),
+        10,
+        TXN_RESERVED,
+        1,
+    );
+    let output = executor.execute_transaction(txn);
+    assert_eq!(
+        output.status(),
+        &TransactionStatus::Keep(VMStatus::new(StatusCode::ABORTED).with_sub_status(1))
+    );
+}
+
+#[test]
+fn update_gas_schedule_malformed() {
+    let mut executor = executor_with_fresh_genesis();
+    let association = Account::new_association();
+
+    // A native function without cost.
+    let mut gas_schedule = INITIAL_GAS_SCHEDULE.clone();
+    gas_schedule.native_table.pop();
+    let txn = association.create_signed_txn(
+        TransactionPayload::Script(encode_update_gas_schedule_script(&gas_schedule)),
+        1,
+        TXN_RESERVED,
+        1,
+    );
+    let output = executor.execute_transaction(txn);
+    assert_eq!(
+        output.status(),
+        &TransactionStatus::Keep(VMStatus::new(StatusCode::ABORTED).with_sub_status(2))
+    );
+    executor.apply_write_set(output.write_set());
+
+    let blob = executor
+        .read_from_access_path(&gas_schedule_access_path())
+        .unwrap();
+    assert_eq!(
+        CostTable::from_resource_bytes(&blob).unwrap(),
+        *INITIAL_GAS_SCHEDULE
+    );
+}
+
+#[test]
+fn corrupted_gas_schedule() {
+    let mut executor = executor_with_fresh_genesis();
+    let sender = AccountData::new(1_000_000, 10);
+    let receiver = AccountData::new(100_000, 10);
+    executor.add_account_data(&sender);
+    executor.add_account_data(&receiver);
+
+    let write_set = WriteSetMut::new(vec![(
+        gas_schedule_access_path(),
+        WriteOp::Value(vec![1, 2, 3]),
+    )])
+    .freeze()
+    .unwrap();
+    executor.apply_write_set(&write_set);
+
+    // No transaction is charged with another gas schedule than the published one.
+    let transfer = peer_to_peer_txn(sender.account(), receiver.account(), 10, 1_000);
+    assert_prologue_parity!(
+        executor.verify_transaction(transfer.clone()),
+        executor.execute_transaction(transfer).status(),
+        VMStatus::new(StatusCode::GAS_SCHEDULE_ERROR)
+    );
+}
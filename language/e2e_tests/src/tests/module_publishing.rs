@@ -2,12 +2,17 @@
 // SPDX-License-Identifier: Apache-2.0
 
 use crate::{
-    account::AccountData, assert_prologue_parity, assert_status_eq,
-    compile::compile_module_with_address, executor::FakeExecutor, transaction_status_eq,
+    account::AccountData,
+    assert_prologue_parity, assert_status_eq,
+    compile::{compile_module_with_address, compile_script},
+    executor::FakeExecutor,
+    transaction_status_eq,
 };
+use bytecode_verifier::VerifiedModule;
+use compiler::Compiler;
 use config::config::VMPublishingOption;
 use libra_types::{
-    transaction::TransactionStatus,
+    transaction::{Program, Script, SignedTransaction, TransactionPayload, TransactionStatus},
     vm_error::{StatusCode, StatusType, VMStatus},
 };
 
@@ -59,9 +64,9 @@ fn bad_module_address() {
     assert!(status.major_status == StatusCode::MODULE_ADDRESS_DOES_NOT_MATCH_SENDER);
 }
 
-// Publishing a module named M under the same address twice should be rejected
+// Republishing an unchanged module named M under the same address is a compatible upgrade
 #[test]
-fn duplicate_module() {
+fn republish_unchanged_module() {
     let mut executor = FakeExecutor::from_genesis_with_options(VMPublishingOption::Open);
 
     let sequence_number = 2;
@@ -101,12 +106,330 @@ fn duplicate_module() {
         &TransactionStatus::Keep(VMStatus::new(StatusCode::EXECUTED)),
     ));
 
-    // second one should fail because it tries to re-publish a module named M
+    // second one should succeed because the module is unchanged
     let output2 = executor.execute_transaction(txn2);
     assert!(transaction_status_eq(
         &output2.status(),
-        &TransactionStatus::Keep(VMStatus::new(StatusCode::DUPLICATE_MODULE_NAME)),
+        &TransactionStatus::Keep(VMStatus::new(StatusCode::EXECUTED)),
+    ));
+}
+
+// Publishes `old_program` and then `new_program` as module M, and returns the status of the upgrade
+fn upgrade_module(old_program: &str, new_program: &str) -> TransactionStatus {
+    let mut executor = FakeExecutor::from_genesis_with_options(VMPublishingOption::Open);
+
+    let sequence_number = 2;
+    let account = AccountData::new(1_000_000, sequence_number);
+    executor.add_account_data(&account);
+
+    let txn1 = account.account().create_signed_txn_impl(
+        *account.address(),
+        compile_module_with_address(account.address(), old_program),
+        sequence_number,
+        100_000,
+        1,
+    );
+    let txn2 = account.account().create_signed_txn_impl(
+        *account.address(),
+        compile_module_with_address(account.address(), new_program),
+        sequence_number + 1,
+        100_000,
+        1,
+    );
+
+    let output1 = executor.execute_transaction(txn1);
+    executor.apply_write_set(output1.write_set());
+    assert!(transaction_status_eq(
+        &output1.status(),
+        &TransactionStatus::Keep(VMStatus::new(StatusCode::EXECUTED)),
+    ));
+
+    executor.execute_transaction(txn2).status().clone()
+}
+
+// Adding structs and functions, and changing the body of a public function, is allowed
+#[test]
+fn compatible_module_upgrade() {
+    let status = upgrade_module(
+        "
+        module M {
+            struct T { x: u64 }
+            public f(): u64 { return 1; }
+        }
+        ",
+        "
+        module M {
+            struct T { x: u64 }
+            struct U { b: bool }
+            public f(): u64 { return 2; }
+            public g() { return; }
+        }
+        ",
+    );
+    assert!(transaction_status_eq(
+        &status,
+        &TransactionStatus::Keep(VMStatus::new(StatusCode::EXECUTED)),
+    ));
+}
+
+// Removing a public function breaks the modules linking against M and is rejected
+#[test]
+fn remove_public_function_upgrade() {
+    let status = upgrade_module(
+        "
+        module M {
+            public f() { return; }
+        }
+        ",
+        "
+        module M {
+        }
+        ",
+    );
+    assert!(transaction_status_eq(
+        &status,
+        &TransactionStatus::Keep(VMStatus::new(StatusCode::INCOMPATIBLE_MODULE_UPGRADE)),
+    ));
+}
+
+// Changing the layout of a struct would make the values stored on chain unreadable
+#[test]
+fn change_struct_layout_upgrade() {
+    let status = upgrade_module(
+        "
+        module M {
+            resource T { x: u64 }
+        }
+        ",
+        "
+        module M {
+            resource T { x: u64, y: u64 }
+        }
+        ",
+    );
+    assert!(transaction_status_eq(
+        &status,
+        &TransactionStatus::Keep(VMStatus::new(StatusCode::INCOMPATIBLE_MODULE_UPGRADE)),
+    ));
+}
+
+const M_V1: &str = "
+    module M {
+        struct T { x: u64 }
+        public f(): u64 { return 1; }
+    }
+    ";
+
+// A compatible upgrade of `M_V1`.
+const M_V2: &str = "
+    module M {
+        struct T { x: u64 }
+        public f(): u64 { return 2; }
+    }
+    ";
+
+// An incompatible upgrade of `M_V1`, which changes the layout of `T`.
+const M_V2_INCOMPATIBLE: &str = "
+    module M {
+        struct T { x: u64, y: u64 }
+        public f(): u64 { return 2; }
+    }
+    ";
+
+// Returns a transaction publishing `module` under the account of `sender`
+fn publish_module_txn(
+    sender: &AccountData,
+    module: &str,
+    sequence_number: u64,
+) -> SignedTransaction {
+    sender.account().create_signed_txn_impl(
+        *sender.address(),
+        compile_module_with_address(sender.address(), module),
+        sequence_number,
+        100_000,
+        1,
+    )
+}
+
+// Returns a transaction running a script which aborts with code 42 unless `M.f()`, published
+// under the account of `sender`, returns `expected`
+fn call_f_txn(sender: &AccountData, expected: u64, sequence_number: u64) -> SignedTransaction {
+    let compiler = Compiler {
+        address: *sender.address(),
+        ..Compiler::default()
+    };
+    let module = compiler
+        .into_compiled_module(M_V1)
+        .expect("Failed to compile");
+    let compiler = Compiler {
+        address: *sender.address(),
+        extra_deps: vec![VerifiedModule::new(module).expect("Failed to verify")],
+        ..Compiler::default()
+    };
+    let script = compiler
+        .into_script_blob(&format!(
+            "
+            import Transaction.M;
+
+            main() {{
+                assert(M.f() == {}, 42);
+                return;
+            }}
+            ",
+            expected,
+        ))
+        .expect("Failed to compile");
+    sender.account().create_signed_txn_impl(
+        *sender.address(),
+        TransactionPayload::Script(Script::new(script, vec![])),
+        sequence_number,
+        100_000,
+        1,
+    )
+}
+
+// Executes `block` and applies its write sets, asserting that the transactions have `statuses`
+fn execute_and_apply_block(
+    executor: &mut FakeExecutor,
+    block: Vec<SignedTransaction>,
+    statuses: &[StatusCode],
+) {
+    let outputs = executor.execute_block(block);
+    assert_eq!(outputs.len(), statuses.len());
+    for (output, status) in outputs.iter().zip(statuses) {
+        assert!(transaction_status_eq(
+            &output.status(),
+            &TransactionStatus::Keep(VMStatus::new(*status)),
+        ));
+        executor.apply_write_set(output.write_set());
+    }
+}
+
+// The transactions following an upgrade, in the same block and in the later blocks, call the new
+// version of the module
+#[test]
+fn call_upgraded_module() {
+    let mut executor = FakeExecutor::from_genesis_with_options(VMPublishingOption::Open);
+
+    let sequence_number = 2;
+    let account = AccountData::new(1_000_000, sequence_number);
+    executor.add_account_data(&account);
+
+    execute_and_apply_block(
+        &mut executor,
+        vec![
+            publish_module_txn(&account, M_V1, sequence_number),
+            call_f_txn(&account, 1, sequence_number + 1),
+        ],
+        &[StatusCode::EXECUTED, StatusCode::EXECUTED],
+    );
+    // The module loaded by the first transaction of the block is replaced by the upgrade.
+    execute_and_apply_block(
+        &mut executor,
+        vec![
+            call_f_txn(&account, 1, sequence_number + 2),
+            publish_module_txn(&account, M_V2, sequence_number + 3),
+            call_f_txn(&account, 2, sequence_number + 4),
+        ],
+        &[
+            StatusCode::EXECUTED,
+            StatusCode::EXECUTED,
+            StatusCode::EXECUTED,
+        ],
+    );
+    execute_and_apply_block(
+        &mut executor,
+        vec![call_f_txn(&account, 2, sequence_number + 5)],
+        &[StatusCode::EXECUTED],
+    );
+}
+
+// A failed upgrade leaves the published module, and the module cached for the following
+// transactions, unchanged
+#[test]
+fn call_module_after_failed_upgrade() {
+    let mut executor = FakeExecutor::from_genesis_with_options(VMPublishingOption::Open);
+
+    let sequence_number = 2;
+    let account = AccountData::new(1_000_000, sequence_number);
+    executor.add_account_data(&account);
+
+    execute_and_apply_block(
+        &mut executor,
+        vec![publish_module_txn(&account, M_V1, sequence_number)],
+        &[StatusCode::EXECUTED],
+    );
+    execute_and_apply_block(
+        &mut executor,
+        vec![
+            call_f_txn(&account, 1, sequence_number + 1),
+            publish_module_txn(&account, M_V2_INCOMPATIBLE, sequence_number + 2),
+            call_f_txn(&account, 1, sequence_number + 3),
+        ],
+        &[
+            StatusCode::EXECUTED,
+            StatusCode::INCOMPATIBLE_MODULE_UPGRADE,
+            StatusCode::EXECUTED,
+        ],
+    );
+    execute_and_apply_block(
+        &mut executor,
+        vec![call_f_txn(&account, 1, sequence_number + 4)],
+        &[StatusCode::EXECUTED],
+    );
+}
+
+// The parallel executor runs the blocks with programs publishing modules sequentially: each
+// transaction sees the modules published by the previous ones
+#[test]
+fn parallel_execution_of_programs_publishing_modules() {
+    let mut executor = FakeExecutor::from_genesis_with_options(VMPublishingOption::Open);
+
+    let sequence_number = 2;
+    let account = AccountData::new(1_000_000, sequence_number);
+    executor.add_account_data(&account);
+
+    let publish_program = |module: &str, sequence_number| {
+        let module = match compile_module_with_address(account.address(), module) {
+            TransactionPayload::Module(module) => module.into_inner(),
+            _ => unreachable!("Modules compile to module payloads"),
+        };
+        let program = Program::new(compile_script("main() { return; }"), vec![module], vec![]);
+        account.account().create_signed_txn_impl(
+            *account.address(),
+            TransactionPayload::Program(program),
+            sequence_number,
+            100_000,
+            1,
+        )
+    };
+    let txn1 = publish_program(
+        "
+        module M {
+            public f() { return; }
+        }
+        ",
+        sequence_number,
+    );
+    // An incompatible upgrade of the module published by the first transaction.
+    let txn2 = publish_program(
+        "
+        module M {
+        }
+        ",
+        sequence_number + 1,
+    );
+
+    let outputs = executor.execute_block(vec![txn1.clone(), txn2.clone()]);
+    assert!(transaction_status_eq(
+        &outputs[0].status(),
+        &TransactionStatus::Keep(VMStatus::new(StatusCode::EXECUTED)),
+    ));
+    assert!(transaction_status_eq(
+        &outputs[1].status(),
+        &TransactionStatus::Keep(VMStatus::new(StatusCode::INCOMPATIBLE_MODULE_UPGRADE)),
     ));
+    assert_eq!(executor.execute_block_parallel(vec![txn1, txn2]), outputs);
 }
 
 #[test]
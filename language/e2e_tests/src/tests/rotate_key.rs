@@ -3,13 +3,20 @@
 
 use crate::{
     account::{Account, AccountData},
+    assert_prologue_parity, assert_status_eq,
     common_transactions::{create_account_txn, rotate_key_txn},
     executor::test_all_genesis,
+    transaction_status_eq,
+};
+use crypto::{
+    ed25519::compat,
+    hash::CryptoHash,
+    multi_ed25519::{MultiEd25519PublicKey, MultiEd25519Signature},
+    traits::SigningKey,
 };
-use crypto::ed25519::compat;
 use libra_types::{
     account_address::AccountAddress,
-    transaction::TransactionStatus,
+    transaction::{SignedTransaction, TransactionStatus},
     vm_error::{StatusCode, VMStatus},
 };
 
@@ -64,3 +71,57 @@ fn rotate_key() {
         );
     });
 }
+
+#[test]
+fn rotate_key_to_multisig() {
+    test_all_genesis(|mut executor| {
+        // create and publish sender
+        let sender = AccountData::new(1_000_000, 10);
+        executor.add_account_data(&sender);
+
+        // rotate to a 2-of-3 key
+        let keypairs: Vec<_> = (0..3).map(|_| compat::generate_keypair(None)).collect();
+        let public_key = MultiEd25519PublicKey::new(
+            keypairs.iter().map(|(_, pubkey)| pubkey.clone()).collect(),
+            2, /* threshold */
+        )
+        .unwrap();
+        let new_key_hash = AccountAddress::from_public_key(&public_key);
+        let txn = rotate_key_txn(sender.account(), new_key_hash, 10);
+        let output = &executor.execute_block(vec![txn])[0];
+        assert_eq!(
+            output.status(),
+            &TransactionStatus::Keep(VMStatus::new(StatusCode::EXECUTED)),
+        );
+        executor.apply_write_set(output.write_set());
+
+        let new_account = Account::new();
+        let raw_txn =
+            create_account_txn(sender.account(), &new_account, 11, 100_000).into_raw_transaction();
+        let sign = |indexes: &[u8]| {
+            MultiEd25519Signature::new(
+                indexes
+                    .iter()
+                    .map(|i| (keypairs[*i as usize].0.sign_message(&raw_txn.hash()), *i))
+                    .collect(),
+            )
+            .unwrap()
+        };
+
+        // Check that transactions cannot be sent with fewer signatures than the threshold.
+        let txn = SignedTransaction::new_multisig(raw_txn.clone(), public_key.clone(), sign(&[1]));
+        assert_prologue_parity!(
+            executor.verify_transaction(txn.clone()),
+            executor.execute_transaction(txn).status(),
+            VMStatus::new(StatusCode::INVALID_SIGNATURE)
+        );
+
+        // Check that transactions can be sent with enough signatures.
+        let txn = SignedTransaction::new_multisig(raw_txn.clone(), public_key, sign(&[2, 0]));
+        let output = &executor.execute_block(vec![txn])[0];
+        assert_eq!(
+            output.status(),
+            &TransactionStatus::Keep(VMStatus::new(StatusCode::EXECUTED)),
+        );
+    });
+}
@@ -0,0 +1,83 @@
+// Copyright (c) The Libra Core Contributors
+// SPDX-License-Identifier: Apache-2.0
+
+use crate::{
+    account::AccountData,
+    common_transactions::peer_to_peer_txn,
+    executor::{test_all_genesis, FakeExecutor},
+};
+use crypto::ed25519::*;
+use libra_types::{
+    test_helpers::transaction_test_helpers,
+    transaction::TransactionStatus,
+    vm_error::{StatusCode, VMStatus},
+};
+use transaction_builder::encode_transfer_script;
+
+#[test]
+fn simulate_peer_to_peer() {
+    test_all_genesis(|mut executor| {
+        let sender = AccountData::new(1_000_000, 10);
+        let receiver = AccountData::new(100_000, 10);
+        executor.add_account_data(&sender);
+        executor.add_account_data(&receiver);
+
+        let txn = peer_to_peer_txn(sender.account(), receiver.account(), 10, 1_000);
+        let output = executor.simulate_transaction(txn.clone(), false);
+        assert_eq!(
+            output.status(),
+            &TransactionStatus::Keep(VMStatus::new(StatusCode::EXECUTED))
+        );
+        // The simulation has the same output as the execution of the transaction.
+        assert_eq!(output, executor.execute_transaction(txn));
+        // The simulation doesn't touch the state.
+        let sender_resource = executor
+            .read_account_resource(sender.account())
+            .expect("sender must exist");
+        assert_eq!(sender_resource.balance(), 1_000_000);
+        assert_eq!(sender_resource.sequence_number(), 10);
+    });
+}
+
+#[test]
+fn simulate_unsigned() {
+    let mut executor = FakeExecutor::from_genesis_file();
+    let sender = AccountData::new(1_000_000, 10);
+    let receiver = AccountData::new(100_000, 10);
+    executor.add_account_data(&sender);
+    executor.add_account_data(&receiver);
+
+    // A transaction signed with a key that doesn't match the public key of the sender.
+    let (private_key, _public_key) = compat::generate_keypair(None);
+    let program = encode_transfer_script(receiver.address(), 1_000);
+    let unsigned_txn = transaction_test_helpers::get_test_unchecked_txn(
+        *sender.address(),
+        10,
+        private_key,
+        sender.account().pubkey.clone(),
+        Some(program.clone()),
+    );
+    let signed_txn = transaction_test_helpers::get_test_signed_txn(
+        *sender.address(),
+        10,
+        sender.account().privkey.clone(),
+        sender.account().pubkey.clone(),
+        Some(program),
+    );
+
+    assert_eq!(
+        executor
+            .simulate_transaction(unsigned_txn.clone(), false)
+            .status(),
+        &TransactionStatus::Discard(VMStatus::new(StatusCode::INVALID_SIGNATURE))
+    );
+    let output = executor.simulate_transaction(unsigned_txn, true);
+    assert_eq!(
+        output.status(),
+        &TransactionStatus::Keep(VMStatus::new(StatusCode::EXECUTED))
+    );
+    assert_eq!(
+        output.gas_used(),
+        executor.execute_transaction(signed_txn).gas_used()
+    );
+}
@@ -2,7 +2,9 @@
 // SPDX-License-Identifier: Apache-2.0
 
 use crate::{config::global::Config as GlobalConfig, errors::*, evaluator::Stage};
-use libra_types::transaction::{parse_as_transaction_argument, TransactionArgument};
+use libra_types::transaction::{
+    parse_as_transaction_argument, split_arguments, TransactionArgument,
+};
 use std::{collections::BTreeSet, str::FromStr};
 
 /// A partially parsed transaction argument.
@@ -55,9 +57,8 @@ impl FromStr for Entry {
             return Ok(Entry::Sender(s.to_ascii_lowercase()));
         }
         if s.starts_with("args:") {
-            let res: Result<Vec<_>> = s[5..]
-                .split(',')
-                .map(|s| s.trim())
+            let res: Result<Vec<_>> = split_arguments(&s[5..])?
+                .into_iter()
                 .filter(|s| !s.is_empty())
                 .map(|s| s.parse::<Argument>())
                 .collect();
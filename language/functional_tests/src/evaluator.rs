@@ -5,15 +5,17 @@ use crate::{
     config::{global::Config as GlobalConfig, transaction::Config as TransactionConfig},
     errors::*,
 };
+use bytecode_source_map::{source_map::ModuleSourceMap, utils::render_verification_errors};
 use bytecode_verifier::verifier::{
     verify_module_dependencies, verify_script_dependencies, VerifiedModule, VerifiedScript,
 };
 use config::config::VMPublishingOption;
 use ir_to_bytecode::{
     compiler::{compile_module, compile_script},
+    errors::render_error,
     parser::parse_script_or_module,
 };
-use ir_to_bytecode_syntax::ast::ScriptOrModule;
+use ir_to_bytecode_syntax::ast::{Loc, ScriptOrModule};
 use language_e2e_tests::{account::Account, executor::FakeExecutor};
 use libra_types::{
     transaction::{
@@ -74,11 +76,16 @@ pub enum OutputType {
     CompiledScript(CompiledScript),
     Ast(ScriptOrModule),
     TransactionOutput(TransactionOutput),
+    /// An error rendered as snippets of the source code it was located in.
+    Diagnostics(String),
 }
 
 impl OutputType {
     pub fn to_check_string(&self) -> String {
-        format!("{:?}", self)
+        match self {
+            OutputType::Diagnostics(diagnostics) => diagnostics.clone(),
+            _ => format!("{:?}", self),
+        }
     }
 }
 
@@ -135,6 +142,7 @@ impl fmt::Display for OutputType {
             CompiledScript(cs) => write!(f, "{:#?}", cs),
             Ast(ast) => write!(f, "{}", ast),
             TransactionOutput(output) => write!(f, "{:#?}", output),
+            Diagnostics(diagnostics) => write!(f, "{}", diagnostics),
         }
     }
 }
@@ -302,7 +310,32 @@ fn serialize_and_deserialize_module(module: &CompiledModule) -> Result<()> {
     Ok(())
 }
 
+/// Renders the verification errors in `err`, if any, against the source code of the transaction.
+fn render_verification_failure(
+    idx: usize,
+    transaction: &Transaction,
+    source_map: &ModuleSourceMap<Loc>,
+    err: &Error,
+) -> String {
+    match err.downcast_ref::<ErrorKind>() {
+        Some(ErrorKind::VerificationFailure(errs)) => render_verification_errors(
+            &transaction_file_name(idx),
+            &transaction.input,
+            source_map,
+            errs,
+        ),
+        _ => String::new(),
+    }
+}
+
+/// The name under which the source code of a transaction is shown in diagnostics.
+fn transaction_file_name(idx: usize) -> String {
+    format!("transaction_{}", idx)
+}
+
 /// Tries to unwrap the given result. Upon failure, log the error and aborts.
+/// If a function rendering the error against the source code is given, its output is logged
+/// after the error.
 macro_rules! unwrap_or_abort {
     ($res: expr, $log: expr) => {{
         match $res {
@@ -313,6 +346,21 @@ macro_rules! unwrap_or_abort {
             }
         }
     }};
+    ($res: expr, $log: expr, $render: expr) => {{
+        match $res {
+            Ok(r) => r,
+            Err(e) => {
+                let diagnostics = $render(&e);
+                $log.append(EvaluationOutput::Error(Box::new(e)));
+                if !diagnostics.is_empty() {
+                    $log.append(EvaluationOutput::Output(Box::new(OutputType::Diagnostics(
+                        diagnostics,
+                    ))));
+                }
+                return Ok(Status::Failure);
+            }
+        }
+    }};
 }
 
 fn eval_transaction(
@@ -351,8 +399,11 @@ fn eval_transaction(
             }
             log.append(EvaluationOutput::Stage(Stage::Compiler));
 
-            let compiled_script =
-                unwrap_or_abort!(compile_script(*addr, parsed_script, &*deps), log).0;
+            let (compiled_script, source_map) = unwrap_or_abort!(
+                compile_script(*addr, parsed_script, &*deps),
+                log,
+                |e| render_error(&transaction_file_name(idx), &transaction.input, e)
+            );
             log.append(EvaluationOutput::Output(Box::new(
                 OutputType::CompiledScript(compiled_script.clone()),
             )));
@@ -362,8 +413,12 @@ fn eval_transaction(
                 return Ok(Status::Success);
             }
             log.append(EvaluationOutput::Stage(Stage::Verifier));
-            let compiled_script =
-                unwrap_or_abort!(do_verify_script(compiled_script, &*deps), log).into_inner();
+            let compiled_script = unwrap_or_abort!(
+                do_verify_script(compiled_script, &*deps),
+                log,
+                |e| render_verification_failure(idx, transaction, &source_map, e)
+            )
+            .into_inner();
 
             // stage 4: serializer round trip
             if !transaction.config.is_stage_disabled(Stage::Serializer) {
@@ -390,8 +445,11 @@ fn eval_transaction(
             }
             log.append(EvaluationOutput::Stage(Stage::Compiler));
 
-            let compiled_module =
-                unwrap_or_abort!(compile_module(*addr, parsed_module, &*deps), log).0;
+            let (compiled_module, source_map) = unwrap_or_abort!(
+                compile_module(*addr, parsed_module, &*deps),
+                log,
+                |e| render_error(&transaction_file_name(idx), &transaction.input, e)
+            );
             log.append(EvaluationOutput::Output(Box::new(
                 OutputType::CompiledModule(compiled_module.clone()),
             )));
@@ -407,8 +465,12 @@ fn eval_transaction(
                 return Ok(Status::Success);
             }
             log.append(EvaluationOutput::Stage(Stage::Verifier));
-            let compiled_module =
-                unwrap_or_abort!(do_verify_module(compiled_module, &*deps), log).into_inner();
+            let compiled_module = unwrap_or_abort!(
+                do_verify_module(compiled_module, &*deps),
+                log,
+                |e| render_verification_failure(idx, transaction, &source_map, e)
+            )
+            .into_inner();
 
             // stage 4: serializer round trip
             if !transaction.config.is_stage_disabled(Stage::Serializer) {
@@ -0,0 +1,83 @@
+// Copyright (c) The Libra Core Contributors
+// SPDX-License-Identifier: Apache-2.0
+
+use bytecode_source_map::{
+    decompiler::Decompiler, mapping::SourceMapping, source_map::ModuleSourceMap,
+};
+use bytecode_verifier::VerifiedModule;
+use functional_tests::utils::parse_input;
+use ir_to_bytecode::{
+    compiler::compile_module,
+    parser::{parse_module, parse_script_or_module},
+};
+use ir_to_bytecode_syntax::ast::ScriptOrModule;
+use libra_types::account_address::AccountAddress;
+use std::{fs::File, io::Read, path::Path};
+use stdlib::stdlib_modules;
+use vm::{file_format::CompiledModule, printers::display_function_definitions};
+
+fn decompile(module: CompiledModule) -> datatest_stable::Result<String> {
+    let source_map = ModuleSourceMap::dummy_from_module(&module)?;
+    Ok(Decompiler::new(SourceMapping::new(source_map, module)).decompile()?)
+}
+
+// Checks that the decompiled module compiles to a module that decompiles to the same code, and
+// whose functions have the same signatures, acquires and code as the functions of `module`.
+fn round_trip(
+    address: AccountAddress,
+    module: CompiledModule,
+    deps: &[VerifiedModule],
+) -> datatest_stable::Result<()> {
+    let functions = display_function_definitions(&module);
+    let decompiled = decompile(module)?;
+    let recompiled = parse_module(&decompiled)
+        .and_then(|parsed_module| compile_module(address, parsed_module, deps))
+        .map_err(|err| format!("Unable to compile\n{}\n{}", decompiled, err))?
+        .0;
+    if display_function_definitions(&recompiled) != functions {
+        return Err(format!(
+            "The functions of the recompiled module differ from\n{}",
+            decompiled
+        )
+        .into());
+    }
+    if decompile(recompiled)? != decompiled {
+        return Err(format!("The recompiled module differs from\n{}", decompiled).into());
+    }
+    Ok(())
+}
+
+// Decompiles the modules of the tests under the test/testsuite directory that pass the verifier.
+fn decompiler_round_trip(path: &Path) -> datatest_stable::Result<()> {
+    let mut file = File::open(path)?;
+    let mut input = String::new();
+    file.read_to_string(&mut input)?;
+
+    let (config, _, transactions) = parse_input(&input)?;
+    let mut deps = stdlib_modules().to_vec();
+    for transaction in transactions {
+        let parsed_module = match parse_script_or_module(&transaction.input) {
+            Ok(ScriptOrModule::Module(parsed_module)) => parsed_module,
+            _ => continue,
+        };
+        let address = *config
+            .get_account_for_name(&transaction.config.sender)
+            .unwrap()
+            .address();
+        let compiled_module = match compile_module(address, parsed_module, &deps) {
+            Ok((compiled_module, _)) => compiled_module,
+            Err(_) => continue,
+        };
+        if let Ok(module) = VerifiedModule::new(compiled_module.clone()) {
+            round_trip(address, module.into_inner(), &deps)?;
+        }
+        // As in the evaluator, the module is added to the dependencies even if it doesn't pass
+        // the verifier.
+        deps.push(VerifiedModule::bypass_verifier_DANGEROUS_FOR_TESTING_ONLY(
+            compiled_module,
+        ));
+    }
+    Ok(())
+}
+
+datatest_stable::harness!(decompiler_round_trip, "tests/testsuite", r".*\.mvir");
@@ -63,6 +63,7 @@ impl BoogieTranslator {
         &self,
         module: &VerifiedModule,
         def_idx: usize,
+        invariants: &[String],
     ) -> String {
         let mut res = String::from("\n");
         let field_info = get_field_info_from_def_index(module, def_idx);
@@ -72,12 +73,14 @@ impl BoogieTranslator {
         let mut typechecking_str = String::new();
         let mut fields_str = String::new();
         // pack
-        for (i, (field_name, (_, value_cons))) in field_info.iter().enumerate() {
+        for (i, (field_name, field_type)) in field_info.iter().enumerate() {
             if i > 0 {
                 args_str.push_str(", ");
             }
             args_str.push_str(&format!("v{}: Value", i));
-            typechecking_str.push_str(&format!("    assert is#{}(v{});\n", value_cons, i));
+            if let Some(value_cons) = format_value_cons(field_type) {
+                typechecking_str.push_str(&format!("    assert is#{}(v{});\n", value_cons, i));
+            }
             fields_str.push_str(&format!(
                 "[Field({}_{}) := v{}]",
                 struct_name, field_name, i
@@ -88,7 +91,12 @@ impl BoogieTranslator {
             struct_name, args_str
         ));
         res.push_str(&typechecking_str);
-        res.push_str(&format!("    v := Map(DefaultMap{});\n}}\n\n", fields_str));
+        res.push_str(&format!("    v := Map(DefaultMap{});\n", fields_str));
+        // the invariants must hold for every value that is packed
+        for invariant in invariants {
+            res.push_str(&format!("    assert {};\n", invariant));
+        }
+        res.push_str("}\n\n");
 
         // unpack
         res.push_str(&format!(
@@ -96,6 +104,10 @@ impl BoogieTranslator {
             struct_name, args_str
         ));
         res.push_str("    assert is#Map(v);\n");
+        // so they hold for every value that is unpacked
+        for invariant in invariants {
+            res.push_str(&format!("    assume {};\n", invariant));
+        }
         for (i, (field_name, _)) in field_info.iter().enumerate() {
             res.push_str(&format!(
                 "    v{} := m#Map(v)[Field({}_{})];\n",
@@ -111,12 +123,17 @@ impl BoogieTranslator {
             "procedure {{:inline 1}} Eq_{}(v1: Value, v2: Value) returns (res: Value)\n{{\n",
             struct_name,
         ));
-        for (i, (field_name, (field_type, _))) in field_info.iter().enumerate() {
+        for (i, (field_name, field_type)) in field_info.iter().enumerate() {
             res.push_str(&format!("    var b{}: Value;\n", i));
             bool_res_str.push_str(&format!(" && b#Boolean(b{})", i));
             bool_assign_str.push_str(&format!(
                 "    call b{} := Eq_{}(m#Map(v1)[Field({}_{})], m#Map(v2)[Field({}_{})]);\n",
-                i, field_type, struct_name, field_name, struct_name, field_name,
+                i,
+                format_type(module, field_type),
+                struct_name,
+                field_name,
+                struct_name,
+                field_name,
             ));
         }
         res.push_str("    assert is#Map(v1) && is#Map(v2);\n");
@@ -1,29 +1,52 @@
 use bytecode_to_boogie::translator::BoogieTranslator;
 use bytecode_verifier::VerifiedModule;
-use ir_to_bytecode::{compiler::compile_module, parser::parse_module};
-use libra_types::account_address::AccountAddress;
+use ir_to_bytecode::{
+    compiler::{compile_module_with_specs, compile_script},
+    parser::{ast::ScriptOrModule, parse_script_or_module},
+    specs::ModuleSpecs,
+};
+use libra_types::{account_address::AccountAddress, account_config};
 use std::{
     env,
     fs::{self, File},
     io::prelude::*,
 };
-use stdlib::stdlib_modules;
+use stdlib::stdlib::module_defs;
 
-// mod translator;
-fn compile_files(file_names: Vec<String>) -> Vec<VerifiedModule> {
-    let mut verified_modules = stdlib_modules().to_vec();
-    let files_len = file_names.len();
-    //    let dep_files = &file_names[0..files_len - 1];
-    let dep_files = &file_names[0..files_len];
+// compile the stdlib and the files, which are modules or scripts, along with the specifications
+// of the modules. the main function of a script is translated as the function of a module.
+fn compile_files(file_names: &[String]) -> (Vec<VerifiedModule>, Vec<ModuleSpecs>) {
+    let mut verified_modules = vec![];
+    let mut specs = vec![];
+    for module_def in module_defs() {
+        let (compiled_module, _, module_specs) = compile_module_with_specs(
+            account_config::core_code_address(),
+            (*module_def).clone(),
+            &verified_modules,
+        )
+        .expect("stdlib module failed to compile");
+        let verified_module =
+            VerifiedModule::new(compiled_module).expect("stdlib module failed to verify");
+        verified_modules.push(verified_module);
+        specs.push(module_specs);
+    }
 
-    //    let main_file = &file_names[files_len - 1];
     let address = AccountAddress::default();
-    for file_name in dep_files {
+    for file_name in file_names {
         let code = fs::read_to_string(file_name).unwrap();
-        let module = parse_module(&code).unwrap();
-        let compiled_module = compile_module(address, module, &verified_modules)
-            .expect("module failed to compile")
-            .0;
+        let (compiled_module, module_specs) = match parse_script_or_module(&code).unwrap() {
+            ScriptOrModule::Module(module) => {
+                let (compiled_module, _, module_specs) =
+                    compile_module_with_specs(address, module, &verified_modules)
+                        .expect("module failed to compile");
+                (compiled_module, module_specs)
+            }
+            ScriptOrModule::Script(script) => {
+                let (compiled_script, _) = compile_script(address, script, &verified_modules)
+                    .expect("script failed to compile");
+                (compiled_script.into_module(), ModuleSpecs::default())
+            }
+        };
         let verified_module_res = VerifiedModule::new(compiled_module);
 
         match verified_module_res {
@@ -32,44 +55,20 @@ fn compile_files(file_names: Vec<String>) -> Vec<VerifiedModule> {
             }
             Ok(verified_module) => {
                 verified_modules.push(verified_module);
+                specs.push(module_specs);
             }
         }
     }
-    // let main_code = fs::read_to_string(main_file).unwrap();
-    // let program = parse_program(&main_code).unwrap();
-    // let address = AccountAddress::default();
-    // let compiled_program =
-    //     compile_program(address, program, &verified_modules).expect("program failed to compile");
-    // let mut main_modules = compiled_program.modules;
-    // main_modules.push(compiled_program.script.into_module());
-    // for module in main_modules {
-    //     let verified_module_res = VerifiedModule::new(module);
-
-    //     match verified_module_res {
-    //         Err(e) => {
-    //             panic!("{:?}", e);
-    //         }
-    //         Ok(verified_module) => {
-    //             verified_modules.push(verified_module);
-    //         }
-    //     }
-    // }
-
-    verified_modules
+    (verified_modules, specs)
 }
 
 fn main() {
     let args: Vec<String> = env::args().collect();
     let file_names = &args[1..];
     // read files and compile into compiled modules
-    let modules = compile_files(file_names.to_vec());
-    let mut ts = BoogieTranslator::new(&modules);
-    let mut res = String::new();
-
-    // handwritten boogie code
-    let written_code = fs::read_to_string("src/bytecode_instrs.bpl").unwrap();
-    res.push_str(&written_code);
-    res.push_str(&ts.translate());
+    let (modules, specs) = compile_files(file_names);
+    let mut ts = BoogieTranslator::new(&modules, &specs);
+    let res = ts.translate();
     let mut f = File::create("output.bpl").expect("Unable to create file");
 
     // write resulting code into output.bpl
@@ -0,0 +1,377 @@
+//! This module translates the specifications of functions and structs to Boogie expressions.
+
+use crate::translator::{
+    format_address, format_byte_array, format_string, get_field_info_from_def_index,
+    struct_name_from_handle_index, BoogieTranslator,
+};
+use bytecode_verifier::VerifiedModule;
+use ir_to_bytecode::{
+    parser::ast::{BinOp, Condition, CopyableVal, SpecExp, StorageLocation, Type},
+    specs::{FunctionSpecs, ModuleSpecs, StructSpecs},
+};
+use std::collections::BTreeSet;
+use vm::{
+    access::ModuleAccess,
+    file_format::{FunctionSignature, SignatureToken, StructDefinitionIndex},
+};
+
+pub struct SpecTranslator<'a> {
+    parent: &'a BoogieTranslator,
+    module: &'a VerifiedModule,
+    target: SpecTarget<'a>,
+}
+
+// What the names in the specifications refer to
+enum SpecTarget<'a> {
+    // The formals of a function, its results being ret0, ret1, ...
+    Function {
+        specs: &'a FunctionSpecs,
+        signature: &'a FunctionSignature,
+    },
+    // The fields of the struct value v
+    Struct {
+        specs: &'a StructSpecs,
+        def_idx: StructDefinitionIndex,
+    },
+}
+
+impl<'a> SpecTranslator<'a> {
+    pub fn new_for_function(
+        parent: &'a BoogieTranslator,
+        module: &'a VerifiedModule,
+        specs: &'a FunctionSpecs,
+        signature: &'a FunctionSignature,
+    ) -> Self {
+        Self {
+            parent,
+            module,
+            target: SpecTarget::Function { specs, signature },
+        }
+    }
+
+    pub fn new_for_struct(
+        parent: &'a BoogieTranslator,
+        module: &'a VerifiedModule,
+        specs: &'a StructSpecs,
+        def_idx: StructDefinitionIndex,
+    ) -> Self {
+        Self {
+            parent,
+            module,
+            target: SpecTarget::Struct { specs, def_idx },
+        }
+    }
+
+    /// Returns the requires and ensures clauses of the conditions of a function, one per line.
+    /// The ensures conditions only need to hold if the function doesn't abort, and the function
+    /// must abort exactly when one of the aborts_if conditions holds, if there are any.
+    pub fn translate_conditions(&self) -> String {
+        let conditions = match &self.target {
+            SpecTarget::Function { specs, .. } => &specs.conditions,
+            SpecTarget::Struct { .. } => panic!("structs have no conditions"),
+        };
+        let mut res = String::new();
+        let mut aborts_if = vec![];
+        for condition in conditions {
+            match &condition.value {
+                Condition::Requires(e) => {
+                    res.push_str(&format!("requires b#Boolean({});\n", self.exp(e, true)))
+                }
+                Condition::Ensures(e) => res.push_str(&format!(
+                    "ensures !abort_flag ==> b#Boolean({});\n",
+                    self.exp(e, false)
+                )),
+                Condition::AbortsIf(e) => {
+                    aborts_if.push(format!("b#Boolean(old({}))", self.exp(e, true)))
+                }
+            }
+        }
+        if !aborts_if.is_empty() {
+            res.push_str(&format!(
+                "ensures abort_flag <==> ({});\n",
+                aborts_if.join(" || ")
+            ));
+        }
+        res
+    }
+
+    /// Returns the invariants of a struct as boolean expressions about the struct value `v`.
+    pub fn translate_invariants(&self) -> Vec<String> {
+        let invariants = match &self.target {
+            SpecTarget::Struct { specs, .. } => &specs.invariants,
+            SpecTarget::Function { .. } => panic!("functions have no invariants"),
+        };
+        invariants
+            .iter()
+            .map(|e| format!("b#Boolean({})", self.exp(e, false)))
+            .collect()
+    }
+
+    // Translates a specification expression to a Boogie expression of type Value. In the
+    // pre-state, the mutable references a function takes refer to their values before the call.
+    fn exp(&self, e: &SpecExp, pre_state: bool) -> String {
+        match e {
+            SpecExp::Constant(v) => constant(v),
+            SpecExp::StorageLocation(l) | SpecExp::Dereference(l) => self.location(l, pre_state).0,
+            SpecExp::GlobalExists {
+                type_,
+                type_actuals,
+                address,
+            } => format!(
+                "Boolean(domain#ResourceStore(rs[{}])[a#Address({})])",
+                self.type_value(&Type::Struct(type_.clone(), type_actuals.clone())),
+                self.location(address, pre_state).0
+            ),
+            SpecExp::Not(e) => format!("Boolean(!b#Boolean({}))", self.exp(e, pre_state)),
+            SpecExp::Binop(e1, op, e2) => {
+                let e1 = self.exp(e1, pre_state);
+                let e2 = self.exp(e2, pre_state);
+                let integer_op =
+                    |op: &str| format!("Integer(i#Integer({}) {} i#Integer({}))", e1, op, e2);
+                let bit_op = |function: &str| {
+                    format!(
+                        "Integer({}(i#Integer({}), i#Integer({})))",
+                        function, e1, e2
+                    )
+                };
+                let comparison =
+                    |op: &str| format!("Boolean(i#Integer({}) {} i#Integer({}))", e1, op, e2);
+                let boolean_op =
+                    |op: &str| format!("Boolean(b#Boolean({}) {} b#Boolean({}))", e1, op, e2);
+                match op {
+                    BinOp::Add => integer_op("+"),
+                    BinOp::Sub => integer_op("-"),
+                    BinOp::Mul => integer_op("*"),
+                    BinOp::Mod => integer_op("mod"),
+                    BinOp::Div => integer_op("div"),
+                    BinOp::BitOr => bit_op("BitOrInt"),
+                    BinOp::BitAnd => bit_op("BitAndInt"),
+                    BinOp::Xor => bit_op("XorInt"),
+                    BinOp::And => boolean_op("&&"),
+                    BinOp::Or => boolean_op("||"),
+                    BinOp::Eq => format!("Boolean({} == {})", e1, e2),
+                    BinOp::Neq => format!("Boolean({} != {})", e1, e2),
+                    BinOp::Lt => comparison("<"),
+                    BinOp::Gt => comparison(">"),
+                    BinOp::Le => comparison("<="),
+                    BinOp::Ge => comparison(">="),
+                }
+            }
+            SpecExp::Old(e) => format!("old({})", self.exp(e, true)),
+        }
+    }
+
+    // Translates a storage location to the Boogie expression of its value, and returns the type
+    // of the value along with the module the type is from. References are dereferenced.
+    fn location(
+        &self,
+        l: &StorageLocation,
+        pre_state: bool,
+    ) -> (String, &'a VerifiedModule, SignatureToken) {
+        match l {
+            StorageLocation::Formal(name) => match &self.target {
+                SpecTarget::Function { specs, signature } => {
+                    let idx = specs
+                        .formals
+                        .iter()
+                        .position(|formal| formal == name)
+                        .unwrap_or_else(|| panic!("unbound formal {}", name));
+                    let sig = &signature.arg_types[idx];
+                    // the value of a mutable reference after the call is returned in t{idx}
+                    if sig.is_mutable_reference() && !pre_state {
+                        self.dereference(format!("t{}", idx), sig)
+                    } else {
+                        self.dereference(format!("arg{}", idx), sig)
+                    }
+                }
+                SpecTarget::Struct { def_idx, .. } => {
+                    self.field("v".to_string(), self.module, *def_idx, &name.to_string())
+                }
+            },
+            StorageLocation::GlobalResource {
+                type_,
+                type_actuals,
+                address,
+            } => {
+                let (def_module, def_idx) = self
+                    .parent
+                    .struct_def(type_.module().as_inner(), type_.name().as_inner());
+                let struct_handle = def_module.struct_def_at(def_idx).struct_handle;
+                (
+                    format!(
+                        "contents#ResourceStore(rs[{}])[a#Address({})]",
+                        self.type_value(&Type::Struct(type_.clone(), type_actuals.clone())),
+                        self.location(address, pre_state).0
+                    ),
+                    def_module,
+                    SignatureToken::Struct(struct_handle, vec![]),
+                )
+            }
+            StorageLocation::AccessPath { base, fields } => {
+                let mut location = self.location(base, pre_state);
+                for f in fields {
+                    let (exp, module, sig) = location;
+                    let (def_module, def_idx) = match &sig {
+                        SignatureToken::Struct(idx, _) => {
+                            let struct_handle = module.struct_handle_at(*idx);
+                            self.parent.struct_def(
+                                module.identifier_at(
+                                    module.module_handle_at(struct_handle.module).name,
+                                ),
+                                module.identifier_at(struct_handle.name),
+                            )
+                        }
+                        _ => panic!("field {} of a value that isn't a struct", f),
+                    };
+                    location = self.field(exp, def_module, def_idx, f.name().as_str());
+                }
+                location
+            }
+            StorageLocation::Ret(idx) => match &self.target {
+                SpecTarget::Function { signature, .. } => {
+                    let sig = &signature.return_types[*idx as usize];
+                    self.dereference(format!("ret{}", idx), sig)
+                }
+                SpecTarget::Struct { .. } => panic!("RET in an invariant"),
+            },
+            StorageLocation::TxnSenderAddress => (
+                "Address(sender#Transaction_cons(txn))".to_string(),
+                self.module,
+                SignatureToken::Address,
+            ),
+            StorageLocation::Address(addr) => (
+                format!("Address({})", format_address(addr)),
+                self.module,
+                SignatureToken::Address,
+            ),
+        }
+    }
+
+    fn dereference(
+        &self,
+        name: String,
+        sig: &SignatureToken,
+    ) -> (String, &'a VerifiedModule, SignatureToken) {
+        match sig {
+            SignatureToken::Reference(t) | SignatureToken::MutableReference(t) => {
+                (format!("v#Reference({})", name), self.module, (**t).clone())
+            }
+            _ => (name, self.module, sig.clone()),
+        }
+    }
+
+    // Returns the field `field_name` of the struct value `exp`, whose struct is defined in
+    // `module` at `def_idx`.
+    fn field(
+        &self,
+        exp: String,
+        module: &'a VerifiedModule,
+        def_idx: StructDefinitionIndex,
+        field_name: &str,
+    ) -> (String, &'a VerifiedModule, SignatureToken) {
+        let struct_handle = module.struct_def_at(def_idx).struct_handle;
+        let struct_name = struct_name_from_handle_index(module, struct_handle);
+        let sig = get_field_info_from_def_index(module, def_idx.0 as usize)
+            .into_iter()
+            .find(|(name, _)| name == field_name)
+            .map(|(_, sig)| sig)
+            .unwrap_or_else(|| panic!("{} has no field {}", struct_name, field_name));
+        (
+            format!("m#Map({})[Field({}_{})]", exp, struct_name, field_name),
+            module,
+            sig,
+        )
+    }
+
+    fn type_value(&self, ty: &Type) -> String {
+        match ty {
+            Type::Address => "AddressType()".into(),
+            Type::U8 | Type::U64 | Type::U128 => "IntegerType()".into(),
+            Type::Bool => "BooleanType()".into(),
+            Type::ByteArray => "ByteArrayType()".into(),
+            Type::String => "StrType()".into(),
+            Type::Struct(ident, type_actuals) => format!(
+                "StructType({}_{}, {})",
+                ident.module(),
+                ident.name(),
+                type_actuals
+                    .iter()
+                    .fold("EmptyTypeValueArray".to_string(), |array, ty| {
+                        format!("ExtendTypeValueArray({}, {})", array, self.type_value(ty))
+                    })
+            ),
+            Type::Reference(_, _) => panic!("references can't be type actuals"),
+            Type::TypeParameter(tv) => match &self.target {
+                SpecTarget::Function { specs, .. } => {
+                    let idx = specs
+                        .type_formals
+                        .iter()
+                        .position(|type_formal| type_formal == tv)
+                        .unwrap_or_else(|| panic!("unbound type formal {}", tv));
+                    format!("tv{}", idx)
+                }
+                SpecTarget::Struct { .. } => {
+                    panic!("the type formals of a struct can't be used in its invariants")
+                }
+            },
+        }
+    }
+}
+
+fn constant(v: &CopyableVal) -> String {
+    match v {
+        CopyableVal::Address(addr) => format!("Address({})", format_address(addr)),
+        CopyableVal::U8(i) => format!("Integer({})", i),
+        CopyableVal::U64(i) => format!("Integer({})", i),
+        CopyableVal::U128(i) => format!("Integer({})", i),
+        CopyableVal::Bool(b) => format!("Boolean({})", b),
+        CopyableVal::ByteArray(b) => format!("ByteArray({})", format_byte_array(b.as_bytes())),
+        CopyableVal::String(s) => format!("Str({})", format_string(s.as_bytes())),
+    }
+}
+
+/// Adds the names of the byte arrays and strings in the specifications of a module to
+/// `byte_arrays` and `strings`.
+pub fn collect_spec_constants(
+    specs: &ModuleSpecs,
+    byte_arrays: &mut BTreeSet<String>,
+    strings: &mut BTreeSet<String>,
+) {
+    let mut collect = |e: &SpecExp| collect_constants(e, byte_arrays, strings);
+    for function_specs in &specs.functions {
+        for condition in &function_specs.conditions {
+            match &condition.value {
+                Condition::Requires(e) | Condition::Ensures(e) | Condition::AbortsIf(e) => {
+                    collect(e)
+                }
+            }
+        }
+    }
+    for struct_specs in &specs.structs {
+        struct_specs.invariants.iter().for_each(&mut collect);
+    }
+}
+
+fn collect_constants(
+    e: &SpecExp,
+    byte_arrays: &mut BTreeSet<String>,
+    strings: &mut BTreeSet<String>,
+) {
+    match e {
+        SpecExp::Constant(CopyableVal::ByteArray(b)) => {
+            byte_arrays.insert(format_byte_array(b.as_bytes()));
+        }
+        SpecExp::Constant(CopyableVal::String(s)) => {
+            strings.insert(format_string(s.as_bytes()));
+        }
+        SpecExp::Not(e) | SpecExp::Old(e) => collect_constants(e, byte_arrays, strings),
+        SpecExp::Binop(e1, _, e2) => {
+            collect_constants(e1, byte_arrays, strings);
+            collect_constants(e2, byte_arrays, strings);
+        }
+        SpecExp::Constant(_)
+        | SpecExp::StorageLocation(_)
+        | SpecExp::GlobalExists { .. }
+        | SpecExp::Dereference(_) => (),
+    }
+}
@@ -1,7 +1,12 @@
 //! This module translates the bytecode of a module to Boogie code.
 
+use crate::spec_translator::{collect_spec_constants, SpecTranslator};
 use bytecode_verifier::VerifiedModule;
-use libra_types::identifier::Identifier;
+use ir_to_bytecode::specs::ModuleSpecs;
+use libra_types::{
+    account_address::AccountAddress,
+    identifier::{IdentStr, Identifier},
+};
 use num::{BigInt, Num};
 use stackless_bytecode_generator::{
     stackless_bytecode::StacklessBytecode::{self, *},
@@ -11,31 +16,37 @@ use std::collections::{BTreeMap, BTreeSet};
 use vm::{
     access::ModuleAccess,
     file_format::{
-        FieldDefinitionIndex, FunctionHandleIndex, ModuleHandleIndex, SignatureToken,
-        StructDefinitionIndex, StructHandleIndex,
+        FieldDefinitionIndex, FunctionHandleIndex, FunctionSignature, LocalsSignatureIndex,
+        ModuleHandleIndex, SignatureToken, StructDefinitionIndex, StructHandleIndex,
     },
-    internals::ModuleIndex,
     views::{
         FieldDefinitionView, FunctionHandleView, SignatureTokenView, StructDefinitionView,
         StructHandleView, ViewInternals,
     },
 };
 
+/// The handwritten Boogie code the generated code relies on
+const PRELUDE: &str = include_str!("bytecode_instrs.bpl");
+
 pub struct BoogieTranslator {
     pub modules: Vec<VerifiedModule>,
+    pub specs: Vec<ModuleSpecs>,
     pub struct_defs: BTreeMap<String, usize>,
     pub max_struct_depth: usize,
     pub module_name_to_idx: BTreeMap<Identifier, usize>,
 }
 
 pub struct ModuleTranslator<'a> {
+    pub parent: &'a BoogieTranslator,
     pub module: &'a VerifiedModule,
+    pub specs: &'a ModuleSpecs,
     pub stackless_bytecode: Vec<StacklessFunction>,
-    pub all_type_strs: BTreeSet<String>,
 }
 
 impl BoogieTranslator {
-    pub fn new(modules: &[VerifiedModule]) -> Self {
+    /// Creates a translator for `modules`, where `specs[i]` are the specifications of
+    /// `modules[i]`. Modules past the end of `specs` have no specifications.
+    pub fn new(modules: &[VerifiedModule], specs: &[ModuleSpecs]) -> Self {
         let mut struct_defs: BTreeMap<String, usize> = BTreeMap::new();
         let mut module_name_to_idx: BTreeMap<Identifier, usize> = BTreeMap::new();
         for (module_idx, module) in modules.iter().enumerate() {
@@ -53,27 +64,36 @@ impl BoogieTranslator {
                 struct_defs.insert(struct_name, idx);
             }
         }
-        Self {
+        let mut specs = specs.to_vec();
+        specs.resize(modules.len(), ModuleSpecs::default());
+        let mut translator = Self {
             modules: modules.to_vec(),
+            specs,
             struct_defs,
             max_struct_depth: 0,
             module_name_to_idx,
-        }
+        };
+        translator.max_struct_depth = translator.compute_max_struct_depth();
+        translator
     }
 
     pub fn translate(&mut self) -> String {
-        let mut res = String::from("\n\n// everything below is auto generated\n\n");
+        let mut res = String::from(PRELUDE);
+        res.push_str("\n\n// everything below is auto generated\n\n");
         // generate names and struct specific functions for all structs
         res.push_str(&self.emit_struct_code());
 
+        // generate names for the byte arrays and strings of the modules and specifications
+        res.push_str(&self.emit_constants());
+
         // calculate maximum number of locals and generate this many local names
         res.push_str(&self.generate_local_names());
 
         // generate IsPrefix and UpdateValue to the max depth
         res.push_str(&self.emit_stratified_functions());
 
-        for module in self.modules.iter() {
-            let mut mt = ModuleTranslator::new(&module);
+        for (module, specs) in self.modules.iter().zip(&self.specs) {
+            let mut mt = ModuleTranslator::new(self, module, specs);
             res.push_str(&mt.translate());
         }
         res
@@ -101,13 +121,12 @@ impl BoogieTranslator {
         res
     }
 
-    pub fn emit_struct_code(&mut self) -> String {
+    pub fn emit_struct_code(&self) -> String {
         let mut res = String::new();
-        for module in self.modules.iter() {
+        for (module, specs) in self.modules.iter().zip(&self.specs) {
             for (def_idx, struct_def) in module.struct_defs().iter().enumerate() {
                 let struct_name = struct_name_from_handle_index(module, struct_def.struct_handle);
                 res.push_str(&format!("const unique {}: TypeName;\n", struct_name));
-                res.push_str(&format!("var rs_{}: ResourceStore;\n", struct_name));
                 let struct_definition_view = StructDefinitionView::new(module, struct_def);
                 if struct_definition_view.is_native() {
                     continue;
@@ -119,68 +138,138 @@ impl BoogieTranslator {
                         struct_name, field_name
                     ));
                 }
-                res.push_str(&self.emit_struct_specific_functions(module, def_idx));
-                let struct_handle_index = struct_def.struct_handle;
-                // calculate the max depth of a struct
-                self.max_struct_depth = std::cmp::max(
-                    self.max_struct_depth,
-                    self.get_struct_depth(
+                let invariants = match specs.structs.get(def_idx) {
+                    Some(struct_specs) => SpecTranslator::new_for_struct(
+                        self,
                         module,
-                        &SignatureToken::Struct(struct_handle_index, vec![]),
-                    ),
-                );
+                        struct_specs,
+                        StructDefinitionIndex::new(def_idx as u16),
+                    )
+                    .translate_invariants(),
+                    None => vec![],
+                };
+                res.push_str(&self.emit_struct_specific_functions(module, def_idx, &invariants));
             }
         }
         res
     }
 
-    fn get_struct_depth(&self, module: &VerifiedModule, sig: &SignatureToken) -> usize {
-        if let SignatureToken::Struct(idx, _) = sig {
-            let mut max_field_depth = 0;
-            let struct_handle = module.struct_handle_at(*idx);
-            let struct_handle_view = StructHandleView::new(module, struct_handle);
-            let module_name = module.identifier_at(struct_handle_view.module_handle().name);
-            let def_module_idx = self
-                .module_name_to_idx
-                .get(module_name)
-                .unwrap_or_else(|| panic!("no module named {}", module_name));
-            let def_module = &self.modules[*def_module_idx];
-            let struct_name = struct_name_from_handle_index(module, *idx);
-            let def_idx = *self
-                .struct_defs
-                .get(&struct_name)
-                .expect("can't find struct def");
-            let struct_definition = &def_module.struct_defs()[def_idx];
-            let struct_definition_view = StructDefinitionView::new(def_module, struct_definition);
-            if struct_definition_view.is_native() {
-                return 0;
-            }
-            for field_definition_view in struct_definition_view.fields().unwrap() {
-                let field_depth = self.get_struct_depth(
-                    def_module,
-                    field_definition_view.type_signature().token().as_inner(),
-                );
-                max_field_depth = std::cmp::max(max_field_depth, field_depth);
+    pub fn emit_constants(&self) -> String {
+        let mut byte_arrays = BTreeSet::new();
+        let mut strings = BTreeSet::new();
+        for module in self.modules.iter() {
+            for byte_array in module.byte_array_pool() {
+                byte_arrays.insert(format_byte_array(byte_array.as_bytes()));
             }
-            max_field_depth + 1
-        } else {
-            0
+            for string in module.user_strings() {
+                strings.insert(format_string(string.as_vm_str().as_bytes()));
+            }
+        }
+        for specs in self.specs.iter() {
+            collect_spec_constants(specs, &mut byte_arrays, &mut strings);
+        }
+        let mut res = String::new();
+        for name in byte_arrays {
+            res.push_str(&format!("const unique {}: ByteArray;\n", name));
+        }
+        for name in strings {
+            res.push_str(&format!("const unique {}: String;\n", name));
+        }
+        res.push_str("\n");
+        res
+    }
+
+    /// Returns the module defining the struct `struct_name` of `module_name`, and the index of
+    /// its definition there.
+    pub fn struct_def(
+        &self,
+        module_name: &IdentStr,
+        struct_name: &IdentStr,
+    ) -> (&VerifiedModule, StructDefinitionIndex) {
+        let def_module_idx = self
+            .module_name_to_idx
+            .get(module_name)
+            .unwrap_or_else(|| panic!("no module named {}", module_name));
+        let def_idx = *self
+            .struct_defs
+            .get(&format!("{}_{}", module_name, struct_name))
+            .expect("can't find struct def");
+        (
+            &self.modules[*def_module_idx],
+            StructDefinitionIndex::new(def_idx as u16),
+        )
+    }
+
+    // The depth of the values of generic types isn't known before they are instantiated, so a
+    // field of a generic type is assumed to be as deep as the deepest struct with its generic
+    // fields ignored.
+    fn compute_max_struct_depth(&self) -> usize {
+        let max_depth = |type_param_depth| {
+            let mut max_struct_depth = 0;
+            for module in self.modules.iter() {
+                for struct_def in module.struct_defs() {
+                    max_struct_depth = std::cmp::max(
+                        max_struct_depth,
+                        self.get_struct_depth(
+                            module,
+                            &SignatureToken::Struct(struct_def.struct_handle, vec![]),
+                            type_param_depth,
+                        ),
+                    );
+                }
+            }
+            max_struct_depth
+        };
+        max_depth(max_depth(0))
+    }
+
+    fn get_struct_depth(
+        &self,
+        module: &VerifiedModule,
+        sig: &SignatureToken,
+        type_param_depth: usize,
+    ) -> usize {
+        match sig {
+            SignatureToken::Struct(idx, _) => {
+                let mut max_field_depth = 0;
+                let struct_handle = module.struct_handle_at(*idx);
+                let struct_handle_view = StructHandleView::new(module, struct_handle);
+                let module_name = module.identifier_at(struct_handle_view.module_handle().name);
+                let (def_module, def_idx) = self.struct_def(module_name, struct_handle_view.name());
+                let struct_definition = def_module.struct_def_at(def_idx);
+                let struct_definition_view =
+                    StructDefinitionView::new(def_module, struct_definition);
+                if struct_definition_view.is_native() {
+                    return 0;
+                }
+                for field_definition_view in struct_definition_view.fields().unwrap() {
+                    let field_depth = self.get_struct_depth(
+                        def_module,
+                        field_definition_view.type_signature().token().as_inner(),
+                        type_param_depth,
+                    );
+                    max_field_depth = std::cmp::max(max_field_depth, field_depth);
+                }
+                max_field_depth + 1
+            }
+            SignatureToken::TypeParameter(_) => type_param_depth,
+            _ => 0,
         }
     }
 }
 
 impl<'a> ModuleTranslator<'a> {
-    pub fn new(module: &'a VerifiedModule) -> Self {
+    pub fn new(
+        parent: &'a BoogieTranslator,
+        module: &'a VerifiedModule,
+        specs: &'a ModuleSpecs,
+    ) -> Self {
         let stackless_bytecode = StacklessModuleGenerator::new(module.as_inner()).generate_module();
-        let mut all_type_strs = BTreeSet::new();
-        for struct_def in module.struct_defs().iter() {
-            let struct_name = struct_name_from_handle_index(module, struct_def.struct_handle);
-            all_type_strs.insert(struct_name);
-        }
         Self {
+            parent,
             module,
+            specs,
             stackless_bytecode,
-            all_type_strs,
         }
     }
 
@@ -189,8 +278,14 @@ impl<'a> ModuleTranslator<'a> {
         // translation of stackless bytecode
         for (idx, function_def) in self.module.function_defs().iter().enumerate() {
             if function_def.is_native() {
-                res.push_str(&self.generate_function_sig(idx, false, &None));
-                res.push_str(";\n");
+                // native functions have no body, so Boogie treats their results as arbitrary
+                let (args, rets) = self.generate_function_params(idx);
+                res.push_str(&format!(
+                    "procedure {} ({}) returns ({});\n\n",
+                    self.function_name_from_definition_index(idx),
+                    args,
+                    rets
+                ));
                 continue;
             }
             res.push_str(&self.translate_function(idx));
@@ -200,20 +295,19 @@ impl<'a> ModuleTranslator<'a> {
 
     pub fn translate_function(&self, idx: usize) -> String {
         let mut res = String::new();
-        // generate function signature
-        res.push_str(&self.generate_function_sig(idx, false, &None)); // no inline
-                                                                      // generate function body
-        res.push_str(&self.generate_verify_function_body(idx, &None));
+        // generate the inline version of the function, which does the actual work
+        res.push_str(&self.generate_function_sig(idx, true));
+        res.push_str(&self.generate_inline_function_body(idx));
+        // generate the verify version of the function, which checks the specifications
+        res.push_str(&self.generate_function_sig(idx, false));
+        res.push_str(&self.generate_function_specs(idx));
+        res.push_str(&self.generate_verify_function_body(idx));
         res
     }
 
-    pub fn translate_bytecode(
-        &self,
-        bytecode: &StacklessBytecode,
-        func_idx: usize,
-        arg_names: &Option<Vec<String>>,
-    ) -> String {
+    pub fn translate_bytecode(&self, bytecode: &StacklessBytecode, func_idx: usize) -> String {
         let mut res = String::new();
+        let abort_check = || "if (abort_flag) { goto Label_Abort; }".to_string();
         let stmts = match bytecode {
             Branch(target) => vec![format!("goto Label_{};", target)],
             BrTrue(target, idx) => vec![format!(
@@ -224,33 +318,18 @@ impl<'a> ModuleTranslator<'a> {
                 "if (!b#Boolean(t{})) {{ goto Label_{}; }}",
                 idx, target
             )],
-            MoveLoc(dest, src) => {
-                if self.is_local_ref(*dest, func_idx) {
-                    vec![format!(
-                        "call t{} := CopyOrMoveRef({});",
-                        dest,
-                        self.get_local_name(*src as usize, arg_names)
-                    )]
-                } else {
-                    vec![format!(
-                        "call t{} := CopyOrMoveValue({});",
-                        dest,
-                        self.get_local_name(*src as usize, arg_names)
-                    )]
-                }
-            }
-            CopyLoc(dest, src) => {
+            MoveLoc(dest, src) | CopyLoc(dest, src) => {
                 if self.is_local_ref(*dest, func_idx) {
                     vec![format!(
                         "call t{} := CopyOrMoveRef({});",
                         dest,
-                        self.get_local_name(*src as usize, arg_names)
+                        self.get_local_name(*src as usize)
                     )]
                 } else {
                     vec![format!(
                         "call t{} := CopyOrMoveValue({});",
                         dest,
-                        self.get_local_name(*src as usize, arg_names)
+                        self.get_local_name(*src as usize)
                     )]
                 }
             }
@@ -259,13 +338,13 @@ impl<'a> ModuleTranslator<'a> {
                     // TODO: release the scoop
                     vec![format!(
                         "call {} := CopyOrMoveRef(t{});",
-                        self.get_local_name(*dest as usize, arg_names),
+                        self.get_local_name(*dest as usize),
                         src
                     )]
                 } else {
                     vec![format!(
                         "call {} := CopyOrMoveValue(t{});",
-                        self.get_local_name(*dest as usize, arg_names),
+                        self.get_local_name(*dest as usize),
                         src
                     )]
                 }
@@ -274,27 +353,27 @@ impl<'a> ModuleTranslator<'a> {
                 "call t{} := BorrowLoc(c, t{}_LocalName, {});",
                 dest,
                 src,
-                self.get_local_name(*src as usize, arg_names)
+                self.get_local_name(*src as usize)
             )],
             ReadRef(dest, src) => vec![format!("call t{} := ReadRef(t{});", dest, src)],
             WriteRef(dest, src) => {
                 vec![format!("call t{} := WriteRef(t{}, t{});", dest, dest, src)]
             }
             FreezeRef(dest, src) => vec![format!("call t{} := FreezeRef(t{});", dest, src)],
-            Call(dests, callee_index, _, args) => {
+            Call(dests, callee_index, type_actuals, args) => {
                 let callee_name = self.function_name_from_handle_index(*callee_index);
                 let callee_function_handle = self.module.function_handle_at(*callee_index);
                 let callee_function_signature = self
                     .module
                     .function_signature_at(callee_function_handle.signature);
                 let mut dest_str = String::new();
-                let mut args_str = String::new();
+                let mut args_str = self.format_type_actuals(*type_actuals);
                 let mut dest_type_assumptions = vec![];
                 for (i, arg) in args.iter().enumerate() {
                     args_str.push_str(&format!(", t{}", arg));
                     if callee_function_signature.arg_types[i].is_mutable_reference() {
                         dest_str.push_str(&format!(", t{}", arg));
-                        dest_type_assumptions.push(self.format_type_checking(
+                        dest_type_assumptions.extend(self.format_type_checking(
                             format!("t{}", arg),
                             &self.get_local_type(*arg, func_idx),
                         ));
@@ -302,15 +381,18 @@ impl<'a> ModuleTranslator<'a> {
                 }
                 for dest in dests.iter() {
                     dest_str.push_str(&format!(", t{}", dest));
-                    dest_type_assumptions.push(self.format_type_checking(
+                    dest_type_assumptions.extend(self.format_type_checking(
                         format!("t{}", dest),
                         &self.get_local_type(*dest, func_idx),
                     ));
                 }
-                let mut res_vec = vec![format!(
-                    "call addr_exists'{} := {}(c', addr_exists'{});",
-                    dest_str, callee_name, args_str
-                )];
+                let mut res_vec = vec![
+                    format!(
+                        "call addr_exists'{} := {}(c', addr_exists'{});",
+                        dest_str, callee_name, args_str
+                    ),
+                    abort_check(),
+                ];
                 res_vec.extend(dest_type_assumptions);
                 res_vec
             }
@@ -323,7 +405,7 @@ impl<'a> ModuleTranslator<'a> {
                         fields_str.push_str(", ");
                     }
                     fields_str.push_str(&format!("t{}", field_temp));
-                    res_vec.push(self.format_type_checking(
+                    res_vec.extend(self.format_type_checking(
                         format!("t{}", field_temp),
                         &self.get_local_type(*field_temp, func_idx),
                     ));
@@ -343,7 +425,7 @@ impl<'a> ModuleTranslator<'a> {
                         dests_str.push_str(", ");
                     }
                     dests_str.push_str(&format!("t{}", dest));
-                    dest_type_assumptions.push(self.format_type_checking(
+                    dest_type_assumptions.extend(self.format_type_checking(
                         format!("t{}", dest),
                         &self.get_local_type(*dest, func_idx),
                     ));
@@ -358,48 +440,53 @@ impl<'a> ModuleTranslator<'a> {
             BorrowField(dest, src, field_def_index) => {
                 let field_name = self.field_name_from_index(*field_def_index);
                 let field_sig = self.get_local_type(*dest, func_idx);
-                vec![
-                    format!("call t{} := BorrowField(t{}, {});", dest, src, field_name),
-                    self.format_type_checking(format!("t{}", dest), &field_sig),
-                ]
-            }
-            Exists(dest, addr, struct_def_index, _) => {
-                let struct_str = self.struct_name_from_definition_index(*struct_def_index);
-                vec![format!(
-                    "call t{} := Exists(t{}, rs_{});",
-                    dest, addr, struct_str
-                )]
-            }
-            BorrowGlobal(dest, addr, struct_def_index, _) => {
-                let struct_str = self.struct_name_from_definition_index(*struct_def_index);
-                vec![
-                    format!(
-                        "call t{} := BorrowGlobal(t{}, {}, rs_{});",
-                        dest, addr, struct_str, struct_str,
-                    ),
-                    format!("assume is#Global(rt#Reference(t{}));", dest),
-                    format!("assume is#Map(v#Reference(t{}));", dest),
-                ]
-            }
-            MoveToSender(src, struct_def_index, _) => {
-                let struct_str = self.struct_name_from_definition_index(*struct_def_index);
-                vec![format!(
-                    "call rs_{} := MoveToSender(rs_{}, t{});",
-                    struct_str, struct_str, src,
-                )]
+                let mut res_vec = vec![format!(
+                    "call t{} := BorrowField(t{}, {});",
+                    dest, src, field_name
+                )];
+                res_vec.extend(self.format_type_checking(format!("t{}", dest), &field_sig));
+                res_vec
             }
-            MoveFrom(dest, src, struct_def_index, _) => {
-                let struct_str = self.struct_name_from_definition_index(*struct_def_index);
-                vec![
+            Exists(dest, addr, struct_def_index, type_actuals) => vec![format!(
+                "call t{} := Exists(t{}, {});",
+                dest,
+                addr,
+                self.format_struct_type_value(*struct_def_index, *type_actuals)
+            )],
+            BorrowGlobal(dest, addr, struct_def_index, type_actuals) => vec![
+                format!(
+                    "call t{} := BorrowGlobal(t{}, {});",
+                    dest,
+                    addr,
+                    self.format_struct_type_value(*struct_def_index, *type_actuals)
+                ),
+                abort_check(),
+                format!("assume is#Global(rt#Reference(t{}));", dest),
+                format!("assume is#Map(v#Reference(t{}));", dest),
+            ],
+            MoveToSender(src, struct_def_index, type_actuals) => vec![
+                format!(
+                    "call MoveToSender({}, t{});",
+                    self.format_struct_type_value(*struct_def_index, *type_actuals),
+                    src,
+                ),
+                abort_check(),
+            ],
+            MoveFrom(dest, src, struct_def_index, type_actuals) => {
+                let mut res_vec = vec![
                     format!(
-                        "call t{}, rs_{} := MoveFrom(t{}, rs_{});",
-                        dest, struct_str, src, struct_str,
-                    ),
-                    self.format_type_checking(
-                        format!("t{}", dest),
-                        &self.get_local_type(*dest, func_idx),
+                        "call t{} := MoveFrom(t{}, {});",
+                        dest,
+                        src,
+                        self.format_struct_type_value(*struct_def_index, *type_actuals)
                     ),
-                ]
+                    abort_check(),
+                ];
+                res_vec.extend(self.format_type_checking(
+                    format!("t{}", dest),
+                    &self.get_local_type(*dest, func_idx),
+                ));
+                res_vec
             }
             Ret(rets) => {
                 let mut ret_assignments = vec![];
@@ -411,59 +498,110 @@ impl<'a> ModuleTranslator<'a> {
             }
             LdTrue(idx) => vec![format!("call t{} := LdTrue();", idx)],
             LdFalse(idx) => vec![format!("call t{} := LdFalse();", idx)],
+            LdU8(idx, num) => vec![format!("call t{} := LdConst({});", idx, num)],
             LdConst(idx, num) => vec![format!("call t{} := LdConst({});", idx, num)],
+            LdU128(idx, num) => vec![format!("call t{} := LdConst({});", idx, num)],
             LdAddr(idx, addr_idx) => {
-                let addr = self.module.address_pool()[(*addr_idx).into_index()];
-                let addr_int = BigInt::from_str_radix(&addr.to_string(), 16).unwrap();
-                vec![format!("call t{} := LdAddr({});", idx, addr_int)]
+                let addr = self.module.address_at(*addr_idx);
+                vec![format!(
+                    "call t{} := LdAddr({});",
+                    idx,
+                    format_address(addr)
+                )]
             }
-            Not(dest, operand) => vec![format!("call t{} := Not(t{});", dest, operand)],
-            Add(dest, op1, op2) => vec![format!("call t{} := Add(t{}, t{});", dest, op1, op2)],
-            Sub(dest, op1, op2) => vec![format!("call t{} := Sub(t{}, t{});", dest, op1, op2)],
-            Mul(dest, op1, op2) => vec![format!("call t{} := Mul(t{}, t{});", dest, op1, op2)],
-            Div(dest, op1, op2) => vec![format!("call t{} := Div(t{}, t{});", dest, op1, op2)],
-            Mod(dest, op1, op2) => vec![format!("call t{} := Mod(t{}, t{});", dest, op1, op2)],
-            Lt(dest, op1, op2) => vec![format!("call t{} := Lt(t{}, t{});", dest, op1, op2)],
-            Gt(dest, op1, op2) => vec![format!("call t{} := Gt(t{}, t{});", dest, op1, op2)],
-            Le(dest, op1, op2) => vec![format!("call t{} := Le(t{}, t{});", dest, op1, op2)],
-            Ge(dest, op1, op2) => vec![format!("call t{} := Ge(t{}, t{});", dest, op1, op2)],
-            Or(dest, op1, op2) => vec![format!("call t{} := Or(t{}, t{});", dest, op1, op2)],
-            And(dest, op1, op2) => vec![format!("call t{} := And(t{}, t{});", dest, op1, op2)],
-            Eq(dest, op1, op2) => {
-                let operand_type = self.get_local_type(*op1, func_idx);
+            LdByteArray(idx, byte_array_idx) => {
+                let byte_array = self.module.byte_array_at(*byte_array_idx);
                 vec![format!(
-                    "call t{} := Eq_{}(t{}, t{});",
-                    dest,
-                    format_type(self.module, &operand_type),
-                    op1,
-                    op2
+                    "call t{} := LdByteArray({});",
+                    idx,
+                    format_byte_array(byte_array.as_bytes())
                 )]
             }
-            Neq(dest, op1, op2) => {
-                let operand_type = self.get_local_type(*op1, func_idx);
+            LdStr(idx, string_idx) => {
+                let string = self.module.user_string_at(*string_idx);
                 vec![format!(
-                    "call t{} := Neq_{}(t{}, t{});",
+                    "call t{} := LdStr({});",
+                    idx,
+                    format_string(string.as_bytes())
+                )]
+            }
+            CastU8(dest, src) => vec![
+                format!("call t{} := CastU8(t{});", dest, src),
+                abort_check(),
+            ],
+            CastU64(dest, src) => vec![
+                format!("call t{} := CastU64(t{});", dest, src),
+                abort_check(),
+            ],
+            CastU128(dest, src) => vec![
+                format!("call t{} := CastU128(t{});", dest, src),
+                abort_check(),
+            ],
+            Not(dest, operand) => vec![format!("call t{} := Not(t{});", dest, operand)],
+            Add(dest, op1, op2) => vec![
+                format!(
+                    "call t{} := Add{}(t{}, t{});",
                     dest,
-                    format_type(self.module, &operand_type),
+                    self.width_suffix(*dest, func_idx),
                     op1,
                     op2
-                )]
+                ),
+                abort_check(),
+            ],
+            Sub(dest, op1, op2) => vec![
+                format!("call t{} := Sub(t{}, t{});", dest, op1, op2),
+                abort_check(),
+            ],
+            Mul(dest, op1, op2) => vec![
+                format!(
+                    "call t{} := Mul{}(t{}, t{});",
+                    dest,
+                    self.width_suffix(*dest, func_idx),
+                    op1,
+                    op2
+                ),
+                abort_check(),
+            ],
+            Div(dest, op1, op2) => vec![
+                format!("call t{} := Div(t{}, t{});", dest, op1, op2),
+                abort_check(),
+            ],
+            Mod(dest, op1, op2) => vec![
+                format!("call t{} := Mod(t{}, t{});", dest, op1, op2),
+                abort_check(),
+            ],
+            BitOr(dest, op1, op2) => {
+                vec![format!("call t{} := BitOr(t{}, t{});", dest, op1, op2)]
             }
-            BitOr(_, _, _) | BitAnd(_, _, _) | Xor(_, _, _) => {
-                vec!["// bit operation not supported".into()]
+            BitAnd(dest, op1, op2) => {
+                vec![format!("call t{} := BitAnd(t{}, t{});", dest, op1, op2)]
             }
-            Abort(_) => vec!["abort_flag := true;".into()],
+            Xor(dest, op1, op2) => vec![format!("call t{} := Xor(t{}, t{});", dest, op1, op2)],
+            Lt(dest, op1, op2) => vec![format!("call t{} := Lt(t{}, t{});", dest, op1, op2)],
+            Gt(dest, op1, op2) => vec![format!("call t{} := Gt(t{}, t{});", dest, op1, op2)],
+            Le(dest, op1, op2) => vec![format!("call t{} := Le(t{}, t{});", dest, op1, op2)],
+            Ge(dest, op1, op2) => vec![format!("call t{} := Ge(t{}, t{});", dest, op1, op2)],
+            Or(dest, op1, op2) => vec![format!("call t{} := Or(t{}, t{});", dest, op1, op2)],
+            And(dest, op1, op2) => vec![format!("call t{} := And(t{}, t{});", dest, op1, op2)],
+            Eq(dest, op1, op2) => vec![self.format_equality("Eq", *dest, *op1, *op2, func_idx)],
+            Neq(dest, op1, op2) => {
+                vec![self.format_equality("Neq", *dest, *op1, *op2, func_idx)]
+            }
+            Abort(_) => vec!["goto Label_Abort;".into()],
             GetGasRemaining(idx) => vec![format!("call t{} := GetGasRemaining();", idx)],
             GetTxnSequenceNumber(idx) => vec![format!("call t{} := GetTxnSequenceNumber();", idx)],
             GetTxnPublicKey(idx) => vec![format!("call t{} := GetTxnPublicKey();", idx)],
             GetTxnSenderAddress(idx) => vec![format!("call t{} := GetTxnSenderAddress();", idx)],
             GetTxnMaxGasUnits(idx) => vec![format!("call t{} := GetTxnMaxGasUnits();", idx)],
             GetTxnGasUnitPrice(idx) => vec![format!("call t{} := GetTxnGasUnitPrice();", idx)],
-            CreateAccount(idx) => vec![format!(
-                "call addr_exists' := CreateAccount(t{}, addr_exists');",
-                idx
-            )],
-            _ => vec!["// unimplemented instruction".into()],
+            CreateAccount(idx) => vec![
+                format!(
+                    "call addr_exists' := CreateAccount(t{}, addr_exists');",
+                    idx
+                ),
+                abort_check(),
+            ],
+            NoOp => vec![],
         };
         for code in stmts {
             res.push_str(&format!("    {}\n", code));
@@ -472,37 +610,27 @@ impl<'a> ModuleTranslator<'a> {
         res
     }
 
-    // return a string for a boogie procedure header.
-    // if inline = true, add the inline attribute and use the plain function name
-    // for the procedure name.
-    // else, generate the function signature without the ":inlne" attribute, and
-    // append _verify to the function name.
-    pub fn generate_function_sig(
-        &self,
-        idx: usize,
-        inline: bool,
-        arg_names: &Option<Vec<String>>,
-    ) -> String {
-        let function_def = &self.module.function_defs()[idx];
-        let fun_name = self.function_name_from_definition_index(idx);
-        let function_handle = self.module.function_handle_at(function_def.function);
-        let function_signature = self.module.function_signature_at(function_handle.signature);
-        let mut arg_decls = String::new(); // vector of ", argname : type"
-        let mut rets = String::new();
+    // return the parameters and the results of the boogie procedures of a function.
+    // the results are addr_exists' (always), the mutable references the function takes and the
+    // values it returns.
+    pub fn generate_function_params(&self, idx: usize) -> (String, String) {
+        let function_signature = self.function_signature_from_definition_index(idx);
+        let mut args = String::from("c: CreationTime, addr_exists: [Address]bool");
+        let mut rets = String::from("addr_exists': [Address]bool");
+        for i in 0..function_signature.type_formals.len() {
+            args.push_str(&format!(", tv{}: TypeValue", i));
+        }
         for (i, arg_type) in function_signature.arg_types.iter().enumerate() {
-            // "arg_decls" is substituted later in an arglist that already has a few entries,
-            // which is why there is a comma before first arg.
-            arg_decls.push_str(&format!(
+            args.push_str(&format!(
                 ", {}: {}",
-                self.get_arg_name(i, arg_names),
-                self.format_value_or_ref(&arg_type)
+                self.get_arg_name(i),
+                self.format_value_or_ref(arg_type)
             ));
-            // *** Some derivative of this code needs to go into the verify function call to inline function
             if arg_type.is_mutable_reference() {
                 rets.push_str(&format!(
                     ", {}: {}",
-                    self.get_local_name(i, arg_names),
-                    self.format_value_or_ref(&arg_type)
+                    self.get_local_name(i),
+                    self.format_value_or_ref(arg_type)
                 ));
             }
         }
@@ -510,78 +638,85 @@ impl<'a> ModuleTranslator<'a> {
             rets.push_str(&format!(
                 ", ret{}: {}",
                 i,
-                self.format_value_or_ref(&return_type)
+                self.format_value_or_ref(return_type)
             ));
         }
+        (args, rets)
+    }
+
+    // return a string for a boogie procedure header.
+    // if inline = true, add the inline attribute and use the plain function name
+    // for the procedure name.
+    // else, generate the function signature without the ":inline" attribute, and
+    // append _verify to the function name.
+    pub fn generate_function_sig(&self, idx: usize, inline: bool) -> String {
+        let fun_name = self.function_name_from_definition_index(idx);
+        let (args, rets) = self.generate_function_params(idx);
         if inline {
-            // generates the "inlined" version of the procedure, which has the unadorned
-            // procedure name.
-            // FIXME: Can we make this more readable using backslash to linebreak the string?
             format!(
-                "procedure {{:inline 1}} {} (c: CreationTime, addr_exists: [Address]bool{}) returns (addr_exists': [Address]bool{})",
-                fun_name, arg_decls, rets
+                "procedure {{:inline 1}} {} ({}) returns ({})\nmodifies rs, abort_flag;\n",
+                fun_name, args, rets
             )
         } else {
-            // generates the "verify" version of the procedure, which just calls the "inlined"
-            // version.  "Verify" version will have specs integrated later.
-            // FIXME: function name is misleading.  Maybe just generate the real body in the "inline" case?
-            // FIXME: This is not going to work, because main.rs integrates the specs after the signature and before body.
-            // So, minimal change is to generate body separately.
             format!(
-                "procedure {}_verify (c: CreationTime, addr_exists: [Address]bool{}) returns (addr_exists': [Address]bool{})",
-                fun_name, arg_decls, rets
+                "procedure {}_verify ({}) returns ({})\nmodifies rs, abort_flag;\n",
+                fun_name, args, rets
             )
         }
     }
 
+    // return the specifications of the verify version of a function, one per line.
+    pub fn generate_function_specs(&self, idx: usize) -> String {
+        match self.specs.functions.get(idx) {
+            Some(function_specs) => SpecTranslator::new_for_function(
+                self.parent,
+                self.module,
+                function_specs,
+                self.function_signature_from_definition_index(idx),
+            )
+            .translate_conditions(),
+            None => String::new(),
+        }
+    }
+
     // return string for body of verify function, which is just a call to the
     // inline version of the function.
-    pub fn generate_verify_function_body(
-        &self,
-        idx: usize,
-        arg_names: &Option<Vec<String>>,
-    ) -> String {
+    pub fn generate_verify_function_body(&self, idx: usize) -> String {
         let fun_name = self.function_name_from_definition_index(idx);
-        let function_def = &self.module.function_defs()[idx];
-        let function_handle = self.module.function_handle_at(function_def.function);
-        let function_signature = self.module.function_signature_at(function_handle.signature);
-        let mut args = String::new(); // vector of ", argname"
-        let mut rets = String::new(); // vector of ", argname"
-                                      // return values are: addr_exists' (always), <mutable references>, <actual returns>
+        let function_signature = self.function_signature_from_definition_index(idx);
+        let mut args = String::from("c, addr_exists");
+        // return values are: addr_exists' (always), <mutable references>, <actual returns>
+        let mut rets = String::from("addr_exists'");
+        for i in 0..function_signature.type_formals.len() {
+            args.push_str(&format!(", tv{}", i));
+        }
         for (i, arg_type) in function_signature.arg_types.iter().enumerate() {
-            args.push_str(&format!(", {}", self.get_arg_name(i, arg_names)));
+            args.push_str(&format!(", {}", self.get_arg_name(i)));
             // collect mutable reference return values
             if arg_type.is_mutable_reference() {
-                rets.push_str(&format!(", {}", self.get_local_name(i, arg_names),));
+                rets.push_str(&format!(", {}", self.get_local_name(i)));
             }
         }
         // Next loop collects actual return values from Move function
         for i in 0..function_signature.return_types.len() {
             rets.push_str(&format!(", ret{}", i));
         }
-        format!(
-            "\n{{\n    call addr_exists' {} := {}(c, addr_exists {});\n}}\n\n",
-            rets, fun_name, args
-        )
+        format!("{{\n    call {} := {}({});\n}}\n\n", rets, fun_name, args)
     }
 
     // This generates boogie code for everything after the function signature
     // The function body is only generated for the "inline" version of the function.
-    pub fn generate_inline_function_body(
-        &self,
-        idx: usize,
-        arg_names: &Option<Vec<String>>,
-    ) -> String {
+    pub fn generate_inline_function_body(&self, idx: usize) -> String {
         let mut res = String::new();
-        let function_def = &self.module.function_defs()[idx];
         let code = &self.stackless_bytecode[idx];
 
-        res.push_str("\n{\n");
+        res.push_str("{\n");
         res.push_str("    // declare local variables\n");
 
-        let function_handle = self.module.function_handle_at(function_def.function);
-        let function_signature = self.module.function_signature_at(function_handle.signature);
-        let num_args = function_signature.arg_types.len();
+        let num_args = self
+            .function_signature_from_definition_index(idx)
+            .arg_types
+            .len();
         let mut ref_vars = BTreeSet::new(); // set of locals that are references
         let mut val_vars = BTreeSet::new(); // set of locals that are not
         let mut arg_assignment_str = String::new();
@@ -590,22 +725,23 @@ impl<'a> ModuleTranslator<'a> {
             if i < num_args {
                 arg_assignment_str.push_str(&format!(
                     "    {} := {};\n",
-                    self.get_local_name(i, arg_names),
-                    self.get_arg_name(i, arg_names)
-                ));
-                arg_value_assumption_str.push_str(&format!(
-                    "    {}",
-                    self.format_type_checking(self.get_arg_name(i, arg_names), local_type)
+                    self.get_local_name(i),
+                    self.get_arg_name(i)
                 ));
+                if let Some(assumption) =
+                    self.format_type_checking(self.get_arg_name(i), local_type)
+                {
+                    arg_value_assumption_str.push_str(&format!("    {}\n", assumption));
+                }
 
                 if self.is_local_ref(i, idx) {
                     arg_value_assumption_str.push_str(&format!(
                         "    if (is#Local(rt#Reference({}))) {{\n",
-                        self.get_arg_name(i, arg_names)
+                        self.get_arg_name(i)
                     ));
                     arg_value_assumption_str.push_str(&format!(
                         "        assume c#Local(rt#Reference({})) < c;\n    }}\n",
-                        self.get_arg_name(i, arg_names)
+                        self.get_arg_name(i)
                     ));
                 }
             }
@@ -619,18 +755,15 @@ impl<'a> ModuleTranslator<'a> {
             }
             res.push_str(&format!(
                 "    var {}: {}; // {}\n",
-                self.get_local_name(i, arg_names),
-                self.format_value_or_ref(&local_type),
+                self.get_local_name(i),
+                self.format_value_or_ref(local_type),
                 format_type(self.module, &local_type)
             ));
         }
 
         res.push_str("\n    // declare a new creation time for calls inside this function\n");
         res.push_str("    var c': CreationTime;\n    assume c' > c;\n");
-        // DD 10/8/2019: I think it's ok to assume !abort_flag even if function is inlined.
-        // if !inline {
         res.push_str("    assume !abort_flag;\n");
-        // }
         res.push_str("\n    // assume arguments are of correct types\n");
         res.push_str(&arg_value_assumption_str);
         res.push_str("\n    // assign arguments to locals so they can be modified\n");
@@ -658,7 +791,7 @@ impl<'a> ModuleTranslator<'a> {
             if branching_targets.contains(&offset) {
                 res.push_str(&format!("Label_{}:\n", offset));
             }
-            res.push_str(&self.translate_bytecode(bytecode, idx, arg_names));
+            res.push_str(&self.translate_bytecode(bytecode, idx));
             if let WriteRef(dest, src) = bytecode {
                 // update everything that might be related to the updated reference
                 for s in &ref_vars {
@@ -667,30 +800,25 @@ impl<'a> ModuleTranslator<'a> {
                     }
                     res.push_str(&format!(
                         "    call {} := DeepUpdateReference({}, {});\n",
-                        self.get_local_name(*s, arg_names),
-                        self.get_local_name(*dest, arg_names),
-                        self.get_local_name(*s, arg_names)
-                    ));
-                }
-                for t in &self.all_type_strs {
-                    res.push_str(&format!(
-                        "    call rs_{} := DeepUpdateGlobal({}, {}, rs_{});\n",
-                        t,
-                        t,
-                        self.get_local_name(*dest, arg_names),
-                        t
+                        self.get_local_name(*s),
+                        self.get_local_name(*dest),
+                        self.get_local_name(*s)
                     ));
                 }
+                res.push_str(&format!(
+                    "    call DeepUpdateGlobal({});\n",
+                    self.get_local_name(*dest)
+                ));
                 for s in &val_vars {
                     if s == src {
                         continue;
                     }
                     res.push_str(&format!(
                         "    call {} := DeepUpdateLocal(c, t{}_LocalName, {}, {});\n",
-                        self.get_local_name(*s, arg_names),
+                        self.get_local_name(*s),
                         s,
-                        self.get_local_name(*dest, arg_names),
-                        self.get_local_name(*s, arg_names)
+                        self.get_local_name(*dest),
+                        self.get_local_name(*s)
                     ));
                 }
                 res.push_str("\n");
@@ -707,52 +835,40 @@ impl<'a> ModuleTranslator<'a> {
                         }
                         res.push_str(&format!(
                             "    call {} := DeepUpdateReference({}, {});\n",
-                            self.get_local_name(*s, arg_names),
-                            self.get_local_name(*dest, arg_names),
-                            self.get_local_name(*s, arg_names)
-                        ));
-                    }
-                    for t in &self.all_type_strs {
-                        res.push_str(&format!(
-                            "    call rs_{} := DeepUpdateGlobal({}, {}, rs_{});\n",
-                            t,
-                            t,
-                            self.get_local_name(*dest, arg_names),
-                            t
+                            self.get_local_name(*s),
+                            self.get_local_name(*dest),
+                            self.get_local_name(*s)
                         ));
                     }
+                    res.push_str(&format!(
+                        "    call DeepUpdateGlobal({});\n",
+                        self.get_local_name(*dest)
+                    ));
                     for s in &val_vars {
                         res.push_str(&format!(
                             "    call {} := DeepUpdateLocal(c, t{}_LocalName, {}, {});\n",
-                            self.get_local_name(*s, arg_names),
+                            self.get_local_name(*s),
                             s,
-                            self.get_local_name(*dest, arg_names),
-                            self.get_local_name(*s, arg_names)
+                            self.get_local_name(*dest),
+                            self.get_local_name(*s)
                         ));
                     }
                     res.push_str("\n");
                 }
             }
         }
-        res.push_str("}\n");
+        // the instructions that abort jump here
+        res.push_str("Label_Abort:\n    abort_flag := true;\n");
+        res.push_str("}\n\n");
         res
     }
 
-    pub fn get_local_name(&self, idx: usize, arg_names: &Option<Vec<String>>) -> String {
-        if let Some(names) = arg_names {
-            if idx < names.len() {
-                return format!("new_{}", names[idx]);
-            }
-        }
+    pub fn get_local_name(&self, idx: usize) -> String {
         format!("t{}", idx)
     }
 
-    pub fn get_arg_name(&self, idx: usize, arg_names: &Option<Vec<String>>) -> String {
-        if let Some(names) = arg_names {
-            format!("old_{}", names[idx])
-        } else {
-            format!("arg{}", idx)
-        }
+    pub fn get_arg_name(&self, idx: usize) -> String {
+        format!("arg{}", idx)
     }
 
     /*
@@ -791,6 +907,12 @@ impl<'a> ModuleTranslator<'a> {
         format!("{}_{}", module_name, function_name)
     }
 
+    fn function_signature_from_definition_index(&self, idx: usize) -> &'a FunctionSignature {
+        let function_def = &self.module.function_defs()[idx];
+        let function_handle = self.module.function_handle_at(function_def.function);
+        self.module.function_signature_at(function_handle.signature)
+    }
+
     pub fn get_local_type(&self, local_idx: usize, func_idx: usize) -> SignatureToken {
         self.stackless_bytecode[func_idx].local_types[local_idx].clone()
     }
@@ -811,6 +933,68 @@ impl<'a> ModuleTranslator<'a> {
         }
     }
 
+    // Add and Mul check for overflows of the width of the integer they compute
+    fn width_suffix(&self, local_idx: usize, func_idx: usize) -> &'static str {
+        match self.get_local_type(local_idx, func_idx) {
+            SignatureToken::U8 => "U8",
+            SignatureToken::U128 => "U128",
+            _ => "",
+        }
+    }
+
+    // Eq and Neq compare the values references point to
+    fn format_equality(
+        &self,
+        op: &str,
+        dest: usize,
+        op1: usize,
+        op2: usize,
+        func_idx: usize,
+    ) -> String {
+        match self.get_local_type(op1, func_idx) {
+            SignatureToken::Reference(t) | SignatureToken::MutableReference(t) => format!(
+                "call t{} := {}_{}(v#Reference(t{}), v#Reference(t{}));",
+                dest,
+                op,
+                format_type(self.module, &t),
+                op1,
+                op2
+            ),
+            operand_type => format!(
+                "call t{} := {}_{}(t{}, t{});",
+                dest,
+                op,
+                format_type(self.module, &operand_type),
+                op1,
+                op2
+            ),
+        }
+    }
+
+    fn format_type_actuals(&self, idx: LocalsSignatureIndex) -> String {
+        self.module
+            .locals_signature_at(idx)
+            .0
+            .iter()
+            .map(|sig| format!(", {}", format_type_value(self.module, sig)))
+            .collect()
+    }
+
+    fn format_struct_type_value(
+        &self,
+        def_idx: StructDefinitionIndex,
+        type_actuals: LocalsSignatureIndex,
+    ) -> String {
+        format!(
+            "StructType({}, {})",
+            self.struct_name_from_definition_index(def_idx),
+            format_type_value_array(
+                self.module,
+                &self.module.locals_signature_at(type_actuals).0
+            )
+        )
+    }
+
     pub fn format_value_or_ref(&self, sig: &SignatureToken) -> String {
         match sig {
             SignatureToken::Reference(_) | SignatureToken::MutableReference(_) => "Reference",
@@ -819,14 +1003,14 @@ impl<'a> ModuleTranslator<'a> {
         .into()
     }
 
-    pub fn format_type_checking(&self, name: String, sig: &SignatureToken) -> String {
+    // return the assumption that the value of a local is of its type, if that type is known.
+    pub fn format_type_checking(&self, name: String, sig: &SignatureToken) -> Option<String> {
         match sig {
-            SignatureToken::Reference(s) | SignatureToken::MutableReference(s) => format!(
-                "assume is#{}(v#Reference({}));\n",
-                format_value_cons(s),
-                name,
-            ),
-            _ => format!("assume is#{}({});\n", format_value_cons(sig), name,),
+            SignatureToken::Reference(s) | SignatureToken::MutableReference(s) => {
+                format_value_cons(s)
+                    .map(|cons| format!("assume is#{}(v#Reference({}));", cons, name))
+            }
+            _ => format_value_cons(sig).map(|cons| format!("assume is#{}({});", cons, name)),
         }
     }
 }
@@ -842,7 +1026,7 @@ pub fn struct_name_from_handle_index(module: &VerifiedModule, idx: StructHandleI
 pub fn format_type(module: &VerifiedModule, sig: &SignatureToken) -> String {
     match sig {
         SignatureToken::Bool => "bool".into(),
-        SignatureToken::U64 => "int".into(),
+        SignatureToken::U8 | SignatureToken::U64 | SignatureToken::U128 => "int".into(),
         SignatureToken::String => "string".into(),
         SignatureToken::ByteArray => "bytearray".into(),
         SignatureToken::Address => "address".into(),
@@ -850,37 +1034,95 @@ pub fn format_type(module: &VerifiedModule, sig: &SignatureToken) -> String {
         SignatureToken::Reference(t) | SignatureToken::MutableReference(t) => {
             format!("{}_ref", format_type(module, &*t))
         }
-        SignatureToken::TypeParameter(_) => "unsupported".into(),
+        SignatureToken::TypeParameter(_) => "Value".into(),
     }
 }
 
-pub fn format_value_cons(sig: &SignatureToken) -> String {
+// return the constructor of the values of a type, which isn't known for type parameters.
+pub fn format_value_cons(sig: &SignatureToken) -> Option<String> {
     match sig {
-        SignatureToken::Bool => "Boolean",
-        SignatureToken::U64 => "Integer",
-        SignatureToken::String => "Str",
-        SignatureToken::ByteArray => "ByteArray",
-        SignatureToken::Address => "Address",
-        SignatureToken::Struct(_, _) => "Map",
-        _ => "unsupported",
-    }
-    .into()
+        SignatureToken::Bool => Some("Boolean"),
+        SignatureToken::U8 | SignatureToken::U64 | SignatureToken::U128 => Some("Integer"),
+        SignatureToken::String => Some("Str"),
+        SignatureToken::ByteArray => Some("ByteArray"),
+        SignatureToken::Address => Some("Address"),
+        SignatureToken::Struct(_, _) => Some("Map"),
+        _ => None,
+    }
+    .map(String::from)
 }
 
+// return the TypeValue of a type, where the type parameters of the enclosing function are the
+// parameters tv0, tv1, ... of the procedure.
+pub fn format_type_value(module: &VerifiedModule, sig: &SignatureToken) -> String {
+    match sig {
+        SignatureToken::Bool => "BooleanType()".into(),
+        SignatureToken::U8 | SignatureToken::U64 | SignatureToken::U128 => "IntegerType()".into(),
+        SignatureToken::String => "StrType()".into(),
+        SignatureToken::ByteArray => "ByteArrayType()".into(),
+        SignatureToken::Address => "AddressType()".into(),
+        SignatureToken::Struct(idx, type_actuals) => format!(
+            "StructType({}, {})",
+            struct_name_from_handle_index(module, *idx),
+            format_type_value_array(module, type_actuals)
+        ),
+        SignatureToken::TypeParameter(idx) => format!("tv{}", idx),
+        SignatureToken::Reference(_) | SignatureToken::MutableReference(_) => {
+            panic!("references can't be type actuals")
+        }
+    }
+}
+
+pub fn format_type_value_array(module: &VerifiedModule, sigs: &[SignatureToken]) -> String {
+    sigs.iter()
+        .fold("EmptyTypeValueArray".to_string(), |array, sig| {
+            format!(
+                "ExtendTypeValueArray({}, {})",
+                array,
+                format_type_value(module, sig)
+            )
+        })
+}
+
+pub fn format_address(addr: &AccountAddress) -> String {
+    BigInt::from_str_radix(&format!("{:x}", addr), 16)
+        .unwrap()
+        .to_string()
+}
+
+// return the name of the constant a byte array is represented by.
+pub fn format_byte_array(bytes: &[u8]) -> String {
+    format!("ByteArray_{}", format_bytes(bytes))
+}
+
+// return the name of the constant a string is represented by.
+pub fn format_string(bytes: &[u8]) -> String {
+    format!("String_{}", format_bytes(bytes))
+}
+
+fn format_bytes(bytes: &[u8]) -> String {
+    bytes.iter().map(|b| format!("{:02x}", b)).collect()
+}
+
+// return the names and types of the fields of a struct, in declaration order.
 pub fn get_field_info_from_def_index(
     module: &VerifiedModule,
     def_idx: usize,
-) -> BTreeMap<String, (String, String)> {
-    let mut name_to_type = BTreeMap::new();
+) -> Vec<(String, SignatureToken)> {
     let struct_definition = &module.struct_defs()[def_idx];
     let struct_definition_view = StructDefinitionView::new(module, struct_definition);
-    for field_definition_view in struct_definition_view.fields().unwrap() {
-        let field_name = field_definition_view.name().to_string();
-        let sig = field_definition_view.type_signature().token().as_inner();
-        name_to_type.insert(
-            field_name,
-            (format_type(module, sig), format_value_cons(sig)),
-        );
-    }
-    name_to_type
+    struct_definition_view
+        .fields()
+        .unwrap()
+        .map(|field_definition_view| {
+            (
+                field_definition_view.name().to_string(),
+                field_definition_view
+                    .type_signature()
+                    .token()
+                    .as_inner()
+                    .clone(),
+            )
+        })
+        .collect()
 }
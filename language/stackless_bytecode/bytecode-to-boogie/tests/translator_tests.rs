@@ -1,24 +1,35 @@
 use bytecode_to_boogie::translator::BoogieTranslator;
 use bytecode_verifier::VerifiedModule;
-use ir_to_bytecode::{compiler::compile_module, parser::parse_module};
-use libra_types::account_address::AccountAddress;
-use std::fs;
-use stdlib::stdlib_modules;
-
-// mod translator;
-fn compile_files(file_names: Vec<String>) -> Vec<VerifiedModule> {
-    let mut verified_modules = stdlib_modules().to_vec();
-    let files_len = file_names.len();
-    let dep_files = &file_names[0..files_len];
+use ir_to_bytecode::{
+    compiler::compile_module_with_specs, parser::parse_module, specs::ModuleSpecs,
+};
+use libra_types::{account_address::AccountAddress, account_config};
+use std::{env, fs};
+use stdlib::stdlib::module_defs;
+
+fn compile_files(file_names: &[&str]) -> (Vec<VerifiedModule>, Vec<ModuleSpecs>) {
+    let mut verified_modules = vec![];
+    let mut specs = vec![];
+    for module_def in module_defs() {
+        let (compiled_module, _, module_specs) = compile_module_with_specs(
+            account_config::core_code_address(),
+            (*module_def).clone(),
+            &verified_modules,
+        )
+        .expect("stdlib module failed to compile");
+        let verified_module =
+            VerifiedModule::new(compiled_module).expect("stdlib module failed to verify");
+        verified_modules.push(verified_module);
+        specs.push(module_specs);
+    }
 
-    // assuming the last file is a program that might contain a script
     let address = AccountAddress::default();
-    for file_name in dep_files {
+    for file_name in file_names {
         let code = fs::read_to_string(file_name).unwrap();
         let module = parse_module(&code).unwrap();
-        let compiled_module = compile_module(address, module, &verified_modules)
-            .expect("module failed to compile")
-            .0;
+        let (compiled_module, _, module_specs) =
+            compile_module_with_specs(address, module, &verified_modules)
+                .expect("module failed to compile");
         let verified_module_res = VerifiedModule::new(compiled_module);
 
         match verified_module_res {
@@ -27,123 +38,84 @@ fn compile_files(file_names: Vec<String>) -> Vec<VerifiedModule> {
             }
             Ok(verified_module) => {
                 verified_modules.push(verified_module);
+                specs.push(module_specs);
             }
         }
     }
-    verified_modules
+    (verified_modules, specs)
 }
 
-#[test]
-fn test3() {
-    let mut file_names = vec![];
-    let name = "test_mvir/test3.mvir".to_string();
-    file_names.push(name);
-    let modules = compile_files(file_names.to_vec());
+// Translates the stdlib and the files, and compares the result with the golden file
+// test_mvir/<name>.bpl.expect. The golden file is written instead if the REGENERATE_GOLDENFILES
+// environment variable is set.
+fn check_translation(name: &str, file_names: &[&str]) {
+    let (modules, specs) = compile_files(file_names);
+    let res = BoogieTranslator::new(&modules, &specs).translate();
+
+    let golden_file = format!("test_mvir/{}.bpl.expect", name);
+    if env::var_os("REGENERATE_GOLDENFILES").is_some() {
+        fs::write(&golden_file, res).unwrap();
+        return;
+    }
+    let expected_code = fs::read_to_string(&golden_file).unwrap_or_else(|err| {
+        panic!(
+            "unable to read {}: {}, rerun with REGENERATE_GOLDENFILES=1 to create it",
+            golden_file, err
+        )
+    });
+    assert!(
+        res == expected_code,
+        "the translation differs from {}, rerun with REGENERATE_GOLDENFILES=1 to update it",
+        golden_file
+    );
+}
 
-    let mut ts = BoogieTranslator::new(&modules);
-    let mut res = String::new();
+#[test]
+fn test_stdlib() {
+    check_translation("stdlib", &[]);
+}
 
-    // handwritten boogie code
-    let written_code = fs::read_to_string("src/bytecode_instrs.bpl").unwrap();
-    res.push_str(&written_code);
-    res.push_str(&ts.translate());
-    // This is probably too sensitive to minor changes in libra; commenting for now.
-    //    let expected_code = fs::read_to_string("test_mvir/test3.bpl.expect").unwrap();
-    //    assert_eq!(res, expected_code);
+#[test]
+fn test3() {
+    check_translation("test3", &["test_mvir/test3.mvir"]);
 }
 
 #[test]
 fn test_arithmetic() {
-    let mut file_names = vec![];
-    let name = "test_mvir/test-arithmetic.mvir".to_string();
-    file_names.push(name);
-    let modules = compile_files(file_names.to_vec());
-
-    let mut ts = BoogieTranslator::new(&modules);
-    let mut res = String::new();
-
-    // handwritten boogie code
-    let written_code = fs::read_to_string("src/bytecode_instrs.bpl").unwrap();
-    res.push_str(&written_code);
-    res.push_str(&ts.translate());
+    check_translation("test-arithmetic", &["test_mvir/test-arithmetic.mvir"]);
 }
 
 #[test]
 fn test_control_flow() {
-    let mut file_names = vec![];
-    let name = "test_mvir/test-control-flow.mvir".to_string();
-    file_names.push(name);
-    let modules = compile_files(file_names.to_vec());
-
-    let mut ts = BoogieTranslator::new(&modules);
-    let mut res = String::new();
-
-    // handwritten boogie code
-    let written_code = fs::read_to_string("src/bytecode_instrs.bpl").unwrap();
-    res.push_str(&written_code);
-    res.push_str(&ts.translate());
+    check_translation("test-control-flow", &["test_mvir/test-control-flow.mvir"]);
 }
 
 #[test]
 fn test_func_call() {
-    let mut file_names = vec![];
-    let name = "test_mvir/test-func-call.mvir".to_string();
-    file_names.push(name);
-    let modules = compile_files(file_names.to_vec());
-
-    let mut ts = BoogieTranslator::new(&modules);
-    let mut res = String::new();
-
-    // handwritten boogie code
-    let written_code = fs::read_to_string("src/bytecode_instrs.bpl").unwrap();
-    res.push_str(&written_code);
-    res.push_str(&ts.translate());
+    check_translation("test-func-call", &["test_mvir/test-func-call.mvir"]);
 }
 
 #[test]
 fn test_reference() {
-    let mut file_names = vec![];
-    let name = "test_mvir/test-reference.mvir".to_string();
-    file_names.push(name);
-    let modules = compile_files(file_names.to_vec());
-
-    let mut ts = BoogieTranslator::new(&modules);
-    let mut res = String::new();
-
-    // handwritten boogie code
-    let written_code = fs::read_to_string("src/bytecode_instrs.bpl").unwrap();
-    res.push_str(&written_code);
-    res.push_str(&ts.translate());
+    check_translation("test-reference", &["test_mvir/test-reference.mvir"]);
 }
 
 #[test]
 fn test_special_instr() {
-    let mut file_names = vec![];
-    let name = "test_mvir/test-special-instr.mvir".to_string();
-    file_names.push(name);
-    let modules = compile_files(file_names.to_vec());
-
-    let mut ts = BoogieTranslator::new(&modules);
-    let mut res = String::new();
-
-    // handwritten boogie code
-    let written_code = fs::read_to_string("src/bytecode_instrs.bpl").unwrap();
-    res.push_str(&written_code);
-    res.push_str(&ts.translate());
+    check_translation("test-special-instr", &["test_mvir/test-special-instr.mvir"]);
 }
 
 #[test]
 fn test_struct() {
-    let mut file_names = vec![];
-    let name = "test_mvir/test-struct.mvir".to_string();
-    file_names.push(name);
-    let modules = compile_files(file_names.to_vec());
+    check_translation("test-struct", &["test_mvir/test-struct.mvir"]);
+}
 
-    let mut ts = BoogieTranslator::new(&modules);
-    let mut res = String::new();
+#[test]
+fn test_generics() {
+    check_translation("test-generics", &["test_mvir/test-generics.mvir"]);
+}
 
-    // handwritten boogie code
-    let written_code = fs::read_to_string("src/bytecode_instrs.bpl").unwrap();
-    res.push_str(&written_code);
-    res.push_str(&ts.translate());
+#[test]
+fn test_specs() {
+    check_translation("test-specs", &["test_mvir/test-specs.mvir"]);
 }
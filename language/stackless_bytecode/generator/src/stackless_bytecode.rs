@@ -69,11 +69,17 @@ pub enum StacklessBytecode {
 
     LdTrue(TempIndex),
     LdFalse(TempIndex),
+    LdU8(TempIndex, u8),
     LdConst(TempIndex, u64),
+    LdU128(TempIndex, u128),
     LdAddr(TempIndex, AddressPoolIndex),
     LdByteArray(TempIndex, ByteArrayPoolIndex),
     LdStr(TempIndex, UserStringIndex),
 
+    CastU8(TempIndex, TempIndex),   // t1 = (u8) t2
+    CastU64(TempIndex, TempIndex),  // t1 = (u64) t2
+    CastU128(TempIndex, TempIndex), // t1 = (u128) t2
+
     Not(TempIndex, TempIndex),            // t1 = !t2
     Add(TempIndex, TempIndex, TempIndex), // t1 = t2 binop t3
     Sub(TempIndex, TempIndex, TempIndex),
@@ -243,6 +243,15 @@ impl<'a> StacklessBytecodeGenerator<'a> {
                     .push(SignatureToken::Reference(Box::new(field_type)));
             }
 
+            Bytecode::LdU8(number) => {
+                let temp_index = self.temp_count;
+                self.temp_stack.push(temp_index);
+                self.local_types.push(SignatureToken::U8);
+                self.code
+                    .push(StacklessBytecode::LdU8(temp_index, *number));
+                self.temp_count += 1;
+            }
+
             Bytecode::LdConst(number) => {
                 let temp_index = self.temp_count;
                 self.temp_stack.push(temp_index);
@@ -252,6 +261,40 @@ impl<'a> StacklessBytecodeGenerator<'a> {
                 self.temp_count += 1;
             }
 
+            Bytecode::LdU128(number) => {
+                let temp_index = self.temp_count;
+                self.temp_stack.push(temp_index);
+                self.local_types.push(SignatureToken::U128);
+                self.code
+                    .push(StacklessBytecode::LdU128(temp_index, *number));
+                self.temp_count += 1;
+            }
+
+            Bytecode::CastU8 | Bytecode::CastU64 | Bytecode::CastU128 => {
+                let operand_index = self.temp_stack.pop().unwrap();
+                let temp_index = self.temp_count;
+                self.temp_stack.push(temp_index);
+                self.temp_count += 1;
+                match bytecode {
+                    Bytecode::CastU8 => {
+                        self.local_types.push(SignatureToken::U8);
+                        self.code
+                            .push(StacklessBytecode::CastU8(temp_index, operand_index));
+                    }
+                    Bytecode::CastU64 => {
+                        self.local_types.push(SignatureToken::U64);
+                        self.code
+                            .push(StacklessBytecode::CastU64(temp_index, operand_index));
+                    }
+                    Bytecode::CastU128 => {
+                        self.local_types.push(SignatureToken::U128);
+                        self.code
+                            .push(StacklessBytecode::CastU128(temp_index, operand_index));
+                    }
+                    _ => {}
+                }
+            }
+
             Bytecode::LdAddr(address_pool_index) => {
                 let temp_index = self.temp_count;
                 self.temp_stack.push(temp_index);
@@ -465,7 +508,9 @@ impl<'a> StacklessBytecodeGenerator<'a> {
                 let operand2_index = self.temp_stack.pop().unwrap();
                 let operand1_index = self.temp_stack.pop().unwrap();
                 let temp_index = self.temp_count;
-                self.local_types.push(SignatureToken::U64);
+                // Both operands have the same integer type, which is the type of the result.
+                let operand_type = self.local_types[operand1_index].clone();
+                self.local_types.push(operand_type);
                 self.temp_stack.push(temp_index);
                 self.temp_count += 1;
                 match bytecode {
@@ -18,6 +18,8 @@ lazy_static! {
         make_module_definition!("../modules/block.mvir");
     static ref COIN_MODULE: ModuleDefinition =
         make_module_definition!("../modules/libra_coin.mvir");
+    static ref GAS_SCHEDULE_MODULE: ModuleDefinition =
+        make_module_definition!("../modules/gas_schedule.mvir");
     static ref NATIVE_HASH_MODULE: ModuleDefinition =
         make_module_definition!("../modules/hash.mvir");
     static ref SIGNATURE_MODULE: ModuleDefinition =
@@ -45,6 +47,7 @@ lazy_static! {
             &*BLOCK_MODULE,
             &*BYTEARRAY_UTIL_MODULE,
             &*COIN_MODULE,
+            &*GAS_SCHEDULE_MODULE,
             &*NATIVE_HASH_MODULE,
             &*SIGNATURE_MODULE,
             &*U64_UTIL_MODULE,
@@ -66,6 +69,10 @@ pub fn coin_module() -> ModuleDefinition {
     COIN_MODULE.clone()
 }
 
+pub fn gas_schedule_module() -> ModuleDefinition {
+    GAS_SCHEDULE_MODULE.clone()
+}
+
 pub fn native_hash_module() -> ModuleDefinition {
     NATIVE_HASH_MODULE.clone()
 }
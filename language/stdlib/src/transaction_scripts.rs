@@ -24,6 +24,11 @@ pub fn mint() -> &'static str {
     include_str!("../transaction_scripts/mint.mvir")
 }
 
+/// Returns the source code for the update-gas-schedule transaction script.
+pub fn update_gas_schedule() -> &'static str {
+    include_str!("../transaction_scripts/update_gas_schedule.mvir")
+}
+
 lazy_static! {
     pub static ref PEER_TO_PEER_TRANSFER_TXN_BODY: Program =
         { parse_program(peer_to_peer()).unwrap() };
@@ -41,3 +46,8 @@ lazy_static! {
 lazy_static! {
     pub static ref MINT_TXN_BODY: Program = parse_program(mint()).unwrap();
 }
+
+lazy_static! {
+    pub static ref UPDATE_GAS_SCHEDULE_TXN_BODY: Program =
+        parse_program(update_gas_schedule()).unwrap();
+}
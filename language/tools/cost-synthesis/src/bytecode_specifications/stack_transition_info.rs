@@ -118,12 +118,24 @@ fn bools(num: u64) -> Vec<SignatureTy> {
         .collect()
 }
 
+fn u8s(num: u64) -> Vec<SignatureTy> {
+    (0..num)
+        .map(|_| ty_of_sig_tok(SignatureToken::U8))
+        .collect()
+}
+
 fn u64s(num: u64) -> Vec<SignatureTy> {
     (0..num)
         .map(|_| ty_of_sig_tok(SignatureToken::U64))
         .collect()
 }
 
+fn u128s(num: u64) -> Vec<SignatureTy> {
+    (0..num)
+        .map(|_| ty_of_sig_tok(SignatureToken::U128))
+        .collect()
+}
+
 fn simple_addrs(num: u64) -> Vec<SignatureTy> {
     (0..num)
         .map(|_| ty_of_sig_tok(SignatureToken::Address))
@@ -203,7 +215,13 @@ pub fn call_details(op: &Bytecode) -> Vec<CallDetails> {
             ref_values(1) => empty(),
             ref_resources(1) => empty()
         },
+        Bytecode::LdU8(_) => type_transition! { empty() => u8s(1) },
         Bytecode::LdConst(_) => type_transition! { empty() => u64s(1) },
+        Bytecode::LdU128(_) => type_transition! { empty() => u128s(1) },
+        // Casts are measured on values that fit in the target type.
+        Bytecode::CastU8 => type_transition! { u8s(1) => u8s(1) },
+        Bytecode::CastU64 => type_transition! { u64s(1) => u64s(1) },
+        Bytecode::CastU128 => type_transition! { u64s(1) => u128s(1) },
         Bytecode::LdAddr(_) => type_transition! { empty() => simple_addrs(1) },
         Bytecode::LdByteArray(_) => type_transition! { empty() => byte_arrays(1) },
         Bytecode::LdStr(_) => type_transition! { empty() => strs(1) },
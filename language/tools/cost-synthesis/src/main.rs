@@ -30,7 +30,7 @@ use vm::{
         FunctionDefinitionIndex, FunctionHandleIndex, StructDefinitionIndex, UserStringIndex,
         NO_TYPE_ACTUALS,
     },
-    gas_schedule::{AbstractMemorySize, GasAlgebra, GasCarrier},
+    gas_schedule::{AbstractMemorySize, GasAlgebra, GasCarrier, INITIAL_GAS_SCHEDULE},
     transaction_metadata::TransactionMetadata,
 };
 use vm_cache_map::Arena;
@@ -140,7 +140,12 @@ fn stack_instructions(options: &Opt) {
         Abort,
         LdFalse,
         LdTrue,
+        LdU8(0),
         LdConst(0),
+        LdU128(0),
+        CastU8,
+        CastU64,
+        CastU128,
         LdStr(UserStringIndex::new(0)),
         LdByteArray(ByteArrayPoolIndex::new(0)),
         LdAddr(AddressPoolIndex::new(0)),
@@ -224,7 +229,7 @@ macro_rules! bench_native {
                     let before = Instant::now();
                     let mut args = VecDeque::new();
                     args.push_front(Value::byte_array(stack_access.next_bytearray()));
-                    let _ = $function(args);
+                    let _ = $function(args, &INITIAL_GAS_SCHEDULE);
                     acc + before.elapsed().as_nanos()
                 });
                 // Time per byte averaged over the number of iterations that we performed.
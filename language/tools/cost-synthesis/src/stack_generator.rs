@@ -383,10 +383,18 @@ where
                 let index = self.next_bounded_index(frame_len as TableIndex);
                 (Branch(index as CodeOffset), 1)
             }
+            LdU8(_) => {
+                let i = self.gen.gen::<u8>();
+                (LdU8(i), 1)
+            }
             LdConst(_) => {
                 let i = self.next_int(&[]);
                 (LdConst(i), 1)
             }
+            LdU128(_) => {
+                let i = self.gen.gen::<u128>();
+                (LdU128(i), 1)
+            }
             LdStr(_) => {
                 let string_idx = self.next_user_string_idx();
                 let string_size = self.root_module.user_string_at(string_idx).len();
@@ -496,7 +504,9 @@ where
     fn resolve_to_value(&mut self, sig_token: &SignatureToken, stk: &[Value]) -> Value {
         match sig_token {
             SignatureToken::Bool => Value::bool(self.next_bool()),
+            SignatureToken::U8 => Value::u8(self.gen.gen::<u8>()),
             SignatureToken::U64 => Value::u64(self.next_int(stk)),
+            SignatureToken::U128 => Value::u128(u128::from(self.next_int(stk))),
             SignatureToken::String => Value::string(self.next_vm_string(false)),
             SignatureToken::Address => Value::address(self.next_addr(false)),
             SignatureToken::Reference(sig) | SignatureToken::MutableReference(sig) => {
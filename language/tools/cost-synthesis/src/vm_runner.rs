@@ -39,8 +39,12 @@ macro_rules! with_loaded_vm {
         for (access_path, blob) in $root_account.generate_resources(&mut inhabitor).into_iter() {
             data_cache.set(access_path, blob);
         }
-        let mut $vm =
-            TransactionExecutor::new(&$module_cache, &data_cache, TransactionMetadata::default());
+        let mut $vm = TransactionExecutor::new(
+            &$module_cache,
+            &data_cache,
+            TransactionMetadata::default(),
+            &INITIAL_GAS_SCHEDULE,
+        );
         $vm.turn_off_gas_metering();
         match $vm.execution_stack.push_frame(entry_func) {
             Ok(_) => {}
@@ -0,0 +1,78 @@
+// Copyright (c) The Libra Core Contributors
+// SPDX-License-Identifier: Apache-2.0
+
+//! Replays transactions committed to a DB and attributes the gas they use to the Move functions
+//! that consumed it. For every transaction, the profile is written as folded stacks, which can be
+//! rendered by flamegraph tools such as `flamegraph.pl` or `inferno-flamegraph`, and as a JSON
+//! summary of the gas used by every function.
+
+use config::config::VMConfig;
+use failure::prelude::*;
+use libra_types::transaction::Version;
+use libradb::LibraDB;
+use libradb_state_view::LibraDBStateView;
+use std::{fs, path::PathBuf};
+use structopt::StructOpt;
+use vm_runtime::MoveVM;
+
+#[derive(Debug, StructOpt)]
+#[structopt(about = "Per-function gas profiler for committed Move transactions")]
+struct Args {
+    #[structopt(long, parse(from_os_str))]
+    /// Path to the DB, which must not be in use by a running node
+    db_dir: PathBuf,
+    #[structopt(long)]
+    /// Version of the first transaction to profile
+    start_version: Version,
+    #[structopt(long, default_value = "1")]
+    /// Number of transactions to profile
+    count: u64,
+    #[structopt(long, parse(from_os_str))]
+    /// Directory the profiles are written to, as `<version>.folded` and `<version>.json`
+    output_dir: PathBuf,
+}
+
+fn main() -> Result<()> {
+    let args = Args::from_args();
+    ensure!(
+        args.start_version > 0,
+        "The genesis transaction can't be profiled"
+    );
+
+    let db = LibraDB::new(&args.db_dir);
+    fs::create_dir_all(&args.output_dir)?;
+    for version in args.start_version..args.start_version + args.count {
+        let (txn, txn_info) = db
+            .get_transactions(version, 1, version, false)?
+            .transaction_and_infos
+            .pop()
+            .ok_or_else(|| format_err!("No transaction at version {}", version))?;
+        let state_view = LibraDBStateView::new(&db, version - 1);
+        let (output, profile) = MoveVM::profile_transaction(txn, &VMConfig::default(), &state_view);
+
+        fs::write(
+            args.output_dir.join(format!("{}.folded", version)),
+            profile.to_folded_stacks(),
+        )?;
+        fs::write(
+            args.output_dir.join(format!("{}.json", version)),
+            profile.to_json()?,
+        )?;
+        println!(
+            "Version {}: {} gas units profiled, {} gas used (committed: {})",
+            version,
+            profile.total(),
+            output.gas_used(),
+            txn_info.gas_used()
+        );
+        if output.gas_used() != txn_info.gas_used() {
+            println!(
+                "Version {}: the replayed gas differs from the committed one, the profile may be \
+                 inaccurate",
+                version
+            );
+        }
+    }
+
+    Ok(())
+}
@@ -0,0 +1,19 @@
+// Copyright (c) The Libra Core Contributors
+// SPDX-License-Identifier: Apache-2.0
+
+//! This crate provides an interactive debugger for Move transactions.
+//!
+//! A transaction is replayed with a [`BreakpointDebugger`] attached to the VM, either against a
+//! fresh genesis state in a `FakeDataStore` or against the state of a `LibraDB` preceding a
+//! committed transaction. Whenever the execution stops, commands read from the terminal inspect
+//! the call stack, the locals of every frame, the value stack and the global resources loaded by
+//! the transaction, with the instructions mapped back to the Move IR source through the source
+//! maps written by the compiler.
+//!
+//! [`BreakpointDebugger`]: vm_runtime::debugger::BreakpointDebugger
+
+pub mod repl;
+pub mod source_maps;
+
+#[cfg(test)]
+mod repl_test;
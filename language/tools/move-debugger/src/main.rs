@@ -0,0 +1,133 @@
+// Copyright (c) The Libra Core Contributors
+// SPDX-License-Identifier: Apache-2.0
+
+use config::config::{VMConfig, VMPublishingOption};
+use failure::prelude::*;
+use language_e2e_tests::{account::AccountData, executor::FakeExecutor, gas_costs::TXN_RESERVED};
+use libra_types::{
+    identifier::Identifier,
+    transaction::{
+        parse_as_transaction_argument, Script, TransactionArgument, TransactionOutput, Version,
+    },
+};
+use libradb::LibraDB;
+use libradb_state_view::LibraDBStateView;
+use move_debugger::{
+    repl::{parse_breakpoint, Repl},
+    source_maps::SourceMaps,
+};
+use std::{fs, io, path::PathBuf};
+use structopt::StructOpt;
+use vm::file_format::self_module_name;
+use vm_runtime::{
+    debugger::{Breakpoint, BreakpointDebugger},
+    MoveVM,
+};
+
+#[derive(Debug, StructOpt)]
+#[structopt(about = "Interactive debugger for Move transactions")]
+struct Args {
+    #[structopt(short = "b", long = "break", parse(try_from_str = parse_breakpoint))]
+    /// Breakpoints, as `[<module>::]<function>[@<offset>]`. Without any, the execution stops at
+    /// the entry of the script
+    breakpoints: Vec<Breakpoint>,
+    #[structopt(short = "s", long = "source", parse(from_os_str))]
+    /// Move IR source files of the scripts and modules to debug, each with the source map written
+    /// by the compiler next to it
+    sources: Vec<PathBuf>,
+    #[structopt(subcommand)]
+    command: Command,
+}
+
+#[derive(Debug, StructOpt)]
+enum Command {
+    /// Runs a script compiled by the IR compiler, sent by a new account, against the genesis
+    /// state.
+    Run {
+        #[structopt(parse(from_os_str))]
+        /// Path to the compiled script
+        script: PathBuf,
+        #[structopt(parse(try_from_str = parse_as_transaction_argument))]
+        /// Arguments of the script
+        args: Vec<TransactionArgument>,
+        #[structopt(long, default_value = "1000000")]
+        /// Balance of the sender
+        balance: u64,
+    },
+    /// Replays the transaction committed at a version of a DB against the state preceding it.
+    Replay {
+        #[structopt(long, parse(from_os_str))]
+        /// Path to the DB, which must not be in use by a running node
+        db_dir: PathBuf,
+        #[structopt(long)]
+        /// Version of the transaction
+        version: Version,
+    },
+}
+
+fn main() -> Result<()> {
+    let args = Args::from_args();
+
+    let mut source_maps = SourceMaps::default();
+    for source in &args.sources {
+        source_maps.add_source(source)?;
+    }
+    let mut breakpoints = args.breakpoints;
+    if breakpoints.is_empty() {
+        breakpoints.push(Breakpoint::FunctionEntry {
+            module: Some(self_module_name().to_owned()),
+            function: Identifier::new("main")?,
+        });
+    }
+    let stdin = io::stdin();
+    let mut repl = Repl::new(&source_maps, stdin.lock(), io::stdout());
+    let mut debugger = BreakpointDebugger::new(breakpoints, |context, breakpoints| {
+        repl.on_break(context, breakpoints)
+    });
+
+    let output = match args.command {
+        Command::Run {
+            script,
+            args,
+            balance,
+        } => {
+            let script: Script = serde_json::from_slice(&fs::read(&script)?)?;
+            let mut executor = FakeExecutor::from_genesis_with_options(VMPublishingOption::Open);
+            let sender = AccountData::new(balance, 0);
+            executor.add_account_data(&sender);
+            let txn = sender.account().create_signed_txn_with_args(
+                script.code().to_vec(),
+                args,
+                0,
+                TXN_RESERVED,
+                1,
+            );
+            executor.debug_transaction(txn, &mut debugger)
+        }
+        Command::Replay { db_dir, version } => {
+            ensure!(version > 0, "The genesis transaction can't be debugged");
+            let db = LibraDB::new(&db_dir);
+            let (txn, _txn_info) = db
+                .get_transactions(version, 1, version, false)?
+                .transaction_and_infos
+                .pop()
+                .ok_or_else(|| format_err!("No transaction at version {}", version))?;
+            let state_view = LibraDBStateView::new(&db, version - 1);
+            MoveVM::debug_transaction(txn, &VMConfig::default(), &state_view, &mut debugger)
+        }
+    };
+    print_output(&output);
+
+    Ok(())
+}
+
+fn print_output(output: &TransactionOutput) {
+    println!("Status: {:?}", output.status());
+    println!("Gas used: {}", output.gas_used());
+    for (access_path, write_op) in output.write_set() {
+        println!("Write: {} {:?}", access_path, write_op);
+    }
+    for event in output.events() {
+        println!("Event: {:?}", event);
+    }
+}
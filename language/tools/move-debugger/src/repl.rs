@@ -0,0 +1,188 @@
+// Copyright (c) The Libra Core Contributors
+// SPDX-License-Identifier: Apache-2.0
+
+use crate::source_maps::SourceMaps;
+use failure::prelude::*;
+use libra_types::identifier::Identifier;
+use std::io::{BufRead, Write};
+use vm::access::ModuleAccess;
+use vm_runtime::debugger::{Breakpoint, DebugCommand, DebugContext, StackFrame};
+
+const HELP: &str = "\
+Commands:
+  s, step                                      execute the next instruction
+  c, continue                                  run until the next breakpoint
+  bt, backtrace                                print the call stack
+  l, locals [<frame>]                          print the locals of a frame, 0 being the current one
+  stack                                        print the value stack, from the top
+  globals                                      print the global resources loaded so far
+  b, break [<module>::]<function>[@<offset>]   stop at the entry of a function or at an offset
+  d, delete <breakpoint>                       delete a breakpoint
+  breakpoints                                  list the breakpoints
+  h, help                                      print this help";
+
+/// Parses a breakpoint written as `[<module>::]<function>[@<offset>]`. A breakpoint without an
+/// offset stops at the entry of the function.
+pub fn parse_breakpoint(s: &str) -> Result<Breakpoint> {
+    let (location, offset) = match s.find('@') {
+        Some(idx) => (&s[..idx], Some(s[idx + 1..].parse()?)),
+        None => (s, None),
+    };
+    let (module, function) = match location.find("::") {
+        Some(idx) => (Some(&location[..idx]), &location[idx + 2..]),
+        None => (None, location),
+    };
+    ensure!(
+        !function.is_empty() && module.map_or(true, |module| !module.is_empty()),
+        "Invalid breakpoint {}",
+        s
+    );
+    let module = module.map(Identifier::new).transpose()?;
+    let function = Identifier::new(function)?;
+    Ok(match offset {
+        Some(offset) => Breakpoint::CodeOffset {
+            module,
+            function,
+            offset,
+        },
+        None => Breakpoint::FunctionEntry { module, function },
+    })
+}
+
+fn frame_name(frame: &StackFrame) -> String {
+    format!("{}::{} @{}", frame.module.name(), frame.function, frame.pc)
+}
+
+/// Runs the commands read from `input` whenever the execution stops, and writes their results to
+/// `output`.
+pub struct Repl<'a, R, W> {
+    source_maps: &'a SourceMaps,
+    input: R,
+    output: W,
+}
+
+impl<'a, R, W> Repl<'a, R, W>
+where
+    R: BufRead,
+    W: Write,
+{
+    pub fn new(source_maps: &'a SourceMaps, input: R, output: W) -> Self {
+        Self {
+            source_maps,
+            input,
+            output,
+        }
+    }
+
+    /// Prints where the execution stopped and runs commands until one resumes the execution.
+    /// Once the input is exhausted, the transaction runs to its end.
+    pub fn on_break(
+        &mut self,
+        context: &DebugContext,
+        breakpoints: &mut Vec<Breakpoint>,
+    ) -> DebugCommand {
+        match self.run(context, breakpoints) {
+            Ok(command) => command,
+            Err(err) => {
+                eprintln!("Debugger failed: {}", err);
+                breakpoints.clear();
+                DebugCommand::Continue
+            }
+        }
+    }
+
+    fn run(
+        &mut self,
+        context: &DebugContext,
+        breakpoints: &mut Vec<Breakpoint>,
+    ) -> Result<DebugCommand> {
+        if let Some(frame) = context.current_frame() {
+            writeln!(
+                self.output,
+                "Stopped at {}: {:?}",
+                frame_name(&frame),
+                context.instruction()
+            )?;
+            self.print_source_line(&frame)?;
+        }
+        loop {
+            write!(self.output, "(move-debugger) ")?;
+            self.output.flush()?;
+            let mut line = String::new();
+            if self.input.read_line(&mut line)? == 0 {
+                breakpoints.clear();
+                return Ok(DebugCommand::Continue);
+            }
+            let mut words = line.split_whitespace();
+            let command = match words.next() {
+                Some(command) => command,
+                None => continue,
+            };
+            let argument = words.next();
+            match (command, argument) {
+                ("s", _) | ("step", _) => return Ok(DebugCommand::Step),
+                ("c", _) | ("continue", _) => return Ok(DebugCommand::Continue),
+                ("bt", _) | ("backtrace", _) => {
+                    for (idx, frame) in context.call_stack().iter().rev().enumerate() {
+                        writeln!(self.output, "#{} {}", idx, frame_name(frame))?;
+                        self.print_source_line(frame)?;
+                    }
+                }
+                ("l", frame) | ("locals", frame) => {
+                    let call_stack = context.call_stack();
+                    let frame = frame
+                        .map_or(Ok(0), str::parse::<usize>)
+                        .ok()
+                        .and_then(|depth| call_stack.iter().rev().nth(depth));
+                    match frame {
+                        Some(frame) => writeln!(self.output, "{:?}", frame.locals)?,
+                        None => writeln!(self.output, "No such frame")?,
+                    }
+                }
+                ("stack", _) => {
+                    for (idx, value) in context.value_stack().iter().rev().enumerate() {
+                        writeln!(self.output, "{}: {:?}", idx, value)?;
+                    }
+                }
+                ("globals", _) => {
+                    for (access_path, global_ref) in context.resources() {
+                        writeln!(self.output, "{}: {:?}", access_path, global_ref)?;
+                    }
+                }
+                ("b", Some(breakpoint)) | ("break", Some(breakpoint)) => {
+                    match parse_breakpoint(breakpoint) {
+                        Ok(breakpoint) => {
+                            writeln!(
+                                self.output,
+                                "Breakpoint {}: {:?}",
+                                breakpoints.len(),
+                                breakpoint
+                            )?;
+                            breakpoints.push(breakpoint);
+                        }
+                        Err(err) => writeln!(self.output, "{}", err)?,
+                    }
+                }
+                ("d", Some(idx)) | ("delete", Some(idx)) => match idx.parse::<usize>() {
+                    Ok(idx) if idx < breakpoints.len() => {
+                        breakpoints.remove(idx);
+                    }
+                    _ => writeln!(self.output, "No breakpoint {}", idx)?,
+                },
+                ("breakpoints", _) => {
+                    for (idx, breakpoint) in breakpoints.iter().enumerate() {
+                        writeln!(self.output, "{}: {:?}", idx, breakpoint)?;
+                    }
+                }
+                _ => writeln!(self.output, "{}", HELP)?,
+            }
+        }
+    }
+
+    fn print_source_line(&mut self, frame: &StackFrame) -> Result<()> {
+        if let Some(source_line) = self.source_maps.source_line(frame) {
+            writeln!(self.output, "    {}", source_line)?;
+        }
+        Ok(())
+    }
+}
@@ -0,0 +1,29 @@
+// Copyright (c) The Libra Core Contributors
+// SPDX-License-Identifier: Apache-2.0
+
+use crate::repl::parse_breakpoint;
+use libra_types::identifier::Identifier;
+use vm_runtime::debugger::Breakpoint;
+
+#[test]
+fn test_parse_breakpoint() {
+    assert_eq!(
+        parse_breakpoint("main").unwrap(),
+        Breakpoint::FunctionEntry {
+            module: None,
+            function: Identifier::new("main").unwrap(),
+        }
+    );
+    assert_eq!(
+        parse_breakpoint("LibraAccount::pay_from_sender@3").unwrap(),
+        Breakpoint::CodeOffset {
+            module: Some(Identifier::new("LibraAccount").unwrap()),
+            function: Identifier::new("pay_from_sender").unwrap(),
+            offset: 3,
+        }
+    );
+
+    for s in &["", "LibraAccount::", "::main", "main@", "main@-1", "main@x"] {
+        assert!(parse_breakpoint(s).is_err(), "{} should not parse", s);
+    }
+}
@@ -0,0 +1,67 @@
+// Copyright (c) The Libra Core Contributors
+// SPDX-License-Identifier: Apache-2.0
+
+use bytecode_source_map::{source_map::ModuleSourceMap, utils::module_source_map_from_file};
+use failure::prelude::*;
+use ir_to_bytecode_syntax::ast::Loc;
+use libra_types::identifier::Identifier;
+use std::{
+    collections::HashMap,
+    fs,
+    path::{Path, PathBuf},
+};
+use vm::access::ModuleAccess;
+use vm_runtime::debugger::StackFrame;
+
+/// The Move IR source code of a module or a script with its source map.
+struct ModuleSource {
+    path: PathBuf,
+    source: String,
+    source_map: ModuleSourceMap<Loc>,
+}
+
+/// The sources of the modules and scripts being debugged, by module name. The module of a script
+/// is named `<SELF>`.
+#[derive(Default)]
+pub struct SourceMaps {
+    modules: HashMap<Identifier, ModuleSource>,
+}
+
+impl SourceMaps {
+    /// Loads the Move IR source code at `source_path` along with its source map, which the
+    /// compiler writes next to it with the `mvsm` extension.
+    pub fn add_source(&mut self, source_path: &Path) -> Result<()> {
+        let source = fs::read_to_string(source_path)?;
+        let source_map = module_source_map_from_file(&source_path.with_extension("mvsm"))?;
+        self.modules.insert(
+            source_map.module_name.1.clone(),
+            ModuleSource {
+                path: source_path.to_path_buf(),
+                source,
+                source_map,
+            },
+        );
+        Ok(())
+    }
+
+    /// Returns the path, line number and text of the source line of the instruction of `frame`,
+    /// or `None` if the source of its module wasn't loaded.
+    pub fn source_line(&self, frame: &StackFrame) -> Option<String> {
+        let module = self.modules.get(frame.module.name())?;
+        let function_idx = frame.module.function_defs_table.get(frame.function)?;
+        let loc = module
+            .source_map
+            .get_code_location(*function_idx, frame.pc)
+            .ok()?;
+        let preceding_source = module.source.get(..loc.start().0 as usize)?;
+        let line_number = preceding_source.matches('\n').count() + 1;
+        let line_start = preceding_source.rfind('\n').map_or(0, |idx| idx + 1);
+        let line = module.source[line_start..].lines().next().unwrap_or("");
+        Some(format!(
+            "{}:{}: {}",
+            module.path.display(),
+            line_number,
+            line.trim()
+        ))
+    }
+}
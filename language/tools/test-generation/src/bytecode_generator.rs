@@ -20,9 +20,15 @@ type LocalIndexToBytecode = fn(LocalIndex) -> Bytecode;
 /// This type represents bytecode instructions that take a `u16`
 type CodeOffsetToBytecode = fn(CodeOffset) -> Bytecode;
 
+/// This type represents bytecode instructions that take a `u8`
+type U8ToBytecode = fn(u8) -> Bytecode;
+
 /// This type represents bytecode instructions that take a `u64`
 type U64ToBytecode = fn(u64) -> Bytecode;
 
+/// This type represents bytecode instructions that take a `u128`
+type U128ToBytecode = fn(u128) -> Bytecode;
+
 /// This type represents bytecode instructions that take a `UserStringIndex`
 type UserStringIndexToBytecode = fn(UserStringIndex) -> Bytecode;
 
@@ -55,9 +61,15 @@ enum BytecodeType {
     /// Instructions that take a `CodeOffset`
     CodeOffset(CodeOffsetToBytecode),
 
+    /// Instructions that take a `u8`
+    U8(U8ToBytecode),
+
     /// Instructions that take a `u64`
     U64(U64ToBytecode),
 
+    /// Instructions that take a `u128`
+    U128(U128ToBytecode),
+
     /// Instructions that take a `UserStringIndex`
     UserStringIndex(UserStringIndexToBytecode),
 
@@ -106,7 +118,9 @@ impl BytecodeGenerator {
     pub fn new(seed: Option<[u8; 32]>) -> Self {
         let instructions: Vec<(StackEffect, BytecodeType)> = vec![
             (StackEffect::Sub, BytecodeType::NoArg(Bytecode::Pop)),
+            (StackEffect::Add, BytecodeType::U8(Bytecode::LdU8)),
             (StackEffect::Add, BytecodeType::U64(Bytecode::LdConst)),
+            (StackEffect::Add, BytecodeType::U128(Bytecode::LdU128)),
             (
                 StackEffect::Add,
                 BytecodeType::UserStringIndex(Bytecode::LdStr),
@@ -141,6 +155,9 @@ impl BytecodeGenerator {
             (StackEffect::Nop, BytecodeType::NoArg(Bytecode::ReadRef)),
             (StackEffect::Sub, BytecodeType::NoArg(Bytecode::WriteRef)),
             (StackEffect::Nop, BytecodeType::NoArg(Bytecode::FreezeRef)),
+            (StackEffect::Nop, BytecodeType::NoArg(Bytecode::CastU8)),
+            (StackEffect::Nop, BytecodeType::NoArg(Bytecode::CastU64)),
+            (StackEffect::Nop, BytecodeType::NoArg(Bytecode::CastU128)),
             (StackEffect::Sub, BytecodeType::NoArg(Bytecode::Add)),
             (StackEffect::Sub, BytecodeType::NoArg(Bytecode::Sub)),
             (StackEffect::Sub, BytecodeType::NoArg(Bytecode::Mul)),
@@ -268,10 +285,18 @@ impl BytecodeGenerator {
                     // Set 0 as the offset. This will be set correctly during serialization
                     instruction(0)
                 }
+                BytecodeType::U8(instruction) => {
+                    // Generate a random u8 constant to load
+                    instruction(self.rng.gen_range(0, u8::max_value()))
+                }
                 BytecodeType::U64(instruction) => {
                     // Generate a random u64 constant to load
                     instruction(self.rng.gen_range(0, u64::max_value()))
                 }
+                BytecodeType::U128(instruction) => {
+                    // Generate a random u128 constant to load
+                    instruction(self.rng.gen_range(0, u128::max_value()))
+                }
                 BytecodeType::UserStringIndex(instruction) => {
                     // Select a random user string
                     instruction(UserStringIndex::new(
@@ -488,7 +513,9 @@ impl BytecodeGenerator {
                     let next_instruction = match abstract_value.token {
                         SignatureToken::String => Bytecode::LdStr(UserStringIndex::new(0)),
                         SignatureToken::Address => Bytecode::LdAddr(AddressPoolIndex::new(0)),
+                        SignatureToken::U8 => Bytecode::LdU8(0),
                         SignatureToken::U64 => Bytecode::LdConst(0),
+                        SignatureToken::U128 => Bytecode::LdU128(0),
                         SignatureToken::Bool => Bytecode::LdFalse,
                         SignatureToken::ByteArray => {
                             Bytecode::LdByteArray(ByteArrayPoolIndex::new(0))
@@ -565,7 +592,9 @@ impl BytecodeGenerator {
                     let next_instruction = match token_type {
                         SignatureToken::String => Bytecode::LdStr(UserStringIndex::new(0)),
                         SignatureToken::Address => Bytecode::LdAddr(AddressPoolIndex::new(0)),
+                        SignatureToken::U8 => Bytecode::LdU8(0),
                         SignatureToken::U64 => Bytecode::LdConst(0),
+                        SignatureToken::U128 => Bytecode::LdU128(0),
                         SignatureToken::Bool => Bytecode::LdFalse,
                         SignatureToken::ByteArray => {
                             Bytecode::LdByteArray(ByteArrayPoolIndex::new(0))
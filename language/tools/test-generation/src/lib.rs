@@ -28,6 +28,7 @@ use vm::{
         Bytecode, CompiledModuleMut, FunctionDefinitionIndex, FunctionSignature, SignatureToken,
         StructDefinitionIndex,
     },
+    gas_schedule::INITIAL_GAS_SCHEDULE,
     transaction_metadata::TransactionMetadata,
     CompiledModule,
 };
@@ -72,14 +73,20 @@ fn run_vm(module: VerifiedModule) -> Result<(), String> {
     for m in modules.clone() {
         module_cache.cache_module(m);
     }
-    let mut vm =
-        TransactionExecutor::new(&module_cache, &data_cache, TransactionMetadata::default());
+    let mut vm = TransactionExecutor::new(
+        &module_cache,
+        &data_cache,
+        TransactionMetadata::default(),
+        &INITIAL_GAS_SCHEDULE,
+    );
     let entry_func = FunctionRef::new(&loaded_module, entry_idx);
     let mut function_args: Vec<Value> = Vec::new();
     for arg_type in entry_func.signature().arg_types.clone() {
         function_args.push(match arg_type {
             SignatureToken::Address => Value::address(AccountAddress::new([0; 32])),
+            SignatureToken::U8 => Value::u8(0),
             SignatureToken::U64 => Value::u64(0),
+            SignatureToken::U128 => Value::u128(0),
             SignatureToken::Bool => Value::bool(true),
             SignatureToken::String => Value::string("".into()),
             SignatureToken::ByteArray => Value::byte_array(ByteArray::new(vec![])),
@@ -7,7 +7,7 @@ use crate::{
     state_create_struct, state_function_can_acquire_resource, state_local_availability_is,
     state_local_exists, state_local_kind_is, state_local_place, state_local_set, state_local_take,
     state_local_take_borrow, state_memory_safe, state_never, state_register_dereference,
-    state_stack_function_call, state_stack_function_popn, state_stack_has,
+    state_stack_function_call, state_stack_function_popn, state_stack_has, state_stack_has_integer,
     state_stack_has_polymorphic_eq, state_stack_has_reference, state_stack_has_struct,
     state_stack_kind_is, state_stack_local_polymorphic_eq, state_stack_pop, state_stack_push,
     state_stack_push_register, state_stack_push_register_borrow, state_stack_ref_polymorphic_eq,
@@ -42,12 +42,45 @@ pub fn instruction_summary(instruction: Bytecode) -> Summary {
             ],
             effects: vec![state_stack_pop!()],
         },
+        Bytecode::LdU8(_) => Summary {
+            preconditions: vec![],
+            effects: vec![state_stack_push!(AbstractValue::new_primitive(
+                SignatureToken::U8
+            ))],
+        },
         Bytecode::LdConst(_) => Summary {
             preconditions: vec![],
             effects: vec![state_stack_push!(AbstractValue::new_primitive(
                 SignatureToken::U64
             ))],
         },
+        Bytecode::LdU128(_) => Summary {
+            preconditions: vec![],
+            effects: vec![state_stack_push!(AbstractValue::new_primitive(
+                SignatureToken::U128
+            ))],
+        },
+        Bytecode::CastU8 => Summary {
+            preconditions: vec![state_stack_has_integer!(0)],
+            effects: vec![
+                state_stack_pop!(),
+                state_stack_push!(AbstractValue::new_primitive(SignatureToken::U8)),
+            ],
+        },
+        Bytecode::CastU64 => Summary {
+            preconditions: vec![state_stack_has_integer!(0)],
+            effects: vec![
+                state_stack_pop!(),
+                state_stack_push!(AbstractValue::new_primitive(SignatureToken::U64)),
+            ],
+        },
+        Bytecode::CastU128 => Summary {
+            preconditions: vec![state_stack_has_integer!(0)],
+            effects: vec![
+                state_stack_pop!(),
+                state_stack_push!(AbstractValue::new_primitive(SignatureToken::U128)),
+            ],
+        },
         Bytecode::LdStr(_) => Summary {
             preconditions: vec![],
             effects: vec![state_stack_push!(AbstractValue::new_primitive(
@@ -52,6 +52,13 @@ pub fn stack_has_polymorphic_eq(state: &AbstractState, index1: usize, index2: us
     }
 }
 
+/// Determine whether the abstract value at `index` is an integer of any type
+pub fn stack_has_integer(state: &AbstractState, index: usize) -> bool {
+    state
+        .stack_peek(index)
+        .map_or(false, |abstract_value| abstract_value.token.is_integer())
+}
+
 /// Pop from the top of the stack.
 pub fn stack_pop(state: &AbstractState) -> Result<AbstractState, VMError> {
     let mut state = state.clone();
@@ -524,6 +531,15 @@ macro_rules! state_stack_has_polymorphic_eq {
     };
 }
 
+/// Wrapper for enclosing the arguments of `stack_has_integer` so that only the `state` needs
+/// to be given.
+#[macro_export]
+macro_rules! state_stack_has_integer {
+    ($e: expr) => {
+        Box::new(move |state| stack_has_integer(state, $e))
+    };
+}
+
 /// Wrapper for enclosing the arguments of `stack_pop` so that only the `state` needs
 /// to be given.
 #[macro_export]
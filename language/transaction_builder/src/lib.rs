@@ -14,11 +14,12 @@ use stdlib::{
     stdlib_modules,
     transaction_scripts::{
         CREATE_ACCOUNT_TXN_BODY, MINT_TXN_BODY, PEER_TO_PEER_TRANSFER_TXN_BODY,
-        ROTATE_AUTHENTICATION_KEY_TXN_BODY,
+        ROTATE_AUTHENTICATION_KEY_TXN_BODY, UPDATE_GAS_SCHEDULE_TXN_BODY,
     },
 };
 #[cfg(any(test, feature = "testing"))]
 use vm::file_format::Bytecode;
+use vm::gas_schedule::CostTable;
 
 lazy_static! {
     static ref PEER_TO_PEER_TXN: Vec<u8> = { compile_script(&PEER_TO_PEER_TRANSFER_TXN_BODY) };
@@ -26,6 +27,8 @@ lazy_static! {
     static ref ROTATE_AUTHENTICATION_KEY_TXN: Vec<u8> =
         { compile_script(&ROTATE_AUTHENTICATION_KEY_TXN_BODY) };
     static ref MINT_TXN: Vec<u8> = { compile_script(&MINT_TXN_BODY) };
+    static ref UPDATE_GAS_SCHEDULE_TXN: Vec<u8> =
+        { compile_script(&UPDATE_GAS_SCHEDULE_TXN_BODY) };
 }
 
 fn compile_script(body: &ast::Program) -> Vec<u8> {
@@ -129,6 +132,21 @@ pub fn encode_mint_script(sender: &AccountAddress, amount: u64) -> Script {
     )
 }
 
+/// Encode a program replacing the on-chain gas schedule with `gas_schedule`, effective from the
+/// next block. Fails unless it is sent by the association account.
+pub fn encode_update_gas_schedule_script(gas_schedule: &CostTable) -> Script {
+    let (instruction_schedule, native_schedule) = gas_schedule
+        .to_schedules()
+        .expect("Unable to serialize the gas schedule");
+    Script::new(
+        UPDATE_GAS_SCHEDULE_TXN.clone(),
+        vec![
+            TransactionArgument::ByteArray(instruction_schedule),
+            TransactionArgument::ByteArray(native_schedule),
+        ],
+    )
+}
+
 /// Returns a user friendly mnemonic for the transaction type if the transaction is
 /// for a known, white listed, transaction.
 pub fn get_transaction_name(code: &[u8]) -> String {
@@ -140,6 +158,8 @@ pub fn get_transaction_name(code: &[u8]) -> String {
         return "mint_transaction".to_string();
     } else if code == &ROTATE_AUTHENTICATION_KEY_TXN[..] {
         return "rotate_authentication_key_transaction".to_string();
+    } else if code == &UPDATE_GAS_SCHEDULE_TXN[..] {
+        return "update_gas_schedule_transaction".to_string();
     }
     "<unknown transaction>".to_string()
 }
@@ -150,6 +170,7 @@ pub fn allowing_script_hashes() -> Vec<[u8; SCRIPT_HASH_LENGTH]> {
         PEER_TO_PEER_TXN.clone(),
         ROTATE_AUTHENTICATION_KEY_TXN.clone(),
         CREATE_ACCOUNT_TXN.clone(),
+        UPDATE_GAS_SCHEDULE_TXN.clone(),
     ]
     .into_iter()
     .map(|s| *HashValue::from_sha3_256(&s).as_ref())
@@ -161,5 +182,6 @@ pub fn default_config() -> VMConfig {
         publishing_options: VMPublishingOption::Locked(HashSet::from_iter(
             allowing_script_hashes().into_iter(),
         )),
+        parallel_execution: false,
     }
 }
@@ -707,7 +707,9 @@ fn load_signature_token(cursor: &mut Cursor<&[u8]>) -> BinaryLoaderResult<Signat
     if let Ok(byte) = cursor.read_u8() {
         match SerializedType::from_u8(byte)? {
             SerializedType::BOOL => Ok(SignatureToken::Bool),
+            SerializedType::U8 => Ok(SignatureToken::U8),
             SerializedType::INTEGER => Ok(SignatureToken::U64),
+            SerializedType::U128 => Ok(SignatureToken::U128),
             SerializedType::STRING => Ok(SignatureToken::String),
             SerializedType::BYTEARRAY => Ok(SignatureToken::ByteArray),
             SerializedType::ADDRESS => Ok(SignatureToken::Address),
@@ -926,10 +928,18 @@ fn load_code(cursor: &mut Cursor<&[u8]>, code: &mut Vec<Bytecode>) -> BinaryLoad
                 let jump = read_u16_internal(cursor)?;
                 Bytecode::Branch(jump)
             }
+            Opcodes::LD_U8 => {
+                let value = read_u8_internal(cursor)?;
+                Bytecode::LdU8(value)
+            }
             Opcodes::LD_CONST => {
                 let value = read_u64_internal(cursor)?;
                 Bytecode::LdConst(value)
             }
+            Opcodes::LD_U128 => {
+                let value = read_u128_internal(cursor)?;
+                Bytecode::LdU128(value)
+            }
             Opcodes::LD_ADDR => {
                 let idx = read_uleb_u16_internal(cursor)?;
                 Bytecode::LdAddr(AddressPoolIndex(idx))
@@ -999,6 +1009,9 @@ fn load_code(cursor: &mut Cursor<&[u8]>, code: &mut Vec<Bytecode>) -> BinaryLoad
             }
             Opcodes::READ_REF => Bytecode::ReadRef,
             Opcodes::WRITE_REF => Bytecode::WriteRef,
+            Opcodes::CAST_U8 => Bytecode::CastU8,
+            Opcodes::CAST_U64 => Bytecode::CastU64,
+            Opcodes::CAST_U128 => Bytecode::CastU128,
             Opcodes::ADD => Bytecode::Add,
             Opcodes::SUB => Bytecode::Sub,
             Opcodes::MUL => Bytecode::Mul,
@@ -1074,6 +1087,12 @@ fn read_uleb_u32_internal(cursor: &mut Cursor<&[u8]>) -> BinaryLoaderResult<u32>
     read_uleb128_as_u32(cursor).map_err(|_| VMStatus::new(StatusCode::MALFORMED))
 }
 
+fn read_u8_internal(cursor: &mut Cursor<&[u8]>) -> BinaryLoaderResult<u8> {
+    cursor
+        .read_u8()
+        .map_err(|_| VMStatus::new(StatusCode::MALFORMED))
+}
+
 fn read_u16_internal(cursor: &mut Cursor<&[u8]>) -> BinaryLoaderResult<u16> {
     cursor
         .read_u16::<LittleEndian>()
@@ -1092,6 +1111,12 @@ fn read_u64_internal(cursor: &mut Cursor<&[u8]>) -> BinaryLoaderResult<u64> {
         .map_err(|_| VMStatus::new(StatusCode::MALFORMED))
 }
 
+fn read_u128_internal(cursor: &mut Cursor<&[u8]>) -> BinaryLoaderResult<u128> {
+    cursor
+        .read_u128::<LittleEndian>()
+        .map_err(|_| VMStatus::new(StatusCode::MALFORMED))
+}
+
 impl TableType {
     fn from_u8(value: u8) -> BinaryLoaderResult<TableType> {
         match value {
@@ -1138,6 +1163,8 @@ impl SerializedType {
             0x7 => Ok(SerializedType::STRUCT),
             0x8 => Ok(SerializedType::BYTEARRAY),
             0x9 => Ok(SerializedType::TYPE_PARAMETER),
+            0xA => Ok(SerializedType::U8),
+            0xB => Ok(SerializedType::U128),
             _ => Err(VMStatus::new(StatusCode::UNKNOWN_SERIALIZED_TYPE)),
         }
     }
@@ -1231,6 +1258,11 @@ impl Opcodes {
             0x34 => Ok(Opcodes::GET_TXN_SEQUENCE_NUMBER),
             0x35 => Ok(Opcodes::GET_TXN_PUBLIC_KEY),
             0x36 => Ok(Opcodes::FREEZE_REF),
+            0x37 => Ok(Opcodes::LD_U8),
+            0x38 => Ok(Opcodes::LD_U128),
+            0x39 => Ok(Opcodes::CAST_U8),
+            0x3A => Ok(Opcodes::CAST_U64),
+            0x3B => Ok(Opcodes::CAST_U128),
             _ => Err(VMStatus::new(StatusCode::UNKNOWN_OPCODE)),
         }
     }
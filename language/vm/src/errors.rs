@@ -118,16 +118,61 @@ pub fn bounds_error(kind: IndexKind, idx: usize, len: usize, err: StatusCode) ->
 }
 
 pub fn verification_error(kind: IndexKind, idx: usize, err: StatusCode) -> VMStatus {
-    let msg = format!("at index {} while indexing {}", idx, kind);
-    VMStatus::new(err).with_message(msg)
+    VMStatus::new(err).with_message(index_info(kind, idx))
 }
 
 pub fn append_err_info(status: VMStatus, kind: IndexKind, idx: usize) -> VMStatus {
-    let msg = format!("at index {} while indexing {}", idx, kind);
-    status.append_message_with_separator(' ', msg)
+    status.append_message_with_separator(' ', index_info(kind, idx))
 }
 
 pub fn err_at_offset(status: StatusCode, offset: usize) -> VMStatus {
-    let msg = format!("At offset {}", offset);
-    VMStatus::new(status).with_message(msg)
+    VMStatus::new(status).with_message(offset_info(offset))
+}
+
+const INDEX_INFO_PREFIX: &str = "at index ";
+const INDEX_INFO_SEPARATOR: &str = " while indexing ";
+const OFFSET_INFO_PREFIX: &str = "At offset ";
+
+fn index_info(kind: IndexKind, idx: usize) -> String {
+    format!(
+        "{}{}{}{}",
+        INDEX_INFO_PREFIX, idx, INDEX_INFO_SEPARATOR, kind
+    )
+}
+
+fn offset_info(offset: usize) -> String {
+    format!("{}{}", OFFSET_INFO_PREFIX, offset)
+}
+
+/// The location of an error, as recorded in its message by `verification_error`,
+/// `append_err_info` and `err_at_offset`.
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub struct ErrorInfo {
+    /// The kind of the table indexed when the error occurred.
+    pub kind: IndexKind,
+    /// The index in the table.
+    pub idx: usize,
+    /// The offset of the instruction in the code, for the errors raised while verifying code.
+    pub offset: Option<usize>,
+}
+
+/// Returns the location recorded in the message of `status`, if any. When several indexes are
+/// recorded, the last one, which is the outermost, is returned.
+pub fn err_info(status: &VMStatus) -> Option<ErrorInfo> {
+    let message = status.message.as_ref()?;
+    let index_start = message.rfind(INDEX_INFO_PREFIX)? + INDEX_INFO_PREFIX.len();
+    let mut idx_and_kind = message[index_start..].splitn(2, INDEX_INFO_SEPARATOR);
+    let idx = idx_and_kind.next()?.parse::<usize>().ok()?;
+    let kind_name = idx_and_kind.next()?.trim();
+    let kind = *IndexKind::variants()
+        .iter()
+        .find(|kind| kind.to_string() == kind_name)?;
+    let offset = message.find(OFFSET_INFO_PREFIX).and_then(|start| {
+        message[start + OFFSET_INFO_PREFIX.len()..]
+            .split_whitespace()
+            .next()?
+            .parse::<usize>()
+            .ok()
+    });
+    Some(ErrorInfo { kind, idx, offset })
 }
@@ -82,6 +82,8 @@ pub enum SerializedType {
     STRUCT                  = 0x7,
     BYTEARRAY               = 0x8,
     TYPE_PARAMETER          = 0x9,
+    U8                      = 0xA,
+    U128                    = 0xB,
 }
 
 #[rustfmt::skip]
@@ -172,6 +174,11 @@ pub enum Opcodes {
     GET_TXN_SEQUENCE_NUMBER = 0x34,
     GET_TXN_PUBLIC_KEY      = 0x35,
     FREEZE_REF              = 0x36,
+    LD_U8                   = 0x37,
+    LD_U128                 = 0x38,
+    CAST_U8                 = 0x39,
+    CAST_U64                = 0x3A,
+    CAST_U128               = 0x3B,
 }
 
 /// Upper limit on the binary size
@@ -287,6 +294,11 @@ pub fn write_u64(binary: &mut BinaryData, value: u64) -> Result<()> {
     binary.extend(&value.to_le_bytes())
 }
 
+/// Write a `u128` in Little Endian format.
+pub fn write_u128(binary: &mut BinaryData, value: u128) -> Result<()> {
+    binary.extend(&value.to_le_bytes())
+}
+
 /// Reads a `u16` in ULEB128 format from a `binary`.
 ///
 /// Takes a `&mut Cursor<&[u8]>` and returns a pair:
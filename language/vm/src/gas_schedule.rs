@@ -1,11 +1,13 @@
 // Copyright (c) The Libra Core Contributors
 // SPDX-License-Identifier: Apache-2.0
 
-//! This module lays out the basic abstract costing schedule for bytecode instructions.
+//! This module lays out the basic abstract costing schedule for bytecode instructions and native
+//! functions.
 //!
-//! It is important to note that the cost schedule defined in this file does not track hashing
-//! operations or other native operations; the cost of each native operation will be returned by the
-//! native function itself.
+//! The schedule used by the VM lives on chain in the `GasSchedule.T` resource published under the
+//! association account, so that costs can be changed by a governance transaction instead of a
+//! binary upgrade. The VM reads it at the start of every block. The `INITIAL_GAS_SCHEDULE` defined
+//! in this file is the schedule published at genesis.
 use crate::{
     file_format::{
         AddressPoolIndex, ByteArrayPoolIndex, Bytecode, FieldDefinitionIndex, FunctionHandleIndex,
@@ -13,10 +15,18 @@ use crate::{
     },
     serializer::serialize_instruction,
 };
+use failure::prelude::*;
 use lazy_static::lazy_static;
-use libra_types::transaction::MAX_TRANSACTION_SIZE_IN_BYTES;
+use libra_types::{
+    access_path::{AccessPath, Accesses},
+    account_config,
+    byte_array::ByteArray,
+    identifier::{IdentStr, Identifier},
+    language_storage::StructTag,
+    transaction::MAX_TRANSACTION_SIZE_IN_BYTES,
+};
+use serde::{Deserialize, Serialize};
 use std::{
-    collections::HashMap,
     ops::{Add, Div, Mul, Sub},
     u64,
 };
@@ -105,7 +115,7 @@ macro_rules! define_gas_unit {
         carrier: $carrier: ty,
         doc: $comment: literal
     } => {
-        #[derive(Debug, Hash, Eq, PartialEq, Copy, Clone)]
+        #[derive(Debug, Hash, Eq, PartialEq, Copy, Clone, Serialize, Deserialize)]
         #[doc=$comment]
         pub struct $name<GasCarrier>(GasCarrier);
         impl GasAlgebra<$carrier> for $name<$carrier> {
@@ -191,13 +201,40 @@ lazy_static! {
     pub static ref LARGE_TRANSACTION_CUTOFF: AbstractMemorySize<GasCarrier> = AbstractMemorySize::new(600);
 }
 
-/// The cost tables, keyed by the serialized form of the bytecode instruction.  We use the
-/// serialized form as opposed to the instruction enum itself as the key since this will be the
-/// on-chain representation of bytecode instructions in the future.
-#[derive(Debug)]
+/// The indices of the native functions in the native cost table. The native functions sharing an
+/// implementation (e.g. `Vector.borrow` and `Vector.borrow_mut`) share a cost.
+#[allow(non_camel_case_types)]
+#[derive(Clone, Copy, Debug, Eq, Hash, PartialEq)]
+pub enum NativeCostIndex {
+    SHA2_256 = 0,
+    SHA3_256 = 1,
+    ED25519_VERIFY = 2,
+    ED25519_THRESHOLD_VERIFY = 3,
+    ADDRESS_TO_BYTES = 4,
+    U64_TO_BYTES = 5,
+    BYTEARRAY_CONCAT = 6,
+    LENGTH = 7,
+    EMPTY = 8,
+    BORROW = 9,
+    PUSH_BACK = 10,
+    POP_BACK = 11,
+    DESTROY_EMPTY = 12,
+    SWAP = 13,
+    IS_VALID_GAS_SCHEDULE = 14,
+}
+
+/// The number of native functions with an entry in the native cost table.
+pub const NUMBER_OF_NATIVE_FUNCTIONS: usize = 15;
+
+/// The cost tables, indexed by the serialized form of the bytecode instruction and by
+/// `NativeCostIndex` respectively. We use the serialized form as opposed to the instruction enum
+/// itself as the key since this is the on-chain representation of bytecode instructions.
+///
+/// This is the LCS serialized content of the on-chain gas schedule.
+#[derive(Clone, Debug, Eq, PartialEq, Serialize, Deserialize)]
 pub struct CostTable {
-    pub compute_table: HashMap<InstructionKey, GasUnits<GasCarrier>>,
-    pub memory_table: HashMap<InstructionKey, GasUnits<GasCarrier>>,
+    pub instruction_table: Vec<GasCost>,
+    pub native_table: Vec<GasCost>,
 }
 
 impl InstructionKey {
@@ -208,41 +245,112 @@ impl InstructionKey {
         serialize_instruction(&mut vec, instruction).unwrap();
         Self(vec[0])
     }
+
+    /// The position of the instruction in the instruction table. Opcodes start at 1.
+    fn index(self) -> usize {
+        usize::from(self.0) - 1
+    }
 }
 
 impl CostTable {
-    pub fn new(instrs: Vec<(Bytecode, u64, u64)>) -> Self {
-        let mut compute_table = HashMap::new();
-        let mut memory_table = HashMap::new();
-        let mut instructions_covered = 0;
-        for (instr, comp_cost, mem_cost) in instrs.into_iter() {
-            let code = InstructionKey::new(&instr);
-            if cfg!(debug_assertions) && compute_table.get(&code).is_none() {
-                instructions_covered += 1;
-            }
-            compute_table.insert(code, GasUnits::new(comp_cost));
-            memory_table.insert(code, GasUnits::new(mem_cost));
-        }
+    pub fn new(
+        instrs: Vec<(Bytecode, u64, u64)>,
+        natives: Vec<(NativeCostIndex, u64, u64)>,
+    ) -> Self {
+        let mut instrs: Vec<_> = instrs
+            .into_iter()
+            .map(|(instr, comp_cost, mem_cost)| {
+                (
+                    InstructionKey::new(&instr).index(),
+                    GasCost::new(comp_cost, mem_cost),
+                )
+            })
+            .collect();
+        instrs.sort_by_key(|(index, _)| *index);
         debug_assert!(
-            instructions_covered == NUMBER_OF_BYTECODE_INSTRUCTIONS,
+            instrs
+                .iter()
+                .map(|(index, _)| *index)
+                .eq(0..NUMBER_OF_BYTECODE_INSTRUCTIONS),
             "all instructions must be in the cost table"
         );
+
+        let mut natives: Vec<_> = natives
+            .into_iter()
+            .map(|(native, comp_cost, mem_cost)| {
+                (native as usize, GasCost::new(comp_cost, mem_cost))
+            })
+            .collect();
+        natives.sort_by_key(|(index, _)| *index);
+        debug_assert!(
+            natives
+                .iter()
+                .map(|(index, _)| *index)
+                .eq(0..NUMBER_OF_NATIVE_FUNCTIONS),
+            "all native functions must be in the cost table"
+        );
+
         Self {
-            compute_table,
-            memory_table,
+            instruction_table: instrs.into_iter().map(|(_, cost)| cost).collect(),
+            native_table: natives.into_iter().map(|(_, cost)| cost).collect(),
         }
     }
 
+    /// Deserializes the cost table from the fields of the on-chain `GasSchedule.T` resource.
+    /// Fails unless every instruction and native function is given a cost.
+    pub fn from_schedules(instruction_schedule: &[u8], native_schedule: &[u8]) -> Result<Self> {
+        let cost_table = Self {
+            instruction_table: lcs::from_bytes(instruction_schedule)?,
+            native_table: lcs::from_bytes(native_schedule)?,
+        };
+        ensure!(
+            cost_table.instruction_table.len() == NUMBER_OF_BYTECODE_INSTRUCTIONS,
+            "Gas schedule has {} instruction costs, expected {}",
+            cost_table.instruction_table.len(),
+            NUMBER_OF_BYTECODE_INSTRUCTIONS,
+        );
+        ensure!(
+            cost_table.native_table.len() == NUMBER_OF_NATIVE_FUNCTIONS,
+            "Gas schedule has {} native function costs, expected {}",
+            cost_table.native_table.len(),
+            NUMBER_OF_NATIVE_FUNCTIONS,
+        );
+        Ok(cost_table)
+    }
+
+    /// Deserializes the cost table from the on-chain `GasSchedule.T` resource.
+    pub fn from_resource_bytes(bytes: &[u8]) -> Result<Self> {
+        let resource: GasScheduleResource = lcs::from_bytes(bytes)?;
+        Self::from_schedules(
+            resource.instruction_schedule.as_bytes(),
+            resource.native_schedule.as_bytes(),
+        )
+    }
+
+    /// Serializes the instruction and native tables into the fields of the on-chain
+    /// `GasSchedule.T` resource.
+    pub fn to_schedules(&self) -> Result<(ByteArray, ByteArray)> {
+        Ok((
+            ByteArray::new(lcs::to_bytes(&self.instruction_table)?),
+            ByteArray::new(lcs::to_bytes(&self.native_table)?),
+        ))
+    }
+
+    fn instruction_cost(&self, instr: &Bytecode) -> &GasCost {
+        let index = InstructionKey::new(instr).index();
+        // Cost tables are only created or loaded from chain if every instruction has a cost
+        assume!(index < self.instruction_table.len());
+        &self.instruction_table[index]
+    }
+
     pub fn memory_gas(
         &self,
         instr: &Bytecode,
         size_provider: AbstractMemorySize<GasCarrier>,
     ) -> GasUnits<GasCarrier> {
-        let code = InstructionKey::new(instr);
-        let memory_cost = self.memory_table.get(&code);
-        // CostTable initialization checks that every instruction is included in the memory_table
-        assume!(memory_cost.is_some());
-        memory_cost.unwrap().map2(size_provider, Mul::mul)
+        self.instruction_cost(instr)
+            .memory_gas
+            .map2(size_provider, Mul::mul)
     }
 
     pub fn comp_gas(
@@ -250,17 +358,85 @@ impl CostTable {
         instr: &Bytecode,
         size_provider: AbstractMemorySize<GasCarrier>,
     ) -> GasUnits<GasCarrier> {
-        let code = InstructionKey::new(instr);
-        let compute_cost = self.compute_table.get(&code);
-        // CostTable initialization checks that every instruction is included in the compute_table
-        assume!(compute_cost.is_some());
-        compute_cost.unwrap().map2(size_provider, Mul::mul)
+        self.instruction_cost(instr)
+            .instruction_gas
+            .map2(size_provider, Mul::mul)
     }
+
+    /// Cost a bytecode instruction.
+    ///
+    /// Don't take into account current stack or memory size. Don't track whether references are to
+    /// global or local storage.
+    pub fn instr_cost(
+        &self,
+        instr: &Bytecode,
+        size_provider: AbstractMemorySize<GasCarrier>,
+    ) -> GasCost {
+        GasCost {
+            instruction_gas: self.comp_gas(instr, size_provider),
+            memory_gas: self.memory_gas(instr, size_provider),
+        }
+    }
+
+    /// Cost a call to a native function over an input of size `size_provider`.
+    pub fn native_cost(
+        &self,
+        native: NativeCostIndex,
+        size_provider: AbstractMemorySize<GasCarrier>,
+    ) -> GasUnits<GasCarrier> {
+        let index = native as usize;
+        // Cost tables are only created or loaded from chain if every native function has a cost
+        assume!(index < self.native_table.len());
+        let cost = &self.native_table[index];
+        cost.instruction_gas
+            .add(cost.memory_gas)
+            .map2(size_provider, Mul::mul)
+    }
+}
+
+/// The on-chain representation of the gas schedule: the LCS serialized instruction and native
+/// tables of a `CostTable`.
+#[derive(Debug, Deserialize, Serialize)]
+struct GasScheduleResource {
+    instruction_schedule: ByteArray,
+    native_schedule: ByteArray,
+}
+
+lazy_static! {
+    static ref GAS_SCHEDULE_MODULE_NAME: Identifier = Identifier::new("GasSchedule").unwrap();
+    static ref GAS_SCHEDULE_STRUCT_NAME: Identifier = Identifier::new("T").unwrap();
+}
+
+pub fn gas_schedule_module_name() -> &'static IdentStr {
+    &*GAS_SCHEDULE_MODULE_NAME
+}
+
+pub fn gas_schedule_struct_name() -> &'static IdentStr {
+    &*GAS_SCHEDULE_STRUCT_NAME
+}
+
+pub fn gas_schedule_tag() -> StructTag {
+    StructTag {
+        address: account_config::core_code_address(),
+        module: gas_schedule_module_name().to_owned(),
+        name: gas_schedule_struct_name().to_owned(),
+        type_params: vec![],
+    }
+}
+
+/// The access path of the on-chain gas schedule, published under the association account.
+pub fn gas_schedule_access_path() -> AccessPath {
+    AccessPath::new(
+        account_config::association_address(),
+        AccessPath::resource_access_vec(&gas_schedule_tag(), &Accesses::empty()),
+    )
 }
 
 lazy_static! {
-    static ref GAS_SCHEDULE: CostTable = {
+    /// The gas schedule published at genesis.
+    pub static ref INITIAL_GAS_SCHEDULE: CostTable = {
         use Bytecode::*;
+        use NativeCostIndex::*;
         // Arguments to the instructions don't matter -- these will be removed in the
         // `encode_instruction` function.
         //
@@ -289,7 +465,9 @@ lazy_static! {
             (StLoc(0), 28, 1),
             (Ret, 28, 1),
             (Lt, 49, 1),
+            (LdU8(0), 29, 1),
             (LdConst(0), 29, 1),
+            (LdU128(0), 29, 1),
             (Abort, 39, 1),
             (MutBorrowLoc(0), 45, 1),
             (ImmBorrowLoc(0), 45, 1),
@@ -323,31 +501,48 @@ lazy_static! {
             (LdByteArray(ByteArrayPoolIndex::new(0)), 56, 1),
             (Gt, 46, 1),
             (Pack(StructDefinitionIndex::new(0), NO_TYPE_ACTUALS), 73, 1),
+            (CastU8, 29, 1),
+            (CastU64, 29, 1),
+            (CastU128, 29, 1),
+        ];
+        // The cost of a native function is charged per unit of its input (e.g. per byte hashed).
+        // TODO: The memory cost of native functions has not been determined yet.
+        let natives = vec![
+            (SHA2_256, 30, 0),
+            (SHA3_256, 30, 0),
+            (ED25519_VERIFY, 35, 0),
+            (ED25519_THRESHOLD_VERIFY, 30, 0),
+            (ADDRESS_TO_BYTES, 1, 0),
+            (U64_TO_BYTES, 1, 0),
+            (BYTEARRAY_CONCAT, 1, 0),
+            (LENGTH, 30, 0),
+            (EMPTY, 30, 0),
+            (BORROW, 30, 0),
+            (PUSH_BACK, 30, 0),
+            (POP_BACK, 30, 0),
+            (DESTROY_EMPTY, 30, 0),
+            (SWAP, 30, 0),
+            (IS_VALID_GAS_SCHEDULE, 1, 0),
         ];
-        CostTable::new(instrs)
+        CostTable::new(instrs, natives)
     };
 }
 
 /// The  `GasCost` tracks:
 /// - instruction cost: how much time/computational power is needed to perform the instruction
 /// - memory cost: how much memory is required for the instruction, and storage overhead
-#[derive(Debug)]
+#[derive(Clone, Debug, Eq, PartialEq, Serialize, Deserialize)]
 pub struct GasCost {
     pub instruction_gas: GasUnits<GasCarrier>,
     pub memory_gas: GasUnits<GasCarrier>,
 }
 
-/// Statically cost a bytecode instruction.
-///
-/// Don't take into account current stack or memory size. Don't track whether references are to
-/// global or local storage.
-pub fn static_cost_instr(
-    instr: &Bytecode,
-    size_provider: AbstractMemorySize<GasCarrier>,
-) -> GasCost {
-    GasCost {
-        instruction_gas: GAS_SCHEDULE.comp_gas(instr, size_provider),
-        memory_gas: GAS_SCHEDULE.memory_gas(instr, size_provider),
+impl GasCost {
+    pub fn new(instruction_gas: GasCarrier, memory_gas: GasCarrier) -> Self {
+        Self {
+            instruction_gas: GasUnits::new(instruction_gas),
+            memory_gas: GasUnits::new(memory_gas),
+        }
     }
 }
 
@@ -69,6 +69,7 @@ use std::{collections::VecDeque, fmt};
 // This is designed mainly for the printer -- public APIs should be based on the accessors in
 // `access.rs`.
 pub trait TableAccess {
+    fn get_struct_def_at(&self, idx: StructDefinitionIndex) -> Result<&StructDefinition>;
     fn get_field_def_at(&self, idx: FieldDefinitionIndex) -> Result<&FieldDefinition>;
 
     fn get_module_at(&self, idx: ModuleHandleIndex) -> Result<&ModuleHandle>;
@@ -77,6 +78,7 @@ pub trait TableAccess {
 
     fn get_identifier_at(&self, idx: IdentifierIndex) -> Result<&IdentStr>;
     fn get_user_string_at(&self, idx: UserStringIndex) -> Result<&VMStr>;
+    fn get_byte_array_at(&self, idx: ByteArrayPoolIndex) -> Result<&ByteArray>;
     fn get_address_at(&self, idx: AddressPoolIndex) -> Result<&AccountAddress>;
     fn get_type_signature_at(&self, idx: TypeSignatureIndex) -> Result<&TypeSignature>;
     fn get_function_signature_at(&self, idx: FunctionSignatureIndex) -> Result<&FunctionSignature>;
@@ -84,6 +86,10 @@ pub trait TableAccess {
 }
 
 impl TableAccess for CompiledScriptMut {
+    fn get_struct_def_at(&self, _idx: StructDefinitionIndex) -> Result<&StructDefinition> {
+        bail!("no struct definitions in scripts");
+    }
+
     fn get_field_def_at(&self, _idx: FieldDefinitionIndex) -> Result<&FieldDefinition> {
         bail!("no field definitions in scripts");
     }
@@ -120,6 +126,12 @@ impl TableAccess for CompiledScriptMut {
             .ok_or_else(|| format_err!("bad user string index {}", idx))
     }
 
+    fn get_byte_array_at(&self, idx: ByteArrayPoolIndex) -> Result<&ByteArray> {
+        self.byte_array_pool
+            .get(idx.0 as usize)
+            .ok_or_else(|| format_err!("bad byte array index {}", idx))
+    }
+
     fn get_address_at(&self, idx: AddressPoolIndex) -> Result<&AccountAddress> {
         self.address_pool
             .get(idx.0 as usize)
@@ -146,6 +158,12 @@ impl TableAccess for CompiledScriptMut {
 }
 
 impl TableAccess for CompiledModuleMut {
+    fn get_struct_def_at(&self, idx: StructDefinitionIndex) -> Result<&StructDefinition> {
+        self.struct_defs
+            .get(idx.0 as usize)
+            .ok_or_else(|| format_err!("bad struct definition index {}", idx))
+    }
+
     fn get_field_def_at(&self, idx: FieldDefinitionIndex) -> Result<&FieldDefinition> {
         self.field_defs
             .get(idx.0 as usize)
@@ -184,6 +202,12 @@ impl TableAccess for CompiledModuleMut {
             .ok_or_else(|| format_err!("bad user string index {}", idx))
     }
 
+    fn get_byte_array_at(&self, idx: ByteArrayPoolIndex) -> Result<&ByteArray> {
+        self.byte_array_pool
+            .get(idx.0 as usize)
+            .ok_or_else(|| format_err!("bad byte array index {}", idx))
+    }
+
     fn get_address_at(&self, idx: AddressPoolIndex) -> Result<&AccountAddress> {
         self.address_pool
             .get(idx.0 as usize)
@@ -401,6 +425,50 @@ impl fmt::Display for CompiledModule {
     }
 }
 
+/// Displays the function definitions of `module`, along with the resources they acquire, their
+/// locals and their code. All the table indexes are resolved, so the modules compiled from the same
+/// code display the same whatever the order of their tables.
+pub fn display_function_definitions(module: &CompiledModule) -> Vec<String> {
+    let inner = module.as_inner();
+    inner
+        .function_defs
+        .iter()
+        .map(|function_def| FunctionDefinitionDisplay(function_def, inner).to_string())
+        .collect()
+}
+
+struct FunctionDefinitionDisplay<'a>(&'a FunctionDefinition, &'a CompiledModuleMut);
+
+impl<'a> fmt::Display for FunctionDefinitionDisplay<'a> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let FunctionDefinitionDisplay(function_def, tables) = self;
+        display_function_definition(function_def, *tables, f)?;
+        write!(f, " acquires [")?;
+        for (i, idx) in function_def.acquires_global_resources.iter().enumerate() {
+            if i > 0 {
+                write!(f, ", ")?;
+            }
+            display_struct_definition(tables.get_struct_def_at(*idx).unwrap(), *tables, f)?;
+        }
+        write!(f, "]")?;
+        if function_def.flags & CodeUnit::NATIVE == 0 {
+            write!(f, "\n\tlocals: ")?;
+            display_locals_signature(
+                tables
+                    .get_locals_signature_at(function_def.code.locals)
+                    .unwrap(),
+                *tables,
+                f,
+            )?;
+            for bytecode in &function_def.code.code {
+                write!(f, "\n\t")?;
+                display_bytecode(bytecode, *tables, f)?;
+            }
+        }
+        Ok(())
+    }
+}
+
 fn display_struct_handle<T: TableAccess>(
     struct_: &StructHandle,
     tables: &T,
@@ -594,7 +662,9 @@ fn display_signature_token<T: TableAccess>(
 ) -> fmt::Result {
     match token {
         SignatureToken::Bool => write!(f, "Bool"),
+        SignatureToken::U8 => write!(f, "U8"),
         SignatureToken::U64 => write!(f, "Integer"),
+        SignatureToken::U128 => write!(f, "U128"),
         SignatureToken::String => write!(f, "String"),
         SignatureToken::ByteArray => write!(f, "ByteArray"),
         SignatureToken::Address => write!(f, "Address"),
@@ -636,6 +706,11 @@ fn display_bytecode<T: TableAccess>(
             write!(f, ")")
         }
         Bytecode::LdStr(idx) => write!(f, "LdStr({})", tables.get_user_string_at(*idx).unwrap()),
+        Bytecode::LdByteArray(idx) => {
+            write!(f, "LdByteArray(")?;
+            display_byte_array(tables.get_byte_array_at(*idx).unwrap(), f)?;
+            write!(f, ")")
+        }
         Bytecode::MutBorrowField(idx) => {
             write!(f, "MutBorrowField(")?;
             display_field_definition(tables.get_field_def_at(*idx).unwrap(), tables, f)?;
@@ -657,6 +732,45 @@ fn display_bytecode<T: TableAccess>(
             display_function_handle(tables.get_function_at(*idx).unwrap(), tables, f)?;
             write!(f, ")")
         }
+        Bytecode::Pack(idx, types_idx) => {
+            display_struct_bytecode("Pack", *idx, *types_idx, tables, f)
+        }
+        Bytecode::Unpack(idx, types_idx) => {
+            display_struct_bytecode("Unpack", *idx, *types_idx, tables, f)
+        }
+        Bytecode::MutBorrowGlobal(idx, types_idx) => {
+            display_struct_bytecode("MutBorrowGlobal", *idx, *types_idx, tables, f)
+        }
+        Bytecode::ImmBorrowGlobal(idx, types_idx) => {
+            display_struct_bytecode("ImmBorrowGlobal", *idx, *types_idx, tables, f)
+        }
+        Bytecode::Exists(idx, types_idx) => {
+            display_struct_bytecode("Exists", *idx, *types_idx, tables, f)
+        }
+        Bytecode::MoveFrom(idx, types_idx) => {
+            display_struct_bytecode("MoveFrom", *idx, *types_idx, tables, f)
+        }
+        Bytecode::MoveToSender(idx, types_idx) => {
+            display_struct_bytecode("MoveToSender", *idx, *types_idx, tables, f)
+        }
         _ => write!(f, "{:?}", bytecode),
     }
 }
+
+fn display_struct_bytecode<T: TableAccess>(
+    name: &str,
+    idx: StructDefinitionIndex,
+    types_idx: LocalsSignatureIndex,
+    tables: &T,
+    f: &mut fmt::Formatter,
+) -> fmt::Result {
+    write!(f, "{}", name)?;
+    display_type_actuals(
+        &tables.get_locals_signature_at(types_idx).unwrap().0,
+        tables,
+        f,
+    )?;
+    write!(f, "(")?;
+    display_struct_definition(tables.get_struct_def_at(idx).unwrap(), tables, f)?;
+    write!(f, ")")
+}
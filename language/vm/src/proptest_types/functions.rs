@@ -398,7 +398,9 @@ impl BytecodeGen {
         prop_oneof![
             // The numbers are relative weights, somewhat arbitrarily picked.
             9 => Self::just_bytecode_strategy(),
+            1 => any::<u8>().prop_map(Bytecode::LdU8),
             1 => any::<u64>().prop_map(Bytecode::LdConst),
+            1 => any::<u128>().prop_map(Bytecode::LdU128),
         ]
     }
 
@@ -413,6 +415,9 @@ impl BytecodeGen {
             LdFalse,
             ReadRef,
             WriteRef,
+            CastU8,
+            CastU64,
+            CastU128,
             Add,
             Sub,
             Mul,
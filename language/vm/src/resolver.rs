@@ -61,7 +61,9 @@ impl Resolver {
     ) -> Result<SignatureToken, VMStatus> {
         match sig_token {
             SignatureToken::Bool
+            | SignatureToken::U8
             | SignatureToken::U64
+            | SignatureToken::U128
             | SignatureToken::String
             | SignatureToken::ByteArray
             | SignatureToken::Address
@@ -0,0 +1,61 @@
+// Copyright (c) The Libra Core Contributors
+// SPDX-License-Identifier: Apache-2.0
+
+use crate::{
+    errors::{append_err_info, err_at_offset, err_info, verification_error, ErrorInfo},
+    IndexKind,
+};
+use libra_types::vm_error::{StatusCode, VMStatus};
+
+#[test]
+fn err_info_of_verification_error() {
+    let status = verification_error(
+        IndexKind::StructDefinition,
+        2,
+        StatusCode::RECURSIVE_STRUCT_DEFINITION,
+    );
+    assert_eq!(
+        status.message.as_ref().unwrap(),
+        "at index 2 while indexing struct definition"
+    );
+    assert_eq!(
+        err_info(&status),
+        Some(ErrorInfo {
+            kind: IndexKind::StructDefinition,
+            idx: 2,
+            offset: None,
+        })
+    );
+}
+
+#[test]
+fn err_info_of_code_error() {
+    let status = append_err_info(
+        err_at_offset(StatusCode::NEGATIVE_STACK_SIZE_WITHIN_BLOCK, 3),
+        IndexKind::FunctionDefinition,
+        1,
+    );
+    assert_eq!(
+        status.message.as_ref().unwrap(),
+        "At offset 3 at index 1 while indexing function definition"
+    );
+    assert_eq!(
+        err_info(&status),
+        Some(ErrorInfo {
+            kind: IndexKind::FunctionDefinition,
+            idx: 1,
+            offset: Some(3),
+        })
+    );
+}
+
+#[test]
+fn err_info_of_unlocated_error() {
+    assert_eq!(
+        err_info(&VMStatus::new(StatusCode::UNKNOWN_VERIFICATION_ERROR)),
+        None
+    );
+    let status = VMStatus::new(StatusCode::UNKNOWN_VERIFICATION_ERROR)
+        .with_message("At offset 3".to_string());
+    assert_eq!(err_info(&status), None);
+}
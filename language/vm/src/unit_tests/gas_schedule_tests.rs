@@ -0,0 +1,64 @@
+// Copyright (c) The Libra Core Contributors
+// SPDX-License-Identifier: Apache-2.0
+
+use crate::{
+    file_format::Bytecode,
+    gas_schedule::{
+        AbstractMemorySize, CostTable, GasAlgebra, GasCost, NativeCostIndex, INITIAL_GAS_SCHEDULE,
+    },
+};
+use libra_types::byte_array::ByteArray;
+
+#[test]
+fn schedules_roundtrip() {
+    let (instruction_schedule, native_schedule) = INITIAL_GAS_SCHEDULE.to_schedules().unwrap();
+    let cost_table =
+        CostTable::from_schedules(instruction_schedule.as_bytes(), native_schedule.as_bytes())
+            .unwrap();
+    assert_eq!(cost_table, *INITIAL_GAS_SCHEDULE);
+
+    let resource = lcs::to_bytes(&(instruction_schedule, native_schedule)).unwrap();
+    assert_eq!(
+        CostTable::from_resource_bytes(&resource).unwrap(),
+        *INITIAL_GAS_SCHEDULE
+    );
+}
+
+#[test]
+fn incomplete_schedules_rejected() {
+    let mut cost_table = INITIAL_GAS_SCHEDULE.clone();
+    cost_table.instruction_table.pop();
+    let (instruction_schedule, native_schedule) = cost_table.to_schedules().unwrap();
+    assert!(
+        CostTable::from_schedules(instruction_schedule.as_bytes(), native_schedule.as_bytes())
+            .is_err()
+    );
+
+    let mut cost_table = INITIAL_GAS_SCHEDULE.clone();
+    cost_table.native_table.push(GasCost::new(1, 1));
+    let (instruction_schedule, native_schedule) = cost_table.to_schedules().unwrap();
+    assert!(
+        CostTable::from_schedules(instruction_schedule.as_bytes(), native_schedule.as_bytes())
+            .is_err()
+    );
+
+    let garbage = ByteArray::new(vec![0xff; 3]);
+    assert!(CostTable::from_schedules(garbage.as_bytes(), garbage.as_bytes()).is_err());
+}
+
+#[test]
+fn costs_read_from_table() {
+    let mut cost_table = INITIAL_GAS_SCHEDULE.clone();
+    // Opcodes start at 1: Pop is the first instruction of the table.
+    cost_table.instruction_table[0] = GasCost::new(7, 3);
+    cost_table.native_table[NativeCostIndex::SHA3_256 as usize] = GasCost::new(5, 2);
+    let size = AbstractMemorySize::new(4);
+
+    let cost = cost_table.instr_cost(&Bytecode::Pop, size);
+    assert_eq!(cost.instruction_gas.get(), 7 * 4);
+    assert_eq!(cost.memory_gas.get(), 3 * 4);
+    assert_eq!(
+        cost_table.native_cost(NativeCostIndex::SHA3_256, size).get(),
+        (5 + 2) * 4
+    );
+}
@@ -17,7 +17,10 @@ use rand::{rngs::StdRng, SeedableRng};
 use state_view::StateView;
 use std::time::Duration;
 use stdlib::stdlib_modules;
-use vm::{access::ModuleAccess, transaction_metadata::TransactionMetadata};
+use vm::{
+    access::ModuleAccess, gas_schedule::INITIAL_GAS_SCHEDULE,
+    transaction_metadata::TransactionMetadata,
+};
 use vm_cache_map::Arena;
 use vm_runtime::{
     code_cache::{
@@ -26,7 +29,7 @@ use vm_runtime::{
     },
     data_cache::BlockDataCache,
     txn_executor::{
-        TransactionExecutor, ACCOUNT_MODULE, BLOCK_MODULE, COIN_MODULE,
+        TransactionExecutor, ACCOUNT_MODULE, BLOCK_MODULE, COIN_MODULE, GAS_SCHEDULE_MODULE,
         TRANSACTION_FEE_DISTRIBUTION_MODULE, VALIDATOR_CONFIG_MODULE, VALIDATOR_SET_MODULE,
     },
 };
@@ -212,7 +215,12 @@ pub fn encode_genesis_transaction_with_validator(
             let mut txn_data = TransactionMetadata::default();
             txn_data.sender = genesis_addr;
 
-            let mut txn_executor = TransactionExecutor::new(&block_cache, &data_cache, txn_data);
+            let mut txn_executor = TransactionExecutor::new(
+                &block_cache,
+                &data_cache,
+                txn_data,
+                &INITIAL_GAS_SCHEDULE,
+            );
             txn_executor.create_account(genesis_addr).unwrap();
             txn_executor
                 .create_account(account_config::transaction_fee_address())
@@ -227,6 +235,20 @@ pub fn encode_genesis_transaction_with_validator(
                 .execute_function(&COIN_MODULE, &INITIALIZE, vec![])
                 .unwrap();
 
+            // Publish the initial gas schedule under the Association account.
+            let (instruction_schedule, native_schedule) =
+                INITIAL_GAS_SCHEDULE.to_schedules().unwrap();
+            txn_executor
+                .execute_function(
+                    &GAS_SCHEDULE_MODULE,
+                    &INITIALIZE,
+                    vec![
+                        Value::byte_array(instruction_schedule),
+                        Value::byte_array(native_schedule),
+                    ],
+                )
+                .unwrap();
+
             txn_executor
                 .execute_function(
                     &ACCOUNT_MODULE,
@@ -8,7 +8,10 @@ use crate::{
         script_cache::ScriptCache,
     },
     counters::*,
-    data_cache::BlockDataCache,
+    data_cache::{BlockDataCache, RemoteCache},
+    debugger::Debugger,
+    gas_meter::load_gas_schedule,
+    gas_profiler::GasProfile,
     process_txn::{execute::ExecutedTransaction, validate::ValidationMode, ProcessTransaction},
 };
 use config::config::VMPublishingOption;
@@ -22,6 +25,7 @@ use libra_types::{
 use logger::prelude::*;
 use rayon::prelude::*;
 use state_view::StateView;
+use vm::gas_schedule::CostTable;
 use vm_cache_map::Arena;
 
 pub fn execute_block<'alloc>(
@@ -56,6 +60,16 @@ pub fn execute_block<'alloc>(
         ValidationMode::Executing
     };
 
+    // Updates of the gas schedule within the block only take effect from the next block.
+    let gas_schedule = match load_gas_schedule(data_view) {
+        Ok(gas_schedule) => gas_schedule,
+        Err(vm_status) => {
+            return txn_block
+                .iter()
+                .map(|_| ExecutedTransaction::discard_error_output(vm_status.clone()))
+                .collect();
+        }
+    };
     let module_cache = BlockModuleCache::new(code_cache, ModuleFetcherImpl::new(data_view));
     let mut data_cache = BlockDataCache::new(data_view);
     let mut result = vec![];
@@ -76,6 +90,7 @@ pub fn execute_block<'alloc>(
                         &module_cache,
                         script_cache,
                         &data_cache,
+                        &gas_schedule,
                         mode,
                         publishing_option,
                     ),
@@ -96,6 +111,92 @@ pub fn execute_block<'alloc>(
     result
 }
 
+pub fn simulate_transaction<'alloc>(
+    txn: SignedTransaction,
+    skip_signature_check: bool,
+    code_cache: &VMModuleCache<'alloc>,
+    script_cache: &ScriptCache<'alloc>,
+    data_view: &dyn StateView,
+    publishing_option: &VMPublishingOption,
+) -> TransactionOutput {
+    trace!("[VM] Simulate transaction: {:?}", txn);
+    let txn = if skip_signature_check {
+        txn.into_unchecked_for_simulation()
+    } else {
+        match txn.check_signature() {
+            Ok(txn) => txn,
+            Err(_) => {
+                return ExecutedTransaction::discard_error_output(VMStatus::new(
+                    StatusCode::INVALID_SIGNATURE,
+                ));
+            }
+        }
+    };
+
+    let module_cache = BlockModuleCache::new(code_cache, ModuleFetcherImpl::new(data_view));
+    let data_cache = BlockDataCache::new(data_view);
+    let gas_schedule = match load_gas_schedule(data_view) {
+        Ok(gas_schedule) => gas_schedule,
+        Err(vm_status) => return ExecutedTransaction::discard_error_output(vm_status),
+    };
+    transaction_flow(
+        txn,
+        &module_cache,
+        script_cache,
+        &data_cache,
+        &gas_schedule,
+        ValidationMode::Executing,
+        publishing_option,
+    )
+}
+
+/// Executes a single transaction like `simulate_transaction`, with `debugger` attached to its
+/// execution and its gas recorded in `gas_profile` when given. The signature of the transaction
+/// is checked.
+pub fn execute_instrumented_transaction<'alloc>(
+    txn: SignedTransaction,
+    code_cache: &VMModuleCache<'alloc>,
+    script_cache: &ScriptCache<'alloc>,
+    data_view: &dyn StateView,
+    publishing_option: &VMPublishingOption,
+    debugger: Option<&mut dyn Debugger>,
+    gas_profile: Option<&mut GasProfile>,
+) -> TransactionOutput {
+    trace!("[VM] Execute instrumented transaction: {:?}", txn);
+    let txn = match txn.check_signature() {
+        Ok(txn) => txn,
+        Err(_) => {
+            return ExecutedTransaction::discard_error_output(VMStatus::new(
+                StatusCode::INVALID_SIGNATURE,
+            ));
+        }
+    };
+
+    let module_cache = BlockModuleCache::new(code_cache, ModuleFetcherImpl::new(data_view));
+    let data_cache = BlockDataCache::new(data_view);
+    let gas_schedule = match load_gas_schedule(data_view) {
+        Ok(gas_schedule) => gas_schedule,
+        Err(vm_status) => return ExecutedTransaction::discard_error_output(vm_status),
+    };
+    let arena = Arena::new();
+    let mut process_txn =
+        ProcessTransaction::new(txn, &module_cache, &data_cache, &gas_schedule, &arena);
+    if let Some(debugger) = debugger {
+        process_txn = process_txn.with_debugger(debugger);
+    }
+    if let Some(gas_profile) = gas_profile {
+        process_txn = process_txn.with_gas_profile(gas_profile);
+    }
+    let validated_txn = match process_txn.validate(ValidationMode::Executing, publishing_option) {
+        Ok(validated_txn) => validated_txn,
+        Err(vm_status) => return ExecutedTransaction::discard_error_output(vm_status),
+    };
+    match validated_txn.verify(script_cache) {
+        Ok(verified_txn) => verified_txn.execute().into_output(),
+        Err(vm_status) => ExecutedTransaction::discard_error_output(vm_status),
+    }
+}
+
 /// Process a transaction and emit a TransactionOutput.
 ///
 /// A successful execution will have `TransactionStatus::Keep` in the TransactionOutput and a
@@ -109,11 +210,12 @@ pub fn execute_block<'alloc>(
 /// and this transaction is executed successfully, this function will update `module_cache` to
 /// include those newly published modules. This function will also update the `script_cache` to
 /// cache this `txn`
-fn transaction_flow<'alloc, P>(
+pub(crate) fn transaction_flow<'alloc, P>(
     txn: SignatureCheckedTransaction,
     module_cache: P,
     script_cache: &ScriptCache<'alloc>,
-    data_cache: &BlockDataCache<'_>,
+    data_cache: &dyn RemoteCache,
+    gas_schedule: &CostTable,
     mode: ValidationMode,
     publishing_option: &VMPublishingOption,
 ) -> TransactionOutput
@@ -121,7 +223,8 @@ where
     P: ModuleCache<'alloc>,
 {
     let arena = Arena::new();
-    let process_txn = ProcessTransaction::new(txn, &module_cache, data_cache, &arena);
+    let process_txn =
+        ProcessTransaction::new(txn, &module_cache, data_cache, gas_schedule, &arena);
 
     let validated_txn = record_stats! {time_hist | TXN_VALIDATION_TIME_TAKEN | {
     match process_txn.validate(mode, publishing_option) {
@@ -80,7 +80,8 @@ pub trait ModuleCache<'alloc> {
     fn cache_module(&self, module: VerifiedModule);
 
     /// Recache the list of previously resolved modules. Think of the cache as a generational
-    /// cache and we need to move modules across generations.
+    /// cache and we need to move modules across generations. A recached module replaces the
+    /// module with the same name, if any.
     fn reclaim_cached_module(&self, v: Vec<LoadedModule>);
 }
 
@@ -251,7 +252,9 @@ impl<'alloc> VMModuleCache<'alloc> {
     ) -> VMResult<Option<Type>> {
         match tok {
             SignatureToken::Bool => Ok(Some(Type::Bool)),
+            SignatureToken::U8 => Ok(Some(Type::U8)),
             SignatureToken::U64 => Ok(Some(Type::U64)),
+            SignatureToken::U128 => Ok(Some(Type::U128)),
             SignatureToken::String => Ok(Some(Type::String)),
             SignatureToken::ByteArray => Ok(Some(Type::ByteArray)),
             SignatureToken::Address => Ok(Some(Type::Address)),
@@ -404,9 +407,10 @@ impl<'alloc> ModuleCache<'alloc> for VMModuleCache<'alloc> {
     }
 
     fn reclaim_cached_module(&self, v: Vec<LoadedModule>) {
+        // A reclaimed module replaces the cached module with the same name, which it upgrades.
         for m in v.into_iter() {
             let module_id = m.self_id();
-            self.map.or_insert(module_id, m);
+            self.map.insert(module_id, m);
         }
     }
 }
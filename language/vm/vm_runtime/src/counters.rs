@@ -16,10 +16,12 @@ const TXN_EXECUTION_DISCARD: &str = "txn.execution.discard";
 const TXN_VERIFICATION_SUCCESS: &str = "txn.verification.success";
 const TXN_VERIFICATION_FAIL: &str = "txn.verification.fail";
 const TXN_BLOCK_COUNT: &str = "txn.block.count";
+const TXN_REEXECUTION: &str = "txn.reexecution";
 pub const TXN_TOTAL_TIME_TAKEN: &str = "txn_gas_total_time_taken";
 pub const TXN_VERIFICATION_TIME_TAKEN: &str = "txn_gas_verification_time_taken";
 pub const TXN_VALIDATION_TIME_TAKEN: &str = "txn_gas_validation_time_taken";
 pub const TXN_EXECUTION_TIME_TAKEN: &str = "txn_gas_execution_time_taken";
+pub const TXN_SPECULATIVE_EXECUTION_TIME_TAKEN: &str = "txn_gas_speculative_execution_time_taken";
 pub const TXN_PROLOGUE_TIME_TAKEN: &str = "txn_gas_prologue_time_taken";
 pub const TXN_EPILOGUE_TIME_TAKEN: &str = "txn_gas_epilogue_time_taken";
 pub const TXN_EXECUTION_GAS_USAGE: &str = "txn_gas_execution_gas_usage";
@@ -31,6 +33,7 @@ lazy_static::lazy_static! {
 
     static ref VERIFIED_TRANSACTION: IntCounter = VM_COUNTERS.counter(TXN_VERIFICATION_SUCCESS);
     static ref BLOCK_TRANSACTION_COUNT: IntGauge = VM_COUNTERS.gauge(TXN_BLOCK_COUNT);
+    static ref REEXECUTED_TRANSACTION: IntCounter = VM_COUNTERS.counter(TXN_REEXECUTION);
 }
 
 /// Wrapper around time::Instant.
@@ -46,6 +49,12 @@ pub fn report_block_count(count: usize) {
     }
 }
 
+/// Reports a transaction executed again because its speculative execution was invalidated by a
+/// preceding transaction of the block.
+pub fn report_reexecution() {
+    REEXECUTED_TRANSACTION.inc()
+}
+
 // All statistics gather operations for the time taken/gas usage should go through this macro. This
 // gives us the ability to turn these metrics on and off easily from one place.
 #[macro_export]
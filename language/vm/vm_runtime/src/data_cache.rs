@@ -269,6 +269,12 @@ impl<'txn> TransactionDataCache<'txn> {
             .map_err(|_| vm_error(Location::new(), StatusCode::DATA_FORMAT_ERROR))
     }
 
+    /// Returns the global resources loaded by the transaction so far, along with their access
+    /// paths.
+    pub fn resources(&self) -> impl Iterator<Item = (&AccessPath, &GlobalRef)> {
+        self.data_map.iter()
+    }
+
     /// Flush out the cache and restart from a clean state
     pub fn clear(&mut self) {
         self.data_map.clear()
@@ -0,0 +1,224 @@
+// Copyright (c) The Libra Core Contributors
+// SPDX-License-Identifier: Apache-2.0
+
+//! Hooks to debug the execution of the bytecode of a transaction.
+//!
+//! A [`Debugger`] attached to the `TransactionExecutor` of a transaction is called before every
+//! instruction with a [`DebugContext`], through which it can inspect the call stack, the locals of
+//! every frame, the value stack and the global resources loaded by the transaction so far.
+//! [`BreakpointDebugger`] stops at breakpoints or after every instruction when stepping, and hands
+//! the context over to a front end such as the `move-debugger` tool.
+
+use crate::{
+    data_cache::TransactionDataCache,
+    frame::Frame,
+    loaded_data::{
+        function::{FunctionRef, FunctionReference},
+        loaded_module::LoadedModule,
+    },
+};
+use libra_types::{
+    access_path::AccessPath,
+    identifier::{IdentStr, Identifier},
+};
+use vm::{
+    access::ModuleAccess,
+    file_format::{Bytecode, CodeOffset},
+};
+use vm_runtime_types::value::{GlobalRef, Locals, Value};
+
+/// A hook called by the interpreter before the execution of every instruction.
+pub trait Debugger {
+    fn on_instruction(&mut self, context: &DebugContext);
+}
+
+/// The state of the interpreter before the execution of an instruction.
+pub struct DebugContext<'a, 'txn> {
+    pc: CodeOffset,
+    instruction: &'a Bytecode,
+    call_stack: &'a [Frame<'txn, FunctionRef<'txn>>],
+    value_stack: &'a [Value],
+    data_view: &'a TransactionDataCache<'txn>,
+}
+
+/// A frame of the call stack.
+pub struct StackFrame<'a> {
+    /// The module defining the function.
+    pub module: &'a LoadedModule,
+    /// The name of the function.
+    pub function: &'a IdentStr,
+    /// The offset of the instruction about to be executed for the innermost frame, of the call
+    /// in progress for the other ones.
+    pub pc: CodeOffset,
+    pub locals: &'a Locals,
+}
+
+impl<'a, 'txn> DebugContext<'a, 'txn> {
+    pub(crate) fn new(
+        pc: CodeOffset,
+        instruction: &'a Bytecode,
+        call_stack: &'a [Frame<'txn, FunctionRef<'txn>>],
+        value_stack: &'a [Value],
+        data_view: &'a TransactionDataCache<'txn>,
+    ) -> Self {
+        Self {
+            pc,
+            instruction,
+            call_stack,
+            value_stack,
+            data_view,
+        }
+    }
+
+    /// Returns the offset of the instruction about to be executed.
+    pub fn pc(&self) -> CodeOffset {
+        self.pc
+    }
+
+    /// Returns the instruction about to be executed.
+    pub fn instruction(&self) -> &Bytecode {
+        self.instruction
+    }
+
+    /// Returns the frame of the function being executed.
+    pub fn current_frame(&self) -> Option<StackFrame> {
+        self.call_stack
+            .len()
+            .checked_sub(1)
+            .map(|idx| self.stack_frame(idx))
+    }
+
+    /// Returns the frames of the call stack, from the outermost call to the current one.
+    pub fn call_stack(&self) -> Vec<StackFrame> {
+        (0..self.call_stack.len())
+            .map(|idx| self.stack_frame(idx))
+            .collect()
+    }
+
+    /// Returns the value stack, with the top of the stack last.
+    pub fn value_stack(&self) -> &[Value] {
+        self.value_stack
+    }
+
+    /// Returns the global resources loaded by the transaction so far.
+    pub fn resources(&self) -> impl Iterator<Item = (&AccessPath, &GlobalRef)> {
+        self.data_view.resources()
+    }
+
+    fn stack_frame(&self, idx: usize) -> StackFrame {
+        let frame = &self.call_stack[idx];
+        // The pc of a frame is only saved when it calls into another function.
+        let pc = if idx + 1 == self.call_stack.len() {
+            self.pc
+        } else {
+            frame.get_pc()
+        };
+        StackFrame {
+            module: frame.module(),
+            function: frame.function().name(),
+            pc,
+            locals: frame.get_locals(),
+        }
+    }
+}
+
+/// A place to stop the execution at. A breakpoint without a module matches the functions of that
+/// name in any module.
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub enum Breakpoint {
+    /// Before the first instruction of every call to a function.
+    FunctionEntry {
+        module: Option<Identifier>,
+        function: Identifier,
+    },
+    /// Before the instruction at `offset` in a function.
+    CodeOffset {
+        module: Option<Identifier>,
+        function: Identifier,
+        offset: CodeOffset,
+    },
+}
+
+impl Breakpoint {
+    /// Whether the execution stops at this breakpoint before the instruction of `frame`.
+    /// `at_entry` is whether that instruction is the first one of a call.
+    fn matches(&self, frame: &StackFrame, at_entry: bool) -> bool {
+        let matches_function = |module: &Option<Identifier>, function: &Identifier| {
+            frame.function == function.as_ident_str()
+                && module
+                    .as_ref()
+                    .map_or(true, |module| frame.module.name() == module.as_ident_str())
+        };
+        match self {
+            Breakpoint::FunctionEntry { module, function } => {
+                at_entry && matches_function(module, function)
+            }
+            Breakpoint::CodeOffset {
+                module,
+                function,
+                offset,
+            } => frame.pc == *offset && matches_function(module, function),
+        }
+    }
+}
+
+/// How the execution resumes after it stopped.
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum DebugCommand {
+    /// Stop again before the next instruction.
+    Step,
+    /// Run until the next breakpoint.
+    Continue,
+}
+
+/// A [`Debugger`] stopping before the instructions hitting one of its breakpoints, and before
+/// every instruction when stepping. `on_break` is called every time the execution stops, with the
+/// state of the interpreter and the breakpoints, which it can edit, and returns how to resume.
+pub struct BreakpointDebugger<F> {
+    breakpoints: Vec<Breakpoint>,
+    on_break: F,
+    stepping: bool,
+    // Whether the previous instruction was a branch, in which case an instruction at offset 0 is
+    // the target of a loop rather than the entry of a function.
+    after_branch: bool,
+}
+
+impl<F> BreakpointDebugger<F>
+where
+    F: FnMut(&DebugContext, &mut Vec<Breakpoint>) -> DebugCommand,
+{
+    pub fn new(breakpoints: Vec<Breakpoint>, on_break: F) -> Self {
+        Self {
+            breakpoints,
+            on_break,
+            stepping: false,
+            after_branch: false,
+        }
+    }
+
+    /// Returns the breakpoints, including the ones `on_break` added.
+    pub fn breakpoints(&self) -> &[Breakpoint] {
+        &self.breakpoints
+    }
+}
+
+impl<F> Debugger for BreakpointDebugger<F>
+where
+    F: FnMut(&DebugContext, &mut Vec<Breakpoint>) -> DebugCommand,
+{
+    fn on_instruction(&mut self, context: &DebugContext) {
+        let at_entry = context.pc() == 0 && !self.after_branch;
+        self.after_branch = match context.instruction() {
+            Bytecode::Branch(_) | Bytecode::BrTrue(_) | Bytecode::BrFalse(_) => true,
+            _ => false,
+        };
+        let hits_breakpoint = context.current_frame().map_or(false, |frame| {
+            self.breakpoints
+                .iter()
+                .any(|breakpoint| breakpoint.matches(&frame, at_entry))
+        });
+        if self.stepping || hits_breakpoint {
+            self.stepping = (self.on_break)(context, &mut self.breakpoints) == DebugCommand::Step;
+        }
+    }
+}
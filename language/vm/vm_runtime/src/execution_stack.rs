@@ -167,6 +167,11 @@ where
         &self.stack
     }
 
+    /// Returns the frames of the call stack, from the outermost call to the current one.
+    pub fn get_call_stack(&self) -> &[Frame<'txn, FunctionRef<'txn>>] {
+        &self.function_stack
+    }
+
     pub fn push_frame(&mut self, func: FunctionRef<'txn>) -> VMResult<()> {
         if self.function_stack.len() < (FUNCTION_STACK_SIZE_LIMIT as usize) {
             let count = func.local_count();
@@ -45,6 +45,14 @@ where
         self.function.module()
     }
 
+    pub fn function(&self) -> &F {
+        &self.function
+    }
+
+    pub fn get_locals(&self) -> &Locals {
+        &self.locals
+    }
+
     pub fn copy_loc(&self, idx: LocalIndex) -> VMResult<Value> {
         self.locals.copy_loc(idx as usize)
     }
@@ -88,8 +96,4 @@ where
         self.pc = pc;
         self.locals = locals;
     }
-
-    pub fn get_locals(&self) -> &Locals {
-        &self.locals
-    }
 }
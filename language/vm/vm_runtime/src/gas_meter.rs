@@ -3,13 +3,18 @@
 
 //! Gas metering logic for the Move VM.
 use crate::{
-    code_cache::module_cache::ModuleCache, execution_stack::ExecutionStack,
-    loaded_data::function::FunctionReference,
+    code_cache::module_cache::ModuleCache,
+    execution_stack::ExecutionStack,
+    gas_profiler::{GasProfile, GasUsage},
+    loaded_data::function::{FunctionRef, FunctionReference},
 };
 use libra_types::{
-    account_address::ADDRESS_LENGTH, transaction::MAX_TRANSACTION_SIZE_IN_BYTES,
-    vm_error::StatusCode,
+    account_address::ADDRESS_LENGTH,
+    transaction::MAX_TRANSACTION_SIZE_IN_BYTES,
+    vm_error::{StatusCode, VMStatus},
 };
+use logger::prelude::*;
+use state_view::StateView;
 use vm::{
     access::ModuleAccess,
     errors::{vm_error, VMResult},
@@ -17,28 +22,68 @@ use vm::{
     gas_schedule::*,
 };
 
+/// Reads the gas schedule published on chain by the `GasSchedule` module. The initial gas schedule
+/// is used until one is published (i.e. for the genesis transaction). An on-chain gas schedule
+/// that cannot be read or decoded is an invariant violation: no transaction is executed rather
+/// than charged with another schedule than the other validators.
+pub fn load_gas_schedule(data_view: &dyn StateView) -> VMResult<CostTable> {
+    match data_view.get(&gas_schedule_access_path()) {
+        Ok(Some(blob)) => CostTable::from_resource_bytes(&blob).map_err(|e| {
+            crit!("[VM] Invalid on-chain gas schedule: {:?}", e);
+            VMStatus::new(StatusCode::GAS_SCHEDULE_ERROR)
+                .with_message(format!("Invalid on-chain gas schedule: {}", e))
+        }),
+        Ok(None) => Ok(INITIAL_GAS_SCHEDULE.clone()),
+        Err(e) => {
+            crit!("[VM] Failed to read the on-chain gas schedule: {:?}", e);
+            Err(VMStatus::new(StatusCode::STORAGE_ERROR))
+        }
+    }
+}
+
 /// Holds the state of the gas meter.
-pub struct GasMeter {
+pub struct GasMeter<'a> {
     // The current amount of gas that is left ("unburnt gas") in the gas meter.
     current_gas_left: GasUnits<GasCarrier>,
 
     // We need to disable and enable gas metering for both the prologue and epilogue of the Account
     // contract. The VM will then internally unset/set this flag before executing either of them.
     meter_on: bool,
+
+    // The gas schedule read from chain at the start of the block.
+    cost_table: &'a CostTable,
+
+    // The profile recording the gas consumed by every call stack, when the transaction is
+    // profiled.
+    profile: Option<&'a mut GasProfile>,
 }
 
 // NB: A number of the functions/methods in this struct will return a VMResult<T>
 // since we will need to access stack and memory states, and we need to be able
 // to report errors properly from these accesses.
-impl GasMeter {
-    /// Create a new gas meter with starting gas amount `gas_amount`
-    pub fn new(gas_amount: GasUnits<GasCarrier>) -> Self {
+impl<'a> GasMeter<'a> {
+    /// Create a new gas meter with starting gas amount `gas_amount`, charging the costs of
+    /// `cost_table`
+    pub fn new(gas_amount: GasUnits<GasCarrier>, cost_table: &'a CostTable) -> Self {
         GasMeter {
             current_gas_left: gas_amount,
             meter_on: true,
+            cost_table,
+            profile: None,
         }
     }
 
+    /// Records the gas consumed from now on in `profile`, attributed to the call stack it is
+    /// consumed in.
+    pub fn set_profile(&mut self, profile: &'a mut GasProfile) {
+        self.profile = Some(profile);
+    }
+
+    /// Returns the gas schedule this meter charges.
+    pub fn cost_table(&self) -> &'a CostTable {
+        self.cost_table
+    }
+
     /// Charges additional gas for the transaction based upon the total size (in bytes) of the
     /// submitted transaction. It is important that we charge for the transaction size since a
     /// transaction can contain arbitrary amounts of bytes in the `note` field. We also want to
@@ -55,7 +100,12 @@ impl GasMeter {
     {
         precondition!(transaction_size.get() <= (MAX_TRANSACTION_SIZE_IN_BYTES as u64));
         let cost = calculate_intrinsic_gas(transaction_size);
-        self.consume_gas(cost, stk)
+        let gas_left = self.current_gas_left;
+        let result = self.consume_gas(cost, stk);
+        if let Some(profile) = &mut self.profile {
+            profile.record_intrinsic(gas_left.sub(self.current_gas_left).get());
+        }
+        result
     }
 
     /// Queries the internal state of the gas meter to determine if it has at
@@ -96,12 +146,35 @@ impl GasMeter {
     {
         if self.meter_on {
             let instruction_gas = self.gas_for_instruction(instr, stk, memory_size)?;
-            self.consume_gas(instruction_gas, stk)
+            let usage = GasUsage {
+                instruction: instruction_gas.instruction_gas.get(),
+                memory: instruction_gas.memory_gas.get(),
+                native: 0,
+            };
+            self.consume_and_profile(usage, None, stk)
         } else {
             Ok(())
         }
     }
 
+    /// Consumes the gas `gas_amount` charged by the native function `native`.
+    pub fn consume_native_gas<'alloc, 'txn, P>(
+        &mut self,
+        native: &FunctionRef<'txn>,
+        gas_amount: GasUnits<GasCarrier>,
+        stk: &ExecutionStack<'alloc, 'txn, P>,
+    ) -> VMResult<()>
+    where
+        'alloc: 'txn,
+        P: ModuleCache<'alloc>,
+    {
+        let usage = GasUsage {
+            native: gas_amount.get(),
+            ..GasUsage::default()
+        };
+        self.consume_and_profile(usage, Some(native), stk)
+    }
+
     /// Calculate the gas usage for an instruction taking into account the current stack state, and
     /// the size of memory that is being accessed.
     pub fn gas_for_instruction<'alloc, 'txn, P>(
@@ -109,7 +182,7 @@ impl GasMeter {
         instr: &Bytecode,
         stk: &ExecutionStack<'alloc, 'txn, P>,
         memory_size: AbstractMemorySize<GasCarrier>,
-    ) -> VMResult<GasUnits<GasCarrier>>
+    ) -> VMResult<GasCost>
     where
         'alloc: 'txn,
         P: ModuleCache<'alloc>,
@@ -132,7 +205,12 @@ impl GasMeter {
             | Bytecode::Le
             | Bytecode::LdTrue
             | Bytecode::LdFalse
+            | Bytecode::LdU8(_)
             | Bytecode::LdConst(_)
+            | Bytecode::LdU128(_)
+            | Bytecode::CastU8
+            | Bytecode::CastU64
+            | Bytecode::CastU128
             | Bytecode::Branch(_)
             | Bytecode::Abort
             | Bytecode::Pop
@@ -157,50 +235,44 @@ impl GasMeter {
             // value stack.  Because of this, the cost of the instruction is not dependent upon the
             // size of the value being returned.
             | Bytecode::Ret => {
-                let default_gas = static_cost_instr(instr, AbstractMemorySize::new(1));
-                Self::gas_of(default_gas)
+                self.cost_table.instr_cost(instr, AbstractMemorySize::new(1))
             }
             Bytecode::Eq
             | Bytecode::Neq => {
                 let lhs_size = stk.peek()?.size();
                 let rhs_size = stk.peek_at(1)?.size();
                 let max_size = lhs_size.map2(rhs_size, std::cmp::max);
-                Self::gas_of(static_cost_instr(instr, max_size))
+                self.cost_table.instr_cost(instr, max_size)
             }
             Bytecode::LdAddr(_) => {
                 let size = AbstractMemorySize::new(ADDRESS_LENGTH as GasCarrier);
-                let default_gas = static_cost_instr(instr, size);
-                Self::gas_of(default_gas)
+                self.cost_table.instr_cost(instr, size)
             }
             Bytecode::LdByteArray(idx) => {
                 let byte_array_ref = stk.top_frame()?.module().byte_array_at(*idx);
                 let byte_array_len = AbstractMemorySize::new(byte_array_ref.len() as GasCarrier);
                 let byte_array_len = words_in(byte_array_len);
-                let default_gas = static_cost_instr(instr, byte_array_len);
-                Self::gas_of(default_gas)
+                self.cost_table.instr_cost(instr, byte_array_len)
             }
             // We charge by the length of the string being stored on the stack.
             Bytecode::LdStr(idx) => {
                 let string_ref = stk.top_frame()?.module().user_string_at(*idx);
                 let str_len = AbstractMemorySize::new(string_ref.len() as GasCarrier);
                 let str_len = words_in(str_len);
-                let default_gas = static_cost_instr(instr, str_len);
-                Self::gas_of(default_gas)
+                self.cost_table.instr_cost(instr, str_len)
             }
             Bytecode::StLoc(_) => {
                 // Get the local to store
                 let local = stk.peek()?;
                 // Get the size of the local
                 let size = local.size();
-                let default_gas = static_cost_instr(instr, size);
-                Self::gas_of(default_gas)
+                self.cost_table.instr_cost(instr, size)
             }
             // Note that a moveLoc incurs a copy overhead
             Bytecode::CopyLoc(local_idx) | Bytecode::MoveLoc(local_idx) => {
                 let local = stk.top_frame()?.copy_loc(*local_idx)?;
                 let size = local.size();
-                let default_gas = static_cost_instr(instr, size);
-                Self::gas_of(default_gas)
+                self.cost_table.instr_cost(instr, size)
             }
             Bytecode::Call(call_idx, _) => {
                 let self_module = &stk.top_frame()?.module();
@@ -209,16 +281,16 @@ impl GasMeter {
                     .resolve_function_ref(self_module, *call_idx)?
                     .ok_or_else(|| vm_error(stk.location().unwrap_or_default(), StatusCode::LINKER_ERROR))?;
                 if function_ref.is_native() {
-                    GasUnits::new(0) // This will be costed at the call site/by the native function
+                    // This will be costed at the call site/by the native function
+                    GasCost::new(0, 0)
                 } else {
                     let call_size = AbstractMemorySize::new(function_ref.arg_count() as GasCarrier);
-                    let call_gas = static_cost_instr(instr, call_size);
-                    Self::gas_of(call_gas)
+                    self.cost_table.instr_cost(instr, call_size)
                 }
             }
             Bytecode::Unpack(_, _) => {
                 let size = stk.peek()?.size();
-                Self::gas_of(static_cost_instr(instr, size))
+                self.cost_table.instr_cost(instr, size)
             }
             Bytecode::Pack(struct_idx, _) => {
                 let struct_def = &stk.top_frame()?.module().struct_def_at(*struct_idx);
@@ -229,8 +301,7 @@ impl GasMeter {
                 let arg_count = AbstractMemorySize::new(u64::from(member_count));
 
                 let total_size = arg_count.add(*STRUCT_SIZE);
-                let new_gas = static_cost_instr(instr, total_size);
-                Self::gas_of(new_gas)
+                self.cost_table.instr_cost(instr, total_size)
             }
             Bytecode::WriteRef => {
                 // Get a reference to the value that we are going to write
@@ -239,7 +310,7 @@ impl GasMeter {
                 let ref_val = stk.peek()?;
                 // Get the size of this value and charge accordingly.
                 let size = write_val.size();
-                let mut default_gas = static_cost_instr(instr, size);
+                let mut default_gas = self.cost_table.instr_cost(instr, size);
                 // Determine if the reference is global. If so charge for any expansion of global
                 // memory along with the write operation that will be incurred.
                 if ref_val.is_global_ref() {
@@ -257,17 +328,18 @@ impl GasMeter {
                         // Charge for any memory expansion
                         .add(size_difference.mul(*GLOBAL_MEMORY_PER_BYTE_COST));
                 };
-                Self::gas_of(default_gas)
+                default_gas
             }
             Bytecode::ReadRef => {
                 // NB: We don't charge for reads from global memory: we charge once for the read
                 // from global memory that is performed by a BorrowGlobal operation. After this,
                 // all ReadRefs will be reading from local cache and we don't need to distinguish.
                 let size = stk.peek()?.size();
-                let default_gas = static_cost_instr(instr, size);
-                Self::gas_of(default_gas)
+                self.cost_table.instr_cost(instr, size)
+            }
+            Bytecode::CreateAccount => {
+                self.cost_table.instr_cost(instr, *DEFAULT_ACCOUNT_SIZE)
             }
-            Bytecode::CreateAccount => Self::gas_of(static_cost_instr(instr, *DEFAULT_ACCOUNT_SIZE)),
             // Note that we charge twice for these operations; once at the start of
             // `execute_single_instruction` we charge once with size 1. This then covers the cost
             // of accessing the value and guards (somewhat) against abusive memory accesses. Once
@@ -294,7 +366,7 @@ impl GasMeter {
                 } else {
                     AbstractMemorySize::new(0) // We already charged for size 1
                 };
-                Self::gas_of(static_cost_instr(instr, mem_size))
+                self.cost_table.instr_cost(instr, mem_size)
             }
         };
         Ok(instruction_reqs)
@@ -336,11 +408,37 @@ impl GasMeter {
         }
     }
 
-    /// Take a GasCost from our gas schedule and convert it to a total gas charge in `GasUnits`.
-    ///
-    /// This is used internally for converting from a `GasCost` which is a triple of numbers
-    /// represeing instruction, stack, and memory consumption into a number of `GasUnits`.
-    fn gas_of(gas_cost: GasCost) -> GasUnits<GasCarrier> {
-        gas_cost.instruction_gas.add(gas_cost.memory_gas)
+    /// Consumes the gas of `usage` and records the gas actually consumed in the profile, if any,
+    /// under the current call stack, to which `native` is appended for the gas charged by a
+    /// native function.
+    fn consume_and_profile<'alloc, 'txn, P>(
+        &mut self,
+        usage: GasUsage,
+        native: Option<&FunctionRef<'txn>>,
+        stk: &ExecutionStack<'alloc, 'txn, P>,
+    ) -> VMResult<()>
+    where
+        'alloc: 'txn,
+        P: ModuleCache<'alloc>,
+    {
+        let gas_left = self.current_gas_left;
+        let result = self.consume_gas(GasUnits::new(usage.total()), stk);
+        if let Some(profile) = &mut self.profile {
+            let consumed = gas_left.sub(self.current_gas_left).get();
+            if consumed > 0 {
+                let call_stack = stk
+                    .get_call_stack()
+                    .iter()
+                    .map(|frame| function_name(frame.function()))
+                    .chain(native.map(function_name))
+                    .collect();
+                profile.record(call_stack, usage.truncate(consumed));
+            }
+        }
+        result
     }
 }
+
+fn function_name(function: &FunctionRef) -> String {
+    format!("{}::{}", function.module().name(), function.name())
+}
@@ -0,0 +1,150 @@
+// Copyright (c) The Libra Core Contributors
+// SPDX-License-Identifier: Apache-2.0
+
+//! Attribution of the gas charged to a transaction to the functions of its call stack.
+//!
+//! When profiling is enabled, the gas meter records every charge in a [`GasProfile`], under the
+//! call stack the charge was made in and split between instruction, memory and native function
+//! gas. A profile can be exported as folded stacks, the input of flamegraph tools such as
+//! `flamegraph.pl` or `inferno`, or as a JSON summary of the gas charged by every function.
+
+use serde::Serialize;
+use std::{
+    collections::{BTreeMap, BTreeSet},
+    fmt::Write,
+};
+
+/// An amount of gas, split by what it pays for.
+#[derive(Clone, Copy, Debug, Default, Eq, PartialEq, Serialize)]
+pub struct GasUsage {
+    /// Gas charged for executing instructions.
+    pub instruction: u64,
+    /// Gas charged for the memory accessed by instructions.
+    pub memory: u64,
+    /// Gas charged by native functions.
+    pub native: u64,
+}
+
+impl GasUsage {
+    pub fn total(&self) -> u64 {
+        self.instruction + self.memory + self.native
+    }
+
+    fn add(&mut self, other: &GasUsage) {
+        self.instruction += other.instruction;
+        self.memory += other.memory;
+        self.native += other.native;
+    }
+
+    /// Keeps at most `max` gas, taken from the instruction gas first and from the native gas
+    /// last. This is the part of a charge that is paid when the transaction runs out of gas.
+    pub(crate) fn truncate(self, max: u64) -> Self {
+        let instruction = std::cmp::min(self.instruction, max);
+        let memory = std::cmp::min(self.memory, max - instruction);
+        let native = std::cmp::min(self.native, max - instruction - memory);
+        Self {
+            instruction,
+            memory,
+            native,
+        }
+    }
+}
+
+/// The gas charged by the calls to a function.
+#[derive(Clone, Copy, Debug, Default, Eq, PartialEq, Serialize)]
+pub struct FunctionGas {
+    /// Gas charged by the function itself.
+    pub self_gas: GasUsage,
+    /// Gas charged by the function and the functions it called.
+    pub total_gas: GasUsage,
+}
+
+/// The gas charged to a transaction, by call stack. A call stack is written from the outermost
+/// function and every function is named `module::function`.
+#[derive(Clone, Debug, Default, Eq, PartialEq)]
+pub struct GasProfile {
+    stacks: BTreeMap<Vec<String>, GasUsage>,
+    // The gas charged for the size of the transaction, before any function is called.
+    intrinsic: u64,
+}
+
+#[derive(Serialize)]
+struct GasProfileSummary<'a> {
+    total: u64,
+    intrinsic: u64,
+    functions: &'a BTreeMap<String, FunctionGas>,
+}
+
+impl GasProfile {
+    pub(crate) fn record(&mut self, call_stack: Vec<String>, usage: GasUsage) {
+        self.stacks.entry(call_stack).or_default().add(&usage);
+    }
+
+    pub(crate) fn record_intrinsic(&mut self, gas: u64) {
+        self.intrinsic += gas;
+    }
+
+    /// Returns the gas charged to the transaction. It is the gas used by the transaction, in gas
+    /// units rather than in microlibra.
+    pub fn total(&self) -> u64 {
+        self.intrinsic + self.stacks.values().map(GasUsage::total).sum::<u64>()
+    }
+
+    /// Returns the gas charged for the size of the transaction.
+    pub fn intrinsic(&self) -> u64 {
+        self.intrinsic
+    }
+
+    /// Returns the gas charged by every call stack.
+    pub fn stacks(&self) -> &BTreeMap<Vec<String>, GasUsage> {
+        &self.stacks
+    }
+
+    /// Returns the gas charged by every function. The gas of a recursive function is only
+    /// counted once in its total.
+    pub fn functions(&self) -> BTreeMap<String, FunctionGas> {
+        let mut functions = BTreeMap::<String, FunctionGas>::new();
+        for (call_stack, usage) in &self.stacks {
+            if let Some(function) = call_stack.last() {
+                functions
+                    .entry(function.clone())
+                    .or_default()
+                    .self_gas
+                    .add(usage);
+            }
+            let callers: BTreeSet<&String> = call_stack.iter().collect();
+            for function in callers {
+                functions
+                    .entry(function.clone())
+                    .or_default()
+                    .total_gas
+                    .add(usage);
+            }
+        }
+        functions
+    }
+
+    /// Returns the profile as folded stacks: a line per call stack, with the functions separated
+    /// by `;` followed by the gas charged. The intrinsic gas is charged to `<intrinsic>`.
+    pub fn to_folded_stacks(&self) -> String {
+        let mut folded_stacks = String::new();
+        if self.intrinsic > 0 {
+            writeln!(folded_stacks, "<intrinsic> {}", self.intrinsic)
+                .expect("writing to a String never fails");
+        }
+        for (call_stack, usage) in &self.stacks {
+            writeln!(folded_stacks, "{} {}", call_stack.join(";"), usage.total())
+                .expect("writing to a String never fails");
+        }
+        folded_stacks
+    }
+
+    /// Returns a JSON summary of the profile, with the gas charged by every function.
+    pub fn to_json(&self) -> serde_json::Result<String> {
+        serde_json::to_string_pretty(&GasProfileSummary {
+            total: self.total(),
+            intrinsic: self.intrinsic,
+            functions: &self.functions(),
+        })
+    }
+}
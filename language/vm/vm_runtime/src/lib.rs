@@ -116,6 +116,7 @@ mod block_processor;
 mod frame;
 mod gas_meter;
 mod move_vm;
+mod parallel_executor;
 mod process_txn;
 mod runtime;
 #[cfg(test)]
@@ -123,6 +124,8 @@ mod unit_tests;
 
 pub mod code_cache;
 pub mod data_cache;
+pub mod debugger;
+pub mod gas_profiler;
 pub mod identifier;
 pub mod loaded_data;
 pub mod txn_executor;
@@ -170,3 +173,16 @@ pub trait VMExecutor {
         state_view: &dyn StateView,
     ) -> Vec<TransactionOutput>;
 }
+
+/// This trait describes the VM's simulation interface.
+pub trait VMSimulator {
+    /// Executes a single transaction as if it were the only one of a block and returns its output,
+    /// which is not meant to be committed. The signature of the transaction is not checked if
+    /// `skip_signature_check` is set, so that the output of unsigned transactions can be estimated.
+    fn simulate_transaction(
+        transaction: SignedTransaction,
+        skip_signature_check: bool,
+        config: &VMConfig,
+        state_view: &dyn StateView,
+    ) -> TransactionOutput;
+}
@@ -127,6 +127,11 @@ impl LoadedModule {
         }
     }
 
+    /// Returns the verified module this module was loaded from.
+    pub fn verified_module(&self) -> &VerifiedModule {
+        &self.module
+    }
+
     /// Return a cached copy of the struct def at this index, if available.
     pub fn cached_struct_def_at(&self, idx: StructDefinitionIndex) -> Option<StructDef> {
         let cached = self.cache.struct_defs[idx.into_index()]
@@ -2,7 +2,8 @@
 // SPDX-License-Identifier: Apache-2.0
 
 use crate::{
-    counters::*, loaded_data::loaded_module::LoadedModule, runtime::VMRuntime, VMExecutor,
+    counters::*, debugger::Debugger, gas_profiler::GasProfile,
+    loaded_data::loaded_module::LoadedModule, runtime::VMRuntime, VMExecutor, VMSimulator,
     VMVerifier,
 };
 use libra_types::{
@@ -43,6 +44,34 @@ impl MoveVM {
             inner: Arc::new(inner),
         }
     }
+
+    /// Executes a single transaction as if it were the only one of a block, calling `debugger`
+    /// before every instruction of the script and of the epilogue. The output is not meant to be
+    /// committed.
+    pub fn debug_transaction(
+        transaction: SignedTransaction,
+        config: &VMConfig,
+        state_view: &dyn StateView,
+        debugger: &mut dyn Debugger,
+    ) -> TransactionOutput {
+        let vm = MoveVMImpl::new(Box::new(Arena::new()), |arena| {
+            VMRuntime::new(&*arena, config)
+        });
+        vm.rent(|runtime| runtime.debug_transaction(transaction, state_view, debugger))
+    }
+
+    /// Executes a single transaction as if it were the only one of a block, recording the gas it
+    /// consumes by call stack. The output is not meant to be committed.
+    pub fn profile_transaction(
+        transaction: SignedTransaction,
+        config: &VMConfig,
+        state_view: &dyn StateView,
+    ) -> (TransactionOutput, GasProfile) {
+        let vm = MoveVMImpl::new(Box::new(Arena::new()), |arena| {
+            VMRuntime::new(&*arena, config)
+        });
+        vm.rent(|runtime| runtime.profile_transaction(transaction, state_view))
+    }
 }
 
 impl VMVerifier for MoveVM {
@@ -75,6 +104,24 @@ impl VMExecutor for MoveVM {
     }
 }
 
+impl VMSimulator for MoveVM {
+    fn simulate_transaction(
+        transaction: SignedTransaction,
+        skip_signature_check: bool,
+        config: &VMConfig,
+        state_view: &dyn StateView,
+    ) -> TransactionOutput {
+        // Like blocks, simulated transactions are executed by a VM of their own so that the
+        // modules they publish never end up in a code cache shared with other transactions.
+        let vm = MoveVMImpl::new(Box::new(Arena::new()), |arena| {
+            VMRuntime::new(&*arena, config)
+        });
+        vm.rent(|runtime| {
+            runtime.simulate_transaction(transaction, skip_signature_check, state_view)
+        })
+    }
+}
+
 #[test]
 fn vm_thread_safe() {
     fn assert_send<T: Send>() {}
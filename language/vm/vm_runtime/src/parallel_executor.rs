@@ -0,0 +1,255 @@
+// Copyright (c) The Libra Core Contributors
+// SPDX-License-Identifier: Apache-2.0
+
+//! Optimistic parallel execution of the transactions of a block.
+//!
+//! All the transactions of the block are first executed speculatively in parallel. Each
+//! transaction reads the multi-versioned data of the block, where the writes of the transactions
+//! are kept by `AccessPath` and by position in the block, and records its read set. The
+//! speculative outputs are then validated in the order of the block: a transaction is valid if
+//! none of the values it read has been changed by a preceding transaction. A transaction that
+//! isn't valid is executed again against the outputs of all the preceding transactions, which are
+//! final by then. The outputs are identical to the ones of the sequential `execute_block`.
+
+use crate::{
+    block_processor::{execute_block, transaction_flow},
+    code_cache::{
+        module_adapter::ModuleFetcherImpl,
+        module_cache::{BlockModuleCache, VMModuleCache},
+        script_cache::ScriptCache,
+    },
+    counters::*,
+    data_cache::RemoteCache,
+    gas_meter::load_gas_schedule,
+    process_txn::{execute::ExecutedTransaction, validate::ValidationMode},
+};
+use config::config::VMPublishingOption;
+use libra_types::{
+    access_path::AccessPath,
+    transaction::{
+        SignatureCheckedTransaction, SignedTransaction, TransactionOutput, TransactionPayload,
+    },
+    vm_error::{StatusCode, VMStatus},
+    write_set::{WriteOp, WriteSet},
+};
+use logger::prelude::*;
+use rayon::prelude::*;
+use state_view::StateView;
+use std::{
+    cell::RefCell,
+    collections::{BTreeMap, HashMap},
+};
+use vm::errors::VMResult;
+
+/// The values written by the transactions of a block, by `AccessPath` and by index of the
+/// transaction in the block. A deletion is stored as `None`.
+#[derive(Default)]
+pub(crate) struct MultiVersionData {
+    data: HashMap<AccessPath, BTreeMap<usize, Option<Vec<u8>>>>,
+}
+
+impl MultiVersionData {
+    /// Returns the value of `access_path` written by the latest transaction preceding
+    /// `txn_idx`, or `None` if no preceding transaction wrote it or if it was deleted. Like with
+    /// `BlockDataCache`, the value of a deleted `AccessPath` is read from the state of the
+    /// previous block.
+    pub(crate) fn read(&self, access_path: &AccessPath, txn_idx: usize) -> Option<&Vec<u8>> {
+        self.data
+            .get(access_path)
+            .and_then(|versions| versions.range(..txn_idx).next_back())
+            .and_then(|(_, value)| value.as_ref())
+    }
+
+    /// Records the writes of the transaction at `txn_idx`.
+    pub(crate) fn add_write_set(&mut self, txn_idx: usize, write_set: &WriteSet) {
+        for (access_path, write_op) in write_set.iter() {
+            let value = match write_op {
+                WriteOp::Value(blob) => Some(blob.clone()),
+                WriteOp::Deletion => None,
+            };
+            self.data
+                .entry(access_path.clone())
+                .or_default()
+                .insert(txn_idx, value);
+        }
+    }
+
+    /// Forgets the writes of the transaction at `txn_idx`.
+    pub(crate) fn remove_write_set(&mut self, txn_idx: usize, write_set: &WriteSet) {
+        for (access_path, _) in write_set.iter() {
+            if let Some(versions) = self.data.get_mut(access_path) {
+                versions.remove(&txn_idx);
+            }
+        }
+    }
+}
+
+/// The view of the block data of the transaction at `txn_idx`: the writes of the preceding
+/// transactions on top of the state of the previous block. Every read is recorded.
+struct VersionedDataCache<'a> {
+    data_view: &'a dyn StateView,
+    versioned_data: &'a MultiVersionData,
+    txn_idx: usize,
+    read_set: RefCell<HashMap<AccessPath, VMResult<Option<Vec<u8>>>>>,
+}
+
+impl<'a> VersionedDataCache<'a> {
+    fn new(
+        data_view: &'a dyn StateView,
+        versioned_data: &'a MultiVersionData,
+        txn_idx: usize,
+    ) -> Self {
+        Self {
+            data_view,
+            versioned_data,
+            txn_idx,
+            read_set: RefCell::new(HashMap::new()),
+        }
+    }
+}
+
+impl<'a> RemoteCache for VersionedDataCache<'a> {
+    fn get(&self, access_path: &AccessPath) -> VMResult<Option<Vec<u8>>> {
+        let value = match self.versioned_data.read(access_path, self.txn_idx) {
+            Some(value) => Ok(Some(value.clone())),
+            None => self.data_view.get(access_path).map_err(|_| {
+                crit!("[VM] Error getting data from storage for {:?}", access_path);
+                VMStatus::new(StatusCode::STORAGE_ERROR)
+            }),
+        };
+        self.read_set
+            .borrow_mut()
+            .insert(access_path.clone(), value.clone());
+        value
+    }
+}
+
+/// The output of the speculative execution of a transaction along with the values it read.
+struct SpeculativeExecution {
+    output: TransactionOutput,
+    read_set: HashMap<AccessPath, VMResult<Option<Vec<u8>>>>,
+}
+
+impl SpeculativeExecution {
+    /// A speculative execution is valid if the transactions preceding `txn_idx` haven't changed
+    /// any of the values it read, i.e. if it read the same values as a sequential execution.
+    fn is_valid(&self, versioned_data: &MultiVersionData, txn_idx: usize) -> bool {
+        // The speculative executions only read the state of the previous block, which the
+        // preceding transactions may have overwritten since.
+        self.read_set.iter().all(|(access_path, value)| {
+            versioned_data
+                .read(access_path, txn_idx)
+                .map_or(true, |current_value| match value {
+                    Ok(Some(value)) => value == current_value,
+                    _ => false,
+                })
+        })
+    }
+}
+
+/// Executes the transactions of a block in parallel. Falls back to the sequential `execute_block`
+/// for the genesis block and for the blocks publishing modules: the modules published by a
+/// transaction are visible to the following ones through the code cache, whose reads are not
+/// tracked.
+pub fn execute_block_parallel<'alloc>(
+    txn_block: Vec<SignedTransaction>,
+    code_cache: &VMModuleCache<'alloc>,
+    script_cache: &ScriptCache<'alloc>,
+    data_view: &dyn StateView,
+    publishing_option: &VMPublishingOption,
+) -> Vec<TransactionOutput> {
+    let publishes_modules = txn_block.iter().any(|txn| match txn.payload() {
+        TransactionPayload::Module(_) => true,
+        TransactionPayload::Program(program) => !program.modules().is_empty(),
+        _ => false,
+    });
+    if data_view.is_genesis() || publishes_modules {
+        return execute_block(
+            txn_block,
+            code_cache,
+            script_cache,
+            data_view,
+            publishing_option,
+        );
+    }
+
+    trace!(
+        "[VM] Execute block in parallel, transaction count: {}",
+        txn_block.len()
+    );
+    report_block_count(txn_block.len());
+
+    let module_cache = BlockModuleCache::new(code_cache, ModuleFetcherImpl::new(data_view));
+    // Updates of the gas schedule within the block only take effect from the next block.
+    let gas_schedule = match load_gas_schedule(data_view) {
+        Ok(gas_schedule) => gas_schedule,
+        Err(vm_status) => {
+            return txn_block
+                .iter()
+                .map(|_| ExecutedTransaction::discard_error_output(vm_status.clone()))
+                .collect();
+        }
+    };
+    let mut versioned_data = MultiVersionData::default();
+
+    let signature_verified_block: Vec<Result<SignatureCheckedTransaction, VMStatus>> = txn_block
+        .into_par_iter()
+        .map(|txn| {
+            txn.check_signature()
+                .map_err(|_| VMStatus::new(StatusCode::INVALID_SIGNATURE))
+        })
+        .collect();
+
+    let execute = |txn_idx: usize, versioned_data: &MultiVersionData| {
+        let data_cache = VersionedDataCache::new(data_view, versioned_data, txn_idx);
+        let output = match &signature_verified_block[txn_idx] {
+            Ok(txn) => transaction_flow(
+                txn.clone(),
+                &module_cache,
+                script_cache,
+                &data_cache,
+                &gas_schedule,
+                ValidationMode::Executing,
+                publishing_option,
+            ),
+            Err(vm_status) => ExecutedTransaction::discard_error_output(vm_status.clone()),
+        };
+        SpeculativeExecution {
+            output,
+            read_set: data_cache.read_set.into_inner(),
+        }
+    };
+
+    let speculative_executions: Vec<SpeculativeExecution> = record_stats! {
+        time_hist | TXN_SPECULATIVE_EXECUTION_TIME_TAKEN | {
+            (0..signature_verified_block.len())
+                .into_par_iter()
+                .map(|txn_idx| execute(txn_idx, &versioned_data))
+                .collect()
+        }
+    };
+    for (txn_idx, execution) in speculative_executions.iter().enumerate() {
+        versioned_data.add_write_set(txn_idx, execution.output.write_set());
+    }
+
+    let mut result = vec![];
+    for (txn_idx, execution) in speculative_executions.into_iter().enumerate() {
+        let output = if execution.is_valid(&versioned_data, txn_idx) {
+            execution.output
+        } else {
+            report_reexecution();
+            versioned_data.remove_write_set(txn_idx, execution.output.write_set());
+            let output = execute(txn_idx, &versioned_data).output;
+            versioned_data.add_write_set(txn_idx, output.write_set());
+            output
+        };
+        report_execution_status(output.status());
+
+        // `result` is initally empty, a single element is pushed per loop iteration and
+        // the number of iterations is bound to the max size of `signature_verified_block`
+        assume!(result.len() < usize::max_value());
+        result.push(output);
+    }
+    trace!("[VM] Execute block in parallel finished");
+    result
+}
@@ -2,6 +2,7 @@ use crate::{
     code_cache::module_cache::ModuleCache,
     process_txn::verify::{VerTxn, VerifiedTransaction, VerifiedTransactionState},
 };
+use bytecode_verifier::{verify_module_upgrade, VerifiedModule};
 use libra_types::{
     transaction::{TransactionOutput, TransactionPayload, TransactionStatus},
     vm_error::{StatusCode, StatusType, VMStatus},
@@ -10,7 +11,7 @@ use libra_types::{
 use logger::prelude::*;
 use vm::{
     access::ModuleAccess,
-    errors::{vm_error, Location},
+    errors::{vm_error, Location, VMResult},
 };
 
 /// Represents a transaction that has been executed.
@@ -66,37 +67,13 @@ where
 
             for (module, raw_bytes) in modules.into_iter().zip(module_bytes) {
                 let module_id = module.self_id();
-
-                // Make sure that there is not already a module with this name published
-                // under the transaction sender's account.
-                // Note: although this reads from the "module cache", `get_loaded_module`
-                // will read through the cache to fetch the module from the global storage
-                // if it is not already cached.
-                match txn_executor.module_cache().get_loaded_module(&module_id) {
-                    Ok(None) => (), // No module with this name exists. safe to publish one
-                    Err(ref err) if err.is(StatusType::InvariantViolation) => {
-                        error!(
-                            "[VM] VM internal error while checking for duplicate module {:?}: {:?}",
-                            module_id, err
-                        );
-                        return ExecutedTransaction::discard_error_output(err.clone());
-                    }
-                    Ok(Some(_)) | Err(_) => {
-                        // A module with this name already exists (the error case is when the module
-                        // couldn't be verified, but it still exists so we should fail similarly).
-                        // It is not safe to publish another one; it would clobber the old module.
-                        // This would break code that links against the module and make published
-                        // resources from the old module inaccessible (or worse, accessible and not
-                        // typesafe).
-                        //
-                        // We are currently developing a versioning scheme for safe updates of
-                        // modules and resources.
-                        warn!("[VM] VM error duplicate module {:?}", module_id);
-                        return txn_executor.failed_transaction_cleanup(Err(vm_error(
-                            Location::default(),
-                            StatusCode::DUPLICATE_MODULE_NAME,
-                        )));
-                    }
+                if let Err(err) = check_module_publishing(txn_executor.module_cache(), &module) {
+                    return match err.status_type() {
+                        StatusType::InvariantViolation => {
+                            ExecutedTransaction::discard_error_output(err)
+                        }
+                        _ => txn_executor.failed_transaction_cleanup(Err(err)),
+                    };
                 }
 
                 txn_executor.module_cache().cache_module(module);
@@ -143,37 +120,18 @@ where
                 _ => unreachable!("TransactionPayload::Module expects VerTxn::Module"),
             };
             let module_id = ver_module.self_id();
-            // Make sure that there is not already a module with this name published
-            // under the transaction sender's account.
-            // Note: although this reads from the "module cache", `get_loaded_module`
-            // will read through the cache to fetch the module from the global storage
-            // if it is not already cached.
-            match txn_executor.module_cache().get_loaded_module(&module_id) {
-                Ok(None) => (), // No module with this name exists. safe to publish one
-                Err(ref err) if err.is(StatusType::InvariantViolation) => {
-                    error!(
-                        "[VM] VM internal error while checking for duplicate module {:?}: {:?}",
-                        module_id, err
-                    );
-                    return ExecutedTransaction::discard_error_output(err.clone());
-                }
-                Ok(Some(_)) | Err(_) => {
-                    // A module with this name already exists (the error case is when the module
-                    // couldn't be verified, but it still exists so we should fail similarly).
-                    // It is not safe to publish another one; it would clobber the old module.
-                    // This would break code that links against the module and make published
-                    // resources from the old module inaccessible (or worse, accessible and not
-                    // typesafe).
-                    //
-                    // We are currently developing a versioning scheme for safe updates of
-                    // modules and resources.
-                    warn!("[VM] VM error duplicate module {:?}", module_id);
-                    return txn_executor.failed_transaction_cleanup(Err(vm_error(
-                        Location::default(),
-                        StatusCode::DUPLICATE_MODULE_NAME,
-                    )));
-                }
+            if let Err(err) = check_module_publishing(txn_executor.module_cache(), &ver_module) {
+                return match err.status_type() {
+                    StatusType::InvariantViolation => {
+                        ExecutedTransaction::discard_error_output(err)
+                    }
+                    _ => txn_executor.failed_transaction_cleanup(Err(err)),
+                };
             }
+
+            // Once the transaction is committed to the block, the module replaces any module with
+            // this name cached for the following transactions.
+            txn_executor.module_cache().cache_module(*ver_module);
             let module_bytes = module.into_inner();
             txn_executor.transaction_cleanup(vec![(module_id, module_bytes)])
         }
@@ -206,6 +164,48 @@ where
     }
 }
 
+/// Checks that `module` can be published under the transaction sender's account. If a module with
+/// this name is already published there, `module` replaces it and must be a compatible upgrade: the
+/// code linking against the published module must keep working, and the resources it published
+/// must remain accessible and typesafe.
+fn check_module_publishing<'a>(
+    module_cache: &impl ModuleCache<'a>,
+    module: &VerifiedModule,
+) -> VMResult<()> {
+    let module_id = module.self_id();
+    // Note: although this reads from the "module cache", `get_loaded_module`
+    // will read through the cache to fetch the module from the global storage
+    // if it is not already cached.
+    match module_cache.get_loaded_module(&module_id) {
+        Ok(None) => Ok(()), // No module with this name exists. safe to publish one
+        Ok(Some(published_module)) => {
+            let mut errors = verify_module_upgrade(published_module.verified_module(), module);
+            if errors.is_empty() {
+                Ok(())
+            } else {
+                warn!(
+                    "[VM] VM error incompatible upgrade of module {:?}: {:?}",
+                    module_id, errors
+                );
+                Err(errors.remove(0))
+            }
+        }
+        Err(err) => {
+            if err.is(StatusType::InvariantViolation) {
+                error!(
+                    "[VM] VM internal error while checking for duplicate module {:?}: {:?}",
+                    module_id, err
+                );
+                return Err(err);
+            }
+            // The published module couldn't be verified, so `module` can't be checked against it.
+            // It is not safe to publish another one; it would clobber the old module.
+            warn!("[VM] VM error duplicate module {:?}", module_id);
+            Err(vm_error(Location::default(), StatusCode::DUPLICATE_MODULE_NAME))
+        }
+    }
+}
+
 impl ExecutedTransaction {
     #[inline]
     pub(crate) fn discard_error_output(err: VMStatus) -> TransactionOutput {
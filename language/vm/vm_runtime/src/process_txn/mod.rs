@@ -1,11 +1,11 @@
 use crate::{
-    code_cache::module_cache::ModuleCache, data_cache::RemoteCache,
-    loaded_data::loaded_module::LoadedModule,
+    code_cache::module_cache::ModuleCache, data_cache::RemoteCache, debugger::Debugger,
+    gas_profiler::GasProfile, loaded_data::loaded_module::LoadedModule,
 };
 use config::config::VMPublishingOption;
 use libra_types::transaction::SignatureCheckedTransaction;
 use std::marker::PhantomData;
-use vm::errors::VMResult;
+use vm::{errors::VMResult, gas_schedule::CostTable};
 use vm_cache_map::Arena;
 
 pub mod execute;
@@ -24,7 +24,10 @@ where
     txn: SignatureCheckedTransaction,
     module_cache: P,
     data_cache: &'txn dyn RemoteCache,
+    gas_schedule: &'txn CostTable,
     allocator: &'txn Arena<LoadedModule>,
+    debugger: Option<&'txn mut dyn Debugger>,
+    gas_profile: Option<&'txn mut GasProfile>,
     phantom: PhantomData<&'alloc ()>,
 }
 
@@ -33,22 +36,40 @@ where
     'alloc: 'txn,
     P: ModuleCache<'alloc>,
 {
-    /// Creates a new instance of `ProcessTransaction`.
+    /// Creates a new instance of `ProcessTransaction`. `gas_schedule` is the cost table the
+    /// transaction is charged with.
     pub fn new(
         txn: SignatureCheckedTransaction,
         module_cache: P,
         data_cache: &'txn dyn RemoteCache,
+        gas_schedule: &'txn CostTable,
         allocator: &'txn Arena<LoadedModule>,
     ) -> Self {
         Self {
             txn,
             module_cache,
             data_cache,
+            gas_schedule,
             allocator,
+            debugger: None,
+            gas_profile: None,
             phantom: PhantomData,
         }
     }
 
+    /// Attaches `debugger` to the execution of this transaction. The prologue, which runs during
+    /// the validation, is not debugged.
+    pub fn with_debugger(mut self, debugger: &'txn mut dyn Debugger) -> Self {
+        self.debugger = Some(debugger);
+        self
+    }
+
+    /// Records the gas consumed by this transaction in `gas_profile`, by call stack.
+    pub fn with_gas_profile(mut self, gas_profile: &'txn mut GasProfile) -> Self {
+        self.gas_profile = Some(gas_profile);
+        self
+    }
+
     /// Validates this transaction. Returns a `ValidatedTransaction` on success or `VMStatus` on
     /// failure.
     pub fn validate(
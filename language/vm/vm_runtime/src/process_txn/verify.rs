@@ -23,6 +23,7 @@ use vm::{
     file_format::{CompiledModule, CompiledScript, FunctionSignature, SignatureToken},
     IndexKind,
 };
+use vm_runtime_types::native_structs::dispatch::is_native_vector;
 
 /// Represents a transaction which has been validated and for which the program has been run
 /// through the bytecode verifier.
@@ -103,7 +104,7 @@ where
         // Ensure the script can correctly be resolved into main.
         let main = script_cache.cache_script(&program.code())?;
 
-        if !verify_actuals(main.signature(), program.args()) {
+        if !verify_actuals(main.module(), main.signature(), program.args()) {
             return Err(VMStatus::new(StatusCode::TYPE_MISMATCH)
                 .with_message("Actual Type Mismatch".to_string()));
         }
@@ -183,7 +184,7 @@ where
         // Ensure the script can correctly be resolved into main.
         let main = script_cache.cache_script(&script.code())?;
 
-        if !verify_actuals(main.signature(), script.args()) {
+        if !verify_actuals(main.module(), main.signature(), script.args()) {
             return Err(VMStatus::new(StatusCode::TYPE_MISMATCH)
                 .with_message("Actual Type Mismatch".to_string()));
         }
@@ -333,7 +334,11 @@ pub fn static_verify_program(
 }
 
 /// Verify if the transaction arguments match the type signature of the main function.
-fn verify_actuals(signature: &FunctionSignature, args: &[TransactionArgument]) -> bool {
+fn verify_actuals(
+    module: &impl ModuleAccess,
+    signature: &FunctionSignature,
+    args: &[TransactionArgument],
+) -> bool {
     if signature.arg_types.len() != args.len() {
         warn!(
             "[VM] different argument length: actuals {}, formals {}",
@@ -343,19 +348,39 @@ fn verify_actuals(signature: &FunctionSignature, args: &[TransactionArgument]) -
         return false;
     }
     for (ty, arg) in signature.arg_types.iter().zip(args.iter()) {
-        match (ty, arg) {
-            (SignatureToken::U64, TransactionArgument::U64(_)) => (),
-            (SignatureToken::Address, TransactionArgument::Address(_)) => (),
-            (SignatureToken::ByteArray, TransactionArgument::ByteArray(_)) => (),
-            (SignatureToken::String, TransactionArgument::String(_)) => (),
-            _ => {
-                warn!(
-                    "[VM] different argument type: formal {:?}, actual {:?}",
-                    ty, arg
-                );
-                return false;
-            }
+        if !verify_actual(module, ty, arg) {
+            warn!(
+                "[VM] different argument type: formal {:?}, actual {:?}",
+                ty, arg
+            );
+            return false;
         }
     }
     true
 }
+
+fn verify_actual(
+    module: &impl ModuleAccess,
+    ty: &SignatureToken,
+    arg: &TransactionArgument,
+) -> bool {
+    match (ty, arg) {
+        (SignatureToken::Bool, TransactionArgument::Bool(_))
+        | (SignatureToken::U8, TransactionArgument::U8(_))
+        | (SignatureToken::U64, TransactionArgument::U64(_))
+        | (SignatureToken::U128, TransactionArgument::U128(_))
+        | (SignatureToken::Address, TransactionArgument::Address(_))
+        | (SignatureToken::ByteArray, TransactionArgument::ByteArray(_))
+        | (SignatureToken::String, TransactionArgument::String(_)) => true,
+        (SignatureToken::Struct(idx, type_actuals), TransactionArgument::Vector(elements)) => {
+            let handle = module.struct_handle_at(*idx);
+            let module_id = module.module_id_for_handle(module.module_handle_at(handle.module));
+            is_native_vector(&module_id, module.identifier_at(handle.name))
+                && type_actuals.len() == 1
+                && elements
+                    .iter()
+                    .all(|element| verify_actual(module, &type_actuals[0], element))
+        }
+        _ => false,
+    }
+}
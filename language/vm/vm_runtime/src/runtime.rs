@@ -2,7 +2,7 @@
 // SPDX-License-Identifier: Apache-2.0
 
 use crate::{
-    block_processor::execute_block,
+    block_processor::{execute_block, execute_instrumented_transaction, simulate_transaction},
     code_cache::{
         module_adapter::ModuleFetcherImpl,
         module_cache::{BlockModuleCache, VMModuleCache},
@@ -10,7 +10,11 @@ use crate::{
     },
     counters::report_verification_status,
     data_cache::BlockDataCache,
+    debugger::Debugger,
+    gas_meter::load_gas_schedule,
+    gas_profiler::GasProfile,
     loaded_data::loaded_module::LoadedModule,
+    parallel_executor::execute_block_parallel,
     process_txn::{validate::ValidationMode, ProcessTransaction},
 };
 use config::config::{VMConfig, VMPublishingOption};
@@ -31,10 +35,12 @@ use vm_cache_map::Arena;
 ///   in the whitelist, the VM will just reject it in `verify_transaction`.
 /// * Custom scripts, which will allow arbitrary valid scripts, but no module publishing
 /// * Open script and module publishing
+/// `parallel_execution` is whether the transactions of a block are executed in parallel.
 pub struct VMRuntime<'alloc> {
     code_cache: VMModuleCache<'alloc>,
     script_cache: ScriptCache<'alloc>,
     publishing_option: VMPublishingOption,
+    parallel_execution: bool,
 }
 
 impl<'alloc> VMRuntime<'alloc> {
@@ -45,6 +51,7 @@ impl<'alloc> VMRuntime<'alloc> {
             code_cache: VMModuleCache::new(allocator),
             script_cache: ScriptCache::new(allocator),
             publishing_option: config.publishing_options.clone(),
+            parallel_execution: config.parallel_execution,
         }
     }
 
@@ -72,6 +79,10 @@ impl<'alloc> VMRuntime<'alloc> {
         let module_cache =
             BlockModuleCache::new(&self.code_cache, ModuleFetcherImpl::new(data_view));
         let data_cache = BlockDataCache::new(data_view);
+        let gas_schedule = match load_gas_schedule(data_view) {
+            Ok(gas_schedule) => gas_schedule,
+            Err(vm_status) => return Some(vm_status),
+        };
 
         let arena = Arena::new();
         let signature_verified_txn = match txn.check_signature() {
@@ -79,8 +90,13 @@ impl<'alloc> VMRuntime<'alloc> {
             Err(_) => return Some(VMStatus::new(StatusCode::INVALID_SIGNATURE)),
         };
 
-        let process_txn =
-            ProcessTransaction::new(signature_verified_txn, module_cache, &data_cache, &arena);
+        let process_txn = ProcessTransaction::new(
+            signature_verified_txn,
+            module_cache,
+            &data_cache,
+            &gas_schedule,
+            &arena,
+        );
         let mode = if data_view.is_genesis() {
             ValidationMode::Genesis
         } else {
@@ -120,12 +136,80 @@ impl<'alloc> VMRuntime<'alloc> {
                     .expect("All transactions should be user transaction")
             })
             .collect();
-        execute_block(
-            txns,
+        if self.parallel_execution {
+            execute_block_parallel(
+                txns,
+                &self.code_cache,
+                &self.script_cache,
+                data_view,
+                &self.publishing_option,
+            )
+        } else {
+            execute_block(
+                txns,
+                &self.code_cache,
+                &self.script_cache,
+                data_view,
+                &self.publishing_option,
+            )
+        }
+    }
+
+    /// Execute a single transaction as if it were the only one of a block. The signature of the
+    /// transaction is not checked if `skip_signature_check` is set. The output is not meant to be
+    /// committed.
+    pub fn simulate_transaction(
+        &self,
+        txn: SignedTransaction,
+        skip_signature_check: bool,
+        data_view: &dyn StateView,
+    ) -> TransactionOutput {
+        simulate_transaction(
+            txn,
+            skip_signature_check,
             &self.code_cache,
             &self.script_cache,
             data_view,
             &self.publishing_option,
         )
     }
+
+    /// Execute a single transaction as if it were the only one of a block, with `debugger`
+    /// attached to its execution. The output is not meant to be committed.
+    pub fn debug_transaction(
+        &self,
+        txn: SignedTransaction,
+        data_view: &dyn StateView,
+        debugger: &mut dyn Debugger,
+    ) -> TransactionOutput {
+        execute_instrumented_transaction(
+            txn,
+            &self.code_cache,
+            &self.script_cache,
+            data_view,
+            &self.publishing_option,
+            Some(debugger),
+            None,
+        )
+    }
+
+    /// Execute a single transaction as if it were the only one of a block, recording the gas it
+    /// consumes by call stack. The output is not meant to be committed.
+    pub fn profile_transaction(
+        &self,
+        txn: SignedTransaction,
+        data_view: &dyn StateView,
+    ) -> (TransactionOutput, GasProfile) {
+        let mut gas_profile = GasProfile::default();
+        let output = execute_instrumented_transaction(
+            txn,
+            &self.code_cache,
+            &self.script_cache,
+            data_view,
+            &self.publishing_option,
+            None,
+            Some(&mut gas_profile),
+        );
+        (output, gas_profile)
+    }
 }
@@ -6,8 +6,10 @@ use crate::{
     code_cache::module_cache::{ModuleCache, VMModuleCache},
     counters::*,
     data_cache::{RemoteCache, TransactionDataCache},
+    debugger::{DebugContext, Debugger},
     execution_stack::ExecutionStack,
     gas_meter::GasMeter,
+    gas_profiler::GasProfile,
     identifier::{create_access_path, resource_storage_key},
     loaded_data::{
         function::{FunctionRef, FunctionReference},
@@ -35,14 +37,17 @@ use vm::{
     access::ModuleAccess,
     errors::*,
     file_format::{Bytecode, CodeOffset, CompiledScript, StructDefinitionIndex},
-    gas_schedule::{AbstractMemorySize, GasAlgebra, GasUnits},
+    gas_schedule::{
+        gas_schedule_module_name, AbstractMemorySize, CostTable, GasAlgebra, GasUnits,
+        INITIAL_GAS_SCHEDULE,
+    },
     transaction_metadata::TransactionMetadata,
     vm_string::VMString,
 };
 use vm_cache_map::Arena;
 use vm_runtime_types::{
     native_functions::dispatch::{dispatch_native_function, NativeReturnStatus},
-    value::{ReferenceValue, Struct, Value},
+    value::{IntegerValue, ReferenceValue, Struct, Value},
 };
 
 // Metadata needed for resolving the account module.
@@ -59,6 +64,9 @@ lazy_static! {
     /// The ModuleId for the Event
     pub static ref EVENT_MODULE: ModuleId =
         { ModuleId::new(account_config::core_code_address(), Identifier::new("Event").unwrap()) };
+    /// The ModuleId for the gas schedule module
+    pub static ref GAS_SCHEDULE_MODULE: ModuleId =
+        { ModuleId::new(account_config::core_code_address(), gas_schedule_module_name().to_owned()) };
     /// The ModuleId for the validator config
     pub static ref VALIDATOR_CONFIG_MODULE: ModuleId =
         { ModuleId::new(account_config::core_code_address(), Identifier::new("ValidatorConfig").unwrap()) };
@@ -89,6 +97,22 @@ fn make_access_path(
     create_access_path(&address, struct_tag)
 }
 
+/// Converts a transaction argument into the move value passed to the main function of a script.
+fn argument_value(arg: TransactionArgument) -> Value {
+    match arg {
+        TransactionArgument::U8(i) => Value::u8(i),
+        TransactionArgument::U64(i) => Value::u64(i),
+        TransactionArgument::U128(i) => Value::u128(i),
+        TransactionArgument::Address(a) => Value::address(a),
+        TransactionArgument::ByteArray(b) => Value::byte_array(b),
+        TransactionArgument::String(s) => Value::string(VMString::new(s)),
+        TransactionArgument::Bool(b) => Value::bool(b),
+        TransactionArgument::Vector(elements) => {
+            Value::vector(elements.into_iter().map(argument_value).collect())
+        }
+    }
+}
+
 /// A struct that executes one single transaction.
 /// 'alloc is the lifetime for the code cache, which is the argument type P here. Hence the P should
 /// live as long as alloc.
@@ -97,6 +121,7 @@ fn make_access_path(
 /// `txn_data` contains the information of this transaction, such as sender, sequence number, etc.
 /// `event_data` is the vector that stores all events emitted during execution.
 /// `data_view` is the scratchpad for the local writes emitted by this transaction.
+/// `debugger` is the hook called before each instruction when the transaction is debugged.
 pub struct TransactionExecutor<'alloc, 'txn, P>
 where
     'alloc: 'txn,
@@ -107,10 +132,11 @@ where
 
     #[cfg(not(any(test, feature = "instruction_synthesis")))]
     execution_stack: ExecutionStack<'alloc, 'txn, P>,
-    gas_meter: GasMeter,
+    gas_meter: GasMeter<'txn>,
     txn_data: TransactionMetadata,
     event_data: Vec<ContractEvent>,
     data_view: TransactionDataCache<'txn>,
+    debugger: Option<&'txn mut dyn Debugger>,
 }
 
 impl<'alloc, 'txn, P> TransactionExecutor<'alloc, 'txn, P>
@@ -121,18 +147,21 @@ where
     /// Create a new `TransactionExecutor` to execute a single transaction. `module_cache` is the
     /// cache that stores the modules previously read from the blockchain. `data_cache` is the cache
     /// that holds read-only connection to the state store as well as the changes made by previous
-    /// transactions within the same block.
+    /// transactions within the same block. `gas_schedule` is the cost table read from chain at the
+    /// start of the block.
     pub fn new(
         module_cache: P,
         data_cache: &'txn dyn RemoteCache,
         txn_data: TransactionMetadata,
+        gas_schedule: &'txn CostTable,
     ) -> Self {
         TransactionExecutor {
             execution_stack: ExecutionStack::new(module_cache),
-            gas_meter: GasMeter::new(txn_data.max_gas_amount()),
+            gas_meter: GasMeter::new(txn_data.max_gas_amount(), gas_schedule),
             txn_data,
             event_data: Vec::new(),
             data_view: TransactionDataCache::new(data_cache),
+            debugger: None,
         }
     }
 
@@ -141,40 +170,62 @@ where
         &self.execution_stack.module_cache
     }
 
+    /// Attaches `debugger` to this executor, which then calls it before every instruction.
+    pub fn set_debugger(&mut self, debugger: &'txn mut dyn Debugger) {
+        self.debugger = Some(debugger);
+    }
+
+    /// Records the gas consumed by this executor in `profile`, by call stack.
+    pub fn set_gas_profile(&mut self, profile: &'txn mut GasProfile) {
+        self.gas_meter.set_profile(profile);
+    }
+
+    /// Attach the current location to an arithmetic error (e.g. an overflow or a failed cast).
+    fn locate_arithmetic_error<T>(&self, result: VMResult<T>) -> VMResult<T> {
+        match result {
+            Err(ref err) if err.major_status == StatusCode::ARITHMETIC_ERROR => Err(vm_error(
+                self.execution_stack.location()?,
+                StatusCode::ARITHMETIC_ERROR,
+            )),
+            result => result,
+        }
+    }
+
     /// Perform a binary operation to two values at the top of the stack.
     fn binop<F, T>(&mut self, f: F) -> VMResult<()>
     where
         Option<T>: From<Value>,
-        F: FnOnce(T, T) -> Option<Value>,
+        F: FnOnce(T, T) -> VMResult<Value>,
     {
         let rhs = self.execution_stack.pop_as::<T>()?;
         let lhs = self.execution_stack.pop_as::<T>()?;
-        let result = f(lhs, rhs);
-        if let Some(v) = result {
-            self.execution_stack.push(v)?;
-            Ok(())
-        } else {
-            Err(vm_error(
-                self.execution_stack.location()?,
-                StatusCode::ARITHMETIC_ERROR,
-            ))
-        }
+        let result = self.locate_arithmetic_error(f(lhs, rhs))?;
+        self.execution_stack.push(result)
     }
 
-    fn binop_int<F, T>(&mut self, f: F) -> VMResult<()>
+    fn binop_int<F>(&mut self, f: F) -> VMResult<()>
     where
-        Option<T>: From<Value>,
-        F: FnOnce(T, T) -> Option<u64>,
+        F: FnOnce(IntegerValue, IntegerValue) -> VMResult<IntegerValue>,
     {
-        self.binop(|lhs, rhs| f(lhs, rhs).map(Value::u64))
+        self.binop(|lhs, rhs| f(lhs, rhs).map(IntegerValue::into_value))
     }
 
     fn binop_bool<F, T>(&mut self, f: F) -> VMResult<()>
     where
         Option<T>: From<Value>,
-        F: FnOnce(T, T) -> bool,
+        F: FnOnce(T, T) -> VMResult<bool>,
     {
-        self.binop(|lhs, rhs| Some(Value::bool(f(lhs, rhs))))
+        self.binop(|lhs, rhs| f(lhs, rhs).map(Value::bool))
+    }
+
+    /// Cast the integer at the top of the stack.
+    fn cast<F>(&mut self, f: F) -> VMResult<()>
+    where
+        F: FnOnce(IntegerValue) -> VMResult<Value>,
+    {
+        let integer = self.execution_stack.pop_as::<IntegerValue>()?;
+        let result = self.locate_arithmetic_error(f(integer))?;
+        self.execution_stack.push(result)
     }
 
     /// This function will execute the code sequence starting from the beginning_offset, and return
@@ -189,6 +240,16 @@ where
     ) -> VMResult<CodeOffset> {
         let mut pc = beginning_offset;
         for instruction in &code[beginning_offset as usize..] {
+            if let Some(debugger) = &mut self.debugger {
+                debugger.on_instruction(&DebugContext::new(
+                    pc,
+                    instruction,
+                    self.execution_stack.get_call_stack(),
+                    self.execution_stack.get_value_stack(),
+                    &self.data_view,
+                ));
+            }
+
             // FIXME: Once we add in memory ops, we will need to pass in the current memory size to
             // this function.
             self.gas_meter.calculate_and_consume(
@@ -221,9 +282,15 @@ where
                     }
                 }
                 Bytecode::Branch(offset) => return Ok(*offset),
+                Bytecode::LdU8(int_const) => {
+                    self.execution_stack.push(Value::u8(*int_const))?;
+                }
                 Bytecode::LdConst(int_const) => {
                     self.execution_stack.push(Value::u64(*int_const))?;
                 }
+                Bytecode::LdU128(int_const) => {
+                    self.execution_stack.push(Value::u128(*int_const))?;
+                }
                 Bytecode::LdAddr(idx) => {
                     let top_frame = self.execution_stack.top_frame()?;
                     let addr_ref = top_frame.module().address_at(*idx);
@@ -307,15 +374,20 @@ where
                             for _ in 0..expected_args {
                                 arguments.push_front(self.execution_stack.pop()?);
                             }
-                            let (cost, return_values) = match (native_function.dispatch)(arguments)
-                            {
+                            let (cost, return_values) = match (native_function.dispatch)(
+                                arguments,
+                                self.gas_meter.cost_table(),
+                            ) {
                                 NativeReturnStatus::InvalidArguments => {
                                     // TODO: better error
                                     return Err(VMStatus::new(StatusCode::LINKER_ERROR));
                                 }
                                 NativeReturnStatus::Aborted { cost, error_code } => {
-                                    self.gas_meter
-                                        .consume_gas(GasUnits::new(cost), &self.execution_stack)?;
+                                    self.gas_meter.consume_native_gas(
+                                        &callee_function_ref,
+                                        GasUnits::new(cost),
+                                        &self.execution_stack,
+                                    )?;
                                     return Err(vm_error(
                                         self.execution_stack.location()?,
                                         StatusCode::NATIVE_FUNCTION_ERROR,
@@ -327,8 +399,11 @@ where
                                     return_values,
                                 } => (cost, return_values),
                             };
-                            self.gas_meter
-                                .consume_gas(GasUnits::new(cost), &self.execution_stack)?;
+                            self.gas_meter.consume_native_gas(
+                                &callee_function_ref,
+                                GasUnits::new(cost),
+                                &self.execution_stack,
+                            )?;
                             for value in return_values {
                                 self.execution_stack.push(value)?;
                             }
@@ -387,20 +462,24 @@ where
                     reference.write_ref(value);
                 }
                 // Arithmetic Operations
-                Bytecode::Add => self.binop_int(u64::checked_add)?,
-                Bytecode::Sub => self.binop_int(u64::checked_sub)?,
-                Bytecode::Mul => self.binop_int(u64::checked_mul)?,
-                Bytecode::Mod => self.binop_int(u64::checked_rem)?,
-                Bytecode::Div => self.binop_int(u64::checked_div)?,
-                Bytecode::BitOr => self.binop_int(|l: u64, r| Some(l | r))?,
-                Bytecode::BitAnd => self.binop_int(|l: u64, r| Some(l & r))?,
-                Bytecode::Xor => self.binop_int(|l: u64, r| Some(l ^ r))?,
-                Bytecode::Or => self.binop_bool(|l, r| l || r)?,
-                Bytecode::And => self.binop_bool(|l, r| l && r)?,
-                Bytecode::Lt => self.binop_bool(|l: u64, r| l < r)?,
-                Bytecode::Gt => self.binop_bool(|l: u64, r| l > r)?,
-                Bytecode::Le => self.binop_bool(|l: u64, r| l <= r)?,
-                Bytecode::Ge => self.binop_bool(|l: u64, r| l >= r)?,
+                Bytecode::Add => self.binop_int(IntegerValue::checked_add)?,
+                Bytecode::Sub => self.binop_int(IntegerValue::checked_sub)?,
+                Bytecode::Mul => self.binop_int(IntegerValue::checked_mul)?,
+                Bytecode::Mod => self.binop_int(IntegerValue::checked_rem)?,
+                Bytecode::Div => self.binop_int(IntegerValue::checked_div)?,
+                Bytecode::BitOr => self.binop_int(IntegerValue::bit_or)?,
+                Bytecode::BitAnd => self.binop_int(IntegerValue::bit_and)?,
+                Bytecode::Xor => self.binop_int(IntegerValue::bit_xor)?,
+                Bytecode::Or => self.binop_bool(|l, r| Ok(l || r))?,
+                Bytecode::And => self.binop_bool(|l, r| Ok(l && r))?,
+                Bytecode::Lt => self.binop_bool(IntegerValue::lt)?,
+                Bytecode::Gt => self.binop_bool(IntegerValue::gt)?,
+                Bytecode::Le => self.binop_bool(IntegerValue::le)?,
+                Bytecode::Ge => self.binop_bool(IntegerValue::ge)?,
+                // Casts
+                Bytecode::CastU8 => self.cast(|i| i.cast_u8().map(Value::u8))?,
+                Bytecode::CastU64 => self.cast(|i| i.cast_u64().map(Value::u64))?,
+                Bytecode::CastU128 => self.cast(|i| i.cast_u128().map(Value::u128))?,
                 Bytecode::Abort => {
                     let error_code = self.execution_stack.pop_as::<u64>()?;
                     return Err(
@@ -441,7 +520,7 @@ where
                 }
                 Bytecode::GetTxnPublicKey => {
                     self.execution_stack.push(Value::byte_array(ByteArray::new(
-                        self.txn_data.public_key().to_bytes().to_vec(),
+                        self.txn_data.authentication_key_preimage().to_vec(),
                     )))?;
                 }
                 Bytecode::MutBorrowGlobal(idx, _) | Bytecode::ImmBorrowGlobal(idx, _) => {
@@ -555,12 +634,7 @@ where
     /// Convert the transaction arguments into move values and push them to the top of the stack.
     pub(crate) fn setup_main_args(&mut self, args: Vec<TransactionArgument>) {
         for arg in args.into_iter() {
-            let push_result = self.execution_stack.push(match arg {
-                TransactionArgument::U64(i) => Value::u64(i),
-                TransactionArgument::Address(a) => Value::address(a),
-                TransactionArgument::ByteArray(b) => Value::byte_array(b),
-                TransactionArgument::String(s) => Value::string(VMString::new(s)),
-            });
+            let push_result = self.execution_stack.push(argument_value(arg));
             assume!(push_result.is_ok());
             push_result.expect("Stack should be empty at beginning of function");
         }
@@ -831,10 +905,11 @@ pub fn execute_function(
     }
     let mut vm = TransactionExecutor {
         execution_stack: ExecutionStack::new(&module_cache),
-        gas_meter: GasMeter::new(txn_metadata.max_gas_amount()),
+        gas_meter: GasMeter::new(txn_metadata.max_gas_amount(), &INITIAL_GAS_SCHEDULE),
         txn_data: txn_metadata,
         event_data: Vec::new(),
         data_view: TransactionDataCache::new(data_cache),
+        debugger: None,
     };
     vm.execute_function_impl(entry_func)
 }
@@ -0,0 +1,77 @@
+// Copyright (c) The Libra Core Contributors
+// SPDX-License-Identifier: Apache-2.0
+
+use crate::gas_profiler::{FunctionGas, GasProfile, GasUsage};
+
+fn stack(functions: &[&str]) -> Vec<String> {
+    functions.iter().map(|function| function.to_string()).collect()
+}
+
+fn usage(instruction: u64, memory: u64, native: u64) -> GasUsage {
+    GasUsage {
+        instruction,
+        memory,
+        native,
+    }
+}
+
+fn recursive_profile() -> GasProfile {
+    let mut profile = GasProfile::default();
+    profile.record_intrinsic(600);
+    profile.record(stack(&["M::main"]), usage(10, 1, 0));
+    profile.record(stack(&["M::main", "M::f"]), usage(5, 0, 0));
+    profile.record(stack(&["M::main", "M::f", "M::f"]), usage(3, 2, 0));
+    profile.record(stack(&["M::main", "M::f", "Hash::sha3"]), usage(0, 0, 7));
+    profile.record(stack(&["M::main"]), usage(4, 0, 0));
+    profile
+}
+
+#[test]
+fn total_gas() {
+    let profile = recursive_profile();
+    assert_eq!(profile.intrinsic(), 600);
+    assert_eq!(profile.total(), 632);
+    assert_eq!(profile.stacks()[&stack(&["M::main"])], usage(14, 1, 0));
+}
+
+#[test]
+fn gas_by_function() {
+    let functions = recursive_profile().functions();
+    assert_eq!(
+        functions["M::main"],
+        FunctionGas {
+            self_gas: usage(14, 1, 0),
+            total_gas: usage(22, 3, 7),
+        }
+    );
+    // The recursive call is counted once in the total of `f`.
+    assert_eq!(
+        functions["M::f"],
+        FunctionGas {
+            self_gas: usage(8, 2, 0),
+            total_gas: usage(8, 2, 7),
+        }
+    );
+    assert_eq!(functions["Hash::sha3"].self_gas, usage(0, 0, 7));
+    assert_eq!(functions.len(), 3);
+}
+
+#[test]
+fn folded_stacks() {
+    assert_eq!(
+        recursive_profile().to_folded_stacks(),
+        "<intrinsic> 600\n\
+         M::main 15\n\
+         M::main;M::f 5\n\
+         M::main;M::f;Hash::sha3 7\n\
+         M::main;M::f;M::f 5\n"
+    );
+}
+
+#[test]
+fn truncate_usage() {
+    assert_eq!(usage(5, 3, 2).truncate(10), usage(5, 3, 2));
+    assert_eq!(usage(5, 3, 2).truncate(7), usage(5, 2, 0));
+    assert_eq!(usage(5, 3, 2).truncate(4), usage(4, 0, 0));
+    assert_eq!(usage(0, 3, 2).truncate(4), usage(0, 3, 1));
+}
@@ -4,7 +4,9 @@
 use libra_types::identifier::Identifier;
 
 mod identifier_prop_tests;
+mod gas_profiler_tests;
 mod module_cache_tests;
+mod parallel_executor_tests;
 mod runtime_tests;
 
 // Helper methods for identifiers in tests.
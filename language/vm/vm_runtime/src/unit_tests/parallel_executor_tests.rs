@@ -0,0 +1,38 @@
+// Copyright (c) The Libra Core Contributors
+// SPDX-License-Identifier: Apache-2.0
+
+use crate::parallel_executor::MultiVersionData;
+use libra_types::{
+    access_path::AccessPath,
+    account_address::AccountAddress,
+    write_set::{WriteOp, WriteSet, WriteSetMut},
+};
+
+fn write_set(writes: Vec<(AccessPath, WriteOp)>) -> WriteSet {
+    WriteSetMut::new(writes).freeze().unwrap()
+}
+
+#[test]
+fn read_latest_preceding_write() {
+    let ap = AccessPath::new(AccountAddress::random(), vec![1]);
+    let mut versioned_data = MultiVersionData::default();
+    versioned_data.add_write_set(1, &write_set(vec![(ap.clone(), WriteOp::Value(vec![1]))]));
+    versioned_data.add_write_set(3, &write_set(vec![(ap.clone(), WriteOp::Value(vec![3]))]));
+    versioned_data.add_write_set(5, &write_set(vec![(ap.clone(), WriteOp::Deletion)]));
+
+    // A transaction doesn't see its own writes nor the ones of the following transactions.
+    assert_eq!(versioned_data.read(&ap, 0), None);
+    assert_eq!(versioned_data.read(&ap, 1), None);
+    assert_eq!(versioned_data.read(&ap, 2), Some(&vec![1]));
+    assert_eq!(versioned_data.read(&ap, 4), Some(&vec![3]));
+    // Deleted values are read from the state of the previous block.
+    assert_eq!(versioned_data.read(&ap, 6), None);
+    assert_eq!(
+        versioned_data.read(&AccessPath::new(AccountAddress::random(), vec![1]), 6),
+        None
+    );
+
+    let write_set_3 = write_set(vec![(ap.clone(), WriteOp::Value(vec![3]))]);
+    versioned_data.remove_write_set(3, &write_set_3);
+    assert_eq!(versioned_data.read(&ap, 4), Some(&vec![1]));
+}
@@ -27,7 +27,7 @@ use vm::{
         FunctionSignatureIndex, IdentifierIndex, LocalsSignature, LocalsSignatureIndex,
         ModuleHandle, ModuleHandleIndex, SignatureToken, UserStringIndex, NO_TYPE_ACTUALS,
     },
-    gas_schedule::{AbstractMemorySize, GasAlgebra, GasPrice, GasUnits},
+    gas_schedule::{AbstractMemorySize, GasAlgebra, GasPrice, GasUnits, INITIAL_GAS_SCHEDULE},
     transaction_metadata::TransactionMetadata,
 };
 use vm_cache_map::Arena;
@@ -190,8 +190,12 @@ fn test_simple_instruction_transition() {
     let loaded_main = LoadedModule::new(main_module);
     let entry_func = FunctionRef::new(&loaded_main, CompiledScript::MAIN_INDEX);
     let data_cache = FakeDataCache::new();
-    let mut vm =
-        TransactionExecutor::new(module_cache, &data_cache, TransactionMetadata::default());
+    let mut vm = TransactionExecutor::new(
+        module_cache,
+        &data_cache,
+        TransactionMetadata::default(),
+        &INITIAL_GAS_SCHEDULE,
+    );
     vm.execution_stack
         .push_frame(entry_func)
         .expect("push to empty execution stack should succeed");
@@ -266,6 +270,26 @@ fn test_simple_instruction_transition() {
         1,
     );
 
+    test_simple_instruction(
+        &mut vm,
+        Bytecode::LdU8(100),
+        vec![],
+        vec![Value::u8(100)],
+        Locals::new(0),
+        Locals::new(0),
+        1,
+    );
+
+    test_simple_instruction(
+        &mut vm,
+        Bytecode::LdU128(100),
+        vec![],
+        vec![Value::u128(100)],
+        Locals::new(0),
+        Locals::new(0),
+        1,
+    );
+
     let addr = AccountAddress::default();
     test_simple_instruction(
         &mut vm,
@@ -390,8 +414,12 @@ fn test_arith_instructions() {
     let entry_func = FunctionRef::new(&loaded_main, CompiledScript::MAIN_INDEX);
     let data_cache = FakeDataCache::new();
 
-    let mut vm =
-        TransactionExecutor::new(module_cache, &data_cache, TransactionMetadata::default());
+    let mut vm = TransactionExecutor::new(
+        module_cache,
+        &data_cache,
+        TransactionMetadata::default(),
+        &INITIAL_GAS_SCHEDULE,
+    );
 
     vm.execution_stack
         .push_frame(entry_func)
@@ -577,6 +605,117 @@ fn test_arith_instructions() {
         vec![Value::u64(5), Value::u64(5)],
         Value::bool(true),
     );
+
+    test_binop_instruction(
+        &mut vm,
+        Bytecode::Add,
+        vec![Value::u8(1), Value::u8(2)],
+        Value::u8(3),
+    );
+    test_binop_instruction_overflow(
+        &mut vm,
+        Bytecode::Add,
+        vec![Value::u8(u8::max_value()), Value::u8(1)],
+    );
+
+    test_binop_instruction(
+        &mut vm,
+        Bytecode::Mul,
+        vec![Value::u128(u128::from(u64::max_value())), Value::u128(2)],
+        Value::u128(u128::from(u64::max_value()) * 2),
+    );
+    test_binop_instruction_overflow(
+        &mut vm,
+        Bytecode::Mul,
+        vec![Value::u128(u128::max_value()), Value::u128(2)],
+    );
+
+    test_binop_instruction(
+        &mut vm,
+        Bytecode::Lt,
+        vec![Value::u128(5), Value::u128(6)],
+        Value::bool(true),
+    );
+
+    assert_eq!(
+        test_binop_instruction_impl(
+            &mut vm,
+            Bytecode::Add,
+            vec![Value::u8(1), Value::u64(2)],
+            Value::u64(3),
+        )
+        .unwrap_err()
+        .major_status,
+        StatusCode::INTERNAL_TYPE_ERROR
+    );
+}
+
+fn test_cast_instruction_impl<'alloc, 'txn>(
+    vm: &mut TransactionExecutor<'alloc, 'txn, VMModuleCache<'alloc>>,
+    instr: Bytecode,
+    value: Value,
+    expected_value: Value,
+) -> VMResult<()> {
+    test_simple_instruction_impl(
+        vm,
+        instr,
+        vec![value],
+        vec![expected_value],
+        Locals::new(0),
+        Locals::new(0),
+        1,
+    )
+}
+
+#[test]
+fn test_cast_instructions() {
+    let allocator = Arena::new();
+    let module_cache = VMModuleCache::new(&allocator);
+    let main_module = fake_script().into_module();
+    let loaded_main = LoadedModule::new(main_module);
+    let entry_func = FunctionRef::new(&loaded_main, CompiledScript::MAIN_INDEX);
+    let data_cache = FakeDataCache::new();
+
+    let mut vm = TransactionExecutor::new(
+        module_cache,
+        &data_cache,
+        TransactionMetadata::default(),
+        &INITIAL_GAS_SCHEDULE,
+    );
+
+    vm.execution_stack
+        .push_frame(entry_func)
+        .expect("push to empty execution stack should succeed");
+
+    let casts = vec![
+        (Bytecode::CastU8, Value::u64(255), Value::u8(255)),
+        (Bytecode::CastU8, Value::u128(7), Value::u8(7)),
+        (Bytecode::CastU64, Value::u8(7), Value::u64(7)),
+        (Bytecode::CastU64, Value::u128(7), Value::u64(7)),
+        (Bytecode::CastU128, Value::u8(7), Value::u128(7)),
+        (
+            Bytecode::CastU128,
+            Value::u64(u64::max_value()),
+            Value::u128(u128::from(u64::max_value())),
+        ),
+    ];
+    for (instr, value, expected_value) in casts {
+        test_cast_instruction_impl(&mut vm, instr, value, expected_value).unwrap();
+    }
+
+    let overflows = vec![
+        (Bytecode::CastU8, Value::u64(256)),
+        (Bytecode::CastU8, Value::u128(u128::max_value())),
+        (Bytecode::CastU64, Value::u128(u128::from(u64::max_value()) + 1)),
+    ];
+    for (instr, value) in overflows {
+        assert_eq!(
+            test_cast_instruction_impl(&mut vm, instr, value, Value::u8(0))
+                .unwrap_err()
+                .major_status,
+            StatusCode::ARITHMETIC_ERROR
+        );
+    }
 }
 
 fn fake_module_with_calls(sigs: Vec<(Vec<SignatureToken>, FunctionSignature)>) -> VerifiedModule {
@@ -695,8 +834,12 @@ fn test_call() {
             .unwrap()
     };
     let data_cache = FakeDataCache::new();
-    let mut vm =
-        TransactionExecutor::new(module_cache, &data_cache, TransactionMetadata::default());
+    let mut vm = TransactionExecutor::new(
+        module_cache,
+        &data_cache,
+        TransactionMetadata::default(),
+        &INITIAL_GAS_SCHEDULE,
+    );
     vm.execution_stack
         .push_frame(fake_func)
         .expect("push to empty execution stack should succeed");
@@ -765,7 +908,7 @@ fn test_transaction_info() {
         let (_, public_key) = compat::generate_genesis_keypair();
         TransactionMetadata {
             sender: AccountAddress::default(),
-            public_key,
+            authentication_key_preimage: public_key.to_bytes().to_vec(),
             sequence_number: 10,
             max_gas_amount: GasUnits::new(100_000_009),
             gas_unit_price: GasPrice::new(5),
@@ -773,7 +916,8 @@ fn test_transaction_info() {
         }
     };
     let data_cache = FakeDataCache::new();
-    let mut vm = TransactionExecutor::new(module_cache, &data_cache, txn_info);
+    let mut vm =
+        TransactionExecutor::new(module_cache, &data_cache, txn_info, &INITIAL_GAS_SCHEDULE);
 
     vm.execution_stack
         .push_frame(entry_func)
@@ -128,6 +128,17 @@ where
     {
         self.or_insert_with_transform(key, insert, |value_ref| value_ref)
     }
+
+    /// Insert the value, replacing the value present if any. This is how entries are invalidated:
+    /// references to the replaced value that were handed out remain valid, as the value stays
+    /// allocated in the arena.
+    ///
+    /// Returns the address of the inserted value.
+    pub fn insert(&self, key: K, value: V) -> &'a V {
+        let value: &'a V = self.alloc.alloc(value);
+        self.map.insert(key, value);
+        value
+    }
 }
 
 #[test]
@@ -42,6 +42,27 @@ proptest! {
         }
     }
 
+    #[test]
+    fn insert_replaces(kv_lists in hash_map(".*", vec(".*", 1..4), 0..100)) {
+        let arena = Arena::new();
+        let map = CacheRefMap::new(&arena);
+        for (key, values) in kv_lists {
+            prop_assert_eq!(map.get(&key), None);
+            let mut inserted = vec![];
+            for value in values {
+                let value_ref = map.insert(key.clone(), value.clone());
+                prop_assert_eq!(value_ref, &value);
+                prop_assert_eq!(map.get(&key), Some(&value));
+                inserted.push((value_ref, value));
+            }
+
+            // The references to the replaced values remain valid.
+            for (value_ref, value) in inserted {
+                prop_assert_eq!(value_ref, &value);
+            }
+        }
+    }
+
     #[test]
     fn or_insert_many_threads(kv_lists in hash_map(".*", vec(".*", NUM_THREADS), 0..50)) {
         // Try inserting to the list concurrently with NUM_THREADS threads.
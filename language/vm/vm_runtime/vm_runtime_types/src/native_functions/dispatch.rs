@@ -1,7 +1,7 @@
 // Copyright (c) The Libra Core Contributors
 // SPDX-License-Identifier: Apache-2.0
 
-use super::{hash, primitive_helpers, signature};
+use super::{gas_schedule, hash, primitive_helpers, signature};
 use crate::{
     native_structs::{dispatch::dispatch_native_struct, vector::NativeVector},
     value::Value,
@@ -13,7 +13,10 @@ use libra_types::{
     language_storage::ModuleId,
 };
 use std::collections::{HashMap, VecDeque};
-use vm::file_format::{FunctionSignature, Kind, SignatureToken};
+use vm::{
+    file_format::{FunctionSignature, Kind, SignatureToken},
+    gas_schedule::{AbstractMemorySize, CostTable, GasAlgebra, GasCarrier, NativeCostIndex},
+};
 
 /// Enum representing the result of running a native function
 pub enum NativeReturnStatus {
@@ -37,8 +40,8 @@ pub enum NativeReturnStatus {
 
 /// Struct representing the expected definition for a native function
 pub struct NativeFunction {
-    /// Given the vector of aguments, it executes the native function
-    pub dispatch: fn(VecDeque<Value>) -> NativeReturnStatus,
+    /// Given the vector of aguments and the gas schedule, it executes the native function
+    pub dispatch: fn(VecDeque<Value>, &CostTable) -> NativeReturnStatus,
     /// The signature as defined in it's declaring module.
     /// It should NOT be generally inspected outside of it's declaring module as the various
     /// struct handle indexes are not remapped into the local context
@@ -52,6 +55,14 @@ impl NativeFunction {
     }
 }
 
+/// Returns the gas charged by the native function `key` of `cost_table` for an input of `size`
+/// units (e.g. the number of bytes hashed).
+pub fn native_gas(cost_table: &CostTable, key: NativeCostIndex, size: usize) -> u64 {
+    cost_table
+        .native_cost(key, AbstractMemorySize::new(size as GasCarrier))
+        .get()
+}
+
 /// Looks up the expected native function definition from the module id (address and module) and
 /// function name where it was expected to be declared
 pub fn dispatch_native_function(
@@ -147,6 +158,12 @@ lazy_static! {
             vec![ByteArray, ByteArray],
             vec![ByteArray]
         );
+        // GasSchedule
+        add!(m, addr, "GasSchedule", "is_valid",
+            gas_schedule::native_is_valid_gas_schedule,
+            vec![ByteArray, ByteArray],
+            vec![Bool]
+        );
         // Vector
         add!(m, addr, "Vector", "length",
             NativeVector::native_length,
@@ -215,7 +232,7 @@ lazy_static! {
         );
         // Event
         add!(m, addr, "Event", "write_to_event_store",
-            |_| { NativeReturnStatus::InvalidArguments },
+            |_, _| { NativeReturnStatus::InvalidArguments },
             vec![Kind::Unrestricted],
             vec![ByteArray, U64, TypeParameter(0)],
             vec![]
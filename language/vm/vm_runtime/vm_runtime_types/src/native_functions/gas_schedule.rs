@@ -0,0 +1,33 @@
+// Copyright (c) The Libra Core Contributors
+// SPDX-License-Identifier: Apache-2.0
+
+use super::dispatch::{native_gas, NativeReturnStatus};
+use crate::value::Value;
+use libra_types::byte_array::ByteArray;
+use std::collections::VecDeque;
+use vm::gas_schedule::{CostTable, NativeCostIndex};
+
+pub fn native_is_valid_gas_schedule(
+    mut arguments: VecDeque<Value>,
+    cost_table: &CostTable,
+) -> NativeReturnStatus {
+    if arguments.len() != 2 {
+        return NativeReturnStatus::InvalidArguments;
+    }
+    let native_schedule = pop_arg!(arguments, ByteArray);
+    let instruction_schedule = pop_arg!(arguments, ByteArray);
+    let cost = native_gas(
+        cost_table,
+        NativeCostIndex::IS_VALID_GAS_SCHEDULE,
+        instruction_schedule.len() + native_schedule.len(),
+    );
+
+    let is_valid =
+        CostTable::from_schedules(instruction_schedule.as_bytes(), native_schedule.as_bytes())
+            .is_ok();
+    let return_values = vec![Value::bool(is_valid)];
+    NativeReturnStatus::Success {
+        cost,
+        return_values,
+    }
+}
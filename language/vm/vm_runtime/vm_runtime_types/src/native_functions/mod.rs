@@ -3,6 +3,7 @@
 
 #[macro_use]
 pub mod dispatch;
+pub mod gas_schedule;
 pub mod hash;
 pub mod primitive_helpers;
 pub mod signature;
@@ -1,9 +1,13 @@
-use super::dispatch::NativeReturnStatus;
+use super::dispatch::{native_gas, NativeReturnStatus};
 use crate::value::Value;
 use libra_types::{account_address::AccountAddress, byte_array::ByteArray};
 use std::collections::VecDeque;
+use vm::gas_schedule::{CostTable, NativeCostIndex};
 
-pub fn native_bytearray_concat(mut arguments: VecDeque<Value>) -> NativeReturnStatus {
+pub fn native_bytearray_concat(
+    mut arguments: VecDeque<Value>,
+    cost_table: &CostTable,
+) -> NativeReturnStatus {
     if arguments.len() != 2 {
         return NativeReturnStatus::InvalidArguments;
     }
@@ -13,7 +17,7 @@ pub fn native_bytearray_concat(mut arguments: VecDeque<Value>) -> NativeReturnSt
     return_val.extend_from_slice(arg2.as_bytes());
 
     // TODO: Figure out the gas cost for concatenation.
-    let cost = return_val.len() as u64;
+    let cost = native_gas(cost_table, NativeCostIndex::BYTEARRAY_CONCAT, return_val.len());
     let return_values = vec![Value::byte_array(ByteArray::new(return_val))];
     NativeReturnStatus::Success {
         cost,
@@ -21,7 +25,10 @@ pub fn native_bytearray_concat(mut arguments: VecDeque<Value>) -> NativeReturnSt
     }
 }
 
-pub fn native_address_to_bytes(mut arguments: VecDeque<Value>) -> NativeReturnStatus {
+pub fn native_address_to_bytes(
+    mut arguments: VecDeque<Value>,
+    cost_table: &CostTable,
+) -> NativeReturnStatus {
     if arguments.len() != 1 {
         return NativeReturnStatus::InvalidArguments;
     }
@@ -29,7 +36,7 @@ pub fn native_address_to_bytes(mut arguments: VecDeque<Value>) -> NativeReturnSt
     let return_val = arg.to_vec();
 
     // TODO: Figure out the gas cost for conversion.
-    let cost = return_val.len() as u64;
+    let cost = native_gas(cost_table, NativeCostIndex::ADDRESS_TO_BYTES, return_val.len());
     let return_values = vec![Value::byte_array(ByteArray::new(return_val))];
     NativeReturnStatus::Success {
         cost,
@@ -37,7 +44,10 @@ pub fn native_address_to_bytes(mut arguments: VecDeque<Value>) -> NativeReturnSt
     }
 }
 
-pub fn native_u64_to_bytes(mut arguments: VecDeque<Value>) -> NativeReturnStatus {
+pub fn native_u64_to_bytes(
+    mut arguments: VecDeque<Value>,
+    cost_table: &CostTable,
+) -> NativeReturnStatus {
     if arguments.len() != 1 {
         return NativeReturnStatus::InvalidArguments;
     }
@@ -45,7 +55,7 @@ pub fn native_u64_to_bytes(mut arguments: VecDeque<Value>) -> NativeReturnStatus
     let return_val: Vec<u8> = arg.to_le_bytes().to_vec();
 
     // TODO: Figure out the gas cost for conversion.
-    let cost = return_val.len() as u64;
+    let cost = native_gas(cost_table, NativeCostIndex::U64_TO_BYTES, return_val.len());
     let return_values = vec![Value::byte_array(ByteArray::new(return_val))];
     NativeReturnStatus::Success {
         cost,
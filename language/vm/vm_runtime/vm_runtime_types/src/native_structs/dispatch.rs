@@ -35,6 +35,13 @@ pub fn dispatch_native_struct(
     NATIVE_STRUCT_MAP.get(module)?.get(struct_name)
 }
 
+/// Returns whether the struct with the given name declared in the given module is the native
+/// vector struct
+pub fn is_native_vector(module: &ModuleId, struct_name: &IdentStr) -> bool {
+    dispatch_native_struct(module, struct_name)
+        .map_or(false, |s| s.struct_type.tag == NativeStructTag::Vector)
+}
+
 macro_rules! add {
     ($m:ident, $addr:expr, $module:expr, $name:expr, $resource: expr, $ty_kinds: expr, $tag: expr) => {{
         let ty_args = $ty_kinds
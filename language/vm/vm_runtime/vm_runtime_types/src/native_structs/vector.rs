@@ -1,5 +1,5 @@
 use crate::{
-    native_functions::dispatch::NativeReturnStatus,
+    native_functions::dispatch::{native_gas, NativeReturnStatus},
     native_structs::NativeStructValue,
     pop_arg,
     value::{MutVal, ReferenceValue, Value},
@@ -7,19 +7,13 @@ use crate::{
 use libra_types::vm_error::sub_status::NFE_VECTOR_ERROR_BASE;
 use serde::Serialize;
 use std::{collections::VecDeque, ops::Add};
-use vm::gas_schedule::{AbstractMemorySize, GasAlgebra, GasCarrier, STRUCT_SIZE};
+use vm::gas_schedule::{
+    AbstractMemorySize, CostTable, GasAlgebra, GasCarrier, NativeCostIndex, STRUCT_SIZE,
+};
 
 #[derive(Debug, Clone, Eq, PartialEq, Serialize)]
 pub struct NativeVector(pub(crate) Vec<MutVal>);
 
-const BORROW_COST: u64 = 30; // TODO: determine experimentally
-const EMPTY_COST: u64 = 30; // TODO: determine experimentally
-const LENGTH_COST: u64 = 30; // TODO: determine experimentally
-const PUSH_BACK_COST: u64 = 30; // TODO: determine experimentally
-const POP_COST: u64 = 30; // TODO: determine experimentally
-const DESTROY_EMPTY_VEC_COST: u64 = 30; // TODO: determine experimentally
-const SWAP_COST: u64 = 30; // TODO: determine experimentally
-
 pub const INDEX_OUT_OF_BOUNDS: u64 = NFE_VECTOR_ERROR_BASE + 1;
 pub const POP_EMPTY_VEC: u64 = NFE_VECTOR_ERROR_BASE + 2;
 pub const DESTROY_NON_EMPTY_VEC: u64 = NFE_VECTOR_ERROR_BASE + 3;
@@ -50,26 +44,32 @@ macro_rules! get_vector_ref {
 }
 
 impl NativeVector {
-    pub fn native_empty(_args: VecDeque<Value>) -> NativeReturnStatus {
+    pub fn native_empty(_args: VecDeque<Value>, cost_table: &CostTable) -> NativeReturnStatus {
+        let cost = native_gas(cost_table, NativeCostIndex::EMPTY, 1);
         NativeReturnStatus::Success {
             return_values: vec![Value::native_struct(NativeStructValue::Vector(
                 NativeVector(vec![]),
             ))],
-            cost: EMPTY_COST,
+            cost,
         }
     }
-    pub fn native_length(mut args: VecDeque<Value>) -> NativeReturnStatus {
+    pub fn native_length(mut args: VecDeque<Value>, cost_table: &CostTable) -> NativeReturnStatus {
+        let cost = native_gas(cost_table, NativeCostIndex::LENGTH, 1);
         let reference = get_vector_ref!(args);
         reference
             .read_native_struct(|native_val| Some(get_vector(native_val)?.0.len()))
             .map(|len| NativeReturnStatus::Success {
-                cost: LENGTH_COST,
+                cost,
                 return_values: vec![Value::u64(len as u64)],
             })
             .unwrap_or(NativeReturnStatus::InvalidArguments)
     }
 
-    pub fn native_push_back(mut args: VecDeque<Value>) -> NativeReturnStatus {
+    pub fn native_push_back(
+        mut args: VecDeque<Value>,
+        cost_table: &CostTable,
+    ) -> NativeReturnStatus {
+        let cost = native_gas(cost_table, NativeCostIndex::PUSH_BACK, 1);
         if args.len() != 2 {
             return NativeReturnStatus::InvalidArguments;
         }
@@ -81,13 +81,14 @@ impl NativeVector {
         reference
             .mutate_native_struct(|native_val| Some(get_mut_vector(native_val)?.0.push(elem)))
             .map(|_| NativeReturnStatus::Success {
-                cost: PUSH_BACK_COST,
+                cost,
                 return_values: vec![],
             })
             .unwrap_or(NativeReturnStatus::InvalidArguments)
     }
 
-    pub fn native_borrow(mut args: VecDeque<Value>) -> NativeReturnStatus {
+    pub fn native_borrow(mut args: VecDeque<Value>, cost_table: &CostTable) -> NativeReturnStatus {
+        let cost = native_gas(cost_table, NativeCostIndex::BORROW, 1);
         if args.len() != 2 {
             return NativeReturnStatus::InvalidArguments;
         }
@@ -100,17 +101,18 @@ impl NativeVector {
                 .map(MutVal::clone)
         }) {
             Some(v) => NativeReturnStatus::Success {
-                cost: BORROW_COST,
+                cost,
                 return_values: vec![v],
             },
             None => NativeReturnStatus::Aborted {
-                cost: BORROW_COST,
+                cost,
                 error_code: INDEX_OUT_OF_BOUNDS,
             },
         }
     }
 
-    pub fn native_pop(mut args: VecDeque<Value>) -> NativeReturnStatus {
+    pub fn native_pop(mut args: VecDeque<Value>, cost_table: &CostTable) -> NativeReturnStatus {
+        let cost = native_gas(cost_table, NativeCostIndex::POP_BACK, 1);
         if args.len() != 1 {
             return NativeReturnStatus::InvalidArguments;
         }
@@ -121,11 +123,11 @@ impl NativeVector {
         }) {
             // Vector is already empty.
             None => NativeReturnStatus::Aborted {
-                cost: POP_COST,
+                cost,
                 error_code: POP_EMPTY_VEC,
             },
             Some(Ok(v)) => NativeReturnStatus::Success {
-                cost: POP_COST,
+                cost,
                 return_values: vec![v],
             },
             // The popped element has dangling references.
@@ -133,19 +135,23 @@ impl NativeVector {
         }
     }
 
-    pub fn native_destroy_empty(mut args: VecDeque<Value>) -> NativeReturnStatus {
+    pub fn native_destroy_empty(
+        mut args: VecDeque<Value>,
+        cost_table: &CostTable,
+    ) -> NativeReturnStatus {
+        let cost = native_gas(cost_table, NativeCostIndex::DESTROY_EMPTY, 1);
         if let Some(v) = args.pop_front() {
             if let Some(NativeStructValue::Vector(NativeVector(v))) =
                 v.value_as::<NativeStructValue>()
             {
                 return if v.is_empty() {
                     NativeReturnStatus::Success {
-                        cost: DESTROY_EMPTY_VEC_COST,
+                        cost,
                         return_values: vec![],
                     }
                 } else {
                     NativeReturnStatus::Aborted {
-                        cost: DESTROY_EMPTY_VEC_COST,
+                        cost,
                         error_code: DESTROY_NON_EMPTY_VEC,
                     }
                 };
@@ -154,7 +160,8 @@ impl NativeVector {
         NativeReturnStatus::InvalidArguments
     }
 
-    pub fn native_swap(mut args: VecDeque<Value>) -> NativeReturnStatus {
+    pub fn native_swap(mut args: VecDeque<Value>, cost_table: &CostTable) -> NativeReturnStatus {
+        let cost = native_gas(cost_table, NativeCostIndex::SWAP, 1);
         if args.len() != 3 {
             return NativeReturnStatus::InvalidArguments;
         }
@@ -172,7 +179,7 @@ impl NativeVector {
             Some(len) => {
                 if index1 >= len || index2 >= len {
                     return NativeReturnStatus::Aborted {
-                        cost: SWAP_COST,
+                        cost,
                         error_code: INDEX_OUT_OF_BOUNDS,
                     };
                 }
@@ -188,7 +195,7 @@ impl NativeVector {
                 )
             })
             .map(|_| NativeReturnStatus::Success {
-                cost: SWAP_COST,
+                cost,
                 return_values: vec![],
             })
             .unwrap_or(NativeReturnStatus::InvalidArguments)
@@ -15,7 +15,9 @@ impl Value {
     pub fn single_value_strategy() -> impl Strategy<Value = Self> {
         prop_oneof![
             any::<AccountAddress>().prop_map(Value::address),
+            any::<u8>().prop_map(Value::u8),
             any::<u64>().prop_map(Value::u64),
+            any::<u128>().prop_map(Value::u128),
             any::<bool>().prop_map(Value::bool),
             any::<VMString>().prop_map(Value::string),
             any::<ByteArray>().prop_map(Value::byte_array),
@@ -66,7 +68,9 @@ impl Type {
 
         prop_oneof![
             Just(Bool),
+            Just(U8),
             Just(U64),
+            Just(U128),
             Just(String),
             Just(ByteArray),
             Just(Address),
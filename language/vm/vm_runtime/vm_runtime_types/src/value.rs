@@ -3,7 +3,7 @@
 
 use crate::{
     loaded_data::{struct_def::StructDef, types::Type},
-    native_structs::{serializer::deserialize_native, NativeStructValue},
+    native_structs::{serializer::deserialize_native, vector::NativeVector, NativeStructValue},
 };
 use libra_types::{
     access_path::AccessPath,
@@ -14,6 +14,7 @@ use libra_types::{
 use serde::{Deserialize, Serialize};
 use std::{
     cell::{Ref, RefCell},
+    convert::TryFrom,
     mem::replace,
     ops::Add,
     rc::Rc,
@@ -52,7 +53,9 @@ enum ValueImpl {
     /// Locals are invalid on entry of a function and when moved out.
     Invalid,
     // Primitive types
+    U8(u8),
     U64(u64),
+    U128(u128),
     Address(AccountAddress),
     Bool(bool),
     ByteArray(ByteArray),
@@ -106,6 +109,16 @@ pub enum ReferenceValue {
     GlobalRef(GlobalRef),
 }
 
+/// An integer popped off the stack.
+/// Integer opcodes (arithmetic, bitwise, comparison) only operate on two integers of the same
+/// type, which is checked by the bytecode verifier.
+#[derive(PartialEq, Eq, Debug, Clone, Copy)]
+pub enum IntegerValue {
+    U8(u8),
+    U64(u64),
+    U128(u128),
+}
+
 /// Status for on chain data (published resources):
 /// CLEAN - the data was only read
 /// DIRTY - the data was changed anywhere in the data tree of the given resource
@@ -164,7 +177,11 @@ impl ValueImpl {
 
     fn size(&self) -> AbstractMemorySize<GasCarrier> {
         match self {
-            ValueImpl::Invalid | ValueImpl::U64(_) | ValueImpl::Bool(_) => *CONST_SIZE,
+            ValueImpl::Invalid
+            | ValueImpl::U8(_)
+            | ValueImpl::U64(_)
+            | ValueImpl::U128(_)
+            | ValueImpl::Bool(_) => *CONST_SIZE,
             ValueImpl::Address(_) => AbstractMemorySize::new(ADDRESS_LENGTH as u64),
             // Possible debate topic: Should we charge based upon the size of the string.
             // At this moment, we take the view that you should be charged as though you are
@@ -188,7 +205,9 @@ impl ValueImpl {
             // TODO: this does not look right to me....
             (ValueImpl::Invalid, ValueImpl::Invalid) => Ok(true),
             // values
+            (ValueImpl::U8(u1), ValueImpl::U8(u2)) => Ok(u1 == u2),
             (ValueImpl::U64(u1), ValueImpl::U64(u2)) => Ok(u1 == u2),
+            (ValueImpl::U128(u1), ValueImpl::U128(u2)) => Ok(u1 == u2),
             (ValueImpl::Bool(b1), ValueImpl::Bool(b2)) => Ok(b1 == b2),
             (ValueImpl::Address(a1), ValueImpl::Address(a2)) => Ok(a1 == a2),
             (ValueImpl::ByteArray(ba1), ValueImpl::ByteArray(ba2)) => Ok(ba1 == ba2),
@@ -211,7 +230,9 @@ impl ValueImpl {
     fn to_type_FOR_TESTING(&self) -> Type {
         match self {
             ValueImpl::Invalid => unreachable!("Cannot ask type of invalid location"),
+            ValueImpl::U8(_) => Type::U8,
             ValueImpl::U64(_) => Type::U64,
+            ValueImpl::U128(_) => Type::U128,
             ValueImpl::Address(_) => Type::Address,
             ValueImpl::Bool(_) => Type::Bool,
             ValueImpl::ByteArray(_) => Type::ByteArray,
@@ -235,11 +256,21 @@ impl Value {
         Value(value)
     }
 
+    /// Return a `Value` representing a `u8` in the VM.
+    pub fn u8(value: u8) -> Self {
+        Value(ValueImpl::U8(value))
+    }
+
     /// Return a `Value` representing a `u64` in the VM.
     pub fn u64(value: u64) -> Self {
         Value(ValueImpl::U64(value))
     }
 
+    /// Return a `Value` representing a `u128` in the VM.
+    pub fn u128(value: u128) -> Self {
+        Value(ValueImpl::U128(value))
+    }
+
     /// Return a `Value` representing an `AccountAddress` in the VM.
     pub fn address(address: AccountAddress) -> Self {
         Value(ValueImpl::Address(address))
@@ -279,6 +310,13 @@ impl Value {
         Value(ValueImpl::NativeStruct(v))
     }
 
+    /// Return a `Value` representing a native `Vector` holding the given elements in the VM.
+    pub fn vector(elements: Vec<Value>) -> Self {
+        Value::native_struct(NativeStructValue::Vector(NativeVector(
+            elements.into_iter().map(MutVal::new).collect(),
+        )))
+    }
+
     /// Convert a Value into a `T` if the value represents a type `T`.
     pub fn value_as<T>(self) -> Option<T>
     where
@@ -330,9 +368,19 @@ impl Value {
 //
 // From/Into implementation to read known values off the stack.
 // A pop from the stack returns a `Value` that is owned by the caller of pop. For many opcodes
-// (e.g. Add) the values popped from the stack are expected to be u64 and should fail otherwise.
+// (e.g. BrTrue) the values popped from the stack are expected to be of a given type and should
+// fail otherwise.
 //
 
+impl From<Value> for Option<u8> {
+    fn from(value: Value) -> Option<u8> {
+        match value.0 {
+            ValueImpl::U8(i) => Some(i),
+            _ => None,
+        }
+    }
+}
+
 impl From<Value> for Option<u64> {
     fn from(value: Value) -> Option<u64> {
         match value.0 {
@@ -342,6 +390,26 @@ impl From<Value> for Option<u64> {
     }
 }
 
+impl From<Value> for Option<u128> {
+    fn from(value: Value) -> Option<u128> {
+        match value.0 {
+            ValueImpl::U128(i) => Some(i),
+            _ => None,
+        }
+    }
+}
+
+impl From<Value> for Option<IntegerValue> {
+    fn from(value: Value) -> Option<IntegerValue> {
+        match value.0 {
+            ValueImpl::U8(i) => Some(IntegerValue::U8(i)),
+            ValueImpl::U64(i) => Some(IntegerValue::U64(i)),
+            ValueImpl::U128(i) => Some(IntegerValue::U128(i)),
+            _ => None,
+        }
+    }
+}
+
 impl From<Value> for Option<bool> {
     fn from(value: Value) -> Option<bool> {
         match value.0 {
@@ -680,6 +748,123 @@ impl ReferenceValue {
     }
 }
 
+//
+// Integer implementation
+//
+
+/// Applies `$op` to two integers of the same type, the result of `$op` being an `Option` of
+/// that type. Fails with `ARITHMETIC_ERROR` if `$op` returns `None` (e.g. on overflow).
+macro_rules! integer_op {
+    ($lhs:expr, $rhs:expr, |$l:ident, $r:ident| $op:expr) => {
+        match ($lhs, $rhs) {
+            (IntegerValue::U8($l), IntegerValue::U8($r)) => $op.map(IntegerValue::U8),
+            (IntegerValue::U64($l), IntegerValue::U64($r)) => $op.map(IntegerValue::U64),
+            (IntegerValue::U128($l), IntegerValue::U128($r)) => $op.map(IntegerValue::U128),
+            _ => return Err(VMStatus::new(StatusCode::INTERNAL_TYPE_ERROR)),
+        }
+        .ok_or_else(|| VMStatus::new(StatusCode::ARITHMETIC_ERROR))
+    };
+}
+
+/// Compares two integers of the same type with `$cmp`.
+macro_rules! integer_cmp {
+    ($lhs:expr, $rhs:expr, |$l:ident, $r:ident| $cmp:expr) => {
+        match ($lhs, $rhs) {
+            (IntegerValue::U8($l), IntegerValue::U8($r)) => Ok($cmp),
+            (IntegerValue::U64($l), IntegerValue::U64($r)) => Ok($cmp),
+            (IntegerValue::U128($l), IntegerValue::U128($r)) => Ok($cmp),
+            _ => Err(VMStatus::new(StatusCode::INTERNAL_TYPE_ERROR)),
+        }
+    };
+}
+
+impl IntegerValue {
+    pub fn checked_add(self, other: Self) -> VMResult<Self> {
+        integer_op!(self, other, |l, r| l.checked_add(r))
+    }
+
+    pub fn checked_sub(self, other: Self) -> VMResult<Self> {
+        integer_op!(self, other, |l, r| l.checked_sub(r))
+    }
+
+    pub fn checked_mul(self, other: Self) -> VMResult<Self> {
+        integer_op!(self, other, |l, r| l.checked_mul(r))
+    }
+
+    pub fn checked_div(self, other: Self) -> VMResult<Self> {
+        integer_op!(self, other, |l, r| l.checked_div(r))
+    }
+
+    pub fn checked_rem(self, other: Self) -> VMResult<Self> {
+        integer_op!(self, other, |l, r| l.checked_rem(r))
+    }
+
+    pub fn bit_or(self, other: Self) -> VMResult<Self> {
+        integer_op!(self, other, |l, r| Some(l | r))
+    }
+
+    pub fn bit_and(self, other: Self) -> VMResult<Self> {
+        integer_op!(self, other, |l, r| Some(l & r))
+    }
+
+    pub fn bit_xor(self, other: Self) -> VMResult<Self> {
+        integer_op!(self, other, |l, r| Some(l ^ r))
+    }
+
+    pub fn lt(self, other: Self) -> VMResult<bool> {
+        integer_cmp!(self, other, |l, r| l < r)
+    }
+
+    pub fn le(self, other: Self) -> VMResult<bool> {
+        integer_cmp!(self, other, |l, r| l <= r)
+    }
+
+    pub fn gt(self, other: Self) -> VMResult<bool> {
+        integer_cmp!(self, other, |l, r| l > r)
+    }
+
+    pub fn ge(self, other: Self) -> VMResult<bool> {
+        integer_cmp!(self, other, |l, r| l >= r)
+    }
+
+    /// Casts to a `u8`, failing with `ARITHMETIC_ERROR` if the value does not fit.
+    pub fn cast_u8(self) -> VMResult<u8> {
+        match self {
+            IntegerValue::U8(v) => Some(v),
+            IntegerValue::U64(v) => u8::try_from(v).ok(),
+            IntegerValue::U128(v) => u8::try_from(v).ok(),
+        }
+        .ok_or_else(|| VMStatus::new(StatusCode::ARITHMETIC_ERROR))
+    }
+
+    /// Casts to a `u64`, failing with `ARITHMETIC_ERROR` if the value does not fit.
+    pub fn cast_u64(self) -> VMResult<u64> {
+        match self {
+            IntegerValue::U8(v) => Some(u64::from(v)),
+            IntegerValue::U64(v) => Some(v),
+            IntegerValue::U128(v) => u64::try_from(v).ok(),
+        }
+        .ok_or_else(|| VMStatus::new(StatusCode::ARITHMETIC_ERROR))
+    }
+
+    /// Casts to a `u128`. Never fails as every integer type fits in a `u128`.
+    pub fn cast_u128(self) -> VMResult<u128> {
+        Ok(match self {
+            IntegerValue::U8(v) => u128::from(v),
+            IntegerValue::U64(v) => u128::from(v),
+            IntegerValue::U128(v) => v,
+        })
+    }
+
+    pub fn into_value(self) -> Value {
+        match self {
+            IntegerValue::U8(v) => Value::u8(v),
+            IntegerValue::U64(v) => Value::u64(v),
+            IntegerValue::U128(v) => Value::u128(v),
+        }
+    }
+}
+
 //
 // Global Reference implementation - check how to move part of this code outside
 // (possibly in the cache)
@@ -960,7 +1145,9 @@ pub(crate) fn deserialize_value(
 ) -> VMResult<Value> {
     match ty {
         Type::Bool => bool::deserialize(deserializer).map(Value::bool),
+        Type::U8 => u8::deserialize(deserializer).map(Value::u8),
         Type::U64 => u64::deserialize(deserializer).map(Value::u64),
+        Type::U128 => u128::deserialize(deserializer).map(Value::u128),
         Type::String => VMString::deserialize(deserializer).map(Value::string),
         Type::ByteArray => ByteArray::deserialize(deserializer).map(Value::byte_array),
         Type::Address => AccountAddress::deserialize(deserializer).map(Value::address),
@@ -998,7 +1185,9 @@ impl Serialize for ValueImpl {
     {
         use serde::ser::SerializeTuple;
         match self {
+            ValueImpl::U8(val) => serializer.serialize_u8(*val),
             ValueImpl::U64(val) => serializer.serialize_u64(*val),
+            ValueImpl::U128(val) => serializer.serialize_u128(*val),
             ValueImpl::Address(addr) => {
                 // TODO: this is serializing as a vector but we want just raw bytes
                 // however the AccountAddress story is a bit difficult to work with right now
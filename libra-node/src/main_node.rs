@@ -19,7 +19,6 @@ use network::{
         LibraNetworkProvider,
         // when you add a new protocol const, you must add this in either
         // .direct_send_protocols or .rpc_protocols vector of network_builder in setup_network()
-        ADMISSION_CONTROL_RPC_PROTOCOL,
         CONSENSUS_DIRECT_SEND_PROTOCOL,
         CONSENSUS_RPC_PROTOCOL,
         MEMPOOL_DIRECT_SEND_PROTOCOL,
@@ -42,7 +41,7 @@ use vm_runtime::MoveVM;
 
 pub struct LibraHandle {
     _ac: AdmissionControlRuntime,
-    _mempool: Option<MempoolRuntime>,
+    _mempool: MempoolRuntime,
     _state_synchronizer: StateSynchronizer,
     _network_runtimes: Vec<Runtime>,
     consensus: Option<Box<dyn ConsensusProvider>>,
@@ -112,15 +111,13 @@ pub fn setup_network(
     network_builder
         .permissioned(config.is_permissioned)
         .advertised_address(config.advertised_address.clone())
+        .inbound_rate_limit(config.inbound_rate_limit.clone())
         .direct_send_protocols(vec![
             ProtocolId::from_static(CONSENSUS_DIRECT_SEND_PROTOCOL),
             ProtocolId::from_static(MEMPOOL_DIRECT_SEND_PROTOCOL),
             ProtocolId::from_static(STATE_SYNCHRONIZER_MSG_PROTOCOL),
         ])
-        .rpc_protocols(vec![
-            ProtocolId::from_static(CONSENSUS_RPC_PROTOCOL),
-            ProtocolId::from_static(ADMISSION_CONTROL_RPC_PROTOCOL),
-        ]);
+        .rpc_protocols(vec![ProtocolId::from_static(CONSENSUS_RPC_PROTOCOL)]);
     if config.is_permissioned {
         // If the node wants to run in permissioned mode, it should also have authentication and
         // encryption.
@@ -196,8 +193,7 @@ pub fn setup_environment(node_config: &mut NodeConfig) -> LibraHandle {
     debug!("Executor setup in {} ms", instant.elapsed().as_millis());
     let mut network_runtimes = vec![];
     let mut state_sync_network_handles = vec![];
-    let mut ac_network_sender = None;
-    let mut ac_network_events = vec![];
+    let mut mempool_network_handles = vec![];
     let mut validator_network_provider = None;
 
     for i in 0..node_config.networks.len() {
@@ -208,20 +204,14 @@ pub fn setup_environment(node_config: &mut NodeConfig) -> LibraHandle {
             ProtocolId::from_static(STATE_SYNCHRONIZER_MSG_PROTOCOL),
         ]));
 
-        let (ac_sender, ac_events) =
-            network_provider.add_admission_control(vec![ProtocolId::from_static(
-                ADMISSION_CONTROL_RPC_PROTOCOL,
-            )]);
-        ac_network_events.push(ac_events);
+        mempool_network_handles.push(network_provider.add_mempool(vec![
+            ProtocolId::from_static(MEMPOOL_DIRECT_SEND_PROTOCOL),
+        ]));
 
         let network = &node_config.networks[i];
         if let RoleType::Validator = (&network.role).into() {
             validator_network_provider = Some((peer_id, runtime, network_provider));
-            ac_network_sender = Some(ac_sender);
         } else {
-            if node_config.is_upstream_network(network) {
-                ac_network_sender = Some(ac_sender);
-            }
             // For non-validator roles, the peer_id should be derived from the network identity
             // key.
             assert_eq!(
@@ -252,13 +242,13 @@ pub fn setup_environment(node_config: &mut NodeConfig) -> LibraHandle {
         Arc::clone(&executor),
         &node_config,
     );
-    let admission_control = AdmissionControlRuntime::bootstrap(
-        &node_config,
-        ac_network_sender.unwrap(),
-        ac_network_events,
-    );
+    let admission_control = AdmissionControlRuntime::bootstrap(&node_config);
+
+    // Initialize and start mempool.
+    instant = Instant::now();
+    let mempool = MempoolRuntime::bootstrap(&node_config, mempool_network_handles);
+    debug!("Mempool started in {} ms", instant.elapsed().as_millis());
 
-    let mut mempool = None;
     let mut consensus = None;
     if let Some((peer_id, runtime, mut network_provider)) = validator_network_provider {
         // Note: We need to start network provider before consensus, because the consensus
@@ -270,8 +260,6 @@ pub fn setup_environment(node_config: &mut NodeConfig) -> LibraHandle {
         // network provider -> consensus -> state synchronizer -> network provider. This deadlock
         // was observed in GitHub Issue #749. A long term fix might be make
         // consensus initialization async instead of blocking on state synchronizer.
-        let (mempool_network_sender, mempool_network_events) = network_provider
-            .add_mempool(vec![ProtocolId::from_static(MEMPOOL_DIRECT_SEND_PROTOCOL)]);
         let (consensus_network_sender, consensus_network_events) =
             network_provider.add_consensus(vec![
                 ProtocolId::from_static(CONSENSUS_RPC_PROTOCOL),
@@ -281,15 +269,6 @@ pub fn setup_environment(node_config: &mut NodeConfig) -> LibraHandle {
         network_runtimes.push(runtime);
         debug!("Network started for peer_id: {}", peer_id);
 
-        // Initialize and start mempool.
-        instant = Instant::now();
-        mempool = Some(MempoolRuntime::bootstrap(
-            &node_config,
-            mempool_network_sender,
-            mempool_network_events,
-        ));
-        debug!("Mempool started in {} ms", instant.elapsed().as_millis());
-
         // Initialize and start consensus.
         instant = Instant::now();
         let mut consensus_provider = make_consensus_provider(
@@ -35,25 +35,16 @@ impl PriorityIndex {
 
     /// add transaction to index
     pub(crate) fn insert(&mut self, txn: &MempoolTransaction) {
-        self.data.insert(self.make_key(&txn));
+        self.data.insert(OrderedQueueKey::from(txn));
     }
 
     /// remove transaction from index
     pub(crate) fn remove(&mut self, txn: &MempoolTransaction) {
-        self.data.remove(&self.make_key(&txn));
+        self.data.remove(&OrderedQueueKey::from(txn));
     }
 
     pub(crate) fn contains(&self, txn: &MempoolTransaction) -> bool {
-        self.data.contains(&self.make_key(txn))
-    }
-
-    fn make_key(&self, txn: &MempoolTransaction) -> OrderedQueueKey {
-        OrderedQueueKey {
-            gas_price: txn.get_gas_price(),
-            expiration_time: txn.expiration_time,
-            address: txn.get_sender(),
-            sequence_number: txn.get_sequence_number(),
-        }
+        self.data.contains(&OrderedQueueKey::from(txn))
     }
 
     /// returns iterator over priority queue
@@ -98,6 +89,17 @@ impl Ord for OrderedQueueKey {
     }
 }
 
+impl From<&MempoolTransaction> for OrderedQueueKey {
+    fn from(txn: &MempoolTransaction) -> Self {
+        Self {
+            gas_price: txn.get_gas_price(),
+            expiration_time: txn.expiration_time,
+            address: txn.get_sender(),
+            sequence_number: txn.get_sequence_number(),
+        }
+    }
+}
+
 /// TTLIndex is used to perform garbage collection of old transactions in Mempool
 /// Periodically separate GC-like job queries this index to find out transactions that have to be
 /// removed Index is represented as `BTreeSet<TTLOrderingKey>`
@@ -236,8 +238,10 @@ impl TimelineIndex {
 /// e.g. transactions that can't be included in next block
 /// (because their sequence number is too high)
 /// we keep separate index to be able to efficiently evict them when Mempool is full
+/// Transactions are ordered the same way as in PriorityIndex, so the ones paying the least are
+/// evicted first
 pub struct ParkingLotIndex {
-    data: BTreeSet<TxnPointer>,
+    data: BTreeSet<OrderedQueueKey>,
 }
 
 impl ParkingLotIndex {
@@ -249,17 +253,18 @@ impl ParkingLotIndex {
 
     /// add transaction to index
     pub(crate) fn insert(&mut self, txn: &MempoolTransaction) {
-        self.data.insert(TxnPointer::from(txn));
+        self.data.insert(OrderedQueueKey::from(txn));
     }
 
     /// remove transaction from index
     pub(crate) fn remove(&mut self, txn: &MempoolTransaction) {
-        self.data.remove(&TxnPointer::from(txn));
+        self.data.remove(&OrderedQueueKey::from(txn));
     }
 
-    /// returns random "non-ready" transaction (with highest sequence number for that account)
-    pub(crate) fn pop(&mut self) -> Option<TxnPointer> {
-        self.data.iter().rev().next().cloned()
+    /// returns "non-ready" transaction with lowest priority
+    /// (lowest gas price, highest sequence number for that account)
+    pub(crate) fn lowest_priority(&self) -> Option<&OrderedQueueKey> {
+        self.data.iter().next()
     }
 
     pub(crate) fn size(&self) -> usize {
@@ -0,0 +1,121 @@
+// Copyright (c) The Libra Core Contributors
+// SPDX-License-Identifier: Apache-2.0
+
+//! This module defines the on-disk journal of the transactions accepted by local Mempool, so that
+//! they survive a restart of the node. After a restart the journaled transactions are revalidated
+//! and added back to Mempool.
+//!
+//! Serialized transactions identified by the sender and the sequence number.
+//! ```text
+//! |<-------key------->|<------value------>|
+//! | address | seq_num | SignedTransaction |
+//! ```
+
+use crate::core_mempool::index::TxnPointer;
+use byteorder::{BigEndian, ReadBytesExt, WriteBytesExt};
+use failure::prelude::*;
+use libra_types::{
+    account_address::{AccountAddress, ADDRESS_LENGTH},
+    transaction::SignedTransaction,
+};
+use logger::prelude::*;
+use schemadb::{
+    define_schema,
+    schema::{KeyCodec, ValueCodec},
+    ColumnFamilyName, ColumnFamilyOptions, ColumnFamilyOptionsMap, ReadOptions, SchemaBatch, DB,
+    DEFAULT_CF_NAME,
+};
+use std::{convert::TryFrom, mem::size_of, path::Path, time::Instant};
+
+const JOURNAL_CF_NAME: ColumnFamilyName = "journal";
+
+define_schema!(JournalSchema, TxnPointer, SignedTransaction, JOURNAL_CF_NAME);
+
+impl KeyCodec<JournalSchema> for TxnPointer {
+    fn encode_key(&self) -> Result<Vec<u8>> {
+        let (ref address, sequence_number) = *self;
+
+        let mut encoded = address.to_vec();
+        encoded.write_u64::<BigEndian>(sequence_number)?;
+
+        Ok(encoded)
+    }
+
+    fn decode_key(data: &[u8]) -> Result<Self> {
+        ensure!(
+            data.len() == size_of::<Self>(),
+            "Unexpected data len {}, expected {}.",
+            data.len(),
+            size_of::<Self>(),
+        );
+
+        let address = AccountAddress::try_from(&data[..ADDRESS_LENGTH])?;
+        let sequence_number = (&data[ADDRESS_LENGTH..]).read_u64::<BigEndian>()?;
+
+        Ok((address, sequence_number))
+    }
+}
+
+impl ValueCodec<JournalSchema> for SignedTransaction {
+    fn encode_value(&self) -> Result<Vec<u8>> {
+        Ok(lcs::to_bytes(self)?)
+    }
+
+    fn decode_value(data: &[u8]) -> Result<Self> {
+        Ok(lcs::from_bytes(data)?)
+    }
+}
+
+/// MempoolJournal persists the transactions accepted by local Mempool
+pub(crate) struct MempoolJournal {
+    db: DB,
+}
+
+impl MempoolJournal {
+    pub(crate) fn new<P: AsRef<Path>>(db_root_path: P) -> Self {
+        let cf_opts_map: ColumnFamilyOptionsMap = [
+            (
+                /* UNUSED CF = */ DEFAULT_CF_NAME,
+                ColumnFamilyOptions::default(),
+            ),
+            (JOURNAL_CF_NAME, ColumnFamilyOptions::default()),
+        ]
+        .iter()
+        .cloned()
+        .collect();
+
+        let path = db_root_path.as_ref().join("mempooldb");
+        let instant = Instant::now();
+        let db = DB::open(path.clone(), cf_opts_map).unwrap_or_else(|e| {
+            panic!("Mempool journal open failed due to {:?}, unable to continue", e)
+        });
+
+        info!(
+            "Opened Mempool journal at {:?} in {} ms",
+            path,
+            instant.elapsed().as_millis()
+        );
+
+        Self { db }
+    }
+
+    /// add transaction to journal
+    pub(crate) fn insert(&self, txn: &SignedTransaction) -> Result<()> {
+        self.db
+            .put::<JournalSchema>(&(txn.sender(), txn.sequence_number()), txn)
+    }
+
+    /// remove transaction from journal
+    pub(crate) fn remove(&self, txn_pointer: &TxnPointer) -> Result<()> {
+        let mut batch = SchemaBatch::new();
+        batch.delete::<JournalSchema>(txn_pointer)?;
+        self.db.write_schemas(batch)
+    }
+
+    /// returns all journaled transactions
+    pub(crate) fn get_all(&self) -> Result<Vec<SignedTransaction>> {
+        let mut iter = self.db.iter::<JournalSchema>(ReadOptions::default())?;
+        iter.seek_to_first();
+        iter.map(|entry| entry.map(|(_, txn)| txn)).collect()
+    }
+}
@@ -8,6 +8,7 @@ use std::time::{Duration, SystemTime, UNIX_EPOCH};
 use crate::{
     core_mempool::{
         index::TxnPointer,
+        journal::MempoolJournal,
         transaction::{MempoolTransaction, TimelineState},
         transaction_store::TransactionStore,
     },
@@ -40,7 +41,14 @@ pub struct Mempool {
 impl Mempool {
     pub(crate) fn new(config: &NodeConfig) -> Self {
         Mempool {
-            transactions: TransactionStore::new(&config.mempool),
+            transactions: TransactionStore::new(
+                &config.mempool,
+                if config.mempool.persist_transactions {
+                    Some(MempoolJournal::new(config.get_storage_dir()))
+                } else {
+                    None
+                },
+            ),
             sequence_number_cache: LruCache::new(config.mempool.capacity),
             metrics_cache: TtlCache::new(config.mempool.capacity),
             system_transaction_timeout: Duration::from_secs(
@@ -249,6 +257,22 @@ impl Mempool {
         self.transactions.read_timeline(timeline_id, count)
     }
 
+    /// Returns the accounts having transactions in Mempool
+    pub(crate) fn get_accounts(&self) -> Vec<AccountAddress> {
+        self.transactions.get_accounts()
+    }
+
+    /// Returns transactions persisted by Mempool before a restart of the node
+    /// They are supposed to be revalidated and added back to Mempool
+    pub(crate) fn get_journaled_transactions(&self) -> Vec<SignedTransaction> {
+        self.transactions.get_journaled_transactions()
+    }
+
+    /// Removes journaled transactions that were not added back to Mempool
+    pub(crate) fn prune_journal(&self) {
+        self.transactions.prune_journal();
+    }
+
     /// Check the health of core mempool.
     pub(crate) fn health_check(&self) -> bool {
         self.transactions.health_check()
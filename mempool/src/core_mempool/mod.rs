@@ -2,6 +2,7 @@
 // SPDX-License-Identifier: Apache-2.0
 
 mod index;
+mod journal;
 mod mempool;
 mod transaction;
 mod transaction_store;
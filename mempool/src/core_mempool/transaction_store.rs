@@ -5,14 +5,14 @@ use crate::{
     core_mempool::{
         index::{
             AccountTransactions, ParkingLotIndex, PriorityIndex, PriorityQueueIter, TTLIndex,
-            TimelineIndex,
+            TimelineIndex, TxnPointer,
         },
+        journal::MempoolJournal,
         transaction::{MempoolTransaction, TimelineState},
     },
     OP_COUNTERS,
 };
 use config::config::MempoolConfig;
-use failure::prelude::*;
 use libra_mempool_shared_proto::{
     proto::mempool_status::MempoolAddTransactionStatusCode, MempoolAddTransactionStatus,
 };
@@ -41,13 +41,17 @@ pub struct TransactionStore {
     // keeps track of "non-ready" txns (transactions that can't be included in next block)
     parking_lot_index: ParkingLotIndex,
 
+    // on-disk copy of local transactions (the ones not received from other peers)
+    journal: Option<MempoolJournal>,
+
     // configuration
     capacity: usize,
     capacity_per_user: usize,
+    replacement_gas_price_bump_percent: u64,
 }
 
 impl TransactionStore {
-    pub(crate) fn new(config: &MempoolConfig) -> Self {
+    pub(crate) fn new(config: &MempoolConfig, journal: Option<MempoolJournal>) -> Self {
         Self {
             // main DS
             transactions: HashMap::new(),
@@ -61,9 +65,12 @@ impl TransactionStore {
             timeline_index: TimelineIndex::new(),
             parking_lot_index: ParkingLotIndex::new(),
 
+            journal,
+
             // configuration
             capacity: config.capacity,
             capacity_per_user: config.capacity_per_user,
+            replacement_gas_price_bump_percent: config.replacement_gas_price_bump_percent,
         }
     }
 
@@ -83,6 +90,11 @@ impl TransactionStore {
         None
     }
 
+    /// returns the accounts having transactions in mempool
+    pub(crate) fn get_accounts(&self) -> Vec<AccountAddress> {
+        self.transactions.keys().cloned().collect()
+    }
+
     /// insert transaction into TransactionStore
     /// performs validation checks and updates indexes
     pub(crate) fn insert(
@@ -90,20 +102,21 @@ impl TransactionStore {
         txn: MempoolTransaction,
         current_sequence_number: u64,
     ) -> MempoolAddTransactionStatus {
-        if self.handle_gas_price_update(&txn).is_err() {
-            return MempoolAddTransactionStatus::new(
-                MempoolAddTransactionStatusCode::InvalidUpdate,
-                format!("Failed to update gas price to {}", txn.get_gas_price()),
-            );
+        if let Err(status) = self.handle_gas_price_update(&txn) {
+            return status;
         }
 
-        if self.check_if_full() {
+        if self.check_if_full(&txn) {
             return MempoolAddTransactionStatus::new(
                 MempoolAddTransactionStatusCode::MempoolIsFull,
                 format!(
-                    "mempool size: {}, capacity: {}",
+                    "mempool size: {}, capacity: {}, gas price: {}, min evictable gas price: {}",
                     self.system_ttl_index.size(),
                     self.capacity,
+                    txn.get_gas_price(),
+                    self.parking_lot_index
+                        .lowest_priority()
+                        .map_or_else(|| "none".to_string(), |key| key.gas_price.to_string()),
                 ),
             );
         }
@@ -131,6 +144,13 @@ impl TransactionStore {
             // insert into storage and other indexes
             self.system_ttl_index.insert(&txn);
             self.expiration_time_index.insert(&txn);
+            if let Some(journal) = &self.journal {
+                if txn.timeline_state != TimelineState::NonQualified {
+                    if let Err(e) = journal.insert(&txn.txn) {
+                        error!("[Mempool] Failed to journal transaction: {:?}", e);
+                    }
+                }
+            }
             txns.insert(sequence_number, txn);
             self.track_indices();
         }
@@ -150,17 +170,24 @@ impl TransactionStore {
     }
 
     /// checks if Mempool is full
-    /// If it's full, tries to free some space by evicting transactions from ParkingLot
-    fn check_if_full(&mut self) -> bool {
+    /// If it's full, tries to free some space by evicting the lowest priority transaction from
+    /// ParkingLot, unless it pays a higher gas price than the new transaction `txn`
+    fn check_if_full(&mut self, txn: &MempoolTransaction) -> bool {
         if self.system_ttl_index.size() >= self.capacity {
             // try to free some space in Mempool from ParkingLot
-            if let Some((address, sequence_number)) = self.parking_lot_index.pop() {
-                if let Some(txn) = self
+            let evicted = self
+                .parking_lot_index
+                .lowest_priority()
+                .filter(|key| key.gas_price <= txn.get_gas_price())
+                .map(TxnPointer::from);
+            if let Some((address, sequence_number)) = evicted {
+                if let Some(evicted_txn) = self
                     .transactions
                     .get_mut(&address)
                     .and_then(|txns| txns.remove(&sequence_number))
                 {
-                    self.index_remove(&txn);
+                    OP_COUNTERS.inc("txn.evicted");
+                    self.index_remove(&evicted_txn);
                 }
             }
         }
@@ -169,28 +196,60 @@ impl TransactionStore {
 
     /// check if transaction is already present in Mempool
     /// e.g. given request is update
-    /// we allow increase in gas price to speed up process
-    fn handle_gas_price_update(&mut self, txn: &MempoolTransaction) -> Result<()> {
+    /// we allow increase in gas price to speed up process (replace by fee), as long as the gas
+    /// price is increased by at least `replacement_gas_price_bump_percent` percents
+    fn handle_gas_price_update(
+        &mut self,
+        txn: &MempoolTransaction,
+    ) -> Result<(), MempoolAddTransactionStatus> {
         if let Some(txns) = self.transactions.get_mut(&txn.get_sender()) {
             if let Some(current_version) = txns.get_mut(&txn.get_sequence_number()) {
-                if current_version.txn.max_gas_amount() == txn.txn.max_gas_amount()
-                    && current_version.txn.payload() == txn.txn.payload()
-                    && current_version.txn.expiration_time() == txn.txn.expiration_time()
-                    && current_version.get_gas_price() < txn.get_gas_price()
+                if current_version.txn.max_gas_amount() != txn.txn.max_gas_amount()
+                    || current_version.txn.payload() != txn.txn.payload()
+                    || current_version.txn.expiration_time() != txn.txn.expiration_time()
+                    || current_version.get_gas_price() >= txn.get_gas_price()
                 {
-                    if let Some(txn) = txns.remove(&txn.get_sequence_number()) {
-                        self.index_remove(&txn);
-                    }
-                } else {
-                    return Err(format_err!("Invalid gas price update. txn gas price: {}, current_version gas price: {}",
+                    return Err(MempoolAddTransactionStatus::new(
+                        MempoolAddTransactionStatusCode::InvalidUpdate,
+                        format!(
+                            "Invalid gas price update. txn gas price: {}, current_version gas \
+                             price: {}",
                             txn.get_gas_price(),
-                            current_version.get_gas_price()));
+                            current_version.get_gas_price()
+                        ),
+                    ));
+                }
+                let min_gas_price = Self::min_replacement_gas_price(
+                    current_version.get_gas_price(),
+                    self.replacement_gas_price_bump_percent,
+                );
+                if txn.get_gas_price() < min_gas_price {
+                    return Err(MempoolAddTransactionStatus::new(
+                        MempoolAddTransactionStatusCode::GasPriceBumpTooLow,
+                        format!(
+                            "txn gas price: {}, current_version gas price: {}, min gas price: {}",
+                            txn.get_gas_price(),
+                            current_version.get_gas_price(),
+                            min_gas_price
+                        ),
+                    ));
+                }
+                if let Some(txn) = txns.remove(&txn.get_sequence_number()) {
+                    OP_COUNTERS.inc("txn.replaced");
+                    self.index_remove(&txn);
                 }
             }
         }
         Ok(())
     }
 
+    /// returns the lowest gas price replacing a transaction paying `gas_price`
+    fn min_replacement_gas_price(gas_price: u64, bump_percent: u64) -> u64 {
+        let min_gas_price =
+            u128::from(gas_price) + (u128::from(gas_price) * u128::from(bump_percent) + 99) / 100;
+        min_gas_price.min(u128::from(u64::max_value())) as u64
+    }
+
     /// fixes following invariants:
     /// all transactions of given account that are sequential to current sequence number
     /// supposed to be included in both PriorityIndex (ordering for Consensus) and
@@ -268,9 +327,41 @@ impl TransactionStore {
         self.priority_index.remove(&txn);
         self.timeline_index.remove(&txn);
         self.parking_lot_index.remove(&txn);
+        if let Some(journal) = &self.journal {
+            if txn.timeline_state != TimelineState::NonQualified {
+                if let Err(e) = journal.remove(&TxnPointer::from(txn)) {
+                    error!("[Mempool] Failed to remove transaction from journal: {:?}", e);
+                }
+            }
+        }
         self.track_indices();
     }
 
+    /// returns transactions persisted in journal (e.g. before a restart of the node)
+    pub(crate) fn get_journaled_transactions(&self) -> Vec<SignedTransaction> {
+        match &self.journal {
+            Some(journal) => journal.get_all().unwrap_or_else(|e| {
+                error!("[Mempool] Failed to read journal: {:?}", e);
+                vec![]
+            }),
+            None => vec![],
+        }
+    }
+
+    /// removes transactions that are no longer in Mempool from journal
+    /// e.g. journaled transactions that failed revalidation after a restart
+    pub(crate) fn prune_journal(&self) {
+        if let Some(journal) = &self.journal {
+            for txn in self.get_journaled_transactions() {
+                if self.get(&txn.sender(), txn.sequence_number()).is_none() {
+                    if let Err(e) = journal.remove(&(txn.sender(), txn.sequence_number())) {
+                        error!("[Mempool] Failed to remove transaction from journal: {:?}", e);
+                    }
+                }
+            }
+        }
+    }
+
     /// returns gas amount required to process all transactions for given account
     pub(crate) fn get_required_balance(&mut self, address: &AccountAddress) -> u64 {
         self.transactions.get_mut(&address).map_or(0, |txns| {
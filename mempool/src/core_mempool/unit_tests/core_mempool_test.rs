@@ -10,6 +10,7 @@ use crate::core_mempool::{
 };
 use config::config::NodeConfigHelpers;
 use libra_mempool_shared_proto::proto::mempool_status::MempoolAddTransactionStatusCode;
+use libra_tools::tempdir::TempPath;
 use libra_types::transaction::SignedTransaction;
 use std::{collections::HashSet, time::Duration};
 
@@ -359,3 +360,124 @@ fn test_gc_ready_transaction() {
     assert_eq!(timeline.len(), 1);
     assert_eq!(timeline[0].sequence_number(), 0);
 }
+
+#[test]
+fn test_replace_by_fee() {
+    let (mut pool, mut consensus) = setup_mempool();
+    add_txns_to_mempool(
+        &mut pool,
+        vec![TestTransaction::new(0, 0, 10), TestTransaction::new(1, 0, 12)],
+    );
+    let add_update = |pool: &mut CoreMempool, gas_price| {
+        pool.add_txn(
+            TestTransaction::new(0, 0, gas_price).make_signed_transaction(),
+            0,
+            0,
+            1000,
+            TimelineState::NotReady,
+        )
+        .code
+    };
+
+    // the gas price has to be increased by at least 10%
+    assert_eq!(
+        add_update(&mut pool, 10),
+        MempoolAddTransactionStatusCode::InvalidUpdate
+    );
+    assert_eq!(
+        add_update(&mut pool, 9),
+        MempoolAddTransactionStatusCode::InvalidUpdate
+    );
+    assert_eq!(
+        add_update(&mut pool, 11),
+        MempoolAddTransactionStatusCode::Valid
+    );
+    assert_eq!(
+        add_update(&mut pool, 12),
+        MempoolAddTransactionStatusCode::GasPriceBumpTooLow
+    );
+    assert_eq!(
+        add_update(&mut pool, 13),
+        MempoolAddTransactionStatusCode::Valid
+    );
+
+    // the replacement is ordered by its new gas price
+    let block = consensus.get_block(&mut pool, 1);
+    assert_eq!(block[0].sender(), TestTransaction::get_address(0));
+    assert_eq!(block[0].gas_unit_price(), 13);
+}
+
+#[test]
+fn test_parking_lot_eviction_by_gas_price() {
+    let mut config = NodeConfigHelpers::get_single_node_test_config(true);
+    config.mempool.capacity = 3;
+    let mut pool = CoreMempool::new(&config);
+    add_txn(&mut pool, TestTransaction::new(1, 0, 1)).unwrap();
+    // non-ready transactions
+    add_txn(&mut pool, TestTransaction::new(1, 5, 1)).unwrap();
+    add_txn(&mut pool, TestTransaction::new(1, 6, 5)).unwrap();
+
+    // Mempool is full and no non-ready transaction pays less than the new one
+    let status = pool.add_txn(
+        TestTransaction::new(0, 0, 0).make_signed_transaction(),
+        0,
+        0,
+        1000,
+        TimelineState::NotReady,
+    );
+    assert_eq!(status.code, MempoolAddTransactionStatusCode::MempoolIsFull);
+
+    // the non-ready transaction with the lowest gas price is evicted
+    add_txn(&mut pool, TestTransaction::new(0, 0, 2)).unwrap();
+    assert!(add_txn(&mut pool, TestTransaction::new(0, 1, 2)).is_err());
+    add_txn(&mut pool, TestTransaction::new(0, 1, 5)).unwrap();
+
+    let mut txns: Vec<_> = pool
+        .get_block(5, HashSet::new())
+        .iter()
+        .map(|txn| (txn.sequence_number(), txn.gas_unit_price()))
+        .collect();
+    txns.sort();
+    assert_eq!(txns, vec![(0, 1), (0, 2), (1, 5)]);
+}
+
+#[test]
+fn test_journal() {
+    let tmp_dir = TempPath::new();
+    let mut config = NodeConfigHelpers::get_single_node_test_config(true);
+    config.storage.dir = tmp_dir.path().to_path_buf();
+    config.mempool.persist_transactions = true;
+
+    let mut pool = CoreMempool::new(&config);
+    let txns = add_txns_to_mempool(
+        &mut pool,
+        vec![TestTransaction::new(0, 0, 1), TestTransaction::new(0, 1, 1)],
+    );
+    // transactions received from other peers are not journaled
+    pool.add_txn(
+        TestTransaction::new(1, 0, 1).make_signed_transaction(),
+        0,
+        0,
+        1000,
+        TimelineState::NonQualified,
+    );
+    // committed transactions are removed from journal
+    pool.remove_transaction(&TestTransaction::get_address(0), 0, false);
+    assert_eq!(pool.get_journaled_transactions(), vec![txns[1].clone()]);
+
+    // journal survives a restart
+    drop(pool);
+    let mut pool = CoreMempool::new(&config);
+    assert!(pool.get_block(10, HashSet::new()).is_empty());
+    let journaled = pool.get_journaled_transactions();
+    assert_eq!(journaled, vec![txns[1].clone()]);
+
+    // journaled transactions that are not added back to Mempool get pruned
+    pool.prune_journal();
+    assert!(pool.get_journaled_transactions().is_empty());
+    for txn in journaled {
+        add_signed_txn(&mut pool, txn).unwrap();
+    }
+    pool.prune_journal();
+    assert_eq!(pool.get_journaled_transactions(), vec![txns[1].clone()]);
+}
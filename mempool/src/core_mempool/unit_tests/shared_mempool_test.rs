@@ -44,39 +44,57 @@ impl SharedMempoolNetwork {
         config.mempool.shared_mempool_batch_size = 1;
 
         for peer in peers {
-            let mempool = Arc::new(Mutex::new(CoreMempool::new(&config)));
-            let (network_reqs_tx, network_reqs_rx) = channel::new_test(8);
-            let (network_notifs_tx, network_notifs_rx) = channel::new_test(8);
-            let network_sender = MempoolNetworkSender::new(network_reqs_tx);
-            let network_events = MempoolNetworkEvents::new(network_notifs_rx);
-            let (sender, subscriber) = unbounded();
-            let (timer_sender, timer_receiver) = unbounded();
-
-            let runtime = start_shared_mempool(
-                &config,
-                Arc::clone(&mempool),
-                network_sender,
-                network_events,
-                Arc::new(MockStorageReadClient),
-                Arc::new(MockVMValidator),
-                vec![sender],
-                Some(timer_receiver.compat().map(|_| SyncEvent).boxed()),
-            );
-
-            smp.mempools.insert(peer, mempool);
-            smp.network_reqs_rxs.insert(peer, network_reqs_rx);
-            smp.network_notifs_txs.insert(peer, network_notifs_tx);
-            smp.subscribers.insert(peer, subscriber);
-            smp.timers.insert(peer, timer_sender);
-            smp.runtimes.insert(peer, runtime);
+            smp.add_node(peer, &config);
         }
         smp
     }
 
+    fn add_node(&mut self, peer: PeerId, config: &NodeConfig) {
+        let mempool = Arc::new(Mutex::new(CoreMempool::new(config)));
+        let (network_reqs_tx, network_reqs_rx) = channel::new_test(8);
+        let (network_notifs_tx, network_notifs_rx) = channel::new_test(8);
+        let network_sender = MempoolNetworkSender::new(network_reqs_tx);
+        let network_events = MempoolNetworkEvents::new(network_notifs_rx);
+        let (sender, subscriber) = unbounded();
+        let (timer_sender, timer_receiver) = unbounded();
+
+        let runtime = start_shared_mempool(
+            config,
+            Arc::clone(&mempool),
+            vec![(network_sender, network_events)],
+            Arc::new(MockStorageReadClient),
+            Arc::new(MockVMValidator),
+            vec![sender],
+            Some(timer_receiver.compat().map(|_| SyncEvent).boxed()),
+        );
+
+        self.mempools.insert(peer, mempool);
+        self.network_reqs_rxs.insert(peer, network_reqs_rx);
+        self.network_notifs_txs.insert(peer, network_notifs_tx);
+        self.subscribers.insert(peer, subscriber);
+        self.timers.insert(peer, timer_sender);
+        self.runtimes.insert(peer, runtime);
+    }
+
     fn bootstrap(peers: Vec<PeerId>) -> Self {
         Self::bootstrap_with_config(peers, NodeConfigHelpers::get_single_node_test_config(true))
     }
 
+    /// The config of a full node with the upstream peers `upstream_peer_ids`, by order of
+    /// preference.
+    fn full_node_config(upstream_peer_ids: &[PeerId]) -> NodeConfig {
+        let mut config = NodeConfigHelpers::get_single_node_test_config(true);
+        config.mempool.shared_mempool_batch_size = 1;
+        for network in config.networks.iter_mut() {
+            network.role = "full_node".to_string();
+        }
+        config.state_sync.upstream_peers.upstream_peers = upstream_peer_ids
+            .iter()
+            .map(|peer_id| format!("{:x}", peer_id))
+            .collect();
+        config
+    }
+
     fn add_txns(&mut self, peer_id: &PeerId, txns: Vec<TestTransaction>) {
         let mut mempool = self.mempools.get(peer_id).unwrap().lock().unwrap();
         for txn in txns {
@@ -337,3 +355,77 @@ fn test_broadcast_updated_transaction() {
     assert_eq!(txn.sequence_number(), 0);
     assert_eq!(txn.gas_unit_price(), 5);
 }
+
+#[test]
+fn test_full_node_upstream_failover() {
+    let (full_node, downstream) = (PeerId::random(), PeerId::random());
+    let (upstream_a, upstream_b) = (PeerId::random(), PeerId::random());
+    let mut smp = SharedMempoolNetwork::bootstrap(vec![upstream_a, upstream_b]);
+    smp.add_node(full_node, &SharedMempoolNetwork::full_node_config(&[upstream_a, upstream_b]));
+    smp.add_node(downstream, &SharedMempoolNetwork::full_node_config(&[full_node]));
+    smp.add_txns(&full_node, vec![TestTransaction::new(1, 0, 1)]);
+
+    // The full node discovers a downstream peer and both its upstream peers
+    smp.send_event(&full_node, NetworkNotification::NewPeer(downstream));
+    smp.send_event(&full_node, NetworkNotification::NewPeer(upstream_b));
+    smp.send_event(&full_node, NetworkNotification::NewPeer(upstream_a));
+
+    // Only the preferred upstream peer receives transactions
+    let (txn, peer_id) = smp.deliver_message(&full_node);
+    assert_eq!(peer_id, upstream_a);
+    assert_eq!(txn.sequence_number(), 0);
+    smp.add_txns(&full_node, vec![TestTransaction::new(1, 1, 1)]);
+    let (txn, peer_id) = smp.deliver_message(&full_node);
+    assert_eq!(peer_id, upstream_a);
+    assert_eq!(txn.sequence_number(), 1);
+
+    // The other upstream peer takes over when the preferred one is lost, receiving all the
+    // pending transactions
+    smp.send_event(&full_node, NetworkNotification::LostPeer(upstream_a));
+    for seq in 0..2 {
+        let (txn, peer_id) = smp.deliver_message(&full_node);
+        assert_eq!(peer_id, upstream_b);
+        assert_eq!(txn.sequence_number(), seq);
+    }
+}
+
+#[test]
+fn test_full_node_relay_downstream_transactions() {
+    let (full_node, downstream, upstream) = (PeerId::random(), PeerId::random(), PeerId::random());
+    let mut smp = SharedMempoolNetwork::bootstrap(vec![upstream]);
+    smp.add_node(full_node, &SharedMempoolNetwork::full_node_config(&[upstream]));
+    smp.add_node(downstream, &SharedMempoolNetwork::full_node_config(&[full_node]));
+    smp.add_txns(&downstream, vec![TestTransaction::new(1, 0, 1)]);
+
+    smp.send_event(&downstream, NetworkNotification::NewPeer(full_node));
+    smp.send_event(&full_node, NetworkNotification::NewPeer(downstream));
+    smp.send_event(&full_node, NetworkNotification::NewPeer(upstream));
+
+    // The transaction goes from the downstream peer to the upstream one through the full node
+    let (txn, peer_id) = smp.deliver_message(&downstream);
+    assert_eq!(peer_id, full_node);
+    let (relayed_txn, peer_id) = smp.deliver_message(&full_node);
+    assert_eq!(peer_id, upstream);
+    assert_eq!(relayed_txn, txn);
+}
+
+#[test]
+fn test_full_node_rebroadcast() {
+    let (full_node, upstream) = (PeerId::random(), PeerId::random());
+    let mut smp = SharedMempoolNetwork::bootstrap(vec![upstream]);
+    let mut config = SharedMempoolNetwork::full_node_config(&[upstream]);
+    // The pending transactions are sent again every other tick
+    config.mempool.shared_mempool_rebroadcast_interval_ms =
+        2 * config.mempool.shared_mempool_tick_interval_ms;
+    smp.add_node(full_node, &config);
+    smp.add_txns(&full_node, vec![TestTransaction::new(1, 0, 1)]);
+
+    smp.send_event(&full_node, NetworkNotification::NewPeer(upstream));
+    let (txn, _) = smp.deliver_message(&full_node);
+    assert_eq!(txn.sequence_number(), 0);
+
+    // The pending transaction is sent again on the next tick in case it was dropped upstream
+    let (txn, peer_id) = smp.deliver_message(&full_node);
+    assert_eq!(peer_id, upstream);
+    assert_eq!(txn.sequence_number(), 0);
+}
@@ -27,10 +27,11 @@ pub struct MempoolRuntime {
 
 impl MempoolRuntime {
     /// setup Mempool runtime
+    /// `network` holds the sender and the events of each network of the node, in the order of
+    /// `config.networks`
     pub fn bootstrap(
         config: &NodeConfig,
-        network_sender: MempoolNetworkSender,
-        network_events: MempoolNetworkEvents,
+        network: Vec<(MempoolNetworkSender, MempoolNetworkEvents)>,
     ) -> Self {
         let mempool = Arc::new(Mutex::new(CoreMempool::new(&config)));
 
@@ -64,8 +65,7 @@ impl MempoolRuntime {
         let shared_mempool = start_shared_mempool(
             config,
             mempool,
-            network_sender,
-            network_events,
+            network,
             storage_client,
             vm_validator,
             vec![],
@@ -6,9 +6,16 @@ use crate::{
     OP_COUNTERS,
 };
 use bounded_executor::BoundedExecutor;
-use config::config::{MempoolConfig, NodeConfig};
+use config::config::{MempoolConfig, NodeConfig, RoleType};
 use futures::sync::mpsc::UnboundedSender;
-use futures_preview::{compat::Future01CompatExt, future::join_all, Stream, StreamExt};
+use futures_preview::{
+    compat::Future01CompatExt,
+    future::join_all,
+    stream::{select_all, Stream, StreamExt},
+};
+use libra_mempool_shared_proto::{
+    proto::mempool_status::MempoolAddTransactionStatusCode, MempoolAddTransactionStatus,
+};
 use libra_types::{transaction::SignedTransaction, PeerId};
 use logger::prelude::*;
 use network::{
@@ -16,12 +23,13 @@ use network::{
     validator_network::{Event, MempoolNetworkEvents, MempoolNetworkSender},
 };
 use std::{
+    cmp,
     collections::HashMap,
     convert::{TryFrom, TryInto},
     ops::Deref,
     pin::Pin,
     sync::{Arc, Mutex},
-    time::Duration,
+    time::{Duration, SystemTime, UNIX_EPOCH},
 };
 use storage_client::StorageRead;
 use tokio::{
@@ -33,10 +41,12 @@ use vm_validator::vm_validator::{get_account_state, TransactionValidation};
 /// state of last sync with peer
 /// `timeline_id` is position in log of ready transactions
 /// `is_alive` - is connection healthy
+/// `network_id` - index of the network the peer is connected on
 #[derive(Clone)]
 struct PeerSyncState {
     timeline_id: u64,
     is_alive: bool,
+    network_id: usize,
 }
 
 type PeerInfo = HashMap<PeerId, PeerSyncState>;
@@ -60,11 +70,16 @@ where
     V: TransactionValidation + 'static,
 {
     mempool: Arc<Mutex<CoreMempool>>,
-    network_sender: MempoolNetworkSender,
+    // one sender per network of the node, in the order of the network configs
+    network_senders: Vec<MempoolNetworkSender>,
     config: MempoolConfig,
     storage_read_client: Arc<dyn StorageRead>,
     validator: Arc<V>,
     peer_info: Arc<Mutex<PeerInfo>>,
+    // index of the validator network if the node is a validator
+    validator_network_id: Option<usize>,
+    // upstream peers of a full node, by order of preference
+    upstream_peer_ids: Vec<PeerId>,
     subscribers: Vec<UnboundedSender<SharedMempoolNotification>>,
 }
 
@@ -77,16 +92,55 @@ where
     fn clone(&self) -> Self {
         Self {
             mempool: Arc::clone(&self.mempool),
-            network_sender: self.network_sender.clone(),
+            network_senders: self.network_senders.clone(),
             config: self.config.clone(),
             storage_read_client: Arc::clone(&self.storage_read_client),
             validator: Arc::clone(&self.validator),
             peer_info: self.peer_info.clone(),
+            validator_network_id: self.validator_network_id,
+            upstream_peer_ids: self.upstream_peer_ids.clone(),
             subscribers: self.subscribers.clone(),
         }
     }
 }
 
+impl<V> SharedMempool<V>
+where
+    V: TransactionValidation + 'static,
+{
+    /// Returns true if `peer_id`, connected on the network `network_id`, is upstream: either
+    /// another validator for a validator, or an upstream peer for a full node. Transactions are
+    /// only broadcast upstream, while the ones received from downstream are broadcast in turn.
+    fn is_upstream_peer(&self, network_id: usize, peer_id: &PeerId) -> bool {
+        Some(network_id) == self.validator_network_id || self.upstream_peer_ids.contains(peer_id)
+    }
+
+    /// Returns the alive peers to broadcast transactions to: all the other validators for a
+    /// validator. A full node only broadcasts to its most preferred upstream peer alive, the next
+    /// one taking over when it is lost.
+    fn get_broadcast_peers(&self, peer_info: &PeerInfo) -> Vec<PeerId> {
+        let is_alive_upstream = |peer_id: &PeerId| {
+            peer_info.get(peer_id).map_or(false, |state| {
+                state.is_alive && self.is_upstream_peer(state.network_id, peer_id)
+            })
+        };
+        if self.validator_network_id.is_some() {
+            peer_info
+                .keys()
+                .filter(|peer_id| is_alive_upstream(peer_id))
+                .cloned()
+                .collect()
+        } else {
+            self.upstream_peer_ids
+                .iter()
+                .find(|peer_id| is_alive_upstream(peer_id))
+                .cloned()
+                .into_iter()
+                .collect()
+        }
+    }
+}
+
 fn notify_subscribers(
     event: SharedMempoolNotification,
     subscribers: &[UnboundedSender<SharedMempoolNotification>],
@@ -104,16 +158,17 @@ fn default_timer(tick_ms: u64) -> IntervalStream {
 
 /// new peer discovery handler
 /// adds new entry to `peer_info`
-fn new_peer(peer_info: &Mutex<PeerInfo>, peer_id: PeerId) {
-    peer_info
+fn new_peer(peer_info: &Mutex<PeerInfo>, peer_id: PeerId, network_id: usize) {
+    let mut peer_info = peer_info
         .lock()
-        .expect("[shared mempool] failed to acquire peer_info lock")
-        .entry(peer_id)
-        .or_insert(PeerSyncState {
-            timeline_id: 0,
-            is_alive: true,
-        })
-        .is_alive = true;
+        .expect("[shared mempool] failed to acquire peer_info lock");
+    let state = peer_info.entry(peer_id).or_insert(PeerSyncState {
+        timeline_id: 0,
+        is_alive: true,
+        network_id,
+    });
+    state.is_alive = true;
+    state.network_id = network_id;
 }
 
 /// lost peer handler. Marks connection as dead
@@ -129,16 +184,15 @@ fn lost_peer(peer_info: &Mutex<PeerInfo>, peer_id: PeerId) {
 
 /// sync routine
 /// used to periodically broadcast ready to go transactions to peers
-async fn sync_with_peers<'a>(
-    peer_info: &'a Mutex<PeerInfo>,
-    mempool: &'a Mutex<CoreMempool>,
-    network_sender: &'a mut MempoolNetworkSender,
-    batch_size: usize,
-) {
+async fn sync_with_peers<V>(smp: &mut SharedMempool<V>, batch_size: usize)
+where
+    V: TransactionValidation,
+{
     // Clone the underlying peer_info map and use this to sync and collect
     // state updates. We do this instead of holding the lock for the whole
     // function since that would hold the lock across await points which is bad.
-    let peer_info_copy = peer_info
+    let peer_info_copy = smp
+        .peer_info
         .lock()
         .expect("[shared mempool] failed to acquire peer_info lock")
         .deref()
@@ -146,11 +200,12 @@ async fn sync_with_peers<'a>(
 
     let mut state_updates = vec![];
 
-    for (peer_id, peer_state) in peer_info_copy.into_iter() {
-        if peer_state.is_alive {
+    for peer_id in smp.get_broadcast_peers(&peer_info_copy) {
+        if let Some(peer_state) = peer_info_copy.get(&peer_id) {
             let timeline_id = peer_state.timeline_id;
 
-            let (transactions, new_timeline_id) = mempool
+            let (transactions, new_timeline_id) = smp
+                .mempool
                 .lock()
                 .expect("[shared mempool] failed to acquire mempool lock")
                 .read_timeline(timeline_id, batch_size);
@@ -170,7 +225,7 @@ async fn sync_with_peers<'a>(
                 );
                 // Since this is a direct-send, this will only error if the network
                 // module has unexpectedly crashed or shutdown.
-                network_sender
+                smp.network_senders[peer_state.network_id]
                     .send_to(peer_id, msg)
                     .await
                     .expect("[shared mempool] failed to direct-send mempool sync message");
@@ -181,7 +236,8 @@ async fn sync_with_peers<'a>(
     }
 
     // Lock the shared peer_info and apply state updates.
-    let mut peer_info = peer_info
+    let mut peer_info = smp
+        .peer_info
         .lock()
         .expect("[shared mempool] failed to acquire peer_info lock");
     for (peer_id, new_timeline_id) in state_updates {
@@ -191,12 +247,14 @@ async fn sync_with_peers<'a>(
     }
 }
 
-/// used to validate incoming transactions and add them to local Mempool
-async fn process_incoming_transactions<V>(
-    smp: SharedMempool<V>,
-    peer_id: PeerId,
+/// used to validate transactions and add them to local Mempool
+/// returns insertion status for every transaction that passed validation
+async fn validate_and_add_transactions<V>(
+    smp: &SharedMempool<V>,
     transactions: Vec<SignedTransaction>,
-) where
+    timeline_state: TimelineState,
+) -> Vec<Option<MempoolAddTransactionStatus>>
+where
     V: TransactionValidation,
 {
     let account_states = join_all(
@@ -227,53 +285,182 @@ async fn process_incoming_transactions<V>(
     )
     .await;
 
-    {
-        let mut mempool = smp
-            .mempool
-            .lock()
-            .expect("[shared mempool] failed to acquire mempool lock");
+    let mut mempool = smp
+        .mempool
+        .lock()
+        .expect("[shared mempool] failed to acquire mempool lock");
 
-        for (idx, (transaction, sequence_number, balance)) in transactions.into_iter().enumerate() {
+    transactions
+        .into_iter()
+        .enumerate()
+        .map(|(idx, (transaction, sequence_number, balance))| {
             if let Ok(None) = validations[idx] {
                 let gas_cost = transaction.max_gas_amount();
-                let insertion_result = mempool.add_txn(
+                Some(mempool.add_txn(
                     transaction,
                     gas_cost,
                     sequence_number,
                     balance,
-                    TimelineState::NonQualified,
-                );
-                OP_COUNTERS.inc(&format!(
-                    "smp.transactions.status.{:?}.{:?}",
-                    insertion_result.code, peer_id
-                ));
+                    timeline_state,
+                ))
             } else {
-                OP_COUNTERS.inc(&format!(
-                    "smp.transactions.status.validation_failed.{:?}",
-                    peer_id
-                ));
+                None
             }
+        })
+        .collect()
+}
+
+/// used to validate incoming transactions and add them to local Mempool
+async fn process_incoming_transactions<V>(
+    smp: SharedMempool<V>,
+    peer_id: PeerId,
+    network_id: usize,
+    transactions: Vec<SignedTransaction>,
+) where
+    V: TransactionValidation,
+{
+    // The transactions received from upstream were already broadcast to the peers they are meant
+    // for, while the ones received from downstream have to be relayed upstream.
+    let timeline_state = if smp.is_upstream_peer(network_id, &peer_id) {
+        TimelineState::NonQualified
+    } else {
+        TimelineState::NotReady
+    };
+    let statuses = validate_and_add_transactions(&smp, transactions, timeline_state).await;
+    for status in statuses {
+        match status {
+            Some(status) => OP_COUNTERS.inc(&format!(
+                "smp.transactions.status.{:?}.{:?}",
+                status.code, peer_id
+            )),
+            None => OP_COUNTERS.inc(&format!(
+                "smp.transactions.status.validation_failed.{:?}",
+                peer_id
+            )),
         }
     }
     notify_subscribers(SharedMempoolNotification::NewTransactions, &smp.subscribers);
 }
 
+/// Revalidates transactions journaled before a restart of the node and adds them back to Mempool
+async fn restore_journal_task<V>(smp: SharedMempool<V>)
+where
+    V: TransactionValidation,
+{
+    let transactions = smp
+        .mempool
+        .lock()
+        .expect("[shared mempool] failed to acquire mempool lock")
+        .get_journaled_transactions();
+    if transactions.is_empty() {
+        return;
+    }
+    let journaled = transactions.len();
+    let statuses =
+        validate_and_add_transactions(&smp, transactions, TimelineState::NotReady).await;
+    let restored = statuses
+        .iter()
+        .filter(|status| match status {
+            Some(status) => status.code == MempoolAddTransactionStatusCode::Valid,
+            None => false,
+        })
+        .count();
+    smp.mempool
+        .lock()
+        .expect("[shared mempool] failed to acquire mempool lock")
+        .prune_journal();
+    OP_COUNTERS.inc_by("smp.transactions.restored", restored);
+    info!(
+        "[shared mempool] restored {} out of {} journaled transactions",
+        restored, journaled
+    );
+    notify_subscribers(SharedMempoolNotification::NewTransactions, &smp.subscribers);
+}
+
+/// Prepares the pending transactions of a full node to be broadcast again from the start
+/// Unlike validators, full nodes are not notified by consensus when transactions are committed, so
+/// the transactions committed in storage and the expired ones are removed first
+async fn rebroadcast_pending_transactions<V>(smp: &SharedMempool<V>)
+where
+    V: TransactionValidation,
+{
+    let accounts = smp
+        .mempool
+        .lock()
+        .expect("[shared mempool] failed to acquire mempool lock")
+        .get_accounts();
+    let account_states = join_all(
+        accounts
+            .iter()
+            .map(|account| get_account_state(smp.storage_read_client.clone(), *account)),
+    )
+    .await;
+
+    {
+        let mut mempool = smp
+            .mempool
+            .lock()
+            .expect("[shared mempool] failed to acquire mempool lock");
+        for (account, account_state) in accounts.iter().zip(account_states) {
+            match account_state {
+                // all the transactions of the account up to this one were committed
+                Ok((sequence_number, _)) if sequence_number > 0 => {
+                    mempool.remove_transaction(account, sequence_number - 1, false);
+                }
+                Ok(_) => (),
+                Err(e) => warn!(
+                    "[shared mempool] failed to fetch account state of {}: {:?}",
+                    account, e
+                ),
+            }
+        }
+        let now = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .expect("[shared mempool] system time is before the UNIX epoch");
+        mempool.gc_by_expiration_time(now);
+    }
+    OP_COUNTERS.inc("smp.rebroadcast");
+
+    for state in smp
+        .peer_info
+        .lock()
+        .expect("[shared mempool] failed to acquire peer_info lock")
+        .values_mut()
+    {
+        state.timeline_id = 0;
+    }
+}
+
 /// This task handles [`SyncEvent`], which is periodically emitted for us to
 /// broadcast ready to go transactions to peers.
-async fn outbound_sync_task<V>(smp: SharedMempool<V>, mut interval: IntervalStream)
+async fn outbound_sync_task<V>(mut smp: SharedMempool<V>, mut interval: IntervalStream)
 where
     V: TransactionValidation,
 {
-    let peer_info = smp.peer_info;
-    let mempool = smp.mempool;
-    let mut network_sender = smp.network_sender;
     let batch_size = smp.config.shared_mempool_batch_size;
-    let subscribers = smp.subscribers;
+    // Full nodes keep broadcasting their pending transactions in case they were dropped upstream,
+    // once every `rebroadcast_ticks` sync events
+    let rebroadcast_ticks = match smp.config.shared_mempool_rebroadcast_interval_ms {
+        0 => None,
+        _ if smp.validator_network_id.is_some() => None,
+        interval_ms => Some(cmp::max(
+            interval_ms / cmp::max(smp.config.shared_mempool_tick_interval_ms, 1),
+            1,
+        )),
+    };
+    let mut ticks_since_rebroadcast = 0;
 
     while let Some(sync_event) = interval.next().await {
         trace!("SyncEvent: {:?}", sync_event);
-        sync_with_peers(&peer_info, &mempool, &mut network_sender, batch_size).await;
-        notify_subscribers(SharedMempoolNotification::Sync, &subscribers);
+        if let Some(rebroadcast_ticks) = rebroadcast_ticks {
+            ticks_since_rebroadcast += 1;
+            if ticks_since_rebroadcast >= rebroadcast_ticks {
+                rebroadcast_pending_transactions(&smp).await;
+                ticks_since_rebroadcast = 0;
+            }
+        }
+        sync_with_peers(&mut smp, batch_size).await;
+        notify_subscribers(SharedMempoolNotification::Sync, &smp.subscribers);
     }
 
     crit!("SharedMempool outbound_sync_task terminated");
@@ -283,7 +470,7 @@ where
 async fn inbound_network_task<V>(
     smp: SharedMempool<V>,
     executor: TaskExecutor,
-    mut network_events: MempoolNetworkEvents,
+    network_events: Vec<MempoolNetworkEvents>,
 ) where
     V: TransactionValidation,
 {
@@ -295,13 +482,20 @@ async fn inbound_network_task<V>(
     let workers_available = smp.config.shared_mempool_max_concurrent_inbound_syncs;
     let bounded_executor = BoundedExecutor::new(workers_available, executor);
 
-    while let Some(event) = network_events.next().await {
+    let events: Vec<_> = network_events
+        .into_iter()
+        .enumerate()
+        .map(|(network_id, events)| events.map(move |e| (network_id, e)))
+        .collect();
+    let mut network_events = select_all(events);
+
+    while let Some((network_id, event)) = network_events.next().await {
         trace!("SharedMempoolEvent::NetworkEvent::{:?}", event);
         match event {
             Ok(network_event) => match network_event {
                 Event::NewPeer(peer_id) => {
                     OP_COUNTERS.inc("smp.event.new_peer");
-                    new_peer(&peer_info, peer_id);
+                    new_peer(&peer_info, peer_id, network_id);
                     notify_subscribers(SharedMempoolNotification::PeerStateChange, &subscribers);
                 }
                 Event::LostPeer(peer_id) => {
@@ -334,6 +528,7 @@ async fn inbound_network_task<V>(
                         .spawn(process_incoming_transactions(
                             smp.clone(),
                             peer_id,
+                            network_id,
                             transactions,
                         ))
                         .await;
@@ -374,11 +569,13 @@ async fn gc_task(mempool: Arc<Mutex<CoreMempool>>, gc_interval_ms: u64) {
 ///   - outbound_sync_task (task that periodically broadcasts transactions to peers)
 ///   - inbound_network_task (task that handles inbound mempool messages and network events)
 ///   - gc_task (task that performs GC of all expired transactions by SystemTTL)
+///   - restore_journal_task (task that adds back transactions journaled before a restart)
+/// `network` holds the sender and the events of each network of the node, in the order of
+/// `config.networks`
 pub(crate) fn start_shared_mempool<V>(
     config: &NodeConfig,
     mempool: Arc<Mutex<CoreMempool>>,
-    network_sender: MempoolNetworkSender,
-    network_events: MempoolNetworkEvents,
+    network: Vec<(MempoolNetworkSender, MempoolNetworkEvents)>,
     storage_read_client: Arc<dyn StorageRead>,
     validator: Arc<V>,
     subscribers: Vec<UnboundedSender<SharedMempoolNotification>>,
@@ -394,20 +591,29 @@ where
     let executor = runtime.executor();
 
     let peer_info = Arc::new(Mutex::new(PeerInfo::new()));
+    let (network_senders, network_events): (Vec<_>, Vec<_>) = network.into_iter().unzip();
+    let validator_network_id = config
+        .networks
+        .iter()
+        .position(|network| RoleType::Validator == (&network.role).into());
 
     let smp = SharedMempool {
         mempool: mempool.clone(),
         config: config.mempool.clone(),
-        network_sender,
+        network_senders,
         storage_read_client,
         validator,
         peer_info,
+        validator_network_id,
+        upstream_peer_ids: config.get_upstream_peer_ids(),
         subscribers,
     };
 
     let interval =
         timer.unwrap_or_else(|| default_timer(config.mempool.shared_mempool_tick_interval_ms));
 
+    executor.spawn(restore_journal_task(smp.clone()));
+
     executor.spawn(outbound_sync_task(smp.clone(), interval));
 
     executor.spawn(inbound_network_task(smp, executor.clone(), network_events));
@@ -54,6 +54,18 @@ lazy_static::lazy_static! {
     /// Counter of bytes received via the direct send protocol
     pub static ref DIRECT_SEND_BYTES_RECEIVED: IntCounter = OP_COUNTERS.counter("direct_send_bytes_received");
 
+    /// Counter of inbound messages delayed because their peer exceeded its rate limits
+    pub static ref INBOUND_MESSAGES_THROTTLED: IntCounter = OP_COUNTERS.counter("inbound_messages_throttled");
+
+    /// Counter of inbound rpc requests rejected because their peer exceeded its rate limits
+    pub static ref INBOUND_RPCS_REJECTED: IntCounter = OP_COUNTERS.counter("inbound_rpcs_rejected");
+
+    /// Histogram of the delays imposed on throttled inbound messages
+    pub static ref INBOUND_THROTTLE_DELAY: Histogram = OP_COUNTERS.histogram("inbound_throttle_delay");
+
+    /// Counter of peers disconnected because they kept exceeding their rate limits
+    pub static ref RATE_LIMITED_PEERS_DISCONNECTED: IntCounter = OP_COUNTERS.counter("rate_limited_peers_disconnected");
+
     ///
     /// Channel Counters
     ///
@@ -20,6 +20,7 @@ mod connectivity_manager;
 mod counters;
 mod error;
 mod peer_manager;
+mod rate_limiter;
 mod sink;
 mod transport;
 mod utils;
@@ -33,6 +33,6 @@ pub use self::{
     },
     state_synchronizer::{
         state_synchronizer_msg::Message as StateSynchronizerMsg_oneof, GetChunkRequest,
-        GetChunkResponse, StateSynchronizerMsg,
+        GetChunkResponse, GetStateChunkRequest, GetStateChunkResponse, StateSynchronizerMsg,
     },
 };
@@ -47,6 +47,7 @@ use crate::{
     counters,
     error::NetworkError,
     peer_manager::{PeerManagerNotification, PeerManagerRequestSender},
+    rate_limiter::InboundRateLimiter,
     ProtocolId,
 };
 use bytes::Bytes;
@@ -120,6 +121,8 @@ pub struct DirectSend<TSubstream> {
     peer_mgr_reqs_tx: PeerManagerRequestSender<TSubstream>,
     /// Outbound message queues for each (PeerId, ProtocolId) pair.
     message_queues: HashMap<(PeerId, ProtocolId), channel::Sender<Bytes>>,
+    /// Limits on the inbound messages of each peer.
+    rate_limiter: InboundRateLimiter,
 }
 
 impl<TSubstream> DirectSend<TSubstream>
@@ -132,6 +135,7 @@ where
         ds_notifs_tx: channel::Sender<DirectSendNotification>,
         peer_mgr_notifs_rx: channel::Receiver<PeerManagerNotification<TSubstream>>,
         peer_mgr_reqs_tx: PeerManagerRequestSender<TSubstream>,
+        rate_limiter: InboundRateLimiter,
     ) -> Self {
         Self {
             executor,
@@ -140,6 +144,7 @@ where
             peer_mgr_notifs_rx,
             peer_mgr_reqs_tx,
             message_queues: HashMap::new(),
+            rate_limiter,
         }
     }
 
@@ -170,23 +175,35 @@ where
                     substream.protocol,
                     substream.substream,
                     self.ds_notifs_tx.clone(),
+                    self.peer_mgr_reqs_tx.clone(),
+                    self.rate_limiter.clone(),
                 ));
             }
             _ => unreachable!("Unexpected PeerManagerNotification"),
         }
     }
 
-    // Handle a new inbound substream. Keep forwarding the messages to the NetworkProvider.
+    // Handle a new inbound substream. Keep forwarding the messages to the NetworkProvider, as
+    // fast as the rate limits of the peer allow.
     async fn handle_inbound_substream(
         peer_id: PeerId,
         protocol: ProtocolId,
         substream: TSubstream,
         mut ds_notifs_tx: channel::Sender<DirectSendNotification>,
+        mut peer_mgr_reqs_tx: PeerManagerRequestSender<TSubstream>,
+        rate_limiter: InboundRateLimiter,
     ) {
         let mut substream = Framed::new(IoCompat::new(substream), LengthDelimitedCodec::new());
         while let Some(item) = substream.next().await {
             match item {
                 Ok(data) => {
+                    if !rate_limiter
+                        .throttle(&mut peer_mgr_reqs_tx, peer_id, &protocol, data.len())
+                        .await
+                    {
+                        counters::DIRECT_SEND_MESSAGES_DROPPED.inc();
+                        break;
+                    }
                     let notif = DirectSendNotification::RecvMessage(
                         peer_id,
                         Message {
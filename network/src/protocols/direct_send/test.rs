@@ -7,10 +7,12 @@ use crate::{
         PeerManagerError, PeerManagerNotification, PeerManagerRequest, PeerManagerRequestSender,
     },
     protocols::direct_send::{DirectSend, DirectSendNotification, DirectSendRequest, Message},
+    rate_limiter::InboundRateLimiter,
     ProtocolId,
 };
 use bytes::Bytes;
 use channel;
+use config::config::RateLimitConfig;
 use futures::{sink::SinkExt, stream::StreamExt};
 use libra_types::PeerId;
 use memsocket::MemorySocket;
@@ -33,6 +35,18 @@ fn start_direct_send_actor(
     channel::Receiver<DirectSendNotification>,
     channel::Sender<PeerManagerNotification<MemorySocket>>,
     channel::Receiver<PeerManagerRequest<MemorySocket>>,
+) {
+    start_rate_limited_direct_send_actor(executor, InboundRateLimiter::unlimited())
+}
+
+fn start_rate_limited_direct_send_actor(
+    executor: TaskExecutor,
+    rate_limiter: InboundRateLimiter,
+) -> (
+    channel::Sender<DirectSendRequest>,
+    channel::Receiver<DirectSendNotification>,
+    channel::Sender<PeerManagerNotification<MemorySocket>>,
+    channel::Receiver<PeerManagerRequest<MemorySocket>>,
 ) {
     let (ds_requests_tx, ds_requests_rx) = channel::new_test(8);
     let (ds_notifs_tx, ds_notifs_rx) = channel::new_test(8);
@@ -44,6 +58,7 @@ fn start_direct_send_actor(
         ds_notifs_tx,
         peer_mgr_notifs_rx,
         PeerManagerRequestSender::new(peer_mgr_reqs_tx),
+        rate_limiter,
     );
     executor.spawn(direct_send.start());
 
@@ -179,6 +194,68 @@ fn test_inbound_substream_closed() {
     rt.block_on(f_network_provider);
 }
 
+#[test]
+fn test_inbound_rate_limit() {
+    let rt = Runtime::new().unwrap();
+
+    // A single message per second is allowed and the first throttled one disconnects the peer.
+    let rate_limiter = InboundRateLimiter::new(RateLimitConfig {
+        bytes_per_sec: 0,
+        burst_bytes: 0,
+        messages_per_sec: 1,
+        burst_messages: 1,
+        max_throttled_messages: 0,
+        disconnect_cool_down_secs: 0,
+    });
+    let (_ds_requests_tx, mut ds_notifs_rx, mut peer_mgr_notifs_tx, mut peer_mgr_reqs_rx) =
+        start_rate_limited_direct_send_actor(rt.executor(), rate_limiter);
+
+    let peer_id = PeerId::random();
+    let (dialer_substream, listener_substream) = MemorySocket::new_pair();
+
+    // The dialer sends two messages to the listener.
+    let f_substream = async move {
+        let mut dialer_substream =
+            Framed::new(IoCompat::new(dialer_substream), LengthDelimitedCodec::new());
+        dialer_substream
+            .send(Bytes::from_static(MESSAGE_1))
+            .await
+            .unwrap();
+        dialer_substream
+            .send(Bytes::from_static(MESSAGE_2))
+            .await
+            .unwrap();
+    };
+
+    // Fake the listener NetworkProvider
+    let f_network_provider = async move {
+        peer_mgr_notifs_tx
+            .send(PeerManagerNotification::NewInboundSubstream(
+                peer_id,
+                NegotiatedSubstream {
+                    protocol: ProtocolId::from_static(&PROTOCOL_1[..]),
+                    substream: listener_substream,
+                },
+            ))
+            .await
+            .unwrap();
+
+        // The first message is received, the second one exceeds the limits.
+        expect_network_provider_recv_message(&mut ds_notifs_rx, peer_id, PROTOCOL_1, MESSAGE_1)
+            .await;
+        match peer_mgr_reqs_rx.next().await.unwrap() {
+            PeerManagerRequest::DisconnectPeer(disconnected_peer_id, response_tx) => {
+                assert_eq!(disconnected_peer_id, peer_id);
+                response_tx.send(Ok(())).unwrap();
+            }
+            _ => panic!("Unexpected event"),
+        }
+    };
+
+    rt.spawn(f_substream);
+    rt.block_on(f_network_provider);
+}
+
 #[test]
 fn test_outbound_single_protocol() {
     let rt = Runtime::new().unwrap();
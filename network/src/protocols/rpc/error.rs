@@ -33,6 +33,9 @@ pub enum RpcError {
     #[fail(display = "Received unexpected rpc request message; expected remote to half-close.")]
     UnexpectedRpcRequest,
 
+    #[fail(display = "Remote peer exceeded its inbound rate limits")]
+    RateLimitExceeded,
+
     #[fail(display = "Application layer unexpectedly dropped response channel")]
     UnexpectedResponseChannelCancel,
 
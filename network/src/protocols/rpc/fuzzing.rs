@@ -3,8 +3,9 @@
 
 use crate::{
     common::NegotiatedSubstream,
-    peer_manager::PeerManagerNotification,
+    peer_manager::{PeerManagerNotification, PeerManagerRequestSender},
     protocols::rpc::{self, RpcNotification},
+    rate_limiter::InboundRateLimiter,
     ProtocolId,
 };
 use bytes::{Bytes, BytesMut};
@@ -75,10 +76,17 @@ pub fn fuzzer(data: &[u8]) {
     let peer_mgr_notif =
         PeerManagerNotification::NewInboundSubstream(MOCK_PEER_ID, listener_substream);
 
+    let (peer_mgr_reqs_tx, _peer_mgr_reqs_rx) = channel::new_test(8);
+
     // run the rpc inbound protocol using the in-memory substream
-    let f_handle_inbound =
-        rpc::handle_inbound_substream(notification_tx, peer_mgr_notif, INBOUND_RPC_TIMEOUT)
-            .map(|_| io::Result::Ok(()));
+    let f_handle_inbound = rpc::handle_inbound_substream(
+        notification_tx,
+        peer_mgr_notif,
+        INBOUND_RPC_TIMEOUT,
+        PeerManagerRequestSender::new(peer_mgr_reqs_tx),
+        InboundRateLimiter::unlimited(),
+    )
+    .map(|_| io::Result::Ok(()));
 
     // mock the notification channel to echo the fuzzer data back to the dialer
     // as an rpc response
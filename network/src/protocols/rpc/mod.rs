@@ -58,6 +58,7 @@
 use crate::{
     counters,
     peer_manager::{PeerManagerNotification, PeerManagerRequestSender},
+    rate_limiter::InboundRateLimiter,
     sink::NetworkSinkExt,
     ProtocolId,
 };
@@ -177,6 +178,8 @@ pub struct Rpc<TSubstream> {
     // TODO(philiphayes): partition inbound queue by peer to prevent one peer
     // from starving other peers' rpcs?
     max_concurrent_inbound_rpcs: u32,
+    /// Limits on the inbound rpc requests of each peer.
+    rate_limiter: InboundRateLimiter,
 }
 
 impl<TSubstream> Rpc<TSubstream>
@@ -193,6 +196,7 @@ where
         inbound_rpc_timeout: Duration,
         max_concurrent_outbound_rpcs: u32,
         max_concurrent_inbound_rpcs: u32,
+        rate_limiter: InboundRateLimiter,
     ) -> Self {
         Self {
             executor,
@@ -203,6 +207,7 @@ where
             inbound_rpc_timeout,
             max_concurrent_outbound_rpcs,
             max_concurrent_inbound_rpcs,
+            rate_limiter,
         }
     }
 
@@ -217,6 +222,7 @@ where
         let inbound_rpc_timeout = self.inbound_rpc_timeout;
         let max_concurrent_outbound_rpcs = self.max_concurrent_outbound_rpcs;
         let max_concurrent_inbound_rpcs = self.max_concurrent_inbound_rpcs;
+        let rate_limiter = self.rate_limiter;
 
         // inbound and outbound requests use separate bounded executors to ensure
         // backpressure propagates independently and doesn't starve the other
@@ -225,7 +231,7 @@ where
         let outbound_handler = handle_outbounds(
             BoundedExecutor::new(max_concurrent_outbound_rpcs as usize, executor.clone()),
             requests_rx,
-            peer_mgr_reqs_tx,
+            peer_mgr_reqs_tx.clone(),
         );
 
         let inbound_handler = handle_inbounds(
@@ -233,6 +239,8 @@ where
             peer_mgr_notifs_rx,
             rpc_handler_tx,
             inbound_rpc_timeout,
+            peer_mgr_reqs_tx,
+            rate_limiter,
         );
 
         // drive inbound and outbound handlers to completion
@@ -263,6 +271,8 @@ async fn handle_inbounds<TSubstream>(
     mut peer_mgr_notifs_rx: channel::Receiver<PeerManagerNotification<TSubstream>>,
     rpc_handler_tx: channel::Sender<RpcNotification>,
     inbound_rpc_timeout: Duration,
+    peer_mgr_tx: PeerManagerRequestSender<TSubstream>,
+    rate_limiter: InboundRateLimiter,
 ) where
     TSubstream: AsyncRead + AsyncWrite + Debug + Send + Unpin + 'static,
 {
@@ -272,6 +282,8 @@ async fn handle_inbounds<TSubstream>(
                 rpc_handler_tx.clone(),
                 notif,
                 inbound_rpc_timeout,
+                peer_mgr_tx.clone(),
+                rate_limiter.clone(),
             ))
             .await;
     }
@@ -388,6 +400,8 @@ async fn handle_inbound_substream<TSubstream>(
     notification_tx: channel::Sender<RpcNotification>,
     notif: PeerManagerNotification<TSubstream>,
     timeout: Duration,
+    peer_mgr_tx: PeerManagerRequestSender<TSubstream>,
+    rate_limiter: InboundRateLimiter,
 ) where
     TSubstream: AsyncRead + AsyncWrite + Debug + Send + Unpin,
 {
@@ -396,6 +410,8 @@ async fn handle_inbound_substream<TSubstream>(
             // Run the actual inbound rpc protocol.
             let res = handle_inbound_substream_inner(
                 notification_tx,
+                peer_mgr_tx,
+                rate_limiter,
                 peer_id,
                 substream.protocol,
                 substream.substream,
@@ -429,6 +445,8 @@ async fn handle_inbound_substream<TSubstream>(
 
 async fn handle_inbound_substream_inner<TSubstream>(
     mut notification_tx: channel::Sender<RpcNotification>,
+    mut peer_mgr_tx: PeerManagerRequestSender<TSubstream>,
+    rate_limiter: InboundRateLimiter,
     peer_id: PeerId,
     protocol: ProtocolId,
     substream: TSubstream,
@@ -452,6 +470,16 @@ where
         return Err(RpcError::UnexpectedRpcRequest);
     };
 
+    // Reject the request right away if the peer exceeds its rate limits, rather than holding a
+    // slot of the inbound executor until they are met: the dialer receives no response.
+    if !rate_limiter
+        .admit(&mut peer_mgr_tx, peer_id, &protocol, req_data.len())
+        .await
+    {
+        substream.close().await?;
+        return Err(RpcError::RateLimitExceeded);
+    }
+
     // Build the event and context we push up to upper layers for handling.
     let (res_tx, res_rx) = oneshot::channel();
     let notification = RpcNotification::RecvRpc(
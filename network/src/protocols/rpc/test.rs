@@ -6,6 +6,7 @@ use crate::{
     common::NegotiatedSubstream,
     peer_manager::{PeerManagerNotification, PeerManagerRequest},
 };
+use config::config::RateLimitConfig;
 use futures::future::{join, join3, join4};
 use memsocket::MemorySocket;
 use tokio::runtime::Runtime;
@@ -88,10 +89,13 @@ fn upgrades() {
     let inbound_notif = PeerManagerNotification::NewInboundSubstream(dialer_peer_id, substream);
 
     // Handle the inbound substream
+    let (listener_peer_mgr_reqs_tx, _) = channel::new_test(8);
     let f_listener_upgrade = handle_inbound_substream(
         listener_rpc_notifs_tx,
         inbound_notif,
         Duration::from_millis(500),
+        PeerManagerRequestSender::new(listener_peer_mgr_reqs_tx),
+        InboundRateLimiter::unlimited(),
     );
 
     // Make an outbound substream request
@@ -437,10 +441,13 @@ fn inbound_rpc_timeout() {
         substream: listener_substream,
     };
     let inbound_notif = PeerManagerNotification::NewInboundSubstream(dialer_peer_id, substream);
+    let (listener_peer_mgr_reqs_tx, _) = channel::new_test(8);
     let f_listener_upgrade = handle_inbound_substream(
         listener_rpc_notifs_tx,
         inbound_notif,
         Duration::from_millis(100),
+        PeerManagerRequestSender::new(listener_peer_mgr_reqs_tx),
+        InboundRateLimiter::unlimited(),
     );
 
     // The listener future should complete (with a timeout) despite the dialer
@@ -448,6 +455,75 @@ fn inbound_rpc_timeout() {
     Runtime::new().unwrap().block_on(f_listener_upgrade);
 }
 
+// Test that inbound rpc calls exceeding the rate limits of their peer are rejected right away.
+#[test]
+fn inbound_rpc_rate_limited() {
+    ::logger::try_init_for_testing();
+
+    let listener_peer_id = PeerId::random();
+    let dialer_peer_id = PeerId::random();
+    let protocol_id = b"/get_blocks/1.0.0";
+    let req_data = b"hello";
+
+    let (dialer_substream, listener_substream) = MemorySocket::new_pair();
+
+    // Fake the dialer NetworkProvider
+    let (dialer_peer_mgr_reqs_tx, dialer_peer_mgr_reqs_rx) = channel::new_test(8);
+    let dialer_peer_mgr_reqs_tx = PeerManagerRequestSender::new(dialer_peer_mgr_reqs_tx);
+    let f_dialer_peer_mgr = mock_peer_manager(dialer_peer_mgr_reqs_rx, dialer_substream);
+
+    // Handle the inbound substream, with no burst allowed so that the request is throttled.
+    let (listener_rpc_notifs_tx, mut listener_rpc_notifs_rx) = channel::new_test(8);
+    let substream = NegotiatedSubstream {
+        protocol: ProtocolId::from_static(protocol_id),
+        substream: listener_substream,
+    };
+    let inbound_notif = PeerManagerNotification::NewInboundSubstream(dialer_peer_id, substream);
+    let (listener_peer_mgr_reqs_tx, _) = channel::new_test(8);
+    let rate_limiter = InboundRateLimiter::new(RateLimitConfig {
+        bytes_per_sec: 0,
+        burst_bytes: 0,
+        messages_per_sec: 1,
+        burst_messages: 0,
+        max_throttled_messages: 1,
+        disconnect_cool_down_secs: 0,
+    });
+    let f_listener_upgrade = async move {
+        handle_inbound_substream(
+            listener_rpc_notifs_tx,
+            inbound_notif,
+            Duration::from_secs(10),
+            PeerManagerRequestSender::new(listener_peer_mgr_reqs_tx),
+            rate_limiter,
+        )
+        .await;
+        // The request never reaches the upper layers.
+        assert!(listener_rpc_notifs_rx.next().await.is_none());
+    };
+
+    // Make an outbound rpc request
+    let f_dialer_upgrade = async move {
+        let res = do_outbound_rpc_req(
+            dialer_peer_mgr_reqs_tx,
+            listener_peer_id,
+            ProtocolId::from_static(protocol_id),
+            Bytes::from_static(req_data),
+            Duration::from_secs(5),
+        )
+        .await;
+
+        // The dialer is not left waiting for the listener or its own timeout.
+        let err = res.expect_err("Dialer's rpc request should fail");
+        match err {
+            RpcError::IoError(err) => assert_eq!(err.kind(), io::ErrorKind::UnexpectedEof),
+            err => panic!("Unexpected error: {:?}, expected IoError", err),
+        };
+    };
+
+    let f = join3(f_dialer_peer_mgr, f_dialer_upgrade, f_listener_upgrade);
+    Runtime::new().unwrap().block_on(f);
+}
+
 // Test that outbound rpcs can be canceled before sending
 #[test]
 fn outbound_cancellation_before_send() {
@@ -604,6 +680,7 @@ fn rpc_protocol() {
         Duration::from_millis(500),
         10,
         10,
+        InboundRateLimiter::unlimited(),
     );
 
     // Fake the dialer NetworkProvider
@@ -655,6 +732,7 @@ fn rpc_protocol() {
         Duration::from_millis(500),
         10,
         10,
+        InboundRateLimiter::unlimited(),
     );
 
     // Fake the listener NetworkProvider
@@ -0,0 +1,328 @@
+// Copyright (c) The Libra Core Contributors
+// SPDX-License-Identifier: Apache-2.0
+
+//! Per-peer limits on inbound network traffic
+//!
+//! Every connected peer is granted, for each protocol it speaks, two token buckets: one counting
+//! bytes and one counting messages. Each inbound rpc request or direct-send message takes tokens
+//! from the buckets of its peer and protocol, which are refilled at a constant rate up to their
+//! burst capacity.
+//!
+//! A peer exceeding its limits is first throttled: its direct-send messages are only handed to
+//! the upper layers once its buckets have refilled, which also stops reading from its substreams
+//! and pushes back on the peer. Its rpc requests are rejected right away instead, so that they do
+//! not hold the slots of the executor shared by all the inbound rpcs while the buckets refill.
+//!
+//! A peer whose messages keep being throttled is disconnected. Its state is kept for a cool-down
+//! period, during which it is disconnected again as soon as it sends anything, so that it cannot
+//! reconnect to get full buckets.
+use crate::{counters, peer_manager::PeerManagerRequestSender, ProtocolId};
+use config::config::RateLimitConfig;
+use libra_types::PeerId;
+use logger::prelude::*;
+use std::{
+    collections::HashMap,
+    sync::{Arc, Mutex},
+    time::{Duration, Instant},
+};
+use tokio::timer;
+
+#[cfg(test)]
+mod test;
+
+/// The state of idle peers is pruned at most this often.
+const PRUNE_INTERVAL: Duration = Duration::from_secs(60);
+
+/// A bucket holding up to `capacity` tokens, refilled at `rate` tokens per second. Tokens can be
+/// taken in advance, the bucket then owes the missing tokens until it is refilled.
+#[derive(Debug)]
+struct TokenBucket {
+    capacity: f64,
+    rate: f64,
+    tokens: f64,
+    last_refill: Instant,
+}
+
+impl TokenBucket {
+    /// Create a full bucket, or None if the rate is 0, i.e., there is no limit.
+    fn new(capacity: u64, rate: u64, now: Instant) -> Option<Self> {
+        if rate == 0 {
+            return None;
+        }
+        Some(Self {
+            capacity: capacity as f64,
+            rate: rate as f64,
+            tokens: capacity as f64,
+            last_refill: now,
+        })
+    }
+
+    fn refill(&mut self, now: Instant) {
+        let elapsed = now.saturating_duration_since(self.last_refill);
+        self.tokens = (self.tokens + elapsed.as_secs_f64() * self.rate).min(self.capacity);
+        self.last_refill = now;
+    }
+
+    /// Take `amount` tokens and return how long it takes for the bucket to repay what it owes.
+    fn take(&mut self, amount: u64, now: Instant) -> Duration {
+        self.refill(now);
+        self.tokens -= amount as f64;
+        if self.tokens >= 0.0 {
+            Duration::from_secs(0)
+        } else {
+            Duration::from_secs_f64(-self.tokens / self.rate)
+        }
+    }
+
+    fn is_full(&mut self, now: Instant) -> bool {
+        self.refill(now);
+        self.tokens >= self.capacity
+    }
+}
+
+/// The buckets limiting the traffic of a peer on a protocol.
+#[derive(Debug)]
+struct ProtocolBuckets {
+    bytes: Option<TokenBucket>,
+    messages: Option<TokenBucket>,
+}
+
+impl ProtocolBuckets {
+    fn new(config: &RateLimitConfig, now: Instant) -> Self {
+        Self {
+            bytes: TokenBucket::new(config.burst_bytes, config.bytes_per_sec, now),
+            messages: TokenBucket::new(config.burst_messages, config.messages_per_sec, now),
+        }
+    }
+
+    fn take(&mut self, num_bytes: u64, now: Instant) -> Duration {
+        let bytes_delay = self
+            .bytes
+            .as_mut()
+            .map_or(Duration::from_secs(0), |bucket| bucket.take(num_bytes, now));
+        let messages_delay = self
+            .messages
+            .as_mut()
+            .map_or(Duration::from_secs(0), |bucket| bucket.take(1, now));
+        bytes_delay.max(messages_delay)
+    }
+
+    fn is_full(&mut self, now: Instant) -> bool {
+        self.bytes
+            .as_mut()
+            .map_or(true, |bucket| bucket.is_full(now))
+            && self
+                .messages
+                .as_mut()
+                .map_or(true, |bucket| bucket.is_full(now))
+    }
+}
+
+#[derive(Debug, Default)]
+struct PeerState {
+    buckets: HashMap<ProtocolId, ProtocolBuckets>,
+    // The number of consecutive messages of the peer that were throttled
+    throttled_messages: u64,
+    // When the peer was last disconnected for exceeding its limits
+    disconnected_at: Option<Instant>,
+}
+
+impl PeerState {
+    fn is_cooling_down(&self, cool_down: Duration, now: Instant) -> bool {
+        self.disconnected_at.map_or(false, |disconnected_at| {
+            now.saturating_duration_since(disconnected_at) < cool_down
+        })
+    }
+}
+
+/// What to do with an inbound message.
+#[derive(Debug, PartialEq)]
+pub enum RateLimitDecision {
+    /// The peer is within its limits: handle the message right away.
+    Allow,
+    /// The peer exceeded its limits: handle the message once the duration has elapsed.
+    Throttle(Duration),
+    /// The peer kept exceeding its limits: drop the message and disconnect the peer.
+    Disconnect,
+}
+
+#[derive(Debug)]
+struct Inner {
+    config: RateLimitConfig,
+    peers: HashMap<PeerId, PeerState>,
+    last_prune: Instant,
+}
+
+impl Inner {
+    fn check(
+        &mut self,
+        peer_id: PeerId,
+        protocol: &ProtocolId,
+        num_bytes: usize,
+        now: Instant,
+    ) -> RateLimitDecision {
+        if now.saturating_duration_since(self.last_prune) >= PRUNE_INTERVAL {
+            self.prune(now);
+        }
+        let config = &self.config;
+        let cool_down = Duration::from_secs(config.disconnect_cool_down_secs);
+        let peer = self.peers.entry(peer_id).or_insert_with(PeerState::default);
+        if peer.is_cooling_down(cool_down, now) {
+            return RateLimitDecision::Disconnect;
+        }
+        let delay = peer
+            .buckets
+            .entry(protocol.clone())
+            .or_insert_with(|| ProtocolBuckets::new(config, now))
+            .take(num_bytes as u64, now);
+        if delay == Duration::from_secs(0) {
+            peer.throttled_messages = 0;
+            return RateLimitDecision::Allow;
+        }
+        peer.throttled_messages += 1;
+        if peer.throttled_messages > config.max_throttled_messages {
+            // The peer keeps its buckets if it reconnects.
+            peer.throttled_messages = 0;
+            peer.disconnected_at = Some(now);
+            return RateLimitDecision::Disconnect;
+        }
+        RateLimitDecision::Throttle(delay)
+    }
+
+    /// Forget the peers that have been idle long enough for all their buckets to refill and their
+    /// cool-down to elapse: they are in the same state as peers that never sent anything.
+    fn prune(&mut self, now: Instant) {
+        let cool_down = Duration::from_secs(self.config.disconnect_cool_down_secs);
+        self.peers.retain(|_, peer| {
+            peer.is_cooling_down(cool_down, now)
+                || peer
+                    .buckets
+                    .values_mut()
+                    .any(|buckets| !buckets.is_full(now))
+        });
+        self.last_prune = now;
+    }
+}
+
+/// Enforces the inbound rate limits of all the connected peers. It is shared by the protocols
+/// handling inbound messages, so that a peer is limited the same way whichever actor receives its
+/// messages.
+#[derive(Clone, Debug)]
+pub struct InboundRateLimiter {
+    // None if there are no limits.
+    inner: Option<Arc<Mutex<Inner>>>,
+}
+
+impl InboundRateLimiter {
+    pub fn new(config: RateLimitConfig) -> Self {
+        Self {
+            inner: Some(Arc::new(Mutex::new(Inner {
+                config,
+                peers: HashMap::new(),
+                last_prune: Instant::now(),
+            }))),
+        }
+    }
+
+    /// A rate limiter allowing all the inbound messages.
+    pub fn unlimited() -> Self {
+        Self { inner: None }
+    }
+
+    /// Charge a message of `num_bytes` received from `peer_id` on `protocol` at `now` to the peer
+    /// and decide what to do with it.
+    pub fn check(
+        &self,
+        peer_id: PeerId,
+        protocol: &ProtocolId,
+        num_bytes: usize,
+        now: Instant,
+    ) -> RateLimitDecision {
+        match &self.inner {
+            Some(inner) => inner
+                .lock()
+                .unwrap()
+                .check(peer_id, protocol, num_bytes, now),
+            None => RateLimitDecision::Allow,
+        }
+    }
+
+    /// Charge a message of `num_bytes` received from `peer_id` on `protocol` to the peer and wait
+    /// until the message can be handled. Returns false if the message must be dropped instead, in
+    /// which case the peer is disconnected.
+    pub async fn throttle<TSubstream>(
+        &self,
+        peer_mgr_tx: &mut PeerManagerRequestSender<TSubstream>,
+        peer_id: PeerId,
+        protocol: &ProtocolId,
+        num_bytes: usize,
+    ) -> bool {
+        match self.check(peer_id, protocol, num_bytes, Instant::now()) {
+            RateLimitDecision::Allow => true,
+            RateLimitDecision::Throttle(delay) => {
+                counters::INBOUND_MESSAGES_THROTTLED.inc();
+                counters::INBOUND_THROTTLE_DELAY.observe(delay.as_secs_f64());
+                debug!(
+                    "Throttling message from peer {} on protocol {:?} for {:?}",
+                    peer_id.short_str(),
+                    protocol,
+                    delay
+                );
+                timer::delay_for(delay).await;
+                true
+            }
+            RateLimitDecision::Disconnect => {
+                Self::disconnect(peer_mgr_tx, peer_id, protocol).await;
+                false
+            }
+        }
+    }
+
+    /// Charge a message of `num_bytes` received from `peer_id` on `protocol` to the peer. Returns
+    /// whether the message can be handled right away: a message exceeding the limits must be
+    /// rejected, and the peer is disconnected if it kept exceeding them.
+    pub async fn admit<TSubstream>(
+        &self,
+        peer_mgr_tx: &mut PeerManagerRequestSender<TSubstream>,
+        peer_id: PeerId,
+        protocol: &ProtocolId,
+        num_bytes: usize,
+    ) -> bool {
+        match self.check(peer_id, protocol, num_bytes, Instant::now()) {
+            RateLimitDecision::Allow => true,
+            RateLimitDecision::Throttle(_) => {
+                counters::INBOUND_RPCS_REJECTED.inc();
+                debug!(
+                    "Rejecting message from peer {} on protocol {:?}",
+                    peer_id.short_str(),
+                    protocol
+                );
+                false
+            }
+            RateLimitDecision::Disconnect => {
+                Self::disconnect(peer_mgr_tx, peer_id, protocol).await;
+                false
+            }
+        }
+    }
+
+    async fn disconnect<TSubstream>(
+        peer_mgr_tx: &mut PeerManagerRequestSender<TSubstream>,
+        peer_id: PeerId,
+        protocol: &ProtocolId,
+    ) {
+        counters::RATE_LIMITED_PEERS_DISCONNECTED.inc();
+        security_log(SecurityEvent::NetworkRateLimitExceeded)
+            .error("RateLimitExceeded")
+            .data(&peer_id)
+            .data(protocol)
+            .log();
+        if let Err(e) = peer_mgr_tx.disconnect_peer(peer_id).await {
+            warn!(
+                "Failed to disconnect rate limited peer {}: {}",
+                peer_id.short_str(),
+                e
+            );
+        }
+    }
+}
@@ -0,0 +1,235 @@
+// Copyright (c) The Libra Core Contributors
+// SPDX-License-Identifier: Apache-2.0
+
+use super::*;
+
+fn config() -> RateLimitConfig {
+    RateLimitConfig {
+        bytes_per_sec: 1000,
+        burst_bytes: 2000,
+        messages_per_sec: 10,
+        burst_messages: 20,
+        max_throttled_messages: 3,
+        disconnect_cool_down_secs: 120,
+    }
+}
+
+fn protocol() -> ProtocolId {
+    ProtocolId::from_static(b"/libra/test/direct_send/0.1.0")
+}
+
+#[test]
+fn test_burst_then_throttle() {
+    let rate_limiter = InboundRateLimiter::new(config());
+    let peer_id = PeerId::random();
+    let now = Instant::now();
+
+    // The burst capacity is available right away.
+    for _ in 0..20 {
+        assert_eq!(
+            rate_limiter.check(peer_id, &protocol(), 100, now),
+            RateLimitDecision::Allow
+        );
+    }
+    // Then the peer has to wait for the messages bucket to refill.
+    assert_eq!(
+        rate_limiter.check(peer_id, &protocol(), 0, now),
+        RateLimitDecision::Throttle(Duration::from_millis(100))
+    );
+    // Once refilled, the peer is within its limits again.
+    let now = now + Duration::from_secs(1);
+    assert_eq!(
+        rate_limiter.check(peer_id, &protocol(), 0, now),
+        RateLimitDecision::Allow
+    );
+}
+
+#[test]
+fn test_bytes_limit() {
+    let rate_limiter = InboundRateLimiter::new(config());
+    let peer_id = PeerId::random();
+    let now = Instant::now();
+
+    assert_eq!(
+        rate_limiter.check(peer_id, &protocol(), 2000, now),
+        RateLimitDecision::Allow
+    );
+    // The delay accounts for all the bytes owed.
+    assert_eq!(
+        rate_limiter.check(peer_id, &protocol(), 500, now),
+        RateLimitDecision::Throttle(Duration::from_millis(500))
+    );
+    assert_eq!(
+        rate_limiter.check(peer_id, &protocol(), 500, now),
+        RateLimitDecision::Throttle(Duration::from_secs(1))
+    );
+}
+
+#[test]
+fn test_limits_per_peer_and_protocol() {
+    let rate_limiter = InboundRateLimiter::new(config());
+    let peer_id = PeerId::random();
+    let other_protocol = ProtocolId::from_static(b"/libra/test/rpc/0.1.0");
+    let now = Instant::now();
+
+    assert_eq!(
+        rate_limiter.check(peer_id, &protocol(), 3000, now),
+        RateLimitDecision::Throttle(Duration::from_secs(1))
+    );
+    assert_ne!(
+        rate_limiter.check(peer_id, &protocol(), 0, now),
+        RateLimitDecision::Allow
+    );
+    // Neither other protocols of the peer nor other peers are limited.
+    assert_eq!(
+        rate_limiter.check(peer_id, &other_protocol, 0, now),
+        RateLimitDecision::Allow
+    );
+    assert_eq!(
+        rate_limiter.check(PeerId::random(), &protocol(), 0, now),
+        RateLimitDecision::Allow
+    );
+}
+
+#[test]
+fn test_disconnect_after_consecutive_throttles() {
+    let rate_limiter = InboundRateLimiter::new(config());
+    let peer_id = PeerId::random();
+    let mut now = Instant::now();
+
+    assert_eq!(
+        rate_limiter.check(peer_id, &protocol(), 2000, now),
+        RateLimitDecision::Allow
+    );
+    // Throttles interrupted by a message within the limits are tolerated.
+    for _ in 0..3 {
+        assert_ne!(
+            rate_limiter.check(peer_id, &protocol(), 1000, now),
+            RateLimitDecision::Allow
+        );
+    }
+    now += Duration::from_secs(5);
+    assert_eq!(
+        rate_limiter.check(peer_id, &protocol(), 2000, now),
+        RateLimitDecision::Allow
+    );
+    for _ in 0..3 {
+        assert_ne!(
+            rate_limiter.check(peer_id, &protocol(), 1000, now),
+            RateLimitDecision::Allow
+        );
+    }
+    // One throttle too many disconnects the peer.
+    assert_eq!(
+        rate_limiter.check(peer_id, &protocol(), 0, now),
+        RateLimitDecision::Disconnect
+    );
+    // It is disconnected again if it comes back during the cool-down, even once its buckets
+    // have refilled and idle peers are pruned.
+    assert_eq!(
+        rate_limiter.check(peer_id, &protocol(), 0, now),
+        RateLimitDecision::Disconnect
+    );
+    now += PRUNE_INTERVAL;
+    assert_eq!(
+        rate_limiter.check(peer_id, &protocol(), 0, now),
+        RateLimitDecision::Disconnect
+    );
+    // Afterwards, it is limited as before.
+    now += Duration::from_secs(60);
+    assert_eq!(
+        rate_limiter.check(peer_id, &protocol(), 2000, now),
+        RateLimitDecision::Allow
+    );
+    assert_ne!(
+        rate_limiter.check(peer_id, &protocol(), 1000, now),
+        RateLimitDecision::Allow
+    );
+}
+
+#[test]
+fn test_disconnected_peer_keeps_buckets() {
+    let rate_limiter = InboundRateLimiter::new(RateLimitConfig {
+        disconnect_cool_down_secs: 1,
+        ..config()
+    });
+    let peer_id = PeerId::random();
+    let mut now = Instant::now();
+
+    // The peer owes 10s worth of bytes when it gets disconnected.
+    assert_eq!(
+        rate_limiter.check(peer_id, &protocol(), 12_000, now),
+        RateLimitDecision::Throttle(Duration::from_secs(10))
+    );
+    for _ in 0..2 {
+        rate_limiter.check(peer_id, &protocol(), 0, now);
+    }
+    assert_eq!(
+        rate_limiter.check(peer_id, &protocol(), 0, now),
+        RateLimitDecision::Disconnect
+    );
+    // Once the cool-down elapsed, it still owes what it did not repay.
+    now += Duration::from_secs(2);
+    assert_eq!(
+        rate_limiter.check(peer_id, &protocol(), 0, now),
+        RateLimitDecision::Throttle(Duration::from_secs(8))
+    );
+}
+
+#[test]
+fn test_disabled_limits() {
+    let peer_id = PeerId::random();
+    let now = Instant::now();
+
+    let rate_limiter = InboundRateLimiter::unlimited();
+    for _ in 0..100 {
+        assert_eq!(
+            rate_limiter.check(peer_id, &protocol(), 1_000_000, now),
+            RateLimitDecision::Allow
+        );
+    }
+
+    // Only the messages are limited.
+    let rate_limiter = InboundRateLimiter::new(RateLimitConfig {
+        bytes_per_sec: 0,
+        ..config()
+    });
+    for _ in 0..20 {
+        assert_eq!(
+            rate_limiter.check(peer_id, &protocol(), 1_000_000, now),
+            RateLimitDecision::Allow
+        );
+    }
+    assert_ne!(
+        rate_limiter.check(peer_id, &protocol(), 0, now),
+        RateLimitDecision::Allow
+    );
+}
+
+#[test]
+fn test_prune_idle_peers() {
+    let rate_limiter = InboundRateLimiter::new(config());
+    let idle_peer_id = PeerId::random();
+    let active_peer_id = PeerId::random();
+    let now = Instant::now();
+    let num_peers = || {
+        rate_limiter
+            .inner
+            .as_ref()
+            .unwrap()
+            .lock()
+            .unwrap()
+            .peers
+            .len()
+    };
+
+    rate_limiter.check(idle_peer_id, &protocol(), 100, now);
+    rate_limiter.check(active_peer_id, &protocol(), 100, now);
+    assert_eq!(num_peers(), 2);
+
+    // The active peer still owes tokens at the next prune, unlike the idle one.
+    let later = now + PRUNE_INTERVAL;
+    rate_limiter.check(active_peer_id, &protocol(), 1_000_000, now);
+    rate_limiter.check(active_peer_id, &protocol(), 0, later);
+    assert_eq!(num_peers(), 1);
+}
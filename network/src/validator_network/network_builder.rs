@@ -23,11 +23,12 @@ use crate::{
         identity::Identity,
         rpc::Rpc,
     },
+    rate_limiter::InboundRateLimiter,
     transport::*,
     ProtocolId,
 };
 use channel;
-use config::config::RoleType;
+use config::config::{RateLimitConfig, RoleType};
 use crypto::{
     ed25519::*,
     x25519::{X25519StaticPrivateKey, X25519StaticPublicKey},
@@ -100,6 +101,7 @@ pub struct NetworkBuilder {
     max_concurrent_network_reqs: u32,
     max_concurrent_network_notifs: u32,
     max_connection_delay_ms: u64,
+    inbound_rate_limit: Option<RateLimitConfig>,
     signing_keys: Option<(Ed25519PrivateKey, Ed25519PublicKey)>,
     is_permissioned: bool,
 }
@@ -136,6 +138,7 @@ impl NetworkBuilder {
             max_concurrent_network_reqs: MAX_CONCURRENT_NETWORK_REQS,
             max_concurrent_network_notifs: MAX_CONCURRENT_NETWORK_NOTIFS,
             max_connection_delay_ms: MAX_CONNECTION_DELAY_MS,
+            inbound_rate_limit: None,
             signing_keys: None,
             is_permissioned: true,
         }
@@ -265,6 +268,12 @@ impl NetworkBuilder {
         self
     }
 
+    /// Limit the inbound traffic of each peer. There are no limits unless set.
+    pub fn inbound_rate_limit(&mut self, inbound_rate_limit: RateLimitConfig) -> &mut Self {
+        self.inbound_rate_limit = Some(inbound_rate_limit);
+        self
+    }
+
     /// Set the size of the channels between different network actors.
     pub fn channel_size(&mut self, channel_size: usize) -> &mut Self {
         self.channel_size = channel_size;
@@ -354,6 +363,11 @@ impl NetworkBuilder {
         // Setup channel to send requests to peer manager.
         let (pm_reqs_tx, pm_reqs_rx) =
             channel::new(self.channel_size, &counters::PENDING_PEER_MANAGER_REQUESTS);
+        // The inbound rate limits of the peers are shared by the DirectSend and RPC actors.
+        let rate_limiter = self
+            .inbound_rate_limit
+            .clone()
+            .map_or_else(InboundRateLimiter::unlimited, InboundRateLimiter::new);
 
         // Initialize and start DirectSend actor.
         let (pm_ds_notifs_tx, pm_ds_notifs_rx) = channel::new(
@@ -377,6 +391,7 @@ impl NetworkBuilder {
             ds_net_notifs_tx,
             pm_ds_notifs_rx,
             PeerManagerRequestSender::new(pm_reqs_tx.clone()),
+            rate_limiter.clone(),
         );
         self.executor.spawn(ds.start());
         debug!("Started direct send actor");
@@ -404,6 +419,7 @@ impl NetworkBuilder {
             Duration::from_millis(self.inbound_rpc_timeout_ms),
             self.max_concurrent_outbound_rpcs,
             self.max_concurrent_inbound_rpcs,
+            rate_limiter,
         );
         self.executor.spawn(rpc.start());
         debug!("Started RPC actor");
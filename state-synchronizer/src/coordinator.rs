@@ -8,6 +8,7 @@ use crate::{
     LedgerInfo, PeerId,
 };
 use config::config::StateSyncConfig;
+use crypto::HashValue;
 use failure::prelude::*;
 use futures::{
     channel::{mpsc, oneshot},
@@ -15,11 +16,15 @@ use futures::{
     StreamExt,
 };
 use libra_types::{
-    crypto_proxies::LedgerInfoWithSignatures, transaction::TransactionListWithProof,
+    account_state_blob::AccountStateChunkWithProof, crypto_proxies::LedgerInfoWithSignatures,
+    transaction::TransactionListWithProof,
 };
 use logger::prelude::*;
 use network::{
-    proto::{GetChunkRequest, GetChunkResponse, StateSynchronizerMsg, StateSynchronizerMsg_oneof},
+    proto::{
+        GetChunkRequest, GetChunkResponse, GetStateChunkRequest, GetStateChunkResponse,
+        StateSynchronizerMsg, StateSynchronizerMsg_oneof,
+    },
     validator_network::{Event, StateSynchronizerEvents, StateSynchronizerSender},
 };
 use std::{
@@ -39,6 +44,16 @@ pub enum CoordinatorMessage {
     GetState(oneshot::Sender<u64>),
 }
 
+/// progress of state snapshot sync
+struct StateSnapshotProgress {
+    // ledger info the state snapshot is taken at
+    target: LedgerInfo,
+    // key of the last account that was saved, None if no account was saved yet
+    left_key: Option<HashValue>,
+    // time when the last state chunk was requested
+    last_request_tst: SystemTime,
+}
+
 /// used to coordinate synchronization process
 /// handles external sync requests and drives synchronization with remote peers
 pub(crate) struct SyncCoordinator<T> {
@@ -61,6 +76,8 @@ pub(crate) struct SyncCoordinator<T> {
     // peer will be notified about new chunk of transactions if it's available before expiry time
     // value format is (expiration_time, known_version, limit)
     subscriptions: HashMap<PeerId, (SystemTime, u64, u64)>,
+    // set while the node is downloading a state snapshot instead of replaying transactions
+    state_snapshot: Option<StateSnapshotProgress>,
     executor_proxy: T,
 }
 
@@ -91,6 +108,7 @@ impl<T: ExecutorProxyTrait> SyncCoordinator<T> {
             peer_manager: PeerManager::new(upstream_peers),
             subscriptions: HashMap::new(),
             callback: None,
+            state_snapshot: None,
             executor_proxy,
         }
     }
@@ -160,6 +178,20 @@ impl<T: ExecutorProxyTrait> SyncCoordinator<T> {
                                                 counters::OP_COUNTERS.inc(&format!("{}.{}", counters::APPLY_CHUNK_SUCCESS, peer_id));
                                             }
                                         }
+                                        StateSynchronizerMsg_oneof::StateChunkRequest(request) => {
+                                            if let Err(err) = self.process_state_chunk_request(peer_id, request).await {
+                                                error!("[state sync] failed to serve state chunk request to {}: {:?}", peer_id, err);
+                                            }
+                                        }
+                                        StateSynchronizerMsg_oneof::StateChunkResponse(response) => {
+                                            if let Err(err) = self.process_state_chunk_response(&peer_id, response).await {
+                                                error!("[state sync] failed to process state chunk response from {}: {:?}", peer_id, err);
+                                                counters::OP_COUNTERS.inc(&format!("{}.{}", counters::APPLY_STATE_CHUNK_FAILURE, peer_id));
+                                            } else {
+                                                self.peer_manager.update_score(&peer_id, PeerScoreUpdateType::Success);
+                                                counters::OP_COUNTERS.inc(&format!("{}.{}", counters::APPLY_STATE_CHUNK_SUCCESS, peer_id));
+                                            }
+                                        }
                                     }
                                 }
                                 _ => {}
@@ -212,9 +244,29 @@ impl<T: ExecutorProxyTrait> SyncCoordinator<T> {
         // TODO: Should we be changing peer manager peer set for every target?
         self.peer_manager
             .set_peers(target.signatures().keys().copied().collect());
-        self.target = Some(target);
-        self.request_next_chunk(0).await;
+        let is_far_behind =
+            requested_version - self.known_version > self.config.state_snapshot_threshold;
+        self.target = Some(target.clone());
         self.callback = Some(callback);
+        if self.state_snapshot.is_some() {
+            // the ongoing state snapshot sync is finished first, remaining transactions up to the
+            // new target are replayed afterwards
+            return;
+        }
+        if self.config.state_snapshot_sync && is_far_behind {
+            debug!(
+                "[state sync] start state snapshot sync at version {}",
+                requested_version
+            );
+            self.state_snapshot = Some(StateSnapshotProgress {
+                target,
+                left_key: None,
+                last_request_tst: UNIX_EPOCH,
+            });
+            self.request_state_chunk().await;
+        } else {
+            self.request_next_chunk(0).await;
+        }
     }
 
     async fn commit(&mut self, version: u64) {
@@ -344,6 +396,10 @@ impl<T: ExecutorProxyTrait> SyncCoordinator<T> {
         response: GetChunkResponse,
     ) -> Result<()> {
         counters::OP_COUNTERS.inc(&format!("{}.{}", counters::RESPONSES_RECEIVED, peer_id));
+        ensure!(
+            self.state_snapshot.is_none(),
+            "[state sync] state snapshot sync is in progress"
+        );
         let txn_list_with_proof: TransactionListWithProof = response
             .txn_list_with_proof
             .ok_or_else(|| format_err!("Missing txn_list_with_proof"))?
@@ -417,9 +473,145 @@ impl<T: ExecutorProxyTrait> SyncCoordinator<T> {
         Ok(())
     }
 
+    /// Get a chunk of account states of the state snapshot at the requested version
+    async fn process_state_chunk_request(
+        &mut self,
+        peer_id: PeerId,
+        request: GetStateChunkRequest,
+    ) -> Result<()> {
+        ensure!(
+            request.limit <= self.config.max_chunk_limit,
+            "[state sync] state chunk limit: {:?}, but it must not exceed {:?}",
+            request.limit,
+            self.config.max_chunk_limit
+        );
+        let left_key = if request.left_key.is_empty() {
+            None
+        } else {
+            Some(HashValue::from_slice(&request.left_key)?)
+        };
+        let target: LedgerInfo = request
+            .ledger_info_with_sigs
+            .ok_or_else(|| format_err!("Missing ledger_info_with_sigs"))?
+            .try_into()?;
+
+        debug!(
+            "[state sync] state chunk request: peer_id: {:?}, left_key: {:?}, target: {}",
+            peer_id,
+            left_key,
+            target.ledger_info().version()
+        );
+
+        let mut sender = self
+            .peer_manager
+            .get_network_sender(&peer_id)
+            .ok_or_else(|| {
+                format_err!("[state sync] failed to find network for peer {}", peer_id)
+            })?;
+        let response = self
+            .executor_proxy
+            .get_state_chunk(left_key, request.limit, target)
+            .await?;
+        let msg = StateSynchronizerMsg {
+            message: Some(StateSynchronizerMsg_oneof::StateChunkResponse(response)),
+        };
+        if sender.send_to(peer_id, msg).await.is_err() {
+            error!("[state sync] failed to send p2p message");
+        }
+        Ok(())
+    }
+
+    /// verifies and saves chunk of account states downloaded from peer
+    /// finishes the state snapshot sync once the last chunk is saved
+    async fn process_state_chunk_response(
+        &mut self,
+        peer_id: &PeerId,
+        response: GetStateChunkResponse,
+    ) -> Result<()> {
+        counters::OP_COUNTERS.inc(&format!(
+            "{}.{}",
+            counters::STATE_RESPONSES_RECEIVED,
+            peer_id
+        ));
+        let (target, left_key) = match &self.state_snapshot {
+            Some(progress) => (progress.target.clone(), progress.left_key),
+            None => bail!("[state sync] no state snapshot sync in progress"),
+        };
+        let version = target.ledger_info().version();
+        let chunk: AccountStateChunkWithProof = response
+            .account_state_chunk_with_proof
+            .ok_or_else(|| format_err!("Missing account_state_chunk_with_proof"))?
+            .try_into()?;
+        ensure!(
+            chunk.left_key == left_key,
+            "[state sync] non sequential state chunk. Expected left key: {:?}, received: {:?}",
+            left_key,
+            chunk.left_key
+        );
+        if let Err(err) = chunk.verify(target.ledger_info(), version) {
+            self.peer_manager
+                .update_score(peer_id, PeerScoreUpdateType::InvalidChunk);
+            return Err(err);
+        }
+
+        let num_accounts = chunk.account_blobs.len();
+        let last_key = chunk.last_key();
+        let is_last_chunk = chunk.is_last_chunk();
+        let txn_info = chunk.proof.transaction_info().clone();
+        let txn_info_proof = chunk.proof.ledger_info_to_transaction_info_proof().clone();
+        self.executor_proxy.save_state_chunk(chunk).await?;
+        counters::STATE_SNAPSHOT_ACCOUNTS_SYNCED.inc_by(num_accounts as i64);
+        debug!(
+            "[state sync] saved state chunk. Version: {}, last key: {:?}, chunk size: {}",
+            version, last_key, num_accounts
+        );
+
+        if !is_last_chunk {
+            if let Some(progress) = self.state_snapshot.as_mut() {
+                progress.left_key = last_key;
+                progress.last_request_tst = UNIX_EPOCH;
+            }
+            self.request_state_chunk().await;
+            return Ok(());
+        }
+
+        let ledger_frozen_subtree_hashes = response
+            .ledger_frozen_subtree_hashes
+            .into_iter()
+            .map(|hash| HashValue::from_slice(&hash))
+            .collect::<Result<Vec<_>>>()?;
+        self.executor_proxy
+            .finish_state_snapshot(
+                target,
+                txn_info,
+                txn_info_proof,
+                ledger_frozen_subtree_hashes,
+            )
+            .await?;
+        self.state_snapshot = None;
+        debug!(
+            "[state sync] state snapshot sync is finished at version {}",
+            version
+        );
+        self.commit(version).await;
+        // replay the transactions between the snapshot and the latest target
+        self.request_next_chunk(0).await;
+        Ok(())
+    }
+
     /// ensures that StateSynchronizer makes progress
     /// if peer is not responding, issues new sync request
     async fn check_progress(&mut self) {
+        if let Some(progress) = &self.state_snapshot {
+            let timeout = Duration::from_millis(2 * self.config.tick_interval_ms);
+            if let Some(tst) = progress.last_request_tst.checked_add(timeout) {
+                if SystemTime::now().duration_since(tst).is_ok() {
+                    self.request_state_chunk().await;
+                    counters::TIMEOUT.inc();
+                }
+            }
+            return;
+        }
         if !self.peer_manager.is_empty() && (self.autosync || self.target.is_some()) {
             let last_request_tst = self
                 .peer_manager
@@ -479,6 +671,40 @@ impl<T: ExecutorProxyTrait> SyncCoordinator<T> {
         }
     }
 
+    async fn request_state_chunk(&mut self) {
+        let (target, left_key) = match &self.state_snapshot {
+            Some(progress) => (progress.target.clone(), progress.left_key),
+            None => return,
+        };
+        if let Some((peer_id, mut sender)) = self.peer_manager.pick_peer() {
+            let req = GetStateChunkRequest {
+                left_key: left_key.map_or_else(Vec::new, |key| key.to_vec()),
+                limit: self.config.state_chunk_limit,
+                ledger_info_with_sigs: Some(target.into()),
+            };
+            debug!(
+                "[state sync] request state chunk. peer_id: {:?}, left_key: {:?}",
+                peer_id, left_key
+            );
+
+            let msg = StateSynchronizerMsg {
+                message: Some(StateSynchronizerMsg_oneof::StateChunkRequest(req)),
+            };
+
+            if sender.send_to(peer_id, msg).await.is_err() {
+                error!("[state sync] failed to send p2p message");
+            }
+            if let Some(progress) = self.state_snapshot.as_mut() {
+                progress.last_request_tst = SystemTime::now();
+            }
+            counters::OP_COUNTERS.inc(&format!(
+                "{}.{}",
+                counters::STATE_REQUESTS_SENT,
+                peer_id
+            ));
+        }
+    }
+
     async fn store_transactions(
         &self,
         txn_list_with_proof: TransactionListWithProof,
@@ -21,11 +21,27 @@ pub const APPLY_CHUNK_SUCCESS: &str = "apply_chunk_success";
 /// Number of failed attempts to apply a chunk
 pub const APPLY_CHUNK_FAILURE: &str = "apply_chunk_failure";
 
+/// Number of state snapshot chunk requests sent from a node
+pub const STATE_REQUESTS_SENT: &str = "state_requests_sent";
+
+/// Number of state snapshot chunk responses a node received
+pub const STATE_RESPONSES_RECEIVED: &str = "state_responses_received";
+
+/// Number of Success results of saving a state snapshot chunk
+pub const APPLY_STATE_CHUNK_SUCCESS: &str = "apply_state_chunk_success";
+
+/// Number of failed attempts to save a state snapshot chunk
+pub const APPLY_STATE_CHUNK_FAILURE: &str = "apply_state_chunk_failure";
+
 lazy_static::lazy_static! {
 /// Count the overall number of transactions state synchronizer has retrieved since last restart.
 /// Large values mean that a node has been significantly behind and had to replay a lot of txns.
 pub static ref STATE_SYNC_TXN_REPLAYED: IntCounter = OP_COUNTERS.counter("state_sync_txns_replayed");
 
+/// Count the overall number of accounts state synchronizer has downloaded as part of state
+/// snapshots since last restart.
+pub static ref STATE_SNAPSHOT_ACCOUNTS_SYNCED: IntCounter = OP_COUNTERS.counter("state_snapshot_accounts_synced");
+
 /// Number of peers that are currently active and upstream.
 /// They are the set of nodes a node can make sync requests to
 pub static ref ACTIVE_UPSTREAM_PEERS: IntGauge = OP_COUNTERS.gauge("active_upstream_peers");
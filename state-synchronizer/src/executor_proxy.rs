@@ -1,17 +1,22 @@
 use crate::LedgerInfo;
 use config::config::NodeConfig;
+use crypto::HashValue;
 use executor::Executor;
 use failure::prelude::*;
 use futures::{channel::oneshot, Future, FutureExt};
 use grpcio::EnvBuilder;
 use libra_types::{
+    account_state_blob::AccountStateChunkWithProof,
     crypto_proxies::{LedgerInfoWithSignatures, ValidatorVerifier},
-    transaction::TransactionListWithProof,
+    proof::TransactionAccumulatorProof,
+    transaction::{TransactionInfo, TransactionListWithProof},
 };
 use logger::prelude::*;
-use network::proto::GetChunkResponse;
+use network::proto::{GetChunkResponse, GetStateChunkResponse};
 use std::{pin::Pin, sync::Arc};
-use storage_client::{StorageRead, StorageReadServiceClient};
+use storage_client::{
+    StorageRead, StorageReadServiceClient, StorageWrite, StorageWriteServiceClient,
+};
 use vm_runtime::MoveVM;
 
 /// Proxies interactions with execution and storage for state synchronization
@@ -37,11 +42,36 @@ pub trait ExecutorProxyTrait: Sync + Send {
         target: LedgerInfoWithSignatures,
     ) -> Pin<Box<dyn Future<Output = Result<GetChunkResponse>> + Send>>;
 
+    /// Gets chunk of account states of the state snapshot at the version of `target`
+    fn get_state_chunk(
+        &self,
+        left_key: Option<HashValue>,
+        limit: u64,
+        target: LedgerInfoWithSignatures,
+    ) -> Pin<Box<dyn Future<Output = Result<GetStateChunkResponse>> + Send>>;
+
+    /// Saves a verified chunk of account states of the state snapshot being restored
+    fn save_state_chunk(
+        &self,
+        chunk: AccountStateChunkWithProof,
+    ) -> Pin<Box<dyn Future<Output = Result<()>> + Send>>;
+
+    /// Finishes the state snapshot restore at the version of `target` and makes it the latest
+    /// committed state
+    fn finish_state_snapshot(
+        &self,
+        target: LedgerInfoWithSignatures,
+        txn_info: TransactionInfo,
+        txn_info_proof: TransactionAccumulatorProof,
+        ledger_frozen_subtree_hashes: Vec<HashValue>,
+    ) -> Pin<Box<dyn Future<Output = Result<()>> + Send>>;
+
     fn validate_ledger_info(&self, target: &LedgerInfoWithSignatures) -> Result<()>;
 }
 
 pub(crate) struct ExecutorProxy {
     storage_read_client: Arc<StorageReadServiceClient>,
+    storage_write_client: Arc<StorageWriteServiceClient>,
     executor: Arc<Executor<MoveVM>>,
     validator_verifier: ValidatorVerifier,
 }
@@ -50,13 +80,20 @@ impl ExecutorProxy {
     pub(crate) fn new(executor: Arc<Executor<MoveVM>>, config: &NodeConfig) -> Self {
         let client_env = Arc::new(EnvBuilder::new().name_prefix("grpc-coord-").build());
         let storage_read_client = Arc::new(StorageReadServiceClient::new(
+            Arc::clone(&client_env),
+            &config.storage.address,
+            config.storage.port,
+        ));
+        let storage_write_client = Arc::new(StorageWriteServiceClient::new(
             client_env,
             &config.storage.address,
             config.storage.port,
+            config.storage.grpc_max_receive_len,
         ));
         let validator_verifier = config.consensus.consensus_peers.get_validator_verifier();
         Self {
             storage_read_client,
+            storage_write_client,
             executor,
             validator_verifier,
         }
@@ -135,6 +172,61 @@ impl ExecutorProxyTrait for ExecutorProxy {
             .boxed()
     }
 
+    fn get_state_chunk(
+        &self,
+        left_key: Option<HashValue>,
+        limit: u64,
+        target: LedgerInfoWithSignatures,
+    ) -> Pin<Box<dyn Future<Output = Result<GetStateChunkResponse>> + Send>> {
+        let client = Arc::clone(&self.storage_read_client);
+        async move {
+            let version = target.ledger_info().version();
+            let (chunk, ledger_frozen_subtree_hashes) = client
+                .get_account_state_chunk_with_proof_async(version, version, left_key, limit)
+                .await?;
+            Ok(GetStateChunkResponse {
+                ledger_info_with_sigs: Some(target.into()),
+                account_state_chunk_with_proof: Some(chunk.into()),
+                ledger_frozen_subtree_hashes: ledger_frozen_subtree_hashes
+                    .iter()
+                    .map(|hash| hash.to_vec())
+                    .collect(),
+            })
+        }
+            .boxed()
+    }
+
+    fn save_state_chunk(
+        &self,
+        chunk: AccountStateChunkWithProof,
+    ) -> Pin<Box<dyn Future<Output = Result<()>> + Send>> {
+        self.storage_write_client.save_account_state_chunk_async(chunk)
+    }
+
+    fn finish_state_snapshot(
+        &self,
+        target: LedgerInfoWithSignatures,
+        txn_info: TransactionInfo,
+        txn_info_proof: TransactionAccumulatorProof,
+        ledger_frozen_subtree_hashes: Vec<HashValue>,
+    ) -> Pin<Box<dyn Future<Output = Result<()>> + Send>> {
+        let client = Arc::clone(&self.storage_write_client);
+        let executor = Arc::clone(&self.executor);
+        async move {
+            client
+                .finish_account_state_restore_async(
+                    target,
+                    txn_info,
+                    txn_info_proof,
+                    ledger_frozen_subtree_hashes,
+                )
+                .await?;
+            // storage was updated behind the executor's back, so it has to reload its trees
+            convert_to_future(executor.reload_committed_trees()).await
+        }
+            .boxed()
+    }
+
     fn validate_ledger_info(&self, target: &LedgerInfo) -> Result<()> {
         target.verify(&self.validator_verifier)?;
         Ok(())
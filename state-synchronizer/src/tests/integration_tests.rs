@@ -6,20 +6,30 @@ use crate::{
 };
 use config::config::RoleType;
 use config_builder::util::get_test_config;
-use crypto::{ed25519::*, test_utils::TEST_SEED, traits::Genesis, x25519, HashValue, SigningKey};
+use crypto::{
+    ed25519::*,
+    hash::CryptoHash,
+    test_utils::TEST_SEED,
+    traits::Genesis,
+    x25519, HashValue, SigningKey,
+};
 use failure::{prelude::*, Result};
 use futures::{executor::block_on, future::FutureExt, Future};
 use libra_types::{
     account_address::AccountAddress,
+    account_state_blob::{AccountStateBlob, AccountStateChunkWithProof},
     crypto_proxies::LedgerInfoWithSignatures,
     ledger_info::LedgerInfo as TypesLedgerInfo,
-    proof::AccumulatorProof,
+    proof::{
+        AccountStateChunkProof, AccumulatorProof, SparseMerkleLeafNode, SparseMerkleRangeProof,
+        TransactionAccumulatorInternalNode, TransactionAccumulatorProof,
+    },
     test_helpers::transaction_test_helpers::get_test_signed_txn,
     transaction::{TransactionInfo, TransactionListWithProof},
     vm_error::StatusCode,
 };
 use network::{
-    proto::GetChunkResponse,
+    proto::{GetChunkResponse, GetStateChunkResponse},
     validator_network::{
         network_builder::{NetworkBuilder, TransportType},
         STATE_SYNCHRONIZER_MSG_PROTOCOL,
@@ -33,7 +43,7 @@ use std::{
     pin::Pin,
     sync::{
         atomic::{AtomicU64, AtomicUsize, Ordering},
-        Arc,
+        Arc, Mutex,
     },
 };
 use tokio::runtime::{Builder, Runtime};
@@ -47,6 +57,8 @@ pub struct MockExecutorProxy {
     peer_id: PeerId,
     handler: MockRpcHandler,
     version: AtomicU64,
+    // keys of the accounts restored from state snapshot chunks
+    restored_accounts: Arc<Mutex<Vec<HashValue>>>,
 }
 
 impl MockExecutorProxy {
@@ -55,13 +67,55 @@ impl MockExecutorProxy {
             peer_id,
             handler,
             version: AtomicU64::new(0),
+            restored_accounts: Arc::new(Mutex::new(vec![])),
         }
     }
 
+    /// The state at every version holds a single account.
+    fn mock_account() -> (HashValue, AccountStateBlob) {
+        (
+            HashValue::from_sha3_256(b"account"),
+            AccountStateBlob::from(b"blob".to_vec()),
+        )
+    }
+
+    /// The transaction info at every version, whose state root is the one of the mock account.
+    fn mock_txn_info() -> TransactionInfo {
+        let (key, blob) = Self::mock_account();
+        TransactionInfo::new(
+            HashValue::zero(),
+            SparseMerkleLeafNode::new(key, blob.hash()).hash(),
+            HashValue::zero(),
+            0,
+            StatusCode::EXECUTED,
+        )
+    }
+
+    /// The proof of the mock transaction info at `version` in the mock transaction accumulator.
+    fn mock_txn_info_proof(version: u64) -> TransactionAccumulatorProof {
+        let depth = 64 - version.leading_zeros() as usize;
+        TransactionAccumulatorProof::new(vec![HashValue::from_sha3_256(b"sibling"); depth])
+    }
+
+    /// The root of the mock transaction accumulator at `version`.
+    fn mock_accumulator_root(version: u64) -> HashValue {
+        let mut hash = Self::mock_txn_info().hash();
+        let mut index = version;
+        for sibling in Self::mock_txn_info_proof(version).siblings().iter().rev() {
+            hash = if index % 2 == 0 {
+                TransactionAccumulatorInternalNode::new(hash, *sibling).hash()
+            } else {
+                TransactionAccumulatorInternalNode::new(*sibling, hash).hash()
+            };
+            index /= 2;
+        }
+        hash
+    }
+
     fn mock_ledger_info(peer_id: PeerId, version: u64) -> LedgerInfo {
         let ledger_info = TypesLedgerInfo::new(
             version,
-            HashValue::zero(),
+            Self::mock_accumulator_root(version),
             HashValue::zero(),
             HashValue::zero(),
             0,
@@ -144,6 +198,54 @@ impl ExecutorProxyTrait for MockExecutorProxy {
         async move { response }.boxed()
     }
 
+    fn get_state_chunk(
+        &self,
+        left_key: Option<HashValue>,
+        _: u64,
+        target: LedgerInfo,
+    ) -> Pin<Box<dyn Future<Output = Result<GetStateChunkResponse>> + Send>> {
+        let version = target.ledger_info().version();
+        let chunk = AccountStateChunkWithProof::new(
+            version,
+            left_key,
+            vec![Self::mock_account()],
+            AccountStateChunkProof::new(
+                Self::mock_txn_info_proof(version),
+                Self::mock_txn_info(),
+                SparseMerkleRangeProof::new(None, None),
+            ),
+        );
+        let response = GetStateChunkResponse {
+            ledger_info_with_sigs: Some(target.into()),
+            account_state_chunk_with_proof: Some(chunk.into()),
+            ledger_frozen_subtree_hashes: vec![],
+        };
+        async move { Ok(response) }.boxed()
+    }
+
+    fn save_state_chunk(
+        &self,
+        chunk: AccountStateChunkWithProof,
+    ) -> Pin<Box<dyn Future<Output = Result<()>> + Send>> {
+        self.restored_accounts
+            .lock()
+            .unwrap()
+            .extend(chunk.account_blobs.iter().map(|(key, _)| *key));
+        async move { Ok(()) }.boxed()
+    }
+
+    fn finish_state_snapshot(
+        &self,
+        target: LedgerInfo,
+        _: TransactionInfo,
+        _: TransactionAccumulatorProof,
+        _: Vec<HashValue>,
+    ) -> Pin<Box<dyn Future<Output = Result<()>> + Send>> {
+        self.version
+            .store(target.ledger_info().version(), Ordering::Relaxed);
+        async move { Ok(()) }.boxed()
+    }
+
     fn validate_ledger_info(&self, _target: &LedgerInfo) -> Result<()> {
         Ok(())
     }
@@ -154,10 +256,21 @@ struct SynchronizerEnv {
     _synchronizers: Vec<StateSynchronizer>,
     peers: Vec<PeerId>,
     clients: Vec<Arc<StateSyncClient>>,
+    // accounts restored by the first peer from state snapshots
+    restored_accounts: Arc<Mutex<Vec<HashValue>>>,
 }
 
 impl SynchronizerEnv {
     fn new(handler: MockRpcHandler, role: RoleType) -> Self {
+        Self::new_impl(handler, role, false)
+    }
+
+    /// The first peer syncs from a state snapshot whenever it is behind its target.
+    fn new_with_state_snapshot_sync() -> Self {
+        Self::new_impl(Self::default_handler(), RoleType::Validator, true)
+    }
+
+    fn new_impl(handler: MockRpcHandler, role: RoleType, state_snapshot_sync: bool) -> Self {
         let runtime = Builder::new().build().unwrap();
         let peers = vec![PeerId::random(), PeerId::random()];
 
@@ -236,11 +349,17 @@ impl SynchronizerEnv {
             .upstream_peers
             .upstream_peers
             .push(peers[1].to_string());
+        if state_snapshot_sync {
+            config.state_sync.state_snapshot_sync = true;
+            config.state_sync.state_snapshot_threshold = 0;
+        }
+        let executor_proxy = MockExecutorProxy::new(peers[0], Self::default_handler());
+        let restored_accounts = Arc::clone(&executor_proxy.restored_accounts);
         let synchronizers: Vec<StateSynchronizer> = vec![
             StateSynchronizer::bootstrap_with_executor_proxy(
                 vec![(sender_a, events_a)],
                 &config.state_sync,
-                MockExecutorProxy::new(peers[0], Self::default_handler()),
+                executor_proxy,
             ),
             StateSynchronizer::bootstrap_with_executor_proxy(
                 vec![(sender_b, events_b)],
@@ -253,6 +372,7 @@ impl SynchronizerEnv {
         Self {
             peers,
             clients,
+            restored_accounts,
             _synchronizers: synchronizers,
             _runtime: runtime,
         }
@@ -324,3 +444,16 @@ fn test_full_node() {
     // after receiving first chunk immediately
     assert!(env.wait_for_version(0, 20));
 }
+
+#[test]
+fn test_state_snapshot_sync() {
+    let env = SynchronizerEnv::new_with_state_snapshot_sync();
+    assert!(env.sync_to(0, 10));
+    assert_eq!(
+        *env.restored_accounts.lock().unwrap(),
+        vec![MockExecutorProxy::mock_account().0]
+    );
+    // a later target far enough ahead is again reached from a snapshot
+    assert!(env.sync_to(0, 20));
+    assert_eq!(env.restored_accounts.lock().unwrap().len(), 2);
+}
@@ -0,0 +1,114 @@
+// Copyright (c) The Libra Core Contributors
+// SPDX-License-Identifier: Apache-2.0
+
+//! This module takes a backup of a [`LibraDB`].
+
+use crate::manifest::{backup_dir, BackupManifest, FileHandle, TransactionChunkHandle};
+use failure::prelude::*;
+use libra_types::crypto_proxies::LedgerInfoWithSignatures;
+use libradb::LibraDB;
+use logger::prelude::*;
+use prost_ext::MessageExt;
+use std::{fs, path::Path};
+
+/// Maximum number of transactions in a transaction chunk file.
+pub const TRANSACTION_CHUNK_SIZE: u64 = 1000;
+
+/// Maximum number of accounts in a state chunk file.
+pub const STATE_CHUNK_SIZE: u64 = 1000;
+
+/// Backs up `db` at the version of its latest ledger info into a new directory under
+/// `backup_root`, and returns the manifest of the backup.
+pub fn backup(db: &LibraDB, backup_root: &Path) -> Result<BackupManifest> {
+    ensure!(db.get_startup_info()?.is_some(), "DB is empty.");
+    let (_, ledger_info_with_sigs, _, _) = db.update_to_latest_ledger(0, vec![])?;
+    let ledger_info = ledger_info_with_sigs.ledger_info();
+    let version = ledger_info.version();
+
+    let dir = backup_dir(backup_root, version);
+    ensure!(!dir.exists(), "Backup at version {} already exists.", version);
+    fs::create_dir_all(&dir)?;
+    info!("Backing up version {} to {:?}.", version, dir);
+
+    let mut epoch_ledger_infos = db
+        .get_latest_ledger_infos_per_epoch(0 /* start_epoch */)?
+        .into_iter()
+        .filter(|li| {
+            li.ledger_info().epoch() < ledger_info.epoch() && li.ledger_info().version() <= version
+        })
+        .collect::<Vec<_>>();
+    epoch_ledger_infos.sort_by_key(|li| li.ledger_info().epoch());
+    let epoch_ledger_infos = epoch_ledger_infos
+        .into_iter()
+        .map(|li| {
+            let name = format!("epoch_{}.ledger_info", li.ledger_info().epoch());
+            write_ledger_info(&dir, name, li)
+        })
+        .collect::<Result<Vec<_>>>()?;
+
+    let mut transaction_chunks = Vec::new();
+    let mut first_version = 0;
+    while first_version <= version {
+        let txn_list_with_proof = db.get_transactions(
+            first_version,
+            TRANSACTION_CHUNK_SIZE,
+            version, /* ledger_version */
+            true,    /* fetch_events */
+        )?;
+        let last_version = first_version + txn_list_with_proof.len() as u64 - 1;
+        let proto: libra_types::proto::types::TransactionListWithProof =
+            txn_list_with_proof.into();
+        let name = format!("transactions_{}-{}.chunk", first_version, last_version);
+        transaction_chunks.push(TransactionChunkHandle {
+            first_version,
+            last_version,
+            file: FileHandle::write(&dir, name, &proto.to_vec()?)?,
+        });
+        first_version = last_version + 1;
+    }
+
+    let mut state_chunks = Vec::new();
+    let mut left_key = None;
+    loop {
+        let chunk = db.get_account_state_chunk_with_proof(
+            version,
+            version, /* ledger_version */
+            left_key,
+            STATE_CHUNK_SIZE,
+        )?;
+        left_key = chunk.last_key();
+        let is_last_chunk = chunk.is_last_chunk();
+        let proto: libra_types::proto::types::AccountStateChunkWithProof = chunk.into();
+        let name = format!("state_{}.chunk", state_chunks.len());
+        state_chunks.push(FileHandle::write(&dir, name, &proto.to_vec()?)?);
+        if is_last_chunk {
+            break;
+        }
+    }
+
+    let manifest = BackupManifest {
+        version,
+        ledger_info: write_ledger_info(&dir, "ledger_info".to_string(), ledger_info_with_sigs)?,
+        epoch_ledger_infos,
+        transaction_chunks,
+        state_chunks,
+    };
+    manifest.save(&dir)?;
+    info!(
+        "Backed up {} transaction chunks and {} state chunks at version {}.",
+        manifest.transaction_chunks.len(),
+        manifest.state_chunks.len(),
+        version,
+    );
+
+    Ok(manifest)
+}
+
+fn write_ledger_info(
+    dir: &Path,
+    name: String,
+    ledger_info_with_sigs: LedgerInfoWithSignatures,
+) -> Result<FileHandle> {
+    let proto: libra_types::proto::types::LedgerInfoWithSignatures = ledger_info_with_sigs.into();
+    FileHandle::write(dir, name, &proto.to_vec()?)
+}
@@ -0,0 +1,234 @@
+// Copyright (c) The Libra Core Contributors
+// SPDX-License-Identifier: Apache-2.0
+
+use crate::{
+    backup::backup,
+    manifest::{backup_dir, BackupManifest, FileHandle},
+    restore::restore,
+};
+use crypto::{hash::CryptoHash, x25519};
+use failure::prelude::*;
+use libra_tools::tempdir::TempPath;
+use libra_types::{
+    crypto_proxies::{LedgerInfoWithSignatures, ValidatorSigner},
+    ledger_info::LedgerInfo,
+    transaction::TransactionToCommit,
+    validator_public_keys::ValidatorPublicKeys,
+    validator_set::ValidatorSet,
+};
+use libradb::{mock_genesis::GENESIS_INFO, test_helper::arb_blocks_to_commit, LibraDB};
+use proptest::prelude::*;
+use prost_ext::MessageExt;
+use std::{collections::BTreeMap, fs};
+
+/// Returns the validators of `epoch`.
+fn epoch_signers(epoch: u64) -> Vec<ValidatorSigner> {
+    (0..4u8)
+        .map(|i| {
+            let mut seed = [i; 32];
+            seed[..8].copy_from_slice(&epoch.to_le_bytes());
+            ValidatorSigner::random(seed)
+        })
+        .collect()
+}
+
+fn validator_set(signers: &[ValidatorSigner]) -> ValidatorSet {
+    ValidatorSet::new(
+        signers
+            .iter()
+            .map(|signer| {
+                ValidatorPublicKeys::new(
+                    signer.author(),
+                    signer.public_key(),
+                    1, /* consensus_voting_power */
+                    signer.public_key(),
+                    x25519::compat::generate_keypair(None).1,
+                )
+            })
+            .collect(),
+    )
+}
+
+fn sign(ledger_info: LedgerInfo, signers: &[ValidatorSigner]) -> LedgerInfoWithSignatures {
+    let hash = ledger_info.hash();
+    let signatures = signers
+        .iter()
+        .map(|signer| (signer.author(), signer.sign_message(hash).unwrap()))
+        .collect();
+    LedgerInfoWithSignatures::new(ledger_info, signatures)
+}
+
+/// Returns `ledger_info` moved to `epoch` and announcing `next_validator_set`.
+fn with_epoch(
+    ledger_info: &LedgerInfo,
+    epoch: u64,
+    next_validator_set: Option<ValidatorSet>,
+) -> LedgerInfo {
+    LedgerInfo::new(
+        ledger_info.version(),
+        ledger_info.transaction_accumulator_hash(),
+        ledger_info.consensus_data_hash(),
+        ledger_info.consensus_block_id(),
+        epoch,
+        ledger_info.timestamp_usecs(),
+        next_validator_set,
+    )
+}
+
+/// Turns the ledger infos of `blocks` into a chain of epochs: the genesis announces the validators
+/// of epoch 1, and the ledger info of the `i`-th block ends epoch `i + 1`, signed by its validators
+/// and announcing those of the next epoch.
+fn db_with_epochs(
+    dir: &TempPath,
+    blocks: Vec<(Vec<TransactionToCommit>, LedgerInfoWithSignatures)>,
+) -> Result<(LibraDB, LedgerInfoWithSignatures)> {
+    let genesis_ledger_info = with_epoch(
+        GENESIS_INFO.1.ledger_info(),
+        0, /* epoch */
+        Some(validator_set(&epoch_signers(1))),
+    );
+    let db = LibraDB::new(dir);
+    db.save_transactions(
+        &[GENESIS_INFO.2.clone()],
+        0, /* first_version */
+        &Some(LedgerInfoWithSignatures::new(
+            genesis_ledger_info,
+            BTreeMap::new(),
+        )),
+    )?;
+
+    let mut cur_ver = 0;
+    let mut ledger_info_with_sigs = None;
+    for (i, (txns_to_commit, partial_ledger_info_with_sigs)) in blocks.into_iter().enumerate() {
+        let epoch = i as u64 + 1;
+        let ledger_info = with_epoch(
+            partial_ledger_info_with_sigs.ledger_info(),
+            epoch,
+            Some(validator_set(&epoch_signers(epoch + 1))),
+        );
+        let signed = sign(ledger_info, &epoch_signers(epoch));
+        db.save_transactions(
+            &txns_to_commit,
+            cur_ver + 1, /* first_version */
+            &Some(signed.clone()),
+        )?;
+        cur_ver += txns_to_commit.len() as u64;
+        ledger_info_with_sigs = Some(signed);
+    }
+    Ok((db, ledger_info_with_sigs.unwrap()))
+}
+
+/// Backs up `db` under a new backup root, then replaces the ledger info ending `epoch` by
+/// `ledger_info_with_sigs` and updates the manifest accordingly, as anyone handling the backup
+/// could.
+fn tampered_backup(
+    db: &LibraDB,
+    epoch: u64,
+    ledger_info_with_sigs: LedgerInfoWithSignatures,
+) -> Result<TempPath> {
+    let backup_root = TempPath::new();
+    backup_root.create_as_dir()?;
+    let mut manifest = backup(db, backup_root.path())?;
+    let dir = backup_dir(backup_root.path(), manifest.version);
+
+    let file = &mut manifest.epoch_ledger_infos[epoch as usize];
+    fs::remove_file(dir.join(&file.name))?;
+    let proto: libra_types::proto::types::LedgerInfoWithSignatures = ledger_info_with_sigs.into();
+    *file = FileHandle::write(&dir, file.name.clone(), &proto.to_vec()?)?;
+    manifest.save(&dir)?;
+    Ok(backup_root)
+}
+
+fn test_backup_and_restore_impl(
+    blocks: Vec<(Vec<TransactionToCommit>, LedgerInfoWithSignatures)>,
+) -> Result<()> {
+    let tmp_dir = TempPath::new();
+    let (db, ledger_info_with_sigs) = db_with_epochs(&tmp_dir, blocks)?;
+    let backup_root = TempPath::new();
+    backup_root.create_as_dir()?;
+    let manifest = backup(&db, backup_root.path())?;
+
+    let version = ledger_info_with_sigs.ledger_info().version();
+    assert_eq!(manifest.version, version);
+    assert_eq!(
+        BackupManifest::load(&backup_dir(backup_root.path(), version))?,
+        manifest
+    );
+
+    let restore_tmp_dir = TempPath::new();
+    let restore_db = LibraDB::new(&restore_tmp_dir);
+    restore(&restore_db, backup_root.path(), &ledger_info_with_sigs)?;
+
+    assert_eq!(restore_db.get_startup_info()?, db.get_startup_info()?);
+    assert_eq!(
+        restore_db.get_transactions(0, version + 1, version, true /* fetch_events */)?,
+        db.get_transactions(0, version + 1, version, true /* fetch_events */)?,
+    );
+    let epoch = ledger_info_with_sigs.ledger_info().epoch();
+    let mut expected_epoch_ledger_infos = db
+        .get_latest_ledger_infos_per_epoch(0 /* start_epoch */)?
+        .into_iter()
+        .filter(|li| li.ledger_info().epoch() < epoch && li.ledger_info().version() <= version)
+        .collect::<Vec<_>>();
+    expected_epoch_ledger_infos.push(ledger_info_with_sigs.clone());
+    expected_epoch_ledger_infos.sort_by_key(|li| li.ledger_info().epoch());
+    let mut restored_epoch_ledger_infos =
+        restore_db.get_latest_ledger_infos_per_epoch(0 /* start_epoch */)?;
+    restored_epoch_ledger_infos.sort_by_key(|li| li.ledger_info().epoch());
+    assert_eq!(restored_epoch_ledger_infos, expected_epoch_ledger_infos);
+
+    // An epoch announcing other validators than the ones which signed the next epoch is rejected,
+    // even when it is signed by its own validators.
+    let last_epoch = epoch - 1;
+    let last_epoch_ledger_info = expected_epoch_ledger_infos[last_epoch as usize].clone();
+    let forged_ledger_info = with_epoch(
+        last_epoch_ledger_info.ledger_info(),
+        last_epoch,
+        Some(validator_set(&epoch_signers(u64::max_value()))),
+    );
+    let forged_signers = if last_epoch == 0 {
+        vec![]
+    } else {
+        epoch_signers(last_epoch)
+    };
+    let forged_root = tampered_backup(&db, last_epoch, sign(forged_ledger_info, &forged_signers))?;
+    let forged_tmp_dir = TempPath::new();
+    let forged_db = LibraDB::new(&forged_tmp_dir);
+    assert!(restore(&forged_db, forged_root.path(), &ledger_info_with_sigs).is_err());
+
+    // An epoch signed by anyone but the validators announced by the previous epoch is rejected.
+    if last_epoch > 0 {
+        let forged_root = tampered_backup(
+            &db,
+            last_epoch,
+            sign(
+                last_epoch_ledger_info.ledger_info().clone(),
+                &epoch_signers(u64::max_value()),
+            ),
+        )?;
+        let forged_tmp_dir = TempPath::new();
+        let forged_db = LibraDB::new(&forged_tmp_dir);
+        assert!(restore(&forged_db, forged_root.path(), &ledger_info_with_sigs).is_err());
+    }
+
+    // Tampering with any file is detected.
+    let state_chunk = &manifest.state_chunks[0];
+    let path = backup_dir(backup_root.path(), version).join(&state_chunk.name);
+    let mut bytes = fs::read(&path)?;
+    bytes.push(0);
+    fs::write(&path, bytes)?;
+    let another_tmp_dir = TempPath::new();
+    let another_db = LibraDB::new(&another_tmp_dir);
+    assert!(restore(&another_db, backup_root.path(), &ledger_info_with_sigs).is_err());
+
+    Ok(())
+}
+
+proptest! {
+    #![proptest_config(ProptestConfig::with_cases(10))]
+
+    #[test]
+    fn test_backup_and_restore(blocks in arb_blocks_to_commit()) {
+        test_backup_and_restore_impl(blocks).unwrap();
+    }
+}
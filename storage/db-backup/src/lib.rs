@@ -0,0 +1,27 @@
+// Copyright (c) The Libra Core Contributors
+// SPDX-License-Identifier: Apache-2.0
+
+//! This crate provides offline backup and restore of [`LibraDB`](libradb::LibraDB).
+//!
+//! A backup is taken at the version of the latest ledger info in the DB, and is written to a
+//! directory named after that version, so that a backup root can hold backups at many versions.
+//! It consists of:
+//!   - the ledger info at the backed up version,
+//!   - the ledger infos ending each of the earlier epochs,
+//!   - all transactions up to the backed up version, together with their infos and events,
+//!   - a snapshot of the account states at the backed up version,
+//!
+//! each stored in files of protobuf encoded chunks, and a manifest which lists the files together
+//! with the hashes of their content. Every chunk carries a proof against the backed up ledger
+//! info, so a backup can be verified against a trusted [`LedgerInfoWithSignatures`] upon restore.
+//! The ledger infos of the earlier epochs are verified to be signed by the validators of their
+//! epochs, from the genesis one up to the trusted ledger info.
+//!
+//! [`LedgerInfoWithSignatures`]: libra_types::crypto_proxies::LedgerInfoWithSignatures
+
+pub mod backup;
+pub mod manifest;
+pub mod restore;
+
+#[cfg(test)]
+mod backup_test;
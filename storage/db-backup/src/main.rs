@@ -0,0 +1,65 @@
+// Copyright (c) The Libra Core Contributors
+// SPDX-License-Identifier: Apache-2.0
+
+use db_backup::{
+    backup::backup,
+    restore::{load_ledger_info, restore},
+};
+use failure::prelude::*;
+use libradb::LibraDB;
+use std::path::PathBuf;
+use structopt::StructOpt;
+
+#[derive(Debug, StructOpt)]
+#[structopt(about = "Offline LibraDB backup and restore")]
+enum Command {
+    /// Backs up the DB at the version of its latest ledger info.
+    Backup {
+        #[structopt(long, parse(from_os_str))]
+        /// Path to the DB, which must not be in use by a running node
+        db_dir: PathBuf,
+        #[structopt(long, parse(from_os_str))]
+        /// Directory to hold backups, each in a sub directory named after its version
+        backup_dir: PathBuf,
+    },
+    /// Restores an empty DB from the backup at the version of the trusted ledger info.
+    Restore {
+        #[structopt(long, parse(from_os_str))]
+        /// Path to the DB to restore
+        db_dir: PathBuf,
+        #[structopt(long, parse(from_os_str))]
+        /// Directory holding backups, each in a sub directory named after its version
+        backup_dir: PathBuf,
+        #[structopt(long, parse(from_os_str))]
+        /// Path to a protobuf encoded LedgerInfoWithSignatures to verify the backup against, like
+        /// the `ledger_info` file in a backup
+        trusted_ledger_info: PathBuf,
+    },
+}
+
+fn main() -> Result<()> {
+    let _logger = logger::set_default_global_logger(false /* async */, None);
+
+    match Command::from_args() {
+        Command::Backup { db_dir, backup_dir } => {
+            let db = LibraDB::new(&db_dir);
+            let manifest = backup(&db, &backup_dir)?;
+            println!("Backed up version {}.", manifest.version);
+        }
+        Command::Restore {
+            db_dir,
+            backup_dir,
+            trusted_ledger_info,
+        } => {
+            let ledger_info_with_sigs = load_ledger_info(&trusted_ledger_info)?;
+            let db = LibraDB::new(&db_dir);
+            restore(&db, &backup_dir, &ledger_info_with_sigs)?;
+            println!(
+                "Restored version {}.",
+                ledger_info_with_sigs.ledger_info().version()
+            );
+        }
+    }
+
+    Ok(())
+}
@@ -0,0 +1,96 @@
+// Copyright (c) The Libra Core Contributors
+// SPDX-License-Identifier: Apache-2.0
+
+//! This module defines the manifest of a backup and how the files it lists are written and read.
+
+use crypto::HashValue;
+use failure::prelude::*;
+use libra_types::transaction::Version;
+use serde::{Deserialize, Serialize};
+use std::{
+    fs,
+    path::{Path, PathBuf},
+};
+
+/// Name of the manifest file in a backup directory.
+pub const MANIFEST_FILE_NAME: &str = "manifest.toml";
+
+/// Returns the directory under `backup_root` that holds the backup at `version`.
+pub fn backup_dir(backup_root: &Path, version: Version) -> PathBuf {
+    backup_root.join(version.to_string())
+}
+
+/// A file in a backup directory, identified by its name and the hash of its content.
+#[derive(Clone, Debug, Deserialize, Eq, PartialEq, Serialize)]
+pub struct FileHandle {
+    pub name: String,
+    /// Hex encoded sha3-256 hash of the file content.
+    pub hash: String,
+}
+
+impl FileHandle {
+    /// Writes `bytes` to a new file named `name` in `dir`.
+    pub fn write(dir: &Path, name: String, bytes: &[u8]) -> Result<Self> {
+        let path = dir.join(&name);
+        ensure!(!path.exists(), "File {:?} already exists.", path);
+        fs::write(&path, bytes)?;
+        Ok(Self {
+            name,
+            hash: hex::encode(HashValue::from_sha3_256(bytes).to_vec()),
+        })
+    }
+
+    /// Reads the file from `dir`, making sure its content hasn't changed since it was written.
+    pub fn read(&self, dir: &Path) -> Result<Vec<u8>> {
+        let bytes = fs::read(dir.join(&self.name))?;
+        let expected_hash = HashValue::from_slice(&hex::decode(&self.hash)?)?;
+        let hash = HashValue::from_sha3_256(&bytes);
+        ensure!(
+            hash == expected_hash,
+            "Hash of file {} doesn't match the manifest. {:?} vs {:?}",
+            self.name,
+            hash,
+            expected_hash,
+        );
+        Ok(bytes)
+    }
+}
+
+/// A file holding a chunk of consecutive transactions.
+#[derive(Clone, Debug, Deserialize, Eq, PartialEq, Serialize)]
+pub struct TransactionChunkHandle {
+    pub first_version: Version,
+    pub last_version: Version,
+    pub file: FileHandle,
+}
+
+/// Lists the files making up the backup at `version`.
+#[derive(Clone, Debug, Deserialize, Eq, PartialEq, Serialize)]
+pub struct BackupManifest {
+    pub version: Version,
+    /// The ledger info at `version`.
+    pub ledger_info: FileHandle,
+    /// The ledger infos ending each epoch before the one of `ledger_info`, in order of epoch.
+    pub epoch_ledger_infos: Vec<FileHandle>,
+    /// All the transactions up to `version`, in order of version.
+    pub transaction_chunks: Vec<TransactionChunkHandle>,
+    /// All the accounts in the state at `version`, in order of account key.
+    pub state_chunks: Vec<FileHandle>,
+}
+
+impl BackupManifest {
+    /// Loads the manifest from the backup directory `dir`.
+    pub fn load(dir: &Path) -> Result<Self> {
+        let contents = fs::read_to_string(dir.join(MANIFEST_FILE_NAME))?;
+        Ok(toml::from_str(&contents)?)
+    }
+
+    /// Saves the manifest to the backup directory `dir`.
+    pub fn save(&self, dir: &Path) -> Result<()> {
+        // Going through `toml::Value` puts plain values before tables, as TOML requires, which
+        // isn't the case for the field order of the struct when some of the lists are empty.
+        let contents = toml::to_string(&toml::Value::try_from(self)?)?;
+        fs::write(dir.join(MANIFEST_FILE_NAME), contents)?;
+        Ok(())
+    }
+}
@@ -0,0 +1,205 @@
+// Copyright (c) The Libra Core Contributors
+// SPDX-License-Identifier: Apache-2.0
+
+//! This module restores a [`LibraDB`] from a backup.
+
+use crate::manifest::{backup_dir, BackupManifest, FileHandle};
+use crypto::hash::CryptoHash;
+use failure::prelude::*;
+use libra_types::{
+    account_state_blob::AccountStateChunkWithProof,
+    crypto_proxies::{LedgerInfoWithSignatures, ValidatorVerifier},
+    transaction::TransactionListWithProof,
+};
+use libradb::LibraDB;
+use logger::prelude::*;
+use prost::Message;
+use std::{convert::TryInto, fs, path::Path};
+
+/// Restores `db`, which must be empty, from the backup under `backup_root` at the version of
+/// `trusted_ledger_info`. Every file is checked against the hash recorded in the manifest, and
+/// everything restored is verified against `trusted_ledger_info`. The ledger infos of earlier
+/// epochs must in addition form a chain of validator sets leading to `trusted_ledger_info`, see
+/// [`verify_epoch_ledger_infos`].
+pub fn restore(
+    db: &LibraDB,
+    backup_root: &Path,
+    trusted_ledger_info: &LedgerInfoWithSignatures,
+) -> Result<()> {
+    ensure!(db.get_startup_info()?.is_none(), "DB is not empty.");
+    let ledger_info = trusted_ledger_info.ledger_info();
+    let version = ledger_info.version();
+
+    let dir = backup_dir(backup_root, version);
+    let manifest = BackupManifest::load(&dir)?;
+    ensure!(
+        manifest.version == version,
+        "Backup in {:?} is at version {}, expected {}.",
+        dir,
+        manifest.version,
+        version,
+    );
+    let backup_ledger_info = read_ledger_info(&dir, &manifest.ledger_info)?;
+    ensure!(
+        backup_ledger_info.ledger_info() == ledger_info,
+        "Backed up ledger info doesn't match the trusted one. {:?} vs {:?}",
+        backup_ledger_info.ledger_info(),
+        ledger_info,
+    );
+    // The epochs are verified before anything is written, as their ledger infos don't carry
+    // proofs against `trusted_ledger_info`.
+    let epoch_ledger_infos = manifest
+        .epoch_ledger_infos
+        .iter()
+        .map(|file| read_ledger_info(&dir, file))
+        .collect::<Result<Vec<_>>>()?;
+    verify_epoch_ledger_infos(&epoch_ledger_infos, trusted_ledger_info)?;
+    info!("Restoring version {} from {:?}.", version, dir);
+
+    // Ledger history.
+    let mut next_version = 0;
+    for chunk in &manifest.transaction_chunks {
+        ensure!(
+            chunk.first_version == next_version,
+            "Transaction chunks are not consecutive. Expected first version {}, got {}.",
+            next_version,
+            chunk.first_version,
+        );
+        let bytes = chunk.file.read(&dir)?;
+        let txn_list_with_proof: TransactionListWithProof =
+            libra_types::proto::types::TransactionListWithProof::decode(&bytes[..])?.try_into()?;
+        txn_list_with_proof.verify(ledger_info, Some(chunk.first_version))?;
+        ensure!(
+            chunk.first_version + txn_list_with_proof.len() as u64 == chunk.last_version + 1,
+            "Transaction chunk {} doesn't hold versions {} to {}.",
+            chunk.file.name,
+            chunk.first_version,
+            chunk.last_version,
+        );
+        db.restore_transactions(txn_list_with_proof)?;
+        next_version = chunk.last_version + 1;
+    }
+    ensure!(
+        next_version == version + 1,
+        "Transactions are backed up before version {}, expected up to version {}.",
+        next_version,
+        version,
+    );
+
+    // State snapshot.
+    let mut left_key = None;
+    let mut last_chunk_proof = None;
+    for file in &manifest.state_chunks {
+        ensure!(
+            last_chunk_proof.is_none(),
+            "State chunk {} follows the last chunk.",
+            file.name
+        );
+        let bytes = file.read(&dir)?;
+        let chunk: AccountStateChunkWithProof =
+            libra_types::proto::types::AccountStateChunkWithProof::decode(&bytes[..])?
+                .try_into()?;
+        ensure!(
+            chunk.left_key == left_key,
+            "State chunks are not consecutive. Expected left key {:?}, got {:?}.",
+            left_key,
+            chunk.left_key,
+        );
+        chunk.verify(ledger_info, version)?;
+        left_key = chunk.last_key();
+        if chunk.is_last_chunk() {
+            last_chunk_proof = Some(chunk.proof.clone());
+        }
+        db.save_account_state_chunk(chunk)?;
+    }
+    let proof = last_chunk_proof.ok_or_else(|| format_err!("Last state chunk is missing."))?;
+    db.finish_account_state_restore(
+        trusted_ledger_info,
+        proof.transaction_info().clone(),
+        proof.ledger_info_to_transaction_info_proof().clone(),
+        db.get_ledger_frozen_subtree_hashes(version)?,
+    )?;
+
+    // Ledger infos of earlier epochs.
+    db.restore_ledger_infos(&epoch_ledger_infos)?;
+
+    info!("Restored version {}.", version);
+    Ok(())
+}
+
+/// Verifies that `epoch_ledger_infos` holds the ledger infos ending every epoch before the one of
+/// `trusted_ledger_info`, in increasing order of epochs, and that each of them is signed by the
+/// validators announced by the ledger info ending the previous epoch. The ledger info of epoch 0 is
+/// the genesis one, which is not signed. The validators announced by the last ledger info must have
+/// signed `trusted_ledger_info`, so that the whole chain is rooted in it.
+fn verify_epoch_ledger_infos(
+    epoch_ledger_infos: &[LedgerInfoWithSignatures],
+    trusted_ledger_info: &LedgerInfoWithSignatures,
+) -> Result<()> {
+    let trusted_epoch = trusted_ledger_info.ledger_info().epoch();
+    ensure!(
+        epoch_ledger_infos.len() as u64 == trusted_epoch,
+        "Expected the ledger infos of the {} epochs before epoch {}, got {}.",
+        trusted_epoch,
+        trusted_epoch,
+        epoch_ledger_infos.len(),
+    );
+
+    let mut validators: Option<ValidatorVerifier> = None;
+    for (epoch, ledger_info_with_sigs) in epoch_ledger_infos.iter().enumerate() {
+        let ledger_info = ledger_info_with_sigs.ledger_info();
+        ensure!(
+            ledger_info.epoch() == epoch as u64,
+            "Expected the ledger info of epoch {}, got one of epoch {}.",
+            epoch,
+            ledger_info.epoch(),
+        );
+        match &validators {
+            Some(validators) => verify_signatures(ledger_info_with_sigs, validators)
+                .map_err(|e| format_err!("Ledger info of epoch {} is invalid: {}", epoch, e))?,
+            None => ensure!(
+                ledger_info.version() == 0,
+                "Epoch 0 ends at version {}, expected the genesis.",
+                ledger_info.version(),
+            ),
+        }
+        let next_validator_set = ledger_info
+            .next_validator_set()
+            .ok_or_else(|| format_err!("Ledger info of epoch {} doesn't end the epoch.", epoch))?;
+        validators = Some(next_validator_set.into());
+    }
+
+    if let Some(validators) = validators {
+        verify_signatures(trusted_ledger_info, &validators)
+            .map_err(|e| format_err!("Trusted ledger info doesn't extend the epochs: {}", e))?;
+    }
+    Ok(())
+}
+
+/// Verifies that `validators` signed `ledger_info_with_sigs`. Unlike
+/// [`LedgerInfoWithSignatures::verify`], this doesn't accept any ledger info at version 0, which
+/// only the genesis one may be.
+fn verify_signatures(
+    ledger_info_with_sigs: &LedgerInfoWithSignatures,
+    validators: &ValidatorVerifier,
+) -> Result<()> {
+    validators.batch_verify_aggregated_signature(
+        ledger_info_with_sigs.ledger_info().hash(),
+        ledger_info_with_sigs.signatures(),
+    )?;
+    Ok(())
+}
+
+/// Loads a ledger info saved in the protobuf format, like the `ledger_info` file in a backup, to be
+/// passed to [`restore`] as the trusted ledger info.
+pub fn load_ledger_info(path: &Path) -> Result<LedgerInfoWithSignatures> {
+    decode_ledger_info(&fs::read(path)?)
+}
+
+fn read_ledger_info(dir: &Path, file: &FileHandle) -> Result<LedgerInfoWithSignatures> {
+    decode_ledger_info(&file.read(dir)?)
+}
+
+fn decode_ledger_info(bytes: &[u8]) -> Result<LedgerInfoWithSignatures> {
+    libra_types::proto::types::LedgerInfoWithSignatures::decode(bytes)?.try_into()
+}
@@ -15,6 +15,7 @@ use crate::{
 use crypto::{hash::CryptoHash, HashValue};
 use failure::prelude::*;
 use libra_types::{account_state_blob::AccountStateBlob, transaction::Version};
+use std::sync::Arc;
 
 #[derive(Clone, Debug, Eq, PartialEq)]
 enum ChildInfo {
@@ -85,9 +86,10 @@ impl InternalInfo {
     }
 }
 
-pub struct JellyfishMerkleRestore<'a, S> {
-    /// The underlying storage.
-    store: &'a S,
+pub struct JellyfishMerkleRestore<S> {
+    /// The underlying storage. It is shared so that an unfinished restoration can be kept around
+    /// between chunks.
+    store: Arc<S>,
 
     /// The version of the tree we are restoring.
     version: Version,
@@ -135,17 +137,17 @@ pub struct JellyfishMerkleRestore<'a, S> {
     num_keys_received: u64,
 }
 
-impl<'a, S> JellyfishMerkleRestore<'a, S>
+impl<S> JellyfishMerkleRestore<S>
 where
-    S: 'a + TreeReader + TreeWriter,
+    S: TreeReader + TreeWriter,
 {
-    pub fn new(store: &'a S, version: Version) -> Result<Self> {
+    pub fn new(store: Arc<S>, version: Version) -> Result<Self> {
         let (partial_nodes, previous_key) = match store.get_rightmost_leaf()? {
             Some((node_key, leaf_node)) => {
                 // If the system crashed in the middle of the previous restoration attempt, we need
                 // to recover the partial nodes to the state right before the crash.
                 (
-                    Self::recover_partial_nodes(store, version, node_key)?,
+                    Self::recover_partial_nodes(store.as_ref(), version, node_key)?,
                     Some(leaf_node.account_key()),
                 )
             }
@@ -172,7 +174,7 @@ where
     /// Recovers partial nodes from storage. We do this by looking at all the ancestors of the
     /// rightmost leaf. The ones do not exist in storage are the partial nodes.
     fn recover_partial_nodes(
-        store: &'a S,
+        store: &S,
         version: Version,
         rightmost_leaf_node_key: NodeKey,
     ) -> Result<Vec<InternalInfo>> {
@@ -236,6 +238,16 @@ where
         Ok(partial_nodes)
     }
 
+    /// Returns the version of the tree being restored.
+    pub fn version(&self) -> Version {
+        self.version
+    }
+
+    /// Returns the most recently added key, if any.
+    pub fn previous_key(&self) -> Option<HashValue> {
+        self.previous_key
+    }
+
     /// Restores a chunk of accounts. This function assumes that the given chunk has been validated
     /// and comes in the correct order.
     pub fn add_chunk(&mut self, chunk: Vec<(HashValue, AccountStateBlob)>) -> Result<()> {
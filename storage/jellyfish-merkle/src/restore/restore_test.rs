@@ -8,7 +8,7 @@ use crate::{
 use crypto::HashValue;
 use libra_types::{account_state_blob::AccountStateBlob, transaction::Version};
 use proptest::{collection::btree_map, prelude::*};
-use std::collections::BTreeMap;
+use std::{collections::BTreeMap, sync::Arc};
 
 proptest! {
     #![proptest_config(ProptestConfig::with_cases(10))]
@@ -21,8 +21,8 @@ proptest! {
         let expected_root_hash = get_expected_root_hash(&btree);
 
         // For this test, restore everything without interruption.
-        let db = MockTreeStore::default();
-        let mut restore = JellyfishMerkleRestore::new(&db, version).unwrap();
+        let db = Arc::new(MockTreeStore::default());
+        let mut restore = JellyfishMerkleRestore::new(Arc::clone(&db), version).unwrap();
         for (key, value) in &btree {
             restore.add_chunk(vec![(*key, value.clone())]).unwrap();
         }
@@ -42,10 +42,10 @@ proptest! {
         let version = (all.len() - 1) as Version;
         let expected_root_hash = get_expected_root_hash(&all);
         let batch1: Vec<_> = all.clone().into_iter().take(batch1_size).collect();
-        let db = MockTreeStore::default();
+        let db = Arc::new(MockTreeStore::default());
 
         {
-            let mut restore = JellyfishMerkleRestore::new(&db, version).unwrap();
+            let mut restore = JellyfishMerkleRestore::new(Arc::clone(&db), version).unwrap();
             restore.add_chunk(batch1).unwrap();
             // Do not call `finish`.
         }
@@ -62,7 +62,7 @@ proptest! {
                 .into_iter()
                 .filter(|(k, _v)| *k > rightmost_key)
                 .collect();
-            let mut restore = JellyfishMerkleRestore::new(&db, version).unwrap();
+            let mut restore = JellyfishMerkleRestore::new(Arc::clone(&db), version).unwrap();
             restore.add_chunk(remaining_accounts).unwrap();
             restore.finish().unwrap();
         }
@@ -0,0 +1,47 @@
+// Copyright (c) The Libra Core Contributors
+// SPDX-License-Identifier: Apache-2.0
+
+//! A [`StateView`] reading the account states committed to a `LibraDB`, for the tools replaying
+//! committed transactions offline.
+
+use failure::prelude::*;
+use libra_types::{access_path::AccessPath, transaction::Version};
+use libradb::LibraDB;
+use state_view::StateView;
+use std::{collections::BTreeMap, convert::TryFrom};
+
+/// A read-only view of the account states of a `LibraDB` at `version`.
+pub struct LibraDBStateView<'a> {
+    db: &'a LibraDB,
+    version: Version,
+}
+
+impl<'a> LibraDBStateView<'a> {
+    pub fn new(db: &'a LibraDB, version: Version) -> Self {
+        Self { db, version }
+    }
+}
+
+impl<'a> StateView for LibraDBStateView<'a> {
+    fn get(&self, access_path: &AccessPath) -> Result<Option<Vec<u8>>> {
+        let (blob, _proof) = self
+            .db
+            .get_account_state_with_proof_by_version(access_path.address, self.version)?;
+        let mut account_state = match blob {
+            Some(blob) => BTreeMap::<Vec<u8>, Vec<u8>>::try_from(&blob)?,
+            None => return Ok(None),
+        };
+        Ok(account_state.remove(&access_path.path))
+    }
+
+    fn multi_get(&self, access_paths: &[AccessPath]) -> Result<Vec<Option<Vec<u8>>>> {
+        access_paths
+            .iter()
+            .map(|access_path| self.get(access_path))
+            .collect()
+    }
+
+    fn is_genesis(&self) -> bool {
+        false
+    }
+}
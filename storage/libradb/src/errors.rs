@@ -14,4 +14,7 @@ pub enum LibraDbError {
     /// Requested too many items.
     #[fail(display = "Too many items requested: {}, max is {}", _0, _1)]
     TooManyRequested(u64, u64),
+    /// Requested data has been pruned.
+    #[fail(display = "{} has been pruned.", _0)]
+    Pruned(String),
 }
@@ -86,6 +86,17 @@ impl EventStore {
         Ok((event, proof))
     }
 
+    /// Get the smallest sequence number on `event_key` left in the index, which is not
+    /// necessarily 0 if the pruner has been at work. Only call this if the key is known to exist.
+    fn get_first_sequence_number(&self, event_key: &EventKey) -> Result<u64> {
+        let mut iter = self.db.iter::<EventByKeySchema>(ReadOptions::default())?;
+        iter.seek(&(*event_key, 0))?;
+        match iter.next().transpose()? {
+            Some(((key, seq), _)) if key == *event_key => Ok(seq),
+            _ => Err(LibraDbError::NotFound(format!("Event key {:?}", event_key)).into()),
+        }
+    }
+
     fn get_txn_ver_by_seq_num(&self, event_key: &EventKey, seq_num: u64) -> Result<u64> {
         let (ver, _) = self
             .db
@@ -126,7 +137,8 @@ impl EventStore {
                 }
 
                 // Fall back to binary search if the above short linear search didn't work out.
-                let (mut begin, mut end) = (0, seq);
+                // Index entries might have been pruned, so search from the first one left.
+                let (mut begin, mut end) = (self.get_first_sequence_number(event_key)?, seq);
                 while begin < end {
                     let mid = end - (end - begin) / 2;
                     let ver = self.get_txn_ver_by_seq_num(event_key, mid)?;
@@ -168,6 +180,14 @@ impl EventStore {
             if path != *event_key || ver > ledger_version {
                 break;
             }
+            if seq > cur_seq && result.is_empty() {
+                // Index entries are pruned from the smallest sequence numbers on.
+                return Err(LibraDbError::Pruned(format!(
+                    "Event {} of key {:?}",
+                    start_seq_num, event_key
+                ))
+                .into());
+            }
             ensure!(
                 seq == cur_seq,
                 "DB corrupt: Sequence number not continuous, expected: {}, actual: {}.",
@@ -22,7 +22,10 @@ use failure::prelude::*;
 use itertools::Itertools;
 use libra_types::{
     crypto_proxies::LedgerInfoWithSignatures,
-    proof::{position::Position, AccumulatorConsistencyProof, TransactionAccumulatorProof},
+    proof::{
+        position::{FrozenSubTreeIterator, Position},
+        AccumulatorConsistencyProof, TransactionAccumulatorProof,
+    },
     transaction::{TransactionInfo, Version},
 };
 use schemadb::{ReadOptions, DB};
@@ -167,6 +170,23 @@ impl LedgerStore {
         Ok(root_hash)
     }
 
+    /// Write the `txn_info` at `version` together with the roots of the frozen subtrees of the
+    /// accumulator at that version to `cs`, without any of the earlier leaves. This is used when
+    /// restoring from a state snapshot, after which new transactions can be appended as usual.
+    pub fn put_transaction_info_with_frozen_subtrees(
+        &self,
+        version: Version,
+        txn_info: &TransactionInfo,
+        frozen_subtree_hashes: &[HashValue],
+        cs: &mut ChangeSet,
+    ) -> Result<()> {
+        cs.batch.put::<TransactionInfoSchema>(&version, txn_info)?;
+        FrozenSubTreeIterator::new(version + 1 /* num_leaves */)
+            .zip_eq(frozen_subtree_hashes.iter())
+            .map(|(position, hash)| cs.batch.put::<TransactionAccumulatorSchema>(&position, hash))
+            .collect::<Result<()>>()
+    }
+
     /// Write `ledger_info` to `cs`.
     pub fn put_ledger_info(
         &self,
@@ -28,6 +28,8 @@ mod transaction_store;
 #[cfg(test)]
 mod libradb_test;
 
+pub use crate::pruner::PruneWindows;
+
 use crate::{
     change_set::{ChangeSet, SealedChangeSet},
     errors::LibraDbError,
@@ -40,31 +42,39 @@ use crate::{
     system_store::SystemStore,
     transaction_store::TransactionStore,
 };
-use crypto::hash::{CryptoHash, HashValue};
+use crypto::hash::{CryptoHash, HashValue, TransactionAccumulatorHasher};
 use failure::prelude::*;
 use itertools::{izip, zip_eq};
+use jellyfish_merkle::{restore::JellyfishMerkleRestore, JellyfishMerkleTree, TreeReader};
 use lazy_static::lazy_static;
 use libra_types::{
     access_path::AccessPath,
     account_address::AccountAddress,
     account_config::AccountResource,
-    account_state_blob::{AccountStateBlob, AccountStateWithProof},
+    account_state_blob::{AccountStateBlob, AccountStateChunkWithProof, AccountStateWithProof},
     contract_event::EventWithProof,
     crypto_proxies::{LedgerInfoWithSignatures, ValidatorChangeEventWithProof},
     get_with_proof::{RequestItem, ResponseItem},
     proof::{
-        AccountStateProof, AccumulatorConsistencyProof, EventProof, SignedTransactionProof,
-        SparseMerkleProof,
+        accumulator::InMemoryAccumulator, AccountStateChunkProof, AccountStateProof,
+        AccumulatorConsistencyProof, EventProof, SignedTransactionProof, SparseMerkleProof,
+        TransactionAccumulatorProof,
     },
     transaction::{
-        SignedTransactionWithProof, TransactionInfo, TransactionListWithProof, TransactionToCommit,
-        Version,
+        SignedTransactionWithProof, Transaction, TransactionInfo, TransactionListWithProof,
+        TransactionToCommit, Version,
     },
 };
 use logger::prelude::*;
 use metrics::OpMetrics;
 use schemadb::{ColumnFamilyOptions, ColumnFamilyOptionsMap, DB, DEFAULT_CF_NAME};
-use std::{convert::TryInto, iter::Iterator, path::Path, sync::Arc, time::Instant};
+use std::{
+    convert::TryInto,
+    iter::Iterator,
+    path::Path,
+    sync::{Arc, Mutex},
+    time::Instant,
+};
 use storage_proto::StartupInfo;
 
 lazy_static! {
@@ -82,24 +92,43 @@ fn error_if_too_many_requested(num_requested: u64, max_allowed: u64) -> Result<(
     }
 }
 
+fn error_if_pruned(data: &str, version: Version, least_readable_version: Version) -> Result<()> {
+    if version < least_readable_version {
+        Err(LibraDbError::Pruned(format!(
+            "{} at version {} (least readable version {})",
+            data, version, least_readable_version
+        ))
+        .into())
+    } else {
+        Ok(())
+    }
+}
+
 /// This holds a handle to the underlying DB responsible for physical storage and provides APIs for
 /// access to the core Libra data structures.
 pub struct LibraDB {
     db: Arc<DB>,
     ledger_store: LedgerStore,
     transaction_store: TransactionStore,
-    state_store: StateStore,
+    state_store: Arc<StateStore>,
     event_store: EventStore,
     system_store: SystemStore,
     pruner: Pruner,
+    /// The state snapshot being restored chunk by chunk, if any.
+    state_restore: Mutex<Option<JellyfishMerkleRestore<StateStore>>>,
 }
 
 impl LibraDB {
-    /// Config parameter for the pruner.
-    const NUM_HISTORICAL_VERSIONS_TO_KEEP: u64 = 1_000_000;
-
     /// This creates an empty LibraDB instance on disk or opens one if it already exists.
     pub fn new<P: AsRef<Path> + Clone>(db_root_path: P) -> Self {
+        Self::new_with_prune_windows(db_root_path, PruneWindows::default())
+    }
+
+    /// Same as [`LibraDB::new`], but prunes historical data according to `prune_windows`.
+    pub fn new_with_prune_windows<P: AsRef<Path> + Clone>(
+        db_root_path: P,
+        prune_windows: PruneWindows,
+    ) -> Self {
         let cf_opts_map: ColumnFamilyOptionsMap = [
             (
                 /* LedgerInfo CF = */ DEFAULT_CF_NAME,
@@ -147,10 +176,11 @@ impl LibraDB {
             db: Arc::clone(&db),
             event_store: EventStore::new(Arc::clone(&db)),
             ledger_store: LedgerStore::new(Arc::clone(&db)),
-            state_store: StateStore::new(Arc::clone(&db)),
+            state_store: Arc::new(StateStore::new(Arc::clone(&db))),
             transaction_store: TransactionStore::new(Arc::clone(&db)),
             system_store: SystemStore::new(Arc::clone(&db)),
-            pruner: Pruner::new(Arc::clone(&db), Self::NUM_HISTORICAL_VERSIONS_TO_KEEP),
+            pruner: Pruner::new(Arc::clone(&db), prune_windows),
+            state_restore: Mutex::new(None),
         }
     }
 
@@ -176,6 +206,9 @@ impl LibraDB {
             ledger_version,
             latest_version
         );
+        let least_readable_versions = self.pruner.least_readable_versions();
+        error_if_pruned("State", version, least_readable_versions.state)?;
+        error_if_pruned("Transaction info", version, least_readable_versions.ledger)?;
 
         let (txn_info, txn_info_accumulator_proof) = self
             .ledger_store
@@ -250,9 +283,12 @@ impl LibraDB {
             }
         }
 
+        let least_readable_versions = self.pruner.least_readable_versions();
         let mut events_with_proof = event_keys
             .into_iter()
             .map(|(seq, ver, idx)| {
+                error_if_pruned("Event", ver, least_readable_versions.event)?;
+                error_if_pruned("Transaction info", ver, least_readable_versions.ledger)?;
                 let (event, event_proof) = self
                     .event_store
                     .get_event_with_proof_by_version_and_index(ver, idx)?;
@@ -536,6 +572,14 @@ impl LibraDB {
             })
             .collect::<Result<Vec<_>>>()?;
 
+        // Proving consistency with version 0 is always possible, see the ledger pruner.
+        if client_known_version != 0 {
+            error_if_pruned(
+                "Transaction accumulator",
+                client_known_version + 1,
+                self.pruner.least_readable_versions().ledger,
+            )?;
+        }
         let ledger_consistency_proof = self
             .ledger_store
             .get_consistency_proof(client_known_version, ledger_version)?;
@@ -559,6 +603,7 @@ impl LibraDB {
         address: AccountAddress,
         version: Version,
     ) -> Result<(Option<AccountStateBlob>, SparseMerkleProof)> {
+        error_if_pruned("State", version, self.pruner.least_readable_versions().state)?;
         self.state_store
             .get_account_state_with_proof_by_version(address, version)
     }
@@ -609,6 +654,12 @@ impl LibraDB {
         }
 
         let limit = std::cmp::min(limit, ledger_version - start_version + 1);
+        let least_readable_versions = self.pruner.least_readable_versions();
+        error_if_pruned("Transaction", start_version, least_readable_versions.transaction)?;
+        error_if_pruned("Transaction info", start_version, least_readable_versions.ledger)?;
+        if fetch_events {
+            error_if_pruned("Event", start_version, least_readable_versions.event)?;
+        }
         let txn_and_txn_info_list = (start_version..start_version + limit)
             .map(|version| {
                 Ok((
@@ -648,6 +699,270 @@ impl LibraDB {
         ))
     }
 
+    /// Gets up to `limit` consecutive accounts right after `left_key` in the state at `version`,
+    /// with proof based on `ledger_version`. If `left_key` is `None`, starts from the first
+    /// account.
+    ///
+    /// This is used by the State Synchronizer module internally to serve state snapshots.
+    pub fn get_account_state_chunk_with_proof(
+        &self,
+        version: Version,
+        ledger_version: Version,
+        left_key: Option<HashValue>,
+        limit: u64,
+    ) -> Result<AccountStateChunkWithProof> {
+        error_if_too_many_requested(limit, MAX_LIMIT)?;
+        ensure!(
+            version <= ledger_version,
+            "The queried version {} should be equal to or older than ledger version {}.",
+            version,
+            ledger_version
+        );
+        let latest_version = self.get_latest_version()?;
+        ensure!(
+            ledger_version <= latest_version,
+            "The ledger version {} is greater than the latest version currently in ledger: {}",
+            ledger_version,
+            latest_version
+        );
+
+        let least_readable_versions = self.pruner.least_readable_versions();
+        error_if_pruned("State", version, least_readable_versions.state)?;
+        error_if_pruned("Transaction info", version, least_readable_versions.ledger)?;
+
+        let (txn_info, txn_info_accumulator_proof) = self
+            .ledger_store
+            .get_transaction_info_with_proof(version, ledger_version)?;
+        let (account_blobs, range_proof) = self
+            .state_store
+            .get_account_state_chunk_with_proof(version, left_key, limit)?;
+        Ok(AccountStateChunkWithProof::new(
+            version,
+            left_key,
+            account_blobs,
+            AccountStateChunkProof::new(txn_info_accumulator_proof, txn_info, range_proof),
+        ))
+    }
+
+    /// From left to right, gets the root hashes of the frozen subtrees of the transaction
+    /// accumulator at `version`.
+    ///
+    /// This is used by the State Synchronizer module internally to serve state snapshots.
+    pub fn get_ledger_frozen_subtree_hashes(&self, version: Version) -> Result<Vec<HashValue>> {
+        // The frozen subtrees at the least readable version are kept, as are the later ones.
+        error_if_pruned(
+            "Transaction accumulator",
+            version + 1,
+            self.pruner.least_readable_versions().ledger,
+        )?;
+        self.ledger_store.get_ledger_frozen_subtree_hashes(version)
+    }
+
+    /// Writes a chunk of accounts of the state snapshot at `chunk.version` being restored. The
+    /// chunk is expected to have been verified by the caller. Chunks must come in order, but
+    /// accounts already restored are skipped, so after a restart the caller may simply start over
+    /// from the first account.
+    ///
+    /// This is used by the State Synchronizer module internally.
+    pub fn save_account_state_chunk(&self, chunk: AccountStateChunkWithProof) -> Result<()> {
+        let mut state_restore = self
+            .state_restore
+            .lock()
+            .expect("Failed to lock state restore.");
+
+        let mut restore = match state_restore.take() {
+            Some(restore) if restore.version() == chunk.version => restore,
+            _ => {
+                // Nothing is in progress in memory, but an unfinished restoration may have been
+                // left on disk by an earlier run.
+                if let Some((node_key, _)) = self.state_store.get_rightmost_leaf()? {
+                    ensure!(
+                        node_key.version() == chunk.version,
+                        "Found unfinished restoration of version {} while restoring version {}.",
+                        node_key.version(),
+                        chunk.version,
+                    );
+                }
+                JellyfishMerkleRestore::new(Arc::clone(&self.state_store), chunk.version)?
+            }
+        };
+
+        let account_blobs = match restore.previous_key() {
+            Some(previous_key) => chunk
+                .account_blobs
+                .into_iter()
+                .filter(|(key, _)| *key > previous_key)
+                .collect(),
+            None => chunk.account_blobs,
+        };
+        restore.add_chunk(account_blobs)?;
+        *state_restore = Some(restore);
+
+        Ok(())
+    }
+
+    /// Finishes restoring the state snapshot at the version of `ledger_info_with_sigs`, after all
+    /// of its accounts have been saved via `save_account_state_chunk`. `txn_info` is the
+    /// `TransactionInfo` at that version and `txn_info_proof` proves it against the ledger info.
+    /// `ledger_frozen_subtree_hashes` are the roots of the frozen subtrees of the transaction
+    /// accumulator at that version, so that new transactions can be appended afterwards.
+    ///
+    /// This is used by the State Synchronizer module internally.
+    pub fn finish_account_state_restore(
+        &self,
+        ledger_info_with_sigs: &LedgerInfoWithSignatures,
+        txn_info: TransactionInfo,
+        txn_info_proof: TransactionAccumulatorProof,
+        ledger_frozen_subtree_hashes: Vec<HashValue>,
+    ) -> Result<()> {
+        let ledger_info = ledger_info_with_sigs.ledger_info();
+        let version = ledger_info.version();
+        let expected_root_hash = ledger_info.transaction_accumulator_hash();
+
+        txn_info_proof.verify(expected_root_hash, txn_info.hash(), version)?;
+        let frozen_subtree_root_hash = InMemoryAccumulator::<TransactionAccumulatorHasher>::new(
+            ledger_frozen_subtree_hashes.clone(),
+            version + 1, /* num_leaves */
+        )?
+        .root_hash();
+        ensure!(
+            frozen_subtree_root_hash == expected_root_hash,
+            "Frozen subtrees do not match the ledger info. {:?} vs {:?}",
+            frozen_subtree_root_hash,
+            expected_root_hash,
+        );
+
+        let restore = self
+            .state_restore
+            .lock()
+            .expect("Failed to lock state restore.")
+            .take()
+            .ok_or_else(|| format_err!("No state snapshot is being restored."))?;
+        ensure!(
+            restore.version() == version,
+            "Restoring state at version {}, but ledger info is at version {}.",
+            restore.version(),
+            version,
+        );
+        restore.finish()?;
+
+        let state_root_hash =
+            JellyfishMerkleTree::new(self.state_store.as_ref()).get_root_hash(version)?;
+        ensure!(
+            state_root_hash == txn_info.state_root_hash(),
+            "Restored state root hash doesn't match expected. {:?} vs {:?}",
+            state_root_hash,
+            txn_info.state_root_hash(),
+        );
+
+        // Everything checks out. Write the ledger data needed to continue from this version.
+        let mut cs = ChangeSet::new();
+        self.ledger_store.put_transaction_info_with_frozen_subtrees(
+            version,
+            &txn_info,
+            &ledger_frozen_subtree_hashes,
+            &mut cs,
+        )?;
+        self.ledger_store
+            .put_ledger_info(ledger_info_with_sigs, &mut cs)?;
+
+        let (sealed_cs, _counters) = self.seal_change_set(version, 0 /* num_txns */, cs)?;
+        self.commit(sealed_cs)?;
+        self.ledger_store
+            .set_latest_ledger_info(ledger_info_with_sigs.clone());
+
+        Ok(())
+    }
+
+    /// Writes a chunk of transactions together with their infos and events, but without the
+    /// account states, which are restored from a state snapshot instead via
+    /// `save_account_state_chunk`. Chunks must come in order starting from version 0, and are
+    /// expected to have been verified by the caller.
+    ///
+    /// This is used by the backup tool to restore the ledger history.
+    pub fn restore_transactions(
+        &self,
+        txn_list_with_proof: TransactionListWithProof,
+    ) -> Result<()> {
+        let first_version = match txn_list_with_proof.first_transaction_version {
+            Some(first_version) => first_version,
+            None => return Ok(()),
+        };
+        let expected_first_version = self
+            .ledger_store
+            .get_latest_transaction_info_option()?
+            .map_or(0, |(version, _)| version + 1);
+        ensure!(
+            first_version == expected_first_version,
+            "Transaction batch not applicable: first_version {}, expected {}",
+            first_version,
+            expected_first_version,
+        );
+        let num_txns = txn_list_with_proof.len() as u64;
+        let events = txn_list_with_proof
+            .events
+            .ok_or_else(|| format_err!("Events are missing from the transaction list."))?;
+
+        let mut cs = ChangeSet::new();
+        let txn_infos = izip!(
+            first_version..first_version + num_txns,
+            txn_list_with_proof.transaction_and_infos,
+            events
+        )
+        .map(|(version, (txn, txn_info), events)| {
+            self.transaction_store.put_transaction(
+                version,
+                &Transaction::UserTransaction(txn),
+                &mut cs,
+            )?;
+            let event_root_hash = self.event_store.put_events(version, &events, &mut cs)?;
+            ensure!(
+                event_root_hash == txn_info.event_root_hash(),
+                "Event root hash calculated doesn't match expected at version {}. {:?} vs {:?}",
+                version,
+                event_root_hash,
+                txn_info.event_root_hash(),
+            );
+            Ok(txn_info)
+        })
+        .collect::<Result<Vec<_>>>()?;
+        self.ledger_store
+            .put_transaction_infos(first_version, &txn_infos, &mut cs)?;
+
+        let (sealed_cs, _counters) = self.seal_change_set(first_version, num_txns, cs)?;
+        self.commit(sealed_cs)
+    }
+
+    /// Writes ledger infos of past epochs, each of which is verified against the transaction
+    /// accumulator already restored via `restore_transactions`. The latest ledger info is not
+    /// updated.
+    ///
+    /// This is used by the backup tool to restore the ledger history.
+    pub fn restore_ledger_infos(&self, ledger_infos: &[LedgerInfoWithSignatures]) -> Result<()> {
+        let mut cs = ChangeSet::new();
+        for ledger_info_with_sigs in ledger_infos {
+            let ledger_info = ledger_info_with_sigs.ledger_info();
+            let version = ledger_info.version();
+            let root_hash = InMemoryAccumulator::<TransactionAccumulatorHasher>::new(
+                self.ledger_store.get_ledger_frozen_subtree_hashes(version)?,
+                version + 1, /* num_leaves */
+            )?
+            .root_hash();
+            ensure!(
+                root_hash == ledger_info.transaction_accumulator_hash(),
+                "Ledger info of epoch {} doesn't match the ledger history. {:?} vs {:?}",
+                ledger_info.epoch(),
+                root_hash,
+                ledger_info.transaction_accumulator_hash(),
+            );
+            self.ledger_store
+                .put_ledger_info(ledger_info_with_sigs, &mut cs)?;
+        }
+
+        let (sealed_cs, _counters) = self.seal_change_set(0, 0 /* num_txns */, cs)?;
+        self.commit(sealed_cs)
+    }
+
     // ================================== Private APIs ==================================
     /// Convert a `ChangeSet` to `SealedChangeSet`.
     ///
@@ -701,6 +1016,13 @@ impl LibraDB {
         ledger_version: Version,
         fetch_events: bool,
     ) -> Result<SignedTransactionWithProof> {
+        let least_readable_versions = self.pruner.least_readable_versions();
+        error_if_pruned("Transaction", version, least_readable_versions.transaction)?;
+        error_if_pruned("Transaction info", version, least_readable_versions.ledger)?;
+        if fetch_events {
+            error_if_pruned("Event", version, least_readable_versions.event)?;
+        }
+
         let proof = {
             let (txn_info, txn_info_accumulator_proof) = self
                 .ledger_store
@@ -4,6 +4,8 @@
 use super::*;
 use crate::{
     mock_genesis::{db_with_mock_genesis, GENESIS_INFO},
+    pruner::LeastReadableVersions,
+    schema::event::EventSchema,
     test_helper::arb_blocks_to_commit,
 };
 use crypto::hash::CryptoHash;
@@ -14,7 +16,8 @@ use libra_types::{
 };
 use proptest::prelude::*;
 use rusty_fork::{rusty_fork_id, rusty_fork_test, rusty_fork_test_name};
-use std::collections::HashMap;
+use schemadb::ReadOptions;
+use std::{collections::HashMap, fmt::Debug};
 
 fn test_save_blocks_impl(
     input: Vec<(Vec<TransactionToCommit>, LedgerInfoWithSignatures)>,
@@ -106,6 +109,169 @@ fn test_sync_transactions_impl(
     Ok(())
 }
 
+fn test_restore_state_snapshot_impl(
+    input: Vec<(Vec<TransactionToCommit>, LedgerInfoWithSignatures)>,
+) -> Result<()> {
+    let tmp_dir = TempPath::new();
+    let db = db_with_mock_genesis(&tmp_dir)?;
+
+    let mut cur_ver = 0;
+    for (txns_to_commit, ledger_info_with_sigs) in &input {
+        db.save_transactions(
+            &txns_to_commit,
+            cur_ver + 1, /* first_version */
+            &Some(ledger_info_with_sigs.clone()),
+        )?;
+        cur_ver += txns_to_commit.len() as u64;
+    }
+    let ledger_info_with_sigs = input.last().unwrap().1.clone();
+    let version = ledger_info_with_sigs.ledger_info().version();
+
+    // Restore the latest state into an empty DB, a few accounts at a time.
+    let restore_tmp_dir = TempPath::new();
+    let restore_db = LibraDB::new(&restore_tmp_dir);
+    let mut left_key = None;
+    loop {
+        let chunk = db.get_account_state_chunk_with_proof(
+            version,
+            version, /* ledger_version */
+            left_key,
+            2, /* limit */
+        )?;
+        chunk.verify(ledger_info_with_sigs.ledger_info(), version)?;
+        left_key = chunk.last_key();
+        let is_last_chunk = chunk.is_last_chunk();
+        restore_db.save_account_state_chunk(chunk)?;
+        if is_last_chunk {
+            break;
+        }
+    }
+
+    let (txn_info, txn_info_proof) = db
+        .ledger_store
+        .get_transaction_info_with_proof(version, version)?;
+    restore_db.finish_account_state_restore(
+        &ledger_info_with_sigs,
+        txn_info,
+        txn_info_proof,
+        db.get_ledger_frozen_subtree_hashes(version)?,
+    )?;
+
+    assert_eq!(restore_db.get_startup_info()?, db.get_startup_info()?);
+
+    Ok(())
+}
+
+fn assert_pruned<T: Debug>(res: Result<T>) {
+    match res.unwrap_err().downcast::<LibraDbError>() {
+        Ok(LibraDbError::Pruned(_)) => (),
+        other => panic!("Expected pruned error, got {:?}", other),
+    }
+}
+
+fn verify_pruned(db: &LibraDB, least_readable_version: Version, latest_version: Version) {
+    for version in 0..least_readable_version {
+        assert_pruned(db.get_transaction_with_proof(version, latest_version, true));
+        assert_pruned(db.get_transactions(version, 1, latest_version, false));
+        assert_pruned(
+            db.get_account_state_with_proof_by_version(AccountAddress::random(), version),
+        );
+    }
+    let mut iter = db.db.iter::<EventSchema>(ReadOptions::default()).unwrap();
+    iter.seek_to_first();
+    if let Some(((version, _), _)) = iter.next().transpose().unwrap() {
+        assert!(version >= least_readable_version);
+    }
+}
+
+fn test_prune_history_impl(
+    input: Vec<(Vec<TransactionToCommit>, LedgerInfoWithSignatures)>,
+) -> Result<()> {
+    let tmp_dir = TempPath::new();
+    {
+        let db = db_with_mock_genesis(&tmp_dir)?;
+        let mut cur_ver = 0;
+        for (txns_to_commit, ledger_info_with_sigs) in &input {
+            db.save_transactions(
+                &txns_to_commit,
+                cur_ver + 1, /* first_version */
+                &Some(ledger_info_with_sigs.clone()),
+            )?;
+            cur_ver += txns_to_commit.len() as u64;
+        }
+    }
+    let txns_to_commit = input
+        .iter()
+        .flat_map(|(txns_to_commit, _)| txns_to_commit.iter())
+        .collect::<Vec<_>>();
+    let ledger_info = input.last().unwrap().1.ledger_info().clone();
+    let latest_version = ledger_info.version();
+
+    // Reopen the DB, keeping only the latest two versions of everything.
+    let prune_windows = PruneWindows {
+        state: Some(1),
+        transaction: Some(1),
+        event: Some(1),
+        ledger: Some(1),
+    };
+    let db = LibraDB::new_with_prune_windows(&tmp_dir, prune_windows);
+    db.pruner.wake_and_wait(latest_version)?;
+    let least_readable_version = latest_version.saturating_sub(1);
+    assert_eq!(
+        db.pruner.least_readable_versions(),
+        LeastReadableVersions {
+            state: least_readable_version,
+            transaction: least_readable_version,
+            event: least_readable_version,
+            ledger: least_readable_version,
+        }
+    );
+    verify_pruned(&db, least_readable_version, latest_version);
+
+    // Versions kept are still readable with proofs.
+    for version in least_readable_version..=latest_version {
+        let txn_with_proof = db.get_transaction_with_proof(version, latest_version, true)?;
+        if version > 0 {
+            let txn_to_commit = txns_to_commit[version as usize - 1];
+            let txn = txn_to_commit.as_signed_user_txn()?;
+            txn_with_proof.verify(&ledger_info, version, txn.sender(), txn.sequence_number())?;
+            for (addr, expected_blob) in txn_to_commit.account_states() {
+                let account_state_with_proof =
+                    db.get_account_state_with_proof(*addr, version, latest_version)?;
+                assert_eq!(account_state_with_proof.blob, Some(expected_blob.clone()));
+                account_state_with_proof.verify(&ledger_info, version, *addr)?;
+            }
+        }
+    }
+    db.get_transactions(least_readable_version, 2, latest_version, true)?
+        .verify(&ledger_info, Some(least_readable_version))?;
+
+    // Consistency with version 0 can always be proven, but not with pruned versions otherwise.
+    let (_, _, _, consistency_proof) = db.update_to_latest_ledger(0, vec![])?;
+    let root_hash = InMemoryAccumulator::<TransactionAccumulatorHasher>::new(
+        vec![GENESIS_INFO.0.hash()],
+        1, /* num_leaves */
+    )?
+    .append_subtrees(consistency_proof.subtrees(), latest_version)?
+    .root_hash();
+    assert_eq!(root_hash, ledger_info.transaction_accumulator_hash());
+    if least_readable_version >= 2 {
+        assert_pruned(db.update_to_latest_ledger(least_readable_version - 2, vec![]));
+    }
+    if least_readable_version >= 1 {
+        db.update_to_latest_ledger(least_readable_version - 1, vec![])?;
+    }
+
+    // Progress is picked up after restarting.
+    let least_readable_versions = db.pruner.least_readable_versions();
+    drop(db);
+    let db = LibraDB::new_with_prune_windows(&tmp_dir, prune_windows);
+    assert_eq!(db.pruner.least_readable_versions(), least_readable_versions);
+    verify_pruned(&db, least_readable_version, latest_version);
+
+    Ok(())
+}
+
 fn get_events_by_query_path(
     db: &LibraDB,
     ledger_info: &LedgerInfo,
@@ -357,6 +523,16 @@ proptest! {
     fn test_sync_transactions(input in arb_blocks_to_commit()) {
         test_sync_transactions_impl(input).unwrap();
     }
+
+    #[test]
+    fn test_restore_state_snapshot(input in arb_blocks_to_commit()) {
+        test_restore_state_snapshot_impl(input).unwrap();
+    }
+
+    #[test]
+    fn test_prune_history(input in arb_blocks_to_commit()) {
+        test_prune_history_impl(input).unwrap();
+    }
 }
 
 #[test]
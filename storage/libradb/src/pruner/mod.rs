@@ -6,100 +6,288 @@
 
 use crate::{
     schema::{
-        jellyfish_merkle_node::JellyfishMerkleNodeSchema, stale_node_index::StaleNodeIndexSchema,
+        event::EventSchema, event_accumulator::EventAccumulatorSchema,
+        event_by_key::EventByKeySchema, jellyfish_merkle_node::JellyfishMerkleNodeSchema,
+        stale_node_index::StaleNodeIndexSchema, transaction::TransactionSchema,
+        transaction_accumulator::TransactionAccumulatorSchema,
+        transaction_info::TransactionInfoSchema,
     },
     OP_COUNTER,
 };
+use crypto::hash::ACCUMULATOR_PLACEHOLDER_HASH;
 use failure::prelude::*;
-use libra_types::transaction::Version;
+use jellyfish_merkle::StaleNodeIndex;
+use libra_types::{
+    proof::position::{FrozenSubTreeIterator, Position},
+    transaction::Version,
+};
 use logger::prelude::*;
 use schemadb::{ReadOptions, SchemaBatch, SchemaIterator, DB};
+#[cfg(test)]
+use std::thread::sleep;
 use std::{
+    cmp::min,
+    collections::HashSet,
+    iter::Peekable,
     sync::{
         mpsc::{channel, Receiver, Sender},
         Arc, Mutex,
     },
     thread::JoinHandle,
-};
-
-use failure::_core::sync::atomic::Ordering;
-use jellyfish_merkle::StaleNodeIndex;
-#[cfg(test)]
-use std::thread::sleep;
-use std::{
-    iter::Peekable,
-    sync::atomic::AtomicU64,
     time::{Duration, Instant},
 };
 
+/// Other than the latest version, how many historical versions of each kind of data to keep being
+/// readable. For example, a window being `Some(0)` means keep only the latest version, and `None`
+/// means never prune that kind of data.
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub struct PruneWindows {
+    /// Account states, i.e. the Jellyfish Merkle tree nodes.
+    pub state: Option<u64>,
+    /// Transactions.
+    pub transaction: Option<u64>,
+    /// Events, together with the event accumulators and the index by event key.
+    pub event: Option<u64>,
+    /// Transaction infos and the transaction accumulator. Frozen subtrees needed to prove the
+    /// versions kept are never pruned.
+    pub ledger: Option<u64>,
+}
+
+impl Default for PruneWindows {
+    fn default() -> Self {
+        Self {
+            state: Some(1_000_000),
+            transaction: None,
+            event: None,
+            ledger: None,
+        }
+    }
+}
+
+/// The least readable version of each kind of data. Data older than it has been or is being
+/// pruned.
+#[derive(Clone, Copy, Debug, Default, Eq, PartialEq)]
+pub(crate) struct LeastReadableVersions {
+    pub state: Version,
+    pub transaction: Version,
+    pub event: Version,
+    pub ledger: Version,
+}
+
+impl LeastReadableVersions {
+    /// Figures out the progress of previous runs from what is left in the DB.
+    fn load(db: &DB) -> Result<Self> {
+        let mut txn_iter = db.iter::<TransactionSchema>(ReadOptions::default())?;
+        txn_iter.seek_to_first();
+        let mut txn_info_iter = db.iter::<TransactionInfoSchema>(ReadOptions::default())?;
+        txn_info_iter.seek_to_first();
+        let ledger = txn_info_iter.next().transpose()?.map_or(0, |(ver, _)| ver);
+
+        Ok(Self {
+            state: Self::load_least_readable_state_version(db)?,
+            transaction: txn_iter.next().transpose()?.map_or(0, |(ver, _)| ver),
+            event: Self::load_least_readable_event_version(db, ledger)?,
+            ledger,
+        })
+    }
+
+    /// State nodes are pruned a whole version at a time, in order of the versions they became
+    /// stale since, while their entries in the stale node index are only purged later. So the
+    /// versions whose stale nodes are gone form a prefix of the versions left in the index, and
+    /// the last one of them is the least readable version. The index is purged up to but
+    /// excluding that version, which keeps it findable. If no stale node was pruned, everything
+    /// is readable.
+    fn load_least_readable_state_version(db: &DB) -> Result<Version> {
+        // Returns the version of the first index entry at or after `version`, if its node has
+        // been pruned.
+        let pruned_at_or_after = |version: Version| -> Result<Option<Version>> {
+            let mut iter = db.iter::<StaleNodeIndexSchema>(ReadOptions::default())?;
+            iter.seek(&version)?;
+            match iter.next().transpose()? {
+                Some((index, _)) => {
+                    let is_pruned = db
+                        .get::<JellyfishMerkleNodeSchema>(&index.node_key)?
+                        .is_none();
+                    Ok(if is_pruned {
+                        Some(index.stale_since_version)
+                    } else {
+                        None
+                    })
+                }
+                None => Ok(None),
+            }
+        };
+
+        // Binary search for the last version in the pruned prefix, `lo` always being in it.
+        let mut lo = match pruned_at_or_after(0)? {
+            Some(version) => version,
+            None => return Ok(0),
+        };
+        let mut iter = db.iter::<StaleNodeIndexSchema>(ReadOptions::default())?;
+        iter.seek_to_last();
+        let mut hi = match iter.next().transpose()? {
+            Some((index, _)) => index.stale_since_version + 1,
+            None => return Ok(0),
+        };
+        while lo + 1 < hi {
+            let mid = lo + (hi - lo) / 2;
+            match pruned_at_or_after(mid)? {
+                Some(version) if version < hi => lo = version,
+                _ => hi = mid,
+            }
+        }
+        Ok(lo)
+    }
+
+    /// Transactions without events leave no trace in the event store, so the versions right
+    /// before the first event left can't be told pruned or not by looking at events. Since reading
+    /// no events from them is correct either way, they are considered readable as long as their
+    /// transaction infos say they emitted no events.
+    fn load_least_readable_event_version(
+        db: &DB,
+        least_readable_ledger_version: Version,
+    ) -> Result<Version> {
+        let mut event_iter = db.iter::<EventSchema>(ReadOptions::default())?;
+        event_iter.seek_to_first();
+        let mut version = match event_iter.next().transpose()? {
+            Some(((version, _), _)) => version,
+            None => {
+                let mut txn_info_iter = db.iter::<TransactionInfoSchema>(ReadOptions::default())?;
+                txn_info_iter.seek_to_last();
+                match txn_info_iter.next().transpose()? {
+                    Some((version, _)) => version + 1,
+                    None => return Ok(0),
+                }
+            }
+        };
+
+        while version > least_readable_ledger_version {
+            match db.get::<TransactionInfoSchema>(&(version - 1))? {
+                Some(txn_info) if txn_info.event_root_hash() == *ACCUMULATOR_PLACEHOLDER_HASH => {
+                    version -= 1
+                }
+                _ => break,
+            }
+        }
+        Ok(version)
+    }
+
+    fn update_counters(&self) {
+        OP_COUNTER.set("pruner.least_readable_state_version", self.state as usize);
+        OP_COUNTER.set(
+            "pruner.least_readable_transaction_version",
+            self.transaction as usize,
+        );
+        OP_COUNTER.set("pruner.least_readable_event_version", self.event as usize);
+        OP_COUNTER.set("pruner.least_readable_ledger_version", self.ledger as usize);
+    }
+}
+
 /// The `Pruner` is meant to be part of a `LibraDB` instance and runs in the background to prune old
 /// data.
 ///
 /// It creates a worker thread on construction and joins it on destruction. When destructed, it
 /// quits the worker thread eagerly without waiting for all pending work to be done.
 pub(crate) struct Pruner {
-    /// Other than the latest version, how many historical versions to keep being readable. For
-    /// example, this being 0 means keep only the latest version.
-    num_historical_versions_to_keep: u64,
+    /// How many historical versions of each kind of data to keep being readable.
+    prune_windows: PruneWindows,
     /// The worker thread handle, created upon Pruner instance construction and joined upon its
     /// destruction. It only becomes `None` after joined in `drop()`.
     worker_thread: Option<JoinHandle<()>>,
     /// The sender side of the channel talking to the worker thread.
     command_sender: Mutex<Sender<Command>>,
-    /// (For tests) A way for the worker thread to inform the `Pruner` the pruning progress. If it
-    /// sets this atomic value to `V`, all versions before `V` can no longer be accessed.
-    #[allow(dead_code)]
-    worker_progress: Arc<AtomicU64>,
+    /// A way for the worker thread to inform the `Pruner` the pruning progress. Readers consult it
+    /// to tell pruned data from missing data.
+    least_readable_versions: Arc<Mutex<LeastReadableVersions>>,
 }
 
 impl Pruner {
     /// Creates a worker thread that waits on a channel for pruning commands.
-    pub fn new(db: Arc<DB>, num_historical_versions_to_keep: u64) -> Self {
+    pub fn new(db: Arc<DB>, prune_windows: PruneWindows) -> Self {
         let (command_sender, command_receiver) = channel();
-        let worker_progress = Arc::new(AtomicU64::new(0));
-        let worker_progress_clone = Arc::clone(&worker_progress);
+        let least_readable_versions = Arc::new(Mutex::new(
+            LeastReadableVersions::load(&db).expect("Loading pruner progress should succeed."),
+        ));
+        let least_readable_versions_clone = Arc::clone(&least_readable_versions);
 
         let worker_thread = std::thread::Builder::new()
             .name("libradb_pruner".into())
-            .spawn(move || Worker::new(db, command_receiver, worker_progress_clone).work_loop())
+            .spawn(move || {
+                Worker::new(db, command_receiver, least_readable_versions_clone).work_loop()
+            })
             .expect("Creating pruner thread should succeed.");
 
         Self {
-            num_historical_versions_to_keep,
+            prune_windows,
             worker_thread: Some(worker_thread),
             command_sender: Mutex::new(command_sender),
-            worker_progress,
+            least_readable_versions,
         }
     }
 
+    /// Returns the least readable version of each kind of data.
+    pub fn least_readable_versions(&self) -> LeastReadableVersions {
+        *self
+            .least_readable_versions
+            .lock()
+            .expect("least_readable_versions should lock.")
+    }
+
     /// Sends pruning command to the worker thread when necessary.
     pub fn wake(&self, latest_version: Version) {
-        if latest_version > self.num_historical_versions_to_keep {
-            let least_readable_version = latest_version - self.num_historical_versions_to_keep;
+        if let Some(least_readable_versions) = self.target_least_readable_versions(latest_version) {
             self.command_sender
                 .lock()
                 .expect("command_sender to pruner thread should lock.")
                 .send(Command::Prune {
-                    least_readable_version,
+                    least_readable_versions,
                 })
                 .expect("Receiver should not destruct prematurely.");
         }
     }
 
+    /// Returns the least readable versions to prune to given `latest_version`, or `None` if there
+    /// is nothing to prune.
+    fn target_least_readable_versions(
+        &self,
+        latest_version: Version,
+    ) -> Option<LeastReadableVersions> {
+        let target = |window: Option<u64>| match window {
+            Some(window) if latest_version > window => latest_version - window,
+            _ => 0,
+        };
+        let targets = LeastReadableVersions {
+            state: target(self.prune_windows.state),
+            transaction: target(self.prune_windows.transaction),
+            event: target(self.prune_windows.event),
+            ledger: target(self.prune_windows.ledger),
+        };
+
+        if targets == LeastReadableVersions::default() {
+            None
+        } else {
+            Some(targets)
+        }
+    }
+
     /// (For tests only.) Notifies the worker thread and waits for it to finish its job by polling
-    /// an internal counter.
+    /// the pruning progress.
     #[cfg(test)]
     pub fn wake_and_wait(&self, latest_version: Version) -> Result<()> {
         self.wake(latest_version);
 
-        if latest_version > self.num_historical_versions_to_keep {
-            let least_readable_version = latest_version - self.num_historical_versions_to_keep;
+        if let Some(target) = self.target_least_readable_versions(latest_version) {
             // Assuming no big pruning chunks will be issued by a test.
             const TIMEOUT: Duration = Duration::from_secs(10);
             let end = Instant::now() + TIMEOUT;
 
             while Instant::now() < end {
-                if self.worker_progress.load(Ordering::Relaxed) >= least_readable_version {
+                let progress = self.least_readable_versions();
+                if progress.state >= target.state
+                    && progress.transaction >= target.transaction
+                    && progress.event >= target.event
+                    && progress.ledger >= target.ledger
+                {
                     return Ok(());
                 }
                 sleep(Duration::from_millis(1));
@@ -127,17 +315,20 @@ impl Drop for Pruner {
 
 enum Command {
     Quit,
-    Prune { least_readable_version: Version },
+    Prune {
+        least_readable_versions: LeastReadableVersions,
+    },
 }
 
 struct Worker {
     db: Arc<DB>,
     command_receiver: Receiver<Command>,
-    target_least_readable_version: Version,
-    /// Keeps a record of the pruning progress. If this equals to version `V`, we know versions
-    /// smaller than `V` are no longer readable.
-    /// This being an atomic value is to communicate the info with the Pruner thread (for tests).
-    least_readable_version: Arc<AtomicU64>,
+    target_least_readable_versions: LeastReadableVersions,
+    /// Keeps a record of the pruning progress. If `least_readable_versions.transaction` equals to
+    /// version `V`, we know transactions with versions smaller than `V` are no longer readable, and
+    /// so on.
+    /// This being shared is to communicate the info with the Pruner, which serves it to readers.
+    least_readable_versions: Arc<Mutex<LeastReadableVersions>>,
     /// Indicates if there's NOT any pending work to do currently, to hint
     /// `Self::receive_commands()` to `recv()` blocking-ly.
     blocking_recv: bool,
@@ -151,13 +342,16 @@ impl Worker {
     fn new(
         db: Arc<DB>,
         command_receiver: Receiver<Command>,
-        least_readable_version: Arc<AtomicU64>,
+        least_readable_versions: Arc<Mutex<LeastReadableVersions>>,
     ) -> Self {
+        let target_least_readable_versions = *least_readable_versions
+            .lock()
+            .expect("least_readable_versions should lock.");
         Self {
             db,
             command_receiver,
-            least_readable_version,
-            target_least_readable_version: 0,
+            least_readable_versions,
+            target_least_readable_versions,
             blocking_recv: true,
             index_min_nonpurged_version: 0,
             index_purged_at: Instant::now(),
@@ -168,24 +362,11 @@ impl Worker {
         while self.receive_commands() {
             // Process a reasonably small batch of work before trying to receive commands again,
             // in case `Command::Quit` is received (that's when we should quit.)
-            match prune_state(
-                Arc::clone(&self.db),
-                self.least_readable_version.load(Ordering::Relaxed),
-                self.target_least_readable_version,
-                Self::MAX_VERSIONS_TO_PRUNE_PER_BATCH,
-            ) {
-                Ok(least_readable_version) => {
+            match self.prune_batch() {
+                Ok(()) => {
                     // Make next recv() blocking if all done.
                     self.blocking_recv =
-                        least_readable_version == self.target_least_readable_version;
-
-                    // Log the progress.
-                    self.least_readable_version
-                        .store(least_readable_version, Ordering::Relaxed);
-                    OP_COUNTER.set(
-                        "pruner.least_readable_state_version",
-                        least_readable_version as usize,
-                    );
+                        self.least_readable_versions() == self.target_least_readable_versions;
 
                     // Try to purge the log.
                     if let Err(e) = self.maybe_purge_index() {
@@ -193,7 +374,7 @@ impl Worker {
                     }
                 }
                 Err(e) => {
-                    crit!("Error pruning stale state nodes. {:?}", e);
+                    crit!("Error pruning old data. {:?}", e);
                     // On error, stop retrying vigorously by making next recv() blocking.
                     self.blocking_recv = true;
                 }
@@ -201,6 +382,56 @@ impl Worker {
         }
     }
 
+    /// Prunes up to `MAX_VERSIONS_TO_PRUNE_PER_BATCH` versions of each kind of data that is behind
+    /// its target.
+    ///
+    /// Except for the state, the progress is published before the deletions are written, so that
+    /// readers never see partially pruned data as readable.
+    fn prune_batch(&self) -> Result<()> {
+        let least = self.least_readable_versions();
+        let target = self.target_least_readable_versions;
+        let max_versions = Self::MAX_VERSIONS_TO_PRUNE_PER_BATCH;
+        let batch_end = |least: Version, target: Version| min(least + max_versions as u64, target);
+
+        if least.state < target.state {
+            let state = prune_state(Arc::clone(&self.db), least.state, target.state, max_versions)?;
+            self.update_progress(|progress| progress.state = state);
+        }
+        if least.transaction < target.transaction {
+            let end = batch_end(least.transaction, target.transaction);
+            self.update_progress(|progress| progress.transaction = end);
+            prune_transactions(&self.db, least.transaction, end)?;
+        }
+        if least.event < target.event {
+            let end = batch_end(least.event, target.event);
+            self.update_progress(|progress| progress.event = end);
+            prune_events(&self.db, least.event, end)?;
+        }
+        if least.ledger < target.ledger {
+            let end = batch_end(least.ledger, target.ledger);
+            self.update_progress(|progress| progress.ledger = end);
+            prune_ledger(&self.db, least.ledger, end)?;
+        }
+
+        Ok(())
+    }
+
+    fn least_readable_versions(&self) -> LeastReadableVersions {
+        *self
+            .least_readable_versions
+            .lock()
+            .expect("least_readable_versions should lock.")
+    }
+
+    fn update_progress<F: FnOnce(&mut LeastReadableVersions)>(&self, update: F) {
+        let mut least_readable_versions = self
+            .least_readable_versions
+            .lock()
+            .expect("least_readable_versions should lock.");
+        update(&mut least_readable_versions);
+        least_readable_versions.update_counters();
+    }
+
     /// Tries to receive all pending commands, blocking waits for the next command if no work needs
     /// to be done, otherwise quits with `true` to allow the outer loop to do some work before
     /// getting back here.
@@ -227,10 +458,17 @@ impl Worker {
                 // On `Command::Quit` inform the outer loop to quit by returning `false`.
                 Command::Quit => return false,
                 Command::Prune {
-                    least_readable_version,
+                    least_readable_versions,
                 } => {
-                    if least_readable_version > self.target_least_readable_version {
-                        self.target_least_readable_version = least_readable_version;
+                    let target = self.target_least_readable_versions;
+                    let new_target = LeastReadableVersions {
+                        state: target.state.max(least_readable_versions.state),
+                        transaction: target.transaction.max(least_readable_versions.transaction),
+                        event: target.event.max(least_readable_versions.event),
+                        ledger: target.ledger.max(least_readable_versions.ledger),
+                    };
+                    if new_target != target {
+                        self.target_least_readable_versions = new_target;
                         // Switch to non-blocking to allow some work to be done after the
                         // channel has drained.
                         self.blocking_recv = false;
@@ -254,10 +492,12 @@ impl Worker {
         // this imposes at most one minute of work in vain after restarting.)
         let now = Instant::now();
         if now - self.index_purged_at > MIN_INTERVAL {
-            let least_readable_version = self.least_readable_version.load(Ordering::Relaxed);
+            let least_readable_version = self.least_readable_versions().state;
 
-            if least_readable_version - self.index_min_nonpurged_version + 1 > MIN_VERSIONS {
-                let new_min_non_purged_version = least_readable_version + 1;
+            if least_readable_version - self.index_min_nonpurged_version > MIN_VERSIONS {
+                // Entries at the least readable version are kept, so that the pruning progress
+                // can be found after restarting. See `LeastReadableVersions::load()`.
+                let new_min_non_purged_version = least_readable_version;
                 self.db.range_delete::<StaleNodeIndexSchema, Version>(
                     &self.index_min_nonpurged_version,
                     &new_min_non_purged_version, // end is exclusive
@@ -344,7 +584,8 @@ pub fn prune_state(
     .collect::<Vec<_>>();
 
     if indices.is_empty() {
-        Ok(least_readable_version)
+        // Nothing became stale up until the target.
+        Ok(target_least_readable_version)
     } else {
         let new_least_readable_version = indices.last().expect("Should exist.").stale_since_version;
         let mut batch = SchemaBatch::new();
@@ -357,5 +598,93 @@ pub fn prune_state(
     }
 }
 
+/// Deletes transactions with versions in [`least_readable_version`, `new_least_readable_version`).
+/// The index by account is kept, so that a lookup hits the pruned version instead of finding
+/// nothing.
+fn prune_transactions(
+    db: &DB,
+    least_readable_version: Version,
+    new_least_readable_version: Version,
+) -> Result<()> {
+    let mut batch = SchemaBatch::new();
+    (least_readable_version..new_least_readable_version)
+        .map(|version| batch.delete::<TransactionSchema>(&version))
+        .collect::<Result<_>>()?;
+    db.write_schemas(batch)
+}
+
+/// Deletes events emitted by transactions with versions in [`least_readable_version`,
+/// `new_least_readable_version`), together with their accumulators and index entries by event key.
+fn prune_events(
+    db: &DB,
+    least_readable_version: Version,
+    new_least_readable_version: Version,
+) -> Result<()> {
+    let mut batch = SchemaBatch::new();
+
+    let mut iter = db.iter::<EventSchema>(ReadOptions::default())?;
+    iter.seek(&least_readable_version)?;
+    for res in iter {
+        let ((version, index), event) = res?;
+        if version >= new_least_readable_version {
+            break;
+        }
+        batch.delete::<EventByKeySchema>(&(*event.key(), event.sequence_number()))?;
+        batch.delete::<EventSchema>(&(version, index))?;
+    }
+
+    let mut iter = db.iter::<EventAccumulatorSchema>(ReadOptions::default())?;
+    iter.seek(&(least_readable_version, Position::from_inorder_index(0)))?;
+    for res in iter {
+        let ((version, position), _) = res?;
+        if version >= new_least_readable_version {
+            break;
+        }
+        batch.delete::<EventAccumulatorSchema>(&(version, position))?;
+    }
+
+    db.write_schemas(batch)
+}
+
+/// Deletes transaction infos with versions in [`least_readable_version`,
+/// `new_least_readable_version`), together with transaction accumulator nodes no longer needed.
+///
+/// Of the nodes covering only pruned versions, two kinds are kept:
+///   - the frozen subtrees at `new_least_readable_version`, which are the left siblings on the
+///     path from any version kept to the root, needed to prove the version.
+///   - the nodes covering versions [2^k, 2^(k+1)), needed to prove consistency with the ledger
+///     known to a client having only seen version 0.
+fn prune_ledger(
+    db: &DB,
+    least_readable_version: Version,
+    new_least_readable_version: Version,
+) -> Result<()> {
+    let mut batch = SchemaBatch::new();
+    (least_readable_version..new_least_readable_version)
+        .map(|version| batch.delete::<TransactionInfoSchema>(&version))
+        .collect::<Result<_>>()?;
+
+    let frozen_subtrees = FrozenSubTreeIterator::new(new_least_readable_version)
+        .collect::<HashSet<_>>();
+    // In post-order, all nodes covering only versions before `new_least_readable_version` come
+    // before the leaf of it.
+    let end = Position::from_leaf_index(new_least_readable_version).to_postorder_index();
+    let mut iter = db.iter::<TransactionAccumulatorSchema>(ReadOptions::default())?;
+    iter.seek_to_first();
+    for res in iter {
+        let (position, _) = res?;
+        if position.to_postorder_index() >= end {
+            break;
+        }
+        if !frozen_subtrees.contains(&position)
+            && position != Position::from_level_and_pos(position.level(), 1)
+        {
+            batch.delete::<TransactionAccumulatorSchema>(&position)?;
+        }
+    }
+
+    db.write_schemas(batch)
+}
+
 #[cfg(test)]
 mod test;
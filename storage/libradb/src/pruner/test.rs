@@ -54,7 +54,12 @@ fn test_pruner() {
     let state_store = &StateStore::new(Arc::clone(&db));
     let pruner = Pruner::new(
         Arc::clone(&db),
-        0, /* num_historical_versions_to_keep */
+        PruneWindows {
+            state: Some(0),
+            transaction: None,
+            event: None,
+            ledger: None,
+        },
     );
 
     let _root0 = put_account_state_set(
@@ -104,6 +109,8 @@ fn test_pruner() {
         // root2 is still there.
         verify_state_in_store(state_store, address, Some(&value2), 2);
     }
+    // The progress is found again from the stale node index.
+    assert_eq!(LeastReadableVersions::load(&db).unwrap().state, 2);
 }
 
 #[test]
@@ -141,16 +148,22 @@ fn test_worker_quit_eagerly() {
         let worker = Worker::new(
             Arc::clone(&db),
             command_receiver,
-            Arc::new(AtomicU64::new(0)), /* progress */
+            Arc::new(Mutex::new(LeastReadableVersions::default())), /* progress */
         );
         command_sender
             .send(Command::Prune {
-                least_readable_version: 1,
+                least_readable_versions: LeastReadableVersions {
+                    state: 1,
+                    ..LeastReadableVersions::default()
+                },
             })
             .unwrap();
         command_sender
             .send(Command::Prune {
-                least_readable_version: 2,
+                least_readable_versions: LeastReadableVersions {
+                    state: 2,
+                    ..LeastReadableVersions::default()
+                },
             })
             .unwrap();
         command_sender.send(Command::Quit).unwrap();
@@ -16,14 +16,17 @@ use crate::{
 use crypto::{hash::CryptoHash, HashValue};
 use failure::prelude::*;
 use jellyfish_merkle::{
+    iterator::JellyfishMerkleIterator,
     node_type::{LeafNode, Node, NodeKey},
-    JellyfishMerkleTree, TreeReader,
+    JellyfishMerkleTree, NodeBatch, TreeReader, TreeWriter,
 };
 use libra_types::{
-    account_address::AccountAddress, account_state_blob::AccountStateBlob,
-    proof::SparseMerkleProof, transaction::Version,
+    account_address::AccountAddress,
+    account_state_blob::AccountStateBlob,
+    proof::{SparseMerkleProof, SparseMerkleRangeProof},
+    transaction::Version,
 };
-use schemadb::DB;
+use schemadb::{ReadOptions, SchemaBatch, DB};
 use std::{collections::HashMap, sync::Arc};
 
 pub(crate) struct StateStore {
@@ -46,6 +49,59 @@ impl StateStore {
         Ok((blob, proof))
     }
 
+    /// Gets up to `limit` consecutive accounts right after `left_key` in the state at `version`,
+    /// together with the proof of the range. If `left_key` is `None`, starts from the first
+    /// account.
+    pub fn get_account_state_chunk_with_proof(
+        &self,
+        version: Version,
+        left_key: Option<HashValue>,
+        limit: u64,
+    ) -> Result<(Vec<(HashValue, AccountStateBlob)>, SparseMerkleRangeProof)> {
+        ensure!(limit > 0, "limit should > 0, got {}", limit);
+
+        let tree = JellyfishMerkleTree::new(self);
+        let left_boundary_proof = match left_key {
+            Some(key) => {
+                let (blob, proof) = tree.get_with_proof(key, version)?;
+                ensure!(
+                    blob.is_some(),
+                    "Left key {:x} does not exist at version {}.",
+                    key,
+                    version,
+                );
+                Some(proof)
+            }
+            None => None,
+        };
+
+        // Fetch one extra account so we know whether the chunk reaches the end of the tree.
+        let mut account_blobs = JellyfishMerkleIterator::new(
+            self,
+            version,
+            left_key.unwrap_or_else(HashValue::zero),
+        )?
+        .filter(|res| match (res, left_key) {
+            (Ok((key, _)), Some(left_key)) => *key != left_key,
+            _ => true,
+        })
+        .take(limit as usize + 1)
+        .collect::<Result<Vec<_>>>()?;
+
+        let right_boundary_proof = if account_blobs.len() as u64 > limit {
+            account_blobs.pop();
+            let (last_key, _) = account_blobs.last().expect("Chunk should not be empty.");
+            Some(tree.get_with_proof(*last_key, version)?.1)
+        } else {
+            None
+        };
+
+        Ok((
+            account_blobs,
+            SparseMerkleRangeProof::new(left_boundary_proof, right_boundary_proof),
+        ))
+    }
+
     /// Put the results generated by `account_state_sets` to `batch` and return the result root
     /// hashes for each write set.
     pub fn put_account_state_sets(
@@ -104,7 +160,55 @@ impl TreeReader for StateStore {
         Ok(self.db.get::<JellyfishMerkleNodeSchema>(node_key)?)
     }
 
+    /// Finds the rightmost leaf of an unfinished restoration. The restoration only writes nodes
+    /// of the version being restored and writes the root node last, so if the root node at the
+    /// latest version already exists, there is no restoration in progress.
     fn get_rightmost_leaf(&self) -> Result<Option<(NodeKey, LeafNode)>> {
-        unimplemented!();
+        let mut iter = self
+            .db
+            .iter::<JellyfishMerkleNodeSchema>(ReadOptions::default())?;
+        iter.seek_to_last();
+        let version = match iter.next().transpose()? {
+            Some((node_key, _node)) => node_key.version(),
+            None => return Ok(None),
+        };
+
+        let root_node_key = NodeKey::new_empty_path(version);
+        if self.get_node_option(&root_node_key)?.is_some() {
+            return Ok(None);
+        }
+
+        let mut iter = self
+            .db
+            .iter::<JellyfishMerkleNodeSchema>(ReadOptions::default())?;
+        iter.seek(&root_node_key)?;
+        let mut rightmost_leaf: Option<(NodeKey, LeafNode)> = None;
+        for res in iter {
+            let (node_key, node) = res?;
+            if node_key.version() != version {
+                break;
+            }
+            if let Node::Leaf(leaf_node) = node {
+                if rightmost_leaf
+                    .as_ref()
+                    .map_or(true, |(_, leaf)| leaf_node.account_key() > leaf.account_key())
+                {
+                    rightmost_leaf = Some((node_key, leaf_node));
+                }
+            }
+        }
+
+        Ok(rightmost_leaf)
+    }
+}
+
+impl TreeWriter for StateStore {
+    fn write_node_batch(&self, node_batch: &NodeBatch) -> Result<()> {
+        let mut batch = SchemaBatch::new();
+        node_batch
+            .iter()
+            .map(|(node_key, node)| batch.put::<JellyfishMerkleNodeSchema>(node_key, node))
+            .collect::<Result<Vec<()>>>()?;
+        self.db.write_schemas(batch)
     }
 }
@@ -4,6 +4,7 @@
 use super::*;
 use crate::{pruner, LibraDB};
 use crypto::hash::CryptoHash;
+use jellyfish_merkle::restore::JellyfishMerkleRestore;
 use libra_tools::tempdir::TempPath;
 use libra_types::{
     account_address::{AccountAddress, ADDRESS_LENGTH},
@@ -226,3 +227,69 @@ fn test_retired_records() {
         verify_state_in_store(store, address3, Some(&value3_update), 2, root2);
     }
 }
+
+#[test]
+fn test_get_account_state_chunk_and_restore() {
+    let tmp_dir = TempPath::new();
+    let db = LibraDB::new(&tmp_dir);
+    let store = &db.state_store;
+
+    let mut account_state_set: Vec<_> = (1..=10u8)
+        .map(|i| {
+            (
+                AccountAddress::new([i; ADDRESS_LENGTH]),
+                AccountStateBlob::from(vec![i]),
+            )
+        })
+        .collect();
+    let mut cs = ChangeSet::new();
+    let root = store
+        .put_account_state_sets(
+            vec![account_state_set.iter().cloned().collect::<HashMap<_, _>>()],
+            0, /* first_version */
+            &mut cs,
+        )
+        .unwrap()[0];
+    store.db.write_schemas(cs.batch).unwrap();
+    account_state_set.sort_by_key(|(address, _)| address.hash());
+
+    let restore_tmp_dir = TempPath::new();
+    let restore_db = LibraDB::new(&restore_tmp_dir);
+    let mut restore =
+        JellyfishMerkleRestore::new(Arc::clone(&restore_db.state_store), 0 /* version */)
+            .unwrap();
+
+    let mut left_key = None;
+    let mut num_accounts = 0;
+    loop {
+        let (chunk, proof) = store
+            .get_account_state_chunk_with_proof(0 /* version */, left_key, 3 /* limit */)
+            .unwrap();
+        proof.verify(root, left_key, &chunk).unwrap();
+        for (key, blob) in &chunk {
+            let (address, expected_blob) = &account_state_set[num_accounts];
+            assert_eq!(*key, address.hash());
+            assert_eq!(blob, expected_blob);
+            num_accounts += 1;
+        }
+
+        let is_last_chunk = proof.right_boundary_proof().is_none();
+        left_key = chunk.last().map(|(key, _)| *key);
+        restore.add_chunk(chunk).unwrap();
+        if is_last_chunk {
+            break;
+        }
+    }
+    assert_eq!(num_accounts, account_state_set.len());
+    restore.finish().unwrap();
+
+    assert_eq!(
+        JellyfishMerkleTree::new(restore_db.state_store.as_ref())
+            .get_root_hash(0 /* version */)
+            .unwrap(),
+        root
+    );
+    for (address, blob) in &account_state_set {
+        verify_state_in_store(&restore_db.state_store, *address, Some(blob), 0, root);
+    }
+}
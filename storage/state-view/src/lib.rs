@@ -8,8 +8,9 @@ use libra_types::access_path::AccessPath;
 
 /// `StateView` is a trait that defines a read-only snapshot of the global state. It is passed to
 /// the VM for transaction execution, during which the VM is guaranteed to read anything at the
-/// given state.
-pub trait StateView {
+/// given state. It can be read from multiple threads, e.g. by the transactions of a block that
+/// are executed in parallel.
+pub trait StateView: Sync {
     /// Gets the state for a single access path.
     fn get(&self, access_path: &AccessPath) -> Result<Option<Vec<u8>>>;
 
@@ -9,30 +9,32 @@
 
 mod state_view;
 
+use crypto::HashValue;
 use failure::prelude::*;
 use futures::{compat::Future01CompatExt, executor::block_on, prelude::*};
 use futures_01::future::Future as Future01;
 use grpcio::{ChannelBuilder, Environment};
 use libra_types::{
     account_address::AccountAddress,
-    account_state_blob::AccountStateBlob,
+    account_state_blob::{AccountStateBlob, AccountStateChunkWithProof},
     crypto_proxies::{LedgerInfoWithSignatures, ValidatorChangeEventWithProof},
     get_with_proof::{
         RequestItem, ResponseItem, UpdateToLatestLedgerRequest, UpdateToLatestLedgerResponse,
     },
     proof::AccumulatorConsistencyProof,
-    proof::SparseMerkleProof,
-    transaction::{TransactionListWithProof, TransactionToCommit, Version},
+    proof::{SparseMerkleProof, TransactionAccumulatorProof},
+    transaction::{TransactionInfo, TransactionListWithProof, TransactionToCommit, Version},
 };
 use rand::Rng;
 use std::convert::TryFrom;
 use std::{pin::Pin, sync::Arc};
 use storage_proto::{
     proto::storage::{GetStartupInfoRequest, StorageClient},
-    GetAccountStateWithProofByVersionRequest, GetAccountStateWithProofByVersionResponse,
-    GetLatestLedgerInfosPerEpochRequest, GetLatestLedgerInfosPerEpochResponse,
-    GetStartupInfoResponse, GetTransactionsRequest, GetTransactionsResponse,
-    SaveTransactionsRequest, StartupInfo,
+    FinishAccountStateRestoreRequest, GetAccountStateChunkWithProofRequest,
+    GetAccountStateChunkWithProofResponse, GetAccountStateWithProofByVersionRequest,
+    GetAccountStateWithProofByVersionResponse, GetLatestLedgerInfosPerEpochRequest,
+    GetLatestLedgerInfosPerEpochResponse, GetStartupInfoResponse, GetTransactionsRequest,
+    GetTransactionsResponse, SaveAccountStateChunkRequest, SaveTransactionsRequest, StartupInfo,
 };
 
 pub use crate::state_view::VerifiedStateView;
@@ -233,6 +235,45 @@ impl StorageRead for StorageReadServiceClient {
         })
         .boxed()
     }
+
+    fn get_account_state_chunk_with_proof(
+        &self,
+        version: Version,
+        ledger_version: Version,
+        left_key: Option<HashValue>,
+        limit: u64,
+    ) -> Result<(AccountStateChunkWithProof, Vec<HashValue>)> {
+        block_on(self.get_account_state_chunk_with_proof_async(
+            version,
+            ledger_version,
+            left_key,
+            limit,
+        ))
+    }
+
+    fn get_account_state_chunk_with_proof_async(
+        &self,
+        version: Version,
+        ledger_version: Version,
+        left_key: Option<HashValue>,
+        limit: u64,
+    ) -> Pin<Box<dyn Future<Output = Result<(AccountStateChunkWithProof, Vec<HashValue>)>> + Send>>
+    {
+        let req =
+            GetAccountStateChunkWithProofRequest::new(version, ledger_version, left_key, limit);
+        convert_grpc_response(
+            self.client()
+                .get_account_state_chunk_with_proof_async(&req.into()),
+        )
+        .map(|resp| {
+            let resp = GetAccountStateChunkWithProofResponse::try_from(resp?)?;
+            Ok((
+                resp.account_state_chunk_with_proof,
+                resp.ledger_frozen_subtree_hashes,
+            ))
+        })
+        .boxed()
+    }
 }
 
 /// This provides storage write interfaces backed by real storage service.
@@ -280,6 +321,53 @@ impl StorageWrite for StorageWriteServiceClient {
             .map_ok(|_| ())
             .boxed()
     }
+
+    fn save_account_state_chunk(&self, chunk: AccountStateChunkWithProof) -> Result<()> {
+        block_on(self.save_account_state_chunk_async(chunk))
+    }
+
+    fn save_account_state_chunk_async(
+        &self,
+        chunk: AccountStateChunkWithProof,
+    ) -> Pin<Box<dyn Future<Output = Result<()>> + Send>> {
+        let req = SaveAccountStateChunkRequest::new(chunk);
+        convert_grpc_response(self.client().save_account_state_chunk_async(&req.into()))
+            .map_ok(|_| ())
+            .boxed()
+    }
+
+    fn finish_account_state_restore(
+        &self,
+        ledger_info_with_sigs: LedgerInfoWithSignatures,
+        txn_info: TransactionInfo,
+        txn_info_proof: TransactionAccumulatorProof,
+        ledger_frozen_subtree_hashes: Vec<HashValue>,
+    ) -> Result<()> {
+        block_on(self.finish_account_state_restore_async(
+            ledger_info_with_sigs,
+            txn_info,
+            txn_info_proof,
+            ledger_frozen_subtree_hashes,
+        ))
+    }
+
+    fn finish_account_state_restore_async(
+        &self,
+        ledger_info_with_sigs: LedgerInfoWithSignatures,
+        txn_info: TransactionInfo,
+        txn_info_proof: TransactionAccumulatorProof,
+        ledger_frozen_subtree_hashes: Vec<HashValue>,
+    ) -> Pin<Box<dyn Future<Output = Result<()>> + Send>> {
+        let req = FinishAccountStateRestoreRequest::new(
+            ledger_info_with_sigs,
+            txn_info,
+            txn_info_proof,
+            ledger_frozen_subtree_hashes,
+        );
+        convert_grpc_response(self.client().finish_account_state_restore_async(&req.into()))
+            .map_ok(|_| ())
+            .boxed()
+    }
 }
 
 /// This trait defines interfaces to be implemented by a storage read client.
@@ -397,6 +485,31 @@ pub trait StorageRead: Send + Sync {
         &self,
         start_epoch: u64,
     ) -> Pin<Box<dyn Future<Output = Result<Vec<LedgerInfoWithSignatures>>> + Send>>;
+
+    /// See [`LibraDB::get_account_state_chunk_with_proof`]. The frozen subtree hashes of the
+    /// transaction accumulator at `version` are returned alongside the chunk.
+    ///
+    /// [`LibraDB::get_account_state_chunk_with_proof`]:
+    /// ../libradb/struct.LibraDB.html#method.get_account_state_chunk_with_proof
+    fn get_account_state_chunk_with_proof(
+        &self,
+        version: Version,
+        ledger_version: Version,
+        left_key: Option<HashValue>,
+        limit: u64,
+    ) -> Result<(AccountStateChunkWithProof, Vec<HashValue>)>;
+
+    /// See [`LibraDB::get_account_state_chunk_with_proof`].
+    ///
+    /// [`LibraDB::get_account_state_chunk_with_proof`]:
+    /// ../libradb/struct.LibraDB.html#method.get_account_state_chunk_with_proof
+    fn get_account_state_chunk_with_proof_async(
+        &self,
+        version: Version,
+        ledger_version: Version,
+        left_key: Option<HashValue>,
+        limit: u64,
+    ) -> Pin<Box<dyn Future<Output = Result<(AccountStateChunkWithProof, Vec<HashValue>)>> + Send>>;
 }
 
 /// This trait defines interfaces to be implemented by a storage write client.
@@ -424,6 +537,45 @@ pub trait StorageWrite: Send + Sync {
         first_version: Version,
         ledger_info_with_sigs: Option<LedgerInfoWithSignatures>,
     ) -> Pin<Box<dyn Future<Output = Result<()>> + Send>>;
+
+    /// See [`LibraDB::save_account_state_chunk`].
+    ///
+    /// [`LibraDB::save_account_state_chunk`]:
+    /// ../libradb/struct.LibraDB.html#method.save_account_state_chunk
+    fn save_account_state_chunk(&self, chunk: AccountStateChunkWithProof) -> Result<()>;
+
+    /// See [`LibraDB::save_account_state_chunk`].
+    ///
+    /// [`LibraDB::save_account_state_chunk`]:
+    /// ../libradb/struct.LibraDB.html#method.save_account_state_chunk
+    fn save_account_state_chunk_async(
+        &self,
+        chunk: AccountStateChunkWithProof,
+    ) -> Pin<Box<dyn Future<Output = Result<()>> + Send>>;
+
+    /// See [`LibraDB::finish_account_state_restore`].
+    ///
+    /// [`LibraDB::finish_account_state_restore`]:
+    /// ../libradb/struct.LibraDB.html#method.finish_account_state_restore
+    fn finish_account_state_restore(
+        &self,
+        ledger_info_with_sigs: LedgerInfoWithSignatures,
+        txn_info: TransactionInfo,
+        txn_info_proof: TransactionAccumulatorProof,
+        ledger_frozen_subtree_hashes: Vec<HashValue>,
+    ) -> Result<()>;
+
+    /// See [`LibraDB::finish_account_state_restore`].
+    ///
+    /// [`LibraDB::finish_account_state_restore`]:
+    /// ../libradb/struct.LibraDB.html#method.finish_account_state_restore
+    fn finish_account_state_restore_async(
+        &self,
+        ledger_info_with_sigs: LedgerInfoWithSignatures,
+        txn_info: TransactionInfo,
+        txn_info_proof: TransactionAccumulatorProof,
+        ledger_frozen_subtree_hashes: Vec<HashValue>,
+    ) -> Pin<Box<dyn Future<Output = Result<()>> + Send>>;
 }
 
 fn convert_grpc_err(e: grpcio::Error) -> Error {
@@ -11,10 +11,9 @@ use libra_types::{
 use scratchpad::{AccountState, SparseMerkleTree};
 use state_view::StateView;
 use std::{
-    cell::RefCell,
-    collections::{hash_map::Entry, BTreeMap, HashMap},
+    collections::{BTreeMap, HashMap},
     convert::TryInto,
-    sync::Arc,
+    sync::{Arc, Mutex},
 };
 
 /// `VerifiedStateView` is like a snapshot of the global state comprised of state view at two
@@ -69,8 +68,10 @@ pub struct VerifiedStateView<'a> {
     ///        | +------------------------------+ +--------------------+ |
     ///        +---------------------------------------------------------+
     /// ```
-    account_to_btree_cache: RefCell<HashMap<AccountAddress, BTreeMap<Vec<u8>, Vec<u8>>>>,
-    account_to_proof_cache: RefCell<HashMap<HashValue, SparseMerkleProof>>,
+    ///
+    /// The caches are behind locks as the VM may read the state from several threads.
+    account_to_btree_cache: Mutex<HashMap<AccountAddress, BTreeMap<Vec<u8>, Vec<u8>>>>,
+    account_to_proof_cache: Mutex<HashMap<HashValue, SparseMerkleProof>>,
 }
 
 impl<'a> VerifiedStateView<'a> {
@@ -87,10 +88,55 @@ impl<'a> VerifiedStateView<'a> {
             latest_persistent_version: latest_persistent_version_and_state_root.0,
             latest_persistent_state_root: latest_persistent_version_and_state_root.1,
             speculative_state,
-            account_to_btree_cache: RefCell::new(HashMap::new()),
-            account_to_proof_cache: RefCell::new(HashMap::new()),
+            account_to_btree_cache: Mutex::new(HashMap::new()),
+            account_to_proof_cache: Mutex::new(HashMap::new()),
         }
     }
+
+    /// Reads the state of the account at `address`, from the speculative state or from storage
+    /// with its proof verified (and cached).
+    fn fetch_account(&self, address: AccountAddress) -> Result<BTreeMap<Vec<u8>, Vec<u8>>> {
+        let address_hash = address.hash();
+        let account_blob_option = match self.speculative_state.get(address_hash) {
+            AccountState::ExistsInScratchPad(blob) => Some(blob),
+            AccountState::DoesNotExist => None,
+            // No matter it is in db or unknown, we have to query from db since even the
+            // former case, we don't have the blob data but only its hash.
+            AccountState::ExistsInDB | AccountState::Unknown => {
+                let (blob, proof) = match self.latest_persistent_version {
+                    Some(version) => self
+                        .reader
+                        .get_account_state_with_proof_by_version(address, version)?,
+                    None => (None, SparseMerkleProof::new(None, vec![])),
+                };
+                proof
+                    .verify(
+                        self.latest_persistent_state_root,
+                        address.hash(),
+                        blob.as_ref(),
+                    )
+                    .map_err(|err| {
+                        format_err!(
+                            "Proof is invalid for address {:?} with state root hash {:?}: {}",
+                            address,
+                            self.latest_persistent_state_root,
+                            err
+                        )
+                    })?;
+                self.account_to_proof_cache
+                    .lock()
+                    .unwrap()
+                    .entry(address_hash)
+                    .or_insert(proof);
+                blob
+            }
+        };
+        Ok(account_blob_option
+            .as_ref()
+            .map(TryInto::try_into)
+            .transpose()?
+            .unwrap_or_default())
+    }
 }
 
 impl<'a>
@@ -106,8 +152,8 @@ impl<'a>
         HashMap<HashValue, SparseMerkleProof>,
     ) {
         (
-            self.account_to_btree_cache.into_inner(),
-            self.account_to_proof_cache.into_inner(),
+            self.account_to_btree_cache.into_inner().unwrap(),
+            self.account_to_proof_cache.into_inner().unwrap(),
         )
     }
 }
@@ -116,56 +162,21 @@ impl<'a> StateView for VerifiedStateView<'a> {
     fn get(&self, access_path: &AccessPath) -> Result<Option<Vec<u8>>> {
         let address = access_path.address;
         let path = &access_path.path;
-        match self.account_to_btree_cache.borrow_mut().entry(address) {
-            Entry::Occupied(occupied) => Ok(occupied.get().get(path).cloned()),
-            Entry::Vacant(vacant) => {
-                let address_hash = address.hash();
-                let account_blob_option = match self.speculative_state.get(address_hash) {
-                    AccountState::ExistsInScratchPad(blob) => Some(blob),
-                    AccountState::DoesNotExist => None,
-                    // No matter it is in db or unknown, we have to query from db since even the
-                    // former case, we don't have the blob data but only its hash.
-                    AccountState::ExistsInDB | AccountState::Unknown => {
-                        let (blob, proof) = match self.latest_persistent_version {
-                            Some(version) => self
-                                .reader
-                                .get_account_state_with_proof_by_version(address, version)?,
-                            None => (None, SparseMerkleProof::new(None, vec![])),
-                        };
-                        proof
-                            .verify(
-                                self.latest_persistent_state_root,
-                                address.hash(),
-                                blob.as_ref(),
-                            )
-                            .map_err(|err| {
-                                format_err!(
-                                "Proof is invalid for address {:?} with state root hash {:?}: {}",
-                                address,
-                                self.latest_persistent_state_root,
-                                err
-                            )
-                            })?;
-                        assert!(self
-                            .account_to_proof_cache
-                            .borrow_mut()
-                            .insert(address_hash, proof)
-                            .is_none());
-                        blob
-                    }
-                };
-                Ok(vacant
-                    .insert(
-                        account_blob_option
-                            .as_ref()
-                            .map(TryInto::try_into)
-                            .transpose()?
-                            .unwrap_or_default(),
-                    )
-                    .get(path)
-                    .cloned())
-            }
+        if let Some(account) = self.account_to_btree_cache.lock().unwrap().get(&address) {
+            return Ok(account.get(path).cloned());
         }
+        // The account is fetched without holding the lock, so that the other threads keep reading
+        // the cached accounts meanwhile. Threads fetching the same account concurrently read the
+        // same state, the first one cached is kept.
+        let account = self.fetch_account(address)?;
+        Ok(self
+            .account_to_btree_cache
+            .lock()
+            .unwrap()
+            .entry(address)
+            .or_insert(account)
+            .get(path)
+            .cloned())
     }
 
     fn multi_get(&self, _access_paths: &[AccessPath]) -> Result<Vec<Option<Vec<u8>>>> {
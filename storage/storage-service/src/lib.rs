@@ -13,7 +13,7 @@ use config::config::NodeConfig;
 use failure::prelude::*;
 use grpc_helpers::{provide_grpc_response, spawn_service_thread_with_drop_closure, ServerHandle};
 use libra_types::proto::types::{UpdateToLatestLedgerRequest, UpdateToLatestLedgerResponse};
-use libradb::LibraDB;
+use libradb::{LibraDB, PruneWindows};
 use logger::prelude::*;
 use metrics::counters::SVC_COUNTERS;
 use std::{
@@ -23,16 +23,25 @@ use std::{
     sync::{mpsc, Arc, Mutex},
 };
 use storage_proto::proto::storage::{
-    create_storage, GetAccountStateWithProofByVersionRequest,
-    GetAccountStateWithProofByVersionResponse, GetLatestLedgerInfosPerEpochRequest,
-    GetLatestLedgerInfosPerEpochResponse, GetStartupInfoRequest, GetStartupInfoResponse,
-    GetTransactionsRequest, GetTransactionsResponse, SaveTransactionsRequest,
-    SaveTransactionsResponse, Storage,
+    create_storage, FinishAccountStateRestoreRequest, FinishAccountStateRestoreResponse,
+    GetAccountStateChunkWithProofRequest, GetAccountStateChunkWithProofResponse,
+    GetAccountStateWithProofByVersionRequest, GetAccountStateWithProofByVersionResponse,
+    GetLatestLedgerInfosPerEpochRequest, GetLatestLedgerInfosPerEpochResponse,
+    GetStartupInfoRequest, GetStartupInfoResponse, GetTransactionsRequest,
+    GetTransactionsResponse, SaveAccountStateChunkRequest, SaveAccountStateChunkResponse,
+    SaveTransactionsRequest, SaveTransactionsResponse, Storage,
 };
 
 /// Starts storage service according to config.
 pub fn start_storage_service(config: &NodeConfig) -> ServerHandle {
-    let (storage_service, shutdown_receiver) = StorageService::new(&config.get_storage_dir());
+    let prune_windows = PruneWindows {
+        state: config.storage.state_prune_window,
+        transaction: config.storage.transaction_prune_window,
+        event: config.storage.event_prune_window,
+        ledger: config.storage.ledger_prune_window,
+    };
+    let (storage_service, shutdown_receiver) =
+        StorageService::new_with_prune_windows(&config.get_storage_dir(), prune_windows);
     spawn_service_thread_with_drop_closure(
         create_storage(storage_service),
         config.storage.address.clone(),
@@ -72,8 +81,11 @@ struct LibraDBWrapper {
 }
 
 impl LibraDBWrapper {
-    pub fn new<P: AsRef<Path>>(path: &P) -> (Self, mpsc::Receiver<()>) {
-        let db = LibraDB::new(path);
+    pub fn new<P: AsRef<Path>>(
+        path: &P,
+        prune_windows: PruneWindows,
+    ) -> (Self, mpsc::Receiver<()>) {
+        let db = LibraDB::new_with_prune_windows(path, prune_windows);
         let (shutdown_sender, shutdown_receiver) = mpsc::channel();
         (
             Self {
@@ -125,7 +137,16 @@ impl StorageService {
     ///    // LibraDB instance is guaranteed to be properly dropped at this point.
     /// ```
     pub fn new<P: AsRef<Path>>(path: &P) -> (Self, mpsc::Receiver<()>) {
-        let (db_wrapper, shutdown_receiver) = LibraDBWrapper::new(path);
+        Self::new_with_prune_windows(path, PruneWindows::default())
+    }
+
+    /// Same as [`StorageService::new`], but the [`LibraDB`] prunes historical data according to
+    /// `prune_windows`.
+    pub fn new_with_prune_windows<P: AsRef<Path>>(
+        path: &P,
+        prune_windows: PruneWindows,
+    ) -> (Self, mpsc::Receiver<()>) {
+        let (db_wrapper, shutdown_receiver) = LibraDBWrapper::new(path, prune_windows);
         (
             Self {
                 db: Arc::new(db_wrapper),
@@ -227,6 +248,51 @@ impl StorageService {
         let rust_resp = storage_proto::GetLatestLedgerInfosPerEpochResponse::new(ledger_infos);
         Ok(rust_resp.into())
     }
+
+    fn get_account_state_chunk_with_proof_inner(
+        &self,
+        req: GetAccountStateChunkWithProofRequest,
+    ) -> Result<GetAccountStateChunkWithProofResponse> {
+        let rust_req = storage_proto::GetAccountStateChunkWithProofRequest::try_from(req)?;
+        let account_state_chunk_with_proof = self.db.get_account_state_chunk_with_proof(
+            rust_req.version,
+            rust_req.ledger_version,
+            rust_req.left_key,
+            rust_req.limit,
+        )?;
+        let ledger_frozen_subtree_hashes = self
+            .db
+            .get_ledger_frozen_subtree_hashes(rust_req.version)?;
+        let rust_resp = storage_proto::GetAccountStateChunkWithProofResponse::new(
+            account_state_chunk_with_proof,
+            ledger_frozen_subtree_hashes,
+        );
+        Ok(rust_resp.into())
+    }
+
+    fn save_account_state_chunk_inner(
+        &self,
+        req: SaveAccountStateChunkRequest,
+    ) -> Result<SaveAccountStateChunkResponse> {
+        let rust_req = storage_proto::SaveAccountStateChunkRequest::try_from(req)?;
+        self.db
+            .save_account_state_chunk(rust_req.account_state_chunk_with_proof)?;
+        Ok(SaveAccountStateChunkResponse::default())
+    }
+
+    fn finish_account_state_restore_inner(
+        &self,
+        req: FinishAccountStateRestoreRequest,
+    ) -> Result<FinishAccountStateRestoreResponse> {
+        let rust_req = storage_proto::FinishAccountStateRestoreRequest::try_from(req)?;
+        self.db.finish_account_state_restore(
+            &rust_req.ledger_info_with_signatures,
+            rust_req.transaction_info,
+            rust_req.transaction_info_proof,
+            rust_req.ledger_frozen_subtree_hashes,
+        )?;
+        Ok(FinishAccountStateRestoreResponse::default())
+    }
 }
 
 impl Storage for StorageService {
@@ -301,6 +367,42 @@ impl Storage for StorageService {
         let resp = self.get_latest_ledger_infos_per_epoch_inner(req);
         provide_grpc_response(resp, ctx, sink);
     }
+
+    fn get_account_state_chunk_with_proof(
+        &mut self,
+        ctx: grpcio::RpcContext,
+        req: GetAccountStateChunkWithProofRequest,
+        sink: grpcio::UnarySink<GetAccountStateChunkWithProofResponse>,
+    ) {
+        debug!("[GRPC] Storage::get_account_state_chunk_with_proof");
+        let _timer = SVC_COUNTERS.req(&ctx);
+        let resp = self.get_account_state_chunk_with_proof_inner(req);
+        provide_grpc_response(resp, ctx, sink);
+    }
+
+    fn save_account_state_chunk(
+        &mut self,
+        ctx: grpcio::RpcContext,
+        req: SaveAccountStateChunkRequest,
+        sink: grpcio::UnarySink<SaveAccountStateChunkResponse>,
+    ) {
+        debug!("[GRPC] Storage::save_account_state_chunk");
+        let _timer = SVC_COUNTERS.req(&ctx);
+        let resp = self.save_account_state_chunk_inner(req);
+        provide_grpc_response(resp, ctx, sink);
+    }
+
+    fn finish_account_state_restore(
+        &mut self,
+        ctx: grpcio::RpcContext,
+        req: FinishAccountStateRestoreRequest,
+        sink: grpcio::UnarySink<FinishAccountStateRestoreResponse>,
+    ) {
+        debug!("[GRPC] Storage::finish_account_state_restore");
+        let _timer = SVC_COUNTERS.req(&ctx);
+        let resp = self.finish_account_state_restore_inner(req);
+        provide_grpc_response(resp, ctx, sink);
+    }
 }
 
 #[cfg(test)]
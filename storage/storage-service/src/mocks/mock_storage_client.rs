@@ -8,7 +8,7 @@ use failure::prelude::*;
 use futures::prelude::*;
 use libra_types::{
     account_address::{AccountAddress, ADDRESS_LENGTH},
-    account_state_blob::AccountStateBlob,
+    account_state_blob::{AccountStateBlob, AccountStateChunkWithProof},
     crypto_proxies::{LedgerInfoWithSignatures, ValidatorChangeEventWithProof},
     event::EventHandle,
     get_with_proof::{RequestItem, ResponseItem},
@@ -152,6 +152,27 @@ impl StorageRead for MockStorageReadClient {
     ) -> Pin<Box<dyn Future<Output = Result<Vec<LedgerInfoWithSignatures>>> + Send>> {
         unimplemented!()
     }
+
+    fn get_account_state_chunk_with_proof(
+        &self,
+        _version: Version,
+        _ledger_version: Version,
+        _left_key: Option<HashValue>,
+        _limit: u64,
+    ) -> Result<(AccountStateChunkWithProof, Vec<HashValue>)> {
+        unimplemented!()
+    }
+
+    fn get_account_state_chunk_with_proof_async(
+        &self,
+        _version: Version,
+        _ledger_version: Version,
+        _left_key: Option<HashValue>,
+        _limit: u64,
+    ) -> Pin<Box<dyn Future<Output = Result<(AccountStateChunkWithProof, Vec<HashValue>)>> + Send>>
+    {
+        unimplemented!()
+    }
 }
 
 fn get_mock_update_to_latest_ledger(
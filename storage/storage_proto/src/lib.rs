@@ -29,11 +29,11 @@ use crypto::HashValue;
 use failure::prelude::*;
 use libra_types::{
     account_address::AccountAddress,
-    account_state_blob::AccountStateBlob,
+    account_state_blob::{AccountStateBlob, AccountStateChunkWithProof},
     crypto_proxies::LedgerInfoWithSignatures,
     ledger_info::LedgerInfo,
-    proof::SparseMerkleProof,
-    transaction::{TransactionListWithProof, TransactionToCommit, Version},
+    proof::{SparseMerkleProof, TransactionAccumulatorProof},
+    transaction::{TransactionInfo, TransactionListWithProof, TransactionToCommit, Version},
 };
 #[cfg(any(test, feature = "testing"))]
 use proptest_derive::Arbitrary;
@@ -462,6 +462,248 @@ impl Into<Vec<LedgerInfoWithSignatures>> for GetLatestLedgerInfosPerEpochRespons
     }
 }
 
+/// Helper to construct and parse [`proto::storage::GetAccountStateChunkWithProofRequest`]
+#[derive(Clone, Debug, Eq, PartialEq)]
+#[cfg_attr(any(test, feature = "testing"), derive(Arbitrary))]
+pub struct GetAccountStateChunkWithProofRequest {
+    pub version: Version,
+    pub ledger_version: Version,
+    pub left_key: Option<HashValue>,
+    pub limit: u64,
+}
+
+impl GetAccountStateChunkWithProofRequest {
+    /// Constructor.
+    pub fn new(
+        version: Version,
+        ledger_version: Version,
+        left_key: Option<HashValue>,
+        limit: u64,
+    ) -> Self {
+        Self {
+            version,
+            ledger_version,
+            left_key,
+            limit,
+        }
+    }
+}
+
+impl TryFrom<crate::proto::storage::GetAccountStateChunkWithProofRequest>
+    for GetAccountStateChunkWithProofRequest
+{
+    type Error = Error;
+
+    fn try_from(
+        proto: crate::proto::storage::GetAccountStateChunkWithProofRequest,
+    ) -> Result<Self> {
+        let left_key = if proto.left_key.is_empty() {
+            None
+        } else {
+            Some(HashValue::from_slice(&proto.left_key)?)
+        };
+
+        Ok(Self {
+            version: proto.version,
+            ledger_version: proto.ledger_version,
+            left_key,
+            limit: proto.limit,
+        })
+    }
+}
+
+impl From<GetAccountStateChunkWithProofRequest>
+    for crate::proto::storage::GetAccountStateChunkWithProofRequest
+{
+    fn from(request: GetAccountStateChunkWithProofRequest) -> Self {
+        Self {
+            version: request.version,
+            ledger_version: request.ledger_version,
+            left_key: request
+                .left_key
+                .map(|key| key.to_vec())
+                .unwrap_or_default(),
+            limit: request.limit,
+        }
+    }
+}
+
+/// Helper to construct and parse [`proto::storage::GetAccountStateChunkWithProofResponse`]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct GetAccountStateChunkWithProofResponse {
+    pub account_state_chunk_with_proof: AccountStateChunkWithProof,
+    pub ledger_frozen_subtree_hashes: Vec<HashValue>,
+}
+
+impl GetAccountStateChunkWithProofResponse {
+    /// Constructor.
+    pub fn new(
+        account_state_chunk_with_proof: AccountStateChunkWithProof,
+        ledger_frozen_subtree_hashes: Vec<HashValue>,
+    ) -> Self {
+        Self {
+            account_state_chunk_with_proof,
+            ledger_frozen_subtree_hashes,
+        }
+    }
+}
+
+impl TryFrom<crate::proto::storage::GetAccountStateChunkWithProofResponse>
+    for GetAccountStateChunkWithProofResponse
+{
+    type Error = Error;
+
+    fn try_from(
+        proto: crate::proto::storage::GetAccountStateChunkWithProofResponse,
+    ) -> Result<Self> {
+        let account_state_chunk_with_proof = proto
+            .account_state_chunk_with_proof
+            .ok_or_else(|| format_err!("Missing account_state_chunk_with_proof"))?
+            .try_into()?;
+        let ledger_frozen_subtree_hashes = proto
+            .ledger_frozen_subtree_hashes
+            .iter()
+            .map(|x| &x[..])
+            .map(HashValue::from_slice)
+            .collect::<Result<Vec<_>>>()?;
+
+        Ok(Self {
+            account_state_chunk_with_proof,
+            ledger_frozen_subtree_hashes,
+        })
+    }
+}
+
+impl From<GetAccountStateChunkWithProofResponse>
+    for crate::proto::storage::GetAccountStateChunkWithProofResponse
+{
+    fn from(response: GetAccountStateChunkWithProofResponse) -> Self {
+        Self {
+            account_state_chunk_with_proof: Some(response.account_state_chunk_with_proof.into()),
+            ledger_frozen_subtree_hashes: response
+                .ledger_frozen_subtree_hashes
+                .into_iter()
+                .map(|x| x.to_vec())
+                .collect(),
+        }
+    }
+}
+
+/// Helper to construct and parse [`proto::storage::SaveAccountStateChunkRequest`]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct SaveAccountStateChunkRequest {
+    pub account_state_chunk_with_proof: AccountStateChunkWithProof,
+}
+
+impl SaveAccountStateChunkRequest {
+    /// Constructor.
+    pub fn new(account_state_chunk_with_proof: AccountStateChunkWithProof) -> Self {
+        Self {
+            account_state_chunk_with_proof,
+        }
+    }
+}
+
+impl TryFrom<crate::proto::storage::SaveAccountStateChunkRequest>
+    for SaveAccountStateChunkRequest
+{
+    type Error = Error;
+
+    fn try_from(proto: crate::proto::storage::SaveAccountStateChunkRequest) -> Result<Self> {
+        Ok(Self {
+            account_state_chunk_with_proof: proto
+                .account_state_chunk_with_proof
+                .ok_or_else(|| format_err!("Missing account_state_chunk_with_proof"))?
+                .try_into()?,
+        })
+    }
+}
+
+impl From<SaveAccountStateChunkRequest> for crate::proto::storage::SaveAccountStateChunkRequest {
+    fn from(request: SaveAccountStateChunkRequest) -> Self {
+        Self {
+            account_state_chunk_with_proof: Some(request.account_state_chunk_with_proof.into()),
+        }
+    }
+}
+
+/// Helper to construct and parse [`proto::storage::FinishAccountStateRestoreRequest`]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct FinishAccountStateRestoreRequest {
+    pub ledger_info_with_signatures: LedgerInfoWithSignatures,
+    pub transaction_info: TransactionInfo,
+    pub transaction_info_proof: TransactionAccumulatorProof,
+    pub ledger_frozen_subtree_hashes: Vec<HashValue>,
+}
+
+impl FinishAccountStateRestoreRequest {
+    /// Constructor.
+    pub fn new(
+        ledger_info_with_signatures: LedgerInfoWithSignatures,
+        transaction_info: TransactionInfo,
+        transaction_info_proof: TransactionAccumulatorProof,
+        ledger_frozen_subtree_hashes: Vec<HashValue>,
+    ) -> Self {
+        Self {
+            ledger_info_with_signatures,
+            transaction_info,
+            transaction_info_proof,
+            ledger_frozen_subtree_hashes,
+        }
+    }
+}
+
+impl TryFrom<crate::proto::storage::FinishAccountStateRestoreRequest>
+    for FinishAccountStateRestoreRequest
+{
+    type Error = Error;
+
+    fn try_from(proto: crate::proto::storage::FinishAccountStateRestoreRequest) -> Result<Self> {
+        let ledger_info_with_signatures = proto
+            .ledger_info_with_signatures
+            .ok_or_else(|| format_err!("Missing ledger_info_with_signatures"))?
+            .try_into()?;
+        let transaction_info = proto
+            .transaction_info
+            .ok_or_else(|| format_err!("Missing transaction_info"))?
+            .try_into()?;
+        let transaction_info_proof = proto
+            .transaction_info_proof
+            .ok_or_else(|| format_err!("Missing transaction_info_proof"))?
+            .try_into()?;
+        let ledger_frozen_subtree_hashes = proto
+            .ledger_frozen_subtree_hashes
+            .iter()
+            .map(|x| &x[..])
+            .map(HashValue::from_slice)
+            .collect::<Result<Vec<_>>>()?;
+
+        Ok(Self {
+            ledger_info_with_signatures,
+            transaction_info,
+            transaction_info_proof,
+            ledger_frozen_subtree_hashes,
+        })
+    }
+}
+
+impl From<FinishAccountStateRestoreRequest>
+    for crate::proto::storage::FinishAccountStateRestoreRequest
+{
+    fn from(request: FinishAccountStateRestoreRequest) -> Self {
+        Self {
+            ledger_info_with_signatures: Some(request.ledger_info_with_signatures.into()),
+            transaction_info: Some(request.transaction_info.into()),
+            transaction_info_proof: Some(request.transaction_info_proof.into()),
+            ledger_frozen_subtree_hashes: request
+                .ledger_frozen_subtree_hashes
+                .into_iter()
+                .map(|x| x.to_vec())
+                .collect(),
+        }
+    }
+}
+
 pub mod prelude {
     pub use super::*;
 }
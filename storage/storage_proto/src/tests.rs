@@ -32,4 +32,9 @@ proptest! {
     fn test_get_startup_info_response(res in any::<GetStartupInfoResponse>()) {
         assert_protobuf_encode_decode::<crate::proto::storage::GetStartupInfoResponse, GetStartupInfoResponse>(&res);
     }
+
+    #[test]
+    fn test_get_account_state_chunk_with_proof_request(req in any::<GetAccountStateChunkWithProofRequest>()) {
+        assert_protobuf_encode_decode::<crate::proto::storage::GetAccountStateChunkWithProofRequest, GetAccountStateChunkWithProofRequest>(&req);
+    }
 }
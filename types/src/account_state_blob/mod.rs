@@ -5,7 +5,9 @@
 use crate::account_config::{account_resource_path, AccountResource};
 use crate::{
     account_address::AccountAddress, account_config::get_account_resource_or_default,
-    ledger_info::LedgerInfo, proof::AccountStateProof, transaction::Version,
+    ledger_info::LedgerInfo,
+    proof::{AccountStateChunkProof, AccountStateProof},
+    transaction::Version,
 };
 use crypto::{
     hash::{AccountStateBlobHasher, CryptoHash, CryptoHasher},
@@ -211,5 +213,122 @@ impl From<AccountStateWithProof> for crate::proto::types::AccountStateWithProof
     }
 }
 
+/// A chunk of consecutive accounts, ordered by the hash of their addresses, in the state at some
+/// version. Chunks like this are used to restore a full state snapshot.
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct AccountStateChunkWithProof {
+    /// The transaction version at which these account states are seen.
+    pub version: Version,
+    /// The key of the account right before this chunk, or `None` if the chunk starts at the first
+    /// account.
+    pub left_key: Option<HashValue>,
+    /// The hashed account addresses and their blobs, in increasing order of the keys.
+    pub account_blobs: Vec<(HashValue, AccountStateBlob)>,
+    /// The proof the client can use to authenticate the chunk.
+    pub proof: AccountStateChunkProof,
+}
+
+impl AccountStateChunkWithProof {
+    /// Constructor.
+    pub fn new(
+        version: Version,
+        left_key: Option<HashValue>,
+        account_blobs: Vec<(HashValue, AccountStateBlob)>,
+        proof: AccountStateChunkProof,
+    ) -> Self {
+        Self {
+            version,
+            left_key,
+            account_blobs,
+            proof,
+        }
+    }
+
+    /// Returns the key of the last account in this chunk, if any.
+    pub fn last_key(&self) -> Option<HashValue> {
+        self.account_blobs.last().map(|(key, _)| *key)
+    }
+
+    /// Returns true if this chunk reaches the last account in the state.
+    pub fn is_last_chunk(&self) -> bool {
+        self.proof
+            .transaction_info_to_chunk_proof()
+            .right_boundary_proof()
+            .is_none()
+    }
+
+    /// Verifies the chunk of account states with the proof, both carried by `self`.
+    ///
+    /// Two things are ensured if no error is raised:
+    ///   1. These account states exist in the ledger represented by `ledger_info` at version
+    /// `version`.
+    ///   2. They are all the accounts whose keys lie between `self.left_key` (exclusive) and the
+    /// last key in the chunk (inclusive).
+    pub fn verify(&self, ledger_info: &LedgerInfo, version: Version) -> Result<()> {
+        ensure!(
+            self.version == version,
+            "State version ({}) is not expected ({}).",
+            self.version,
+            version,
+        );
+
+        self.proof
+            .verify(ledger_info, version, self.left_key, &self.account_blobs)
+    }
+}
+
+impl TryFrom<crate::proto::types::AccountStateChunkWithProof> for AccountStateChunkWithProof {
+    type Error = Error;
+
+    fn try_from(proto: crate::proto::types::AccountStateChunkWithProof) -> Result<Self> {
+        let left_key = if proto.left_key.is_empty() {
+            None
+        } else {
+            Some(HashValue::from_slice(&proto.left_key)?)
+        };
+        let account_blobs = proto
+            .account_blobs
+            .into_iter()
+            .map(|blob_with_key| {
+                Ok((
+                    HashValue::from_slice(&blob_with_key.key)?,
+                    blob_with_key
+                        .blob
+                        .ok_or_else(|| format_err!("Missing blob"))?
+                        .try_into()?,
+                ))
+            })
+            .collect::<Result<Vec<_>>>()?;
+
+        Ok(Self::new(
+            proto.version,
+            left_key,
+            account_blobs,
+            proto
+                .proof
+                .ok_or_else(|| format_err!("Missing proof"))?
+                .try_into()?,
+        ))
+    }
+}
+
+impl From<AccountStateChunkWithProof> for crate::proto::types::AccountStateChunkWithProof {
+    fn from(chunk: AccountStateChunkWithProof) -> Self {
+        Self {
+            version: chunk.version,
+            left_key: chunk.left_key.map(|key| key.to_vec()).unwrap_or_default(),
+            account_blobs: chunk
+                .account_blobs
+                .into_iter()
+                .map(|(key, blob)| crate::proto::types::AccountStateBlobWithKey {
+                    key: key.to_vec(),
+                    blob: Some(blob.into()),
+                })
+                .collect(),
+            proof: Some(chunk.proof.into()),
+        }
+    }
+}
+
 #[cfg(test)]
 mod account_state_blob_test;
@@ -42,6 +42,27 @@ impl BlockMetadata {
         }
     }
 
+    pub fn id(&self) -> HashValue {
+        self.id
+    }
+
+    pub fn timestamp_usec(&self) -> u64 {
+        self.timestamp_usec
+    }
+
+    pub fn previous_block_votes(&self) -> &BTreeMap<AccountAddress, Ed25519Signature> {
+        &self.previous_block_votes
+    }
+
+    pub fn proposer(&self) -> AccountAddress {
+        self.proposer
+    }
+
+    /// Returns the validators that voted for the previous block.
+    pub fn voters(&self) -> Vec<AccountAddress> {
+        self.previous_block_votes.keys().cloned().collect()
+    }
+
     pub fn into_inner(self) -> Result<(ByteArray, u64, ByteArray, AccountAddress)> {
         let id = ByteArray::new(self.id.to_vec());
         let vote_maps = ByteArray::new(lcs::to_bytes(&self.previous_block_votes)?);
@@ -421,6 +421,235 @@ impl From<SparseMerkleProof> for crate::proto::types::SparseMerkleProof {
     }
 }
 
+/// A proof that can be used to authenticate a contiguous range of leaves in a Sparse Merkle Tree
+/// given trusted root hash. It is used to verify the chunks of accounts streamed while restoring
+/// a state snapshot.
+///
+/// The range is delimited by two optional boundary leaves. The left boundary is the leaf right
+/// before the range, which the verifier has already seen (for example, the last account of the
+/// previous chunk). The right boundary is the last leaf in the range. If a boundary is absent, the
+/// range extends to the leftmost (respectively rightmost) end of the tree. Only the siblings on
+/// the outer side of the two boundary paths are taken from the proof. Everything between the two
+/// paths is recomputed from the leaves, so a valid proof also shows that no leaf in the range has
+/// been omitted.
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct SparseMerkleRangeProof {
+    /// The inclusion proof of the leaf right before the range, if any.
+    left_boundary_proof: Option<SparseMerkleProof>,
+
+    /// The inclusion proof of the last leaf in the range, if the range does not reach the
+    /// rightmost end of the tree.
+    right_boundary_proof: Option<SparseMerkleProof>,
+}
+
+/// The key of a boundary leaf of a `SparseMerkleRangeProof` and the siblings on the path from the
+/// root to it.
+type RangeBoundary<'a> = (HashValue, &'a [HashValue]);
+
+impl SparseMerkleRangeProof {
+    /// Constructs a new `SparseMerkleRangeProof` using the proofs of the two boundary leaves.
+    pub fn new(
+        left_boundary_proof: Option<SparseMerkleProof>,
+        right_boundary_proof: Option<SparseMerkleProof>,
+    ) -> Self {
+        Self {
+            left_boundary_proof,
+            right_boundary_proof,
+        }
+    }
+
+    /// Returns the proof of the left boundary leaf.
+    pub fn left_boundary_proof(&self) -> Option<&SparseMerkleProof> {
+        self.left_boundary_proof.as_ref()
+    }
+
+    /// Returns the proof of the right boundary leaf.
+    pub fn right_boundary_proof(&self) -> Option<&SparseMerkleProof> {
+        self.right_boundary_proof.as_ref()
+    }
+
+    /// Verifies that `leaves`, given in increasing key order, are exactly the leaves in the tree
+    /// that are greater than `left_key` and no greater than the last key in `leaves`. If
+    /// `left_key` is `None`, the range starts at the leftmost leaf. If the proof carries no right
+    /// boundary, it additionally shows that the range reaches the rightmost leaf of the tree.
+    pub fn verify(
+        &self,
+        expected_root_hash: HashValue,
+        left_key: Option<HashValue>,
+        leaves: &[(HashValue, AccountStateBlob)],
+    ) -> Result<()> {
+        let mut leaf_hashes = Vec::with_capacity(leaves.len() + 1);
+
+        let left_boundary = match (left_key, &self.left_boundary_proof) {
+            (None, None) => None,
+            (Some(key), Some(proof)) => {
+                let (proof_key, value_hash) = proof.leaf.ok_or_else(|| {
+                    format_err!("Expected inclusion proof of the left boundary leaf.")
+                })?;
+                ensure!(
+                    proof_key == key,
+                    "Left boundary keys do not match. Key in proof: {:x}. Expected key: {:x}.",
+                    proof_key,
+                    key,
+                );
+                leaf_hashes.push((key, value_hash));
+                Some((key, proof.siblings.as_slice()))
+            }
+            (None, Some(_)) => bail!("Unexpected left boundary proof."),
+            (Some(_), None) => bail!("Missing left boundary proof."),
+        };
+
+        for (key, blob) in leaves {
+            if let Some((previous_key, _)) = leaf_hashes.last() {
+                ensure!(
+                    key > previous_key,
+                    "Keys in the range must come in increasing order."
+                );
+            }
+            leaf_hashes.push((*key, blob.hash()));
+        }
+
+        let right_boundary = match &self.right_boundary_proof {
+            Some(proof) => {
+                let (last_key, last_blob) = leaves.last().ok_or_else(|| {
+                    format_err!("Unexpected right boundary proof for empty range.")
+                })?;
+                ensure!(
+                    proof.leaf == Some((*last_key, last_blob.hash())),
+                    "Right boundary proof does not authenticate the last leaf in the range.",
+                );
+                Some((*last_key, proof.siblings.as_slice()))
+            }
+            None => None,
+        };
+
+        for (_, siblings) in left_boundary.iter().chain(right_boundary.iter()) {
+            ensure!(
+                siblings.len() <= HashValue::LENGTH_IN_BITS,
+                "Sparse Merkle Tree proof has more than {} ({}) siblings.",
+                HashValue::LENGTH_IN_BITS,
+                siblings.len(),
+            );
+        }
+
+        let actual_root_hash =
+            compute_range_root_hash(0, &leaf_hashes, left_boundary, right_boundary)?;
+        ensure!(
+            actual_root_hash == expected_root_hash,
+            "Root hashes do not match. Actual root hash: {:x}. Expected root hash: {:x}.",
+            actual_root_hash,
+            expected_root_hash,
+        );
+
+        Ok(())
+    }
+}
+
+/// Returns the bit of `key` at `depth`, counting from the MSB of the first byte.
+fn bit_at(key: HashValue, depth: usize) -> bool {
+    key[depth / 8] & (1 << (7 - depth % 8)) != 0
+}
+
+/// Computes the hash of the subtree at `depth` that holds `leaves` (pairs of key and value hash).
+/// `left` is present if the subtree contains the left boundary leaf, in which case all the leaves
+/// to its left are unknown and summarized by the siblings of the boundary. The same goes for
+/// `right` and the leaves to the right of the right boundary leaf.
+fn compute_range_root_hash(
+    depth: usize,
+    leaves: &[(HashValue, HashValue)],
+    left: Option<RangeBoundary>,
+    right: Option<RangeBoundary>,
+) -> Result<HashValue> {
+    if left.is_none() && right.is_none() {
+        // This subtree lies entirely in the range, so we know all of its leaves.
+        match leaves {
+            [] => return Ok(*SPARSE_MERKLE_PLACEHOLDER_HASH),
+            [(key, value_hash)] => return Ok(SparseMerkleLeafNode::new(*key, *value_hash).hash()),
+            _ => (),
+        }
+    }
+
+    for (boundary_key, siblings) in left.iter().chain(right.iter()) {
+        if depth == siblings.len() {
+            // The boundary leaf is at this position, so it is the only leaf in this subtree.
+            match leaves {
+                [(key, value_hash)] if key == boundary_key => {
+                    return Ok(SparseMerkleLeafNode::new(*key, *value_hash).hash());
+                }
+                _ => bail!(
+                    "Boundary leaf {:x} should be the only leaf in its subtree.",
+                    boundary_key
+                ),
+            }
+        }
+    }
+
+    ensure!(
+        depth < HashValue::LENGTH_IN_BITS,
+        "Distinct keys should have diverged before reaching the bottom of the tree.",
+    );
+    let split_index = leaves
+        .iter()
+        .position(|(key, _)| bit_at(*key, depth))
+        .unwrap_or(leaves.len());
+    let (left_leaves, right_leaves) = leaves.split_at(split_index);
+
+    let left_child_hash = match left {
+        Some((key, siblings)) if bit_at(key, depth) => {
+            // The left child is entirely to the left of the range.
+            ensure!(left_leaves.is_empty(), "Leaf found left of the range.");
+            siblings[depth]
+        }
+        _ => compute_range_root_hash(
+            depth + 1,
+            left_leaves,
+            left,
+            right.filter(|(key, _)| !bit_at(*key, depth)),
+        )?,
+    };
+    let right_child_hash = match right {
+        Some((key, siblings)) if !bit_at(key, depth) => {
+            // The right child is entirely to the right of the range.
+            ensure!(right_leaves.is_empty(), "Leaf found right of the range.");
+            siblings[depth]
+        }
+        _ => compute_range_root_hash(
+            depth + 1,
+            right_leaves,
+            left.filter(|(key, _)| bit_at(*key, depth)),
+            right,
+        )?,
+    };
+
+    Ok(SparseMerkleInternalNode::new(left_child_hash, right_child_hash).hash())
+}
+
+impl TryFrom<crate::proto::types::SparseMerkleRangeProof> for SparseMerkleRangeProof {
+    type Error = Error;
+
+    fn try_from(proto_proof: crate::proto::types::SparseMerkleRangeProof) -> Result<Self> {
+        Ok(Self::new(
+            proto_proof
+                .left_boundary_proof
+                .map(TryInto::try_into)
+                .transpose()?,
+            proto_proof
+                .right_boundary_proof
+                .map(TryInto::try_into)
+                .transpose()?,
+        ))
+    }
+}
+
+impl From<SparseMerkleRangeProof> for crate::proto::types::SparseMerkleRangeProof {
+    fn from(proof: SparseMerkleRangeProof) -> Self {
+        Self {
+            left_boundary_proof: proof.left_boundary_proof.map(Into::into),
+            right_boundary_proof: proof.right_boundary_proof.map(Into::into),
+        }
+    }
+}
+
 /// A proof that can be used to show that two Merkle accumulators are consistent -- the big one can
 /// be obtained by appending certain leaves to the small one. For example, at some point in time a
 /// client knows that the root hash of the ledger at version 10 is `old_root` (it could be a
@@ -686,6 +915,115 @@ impl From<AccountStateProof> for crate::proto::types::AccountStateProof {
     }
 }
 
+/// The complete proof used to authenticate a chunk of accounts in the state at a given version.
+/// This structure consists of the `AccumulatorProof` from `LedgerInfo` to `TransactionInfo`, the
+/// `TransactionInfo` object and the `SparseMerkleRangeProof` from state root to the chunk.
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct AccountStateChunkProof {
+    /// The accumulator proof from ledger info root to leaf that authenticates the hash of the
+    /// `TransactionInfo` object.
+    ledger_info_to_transaction_info_proof: TransactionAccumulatorProof,
+
+    /// The `TransactionInfo` object at the leaf of the accumulator.
+    transaction_info: TransactionInfo,
+
+    /// The sparse merkle range proof from state root to the chunk of accounts.
+    transaction_info_to_chunk_proof: SparseMerkleRangeProof,
+}
+
+impl AccountStateChunkProof {
+    /// Constructs a new `AccountStateChunkProof` using given
+    /// `ledger_info_to_transaction_info_proof`, `transaction_info` and
+    /// `transaction_info_to_chunk_proof`.
+    pub fn new(
+        ledger_info_to_transaction_info_proof: TransactionAccumulatorProof,
+        transaction_info: TransactionInfo,
+        transaction_info_to_chunk_proof: SparseMerkleRangeProof,
+    ) -> Self {
+        AccountStateChunkProof {
+            ledger_info_to_transaction_info_proof,
+            transaction_info,
+            transaction_info_to_chunk_proof,
+        }
+    }
+
+    /// Returns the `ledger_info_to_transaction_info_proof` object in this proof.
+    pub fn ledger_info_to_transaction_info_proof(&self) -> &TransactionAccumulatorProof {
+        &self.ledger_info_to_transaction_info_proof
+    }
+
+    /// Returns the `transaction_info` object in this proof.
+    pub fn transaction_info(&self) -> &TransactionInfo {
+        &self.transaction_info
+    }
+
+    /// Returns the `transaction_info_to_chunk_proof` object in this proof.
+    pub fn transaction_info_to_chunk_proof(&self) -> &SparseMerkleRangeProof {
+        &self.transaction_info_to_chunk_proof
+    }
+
+    /// Verifies that `account_blobs` are exactly the accounts right after `left_key` in the state
+    /// at version `state_version`, using the provided proof.
+    pub fn verify(
+        &self,
+        ledger_info: &LedgerInfo,
+        state_version: Version,
+        left_key: Option<HashValue>,
+        account_blobs: &[(HashValue, AccountStateBlob)],
+    ) -> Result<()> {
+        self.transaction_info_to_chunk_proof.verify(
+            self.transaction_info.state_root_hash(),
+            left_key,
+            account_blobs,
+        )?;
+
+        verify_transaction_info(
+            ledger_info,
+            state_version,
+            &self.transaction_info,
+            &self.ledger_info_to_transaction_info_proof,
+        )?;
+        Ok(())
+    }
+}
+
+impl TryFrom<crate::proto::types::AccountStateChunkProof> for AccountStateChunkProof {
+    type Error = Error;
+
+    fn try_from(proto_proof: crate::proto::types::AccountStateChunkProof) -> Result<Self> {
+        let ledger_info_to_transaction_info_proof = proto_proof
+            .ledger_info_to_transaction_info_proof
+            .ok_or_else(|| format_err!("Missing ledger_info_to_transaction_info_proof"))?
+            .try_into()?;
+        let transaction_info = proto_proof
+            .transaction_info
+            .ok_or_else(|| format_err!("Missing transaction_info"))?
+            .try_into()?;
+        let transaction_info_to_chunk_proof = proto_proof
+            .transaction_info_to_chunk_proof
+            .ok_or_else(|| format_err!("Missing transaction_info_to_chunk_proof"))?
+            .try_into()?;
+
+        Ok(AccountStateChunkProof::new(
+            ledger_info_to_transaction_info_proof,
+            transaction_info,
+            transaction_info_to_chunk_proof,
+        ))
+    }
+}
+
+impl From<AccountStateChunkProof> for crate::proto::types::AccountStateChunkProof {
+    fn from(proof: AccountStateChunkProof) -> Self {
+        Self {
+            ledger_info_to_transaction_info_proof: Some(
+                proof.ledger_info_to_transaction_info_proof.into(),
+            ),
+            transaction_info: Some(proof.transaction_info.into()),
+            transaction_info_to_chunk_proof: Some(proof.transaction_info_to_chunk_proof.into()),
+        }
+    }
+}
+
 /// The complete proof used to authenticate a contract event. This structure consists of the
 /// `AccumulatorProof` from `LedgerInfo` to `TransactionInfo`, the `TransactionInfo` object and the
 /// `AccumulatorProof` from event accumulator root to the event.
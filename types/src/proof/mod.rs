@@ -28,8 +28,9 @@ use failure::prelude::*;
 use std::{collections::VecDeque, marker::PhantomData};
 
 pub use self::definition::{
-    AccountStateProof, AccumulatorConsistencyProof, AccumulatorProof, EventAccumulatorProof,
-    EventProof, SignedTransactionProof, SparseMerkleProof, TransactionAccumulatorProof,
+    AccountStateChunkProof, AccountStateProof, AccumulatorConsistencyProof, AccumulatorProof,
+    EventAccumulatorProof, EventProof, SignedTransactionProof, SparseMerkleProof,
+    SparseMerkleRangeProof, TransactionAccumulatorProof,
 };
 
 #[cfg(any(test, feature = "testing"))]
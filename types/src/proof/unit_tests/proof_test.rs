@@ -8,7 +8,7 @@ use crate::{
     proof::{
         definition::MAX_ACCUMULATOR_PROOF_DEPTH, AccountStateProof, EventAccumulatorInternalNode,
         EventAccumulatorProof, EventProof, MerkleTreeInternalNode, SignedTransactionProof,
-        SparseMerkleInternalNode, SparseMerkleLeafNode, SparseMerkleProof,
+        SparseMerkleInternalNode, SparseMerkleLeafNode, SparseMerkleProof, SparseMerkleRangeProof,
         TestAccumulatorInternalNode, TestAccumulatorProof, TransactionAccumulatorInternalNode,
         TransactionAccumulatorProof,
     },
@@ -250,6 +250,132 @@ fn test_verify_three_element_sparse_merkle() {
     }
 }
 
+#[test]
+fn test_verify_sparse_merkle_range() {
+    // Same tree as in `test_verify_three_element_sparse_merkle`.
+    //            root
+    //           /    \
+    //          a      default
+    //         / \
+    //     key1   b
+    //           / \
+    //       key2   key3
+    let key1 = b"hello".test_only_hash();
+    let key2 = b"world".test_only_hash();
+    let key3 = b"!".test_only_hash();
+
+    let blob1 = AccountStateBlob::from(b"1".to_vec());
+    let blob2 = AccountStateBlob::from(b"2".to_vec());
+    let blob3 = AccountStateBlob::from(b"3".to_vec());
+
+    let leaf1_hash = SparseMerkleLeafNode::new(key1, blob1.hash()).hash();
+    let leaf2_hash = SparseMerkleLeafNode::new(key2, blob2.hash()).hash();
+    let leaf3_hash = SparseMerkleLeafNode::new(key3, blob3.hash()).hash();
+    let internal_b_hash = SparseMerkleInternalNode::new(leaf2_hash, leaf3_hash).hash();
+    let internal_a_hash = SparseMerkleInternalNode::new(leaf1_hash, internal_b_hash).hash();
+    let root_hash =
+        SparseMerkleInternalNode::new(internal_a_hash, *SPARSE_MERKLE_PLACEHOLDER_HASH).hash();
+
+    let proof1 = SparseMerkleProof::new(
+        Some((key1, blob1.hash())),
+        vec![*SPARSE_MERKLE_PLACEHOLDER_HASH, internal_b_hash],
+    );
+    let proof2 = SparseMerkleProof::new(
+        Some((key2, blob2.hash())),
+        vec![*SPARSE_MERKLE_PLACEHOLDER_HASH, leaf1_hash, leaf3_hash],
+    );
+
+    {
+        // The whole tree in one range.
+        let proof = SparseMerkleRangeProof::new(None, None);
+        let leaves = vec![
+            (key1, blob1.clone()),
+            (key2, blob2.clone()),
+            (key3, blob3.clone()),
+        ];
+        assert!(proof.verify(root_hash, None, &leaves).is_ok());
+        // Omitting a leaf in the middle.
+        let leaves = vec![(key1, blob1.clone()), (key3, blob3.clone())];
+        assert!(proof.verify(root_hash, None, &leaves).is_err());
+        // Omitting the last leaf.
+        let leaves = vec![(key1, blob1.clone()), (key2, blob2.clone())];
+        assert!(proof.verify(root_hash, None, &leaves).is_err());
+        // Leaves out of order.
+        let leaves = vec![
+            (key1, blob1.clone()),
+            (key3, blob3.clone()),
+            (key2, blob2.clone()),
+        ];
+        assert!(proof.verify(root_hash, None, &leaves).is_err());
+    }
+
+    {
+        // The first chunk, holding only key1.
+        let proof = SparseMerkleRangeProof::new(None, Some(proof1.clone()));
+        assert!(proof
+            .verify(root_hash, None, &[(key1, blob1.clone())])
+            .is_ok());
+        // Wrong value for key1.
+        assert!(proof
+            .verify(root_hash, None, &[(key1, blob2.clone())])
+            .is_err());
+        // A range can't be proven to start at the leftmost leaf with a left key.
+        assert!(proof
+            .verify(root_hash, Some(key1), &[(key1, blob1.clone())])
+            .is_err());
+    }
+
+    {
+        // The middle chunk, holding only key2.
+        let proof = SparseMerkleRangeProof::new(Some(proof1.clone()), Some(proof2.clone()));
+        assert!(proof
+            .verify(root_hash, Some(key1), &[(key2, blob2.clone())])
+            .is_ok());
+        // The left key must match the left boundary proof.
+        assert!(proof
+            .verify(root_hash, Some(key2), &[(key2, blob2.clone())])
+            .is_err());
+        assert!(proof
+            .verify(root_hash, None, &[(key2, blob2.clone())])
+            .is_err());
+    }
+
+    {
+        // The last chunk, holding only key3.
+        let proof = SparseMerkleRangeProof::new(Some(proof2.clone()), None);
+        assert!(proof
+            .verify(root_hash, Some(key2), &[(key3, blob3.clone())])
+            .is_ok());
+        // The range can't skip key3 and claim to reach the end of the tree.
+        assert!(proof.verify(root_hash, Some(key2), &[]).is_err());
+    }
+
+    {
+        // The last two leaves in one chunk.
+        let proof = SparseMerkleRangeProof::new(Some(proof1), None);
+        let leaves = vec![(key2, blob2.clone()), (key3, blob3.clone())];
+        assert!(proof.verify(root_hash, Some(key1), &leaves).is_ok());
+        // Only key2, pretending it is the last leaf.
+        assert!(proof
+            .verify(root_hash, Some(key1), &[(key2, blob2.clone())])
+            .is_err());
+    }
+}
+
+#[test]
+fn test_verify_empty_sparse_merkle_range() {
+    let proof = SparseMerkleRangeProof::new(None, None);
+    assert!(proof
+        .verify(*SPARSE_MERKLE_PLACEHOLDER_HASH, None, &[])
+        .is_ok());
+
+    let key = b"hello".test_only_hash();
+    let blob = AccountStateBlob::from(b"1".to_vec());
+    assert!(proof
+        .verify(*SPARSE_MERKLE_PLACEHOLDER_HASH, None, &[(key, blob)])
+        .is_err());
+}
+
 #[test]
 fn test_verify_signed_transaction() {
     //            root
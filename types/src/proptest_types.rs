@@ -509,10 +509,23 @@ impl Arbitrary for TransactionArgument {
     type Parameters = ();
     fn arbitrary_with(_args: ()) -> Self::Strategy {
         prop_oneof![
+            any::<u8>().prop_map(TransactionArgument::U8),
             any::<u64>().prop_map(TransactionArgument::U64),
+            any::<u128>().prop_map(TransactionArgument::U128),
             any::<AccountAddress>().prop_map(TransactionArgument::Address),
             any::<ByteArray>().prop_map(TransactionArgument::ByteArray),
             ".*".prop_map(TransactionArgument::String),
+            any::<bool>().prop_map(TransactionArgument::Bool),
+            // The elements of a vector all have the same type.
+            vec(any::<u64>(), 0..10)
+                .prop_map(|v| v.into_iter().map(TransactionArgument::U64).collect::<Vec<_>>())
+                .prop_map(TransactionArgument::Vector),
+            vec(any::<AccountAddress>(), 0..10)
+                .prop_map(|v| v.into_iter().map(TransactionArgument::Address).collect::<Vec<_>>())
+                .prop_map(TransactionArgument::Vector),
+            vec(any::<ByteArray>(), 0..10)
+                .prop_map(|v| v.into_iter().map(TransactionArgument::ByteArray).collect::<Vec<_>>())
+                .prop_map(TransactionArgument::Vector),
         ]
         .boxed()
     }
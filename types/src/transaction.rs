@@ -20,6 +20,7 @@ use crypto::{
         CryptoHash, CryptoHasher, EventAccumulatorHasher, RawTransactionHasher,
         SignedTransactionHasher, TransactionInfoHasher,
     },
+    multi_ed25519::{MultiEd25519PublicKey, MultiEd25519Signature},
     traits::*,
     HashValue,
 };
@@ -34,6 +35,7 @@ use std::{
     time::Duration,
 };
 
+mod authenticator;
 mod module;
 mod program;
 mod script;
@@ -42,12 +44,13 @@ mod transaction_argument;
 #[cfg(test)]
 mod unit_tests;
 
+pub use authenticator::TransactionAuthenticator;
 pub use module::Module;
 pub use program::Program;
 pub use script::{Script, SCRIPT_HASH_LENGTH};
 
 use std::ops::Deref;
-pub use transaction_argument::{parse_as_transaction_argument, TransactionArgument};
+pub use transaction_argument::{parse_as_transaction_argument, split_arguments, TransactionArgument};
 
 pub type Version = u64; // Height - also used for MVCC in StateDB
 
@@ -293,12 +296,10 @@ pub struct SignedTransaction {
     /// The raw transaction
     raw_txn: RawTransaction,
 
-    /// Sender's public key. When checking the signature, we first need to check whether this key
-    /// is indeed the pre-image of the pubkey hash stored under sender's account.
-    public_key: Ed25519PublicKey,
-
-    /// Signature of the transaction that correspond to the public key
-    signature: Ed25519Signature,
+    /// Sender's public key and the signature of the transaction that correspond to it. When
+    /// checking the signature, we first need to check whether this key is indeed the pre-image of
+    /// the authentication key stored under sender's account.
+    authenticator: TransactionAuthenticator,
 }
 
 /// A transaction for which the signature has been verified. Created by
@@ -332,11 +333,10 @@ impl fmt::Debug for SignedTransaction {
             f,
             "SignedTransaction {{ \n \
              {{ raw_txn: {:#?}, \n \
-             public_key: {:#?}, \n \
-             signature: {:#?}, \n \
+             authenticator: {:#?}, \n \
              }} \n \
              }}",
-            self.raw_txn, self.public_key, self.signature,
+            self.raw_txn, self.authenticator,
         )
     }
 }
@@ -349,17 +349,25 @@ impl SignedTransaction {
     ) -> SignedTransaction {
         SignedTransaction {
             raw_txn,
-            public_key,
-            signature,
+            authenticator: TransactionAuthenticator::ed25519(public_key, signature),
         }
     }
 
-    pub fn public_key(&self) -> Ed25519PublicKey {
-        self.public_key.clone()
+    /// Creates a transaction authenticated by a k-of-n multi-signature, which is usually assembled
+    /// from signatures produced separately by the holders of the keys.
+    pub fn new_multisig(
+        raw_txn: RawTransaction,
+        public_key: MultiEd25519PublicKey,
+        signature: MultiEd25519Signature,
+    ) -> SignedTransaction {
+        SignedTransaction {
+            raw_txn,
+            authenticator: TransactionAuthenticator::multi_ed25519(public_key, signature),
+        }
     }
 
-    pub fn signature(&self) -> Ed25519Signature {
-        self.signature.clone()
+    pub fn authenticator(&self) -> TransactionAuthenticator {
+        self.authenticator.clone()
     }
 
     pub fn sender(&self) -> AccountAddress {
@@ -399,21 +407,25 @@ impl SignedTransaction {
     /// Checks that the signature of given transaction. Returns `Ok(SignatureCheckedTransaction)` if
     /// the signature is valid.
     pub fn check_signature(self) -> Result<SignatureCheckedTransaction> {
-        self.public_key
-            .verify_signature(&self.raw_txn.hash(), &self.signature)?;
+        self.authenticator.verify_signature(&self.raw_txn.hash())?;
         Ok(SignatureCheckedTransaction(self))
     }
 
+    /// Returns the transaction as a `SignatureCheckedTransaction` without checking its signature.
+    /// This is only meant for the simulation of transactions, which are never committed, e.g. to
+    /// estimate the gas used by an unsigned transaction.
+    pub fn into_unchecked_for_simulation(self) -> SignatureCheckedTransaction {
+        SignatureCheckedTransaction(self)
+    }
+
     pub fn format_for_client(&self, get_transaction_name: impl Fn(&[u8]) -> String) -> String {
         format!(
             "SignedTransaction {{ \n \
              raw_txn: {}, \n \
-             public_key: {:#?}, \n \
-             signature: {:#?}, \n \
+             authenticator: {:#?}, \n \
              }}",
             self.raw_txn.format_for_client(get_transaction_name),
-            self.public_key,
-            self.signature,
+            self.authenticator,
         )
     }
 }
@@ -0,0 +1,121 @@
+// Copyright (c) The Libra Core Contributors
+// SPDX-License-Identifier: Apache-2.0
+
+use crate::account_address::AccountAddress;
+use crypto::{
+    ed25519::*,
+    multi_ed25519::{MultiEd25519PublicKey, MultiEd25519Signature},
+    traits::*,
+    HashValue,
+};
+use failure::prelude::*;
+use serde::{de, ser, Deserialize, Serialize};
+use std::convert::TryFrom;
+
+/// The public key and signature proving that the sender of a transaction authorized it.
+///
+/// Whatever the scheme, an authenticator is serialized as the bytes of the public key followed by
+/// the bytes of the signature, so that transactions signed with a single Ed25519 key keep the
+/// encoding they had before multi-signature authenticators existed. The scheme is told apart by
+/// the length of the public key: only a single Ed25519 public key is
+/// `ED25519_PUBLIC_KEY_LENGTH` bytes long.
+#[derive(Clone, Debug, Eq, PartialEq, Hash)]
+pub enum TransactionAuthenticator {
+    /// Single signature.
+    Ed25519 {
+        public_key: Ed25519PublicKey,
+        signature: Ed25519Signature,
+    },
+    /// K-of-N multi-signature.
+    MultiEd25519 {
+        public_key: MultiEd25519PublicKey,
+        signature: MultiEd25519Signature,
+    },
+}
+
+impl TransactionAuthenticator {
+    /// Creates a single-signature authenticator.
+    pub fn ed25519(public_key: Ed25519PublicKey, signature: Ed25519Signature) -> Self {
+        TransactionAuthenticator::Ed25519 {
+            public_key,
+            signature,
+        }
+    }
+
+    /// Creates a multi-signature authenticator.
+    pub fn multi_ed25519(
+        public_key: MultiEd25519PublicKey,
+        signature: MultiEd25519Signature,
+    ) -> Self {
+        TransactionAuthenticator::MultiEd25519 {
+            public_key,
+            signature,
+        }
+    }
+
+    /// Checks that the signature is valid for `message` under the public key.
+    pub fn verify_signature(&self, message: &HashValue) -> Result<()> {
+        match self {
+            TransactionAuthenticator::Ed25519 {
+                public_key,
+                signature,
+            } => signature.verify(message, public_key),
+            TransactionAuthenticator::MultiEd25519 {
+                public_key,
+                signature,
+            } => signature.verify(message, public_key),
+        }
+    }
+
+    /// Returns the bytes of the public key. The authentication key of an account is the sha3-256
+    /// hash of these bytes.
+    pub fn public_key_bytes(&self) -> Vec<u8> {
+        match self {
+            TransactionAuthenticator::Ed25519 { public_key, .. } => public_key.to_bytes().to_vec(),
+            TransactionAuthenticator::MultiEd25519 { public_key, .. } => public_key.to_bytes(),
+        }
+    }
+
+    /// Returns the bytes of the signature.
+    pub fn signature_bytes(&self) -> Vec<u8> {
+        match self {
+            TransactionAuthenticator::Ed25519 { signature, .. } => signature.to_bytes().to_vec(),
+            TransactionAuthenticator::MultiEd25519 { signature, .. } => signature.to_bytes(),
+        }
+    }
+
+    /// Returns the address whose authentication key is derived from the public key, which is the
+    /// default address of an account controlled by it.
+    pub fn derived_address(&self) -> AccountAddress {
+        AccountAddress::new(*HashValue::from_sha3_256(&self.public_key_bytes()).as_ref())
+    }
+}
+
+impl Serialize for TransactionAuthenticator {
+    fn serialize<S>(&self, serializer: S) -> std::result::Result<S::Ok, S::Error>
+    where
+        S: ser::Serializer,
+    {
+        (self.public_key_bytes(), self.signature_bytes()).serialize(serializer)
+    }
+}
+
+impl<'de> Deserialize<'de> for TransactionAuthenticator {
+    fn deserialize<D>(deserializer: D) -> std::result::Result<Self, D::Error>
+    where
+        D: de::Deserializer<'de>,
+    {
+        let (public_key, signature) = <(Vec<u8>, Vec<u8>)>::deserialize(deserializer)?;
+        if public_key.len() == ED25519_PUBLIC_KEY_LENGTH {
+            Ok(TransactionAuthenticator::ed25519(
+                Ed25519PublicKey::try_from(&public_key[..]).map_err(de::Error::custom)?,
+                Ed25519Signature::try_from(&signature[..]).map_err(de::Error::custom)?,
+            ))
+        } else {
+            Ok(TransactionAuthenticator::multi_ed25519(
+                MultiEd25519PublicKey::try_from(&public_key[..]).map_err(de::Error::custom)?,
+                MultiEd25519Signature::try_from(&signature[..]).map_err(de::Error::custom)?,
+            ))
+        }
+    }
+}
@@ -13,17 +13,26 @@ pub enum TransactionArgument {
     Address(AccountAddress),
     String(String),
     ByteArray(ByteArray),
+    U8(u8),
+    U128(u128),
+    Bool(bool),
+    /// A vector whose elements all have the same type
+    Vector(Vec<TransactionArgument>),
 }
 
 impl fmt::Debug for TransactionArgument {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
         match self {
+            TransactionArgument::U8(value) => write!(f, "{{U8: {}}}", value),
             TransactionArgument::U64(value) => write!(f, "{{U64: {}}}", value),
+            TransactionArgument::U128(value) => write!(f, "{{U128: {}}}", value),
             TransactionArgument::Address(address) => write!(f, "{{ADDRESS: {:?}}}", address),
             TransactionArgument::String(string) => write!(f, "{{STRING: {}}}", string),
             TransactionArgument::ByteArray(byte_array) => {
                 write!(f, "{{ByteArray: 0x{}}}", byte_array)
             }
+            TransactionArgument::Bool(boolean) => write!(f, "{{BOOL: {}}}", boolean),
+            TransactionArgument::Vector(elements) => write!(f, "{{VECTOR: {:?}}}", elements),
         }
     }
 }
@@ -86,6 +95,86 @@ pub fn parse_as_u64(s: &str) -> Result<TransactionArgument> {
     Ok(TransactionArgument::U64(s.parse::<u64>()?))
 }
 
+/// Parses the given string as u8, the number must be suffixed with `u8` (e.g. `42u8`).
+pub fn parse_as_u8(s: &str) -> Result<TransactionArgument> {
+    if !s.ends_with("u8") {
+        return Err(ErrorKind::ParseError(format!("\"{}\" is not a u8", s)).into());
+    }
+    Ok(TransactionArgument::U8(s[..s.len() - 2].parse::<u8>()?))
+}
+
+/// Parses the given string as u128, the number must be suffixed with `u128` (e.g. `42u128`).
+pub fn parse_as_u128(s: &str) -> Result<TransactionArgument> {
+    if !s.ends_with("u128") {
+        return Err(ErrorKind::ParseError(format!("\"{}\" is not a u128", s)).into());
+    }
+    Ok(TransactionArgument::U128(s[..s.len() - 4].parse::<u128>()?))
+}
+
+/// Parses the given string as bool, either `true` or `false`.
+pub fn parse_as_bool(s: &str) -> Result<TransactionArgument> {
+    Ok(TransactionArgument::Bool(s.parse::<bool>()?))
+}
+
+/// Parses the given string as a vector of transaction arguments of the same type, with the
+/// elements separated by commas inside brackets (e.g. `[0x1,0x2]` or `[[1,2],[]]`).
+pub fn parse_as_vector(s: &str) -> Result<TransactionArgument> {
+    if !s.starts_with('[') || !s.ends_with(']') || s.len() < 2 {
+        return Err(ErrorKind::ParseError(format!("\"{}\" is not a vector", s)).into());
+    }
+    let elements = split_arguments(&s[1..s.len() - 1])?
+        .into_iter()
+        .map(parse_as_transaction_argument)
+        .collect::<Result<Vec<_>>>()?;
+    let vector = TransactionArgument::Vector(elements);
+    if !have_same_type(&vector, &vector) {
+        return Err(ErrorKind::ParseError(format!("\"{}\" is not homogeneous", s)).into());
+    }
+    Ok(vector)
+}
+
+/// Splits the given string at the commas that are not nested in a vector, trimming the pieces.
+/// Returns an empty list for a blank string.
+pub fn split_arguments(s: &str) -> Result<Vec<&str>> {
+    let mut pieces = vec![];
+    let mut depth = 0usize;
+    let mut start = 0;
+    for (i, c) in s.char_indices() {
+        match c {
+            '[' => depth += 1,
+            ']' => {
+                depth = depth.checked_sub(1).ok_or_else(|| {
+                    ErrorKind::ParseError(format!("unbalanced brackets in \"{}\"", s))
+                })?
+            }
+            ',' if depth == 0 => {
+                pieces.push(s[start..i].trim());
+                start = i + 1;
+            }
+            _ => (),
+        }
+    }
+    if depth != 0 {
+        return Err(ErrorKind::ParseError(format!("unbalanced brackets in \"{}\"", s)).into());
+    }
+    let last = s[start..].trim();
+    if !last.is_empty() || !pieces.is_empty() {
+        pieces.push(last);
+    }
+    Ok(pieces)
+}
+
+// Returns whether the two arguments can have the same type. The elements of an empty vector can
+// have any type.
+fn have_same_type(a: &TransactionArgument, b: &TransactionArgument) -> bool {
+    match (a, b) {
+        (TransactionArgument::Vector(v1), TransactionArgument::Vector(v2)) => v1
+            .iter()
+            .all(|e1| v2.iter().all(|e2| have_same_type(e1, e2))),
+        _ => std::mem::discriminant(a) == std::mem::discriminant(b),
+    }
+}
+
 macro_rules! return_if_ok {
     ($e: expr) => {{
         if let Ok(res) = $e {
@@ -98,7 +187,11 @@ macro_rules! return_if_ok {
 pub fn parse_as_transaction_argument(s: &str) -> Result<TransactionArgument> {
     return_if_ok!(parse_as_address(s));
     return_if_ok!(parse_as_u64(s));
+    return_if_ok!(parse_as_u8(s));
+    return_if_ok!(parse_as_u128(s));
     return_if_ok!(parse_as_byte_array(s));
+    return_if_ok!(parse_as_bool(s));
+    return_if_ok!(parse_as_vector(s));
     Err(ErrorKind::ParseError(format!("cannot parse \"{}\" as transaction argument", s)).into())
 }
 
@@ -116,6 +209,22 @@ mod test_transaction_argument {
         }
     }
 
+    #[test]
+    fn parse_u8_and_u128() {
+        for s in &["0u8", "255u8"] {
+            parse_as_u8(s).unwrap();
+        }
+        for s in &["256u8", "42", "u8", "42u128"] {
+            parse_as_u8(s).unwrap_err();
+        }
+        for s in &["0u128", "340282366920938463463374607431768211455u128"] {
+            parse_as_u128(s).unwrap();
+        }
+        for s in &["340282366920938463463374607431768211456u128", "42", "42u8"] {
+            parse_as_u128(s).unwrap_err();
+        }
+    }
+
     #[test]
     fn parse_address() {
         for s in &[
@@ -151,9 +260,44 @@ mod test_transaction_argument {
         }
     }
 
+    #[test]
+    fn parse_bool() {
+        assert_eq!(parse_as_bool("true").unwrap(), TransactionArgument::Bool(true));
+        assert_eq!(parse_as_bool("false").unwrap(), TransactionArgument::Bool(false));
+        for s in &["True", "1", ""] {
+            parse_as_bool(s).unwrap_err();
+        }
+    }
+
+    #[test]
+    fn parse_vector() {
+        assert_eq!(
+            parse_as_vector("[1, 2]").unwrap(),
+            TransactionArgument::Vector(vec![
+                TransactionArgument::U64(1),
+                TransactionArgument::U64(2),
+            ])
+        );
+        assert_eq!(parse_as_vector("[]").unwrap(), TransactionArgument::Vector(vec![]));
+        assert_eq!(
+            parse_as_vector("[[true],[]]").unwrap(),
+            TransactionArgument::Vector(vec![
+                TransactionArgument::Vector(vec![TransactionArgument::Bool(true)]),
+                TransactionArgument::Vector(vec![]),
+            ])
+        );
+        for s in &["[0x1,0x2]", "[b\"00\",b\"ff\"]", "[[1],[],[2,3]]"] {
+            parse_as_vector(s).unwrap();
+        }
+
+        for s in &["", "1", "[", "[1,", "[1,]", "[1]]", "[1,0x1]", "[[1],[],[true]]"] {
+            parse_as_vector(s).unwrap_err();
+        }
+    }
+
     #[test]
     fn parse_args() {
-        for s in &["123", "0xf", "b\"aaa\""] {
+        for s in &["123", "0xf", "b\"aaa\"", "7u8", "7u128", "true", "[0x1,0x2]"] {
             parse_as_transaction_argument(s).unwrap();
         }
 
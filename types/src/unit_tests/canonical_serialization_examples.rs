@@ -191,6 +191,43 @@ fn test_transaction_argument_u64_canonical_serialization_example() {
     assert_eq!(expected_output, actual_output);
 }
 
+#[test]
+fn test_transaction_argument_u128_canonical_serialization_example() {
+    let input = TransactionArgument::U128(9_213_671_392_124_193_148);
+    let expected_output: Vec<u8> = vec![
+        0x05, 0x00, 0x00, 0x00, 0x7C, 0xC9, 0xBD, 0xA4, 0x50, 0x89, 0xDD, 0x7F, 0x00, 0x00, 0x00,
+        0x00, 0x00, 0x00, 0x00, 0x00,
+    ];
+
+    let actual_output = to_bytes(&input).unwrap();
+    assert_eq!(expected_output, actual_output);
+}
+
+#[test]
+fn test_transaction_argument_bool_canonical_serialization_example() {
+    let input = TransactionArgument::Bool(true);
+    let expected_output: Vec<u8> = vec![0x06, 0x00, 0x00, 0x00, 0x01];
+
+    let actual_output = to_bytes(&input).unwrap();
+    assert_eq!(expected_output, actual_output);
+}
+
+#[test]
+fn test_transaction_argument_vector_canonical_serialization_example() {
+    let input = TransactionArgument::Vector(vec![
+        TransactionArgument::U64(9_213_671_392_124_193_148),
+        TransactionArgument::U64(1),
+    ]);
+    let expected_output: Vec<u8> = vec![
+        0x07, 0x00, 0x00, 0x00, 0x02, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x7C, 0xC9, 0xBD,
+        0xA4, 0x50, 0x89, 0xDD, 0x7F, 0x00, 0x00, 0x00, 0x00, 0x01, 0x00, 0x00, 0x00, 0x00, 0x00,
+        0x00, 0x00,
+    ];
+
+    let actual_output = to_bytes(&input).unwrap();
+    assert_eq!(expected_output, actual_output);
+}
+
 #[test]
 fn test_transaction_payload_with_a_program_canonical_serialization_example() {
     let input = TransactionPayload::Script(get_common_program());
@@ -6,7 +6,12 @@ use crate::{
     account_address::AccountAddress,
     transaction::{RawTransaction, Script, SignedTransaction, Transaction, TransactionPayload},
 };
-use crypto::ed25519::*;
+use crypto::{
+    ed25519::*,
+    hash::CryptoHash,
+    multi_ed25519::{MultiEd25519PublicKey, MultiEd25519Signature},
+    traits::SigningKey,
+};
 use proptest::prelude::*;
 use std::convert::TryFrom;
 
@@ -32,6 +37,45 @@ fn test_invalid_signature() {
         .expect_err("signature checking should fail");
 }
 
+#[test]
+fn test_multisig() {
+    let keypairs: Vec<_> = (0..3).map(|_| compat::generate_keypair(None)).collect();
+    let public_key = MultiEd25519PublicKey::new(
+        keypairs.iter().map(|(_, public_key)| public_key.clone()).collect(),
+        2, /* threshold */
+    )
+    .unwrap();
+    let raw_txn = RawTransaction::new_script(
+        AccountAddress::from_public_key(&public_key),
+        0,
+        Script::new(vec![], vec![]),
+        0,
+        0,
+        std::time::Duration::new(0, 0),
+    );
+    let sign = |indexes: &[u8]| {
+        MultiEd25519Signature::new(
+            indexes
+                .iter()
+                .map(|i| (keypairs[*i as usize].0.sign_message(&raw_txn.hash()), *i))
+                .collect(),
+        )
+        .unwrap()
+    };
+
+    let txn = SignedTransaction::new_multisig(raw_txn.clone(), public_key.clone(), sign(&[2, 0]));
+    assert_eq!(txn.authenticator().derived_address(), txn.sender());
+    let proto_txn: crate::proto::types::SignedTransaction = txn.into();
+    let txn = SignedTransaction::try_from(proto_txn)
+        .expect("initial conversion from_proto should succeed");
+    txn.check_signature().expect("signature checking should succeed");
+
+    let not_enough_signatures = sign(&[1]);
+    SignedTransaction::new_multisig(raw_txn, public_key, not_enough_signatures)
+        .check_signature()
+        .expect_err("signature checking should fail");
+}
+
 proptest! {
     #[test]
     fn test_sig(raw_txn in any::<RawTransaction>(), (sk1, pk1) in compat::keypair_strategy()) {
@@ -40,6 +84,21 @@ proptest! {
         assert!(signed_txn.check_signature().is_ok());
     }
 
+    #[test]
+    fn test_ed25519_encoding(
+        raw_txn in any::<RawTransaction>(),
+        (sk1, pk1) in compat::keypair_strategy(),
+    ) {
+        let signed_txn = raw_txn.clone().sign(&sk1, pk1.clone()).unwrap().into_inner();
+        // Single signature transactions are encoded as the raw transaction followed by the public
+        // key and the signature, as they were before multi-signature authenticators existed.
+        let mut expected = lcs::to_bytes(&raw_txn).unwrap();
+        expected.extend(lcs::to_bytes(&pk1).unwrap());
+        expected.extend(lcs::to_bytes(&sk1.sign_message(&raw_txn.hash())).unwrap());
+        prop_assert_eq!(lcs::to_bytes(&signed_txn).unwrap(), expected);
+        assert_canonical_encode_decode(signed_txn);
+    }
+
     #[test]
     fn transaction_payload_round_trip_canonical_serialization(txn_payload in any::<TransactionPayload>()) {
         assert_canonical_encode_decode(txn_payload);
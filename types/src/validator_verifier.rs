@@ -1,8 +1,8 @@
 // Copyright (c) The Libra Core Contributors
 // SPDX-License-Identifier: Apache-2.0
 
-use crate::account_address::AccountAddress;
-use crypto::*;
+use crate::{account_address::AccountAddress, validator_set::ValidatorSet};
+use crypto::{ed25519::Ed25519PublicKey, *};
 use failure::prelude::*;
 use logger::prelude::*;
 use std::collections::BTreeMap;
@@ -275,6 +275,26 @@ impl<PublicKey: VerifyingKey> ValidatorVerifier<PublicKey> {
     }
 }
 
+impl From<&ValidatorSet> for ValidatorVerifier<Ed25519PublicKey> {
+    fn from(validator_set: &ValidatorSet) -> Self {
+        ValidatorVerifier::new(
+            validator_set
+                .payload()
+                .iter()
+                .map(|keys| {
+                    (
+                        *keys.account_address(),
+                        ValidatorInfo::new(
+                            keys.consensus_public_key().clone(),
+                            keys.consensus_voting_power(),
+                        ),
+                    )
+                })
+                .collect(),
+        )
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use crate::crypto_proxies::random_validator_verifier;
@@ -399,6 +399,7 @@ pub enum StatusCode {
     STORAGE_ERROR = 2008,
     INTERNAL_TYPE_ERROR = 2009,
     EVENT_KEY_MISMATCH = 2010,
+    GAS_SCHEDULE_ERROR = 2011,
 
     // Errors that can arise from binary decoding (deserialization)
     // Deserializtion Errors: 3000-3999
@@ -447,6 +448,9 @@ pub enum StatusCode {
     EXECUTION_STACK_OVERFLOW = 4020,
     CALL_STACK_OVERFLOW = 4021,
     NATIVE_FUNCTION_ERROR = 4022,
+    // The sender is trying to republish a module `M` with changes that would break the modules
+    // linking against `M` or the resources of `M` published on chain.
+    INCOMPATIBLE_MODULE_UPGRADE = 4023,
 
     // A reserved status to represent an unknown vm status.
     UNKNOWN_STATUS = std::u64::MAX,
@@ -5,8 +5,9 @@ use crate::vm_validator::TransactionValidation;
 use futures::future::{ok, Future};
 use libra_types::{
     account_address::{AccountAddress, ADDRESS_LENGTH},
-    transaction::SignedTransaction,
+    transaction::{SignedTransaction, TransactionOutput, TransactionStatus},
     vm_error::{StatusCode, VMStatus},
+    write_set::WriteSet,
 };
 use state_view::StateView;
 use std::convert::TryFrom;
@@ -36,38 +37,60 @@ impl TransactionValidation for MockVMValidator {
             Err(_) => return Box::new(ok(Some(VMStatus::new(StatusCode::INVALID_SIGNATURE)))),
         };
 
-        let sender = txn.sender();
-        let account_dne_test_add = AccountAddress::try_from(&[0 as u8; ADDRESS_LENGTH]).unwrap();
-        let invalid_sig_test_add = AccountAddress::try_from(&[1 as u8; ADDRESS_LENGTH]).unwrap();
-        let insufficient_balance_test_add =
-            AccountAddress::try_from(&[2 as u8; ADDRESS_LENGTH]).unwrap();
-        let seq_number_too_new_test_add =
-            AccountAddress::try_from(&[3 as u8; ADDRESS_LENGTH]).unwrap();
-        let seq_number_too_old_test_add =
-            AccountAddress::try_from(&[4 as u8; ADDRESS_LENGTH]).unwrap();
-        let txn_expiration_time_test_add =
-            AccountAddress::try_from(&[5 as u8; ADDRESS_LENGTH]).unwrap();
-        let invalid_auth_key_test_add =
-            AccountAddress::try_from(&[6 as u8; ADDRESS_LENGTH]).unwrap();
-        let ret = if sender == account_dne_test_add {
-            Some(VMStatus::new(StatusCode::SENDING_ACCOUNT_DOES_NOT_EXIST))
-        } else if sender == invalid_sig_test_add {
+        Box::new(ok(validation_status(txn.sender())))
+    }
+
+    fn simulate_transaction(
+        &self,
+        txn: SignedTransaction,
+        skip_signature_check: bool,
+    ) -> Box<dyn Future<Item = TransactionOutput, Error = failure::Error> + Send> {
+        let status = if !skip_signature_check && txn.check_signature().is_err() {
             Some(VMStatus::new(StatusCode::INVALID_SIGNATURE))
-        } else if sender == insufficient_balance_test_add {
-            Some(VMStatus::new(
-                StatusCode::INSUFFICIENT_BALANCE_FOR_TRANSACTION_FEE,
-            ))
-        } else if sender == seq_number_too_new_test_add {
-            Some(VMStatus::new(StatusCode::SEQUENCE_NUMBER_TOO_NEW))
-        } else if sender == seq_number_too_old_test_add {
-            Some(VMStatus::new(StatusCode::SEQUENCE_NUMBER_TOO_OLD))
-        } else if sender == txn_expiration_time_test_add {
-            Some(VMStatus::new(StatusCode::TRANSACTION_EXPIRED))
-        } else if sender == invalid_auth_key_test_add {
-            Some(VMStatus::new(StatusCode::INVALID_AUTH_KEY))
         } else {
-            None
+            validation_status(txn.sender())
+        };
+        let status = match status {
+            Some(status) => TransactionStatus::Discard(status),
+            None => TransactionStatus::Keep(VMStatus::new(StatusCode::EXECUTED)),
         };
-        Box::new(ok(ret))
+        Box::new(ok(TransactionOutput::new(
+            WriteSet::default(),
+            vec![],
+            0,
+            status,
+        )))
+    }
+}
+
+/// Maps the test addresses to the validation status returned for their transactions.
+fn validation_status(sender: AccountAddress) -> Option<VMStatus> {
+    let account_dne_test_add = AccountAddress::try_from(&[0 as u8; ADDRESS_LENGTH]).unwrap();
+    let invalid_sig_test_add = AccountAddress::try_from(&[1 as u8; ADDRESS_LENGTH]).unwrap();
+    let insufficient_balance_test_add =
+        AccountAddress::try_from(&[2 as u8; ADDRESS_LENGTH]).unwrap();
+    let seq_number_too_new_test_add = AccountAddress::try_from(&[3 as u8; ADDRESS_LENGTH]).unwrap();
+    let seq_number_too_old_test_add = AccountAddress::try_from(&[4 as u8; ADDRESS_LENGTH]).unwrap();
+    let txn_expiration_time_test_add =
+        AccountAddress::try_from(&[5 as u8; ADDRESS_LENGTH]).unwrap();
+    let invalid_auth_key_test_add = AccountAddress::try_from(&[6 as u8; ADDRESS_LENGTH]).unwrap();
+    if sender == account_dne_test_add {
+        Some(VMStatus::new(StatusCode::SENDING_ACCOUNT_DOES_NOT_EXIST))
+    } else if sender == invalid_sig_test_add {
+        Some(VMStatus::new(StatusCode::INVALID_SIGNATURE))
+    } else if sender == insufficient_balance_test_add {
+        Some(VMStatus::new(
+            StatusCode::INSUFFICIENT_BALANCE_FOR_TRANSACTION_FEE,
+        ))
+    } else if sender == seq_number_too_new_test_add {
+        Some(VMStatus::new(StatusCode::SEQUENCE_NUMBER_TOO_NEW))
+    } else if sender == seq_number_too_old_test_add {
+        Some(VMStatus::new(StatusCode::SEQUENCE_NUMBER_TOO_OLD))
+    } else if sender == txn_expiration_time_test_add {
+        Some(VMStatus::new(StatusCode::TRANSACTION_EXPIRED))
+    } else if sender == invalid_auth_key_test_add {
+        Some(VMStatus::new(StatusCode::INVALID_AUTH_KEY))
+    } else {
+        None
     }
 }
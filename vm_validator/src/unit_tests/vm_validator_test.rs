@@ -12,8 +12,10 @@ use grpcio::EnvBuilder;
 use libra_types::{
     account_address, account_config,
     test_helpers::transaction_test_helpers,
-    transaction::{Module, Script, TransactionArgument, MAX_TRANSACTION_SIZE_IN_BYTES},
-    vm_error::StatusCode,
+    transaction::{
+        Module, Script, TransactionArgument, TransactionStatus, MAX_TRANSACTION_SIZE_IN_BYTES,
+    },
+    vm_error::{StatusCode, VMStatus},
 };
 use rand::SeedableRng;
 use std::{sync::Arc, u64};
@@ -444,3 +446,57 @@ fn test_validate_non_genesis_write_set() {
         .unwrap();
     assert_eq!(ret.unwrap().major_status, StatusCode::REJECTED_WRITE_SET);
 }
+
+#[test]
+fn test_simulate_transaction() {
+    let (config, keypair) = get_test_config();
+    let vm_validator = TestValidator::new(&config);
+
+    let mut rng = ::rand::rngs::StdRng::from_seed([1u8; 32]);
+    let (other_private_key, _) = compat::generate_keypair(&mut rng);
+
+    let address = account_config::association_address();
+    let program = encode_transfer_script(&address, 100);
+    let signed_txn = transaction_test_helpers::get_test_signed_txn(
+        address,
+        1,
+        keypair.private_key,
+        keypair.public_key.clone(),
+        Some(program.clone()),
+    );
+    let output = vm_validator
+        .simulate_transaction(signed_txn, false)
+        .wait()
+        .unwrap();
+    assert_eq!(
+        output.status(),
+        &TransactionStatus::Keep(VMStatus::new(StatusCode::EXECUTED))
+    );
+    assert!(output.gas_used() > 0);
+    assert!(!output.write_set().is_empty());
+
+    // The signature is only checked if requested.
+    let unsigned_txn = transaction_test_helpers::get_test_unchecked_txn(
+        address,
+        1,
+        other_private_key,
+        keypair.public_key,
+        Some(program),
+    );
+    let output = vm_validator
+        .simulate_transaction(unsigned_txn.clone(), false)
+        .wait()
+        .unwrap();
+    assert_eq!(
+        output.status(),
+        &TransactionStatus::Discard(VMStatus::new(StatusCode::INVALID_SIGNATURE))
+    );
+    let output = vm_validator
+        .simulate_transaction(unsigned_txn, true)
+        .wait()
+        .unwrap();
+    assert_eq!(
+        output.status(),
+        &TransactionStatus::Keep(VMStatus::new(StatusCode::EXECUTED))
+    );
+}
@@ -1,20 +1,20 @@
 // Copyright (c) The Libra Core Contributors
 // SPDX-License-Identifier: Apache-2.0
 
-use config::config::NodeConfig;
+use config::config::{NodeConfig, VMConfig};
 use failure::prelude::*;
-use futures::future::{err, ok, Future};
+use futures::future::{result, Future};
 use libra_types::{
     account_address::{AccountAddress, ADDRESS_LENGTH},
     account_config::get_account_resource_or_default,
     get_with_proof::{RequestItem, ResponseItem},
-    transaction::SignedTransaction,
+    transaction::{SignedTransaction, TransactionOutput},
     vm_error::VMStatus,
 };
 use scratchpad::SparseMerkleTree;
 use std::sync::Arc;
 use storage_client::{StorageRead, VerifiedStateView};
-use vm_runtime::{MoveVM, VMVerifier};
+use vm_runtime::{MoveVM, VMSimulator, VMVerifier};
 
 #[cfg(test)]
 #[path = "unit_tests/vm_validator_test.rs"]
@@ -27,12 +27,21 @@ pub trait TransactionValidation: Send + Sync {
         &self,
         _txn: SignedTransaction,
     ) -> Box<dyn Future<Item = Option<VMStatus>, Error = failure::Error> + Send>;
+
+    /// Simulate the execution of a txn from client against the latest state, skipping the
+    /// signature check if `skip_signature_check` is set. The output is never committed.
+    fn simulate_transaction(
+        &self,
+        _txn: SignedTransaction,
+        _skip_signature_check: bool,
+    ) -> Box<dyn Future<Item = TransactionOutput, Error = failure::Error> + Send>;
 }
 
 #[derive(Clone)]
 pub struct VMValidator {
     storage_read_client: Arc<dyn StorageRead>,
     vm: MoveVM,
+    vm_config: VMConfig,
 }
 
 impl VMValidator {
@@ -40,17 +49,12 @@ impl VMValidator {
         VMValidator {
             storage_read_client,
             vm: MoveVM::new(&config.vm_config),
+            vm_config: config.vm_config.clone(),
         }
     }
-}
-
-impl TransactionValidation for VMValidator {
-    type ValidationInstance = MoveVM;
 
-    fn validate_transaction(
-        &self,
-        txn: SignedTransaction,
-    ) -> Box<dyn Future<Item = Option<VMStatus>, Error = failure::Error> + Send> {
+    /// Runs `f` against a view of the latest state in storage.
+    fn with_latest_state_view<T>(&self, f: impl FnOnce(&VerifiedStateView) -> T) -> Result<T> {
         // TODO: For transaction validation, there are two options to go:
         // 1. Trust storage: there is no need to get root hash from storage here. We will
         // create another struct similar to `VerifiedStateView` that implements `StateView`
@@ -67,43 +71,60 @@ impl TransactionValidation for VMValidator {
         let address = AccountAddress::new([0xff; ADDRESS_LENGTH]);
         let item = RequestItem::GetAccountState { address };
 
-        match self
+        let (mut items, ledger_info_with_sigs, _, _) = self
             .storage_read_client
-            .update_to_latest_ledger(/* client_known_version = */ 0, vec![item])
-        {
-            Ok((mut items, ledger_info_with_sigs, _, _)) => {
-                if items.len() != 1 {
-                    return Box::new(err(format_err!(
-                        "Unexpected number of items ({}).",
-                        items.len()
-                    )));
-                }
+            .update_to_latest_ledger(/* client_known_version = */ 0, vec![item])?;
+        ensure!(
+            items.len() == 1,
+            "Unexpected number of items ({}).",
+            items.len()
+        );
 
-                match items.remove(0) {
-                    ResponseItem::GetAccountState {
-                        account_state_with_proof,
-                    } => {
-                        let transaction_info = account_state_with_proof.proof.transaction_info();
-                        let state_root = transaction_info.state_root_hash();
-                        let smt = SparseMerkleTree::new(state_root);
-                        let state_view = VerifiedStateView::new(
-                            Arc::clone(&self.storage_read_client),
-                            (
-                                Some(ledger_info_with_sigs.ledger_info().version()),
-                                state_root,
-                            ),
-                            &smt,
-                        );
-                        Box::new(ok(self.vm.validate_transaction(txn, &state_view)))
-                    }
-                    _ => panic!("Unexpected item in response."),
-                }
+        match items.remove(0) {
+            ResponseItem::GetAccountState {
+                account_state_with_proof,
+            } => {
+                let transaction_info = account_state_with_proof.proof.transaction_info();
+                let state_root = transaction_info.state_root_hash();
+                let smt = SparseMerkleTree::new(state_root);
+                let state_view = VerifiedStateView::new(
+                    Arc::clone(&self.storage_read_client),
+                    (
+                        Some(ledger_info_with_sigs.ledger_info().version()),
+                        state_root,
+                    ),
+                    &smt,
+                );
+                Ok(f(&state_view))
             }
-            Err(e) => Box::new(err(e)),
+            _ => panic!("Unexpected item in response."),
         }
     }
 }
 
+impl TransactionValidation for VMValidator {
+    type ValidationInstance = MoveVM;
+
+    fn validate_transaction(
+        &self,
+        txn: SignedTransaction,
+    ) -> Box<dyn Future<Item = Option<VMStatus>, Error = failure::Error> + Send> {
+        Box::new(result(self.with_latest_state_view(|state_view| {
+            self.vm.validate_transaction(txn, state_view)
+        })))
+    }
+
+    fn simulate_transaction(
+        &self,
+        txn: SignedTransaction,
+        skip_signature_check: bool,
+    ) -> Box<dyn Future<Item = TransactionOutput, Error = failure::Error> + Send> {
+        Box::new(result(self.with_latest_state_view(|state_view| {
+            MoveVM::simulate_transaction(txn, skip_signature_check, &self.vm_config, state_view)
+        })))
+    }
+}
+
 /// read account state
 /// returns account's current sequence number and balance
 pub async fn get_account_state(